@@ -0,0 +1,38 @@
+//! Build script: embeds build identity for `kulta_build_info` and `/version`
+//!
+//! - `KULTA_GIT_SHA`: taken from the environment if set (container builds have
+//!   no `.git`), otherwise from `git rev-parse --short HEAD`, else "unknown".
+//! - `KULTA_RUSTC_VERSION`: output of `$RUSTC --version`.
+
+use std::process::Command;
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8(output.stdout).ok()?;
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn main() {
+    let git_sha = std::env::var("KULTA_GIT_SHA")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| command_output("git", &["rev-parse", "--short", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version =
+        command_output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=KULTA_GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=KULTA_RUSTC_VERSION={}", rustc_version);
+    println!("cargo:rerun-if-env-changed=KULTA_GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+}
@@ -0,0 +1,104 @@
+//! Stable public API for embedding KULTA
+//!
+//! Everything re-exported here follows semver: it is only renamed, moved or
+//! changed incompatibly in a major release. Other controllers and tools
+//! should import from `kulta::api` rather than from `kulta::controller`,
+//! `kulta::crd` or `kulta::server`, whose layout changes between releases.
+//!
+//! The surface covers:
+//! - Rollout resource types (served as `kulta.io/v1alpha1`; `v1beta1` alongside)
+//!   and the cluster-scoped ClusterRollout
+//! - The Experiment resource for short-lived ReplicaSet comparisons
+//! - A validating builder, validation, linting, offline planning and weight
+//!   previews of a Rollout spec
+//! - The [`RolloutStrategy`] trait, strategy selection and offline
+//!   simulation of the next status transition
+//! - The reconcile entry points (Rollouts, ClusterRollouts, Experiments) and
+//!   their [`Context`]
+//! - Support bundles of a Rollout for bug reports
+//! - Extension points injected into the [`Context`]: CDEvents sinks,
+//!   metrics and alert queriers, analysis advisors, clocks, occurrence
+//!   data extensions and notifiers
+//!
+//! New items may be added in minor releases. `tests/public_api_test.rs`
+//! exercises this module so that an accidental break fails the build.
+
+// Rollout resource types (`StrategySpec` is `spec.strategy`, renamed so it
+// doesn't clash with the `RolloutStrategy` trait)
+pub use crate::crd::rollout::{
+    ABHeaderMatch, ABMatchType, AdvisorPlan, AlertGate, AlertGateErrorPolicy, AnalysisConfig,
+    AnalysisOnResume, BlueGreenStrategy, CanaryBaseline, CanaryStep, CanaryStrategy, CleanupPolicy,
+    DependencyGate, DependencyKind, ExperimentSpecRef, ExperimentStep, ExperimentStepTemplate,
+    MetricComparison, MetricConfig, MetricUnit, MigratedFrom, Pause, Phase, PlannedStep,
+    RollbackVerification, RollbackVerificationOutcome, RollbackVerificationStatus, Rollout,
+    RolloutSpec, RolloutStatus, RolloutStrategy as StrategySpec, SetCanaryScale, SetHeaderRoute,
+    SimpleStrategy, StepAnalysis, TemplateChangePolicy, TrafficRouting, VolumeWeighting, WebMetric,
+    WebMetricHeader, WebMetricMethod, WeightOverride, WorkloadKind, WorkloadRef, WorkloadScaleDown,
+};
+
+// `status.strategy`, shared with the CDEvents customData
+pub use crate::controller::cdevents_data::StrategyKind;
+
+// Programmatic construction
+pub use crate::crd::builder::{RolloutBuildError, RolloutBuilder};
+
+// Migration from Argo Rollouts
+pub use crate::crd::argo::{convert_argo_rollout, ArgoConversion, ArgoConversionError};
+
+// Cluster-scoped Rollouts
+pub use crate::crd::cluster_rollout::{ClusterRollout, ClusterRolloutSpec};
+
+// Experiments
+pub use crate::crd::experiment::{
+    Experiment, ExperimentAnalysis, ExperimentPhase, ExperimentSpec, ExperimentStatus,
+    ExperimentTemplate,
+};
+
+/// The `kulta.io/v1beta1` Rollout version
+pub mod v1beta1 {
+    pub use crate::crd::v1beta1::{Rollout, RolloutSpec};
+}
+
+// Validation, linting and planning
+pub use crate::controller::lint::{lint_rollout, LintOptions, LintRule, LintWarning};
+pub use crate::controller::planner::{plan_rollout, PlanEntry, PlanError, PlanGate, RolloutPlan};
+pub use crate::controller::rollout::{preview_backend_refs, validate_experiment, validate_rollout};
+
+// Strategies
+pub use crate::controller::strategies::{
+    check_feature_support, select_strategy, simulate_next_status, RolloutStrategy, StrategyError,
+};
+
+// Reconcile entry point
+pub use crate::controller::experiment::reconcile_experiment;
+pub use crate::controller::rollout::reconcile_cluster_rollout;
+pub use crate::controller::{reconcile, Context, ReconcileError};
+
+// Support bundles
+pub use crate::controller::support_bundle::{
+    collect_support_bundle, SupportBundle, SupportBundleError, SupportBundleOptions,
+};
+
+// Extension points
+pub use crate::controller::advisor::{AnalysisAdvisor, AnalysisContext, NoOpAdvisor};
+pub use crate::controller::alertmanager::{AlertQuerier, FiringAlert, HttpAlertmanagerClient};
+pub use crate::controller::cdevents::{CDEventsError, EventSink, EventSourceConfig, HttpEventSink};
+pub use crate::controller::clock::{Clock, SystemClock};
+pub use crate::controller::guardrails::{GuardrailPolicy, GuardrailViolation};
+pub use crate::controller::heartbeat::Heartbeats;
+pub use crate::controller::incidents::{OpsgenieNotifier, PagerDutyNotifier};
+pub use crate::controller::notifications::{
+    notification_for, FailureContext, Notification, NotificationError, NotificationTrigger,
+    Notifications, Notifier,
+};
+pub use crate::controller::occurrence::{
+    AnnotationExtension, OccurrenceExtension, OccurrenceExtensions,
+};
+pub use crate::controller::occurrence_sink::{
+    FileOccurrenceSink, NatsOccurrenceSink, NatsSinkConfig, OccurrenceSink, OccurrenceSinkError,
+    OccurrenceSinks,
+};
+pub use crate::controller::prometheus::{
+    HttpPrometheusClient, MetricsQuerier, PrometheusError, RevisionSelector,
+};
+pub use crate::controller::release_hooks::{ReleaseHooks, ReleaseRecord};
@@ -1,4 +1,6 @@
 use kube::CustomResourceExt;
+use kulta::crd::cluster_rollout::ClusterRollout;
+use kulta::crd::experiment::Experiment;
 use kulta::crd::rollout::Rollout as RolloutV1alpha1;
 use kulta::crd::v1beta1::Rollout as RolloutV1beta1;
 use serde_json::{json, Value};
@@ -6,7 +8,20 @@ use serde_json::{json, Value};
 fn main() -> anyhow::Result<()> {
     // Generate CRD with both versions and conversion webhook
     // Use: cargo run --bin gen-crd | python3 -c "import sys,json,yaml; print(yaml.dump(json.load(sys.stdin), default_flow_style=False))"
-    // to convert to YAML
+    // to convert to YAML. `cargo run --bin gen-crd -- cluster-rollout` prints the
+    // ClusterRollout CRD instead (single version, no conversion webhook), and
+    // `-- experiment` the Experiment CRD.
+    match std::env::args().nth(1).as_deref() {
+        Some("cluster-rollout") => {
+            println!("{}", serde_json::to_string_pretty(&ClusterRollout::crd())?);
+            return Ok(());
+        }
+        Some("experiment") => {
+            println!("{}", serde_json::to_string_pretty(&Experiment::crd())?);
+            return Ok(());
+        }
+        _ => {}
+    }
 
     // Get base CRD from v1alpha1 (storage version)
     let mut crd: Value = serde_json::to_value(RolloutV1alpha1::crd())?;
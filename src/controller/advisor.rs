@@ -8,8 +8,10 @@
 //!
 //! The advisor never overrides threshold decisions at Level 2 — it only
 //! provides recommendations that are logged alongside the threshold result.
+//! At Level 3 (Planned) it is also sent the canary steps in effect and may
+//! return a `plan` for the remaining ones (see `rollout::advisor_plan`).
 
-use crate::crd::rollout::{Recommendation, RecommendedAction};
+use crate::crd::rollout::{CanaryStep, Recommendation, RecommendedAction};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -40,6 +42,9 @@ pub struct AnalysisContext {
     pub metrics_healthy: bool,
     pub phase: String,
     pub history: Vec<String>,
+    /// Canary steps in effect, sent at Level 3+ so the advisor can plan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<CanaryStep>,
 }
 
 /// Trait for AI advisory integration
@@ -68,6 +73,7 @@ impl AnalysisAdvisor for NoOpAdvisor {
             action: RecommendedAction::Continue,
             confidence: 0.0,
             reasoning: "no advisor configured".into(),
+            plan: None,
         })
     }
 
@@ -283,6 +289,7 @@ mod tests {
             metrics_healthy: true,
             phase: "Progressing".into(),
             history: vec![],
+            steps: vec![],
         };
 
         let rec = advisor.advise(&ctx).await.unwrap();
@@ -296,6 +303,7 @@ mod tests {
             action: RecommendedAction::Rollback,
             confidence: 0.95,
             reasoning: "high error rate detected".into(),
+            plan: None,
         });
 
         let ctx = AnalysisContext {
@@ -307,6 +315,7 @@ mod tests {
             metrics_healthy: false,
             phase: "Progressing".into(),
             history: vec![],
+            steps: vec![],
         };
 
         let rec = advisor.advise(&ctx).await.unwrap();
@@ -321,6 +330,7 @@ mod tests {
             action: RecommendedAction::Continue,
             confidence: 0.8,
             reasoning: "looks good".into(),
+            plan: None,
         });
 
         let ctx = AnalysisContext {
@@ -332,6 +342,7 @@ mod tests {
             metrics_healthy: true,
             phase: "Progressing".into(),
             history: vec![],
+            steps: vec![],
         };
 
         let _ = advisor.advise(&ctx).await;
@@ -353,6 +364,7 @@ mod tests {
             metrics_healthy: true,
             phase: "Progressing".into(),
             history: vec![],
+            steps: vec![],
         };
 
         let result = advisor.advise(&ctx).await;
@@ -371,6 +383,7 @@ mod tests {
             level: AdvisorLevel::Off,
             endpoint: Some("http://ai:8080".into()),
             timeout_seconds: 10,
+            min_confidence: None,
         };
         let ctx_advisor: std::sync::Arc<dyn AnalysisAdvisor> = std::sync::Arc::new(NoOpAdvisor);
 
@@ -386,6 +399,7 @@ mod tests {
             level: AdvisorLevel::Context,
             endpoint: Some("http://ai:8080".into()),
             timeout_seconds: 10,
+            min_confidence: None,
         };
         let ctx_advisor: std::sync::Arc<dyn AnalysisAdvisor> = std::sync::Arc::new(NoOpAdvisor);
 
@@ -401,6 +415,7 @@ mod tests {
             level: AdvisorLevel::Advised,
             endpoint: Some("http://ai-advisor:8080/advise".into()),
             timeout_seconds: 5,
+            min_confidence: None,
         };
         let ctx_advisor: std::sync::Arc<dyn AnalysisAdvisor> = std::sync::Arc::new(NoOpAdvisor);
 
@@ -416,6 +431,7 @@ mod tests {
             level: AdvisorLevel::Advised,
             endpoint: None,
             timeout_seconds: 10,
+            min_confidence: None,
         };
         let ctx_advisor: std::sync::Arc<dyn AnalysisAdvisor> = std::sync::Arc::new(NoOpAdvisor);
 
@@ -432,12 +448,14 @@ mod tests {
             level: AdvisorLevel::Advised,
             endpoint: Some("http://ai:8080".into()),
             timeout_seconds: 10,
+            min_confidence: None,
         };
         // Context has a MockAdvisor — test override should win
         let mock = MockAdvisor::new(Recommendation {
             action: RecommendedAction::Rollback,
             confidence: 1.0,
             reasoning: "test".into(),
+            plan: None,
         });
         let ctx_advisor: std::sync::Arc<dyn AnalysisAdvisor> = std::sync::Arc::new(mock);
 
@@ -0,0 +1,407 @@
+//! Alertmanager integration for alert-gated canary steps
+//!
+//! Follows the same trait-based pattern as `MetricsQuerier` (prometheus.rs):
+//! - `AlertQuerier` trait for abstraction
+//! - `HttpAlertmanagerClient` queries the Alertmanager v2 API
+//! - `MockAlertmanagerClient` for testing
+//!
+//! A step with `alertGate` holds the rollout at that step while any active
+//! (not silenced, not inhibited) alert matching the gate's label matchers is
+//! firing, so a canary doesn't advance in the middle of an incident.
+
+use crate::crd::rollout::{AlertGate, AlertGateErrorPolicy};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+/// Timeout for Alertmanager API calls
+const ALERTMANAGER_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum AlertmanagerError {
+    #[error("Alertmanager HTTP error: {0}")]
+    HttpError(String),
+
+    #[error("Failed to parse Alertmanager response: {0}")]
+    ParseError(String),
+
+    #[error(
+        "No Alertmanager address configured (set alertGate.address or KULTA_ALERTMANAGER_ADDRESS)"
+    )]
+    NotConfigured,
+}
+
+/// An active alert returned by Alertmanager
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiringAlert {
+    /// Value of the `alertname` label ("unknown" if missing)
+    pub name: String,
+    /// All alert labels
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Trait for querying firing alerts
+#[async_trait]
+pub trait AlertQuerier: Send + Sync {
+    /// Return active alerts matching all `matchers` (label equality)
+    ///
+    /// `address` overrides the client's default Alertmanager address.
+    async fn firing_alerts(
+        &self,
+        address: Option<&str>,
+        matchers: &BTreeMap<String, String>,
+    ) -> Result<Vec<FiringAlert>, AlertmanagerError>;
+
+    /// Downcast support for testing (allows accessing mock-specific methods)
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+/// Outcome of evaluating an alert gate
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertGateDecision {
+    /// No matching alerts firing, progression may continue
+    Open,
+    /// Matching alerts are firing (alert names, deduplicated and sorted)
+    Blocked(Vec<String>),
+}
+
+/// Evaluate an alert gate against Alertmanager
+///
+/// Query failures are resolved by the gate's `onError` policy: `Block` (the
+/// default) holds the rollout, `Ignore` lets it proceed.
+pub async fn check_alert_gate(gate: &AlertGate, querier: &dyn AlertQuerier) -> AlertGateDecision {
+    match querier
+        .firing_alerts(gate.address.as_deref(), &gate.matchers)
+        .await
+    {
+        Ok(alerts) if alerts.is_empty() => AlertGateDecision::Open,
+        Ok(alerts) => {
+            let mut names: Vec<String> = alerts.into_iter().map(|a| a.name).collect();
+            names.sort();
+            names.dedup();
+            AlertGateDecision::Blocked(names)
+        }
+        Err(e) => match gate.on_error {
+            AlertGateErrorPolicy::Block => {
+                warn!(error = %e, "Alertmanager query failed, holding alert-gated step");
+                AlertGateDecision::Blocked(vec![format!("alertmanager unavailable: {}", e)])
+            }
+            AlertGateErrorPolicy::Ignore => {
+                warn!(error = %e, "Alertmanager query failed, ignoring alert gate");
+                AlertGateDecision::Open
+            }
+        },
+    }
+}
+
+/// Build Alertmanager v2 `filter` parameters (`name="value"`) from label matchers
+pub fn build_alert_filters(matchers: &BTreeMap<String, String>) -> Vec<String> {
+    matchers
+        .iter()
+        .map(|(name, value)| {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("{}=\"{}\"", name, escaped)
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertmanagerAlert {
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+/// Parse an Alertmanager `GET /api/v2/alerts` response body
+pub fn parse_alerts_response(body: &str) -> Result<Vec<FiringAlert>, AlertmanagerError> {
+    let alerts: Vec<AlertmanagerAlert> = serde_json::from_str(body)
+        .map_err(|e| AlertmanagerError::ParseError(format!("Invalid JSON: {}", e)))?;
+
+    Ok(alerts
+        .into_iter()
+        .map(|a| FiringAlert {
+            name: a
+                .labels
+                .get("alertname")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            labels: a.labels,
+        })
+        .collect())
+}
+
+/// Production Alertmanager client
+pub struct HttpAlertmanagerClient {
+    client: reqwest::Client,
+    default_address: Option<String>,
+}
+
+impl HttpAlertmanagerClient {
+    pub fn new(default_address: Option<String>) -> Self {
+        let client = match reqwest::Client::builder()
+            .timeout(ALERTMANAGER_TIMEOUT)
+            .build()
+        {
+            Ok(c) => c,
+            Err(e) => {
+                warn!(error = %e, "Failed to build Alertmanager HTTP client, using default");
+                reqwest::Client::new()
+            }
+        };
+        Self {
+            client,
+            default_address: default_address.filter(|a| !a.is_empty()),
+        }
+    }
+
+    /// Create a client using `KULTA_ALERTMANAGER_ADDRESS` as the default address
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("KULTA_ALERTMANAGER_ADDRESS").ok())
+    }
+}
+
+#[async_trait]
+impl AlertQuerier for HttpAlertmanagerClient {
+    async fn firing_alerts(
+        &self,
+        address: Option<&str>,
+        matchers: &BTreeMap<String, String>,
+    ) -> Result<Vec<FiringAlert>, AlertmanagerError> {
+        let address = address
+            .or(self.default_address.as_deref())
+            .ok_or(AlertmanagerError::NotConfigured)?;
+        let url = format!("{}/api/v2/alerts", address.trim_end_matches('/'));
+
+        let mut query: Vec<(&str, String)> = vec![
+            ("active", "true".to_string()),
+            ("silenced", "false".to_string()),
+            ("inhibited", "false".to_string()),
+        ];
+        query.extend(
+            build_alert_filters(matchers)
+                .into_iter()
+                .map(|f| ("filter", f)),
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| AlertmanagerError::HttpError(format!("HTTP request failed: {}", e)))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AlertmanagerError::HttpError(format!("Failed to read response: {}", e)))?;
+
+        if !status.is_success() {
+            return Err(AlertmanagerError::HttpError(format!(
+                "HTTP {}: {}",
+                status,
+                body.chars().take(200).collect::<String>()
+            )));
+        }
+
+        parse_alerts_response(&body)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Mock Alertmanager client for testing
+///
+/// Returns the configured alerts (filtered by matchers), or an error if one is set.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MockAlertmanagerClient {
+    alerts: std::sync::Arc<std::sync::Mutex<Vec<FiringAlert>>>,
+    fail: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+#[cfg(test)]
+impl MockAlertmanagerClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a firing alert with the given name and extra labels
+    pub fn fire(&self, name: &str, labels: &[(&str, &str)]) {
+        let mut all: BTreeMap<String, String> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        all.insert("alertname".to_string(), name.to_string());
+        if let Ok(mut alerts) = self.alerts.lock() {
+            alerts.push(FiringAlert {
+                name: name.to_string(),
+                labels: all,
+            });
+        }
+    }
+
+    /// Make every query fail
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.fail
+            .store(unavailable, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl AlertQuerier for MockAlertmanagerClient {
+    async fn firing_alerts(
+        &self,
+        _address: Option<&str>,
+        matchers: &BTreeMap<String, String>,
+    ) -> Result<Vec<FiringAlert>, AlertmanagerError> {
+        if self.fail.load(std::sync::atomic::Ordering::SeqCst) {
+            return Err(AlertmanagerError::HttpError(
+                "connection refused".to_string(),
+            ));
+        }
+        let alerts = self
+            .alerts
+            .lock()
+            .map_err(|_| AlertmanagerError::HttpError("Lock poisoned".to_string()))?;
+        Ok(alerts
+            .iter()
+            .filter(|a| matchers.iter().all(|(k, v)| a.labels.get(k) == Some(v)))
+            .cloned()
+            .collect())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn gate(matchers: &[(&str, &str)], on_error: AlertGateErrorPolicy) -> AlertGate {
+        AlertGate {
+            matchers: matchers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            address: None,
+            on_error,
+        }
+    }
+
+    #[test]
+    fn test_build_alert_filters_escapes_values() {
+        let matchers: BTreeMap<String, String> = [
+            ("alertname".to_string(), "HighErrorRate".to_string()),
+            ("service".to_string(), r#"check"out"#.to_string()),
+        ]
+        .into();
+
+        let filters = build_alert_filters(&matchers);
+
+        assert_eq!(
+            filters,
+            vec![
+                r#"alertname="HighErrorRate""#.to_string(),
+                r#"service="check\"out""#.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_alerts_response() {
+        let body = r#"[
+            {"labels": {"alertname": "HighErrorRate", "service": "checkout"}, "status": {"state": "active"}},
+            {"labels": {"service": "payments"}, "status": {"state": "active"}}
+        ]"#;
+
+        let alerts = parse_alerts_response(body).unwrap();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].name, "HighErrorRate");
+        assert_eq!(alerts[0].labels.get("service").unwrap(), "checkout");
+        assert_eq!(alerts[1].name, "unknown");
+    }
+
+    #[test]
+    fn test_parse_alerts_response_rejects_invalid_json() {
+        assert!(matches!(
+            parse_alerts_response("not json"),
+            Err(AlertmanagerError::ParseError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gate_open_when_no_matching_alerts() {
+        let mock = MockAlertmanagerClient::new();
+        mock.fire("DiskFull", &[("service", "logging")]);
+
+        let decision = check_alert_gate(
+            &gate(&[("service", "checkout")], AlertGateErrorPolicy::Block),
+            &mock,
+        )
+        .await;
+
+        assert_eq!(decision, AlertGateDecision::Open);
+    }
+
+    #[tokio::test]
+    async fn test_gate_blocked_lists_firing_alert_names() {
+        let mock = MockAlertmanagerClient::new();
+        mock.fire("HighLatency", &[("service", "checkout")]);
+        mock.fire("HighErrorRate", &[("service", "checkout")]);
+        mock.fire("HighErrorRate", &[("service", "checkout"), ("pod", "b")]);
+
+        let decision = check_alert_gate(
+            &gate(&[("service", "checkout")], AlertGateErrorPolicy::Block),
+            &mock,
+        )
+        .await;
+
+        assert_eq!(
+            decision,
+            AlertGateDecision::Blocked(vec![
+                "HighErrorRate".to_string(),
+                "HighLatency".to_string()
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gate_error_policy() {
+        let mock = MockAlertmanagerClient::new();
+        mock.set_unavailable(true);
+
+        let blocked = check_alert_gate(
+            &gate(&[("service", "checkout")], AlertGateErrorPolicy::Block),
+            &mock,
+        )
+        .await;
+        assert!(matches!(blocked, AlertGateDecision::Blocked(_)));
+
+        let open = check_alert_gate(
+            &gate(&[("service", "checkout")], AlertGateErrorPolicy::Ignore),
+            &mock,
+        )
+        .await;
+        assert_eq!(open, AlertGateDecision::Open);
+    }
+
+    #[tokio::test]
+    async fn test_http_client_without_address_is_not_configured() {
+        let client = HttpAlertmanagerClient::new(None);
+
+        let result = client.firing_alerts(None, &BTreeMap::new()).await;
+
+        assert!(matches!(result, Err(AlertmanagerError::NotConfigured)));
+    }
+}
@@ -0,0 +1,249 @@
+//! Argo CD health assessment of Rollouts
+//!
+//! Argo CD doesn't know KULTA's phases, so an app with a Rollout in it shows
+//! `Unknown` health unless a custom health check maps them. The mapping is
+//! defined here once: [`argocd_health`] applies it to a Rollout, and
+//! [`health_check_lua`] renders the same mapping as the Lua script Argo CD
+//! runs (served at `/argocd/health.lua` for
+//! `resource.customizations.health.kulta.io_Rollout` in `argocd-cm`).
+//!
+//! The status contract the check relies on: `status.phase`, `status.message`,
+//! `status.observedGeneration` and a `Degraded` entry in `status.conditions`.
+//! A status older than the spec (the controller hasn't seen the latest
+//! change yet) is `Progressing`, so a sync doesn't look healthy before the
+//! rollout has even started. A `Degraded` condition (e.g., a referenced
+//! Service is missing) wins over the phase.
+
+use crate::controller::rollout::DEGRADED_CONDITION;
+use crate::crd::rollout::{Phase, Rollout};
+use serde::Serialize;
+
+/// Health status understood by Argo CD
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgoHealthStatus {
+    Healthy,
+    Progressing,
+    Degraded,
+    Suspended,
+}
+
+impl ArgoHealthStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ArgoHealthStatus::Healthy => "Healthy",
+            ArgoHealthStatus::Progressing => "Progressing",
+            ArgoHealthStatus::Degraded => "Degraded",
+            ArgoHealthStatus::Suspended => "Suspended",
+        }
+    }
+}
+
+/// Health of a Rollout as Argo CD reports it
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArgoHealth {
+    pub status: ArgoHealthStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Every phase, in the order the Lua mapping lists them
+const PHASES: [Phase; 9] = [
+    Phase::Initializing,
+    Phase::Pending,
+    Phase::Progressing,
+    Phase::Paused,
+    Phase::Preview,
+    Phase::Experimenting,
+    Phase::Concluded,
+    Phase::Completed,
+    Phase::Failed,
+];
+
+/// Argo CD health of a phase
+///
+/// Paused canaries and blue-green previews wait for a promotion, which Argo
+/// CD shows as `Suspended`; a failed (rolled back) rollout is `Degraded`.
+pub fn phase_health(phase: &Phase) -> ArgoHealthStatus {
+    match phase {
+        Phase::Initializing | Phase::Pending | Phase::Progressing | Phase::Experimenting => {
+            ArgoHealthStatus::Progressing
+        }
+        Phase::Paused | Phase::Preview => ArgoHealthStatus::Suspended,
+        Phase::Concluded | Phase::Completed => ArgoHealthStatus::Healthy,
+        Phase::Failed => ArgoHealthStatus::Degraded,
+    }
+}
+
+/// Argo CD health of a Rollout
+pub fn argocd_health(rollout: &Rollout) -> ArgoHealth {
+    let Some(status) = rollout.status.as_ref() else {
+        return ArgoHealth {
+            status: ArgoHealthStatus::Progressing,
+            message: Some("Waiting for the rollout to be initialized".to_string()),
+        };
+    };
+    if let (Some(generation), Some(observed)) =
+        (rollout.metadata.generation, status.observed_generation)
+    {
+        if observed < generation {
+            return ArgoHealth {
+                status: ArgoHealthStatus::Progressing,
+                message: Some("Waiting for the rollout spec change to be observed".to_string()),
+            };
+        }
+    }
+    if let Some(degraded) = status
+        .conditions
+        .iter()
+        .find(|c| c.type_ == DEGRADED_CONDITION && c.status == "True")
+    {
+        return ArgoHealth {
+            status: ArgoHealthStatus::Degraded,
+            message: degraded.message.clone(),
+        };
+    }
+    match &status.phase {
+        Some(phase) => ArgoHealth {
+            status: phase_health(phase),
+            message: status.message.clone(),
+        },
+        None => ArgoHealth {
+            status: ArgoHealthStatus::Progressing,
+            message: Some("Waiting for the rollout to be initialized".to_string()),
+        },
+    }
+}
+
+/// Lua health check for `resource.customizations.health.kulta.io_Rollout`
+///
+/// Implements [`argocd_health`] in the form Argo CD runs.
+pub fn health_check_lua() -> String {
+    let mut lua = String::from(
+        r#"local hs = {}
+if obj.status == nil or obj.status.phase == nil then
+  hs.status = "Progressing"
+  hs.message = "Waiting for the rollout to be initialized"
+  return hs
+end
+if obj.metadata.generation ~= nil and obj.status.observedGeneration ~= nil
+    and obj.status.observedGeneration < obj.metadata.generation then
+  hs.status = "Progressing"
+  hs.message = "Waiting for the rollout spec change to be observed"
+  return hs
+end
+if obj.status.conditions ~= nil then
+  for _, condition in ipairs(obj.status.conditions) do
+    if condition.type == "Degraded" and condition.status == "True" then
+      hs.status = "Degraded"
+      hs.message = condition.message
+      return hs
+    end
+  end
+end
+local health = {
+"#,
+    );
+    for phase in &PHASES {
+        lua.push_str(&format!(
+            "  {:?} = \"{}\",\n",
+            phase,
+            phase_health(phase).as_str()
+        ));
+    }
+    lua.push_str(
+        r#"}
+hs.status = health[obj.status.phase] or "Unknown"
+hs.message = obj.status.message
+return hs
+"#,
+    );
+    lua
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{RolloutCondition, RolloutStatus, SimpleStrategy};
+
+    fn rollout(generation: i64, status: Option<RolloutStatus>) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        rollout.metadata.generation = Some(generation);
+        rollout.status = status;
+        rollout
+    }
+
+    #[test]
+    fn test_argocd_health_follows_phase_and_generation() {
+        assert_eq!(
+            argocd_health(&rollout(1, None)).status,
+            ArgoHealthStatus::Progressing
+        );
+
+        let failed = RolloutStatus {
+            phase: Some(Phase::Failed),
+            message: Some("Rollback triggered".to_string()),
+            observed_generation: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            argocd_health(&rollout(2, Some(failed.clone()))),
+            ArgoHealth {
+                status: ArgoHealthStatus::Degraded,
+                message: Some("Rollback triggered".to_string()),
+            }
+        );
+
+        // A spec change the controller hasn't seen yet
+        let stale = argocd_health(&rollout(3, Some(failed)));
+        assert_eq!(stale.status, ArgoHealthStatus::Progressing);
+
+        let paused = RolloutStatus {
+            phase: Some(Phase::Paused),
+            ..Default::default()
+        };
+        assert_eq!(
+            argocd_health(&rollout(3, Some(paused.clone()))).status,
+            ArgoHealthStatus::Suspended
+        );
+
+        let missing_service = RolloutStatus {
+            conditions: vec![RolloutCondition {
+                type_: DEGRADED_CONDITION.to_string(),
+                status: "True".to_string(),
+                reason: Some("ServiceNotFound".to_string()),
+                message: Some("Referenced Service not found".to_string()),
+                last_transition_time: None,
+            }],
+            ..paused
+        };
+        assert_eq!(
+            argocd_health(&rollout(3, Some(missing_service))),
+            ArgoHealth {
+                status: ArgoHealthStatus::Degraded,
+                message: Some("Referenced Service not found".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_health_check_lua_maps_every_phase() {
+        let lua = health_check_lua();
+        for phase in &PHASES {
+            let entry = format!("  {:?} = \"{}\",", phase, phase_health(phase).as_str());
+            assert!(lua.contains(&entry), "missing {}", entry);
+        }
+        assert!(lua.contains("Completed = \"Healthy\""));
+        assert!(lua.contains("Failed = \"Degraded\""));
+        assert!(lua.contains(&format!("condition.type == \"{}\"", DEGRADED_CONDITION)));
+        assert!(lua.ends_with("return hs\n"));
+    }
+}
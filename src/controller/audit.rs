@@ -0,0 +1,294 @@
+//! Audit log of the writes KULTA performs
+//!
+//! Answers "did KULTA do this?" when something in the cluster moved. Every
+//! create/patch issued while reconciling a Rollout (ReplicaSets, HTTPRoutes,
+//! Rollout status and annotations) is recorded as one JSON line with the
+//! object reference, a short summary of the change, the reason, and the id of
+//! the reconcile that issued it.
+//!
+//! Disabled unless configured:
+//! - `KULTA_AUDIT_LOG_PATH`: append records to this file (rotated to `.1` at 50 MB)
+//! - `KULTA_AUDIT_LOG_URL`: POST each record as JSON to this endpoint
+//!
+//! Records are collected through a task-local scope set up by the reconcile
+//! loop, so write paths don't need to carry the log or the reconcile id.
+//! Lease renewals for leader election are not recorded.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{warn, Instrument};
+
+/// Rotate the audit file once it grows past this size
+const MAX_AUDIT_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Timeout for delivering a record to the audit endpoint
+const AUDIT_HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+tokio::task_local! {
+    static SCOPE: AuditScope;
+}
+
+#[derive(Clone)]
+struct AuditScope {
+    reconcile_id: String,
+    log: Arc<AuditLog>,
+}
+
+/// Kind of write issued to the API server
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    Create,
+    Patch,
+    PatchStatus,
+    Delete,
+}
+
+/// The object a write targeted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditObjectRef {
+    pub kind: String,
+    pub namespace: String,
+    pub name: String,
+}
+
+impl AuditObjectRef {
+    pub fn new(kind: &str, namespace: &str, name: &str) -> Self {
+        AuditObjectRef {
+            kind: kind.to_string(),
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// One audit log line
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuditRecord {
+    /// When the write succeeded (RFC3339)
+    pub timestamp: String,
+    /// Id shared by all writes of one reconcile
+    pub reconcile_id: String,
+    pub operation: AuditOperation,
+    pub object: AuditObjectRef,
+    /// What changed (e.g., "replicas 3 -> 2", "phase Progressing -> Failed")
+    pub summary: String,
+    /// Why KULTA made the change
+    pub reason: String,
+}
+
+/// Audit log destination(s)
+#[derive(Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    url: Option<String>,
+    http: Option<reqwest::Client>,
+}
+
+impl AuditLog {
+    /// Audit log that records nothing
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create audit log from environment variables
+    ///
+    /// Uses:
+    /// - `KULTA_AUDIT_LOG_PATH` (file, JSON lines)
+    /// - `KULTA_AUDIT_LOG_URL` (HTTP endpoint, one POST per record)
+    pub fn from_env() -> Self {
+        let non_empty = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        Self::new(
+            non_empty("KULTA_AUDIT_LOG_PATH").map(PathBuf::from),
+            non_empty("KULTA_AUDIT_LOG_URL"),
+        )
+    }
+
+    pub fn new(path: Option<PathBuf>, url: Option<String>) -> Self {
+        let http = url.as_ref().and_then(|_| {
+            reqwest::Client::builder()
+                .timeout(AUDIT_HTTP_TIMEOUT)
+                .build()
+                .map_err(|e| warn!(error = %e, "Failed to build audit HTTP client"))
+                .ok()
+        });
+        AuditLog { path, url, http }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some() || self.url.is_some()
+    }
+
+    /// Write a record to every configured destination (failures are logged only)
+    async fn write(&self, record: &AuditRecord) {
+        if let Some(path) = &self.path {
+            if let Err(e) = append_line(path, record) {
+                warn!(error = %e, path = %path.display(), "Failed to write audit record");
+            }
+        }
+
+        if let (Some(url), Some(http)) = (&self.url, &self.http) {
+            let result = http
+                .post(url)
+                .json(record)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                warn!(error = %e, "Failed to deliver audit record");
+            }
+        }
+    }
+}
+
+fn append_line(path: &PathBuf, record: &AuditRecord) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let line = serde_json::to_string(record)?;
+
+    // Rotate instead of truncating: the previous file is evidence too
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if metadata.len() > MAX_AUDIT_FILE_BYTES {
+            let mut rotated = path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(path, rotated)?;
+        }
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Run one reconcile with a fresh reconcile id
+///
+/// Writes recorded inside `fut` are attributed to this reconcile, and log
+/// lines carry the id through the `reconcile` tracing span.
+pub async fn with_reconcile_scope<F: Future>(log: Arc<AuditLog>, fut: F) -> F::Output {
+    let reconcile_id = uuid::Uuid::new_v4().to_string();
+    let span = tracing::info_span!("reconcile", reconcile_id = %reconcile_id);
+    SCOPE
+        .scope(AuditScope { reconcile_id, log }, fut.instrument(span))
+        .await
+}
+
+/// Id of the reconcile running on this task, if any
+pub fn current_reconcile_id() -> Option<String> {
+    SCOPE.try_with(|scope| scope.reconcile_id.clone()).ok()
+}
+
+/// Record a successful write
+///
+/// No-op outside a reconcile scope or when no destination is configured.
+pub async fn record(
+    operation: AuditOperation,
+    object: AuditObjectRef,
+    summary: impl Into<String>,
+    reason: impl Into<String>,
+) {
+    let Ok(scope) = SCOPE.try_with(Clone::clone) else {
+        return;
+    };
+    if !scope.log.is_enabled() {
+        return;
+    }
+
+    let record = AuditRecord {
+        timestamp: Utc::now().to_rfc3339(),
+        reconcile_id: scope.reconcile_id,
+        operation,
+        object,
+        summary: summary.into(),
+        reason: reason.into(),
+    };
+    scope.log.write(&record).await;
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn temp_audit_path(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "kulta-audit-{}-{}.jsonl",
+            test,
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    fn read_records(path: &PathBuf) -> Vec<AuditRecord> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_records_share_reconcile_id_within_scope() {
+        let path = temp_audit_path("scope");
+        let log = Arc::new(AuditLog::new(Some(path.clone()), None));
+
+        let reconcile_id = with_reconcile_scope(log, async {
+            record(
+                AuditOperation::Create,
+                AuditObjectRef::new("ReplicaSet", "default", "app-canary"),
+                "replicas 1",
+                "ensure canary ReplicaSet",
+            )
+            .await;
+            record(
+                AuditOperation::PatchStatus,
+                AuditObjectRef::new("Rollout", "default", "app"),
+                "phase Progressing -> Completed",
+                "status update",
+            )
+            .await;
+            current_reconcile_id().unwrap()
+        })
+        .await;
+
+        let records = read_records(&path);
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.reconcile_id == reconcile_id));
+        assert_eq!(records[0].operation, AuditOperation::Create);
+        assert_eq!(records[1].object.kind, "Rollout");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_outside_scope_is_noop() {
+        assert_eq!(current_reconcile_id(), None);
+        // Must not panic without a scope
+        record(
+            AuditOperation::Patch,
+            AuditObjectRef::new("HTTPRoute", "default", "route"),
+            "",
+            "",
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_record_serialization() {
+        let record = AuditRecord {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            reconcile_id: "abc".to_string(),
+            operation: AuditOperation::PatchStatus,
+            object: AuditObjectRef::new("Rollout", "prod", "app"),
+            summary: "phase Progressing -> Failed".to_string(),
+            reason: "metrics exceeded thresholds".to_string(),
+        };
+
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["operation"], "patch_status");
+        assert_eq!(json["object"]["namespace"], "prod");
+    }
+}
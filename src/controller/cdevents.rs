@@ -1,11 +1,19 @@
 //! CDEvents emission for rollout observability.
 //! See the project documentation for specification.
 
+use crate::controller::cdevents_data::{CustomData, EventReason};
+use crate::controller::cdevents_retry::{Delivery, PendingEvent, RetryPolicy, RetryQueue};
+use crate::controller::release_hooks::analysis_summary;
 use crate::crd::rollout::{Rollout, RolloutStatus};
+use crate::server::metrics::SharedMetrics;
 use async_trait::async_trait;
-use cloudevents::Event;
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use cloudevents::{AttributesReader, Event};
+use std::io::Write;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 use thiserror::Error;
+use tracing::warn;
 
 #[derive(Debug, Error)]
 pub enum CDEventsError {
@@ -20,12 +28,359 @@ pub enum CDEventsError {
 #[async_trait]
 pub trait EventSink: Send + Sync {
     async fn send(&self, event: &Event) -> Result<(), CDEventsError>;
+
+    /// Source and environment identifiers stamped on events sent to this sink
+    ///
+    /// Defaults to [`EventSourceConfig::default`].
+    fn source_config(&self) -> &EventSourceConfig {
+        static DEFAULT: OnceLock<EventSourceConfig> = OnceLock::new();
+        DEFAULT.get_or_init(EventSourceConfig::default)
+    }
+}
+
+/// Default CloudEvent `source`
+pub const DEFAULT_EVENT_SOURCE: &str = "https://kulta.io";
+
+/// Default CDEvent `subject.source`
+pub const DEFAULT_SUBJECT_SOURCE: &str = "https://kulta.io/controller";
+
+/// Default environment id template
+pub const DEFAULT_ENVIRONMENT_ID: &str = "{namespace}/{name}";
+
+/// Default environment source template (the Rollout's API path)
+pub const DEFAULT_ENVIRONMENT_SOURCE: &str =
+    "/apis/kulta.io/v1alpha1/namespaces/{namespace}/rollouts/{name}";
+
+/// Identifiers used to attribute CDEvents to this controller
+///
+/// Every field is a template supporting the placeholders `{namespace}`,
+/// `{name}` (the Rollout) and `{cluster}`, so several controllers in one
+/// organization can publish to the same sink without colliding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventSourceConfig {
+    /// CloudEvent `source` attribute
+    pub source: String,
+    /// CDEvent `subject.source`
+    pub subject_source: String,
+    /// `subject.content.environment.id`
+    pub environment_id: String,
+    /// `subject.content.environment.source`
+    pub environment_source: String,
+    /// Value substituted for `{cluster}`
+    pub cluster: String,
+}
+
+impl Default for EventSourceConfig {
+    fn default() -> Self {
+        EventSourceConfig {
+            source: DEFAULT_EVENT_SOURCE.to_string(),
+            subject_source: DEFAULT_SUBJECT_SOURCE.to_string(),
+            environment_id: DEFAULT_ENVIRONMENT_ID.to_string(),
+            environment_source: DEFAULT_ENVIRONMENT_SOURCE.to_string(),
+            cluster: String::new(),
+        }
+    }
+}
+
+impl EventSourceConfig {
+    /// Load identifiers from environment variables, falling back to defaults
+    ///
+    /// - KULTA_CDEVENTS_SOURCE: CloudEvent source (default: https://kulta.io)
+    /// - KULTA_CDEVENTS_SUBJECT_SOURCE: subject source (default: https://kulta.io/controller)
+    /// - KULTA_CDEVENTS_ENVIRONMENT_ID: environment id (default: {namespace}/{name})
+    /// - KULTA_CDEVENTS_ENVIRONMENT_SOURCE: environment source
+    ///   (default: /apis/kulta.io/v1alpha1/namespaces/{namespace}/rollouts/{name})
+    /// - KULTA_CLUSTER_NAME: value for the {cluster} placeholder
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Build from an arbitrary key lookup (used by `from_env` and tests)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let get = |key: &str, default: String| {
+            lookup(key)
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(default)
+        };
+
+        EventSourceConfig {
+            source: get("KULTA_CDEVENTS_SOURCE", defaults.source),
+            subject_source: get("KULTA_CDEVENTS_SUBJECT_SOURCE", defaults.subject_source),
+            environment_id: get("KULTA_CDEVENTS_ENVIRONMENT_ID", defaults.environment_id),
+            environment_source: get(
+                "KULTA_CDEVENTS_ENVIRONMENT_SOURCE",
+                defaults.environment_source,
+            ),
+            cluster: get("KULTA_CLUSTER_NAME", defaults.cluster),
+        }
+    }
+
+    fn render(&self, template: &str, namespace: &str, name: &str) -> String {
+        template
+            .replace("{namespace}", namespace)
+            .replace("{name}", name)
+            .replace("{cluster}", &self.cluster)
+    }
+
+    pub fn source_for(&self, namespace: &str, name: &str) -> String {
+        self.render(&self.source, namespace, name)
+    }
+
+    pub fn subject_source_for(&self, namespace: &str, name: &str) -> String {
+        self.render(&self.subject_source, namespace, name)
+    }
+
+    pub fn environment_id_for(&self, namespace: &str, name: &str) -> String {
+        self.render(&self.environment_id, namespace, name)
+    }
+
+    pub fn environment_source_for(&self, namespace: &str, name: &str) -> String {
+        self.render(&self.environment_source, namespace, name)
+    }
+}
+
+/// Default timeout for one POST to the CDEvents sink
+pub const DEFAULT_CDEVENTS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Timeout for establishing a connection to the CDEvents sink
+const CDEVENTS_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long an idle pooled connection to the sink is kept open
+const CDEVENTS_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Request timeout from `KULTA_CDEVENTS_TIMEOUT_SECS`
+///
+/// Unset, empty, zero or non-numeric values fall back to
+/// [`DEFAULT_CDEVENTS_TIMEOUT`].
+pub(crate) fn parse_timeout(value: Option<&str>) -> Duration {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return DEFAULT_CDEVENTS_TIMEOUT;
+    };
+    match value.parse::<u64>() {
+        Ok(secs) if secs > 0 => Duration::from_secs(secs),
+        _ => {
+            warn!(
+                value = %value,
+                "Invalid KULTA_CDEVENTS_TIMEOUT_SECS, using default"
+            );
+            DEFAULT_CDEVENTS_TIMEOUT
+        }
+    }
+}
+
+/// Build the HTTP client shared by every event sent to one sink
+///
+/// Connections are pooled and kept alive between events. An invalid proxy
+/// URL is logged and ignored rather than disabling event emission.
+fn build_sink_client(timeout: Duration, proxy_url: Option<&str>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(CDEVENTS_CONNECT_TIMEOUT.min(timeout))
+        .pool_idle_timeout(CDEVENTS_POOL_IDLE_TIMEOUT);
+
+    if let Some(proxy_url) = proxy_url {
+        match reqwest::Proxy::all(proxy_url) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => {
+                warn!(error = %e, "Invalid KULTA_CDEVENTS_PROXY_URL, sending events without proxy")
+            }
+        }
+    }
+
+    match builder.build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "Failed to build CDEvents HTTP client, using default");
+            reqwest::Client::new()
+        }
+    }
+}
+
+/// Compression of CDEvents request bodies (`KULTA_CDEVENTS_COMPRESSION`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PayloadCompression {
+    /// Plain JSON
+    #[default]
+    None,
+    /// `Content-Encoding: gzip`
+    Gzip,
+    /// `Content-Encoding: zstd`
+    Zstd,
+}
+
+impl PayloadCompression {
+    /// Compression from `KULTA_CDEVENTS_COMPRESSION`
+    ///
+    /// `gzip` or `zstd`; unset, empty or `none` sends plain JSON. Unknown
+    /// values are logged and ignored.
+    pub(crate) fn parse(value: Option<&str>) -> Self {
+        let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+            return PayloadCompression::None;
+        };
+        match value.to_ascii_lowercase().as_str() {
+            "none" => PayloadCompression::None,
+            "gzip" => PayloadCompression::Gzip,
+            "zstd" => PayloadCompression::Zstd,
+            _ => {
+                warn!(
+                    value = %value,
+                    "Invalid KULTA_CDEVENTS_COMPRESSION, sending uncompressed events"
+                );
+                PayloadCompression::None
+            }
+        }
+    }
+
+    /// `Content-Encoding` header value, if the body is compressed
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            PayloadCompression::None => None,
+            PayloadCompression::Gzip => Some("gzip"),
+            PayloadCompression::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Compress a request body
+    pub fn compress(self, body: Vec<u8>) -> Result<Vec<u8>, CDEventsError> {
+        let compressed = match self {
+            PayloadCompression::None => return Ok(body),
+            PayloadCompression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&body).and_then(|_| encoder.finish())
+            }
+            PayloadCompression::Zstd => zstd::encode_all(body.as_slice(), 0),
+        };
+        compressed.map_err(|e| CDEventsError::Generic(format!("Failed to compress event: {}", e)))
+    }
+}
+
+/// Payload size cap from `KULTA_CDEVENTS_MAX_BYTES`
+///
+/// Unset or empty means no cap; zero and non-numeric values are logged and
+/// ignored.
+pub(crate) fn parse_max_bytes(value: Option<&str>) -> Option<usize> {
+    let value = value.map(str::trim).filter(|v| !v.is_empty())?;
+    match value.parse::<usize>() {
+        Ok(bytes) if bytes > 0 => Some(bytes),
+        _ => {
+            warn!(
+                value = %value,
+                "Invalid KULTA_CDEVENTS_MAX_BYTES, sending events without a size cap"
+            );
+            None
+        }
+    }
+}
+
+/// A serialized event, fitted under the size cap
+#[derive(Debug, PartialEq)]
+pub enum FittedPayload {
+    /// The event as is
+    Whole(Vec<u8>),
+    /// `customData.kulta` was cut down and marked `"truncated": true`
+    Truncated(Vec<u8>),
+    /// Too large even with `customData.kulta` cut down to the rollout
+    /// identity; carries the smallest size reached
+    TooLarge(usize),
+}
+
+/// Cut `customData.kulta` down one rule further
+///
+/// The rules, in order: reduce `template_diff` to its hashes, drop the
+/// per-metric experiment results, drop `links`, then keep only `version`,
+/// `rollout`, `strategy` and `decision`. Returns false once nothing is left
+/// to cut. The CDEvent context and subject are never touched.
+fn truncate_custom_data(
+    kulta: &mut serde_json::Map<String, serde_json::Value>,
+    rule: usize,
+) -> bool {
+    match rule {
+        0 => {
+            if let Some(diff) = kulta
+                .get_mut("template_diff")
+                .and_then(serde_json::Value::as_object_mut)
+            {
+                diff.retain(|key, _| key == "from_hash" || key == "to_hash");
+            }
+        }
+        1 => {
+            if let Some(experiment) = kulta
+                .get_mut("experiment")
+                .and_then(serde_json::Value::as_object_mut)
+            {
+                experiment.remove("metrics");
+            }
+        }
+        2 => {
+            kulta.remove("links");
+        }
+        3 => kulta.retain(|key, _| {
+            matches!(
+                key.as_str(),
+                "version" | "rollout" | "strategy" | "decision"
+            )
+        }),
+        _ => return false,
+    }
+    kulta.insert("truncated".to_string(), serde_json::Value::Bool(true));
+    true
+}
+
+/// Serialize `event`, truncating `customData` to fit `max_bytes`
+///
+/// The cap applies to the JSON body before compression. Without a cap the
+/// event is always sent whole.
+pub fn fit_payload(
+    event: &Event,
+    max_bytes: Option<usize>,
+) -> Result<FittedPayload, CDEventsError> {
+    let serialize = |value: &serde_json::Value| {
+        serde_json::to_vec(value)
+            .map_err(|e| CDEventsError::Generic(format!("Failed to serialize event: {}", e)))
+    };
+    let mut value = serde_json::to_value(event)
+        .map_err(|e| CDEventsError::Generic(format!("Failed to serialize event: {}", e)))?;
+    let mut body = serialize(&value)?;
+    let Some(max_bytes) = max_bytes else {
+        return Ok(FittedPayload::Whole(body));
+    };
+    if body.len() <= max_bytes {
+        return Ok(FittedPayload::Whole(body));
+    }
+
+    for rule in 0.. {
+        let Some(kulta) = value
+            .pointer_mut("/data/customData/kulta")
+            .and_then(serde_json::Value::as_object_mut)
+        else {
+            break;
+        };
+        if !truncate_custom_data(kulta, rule) {
+            break;
+        }
+        body = serialize(&value)?;
+        if body.len() <= max_bytes {
+            return Ok(FittedPayload::Truncated(body));
+        }
+    }
+    Ok(FittedPayload::TooLarge(body.len()))
 }
 
 /// Production event sink that sends CloudEvents via HTTP POST
 pub struct HttpEventSink {
     enabled: bool,
     sink_url: Option<String>,
+    source_config: EventSourceConfig,
+    client: reqwest::Client,
+    compression: PayloadCompression,
+    max_bytes: Option<usize>,
+    metrics: Option<SharedMetrics>,
+    /// Events whose delivery failed, retried in the background
+    retry: Arc<RetryQueue>,
+    /// Set once the retry task has been started
+    retry_worker: OnceLock<()>,
 }
 
 impl Default for HttpEventSink {
@@ -40,14 +395,82 @@ impl HttpEventSink {
     /// Configuration from environment variables:
     /// - KULTA_CDEVENTS_ENABLED: "true" to enable CDEvents emission (default: false)
     /// - KULTA_CDEVENTS_SINK_URL: HTTP endpoint URL for CloudEvents (optional)
+    /// - KULTA_CDEVENTS_TIMEOUT_SECS: timeout per POST in seconds (default: 10)
+    /// - KULTA_CDEVENTS_PROXY_URL: HTTP(S) proxy for reaching the sink (optional)
+    /// - KULTA_CDEVENTS_COMPRESSION: "gzip" or "zstd" request bodies (default: none)
+    /// - KULTA_CDEVENTS_MAX_BYTES: size cap per event before compression (optional)
+    /// - KULTA_CDEVENTS_RETRY_*: retry queue for failed deliveries (see
+    ///   [`cdevents_retry`](crate::controller::cdevents_retry))
+    ///
+    /// Event identifiers are read via `EventSourceConfig::from_env`.
     pub fn new() -> Self {
         let enabled = std::env::var("KULTA_CDEVENTS_ENABLED")
             .unwrap_or_else(|_| "false".to_string())
             == "true";
 
         let sink_url = std::env::var("KULTA_CDEVENTS_SINK_URL").ok();
+        let timeout = parse_timeout(std::env::var("KULTA_CDEVENTS_TIMEOUT_SECS").ok().as_deref());
+        let proxy_url = std::env::var("KULTA_CDEVENTS_PROXY_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        HttpEventSink {
+            enabled,
+            sink_url,
+            source_config: EventSourceConfig::from_env(),
+            client: build_sink_client(timeout, proxy_url.as_deref()),
+            compression: PayloadCompression::parse(
+                std::env::var("KULTA_CDEVENTS_COMPRESSION").ok().as_deref(),
+            ),
+            max_bytes: parse_max_bytes(std::env::var("KULTA_CDEVENTS_MAX_BYTES").ok().as_deref()),
+            metrics: None,
+            retry: Arc::new(RetryQueue::new(RetryPolicy::from_env(), None)),
+            retry_worker: OnceLock::new(),
+        }
+    }
+
+    /// Count oversized and dropped events (`kulta_cdevents_oversized_total`,
+    /// `kulta_cdevents_dropped_total`) and the retry backlog
+    pub fn with_metrics(mut self, metrics: SharedMetrics) -> Self {
+        self.retry = Arc::new(RetryQueue::new(
+            self.retry.policy().clone(),
+            Some(metrics.clone()),
+        ));
+        self.metrics = Some(metrics);
+        self
+    }
 
-        HttpEventSink { enabled, sink_url }
+    /// Queue a failed event, starting the retry task on first use
+    fn queue_for_retry(&self, url: &str, event: PendingEvent) -> bool {
+        self.retry_worker.get_or_init(|| {
+            let client = self.client.clone();
+            let url = url.to_string();
+            let encoding = self.compression.content_encoding();
+            tokio::spawn(
+                Arc::clone(&self.retry)
+                    .run(move |body| post_event(client.clone(), url.clone(), encoding, body)),
+            );
+        });
+        self.retry.push(event)
+    }
+}
+
+/// POST one encoded CloudEvent to the sink
+async fn post_event(
+    client: reqwest::Client,
+    url: String,
+    encoding: Option<&'static str>,
+    body: Vec<u8>,
+) -> Delivery {
+    let mut request = client
+        .post(&url)
+        .header("Content-Type", "application/cloudevents+json");
+    if let Some(encoding) = encoding {
+        request = request.header("Content-Encoding", encoding);
+    }
+    match request.body(body).send().await {
+        Ok(response) => Delivery::from_status(response.status()),
+        Err(e) => Delivery::Retryable(format!("HTTP POST failed: {}", e)),
     }
 }
 
@@ -62,17 +485,75 @@ impl EventSink for HttpEventSink {
             return Ok(()); // No sink URL configured, skip
         };
 
-        // Send CloudEvent as JSON via HTTP POST
-        let client = reqwest::Client::new();
-        client
-            .post(url)
-            .header("Content-Type", "application/cloudevents+json")
-            .json(event)
-            .send()
-            .await
-            .map_err(|e| CDEventsError::Generic(format!("HTTP POST failed: {}", e)))?;
+        let record_oversized = |action: &str| {
+            if let Some(metrics) = &self.metrics {
+                metrics.record_cdevent_oversized(event.ty(), action);
+            }
+        };
+        let body = match fit_payload(event, self.max_bytes)? {
+            FittedPayload::Whole(body) => body,
+            FittedPayload::Truncated(body) => {
+                warn!(
+                    event_type = event.ty(),
+                    max_bytes = ?self.max_bytes,
+                    "CDEvent over size cap, sending with truncated customData"
+                );
+                record_oversized("truncated");
+                body
+            }
+            FittedPayload::TooLarge(size) => {
+                warn!(
+                    event_type = event.ty(),
+                    size,
+                    max_bytes = ?self.max_bytes,
+                    "CDEvent over size cap even with customData truncated, dropping it"
+                );
+                record_oversized("dropped");
+                return Ok(());
+            }
+        };
 
-        Ok(())
+        let body = self.compression.compress(body)?;
+        let pending = |body| PendingEvent {
+            event_type: event.ty().to_string(),
+            body,
+            attempts: 1,
+        };
+
+        // Keep events in order behind ones still waiting for a retry
+        if !self.retry.is_empty() {
+            if self.queue_for_retry(url, pending(body)) {
+                return Ok(());
+            }
+            return Err(CDEventsError::Generic(
+                "CDEvents retry queue is full, event dropped".to_string(),
+            ));
+        }
+
+        // Send CloudEvent as JSON via HTTP POST on the shared client
+        let encoding = self.compression.content_encoding();
+        match post_event(self.client.clone(), url.clone(), encoding, body.clone()).await {
+            Delivery::Delivered => Ok(()),
+            Delivery::Rejected(reason) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_cdevent_dropped(event.ty(), "rejected");
+                }
+                Err(CDEventsError::Generic(reason))
+            }
+            Delivery::Retryable(reason) => {
+                if self.queue_for_retry(url, pending(body)) {
+                    warn!(event_type = event.ty(), reason = %reason,
+                        "CDEvent delivery failed, queued for retry");
+                    Ok(())
+                } else {
+                    Err(CDEventsError::Generic(reason))
+                }
+            }
+        }
+    }
+
+    fn source_config(&self) -> &EventSourceConfig {
+        &self.source_config
     }
 }
 
@@ -80,6 +561,7 @@ impl EventSink for HttpEventSink {
 #[cfg(test)]
 pub struct MockEventSink {
     events: std::sync::Arc<std::sync::Mutex<Vec<Event>>>,
+    source_config: EventSourceConfig,
 }
 
 #[cfg(test)]
@@ -94,6 +576,14 @@ impl MockEventSink {
     pub fn new() -> Self {
         MockEventSink {
             events: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            source_config: EventSourceConfig::default(),
+        }
+    }
+
+    pub fn with_source_config(source_config: EventSourceConfig) -> Self {
+        MockEventSink {
+            events: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            source_config,
         }
     }
 
@@ -111,6 +601,10 @@ impl EventSink for MockEventSink {
         self.events.lock().unwrap().push(event.clone());
         Ok(())
     }
+
+    fn source_config(&self) -> &EventSourceConfig {
+        &self.source_config
+    }
 }
 
 /// Emit CDEvent based on status transition
@@ -125,6 +619,8 @@ pub async fn emit_status_change_event(
 ) -> Result<(), CDEventsError> {
     use crate::crd::rollout::Phase;
 
+    let identity = sink.source_config();
+
     // Detect transition: None → Progressing/Completed/Preview/Experimenting = service.deployed
     // (Simple strategy goes directly to Completed, Canary goes to Progressing,
     // Blue-green goes to Preview, A/B Testing goes to Experimenting)
@@ -137,6 +633,15 @@ pub async fn emit_status_change_event(
                 | Some(Phase::Experimenting)
         );
 
+    // Detect new revision: pod template hash changed on an already-initialized rollout
+    let is_new_revision = match (old_status, &new_status.pod_template_hash) {
+        (Some(old), Some(new_hash)) => old
+            .pod_template_hash
+            .as_ref()
+            .is_some_and(|old_hash| old_hash != new_hash),
+        _ => false,
+    };
+
     // Detect A/B experiment conclusion: Experimenting → Concluded
     let is_experiment_concluded = match (old_status, &new_status.phase) {
         (Some(old), Some(Phase::Concluded)) => {
@@ -161,42 +666,71 @@ pub async fn emit_status_change_event(
     let is_completion = matches!(new_status.phase, Some(Phase::Completed));
 
     if is_initialization {
-        let event = build_service_deployed_event(rollout, new_status)?;
+        let event = build_service_deployed_event(
+            rollout,
+            new_status,
+            identity,
+            EventReason::Initialization,
+        )?;
         sink.send(&event).await?;
 
         // For simple strategy (direct to Completed), also emit service.published
         if is_completion {
-            let event = build_service_published_event(rollout, new_status)?;
+            let event = build_service_published_event(rollout, new_status, identity)?;
             sink.send(&event).await?;
         }
-
-        Ok(())
+    } else if is_new_revision {
+        let event = build_service_deployed_event(
+            rollout,
+            new_status,
+            identity,
+            EventReason::RevisionChanged,
+        )?;
+        sink.send(&event).await?;
     } else if is_step_progression {
-        let event = build_service_upgraded_event(rollout, new_status)?;
+        let event = build_service_upgraded_event(rollout, new_status, identity)?;
         sink.send(&event).await?;
-        Ok(())
     } else if is_rollback {
-        let event = build_service_rolledback_event(rollout, new_status)?;
+        let event = build_service_rolledback_event(rollout, new_status, identity)?;
         sink.send(&event).await?;
-        Ok(())
     } else if is_experiment_concluded {
-        let event = build_experiment_concluded_event(rollout, new_status)?;
+        let event = build_experiment_concluded_event(rollout, new_status, identity)?;
         sink.send(&event).await?;
-        Ok(())
     } else if is_completion {
-        let event = build_service_published_event(rollout, new_status)?;
+        let event = build_service_published_event(rollout, new_status, identity)?;
         sink.send(&event).await?;
-        Ok(())
-    } else {
-        // No event for other transitions (yet)
-        Ok(())
     }
+
+    // Incidents are reported in addition to the service.* event
+    let detected_incident = new_status
+        .open_incident
+        .as_deref()
+        .filter(|id| old_status.as_ref().and_then(|s| s.open_incident.as_deref()) != Some(*id));
+    let resolved_incident = old_status
+        .as_ref()
+        .and_then(|s| s.open_incident.as_deref())
+        .filter(|_| is_completion && new_status.open_incident.is_none());
+    if let Some(incident_id) = detected_incident {
+        let event = build_incident_detected_event(rollout, new_status, identity, incident_id)?;
+        sink.send(&event).await?;
+    }
+    if let Some(incident_id) = resolved_incident {
+        let event = build_incident_resolved_event(rollout, new_status, identity, incident_id)?;
+        sink.send(&event).await?;
+    }
+
+    Ok(())
 }
 
 /// Build a service.deployed CDEvent
+///
+/// Emitted on initialization and again for each new pod template revision
+/// (`reason` distinguishes the two; revision events carry the template diff).
 fn build_service_deployed_event(
     rollout: &Rollout,
     status: &RolloutStatus,
+    identity: &EventSourceConfig,
+    reason: EventReason,
 ) -> Result<Event, CDEventsError> {
     use cdevents_sdk::latest::service_deployed;
     use cdevents_sdk::{CDEvent, Subject};
@@ -220,28 +754,35 @@ fn build_service_deployed_event(
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid artifact_id: {}", e)))?,
             environment: service_deployed::ContentEnvironment {
-                id: format!("{}/{}", namespace, name).try_into().map_err(|e| {
-                    CDEventsError::Generic(format!("Invalid environment id: {}", e))
-                })?,
-                source: Some(
-                    format!(
-                        "/apis/argoproj.io/v1alpha1/namespaces/{}/rollouts/{}",
-                        namespace, name
-                    )
+                id: identity
+                    .environment_id_for(namespace, name)
                     .try_into()
                     .map_err(|e| {
-                        CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
                     })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
                 ),
             },
         })
         .with_id(
-            format!("/rollouts/{}/initialization", name)
-                .try_into()
-                .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
+            match (reason, status.pod_template_hash.as_deref()) {
+                (EventReason::RevisionChanged, Some(hash)) => {
+                    format!("/rollouts/{}/revisions/{}", name, hash)
+                }
+                _ => format!("/rollouts/{}/initialization", name),
+            }
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
         )
         .with_source(
-            "https://kulta.io/controller"
+            identity
+                .subject_source_for(namespace, name)
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
         ),
@@ -253,11 +794,12 @@ fn build_service_deployed_event(
             .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
     )
     .with_source(
-        "https://kulta.io"
+        identity
+            .source_for(namespace, name)
             .try_into()
             .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
     )
-    .with_custom_data(build_kulta_custom_data(rollout, status, "initialization"));
+    .with_custom_data(CustomData::for_rollout(rollout, status, reason).to_value());
 
     let cloudevent: Event = cdevent
         .try_into()
@@ -270,6 +812,7 @@ fn build_service_deployed_event(
 fn build_service_upgraded_event(
     rollout: &Rollout,
     status: &RolloutStatus,
+    identity: &EventSourceConfig,
 ) -> Result<Event, CDEventsError> {
     use cdevents_sdk::latest::service_upgraded;
     use cdevents_sdk::{CDEvent, Subject};
@@ -298,18 +841,19 @@ fn build_service_upgraded_event(
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid artifact_id: {}", e)))?,
             environment: service_upgraded::ContentEnvironment {
-                id: format!("{}/{}", namespace, name).try_into().map_err(|e| {
-                    CDEventsError::Generic(format!("Invalid environment id: {}", e))
-                })?,
-                source: Some(
-                    format!(
-                        "/apis/argoproj.io/v1alpha1/namespaces/{}/rollouts/{}",
-                        namespace, name
-                    )
+                id: identity
+                    .environment_id_for(namespace, name)
                     .try_into()
                     .map_err(|e| {
-                        CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
                     })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
                 ),
             },
         })
@@ -319,7 +863,8 @@ fn build_service_upgraded_event(
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
         )
         .with_source(
-            "https://kulta.io/controller"
+            identity
+                .subject_source_for(namespace, name)
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
         ),
@@ -331,11 +876,14 @@ fn build_service_upgraded_event(
             .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
     )
     .with_source(
-        "https://kulta.io"
+        identity
+            .source_for(namespace, name)
             .try_into()
             .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
     )
-    .with_custom_data(build_kulta_custom_data(rollout, status, "step_advanced"));
+    .with_custom_data(
+        CustomData::for_rollout(rollout, status, EventReason::StepAdvanced).to_value(),
+    );
 
     // Convert to CloudEvent
     let cloudevent: Event = cdevent
@@ -349,6 +897,7 @@ fn build_service_upgraded_event(
 fn build_service_rolledback_event(
     rollout: &Rollout,
     status: &RolloutStatus,
+    identity: &EventSourceConfig,
 ) -> Result<Event, CDEventsError> {
     use cdevents_sdk::latest::service_rolledback;
     use cdevents_sdk::{CDEvent, Subject};
@@ -372,18 +921,19 @@ fn build_service_rolledback_event(
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid artifact_id: {}", e)))?,
             environment: service_rolledback::ContentEnvironment {
-                id: format!("{}/{}", namespace, name).try_into().map_err(|e| {
-                    CDEventsError::Generic(format!("Invalid environment id: {}", e))
-                })?,
-                source: Some(
-                    format!(
-                        "/apis/argoproj.io/v1alpha1/namespaces/{}/rollouts/{}",
-                        namespace, name
-                    )
+                id: identity
+                    .environment_id_for(namespace, name)
                     .try_into()
                     .map_err(|e| {
-                        CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
                     })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
                 ),
             },
         })
@@ -393,7 +943,8 @@ fn build_service_rolledback_event(
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
         )
         .with_source(
-            "https://kulta.io/controller"
+            identity
+                .subject_source_for(namespace, name)
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
         ),
@@ -405,11 +956,14 @@ fn build_service_rolledback_event(
             .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
     )
     .with_source(
-        "https://kulta.io"
+        identity
+            .source_for(namespace, name)
             .try_into()
             .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
     )
-    .with_custom_data(build_kulta_custom_data(rollout, status, "analysis_failed"));
+    .with_custom_data(
+        CustomData::for_rollout(rollout, status, EventReason::AnalysisFailed).to_value(),
+    );
 
     let cloudevent: Event = cdevent
         .try_into()
@@ -422,6 +976,7 @@ fn build_service_rolledback_event(
 fn build_service_published_event(
     rollout: &Rollout,
     status: &RolloutStatus,
+    identity: &EventSourceConfig,
 ) -> Result<Event, CDEventsError> {
     use cdevents_sdk::latest::service_published;
     use cdevents_sdk::{CDEvent, Subject};
@@ -442,18 +997,19 @@ fn build_service_published_event(
     let cdevent = CDEvent::from(
         Subject::from(service_published::Content {
             environment: Some(service_published::ContentEnvironment {
-                id: format!("{}/{}", namespace, name).try_into().map_err(|e| {
-                    CDEventsError::Generic(format!("Invalid environment id: {}", e))
-                })?,
-                source: Some(
-                    format!(
-                        "/apis/argoproj.io/v1alpha1/namespaces/{}/rollouts/{}",
-                        namespace, name
-                    )
+                id: identity
+                    .environment_id_for(namespace, name)
                     .try_into()
                     .map_err(|e| {
-                        CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
                     })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
                 ),
             }),
         })
@@ -463,7 +1019,8 @@ fn build_service_published_event(
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
         )
         .with_source(
-            "https://kulta.io/controller"
+            identity
+                .subject_source_for(namespace, name)
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
         ),
@@ -475,11 +1032,12 @@ fn build_service_published_event(
             .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
     )
     .with_source(
-        "https://kulta.io"
+        identity
+            .source_for(namespace, name)
             .try_into()
             .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
     )
-    .with_custom_data(build_kulta_custom_data(rollout, status, "completed"));
+    .with_custom_data(CustomData::for_rollout(rollout, status, EventReason::Completed).to_value());
 
     let cloudevent: Event = cdevent
         .try_into()
@@ -494,6 +1052,7 @@ fn build_service_published_event(
 fn build_experiment_concluded_event(
     rollout: &Rollout,
     status: &RolloutStatus,
+    identity: &EventSourceConfig,
 ) -> Result<Event, CDEventsError> {
     use cdevents_sdk::latest::service_published;
     use cdevents_sdk::{CDEvent, Subject};
@@ -512,18 +1071,19 @@ fn build_experiment_concluded_event(
     let cdevent = CDEvent::from(
         Subject::from(service_published::Content {
             environment: Some(service_published::ContentEnvironment {
-                id: format!("{}/{}", namespace, name).try_into().map_err(|e| {
-                    CDEventsError::Generic(format!("Invalid environment id: {}", e))
-                })?,
-                source: Some(
-                    format!(
-                        "/apis/kulta.io/v1alpha1/namespaces/{}/rollouts/{}",
-                        namespace, name
-                    )
+                id: identity
+                    .environment_id_for(namespace, name)
                     .try_into()
                     .map_err(|e| {
-                        CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
                     })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
                 ),
             }),
         })
@@ -533,7 +1093,8 @@ fn build_experiment_concluded_event(
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
         )
         .with_source(
-            "https://kulta.io/controller"
+            identity
+                .subject_source_for(namespace, name)
                 .try_into()
                 .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
         ),
@@ -545,11 +1106,12 @@ fn build_experiment_concluded_event(
             .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
     )
     .with_source(
-        "https://kulta.io"
+        identity
+            .source_for(namespace, name)
             .try_into()
             .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
     )
-    .with_custom_data(build_experiment_custom_data(rollout, status));
+    .with_custom_data(CustomData::for_experiment(rollout, status).to_value());
 
     let cloudevent: Event = cdevent
         .try_into()
@@ -558,108 +1120,362 @@ fn build_experiment_concluded_event(
     Ok(cloudevent)
 }
 
-/// Build experiment-specific custom data for CDEvents
-fn build_experiment_custom_data(rollout: &Rollout, status: &RolloutStatus) -> serde_json::Value {
-    let ab_experiment = status.ab_experiment.as_ref();
-
-    let winner = ab_experiment
-        .and_then(|ab| ab.winner.as_ref())
-        .map(|v| format!("{:?}", v))
-        .unwrap_or_else(|| "none".to_string());
-
-    let conclusion_reason = ab_experiment
-        .and_then(|ab| ab.conclusion_reason.as_ref())
-        .map(|r| format!("{:?}", r))
-        .unwrap_or_else(|| "unknown".to_string());
-
-    let results: Vec<serde_json::Value> = ab_experiment
-        .map(|ab| {
-            ab.results
-                .iter()
-                .map(|r| {
-                    json!({
-                        "name": r.name,
-                        "value_a": r.value_a,
-                        "value_b": r.value_b,
-                        "confidence": r.confidence,
-                        "is_significant": r.is_significant,
-                        "winner": r.winner
-                    })
-                })
-                .collect()
-        })
-        .unwrap_or_default();
-
-    json!({
-        "kulta": {
-            "version": "v1",
-            "rollout": {
-                "name": rollout.metadata.name.as_deref().unwrap_or("unknown"),
-                "namespace": rollout.metadata.namespace.as_deref().unwrap_or("default"),
-                "uid": rollout.metadata.uid.as_deref().unwrap_or(""),
-                "generation": rollout.metadata.generation.unwrap_or(0)
+/// CDEvents subject id for an incident caused by a revision
+///
+/// Keyed by pod template hash, so a retry of the same revision reuses the id.
+pub fn incident_subject_id(name: &str, pod_template_hash: &str) -> String {
+    format!("/rollouts/{}/incidents/{}", name, pod_template_hash)
+}
+
+/// Build an incident.detected CDEvent
+///
+/// Emitted when analysis breaches thresholds; the status message becomes the
+/// incident description.
+fn build_incident_detected_event(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    identity: &EventSourceConfig,
+    incident_id: &str,
+) -> Result<Event, CDEventsError> {
+    use cdevents_sdk::latest::incident_detected;
+    use cdevents_sdk::{CDEvent, Subject};
+
+    let image = extract_image_from_rollout(rollout)?;
+
+    let namespace = rollout
+        .metadata
+        .namespace
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing namespace".to_string()))?;
+    let name = rollout
+        .metadata
+        .name
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing name".to_string()))?;
+
+    let description = status
+        .message
+        .clone()
+        .unwrap_or_else(|| "Analysis breached thresholds".to_string());
+
+    let cdevent = CDEvent::from(
+        Subject::from(incident_detected::Content {
+            artifact_id: Some(
+                image
+                    .try_into()
+                    .map_err(|e| CDEventsError::Generic(format!("Invalid artifact_id: {}", e)))?,
+            ),
+            description: Some(
+                description
+                    .try_into()
+                    .map_err(|e| CDEventsError::Generic(format!("Invalid description: {}", e)))?,
+            ),
+            environment: incident_detected::ContentEnvironment {
+                id: identity
+                    .environment_id_for(namespace, name)
+                    .try_into()
+                    .map_err(|e| {
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
+                    })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
+                ),
             },
-            "strategy": "ab-testing",
-            "experiment": {
-                "started_at": ab_experiment.map(|ab| ab.started_at.as_str()).unwrap_or(""),
-                "concluded_at": ab_experiment.and_then(|ab| ab.concluded_at.as_deref()).unwrap_or(""),
-                "sample_size_a": ab_experiment.and_then(|ab| ab.sample_size_a).unwrap_or(0),
-                "sample_size_b": ab_experiment.and_then(|ab| ab.sample_size_b).unwrap_or(0),
-                "winner": winner,
-                "conclusion_reason": conclusion_reason,
-                "metrics": results
+            service: None,
+        })
+        .with_id(
+            incident_id
+                .to_string()
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
+        )
+        .with_source(
+            identity
+                .subject_source_for(namespace, name)
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
+        ),
+    )
+    .with_id(
+        uuid::Uuid::new_v4()
+            .to_string()
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
+    )
+    .with_source(
+        identity
+            .source_for(namespace, name)
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
+    )
+    .with_custom_data(
+        CustomData::for_rollout(rollout, status, EventReason::AnalysisFailed).to_value(),
+    );
+
+    let cloudevent: Event = cdevent
+        .try_into()
+        .map_err(|e| CDEventsError::Generic(format!("Failed to convert to CloudEvent: {}", e)))?;
+
+    Ok(cloudevent)
+}
+
+/// Build an incident.resolved CDEvent
+///
+/// Emitted when a rollout completes while an earlier analysis incident is
+/// still open. Reuses the incident's subject id so consumers can correlate.
+fn build_incident_resolved_event(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    identity: &EventSourceConfig,
+    incident_id: &str,
+) -> Result<Event, CDEventsError> {
+    use cdevents_sdk::latest::incident_resolved;
+    use cdevents_sdk::{CDEvent, Subject};
+
+    let image = extract_image_from_rollout(rollout)?;
+
+    let namespace = rollout
+        .metadata
+        .namespace
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing namespace".to_string()))?;
+    let name = rollout
+        .metadata
+        .name
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing name".to_string()))?;
+
+    let cdevent = CDEvent::from(
+        Subject::from(incident_resolved::Content {
+            artifact_id: Some(
+                image
+                    .try_into()
+                    .map_err(|e| CDEventsError::Generic(format!("Invalid artifact_id: {}", e)))?,
+            ),
+            description: Some(
+                format!("Rollout {} completed", name)
+                    .try_into()
+                    .map_err(|e| CDEventsError::Generic(format!("Invalid description: {}", e)))?,
+            ),
+            environment: incident_resolved::ContentEnvironment {
+                id: identity
+                    .environment_id_for(namespace, name)
+                    .try_into()
+                    .map_err(|e| {
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
+                    })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
+                ),
             },
-            "decision": {
-                "reason": "experiment_concluded"
-            }
-        }
-    })
+            service: None,
+        })
+        .with_id(
+            incident_id
+                .to_string()
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
+        )
+        .with_source(
+            identity
+                .subject_source_for(namespace, name)
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
+        ),
+    )
+    .with_id(
+        uuid::Uuid::new_v4()
+            .to_string()
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
+    )
+    .with_source(
+        identity
+            .source_for(namespace, name)
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
+    )
+    .with_custom_data(CustomData::for_rollout(rollout, status, EventReason::Completed).to_value());
+
+    let cloudevent: Event = cdevent
+        .try_into()
+        .map_err(|e| CDEventsError::Generic(format!("Failed to convert to CloudEvent: {}", e)))?;
+
+    Ok(cloudevent)
 }
 
-/// Build KULTA customData for CDEvents
-fn build_kulta_custom_data(
+/// Emit the terminal service.removed CDEvent for a deleted Rollout
+///
+/// Sent once, after the Rollout's ReplicaSets and traffic rules are torn
+/// down and before its finalizer is released.
+pub async fn emit_removed_event(
     rollout: &Rollout,
     status: &RolloutStatus,
-    decision_reason: &str,
-) -> serde_json::Value {
-    let strategy = if rollout.spec.strategy.canary.is_some() {
-        "canary"
-    } else if rollout.spec.strategy.blue_green.is_some() {
-        "blue-green"
-    } else if rollout.spec.strategy.ab_testing.is_some() {
-        "ab-testing"
-    } else {
-        "simple"
-    };
+    sink: &dyn EventSink,
+) -> Result<(), CDEventsError> {
+    let event = build_service_removed_event(rollout, status, sink.source_config())?;
+    sink.send(&event).await
+}
 
-    let total_steps = rollout
-        .spec
-        .strategy
-        .canary
+/// Build a service.removed CDEvent
+fn build_service_removed_event(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    identity: &EventSourceConfig,
+) -> Result<Event, CDEventsError> {
+    use cdevents_sdk::latest::service_removed;
+    use cdevents_sdk::{CDEvent, Subject};
+
+    let namespace = rollout
+        .metadata
+        .namespace
         .as_ref()
-        .map(|c| c.steps.len())
-        .unwrap_or(0);
-
-    json!({
-        "kulta": {
-            "version": "v1",
-            "rollout": {
-                "name": rollout.metadata.name.as_deref().unwrap_or("unknown"),
-                "namespace": rollout.metadata.namespace.as_deref().unwrap_or("default"),
-                "uid": rollout.metadata.uid.as_deref().unwrap_or(""),
-                "generation": rollout.metadata.generation.unwrap_or(0)
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing namespace".to_string()))?;
+    let name = rollout
+        .metadata
+        .name
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing name".to_string()))?;
+
+    let cdevent = CDEvent::from(
+        Subject::from(service_removed::Content {
+            environment: service_removed::ContentEnvironment {
+                id: identity
+                    .environment_id_for(namespace, name)
+                    .try_into()
+                    .map_err(|e| {
+                        CDEventsError::Generic(format!("Invalid environment id: {}", e))
+                    })?,
+                source: Some(
+                    identity
+                        .environment_source_for(namespace, name)
+                        .try_into()
+                        .map_err(|e| {
+                            CDEventsError::Generic(format!("Invalid environment source: {}", e))
+                        })?,
+                ),
             },
-            "strategy": strategy,
-            "step": {
-                "index": status.current_step_index.unwrap_or(0),
-                "total": total_steps,
-                "traffic_weight": status.current_weight.unwrap_or(0)
+        })
+        .with_id(
+            format!("/rollouts/{}/removal", name)
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject id: {}", e)))?,
+        )
+        .with_source(
+            identity
+                .subject_source_for(namespace, name)
+                .try_into()
+                .map_err(|e| CDEventsError::Generic(format!("Invalid subject source: {}", e)))?,
+        ),
+    )
+    .with_id(
+        uuid::Uuid::new_v4()
+            .to_string()
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event id: {}", e)))?,
+    )
+    .with_source(
+        identity
+            .source_for(namespace, name)
+            .try_into()
+            .map_err(|e| CDEventsError::Generic(format!("Invalid event source: {}", e)))?,
+    )
+    .with_custom_data(CustomData::for_rollout(rollout, status, EventReason::Removed).to_value());
+
+    let cloudevent: Event = cdevent
+        .try_into()
+        .map_err(|e| CDEventsError::Generic(format!("Failed to convert to CloudEvent: {}", e)))?;
+
+    Ok(cloudevent)
+}
+
+/// CloudEvent type of the heartbeat event
+///
+/// CDEvents has no heartbeat predicate, so this is a custom event following
+/// the spec's `dev.cdeventsx.<tool>-<subject>.<predicate>` convention.
+pub const HEARTBEAT_EVENT_TYPE: &str = "dev.cdeventsx.kulta-rollout.heartbeat.0.1.0";
+
+/// CDEvents spec version of custom events
+const CUSTOM_EVENT_SPEC_VERSION: &str = "0.4.1";
+
+/// Emit a heartbeat event for a Rollout in a long-running phase
+pub async fn emit_heartbeat_event(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+    sink: &dyn EventSink,
+) -> Result<(), CDEventsError> {
+    let event = build_heartbeat_event(rollout, status, now, sink.source_config())?;
+    sink.send(&event).await
+}
+
+/// Build a heartbeat custom CDEvent
+///
+/// The subject carries the phase and analysis summary; customData is the
+/// usual KULTA block with reason `heartbeat`.
+fn build_heartbeat_event(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+    identity: &EventSourceConfig,
+) -> Result<Event, CDEventsError> {
+    use cloudevents::{EventBuilder, EventBuilderV10};
+
+    let namespace = rollout
+        .metadata
+        .namespace
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing namespace".to_string()))?;
+    let name = rollout
+        .metadata
+        .name
+        .as_ref()
+        .ok_or_else(|| CDEventsError::Generic("Rollout missing name".to_string()))?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let source = identity.source_for(namespace, name);
+    let data = serde_json::json!({
+        "context": {
+            "specversion": CUSTOM_EVENT_SPEC_VERSION,
+            "id": id,
+            "source": source,
+            "type": HEARTBEAT_EVENT_TYPE,
+            "timestamp": now.to_rfc3339(),
+        },
+        "subject": {
+            "id": format!("/rollouts/{}", name),
+            "source": identity.subject_source_for(namespace, name),
+            "content": {
+                "environment": {
+                    "id": identity.environment_id_for(namespace, name),
+                    "source": identity.environment_source_for(namespace, name),
+                },
+                "phase": status.phase.as_ref().map(|p| format!("{:?}", p)),
+                "progressStartedAt": status.progress_started_at,
+                "analysis": analysis_summary(status),
             },
-            "decision": {
-                "reason": decision_reason
-            }
-        }
-    })
+        },
+        "customData": CustomData::for_rollout(rollout, status, EventReason::Heartbeat).to_value(),
+    });
+
+    EventBuilderV10::new()
+        .id(id)
+        .source(source)
+        .ty(HEARTBEAT_EVENT_TYPE)
+        .time(now)
+        .data("application/json", data)
+        .build()
+        .map_err(|e| CDEventsError::Generic(format!("Failed to build heartbeat event: {}", e)))
 }
 
 /// Extract image from rollout's pod template
@@ -0,0 +1,410 @@
+//! Versioned schema for the `customData` block attached to KULTA CDEvents.
+//!
+//! Every CDEvent emitted by the controller carries a `customData.kulta` object.
+//! Downstream consumers (dashboards, audit pipelines, AHTI) parse it, so its
+//! shape is a public contract and is modelled here as typed structs instead of
+//! ad-hoc `json!` blocks.
+//!
+//! # Compatibility rules
+//!
+//! - `kulta.version` identifies the schema. Consumers should check it first.
+//! - Within a version, fields may be **added** (always optional, omitted when
+//!   absent) but never renamed, retyped or removed.
+//! - Any breaking change introduces a new version string and a new variant of
+//!   [`SchemaVersion`]; the old layout keeps being emitted until it is retired.
+//!
+//! # Schema v1
+//!
+//! ```json
+//! {
+//!   "kulta": {
+//!     "version": "v1",
+//!     "rollout": { "name": "my-app", "namespace": "prod", "uid": "…", "generation": 3 },
+//!     "strategy": "canary",
+//!     "step": { "index": 1, "total": 4, "traffic_weight": 25 },
+//!     "experiment": {
+//!       "started_at": "2026-01-01T00:00:00Z",
+//!       "concluded_at": "2026-01-01T01:00:00Z",
+//!       "sample_size_a": 5000,
+//!       "sample_size_b": 5000,
+//!       "winner": "B",
+//!       "conclusion_reason": "ConsensusReached",
+//!       "metrics": [
+//!         { "name": "error-rate", "value_a": 0.02, "value_b": 0.01,
+//!           "confidence": 0.97, "is_significant": true, "winner": "B" }
+//!       ]
+//!     },
+//!     "template_diff": {
+//!       "from_hash": "5d8c1f0a2b", "to_hash": "9e41c7d3aa",
+//!       "images": [ { "container": "app", "from": "my-app:1.0", "to": "my-app:2.0" } ],
+//!       "env_changed": ["app"], "resources_changed": [],
+//!       "containers_added": [], "containers_removed": [], "other_changes": false
+//!     },
+//!     "decision": { "reason": "step_advanced" },
+//!     "links": { "change-ticket": "CHG-1234" }
+//!   }
+//! }
+//! ```
+//!
+//! - `strategy`: one of `canary`, `blue-green`, `ab-testing`, `simple`.
+//! - `step`: present on every event except `experiment_concluded`.
+//! - `experiment`: present only on `experiment_concluded` events.
+//! - `template_diff`: present only on `revision_changed` events
+//!   (service.deployed for a new pod template revision). Lists hold at most
+//!   20 entries; `"truncated": true` is added when some were dropped. The
+//!   pod template itself is never included.
+//! - `decision.reason`: one of `initialization`, `revision_changed`,
+//!   `step_advanced`, `analysis_failed`, `completed`, `experiment_concluded`.
+//! - `links`: present when the Rollout has `links.kulta.io/<key>` annotations
+//!   (e.g. `links.kulta.io/change-ticket: CHG-1234`); maps each `<key>` to the
+//!   annotation value so change records can be matched to deployments.
+//! - `truncated`: `true` when the sink's `KULTA_CDEVENTS_MAX_BYTES` cap cut
+//!   this block down; any field but `version`, `rollout`, `strategy` and
+//!   `decision` may then be missing.
+
+use crate::crd::rollout::{
+    ABExperimentStatus, ABMetricResult, ABVariant, ImageChange, Rollout, RolloutStatus,
+    TemplateDiff,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Annotation prefix of Rollout links copied into `customData.kulta.links`
+pub const LINK_ANNOTATION_PREFIX: &str = "links.kulta.io/";
+
+/// Schema version of the `customData.kulta` payload
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SchemaVersion {
+    /// Initial schema (rollout, strategy, step, experiment, decision)
+    #[default]
+    #[serde(rename = "v1")]
+    V1,
+}
+
+/// Top-level `customData` envelope
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CustomData {
+    pub kulta: KultaCustomData,
+}
+
+/// KULTA-specific event context
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KultaCustomData {
+    /// Schema version
+    pub version: SchemaVersion,
+
+    /// Identity of the Rollout that produced the event
+    pub rollout: RolloutRef,
+
+    /// Deployment strategy in use
+    pub strategy: StrategyKind,
+
+    /// Step progress (omitted for experiment events)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub step: Option<StepInfo>,
+
+    /// A/B experiment outcome (only on experiment_concluded events)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub experiment: Option<ExperimentInfo>,
+
+    /// Pod template changes (only on revision_changed events)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub template_diff: Option<TemplateDiffInfo>,
+
+    /// Why the event was emitted
+    pub decision: DecisionInfo,
+
+    /// External records (change tickets, approvals) from `links.kulta.io/*` annotations
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub links: BTreeMap<String, String>,
+}
+
+/// Rollout identity
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RolloutRef {
+    pub name: String,
+    pub namespace: String,
+    pub uid: String,
+    pub generation: i64,
+}
+
+/// Strategy identifier as it appears in customData and `status.strategy`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    Canary,
+    BlueGreen,
+    AbTesting,
+    Simple,
+}
+
+/// Step progress snapshot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StepInfo {
+    /// Current step index (0-based)
+    pub index: i32,
+    /// Total number of canary steps (0 for non-canary strategies)
+    pub total: usize,
+    /// Current canary traffic weight (0-100)
+    pub traffic_weight: i32,
+}
+
+/// A/B experiment outcome
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExperimentInfo {
+    pub started_at: String,
+    pub concluded_at: String,
+    pub sample_size_a: i64,
+    pub sample_size_b: i64,
+    /// "A", "B", or "none" when inconclusive
+    pub winner: String,
+    /// Conclusion reason variant name, or "unknown"
+    pub conclusion_reason: String,
+    pub metrics: Vec<ExperimentMetric>,
+}
+
+/// Per-metric A/B comparison
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExperimentMetric {
+    pub name: String,
+    pub value_a: f64,
+    pub value_b: f64,
+    pub confidence: f64,
+    pub is_significant: bool,
+    pub winner: Option<ABVariant>,
+}
+
+/// Summary of pod template changes for a new revision
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TemplateDiffInfo {
+    pub from_hash: String,
+    pub to_hash: String,
+    pub images: Vec<ImageChangeInfo>,
+    pub env_changed: Vec<String>,
+    pub resources_changed: Vec<String>,
+    pub containers_added: Vec<String>,
+    pub containers_removed: Vec<String>,
+    pub other_changes: bool,
+    /// Lists were cut short (omitted when false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Image change for one container
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ImageChangeInfo {
+    pub container: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Decision context
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DecisionInfo {
+    pub reason: EventReason,
+}
+
+/// Reason an event was emitted
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventReason {
+    Initialization,
+    RevisionChanged,
+    StepAdvanced,
+    AnalysisFailed,
+    Completed,
+    ExperimentConcluded,
+    Removed,
+    Heartbeat,
+}
+
+impl StrategyKind {
+    /// Determine the strategy kind from a Rollout spec
+    ///
+    /// Mirrors `select_strategy`, so the kind names the handler that
+    /// actually reconciles the Rollout.
+    pub fn from_rollout(rollout: &Rollout) -> Self {
+        if rollout.spec.strategy.simple.is_some() {
+            StrategyKind::Simple
+        } else if rollout.spec.strategy.blue_green.is_some() {
+            StrategyKind::BlueGreen
+        } else if rollout.spec.strategy.ab_testing.is_some() {
+            StrategyKind::AbTesting
+        } else {
+            StrategyKind::Canary
+        }
+    }
+
+    /// Strategy name as serialized (e.g. `blue-green`)
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StrategyKind::Canary => "canary",
+            StrategyKind::BlueGreen => "blue-green",
+            StrategyKind::AbTesting => "ab-testing",
+            StrategyKind::Simple => "simple",
+        }
+    }
+}
+
+impl RolloutRef {
+    fn from_rollout(rollout: &Rollout) -> Self {
+        RolloutRef {
+            name: rollout
+                .metadata
+                .name
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            namespace: rollout
+                .metadata
+                .namespace
+                .clone()
+                .unwrap_or_else(|| "default".to_string()),
+            uid: rollout.metadata.uid.clone().unwrap_or_default(),
+            generation: rollout.metadata.generation.unwrap_or(0),
+        }
+    }
+}
+
+impl ExperimentInfo {
+    fn from_status(ab: Option<&ABExperimentStatus>) -> Self {
+        ExperimentInfo {
+            started_at: ab.map(|ab| ab.started_at.clone()).unwrap_or_default(),
+            concluded_at: ab
+                .and_then(|ab| ab.concluded_at.clone())
+                .unwrap_or_default(),
+            sample_size_a: ab.and_then(|ab| ab.sample_size_a).unwrap_or(0),
+            sample_size_b: ab.and_then(|ab| ab.sample_size_b).unwrap_or(0),
+            winner: ab
+                .and_then(|ab| ab.winner.as_ref())
+                .map(|v| format!("{:?}", v))
+                .unwrap_or_else(|| "none".to_string()),
+            conclusion_reason: ab
+                .and_then(|ab| ab.conclusion_reason.as_ref())
+                .map(|r| format!("{:?}", r))
+                .unwrap_or_else(|| "unknown".to_string()),
+            metrics: ab
+                .map(|ab| ab.results.iter().map(ExperimentMetric::from).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&ABMetricResult> for ExperimentMetric {
+    fn from(r: &ABMetricResult) -> Self {
+        ExperimentMetric {
+            name: r.name.clone(),
+            value_a: r.value_a,
+            value_b: r.value_b,
+            confidence: r.confidence,
+            is_significant: r.is_significant,
+            winner: r.winner.clone(),
+        }
+    }
+}
+
+impl From<&TemplateDiff> for TemplateDiffInfo {
+    fn from(d: &TemplateDiff) -> Self {
+        TemplateDiffInfo {
+            from_hash: d.from_hash.clone(),
+            to_hash: d.to_hash.clone(),
+            images: d.images.iter().map(ImageChangeInfo::from).collect(),
+            env_changed: d.env_changed.clone(),
+            resources_changed: d.resources_changed.clone(),
+            containers_added: d.containers_added.clone(),
+            containers_removed: d.containers_removed.clone(),
+            other_changes: d.other_changes,
+            truncated: d.truncated,
+        }
+    }
+}
+
+impl From<&ImageChange> for ImageChangeInfo {
+    fn from(c: &ImageChange) -> Self {
+        ImageChangeInfo {
+            container: c.container.clone(),
+            from: c.from.clone(),
+            to: c.to.clone(),
+        }
+    }
+}
+
+/// Links from the Rollout's `links.kulta.io/<key>` annotations, keyed by `<key>`
+pub fn rollout_links(rollout: &Rollout) -> BTreeMap<String, String> {
+    rollout
+        .metadata
+        .annotations
+        .iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let key = key.strip_prefix(LINK_ANNOTATION_PREFIX)?;
+            (!key.is_empty()).then(|| (key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+impl CustomData {
+    /// Build customData for a rollout lifecycle event (deployed/upgraded/rolledback/published)
+    pub fn for_rollout(rollout: &Rollout, status: &RolloutStatus, reason: EventReason) -> Self {
+        let total_steps = rollout
+            .spec
+            .strategy
+            .canary
+            .as_ref()
+            .map(|c| c.steps.len())
+            .unwrap_or(0);
+
+        let template_diff = match reason {
+            EventReason::RevisionChanged => {
+                status.template_diff.as_ref().map(TemplateDiffInfo::from)
+            }
+            _ => None,
+        };
+
+        CustomData {
+            kulta: KultaCustomData {
+                version: SchemaVersion::V1,
+                rollout: RolloutRef::from_rollout(rollout),
+                strategy: StrategyKind::from_rollout(rollout),
+                step: Some(StepInfo {
+                    index: status.current_step_index.unwrap_or(0),
+                    total: total_steps,
+                    traffic_weight: status.current_weight.unwrap_or(0),
+                }),
+                experiment: None,
+                template_diff,
+                decision: DecisionInfo { reason },
+                links: rollout_links(rollout),
+            },
+        }
+    }
+
+    /// Build customData for an A/B experiment conclusion event
+    pub fn for_experiment(rollout: &Rollout, status: &RolloutStatus) -> Self {
+        CustomData {
+            kulta: KultaCustomData {
+                version: SchemaVersion::V1,
+                rollout: RolloutRef::from_rollout(rollout),
+                strategy: StrategyKind::AbTesting,
+                step: None,
+                experiment: Some(ExperimentInfo::from_status(status.ab_experiment.as_ref())),
+                template_diff: None,
+                decision: DecisionInfo {
+                    reason: EventReason::ExperimentConcluded,
+                },
+                links: rollout_links(rollout),
+            },
+        }
+    }
+
+    /// Serialize to a JSON value for `CDEvent::with_custom_data`
+    pub fn to_value(&self) -> serde_json::Value {
+        // Plain structs with string keys always serialize; Null is unreachable in practice
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+#[path = "cdevents_data_test.rs"]
+mod tests;
@@ -0,0 +1,317 @@
+use super::*;
+use crate::crd::builder::RolloutBuilder;
+use crate::crd::rollout::{ABConclusionReason, ABExperimentStatus, ABMetricResult, Phase};
+use serde_json::json;
+
+fn my_app() -> RolloutBuilder {
+    RolloutBuilder::new("my-app")
+        .namespace("prod")
+        .replicas(3)
+        .label("app", "my-app")
+        .image("app", "my-app:2.0")
+}
+
+/// Build with the uid and generation the documented payloads carry
+fn build(builder: RolloutBuilder) -> Rollout {
+    let mut rollout = builder.build().expect("test rollout should be valid");
+    rollout.metadata.uid = Some("abc-123".to_string());
+    rollout.metadata.generation = Some(3);
+    rollout
+}
+
+fn canary_rollout() -> Rollout {
+    build(
+        my_app().canary(
+            serde_json::from_value(json!({
+                "canaryService": "my-app-canary",
+                "stableService": "my-app-stable",
+                "steps": [
+                    {"setWeight": 10},
+                    {"setWeight": 25},
+                    {"setWeight": 50},
+                    {"setWeight": 100}
+                ]
+            }))
+            .expect("canary strategy should deserialize"),
+        ),
+    )
+}
+
+// The exact v1 payload documented in the module docs. If this test needs to
+// change, the change is breaking for consumers and requires a new version.
+#[test]
+fn test_v1_step_event_matches_documented_schema() {
+    let rollout = canary_rollout();
+    let status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(1),
+        current_weight: Some(25),
+        ..Default::default()
+    };
+
+    let value = CustomData::for_rollout(&rollout, &status, EventReason::StepAdvanced).to_value();
+
+    assert_eq!(
+        value,
+        json!({
+            "kulta": {
+                "version": "v1",
+                "rollout": {
+                    "name": "my-app",
+                    "namespace": "prod",
+                    "uid": "abc-123",
+                    "generation": 3
+                },
+                "strategy": "canary",
+                "step": {"index": 1, "total": 4, "traffic_weight": 25},
+                "decision": {"reason": "step_advanced"}
+            }
+        })
+    );
+}
+
+#[test]
+fn test_v1_experiment_event_matches_documented_schema() {
+    let rollout = build(
+        my_app().ab_testing(
+            serde_json::from_value(json!({
+                "variantAService": "svc-a",
+                "variantBService": "svc-b",
+                "variantBMatch": {"header": {"name": "X-Variant", "value": "B"}}
+            }))
+            .expect("abTesting strategy should deserialize"),
+        ),
+    );
+    let status = RolloutStatus {
+        phase: Some(Phase::Concluded),
+        ab_experiment: Some(ABExperimentStatus {
+            started_at: "2026-01-01T00:00:00Z".to_string(),
+            concluded_at: Some("2026-01-01T01:00:00Z".to_string()),
+            sample_size_a: Some(5000),
+            sample_size_b: Some(5000),
+            results: vec![ABMetricResult {
+                name: "error-rate".to_string(),
+                value_a: 0.02,
+                value_b: 0.01,
+                confidence: 0.97,
+                is_significant: true,
+                winner: Some(ABVariant::B),
+            }],
+            winner: Some(ABVariant::B),
+            conclusion_reason: Some(ABConclusionReason::ConsensusReached),
+        }),
+        ..Default::default()
+    };
+
+    let value = CustomData::for_experiment(&rollout, &status).to_value();
+
+    assert_eq!(
+        value,
+        json!({
+            "kulta": {
+                "version": "v1",
+                "rollout": {
+                    "name": "my-app",
+                    "namespace": "prod",
+                    "uid": "abc-123",
+                    "generation": 3
+                },
+                "strategy": "ab-testing",
+                "experiment": {
+                    "started_at": "2026-01-01T00:00:00Z",
+                    "concluded_at": "2026-01-01T01:00:00Z",
+                    "sample_size_a": 5000,
+                    "sample_size_b": 5000,
+                    "winner": "B",
+                    "conclusion_reason": "ConsensusReached",
+                    "metrics": [{
+                        "name": "error-rate",
+                        "value_a": 0.02,
+                        "value_b": 0.01,
+                        "confidence": 0.97,
+                        "is_significant": true,
+                        "winner": "B"
+                    }]
+                },
+                "decision": {"reason": "experiment_concluded"}
+            }
+        })
+    );
+}
+
+#[test]
+fn test_v1_revision_event_includes_template_diff() {
+    use crate::crd::rollout::{ImageChange, TemplateDiff};
+
+    let rollout = canary_rollout();
+    let status = RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        template_diff: Some(TemplateDiff {
+            from_hash: "5d8c1f0a2b".to_string(),
+            to_hash: "9e41c7d3aa".to_string(),
+            images: vec![ImageChange {
+                container: "app".to_string(),
+                from: "my-app:1.0".to_string(),
+                to: "my-app:2.0".to_string(),
+            }],
+            env_changed: vec!["app".to_string()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let value = CustomData::for_rollout(&rollout, &status, EventReason::RevisionChanged).to_value();
+
+    assert_eq!(
+        value["kulta"]["template_diff"],
+        json!({
+            "from_hash": "5d8c1f0a2b",
+            "to_hash": "9e41c7d3aa",
+            "images": [{"container": "app", "from": "my-app:1.0", "to": "my-app:2.0"}],
+            "env_changed": ["app"],
+            "resources_changed": [],
+            "containers_added": [],
+            "containers_removed": [],
+            "other_changes": false
+        })
+    );
+
+    // Other events never carry the diff, even if it is still in status
+    let value = CustomData::for_rollout(&rollout, &status, EventReason::StepAdvanced).to_value();
+    assert!(value["kulta"].get("template_diff").is_none());
+}
+
+#[test]
+fn test_v1_payload_roundtrips() {
+    let rollout = canary_rollout();
+    let status = RolloutStatus {
+        current_step_index: Some(2),
+        current_weight: Some(50),
+        ..Default::default()
+    };
+    let data = CustomData::for_rollout(&rollout, &status, EventReason::Completed);
+
+    let parsed: CustomData = serde_json::from_value(data.to_value()).unwrap();
+    assert_eq!(parsed, data);
+}
+
+// Consumers built against v1 must keep parsing payloads that gained new
+// optional fields within the same version.
+#[test]
+fn test_v1_parser_ignores_additive_fields() {
+    let payload = json!({
+        "kulta": {
+            "version": "v1",
+            "rollout": {"name": "a", "namespace": "b", "uid": "", "generation": 0},
+            "strategy": "simple",
+            "step": {"index": 0, "total": 0, "traffic_weight": 0},
+            "decision": {"reason": "initialization", "future_field": true},
+            "future_section": {"anything": 1}
+        }
+    });
+
+    let parsed: CustomData = serde_json::from_value(payload).unwrap();
+    assert_eq!(parsed.kulta.version, SchemaVersion::V1);
+    assert_eq!(parsed.kulta.strategy, StrategyKind::Simple);
+    assert_eq!(parsed.kulta.decision.reason, EventReason::Initialization);
+}
+
+#[test]
+fn test_links_copied_from_annotations() {
+    let mut rollout = canary_rollout();
+    rollout.metadata.annotations = Some(
+        [
+            ("links.kulta.io/change-ticket", "CHG-1234"),
+            (
+                "links.kulta.io/approval",
+                "https://approvals.example.com/42",
+            ),
+            ("links.kulta.io/", "ignored"),
+            ("kulta.io/promote", "true"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect(),
+    );
+    let status = RolloutStatus::default();
+
+    let value = CustomData::for_rollout(&rollout, &status, EventReason::StepAdvanced).to_value();
+
+    assert_eq!(
+        value["kulta"]["links"],
+        json!({
+            "approval": "https://approvals.example.com/42",
+            "change-ticket": "CHG-1234"
+        })
+    );
+}
+
+#[test]
+fn test_links_omitted_without_annotations() {
+    let value = CustomData::for_rollout(
+        &canary_rollout(),
+        &RolloutStatus::default(),
+        EventReason::StepAdvanced,
+    )
+    .to_value();
+
+    assert!(value["kulta"].get("links").is_none());
+}
+
+#[test]
+fn test_unknown_schema_version_is_rejected() {
+    let payload = json!({
+        "kulta": {
+            "version": "v2",
+            "rollout": {"name": "a", "namespace": "b", "uid": "", "generation": 0},
+            "strategy": "canary",
+            "decision": {"reason": "completed"}
+        }
+    });
+
+    assert!(serde_json::from_value::<CustomData>(payload).is_err());
+}
+
+#[test]
+fn test_strategy_kind_wire_names() {
+    assert_eq!(json!(StrategyKind::Canary), json!("canary"));
+    assert_eq!(json!(StrategyKind::BlueGreen), json!("blue-green"));
+    assert_eq!(json!(StrategyKind::AbTesting), json!("ab-testing"));
+    assert_eq!(json!(StrategyKind::Simple), json!("simple"));
+}
+
+#[test]
+fn test_event_reason_wire_names() {
+    assert_eq!(json!(EventReason::Initialization), json!("initialization"));
+    assert_eq!(
+        json!(EventReason::RevisionChanged),
+        json!("revision_changed")
+    );
+    assert_eq!(json!(EventReason::StepAdvanced), json!("step_advanced"));
+    assert_eq!(json!(EventReason::AnalysisFailed), json!("analysis_failed"));
+    assert_eq!(json!(EventReason::Completed), json!("completed"));
+    assert_eq!(
+        json!(EventReason::ExperimentConcluded),
+        json!("experiment_concluded")
+    );
+    assert_eq!(json!(EventReason::Removed), json!("removed"));
+    assert_eq!(json!(EventReason::Heartbeat), json!("heartbeat"));
+}
+
+#[test]
+fn test_missing_metadata_falls_back_to_defaults() {
+    let mut rollout = canary_rollout();
+    rollout.metadata = Default::default();
+
+    let data = CustomData::for_rollout(
+        &rollout,
+        &RolloutStatus::default(),
+        EventReason::Initialization,
+    );
+
+    assert_eq!(data.kulta.rollout.name, "unknown");
+    assert_eq!(data.kulta.rollout.namespace, "default");
+    assert_eq!(data.kulta.rollout.uid, "");
+    assert_eq!(data.kulta.rollout.generation, 0);
+}
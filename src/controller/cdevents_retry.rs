@@ -0,0 +1,441 @@
+//! Retry queue for CDEvents the sink didn't accept
+//!
+//! An event whose POST fails with a connection error, a timeout, 408, 429 or
+//! a 5xx is queued in memory and retried by a background task with
+//! exponential backoff (1s, 2s, 4s, ... up to `KULTA_CDEVENTS_RETRY_MAX_BACKOFF_SECS`).
+//! Events are delivered in order: while the queue holds events, new ones
+//! are queued behind them instead of being posted directly.
+//!
+//! Events are dropped, and counted in
+//! `kulta_cdevents_dropped_total{type, reason}`, when:
+//!
+//! - `queue_full`: the queue already holds `KULTA_CDEVENTS_RETRY_QUEUE_SIZE`
+//!   events (0 disables retries, so every failed event is dropped)
+//! - `retries_exhausted`: `KULTA_CDEVENTS_RETRY_MAX_ATTEMPTS` deliveries
+//!   failed
+//! - `rejected`: the sink answered with any other 4xx, which retrying won't
+//!   fix
+//!
+//! The queue lives in controller memory and is lost on restart.
+
+use crate::server::metrics::SharedMetrics;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+/// Default number of events held for retry
+pub const DEFAULT_RETRY_QUEUE_SIZE: usize = 1000;
+
+/// Default number of delivery attempts per event, the first one included
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 20;
+
+/// Default cap on the delay between attempts
+pub const DEFAULT_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Delay before the first retry
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How queued events are retried
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Events held at most; 0 disables retries
+    pub queue_size: usize,
+    /// Delivery attempts per event, the first one included
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            queue_size: DEFAULT_RETRY_QUEUE_SIZE,
+            max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+            initial_backoff: INITIAL_BACKOFF,
+            max_backoff: DEFAULT_RETRY_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Read `KULTA_CDEVENTS_RETRY_*`; invalid values are logged and ignored
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: std::str::FromStr>(
+            lookup: &impl Fn(&str) -> Option<String>,
+            key: &str,
+            valid: impl Fn(&T) -> bool,
+        ) -> Option<T> {
+            let value = lookup(key)?;
+            let value = value.trim();
+            if value.is_empty() {
+                return None;
+            }
+            let parsed = value.parse::<T>().ok().filter(|v| valid(v));
+            if parsed.is_none() {
+                warn!(
+                    key,
+                    value, "Invalid CDEvents retry setting, using the default"
+                );
+            }
+            parsed
+        }
+
+        let defaults = RetryPolicy::default();
+        RetryPolicy {
+            queue_size: parse(&lookup, "KULTA_CDEVENTS_RETRY_QUEUE_SIZE", |_: &usize| true)
+                .unwrap_or(defaults.queue_size),
+            max_attempts: parse(&lookup, "KULTA_CDEVENTS_RETRY_MAX_ATTEMPTS", |n: &u32| {
+                *n > 0
+            })
+            .unwrap_or(defaults.max_attempts),
+            max_backoff: parse(
+                &lookup,
+                "KULTA_CDEVENTS_RETRY_MAX_BACKOFF_SECS",
+                |s: &u64| *s > 0,
+            )
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.max_backoff),
+            ..defaults
+        }
+    }
+
+    /// Delay before the next attempt of an event that failed `attempts` times
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let doublings = attempts.saturating_sub(1).min(31);
+        self.initial_backoff
+            .saturating_mul(1u32 << doublings)
+            .min(self.max_backoff)
+    }
+}
+
+/// Result of one delivery attempt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    Delivered,
+    /// Worth retrying (connection error, timeout, 408, 429, 5xx)
+    Retryable(String),
+    /// Retrying won't help (any other 4xx)
+    Rejected(String),
+}
+
+impl Delivery {
+    /// Classify the sink's response status
+    pub fn from_status(status: reqwest::StatusCode) -> Self {
+        if status.is_success() {
+            Delivery::Delivered
+        } else if status.is_server_error()
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        {
+            Delivery::Retryable(format!("sink returned {}", status))
+        } else {
+            Delivery::Rejected(format!("sink returned {}", status))
+        }
+    }
+}
+
+/// An encoded event waiting to be delivered
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingEvent {
+    pub event_type: String,
+    /// Request body, already compressed
+    pub body: Vec<u8>,
+    /// Failed deliveries so far
+    pub attempts: u32,
+}
+
+/// Bounded FIFO of events to retry
+pub struct RetryQueue {
+    policy: RetryPolicy,
+    pending: Mutex<VecDeque<PendingEvent>>,
+    wake: Notify,
+    metrics: Option<SharedMetrics>,
+}
+
+impl RetryQueue {
+    pub fn new(policy: RetryPolicy, metrics: Option<SharedMetrics>) -> Self {
+        RetryQueue {
+            policy,
+            pending: Mutex::new(VecDeque::new()),
+            wake: Notify::new(),
+            metrics,
+        }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Events waiting to be retried
+    pub fn len(&self) -> usize {
+        self.pending
+            .lock()
+            .map(|pending| pending.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Queue an event for retry
+    ///
+    /// Returns false, and counts the event as dropped, when the queue is full.
+    pub fn push(&self, event: PendingEvent) -> bool {
+        let Ok(mut pending) = self.pending.lock() else {
+            return false;
+        };
+        if pending.len() >= self.policy.queue_size {
+            drop(pending);
+            warn!(
+                event_type = %event.event_type,
+                queue_size = self.policy.queue_size,
+                "CDEvents retry queue is full, dropping event"
+            );
+            self.record_dropped(&event.event_type, "queue_full");
+            return false;
+        }
+        pending.push_back(event);
+        self.record_depth(pending.len());
+        drop(pending);
+        self.wake.notify_one();
+        true
+    }
+
+    /// Retry the oldest event once with `deliver`
+    ///
+    /// A delivered or rejected event leaves the queue, as does one that has
+    /// used up its attempts; otherwise it stays at the front.
+    pub async fn retry_front<F, Fut>(&self, deliver: &F)
+    where
+        F: Fn(Vec<u8>) -> Fut,
+        Fut: Future<Output = Delivery>,
+    {
+        let Some(front) = self.front() else {
+            return;
+        };
+        let delivery = deliver(front.body).await;
+
+        // Only this task removes events, so the front is still the same one
+        let Ok(mut pending) = self.pending.lock() else {
+            return;
+        };
+        let Some(event) = pending.front_mut() else {
+            return;
+        };
+        event.attempts += 1;
+        let dropped = match delivery {
+            Delivery::Delivered => {
+                debug!(event_type = %event.event_type, attempts = event.attempts,
+                    "Delivered queued CDEvent");
+                pending.pop_front();
+                None
+            }
+            Delivery::Rejected(reason) => Some(("rejected", reason)),
+            Delivery::Retryable(reason) if event.attempts >= self.policy.max_attempts => {
+                Some(("retries_exhausted", reason))
+            }
+            Delivery::Retryable(reason) => {
+                debug!(event_type = %event.event_type, attempts = event.attempts, reason = %reason,
+                    "CDEvent delivery failed, will retry");
+                None
+            }
+        };
+        if let Some((cause, reason)) = dropped {
+            if let Some(event) = pending.pop_front() {
+                warn!(event_type = %event.event_type, attempts = event.attempts,
+                    reason = %reason, cause, "Dropping CDEvent after failed delivery");
+                self.record_dropped(&event.event_type, cause);
+            }
+        }
+        self.record_depth(pending.len());
+    }
+
+    /// Retry queued events forever, waiting the backoff before each attempt
+    pub async fn run<F, Fut>(self: Arc<Self>, deliver: F)
+    where
+        F: Fn(Vec<u8>) -> Fut,
+        Fut: Future<Output = Delivery>,
+    {
+        loop {
+            let Some(front) = self.front() else {
+                self.wake.notified().await;
+                continue;
+            };
+            tokio::time::sleep(self.policy.backoff(front.attempts)).await;
+            self.retry_front(&deliver).await;
+        }
+    }
+
+    fn front(&self) -> Option<PendingEvent> {
+        self.pending.lock().ok()?.front().cloned()
+    }
+
+    fn record_dropped(&self, event_type: &str, reason: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_cdevent_dropped(event_type, reason);
+        }
+    }
+
+    fn record_depth(&self, depth: usize) {
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .cdevents_retry_queue_depth
+                .set(i64::try_from(depth).unwrap_or(i64::MAX));
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::server::metrics::ControllerMetrics;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn pending(event_type: &str) -> PendingEvent {
+        PendingEvent {
+            event_type: event_type.to_string(),
+            body: event_type.as_bytes().to_vec(),
+            attempts: 1,
+        }
+    }
+
+    fn dropped(metrics: &SharedMetrics, reason: &str) -> u64 {
+        metrics
+            .cdevents_dropped_total
+            .with_label_values(&["dev.cdevents.service.deployed.0.1.1", reason])
+            .get()
+    }
+
+    #[test]
+    fn test_policy_from_lookup() {
+        assert_eq!(RetryPolicy::from_lookup(|_| None), RetryPolicy::default());
+
+        let policy = RetryPolicy::from_lookup(|key| match key {
+            "KULTA_CDEVENTS_RETRY_QUEUE_SIZE" => Some("0".into()),
+            "KULTA_CDEVENTS_RETRY_MAX_ATTEMPTS" => Some("0".into()),
+            "KULTA_CDEVENTS_RETRY_MAX_BACKOFF_SECS" => Some("30".into()),
+            _ => None,
+        });
+        assert_eq!(policy.queue_size, 0);
+        assert_eq!(policy.max_attempts, DEFAULT_RETRY_MAX_ATTEMPTS);
+        assert_eq!(policy.max_backoff, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy::default();
+        let backoffs: Vec<u64> = (1..=8).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(backoffs, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(policy.backoff(u32::MAX), DEFAULT_RETRY_MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_delivery_from_status() {
+        use reqwest::StatusCode;
+
+        assert_eq!(
+            Delivery::from_status(StatusCode::ACCEPTED),
+            Delivery::Delivered
+        );
+        for status in [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::REQUEST_TIMEOUT,
+        ] {
+            assert!(matches!(
+                Delivery::from_status(status),
+                Delivery::Retryable(_)
+            ));
+        }
+        assert!(matches!(
+            Delivery::from_status(StatusCode::BAD_REQUEST),
+            Delivery::Rejected(_)
+        ));
+    }
+
+    #[test]
+    fn test_push_drops_when_full() {
+        let metrics = Arc::new(ControllerMetrics::new().unwrap());
+        let queue = RetryQueue::new(
+            RetryPolicy {
+                queue_size: 1,
+                ..RetryPolicy::default()
+            },
+            Some(metrics.clone()),
+        );
+        let event = "dev.cdevents.service.deployed.0.1.1";
+
+        assert!(queue.push(pending(event)));
+        assert!(!queue.push(pending(event)));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(dropped(&metrics, "queue_full"), 1);
+        assert_eq!(metrics.cdevents_retry_queue_depth.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_front_keeps_order_until_delivered() {
+        let queue = RetryQueue::new(RetryPolicy::default(), None);
+        queue.push(pending("first"));
+        queue.push(pending("second"));
+
+        let attempts = AtomicUsize::new(0);
+        let flaky = |body: Vec<u8>| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                assert_eq!(body, b"first");
+                if attempt == 0 {
+                    Delivery::Retryable("sink returned 503".into())
+                } else {
+                    Delivery::Delivered
+                }
+            }
+        };
+
+        queue.retry_front(&flaky).await;
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.front().unwrap().attempts, 2);
+
+        queue.retry_front(&flaky).await;
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.front().unwrap().event_type, "second");
+    }
+
+    #[tokio::test]
+    async fn test_retry_front_drops_rejected_and_exhausted_events() {
+        let metrics = Arc::new(ControllerMetrics::new().unwrap());
+        let queue = RetryQueue::new(
+            RetryPolicy {
+                max_attempts: 2,
+                ..RetryPolicy::default()
+            },
+            Some(metrics.clone()),
+        );
+        let event = "dev.cdevents.service.deployed.0.1.1";
+
+        queue.push(pending(event));
+        queue
+            .retry_front(&|_| async { Delivery::Rejected("sink returned 400".into()) })
+            .await;
+        assert!(queue.is_empty());
+        assert_eq!(dropped(&metrics, "rejected"), 1);
+
+        // Attempt 1 failed before queueing, attempt 2 uses up the budget
+        queue.push(pending(event));
+        queue
+            .retry_front(&|_| async { Delivery::Retryable("connection refused".into()) })
+            .await;
+        assert!(queue.is_empty());
+        assert_eq!(dropped(&metrics, "retries_exhausted"), 1);
+        assert_eq!(metrics.cdevents_retry_queue_depth.get(), 0);
+    }
+}
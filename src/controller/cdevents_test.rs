@@ -29,9 +29,19 @@ async fn test_emit_service_deployed_on_initialization() {
                     steps: vec![CanaryStep {
                         set_weight: Some(10),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -39,6 +49,11 @@ async fn test_emit_service_deployed_on_initialization() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None, // No status yet - this is a new rollout
     };
@@ -126,14 +141,30 @@ async fn test_emit_service_upgraded_on_step_progression() {
                         CanaryStep {
                             set_weight: Some(10),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -141,6 +172,11 @@ async fn test_emit_service_upgraded_on_step_progression() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -240,9 +276,19 @@ async fn test_emit_service_rolledback_on_failure() {
                     steps: vec![CanaryStep {
                         set_weight: Some(50),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -250,6 +296,11 @@ async fn test_emit_service_rolledback_on_failure() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -350,14 +401,30 @@ async fn test_emit_service_published_on_completion() {
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(100),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -365,6 +432,11 @@ async fn test_emit_service_published_on_completion() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -459,14 +531,30 @@ async fn test_cdevent_contains_kulta_custom_data() {
                         CanaryStep {
                             set_weight: Some(10),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -474,6 +562,11 @@ async fn test_cdevent_contains_kulta_custom_data() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -554,6 +647,11 @@ async fn test_simple_strategy_emits_deployed_and_published() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -622,6 +720,7 @@ async fn test_blue_green_emits_deployed_on_preview() {
                     auto_promotion_seconds: Some(30),
                     traffic_routing: None,
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -630,6 +729,11 @@ async fn test_blue_green_emits_deployed_on_preview() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -701,6 +805,7 @@ async fn test_blue_green_emits_published_on_promotion() {
                     auto_promotion_seconds: Some(30),
                     traffic_routing: None,
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -709,6 +814,11 @@ async fn test_blue_green_emits_published_on_promotion() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -800,6 +910,11 @@ async fn test_emit_experiment_concluded_event() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             phase: Some(Phase::Experimenting),
@@ -904,6 +1019,11 @@ async fn test_emit_service_deployed_on_ab_initialization() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None, // No previous status → initialization
     };
@@ -928,6 +1048,394 @@ async fn test_emit_service_deployed_on_ab_initialization() {
     );
 }
 
+// Default identifiers point at the kulta.io Rollout API, not Argo's
+#[tokio::test]
+async fn test_default_environment_source_uses_kulta_api_path() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &None, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(events[0].source().to_string(), "https://kulta.io");
+
+    let json: serde_json::Value = match events[0].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(
+        json["subject"]["content"]["environment"]["source"],
+        "/apis/kulta.io/v1alpha1/namespaces/default/rollouts/test-app"
+    );
+    assert_eq!(json["subject"]["source"], "https://kulta.io/controller");
+}
+
+#[tokio::test]
+async fn test_custom_source_config_is_applied_to_events() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::with_source_config(EventSourceConfig {
+        source: "https://deploy.example.com/{cluster}".to_string(),
+        subject_source: "https://deploy.example.com/{cluster}/kulta".to_string(),
+        environment_id: "{cluster}/{namespace}/{name}".to_string(),
+        environment_source: "https://k8s.example.com/{cluster}/{namespace}/{name}".to_string(),
+        cluster: "eu-west-1".to_string(),
+    });
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &None, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(
+        events[0].source().to_string(),
+        "https://deploy.example.com/eu-west-1"
+    );
+
+    let json: serde_json::Value = match events[0].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    let environment = &json["subject"]["content"]["environment"];
+    assert_eq!(environment["id"], "eu-west-1/default/test-app");
+    assert_eq!(
+        environment["source"],
+        "https://k8s.example.com/eu-west-1/default/test-app"
+    );
+    assert_eq!(
+        json["subject"]["source"],
+        "https://deploy.example.com/eu-west-1/kulta"
+    );
+}
+
+#[test]
+fn test_source_config_from_lookup_falls_back_to_defaults() {
+    let config = EventSourceConfig::from_lookup(|key| match key {
+        "KULTA_CDEVENTS_SOURCE" => Some("https://ci.example.com".to_string()),
+        "KULTA_CDEVENTS_ENVIRONMENT_ID" => Some("   ".to_string()),
+        _ => None,
+    });
+
+    assert_eq!(config.source, "https://ci.example.com");
+    assert_eq!(config.subject_source, DEFAULT_SUBJECT_SOURCE);
+    assert_eq!(config.environment_id, DEFAULT_ENVIRONMENT_ID);
+    assert_eq!(config.environment_source, DEFAULT_ENVIRONMENT_SOURCE);
+    assert_eq!(config.cluster, "");
+}
+
+// Sinks written before source_config existed keep compiling and use the defaults
+#[test]
+fn test_source_config_defaults_for_sinks_without_override() {
+    struct DiscardSink;
+
+    #[async_trait]
+    impl EventSink for DiscardSink {
+        async fn send(&self, _event: &Event) -> Result<(), CDEventsError> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(DiscardSink.source_config(), &EventSourceConfig::default());
+}
+
+// A new pod template revision on an existing rollout emits service.deployed with the diff
+#[tokio::test]
+async fn test_emit_service_deployed_on_new_revision() {
+    use crate::crd::rollout::{ImageChange, TemplateDiff};
+
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+
+    let old_status = Some(RolloutStatus {
+        phase: Some(Phase::Completed),
+        pod_template_hash: Some("aaaaaaaaaa".to_string()),
+        ..Default::default()
+    });
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Completed),
+        pod_template_hash: Some("bbbbbbbbbb".to_string()),
+        template_diff: Some(TemplateDiff {
+            from_hash: "aaaaaaaaaa".to_string(),
+            to_hash: "bbbbbbbbbb".to_string(),
+            images: vec![ImageChange {
+                container: "nginx".to_string(),
+                from: "nginx:1.0".to_string(),
+                to: "nginx:2.0".to_string(),
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &old_status, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    assert_eq!(events.len(), 1);
+
+    use cloudevents::AttributesReader;
+    assert_eq!(events[0].ty(), "dev.cdevents.service.deployed.0.2.0");
+
+    let json: serde_json::Value = match events[0].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(
+        json["subject"]["id"],
+        "/rollouts/test-app/revisions/bbbbbbbbbb"
+    );
+
+    let kulta = &json["customData"]["kulta"];
+    assert_eq!(kulta["decision"]["reason"], "revision_changed");
+    assert_eq!(kulta["template_diff"]["from_hash"], "aaaaaaaaaa");
+    assert_eq!(kulta["template_diff"]["images"][0]["to"], "nginx:2.0");
+}
+
+// Recording a hash for the first time (e.g. after upgrading KULTA) is not a new revision
+#[tokio::test]
+async fn test_first_recorded_hash_is_not_a_new_revision() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+
+    let old_status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        ..Default::default()
+    });
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        pod_template_hash: Some("bbbbbbbbbb".to_string()),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &old_status, &new_status, &sink)
+        .await
+        .unwrap();
+
+    assert!(sink.get_emitted_events().is_empty());
+}
+
+#[tokio::test]
+async fn test_emit_incident_detected_on_analysis_breach() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+
+    let old_status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        ..Default::default()
+    });
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Failed),
+        current_step_index: Some(0),
+        message: Some("Rollback triggered: metrics exceeded thresholds".to_string()),
+        open_incident: Some(incident_subject_id("test-app", "bbbbbbbbbb")),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &old_status, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].ty(), "dev.cdevents.service.rolledback.0.2.0");
+    assert!(events[1]
+        .ty()
+        .starts_with("dev.cdevents.incident.detected."));
+
+    let json: serde_json::Value = match events[1].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(
+        json["subject"]["id"],
+        "/rollouts/test-app/incidents/bbbbbbbbbb"
+    );
+    assert_eq!(
+        json["subject"]["content"]["description"],
+        "Rollback triggered: metrics exceeded thresholds"
+    );
+    assert_eq!(
+        json["customData"]["kulta"]["decision"]["reason"],
+        "analysis_failed"
+    );
+}
+
+#[tokio::test]
+async fn test_emit_incident_resolved_on_later_completion() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+    let incident = incident_subject_id("test-app", "aaaaaaaaaa");
+
+    let old_status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        open_incident: Some(incident.clone()),
+        ..Default::default()
+    });
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Completed),
+        current_step_index: Some(1),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &old_status, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].ty(), "dev.cdevents.service.published.0.2.0");
+    assert!(events[1]
+        .ty()
+        .starts_with("dev.cdevents.incident.resolved."));
+
+    let json: serde_json::Value = match events[1].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(json["subject"]["id"], incident.as_str());
+}
+
+#[tokio::test]
+async fn test_emit_service_removed_on_deletion() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+    let status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        ..Default::default()
+    };
+
+    emit_removed_event(&rollout, &status, &sink).await.unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(events.len(), 1);
+    assert!(events[0].ty().starts_with("dev.cdevents.service.removed."));
+
+    let json: serde_json::Value = match events[0].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(json["subject"]["id"], "/rollouts/test-app/removal");
+    assert_eq!(
+        json["subject"]["content"]["environment"]["id"],
+        "default/test-app"
+    );
+    assert_eq!(json["customData"]["kulta"]["decision"]["reason"], "removed");
+}
+
+#[tokio::test]
+async fn test_emit_heartbeat_event() {
+    let rollout = create_identity_test_rollout();
+    let sink = MockEventSink::new();
+    let status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(1),
+        current_weight: Some(20),
+        ..Default::default()
+    };
+    let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    emit_heartbeat_event(&rollout, &status, now, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    use cloudevents::AttributesReader;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].ty(), HEARTBEAT_EVENT_TYPE);
+
+    let json: serde_json::Value = match events[0].data().expect("Event should have data") {
+        cloudevents::Data::Json(v) => v.clone(),
+        _ => panic!("Expected JSON data"),
+    };
+    assert_eq!(json["context"]["type"], HEARTBEAT_EVENT_TYPE);
+    assert_eq!(json["context"]["id"], events[0].id());
+    assert_eq!(json["subject"]["content"]["phase"], "Progressing");
+    assert_eq!(json["subject"]["content"]["analysis"]["decisions"], 0);
+    assert_eq!(json["customData"]["kulta"]["step"]["traffic_weight"], 20);
+    assert_eq!(
+        json["customData"]["kulta"]["decision"]["reason"],
+        "heartbeat"
+    );
+}
+
+fn create_identity_test_rollout() -> Rollout {
+    Rollout {
+        metadata: ObjectMeta {
+            name: Some("test-app".to_string()),
+            namespace: Some("default".to_string()),
+            ..Default::default()
+        },
+        spec: RolloutSpec {
+            replicas: 3,
+            selector: Default::default(),
+            template: create_test_pod_template("nginx:1.0"),
+            strategy: RolloutStrategy {
+                simple: None,
+                blue_green: None,
+                ab_testing: None,
+                canary: Some(CanaryStrategy {
+                    canary_service: "test-app-canary".to_string(),
+                    stable_service: "test-app-stable".to_string(),
+                    port: None,
+                    steps: vec![CanaryStep {
+                        set_weight: Some(10),
+                        pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
+                    }],
+                    analysis: None,
+                    traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
+                }),
+            },
+            max_surge: None,
+            max_unavailable: None,
+            progress_deadline_seconds: None,
+            advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
+        },
+        status: None,
+    }
+}
+
 // Helper to create test pod template
 fn create_test_pod_template(image: &str) -> k8s_openapi::api::core::v1::PodTemplateSpec {
     use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
@@ -947,3 +1455,161 @@ fn create_test_pod_template(image: &str) -> k8s_openapi::api::core::v1::PodTempl
         }),
     }
 }
+
+#[test]
+fn test_parse_timeout() {
+    assert_eq!(parse_timeout(None), DEFAULT_CDEVENTS_TIMEOUT);
+    assert_eq!(parse_timeout(Some("")), DEFAULT_CDEVENTS_TIMEOUT);
+    assert_eq!(parse_timeout(Some("3")), Duration::from_secs(3));
+    assert_eq!(parse_timeout(Some(" 30 ")), Duration::from_secs(30));
+    assert_eq!(parse_timeout(Some("0")), DEFAULT_CDEVENTS_TIMEOUT);
+    assert_eq!(parse_timeout(Some("soon")), DEFAULT_CDEVENTS_TIMEOUT);
+}
+
+#[test]
+fn test_build_sink_client_ignores_invalid_proxy() {
+    // Must not panic; events are still sent, just without a proxy
+    let _client = build_sink_client(Duration::from_secs(1), Some("not a url"));
+    let _client = build_sink_client(Duration::from_secs(1), Some("http://proxy.internal:3128"));
+}
+
+#[tokio::test]
+async fn test_events_omit_large_pod_template() {
+    use k8s_openapi::api::core::v1::EnvVar;
+
+    let mut rollout = create_identity_test_rollout();
+    if let Some(spec) = rollout.spec.template.spec.as_mut() {
+        spec.containers[0].env = Some(
+            (0..5000)
+                .map(|i| EnvVar {
+                    name: format!("SETTING_{}", i),
+                    value: Some("x".repeat(64)),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+    }
+    let sink = MockEventSink::new();
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        ..Default::default()
+    };
+
+    emit_status_change_event(&rollout, &None, &new_status, &sink)
+        .await
+        .unwrap();
+
+    let events = sink.get_emitted_events();
+    assert_eq!(events.len(), 1);
+    let payload = serde_json::to_string(&events[0]).unwrap();
+    assert!(!payload.contains("SETTING_0"), "template must not be sent");
+    assert!(
+        payload.len() < 16 * 1024,
+        "payload is {} bytes",
+        payload.len()
+    );
+}
+
+#[test]
+fn test_parse_payload_compression_and_size_cap() {
+    assert_eq!(PayloadCompression::parse(None), PayloadCompression::None);
+    assert_eq!(
+        PayloadCompression::parse(Some("")),
+        PayloadCompression::None
+    );
+    assert_eq!(
+        PayloadCompression::parse(Some("gzip")),
+        PayloadCompression::Gzip
+    );
+    assert_eq!(
+        PayloadCompression::parse(Some(" ZSTD ")),
+        PayloadCompression::Zstd
+    );
+    assert_eq!(
+        PayloadCompression::parse(Some("brotli")),
+        PayloadCompression::None
+    );
+
+    assert_eq!(parse_max_bytes(None), None);
+    assert_eq!(parse_max_bytes(Some("65536")), Some(65536));
+    assert_eq!(parse_max_bytes(Some("0")), None);
+    assert_eq!(parse_max_bytes(Some("64k")), None);
+}
+
+#[test]
+fn test_payload_compression_round_trips() {
+    use std::io::Read;
+
+    let body = br#"{"specversion":"1.0","data":{"customData":{}}}"#.repeat(50);
+
+    let gzip = PayloadCompression::Gzip.compress(body.clone()).unwrap();
+    assert!(gzip.len() < body.len());
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(gzip.as_slice())
+        .read_to_end(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, body);
+
+    let zstd = PayloadCompression::Zstd.compress(body.clone()).unwrap();
+    assert!(zstd.len() < body.len());
+    assert_eq!(zstd::decode_all(zstd.as_slice()).unwrap(), body);
+
+    assert_eq!(
+        PayloadCompression::None.compress(body.clone()).unwrap(),
+        body
+    );
+    assert_eq!(PayloadCompression::None.content_encoding(), None);
+    assert_eq!(PayloadCompression::Zstd.content_encoding(), Some("zstd"));
+}
+
+#[tokio::test]
+async fn test_fit_payload_truncates_custom_data_under_cap() {
+    let mut rollout = create_identity_test_rollout();
+    rollout.metadata.annotations = Some(
+        (0..200)
+            .map(|i| (format!("links.kulta.io/ticket-{}", i), "x".repeat(100)))
+            .collect(),
+    );
+    let sink = MockEventSink::new();
+    let new_status = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(10),
+        ..Default::default()
+    };
+    emit_status_change_event(&rollout, &None, &new_status, &sink)
+        .await
+        .unwrap();
+    let event = &sink.get_emitted_events()[0];
+
+    let FittedPayload::Whole(whole) = fit_payload(event, None).unwrap() else {
+        panic!("no cap must send the event whole");
+    };
+    assert!(whole.len() > 20_000);
+    assert_eq!(
+        fit_payload(event, Some(whole.len())).unwrap(),
+        FittedPayload::Whole(whole.clone())
+    );
+
+    let FittedPayload::Truncated(body) = fit_payload(event, Some(8 * 1024)).unwrap() else {
+        panic!("links must be dropped to fit");
+    };
+    assert!(body.len() <= 8 * 1024);
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let kulta = &json["data"]["customData"]["kulta"];
+    assert_eq!(kulta["truncated"], true);
+    assert!(kulta.get("links").is_none());
+    assert_eq!(kulta["rollout"]["name"], "test-app");
+    assert_eq!(kulta["step"]["traffic_weight"], 10);
+    assert_eq!(
+        json["data"]["subject"]["content"]["artifactId"],
+        "nginx:1.0"
+    );
+
+    assert!(matches!(
+        fit_payload(event, Some(100)).unwrap(),
+        FittedPayload::TooLarge(size) if size > 100
+    ));
+}
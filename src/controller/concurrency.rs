@@ -0,0 +1,172 @@
+//! Per-namespace limit on concurrently active Rollouts
+//!
+//! A deploy train that starts a canary or blue-green preview for every
+//! service at once runs two copies of each and can saturate the cluster.
+//! With `KULTA_MAX_CONCURRENT_ROLLOUTS=N`, at most N Rollouts per namespace
+//! are active (Progressing, Paused, Preview or Experimenting). A Rollout that
+//! would start a new revision past the limit waits in the Pending phase;
+//! waiting Rollouts start in creation order as slots free up.
+
+use crate::crd::rollout::{Phase, Rollout, RolloutStatus};
+use kube::ResourceExt;
+use tracing::warn;
+
+/// Controller-wide limit on active Rollouts per namespace
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConcurrencyLimit {
+    /// Most Rollouts active at once in a namespace (unlimited when unset)
+    pub max_active: Option<usize>,
+}
+
+impl ConcurrencyLimit {
+    /// Create limit from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create limit from an arbitrary key lookup (testable without touching env)
+    ///
+    /// `0` and invalid values leave Rollouts unlimited.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let max_active =
+            lookup("KULTA_MAX_CONCURRENT_ROLLOUTS").and_then(|v| match v.trim().parse::<usize>() {
+                Ok(0) => None,
+                Ok(max) => Some(max),
+                Err(_) => {
+                    warn!(value = %v, "Ignoring invalid KULTA_MAX_CONCURRENT_ROLLOUTS");
+                    None
+                }
+            });
+        Self { max_active }
+    }
+}
+
+/// Whether a Rollout holds a slot (runs its new revision next to the old one)
+pub fn is_active(rollout: &Rollout) -> bool {
+    matches!(
+        rollout.status.as_ref().and_then(|s| s.phase.as_ref()),
+        Some(Phase::Progressing | Phase::Paused | Phase::Preview | Phase::Experimenting)
+    )
+}
+
+fn is_pending(rollout: &Rollout) -> bool {
+    rollout
+        .status
+        .as_ref()
+        .is_some_and(|s| s.phase == Some(Phase::Pending))
+}
+
+/// Queue position of a Rollout that wants to start a new revision
+///
+/// `others` are the Rollouts in the namespace (the Rollout itself is
+/// skipped). Pending Rollouts created earlier are ahead in the queue.
+///
+/// # Returns
+/// * `None` - A slot is free, the Rollout may start
+/// * `Some(position)` - 1-based position among the waiting Rollouts
+pub fn queue_position(max_active: usize, rollout: &Rollout, others: &[Rollout]) -> Option<usize> {
+    let key = |r: &Rollout| (r.creation_timestamp().map(|t| t.0), r.name_any());
+    let own_key = key(rollout);
+    let others = others.iter().filter(|r| r.name_any() != rollout.name_any());
+
+    let (mut active, mut ahead) = (0, 0);
+    for other in others {
+        if is_active(other) {
+            active += 1;
+        } else if is_pending(other) && key(other) < own_key {
+            ahead += 1;
+        }
+    }
+
+    let taken = active + ahead;
+    (taken >= max_active).then(|| taken - max_active + 1)
+}
+
+/// Status for a Rollout waiting for a free slot
+///
+/// Keeps the current status and records the queue position.
+pub fn pending_status(rollout: &Rollout, max_active: usize, position: usize) -> RolloutStatus {
+    RolloutStatus {
+        phase: Some(Phase::Pending),
+        message: Some(format!(
+            "Pending: namespace is at its limit of {} active Rollouts, position {} in queue",
+            max_active, position
+        )),
+        ..rollout.status.clone().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::SimpleStrategy;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    fn rollout(name: &str, created_secs: i64, phase: Option<Phase>) -> Rollout {
+        let mut rollout = RolloutBuilder::new(name)
+            .namespace("shop")
+            .replicas(2)
+            .label("app", name)
+            .image("app", "app:2")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        rollout.metadata.creation_timestamp = Some(Time(
+            chrono::DateTime::from_timestamp(created_secs, 0).unwrap(),
+        ));
+        rollout.status = phase.map(|phase| RolloutStatus {
+            phase: Some(phase),
+            ..Default::default()
+        });
+        rollout
+    }
+
+    #[test]
+    fn test_from_lookup() {
+        let limit = |value: &str| {
+            let value = value.to_string();
+            ConcurrencyLimit::from_lookup(move |_| Some(value.clone())).max_active
+        };
+        assert_eq!(limit("3"), Some(3));
+        assert_eq!(limit("0"), None);
+        assert_eq!(limit("many"), None);
+        assert_eq!(ConcurrencyLimit::from_lookup(|_| None).max_active, None);
+    }
+
+    #[test]
+    fn test_queue_position_counts_active_rollouts() {
+        let others = vec![
+            rollout("cart", 1, Some(Phase::Progressing)),
+            rollout("search", 2, Some(Phase::Preview)),
+            rollout("docs", 3, Some(Phase::Completed)),
+            rollout("auth", 4, Some(Phase::Failed)),
+        ];
+        let checkout = rollout("checkout", 10, None);
+
+        assert_eq!(queue_position(3, &checkout, &others), None);
+        assert_eq!(queue_position(2, &checkout, &others), Some(1));
+        assert_eq!(queue_position(1, &checkout, &others), Some(2));
+    }
+
+    #[test]
+    fn test_queue_position_is_first_come_first_served() {
+        let others = vec![
+            rollout("cart", 1, Some(Phase::Progressing)),
+            rollout("early", 5, Some(Phase::Pending)),
+            rollout("late", 20, Some(Phase::Pending)),
+        ];
+        let checkout = rollout("checkout", 10, Some(Phase::Pending));
+
+        // "early" waits ahead of checkout, "late" behind it
+        assert_eq!(queue_position(1, &checkout, &others), Some(2));
+        assert_eq!(queue_position(2, &checkout, &others), Some(1));
+        assert_eq!(queue_position(3, &checkout, &others), None);
+
+        // The Rollout itself never takes a slot
+        let mut with_self = others.clone();
+        with_self.push(rollout("checkout", 10, Some(Phase::Progressing)));
+        assert_eq!(queue_position(3, &checkout, &with_self), None);
+    }
+}
@@ -0,0 +1,324 @@
+//! Controller-wide defaults for Rollouts that leave fields unset
+//!
+//! Platform standards (a canary step ladder, the metrics every rollout is
+//! judged by, a progress deadline) can be set once for the controller
+//! instead of being copied into every Rollout. `KULTA_DEFAULTS_FILE` names a
+//! YAML file, typically a mounted ConfigMap:
+//!
+//! ```yaml
+//! progressDeadlineSeconds: 600
+//! canary:
+//!   steps:
+//!   - setWeight: 10
+//!     pause: { duration: 5m }
+//!   - setWeight: 50
+//!     pause: { duration: 10m }
+//!   - setWeight: 100
+//!   analysis:
+//!     metrics:
+//!     - name: error-rate
+//!       threshold: 5.0
+//! blueGreen:
+//!   analysis:
+//!     metrics:
+//!     - name: error-rate
+//!       threshold: 5.0
+//! ```
+//!
+//! A default never overrides the spec. Canary `steps` are filled when the
+//! Rollout has neither `steps` nor `stepsFrom`; the default `analysis` is
+//! used when the strategy has none, and only its `metrics` when the
+//! strategy's analysis lists none. The file is read once at startup.
+
+use crate::crd::rollout::{AnalysisConfig, CanaryStep, Rollout};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::{info, warn};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum DefaultsError {
+    #[error("failed to read defaults file {path}: {reason}")]
+    Read { path: String, reason: String },
+
+    #[error("invalid defaults in {path}: {reason}")]
+    Invalid { path: String, reason: String },
+}
+
+/// Defaults for canary Rollouts
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CanaryDefaults {
+    /// Step ladder for canaries without `steps` or `stepsFrom`
+    #[serde(default)]
+    pub steps: Vec<CanaryStep>,
+
+    #[serde(default)]
+    pub analysis: Option<AnalysisConfig>,
+}
+
+/// Defaults for blue-green Rollouts
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct BlueGreenDefaults {
+    #[serde(default)]
+    pub analysis: Option<AnalysisConfig>,
+}
+
+/// Controller-wide Rollout defaults (none unless configured)
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RolloutDefaults {
+    #[serde(rename = "progressDeadlineSeconds", default)]
+    pub progress_deadline_seconds: Option<i32>,
+
+    #[serde(default)]
+    pub canary: CanaryDefaults,
+
+    #[serde(rename = "blueGreen", default)]
+    pub blue_green: BlueGreenDefaults,
+}
+
+impl RolloutDefaults {
+    /// Load the defaults from the file named by `KULTA_DEFAULTS_FILE`
+    ///
+    /// A missing or invalid file is logged and no defaults apply, so a
+    /// broken ConfigMap doesn't stop the controller.
+    pub fn from_env() -> Self {
+        let Some(path) = std::env::var("KULTA_DEFAULTS_FILE")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+        else {
+            return Self::default();
+        };
+
+        match Self::load(&path) {
+            Ok(defaults) => {
+                info!(path = %path, "Loaded controller Rollout defaults");
+                defaults
+            }
+            Err(e) => {
+                warn!(error = %e, "Ignoring KULTA_DEFAULTS_FILE");
+                Self::default()
+            }
+        }
+    }
+
+    /// Read and parse a defaults file
+    pub fn load(path: &str) -> Result<Self, DefaultsError> {
+        let data = std::fs::read_to_string(path).map_err(|e| DefaultsError::Read {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::parse(&data).map_err(|reason| DefaultsError::Invalid {
+            path: path.to_string(),
+            reason,
+        })
+    }
+
+    /// Parse defaults from YAML (or JSON); an empty document sets none
+    pub fn parse(data: &str) -> Result<Self, String> {
+        if data.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(data).map_err(|e| e.to_string())
+    }
+
+    /// Fill the fields the Rollout leaves unset
+    ///
+    /// # Returns
+    /// Paths of the fields that were filled in (empty if none)
+    pub fn apply(&self, rollout: &mut Rollout) -> Vec<String> {
+        let mut defaulted = Vec::new();
+
+        if rollout.spec.progress_deadline_seconds.is_none() {
+            if let Some(seconds) = self.progress_deadline_seconds {
+                rollout.spec.progress_deadline_seconds = Some(seconds);
+                defaulted.push("spec.progressDeadlineSeconds".to_string());
+            }
+        }
+
+        if let Some(canary) = rollout.spec.strategy.canary.as_mut() {
+            if canary.steps.is_empty()
+                && canary.steps_from.is_none()
+                && !self.canary.steps.is_empty()
+            {
+                canary.steps = self.canary.steps.clone();
+                defaulted.push("spec.strategy.canary.steps".to_string());
+            }
+            if let Some(field) = fill_analysis(&mut canary.analysis, self.canary.analysis.as_ref())
+            {
+                defaulted.push(format!("spec.strategy.canary.{}", field));
+            }
+        }
+
+        if let Some(blue_green) = rollout.spec.strategy.blue_green.as_mut() {
+            if let Some(field) =
+                fill_analysis(&mut blue_green.analysis, self.blue_green.analysis.as_ref())
+            {
+                defaulted.push(format!("spec.strategy.blueGreen.{}", field));
+            }
+        }
+
+        defaulted
+    }
+}
+
+/// Fill an unset analysis, or its metrics, from the default
+///
+/// # Returns
+/// The field that was filled in, relative to the strategy
+fn fill_analysis(
+    analysis: &mut Option<AnalysisConfig>,
+    default: Option<&AnalysisConfig>,
+) -> Option<&'static str> {
+    let default = default?;
+    match analysis {
+        None => {
+            *analysis = Some(default.clone());
+            Some("analysis")
+        }
+        Some(analysis) if analysis.metrics.is_empty() && !default.metrics.is_empty() => {
+            analysis.metrics = default.metrics.clone();
+            Some("analysis.metrics")
+        }
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::controller::steps_from::with_steps;
+    use crate::crd::builder::RolloutBuilder;
+
+    const DEFAULTS: &str = r#"
+progressDeadlineSeconds: 600
+canary:
+  steps:
+  - setWeight: 10
+    pause: { duration: 5m }
+  - setWeight: 100
+  analysis:
+    warmupDuration: 1m
+    metrics:
+    - name: error-rate
+      threshold: 5.0
+blueGreen:
+  analysis:
+    metrics:
+    - name: latency-p95
+      threshold: 500
+"#;
+
+    fn rollout() -> RolloutBuilder {
+        RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+    }
+
+    #[test]
+    fn test_apply_fills_unset_canary_fields() {
+        let defaults = RolloutDefaults::parse(DEFAULTS).unwrap();
+        let built = rollout()
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop-stable",
+                    "canaryService": "shop-canary",
+                    "steps": [{ "setWeight": 100 }]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        // Steps left to the defaults, which validation alone rejects
+        let mut rollout = with_steps(&built, Vec::new());
+
+        let defaulted = defaults.apply(&mut rollout);
+
+        assert_eq!(
+            defaulted,
+            vec![
+                "spec.progressDeadlineSeconds",
+                "spec.strategy.canary.steps",
+                "spec.strategy.canary.analysis",
+            ]
+        );
+        let canary = rollout.spec.strategy.canary.as_ref().unwrap();
+        assert_eq!(canary.steps.len(), 2);
+        assert_eq!(canary.steps[0].set_weight, Some(10));
+        let analysis = canary.analysis.as_ref().unwrap();
+        assert_eq!(analysis.warmup_duration.as_deref(), Some("1m"));
+        assert_eq!(analysis.metrics[0].name, "error-rate");
+        assert_eq!(rollout.spec.progress_deadline_seconds, Some(600));
+    }
+
+    #[test]
+    fn test_apply_keeps_spec_values() {
+        let defaults = RolloutDefaults::parse(DEFAULTS).unwrap();
+        let mut canary = rollout()
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop-stable",
+                    "canaryService": "shop-canary",
+                    "stepsFrom": { "configMapRef": { "name": "ladders" } },
+                    "analysis": {
+                        "warmupDuration": "5m",
+                        "metrics": [{ "name": "saturation", "threshold": 80 }]
+                    }
+                }))
+                .unwrap(),
+            )
+            .progress_deadline_seconds(120)
+            .build()
+            .unwrap();
+
+        assert!(defaults.apply(&mut canary).is_empty());
+        let strategy = canary.spec.strategy.canary.as_ref().unwrap();
+        assert!(strategy.steps.is_empty());
+        assert_eq!(
+            strategy.analysis.as_ref().unwrap().metrics[0].name,
+            "saturation"
+        );
+        assert_eq!(canary.spec.progress_deadline_seconds, Some(120));
+
+        // An analysis without metrics only gets the default metrics
+        let mut blue_green = rollout()
+            .blue_green(
+                serde_json::from_value(serde_json::json!({
+                    "activeService": "shop-active",
+                    "previewService": "shop-preview",
+                    "analysis": { "warmupDuration": "2m" }
+                }))
+                .unwrap(),
+            )
+            .progress_deadline_seconds(120)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            defaults.apply(&mut blue_green),
+            vec!["spec.strategy.blueGreen.analysis.metrics"]
+        );
+        let analysis = blue_green
+            .spec
+            .strategy
+            .blue_green
+            .as_ref()
+            .unwrap()
+            .analysis
+            .as_ref()
+            .unwrap();
+        assert_eq!(analysis.warmup_duration.as_deref(), Some("2m"));
+        assert_eq!(analysis.metrics[0].name, "latency-p95");
+    }
+
+    #[test]
+    fn test_parse_and_load_errors() {
+        assert!(RolloutDefaults::parse("").unwrap().canary.steps.is_empty());
+        assert!(RolloutDefaults::parse("canary: { steps: 5 }").is_err());
+        assert!(matches!(
+            RolloutDefaults::load("/nonexistent/kulta-defaults.yaml"),
+            Err(DefaultsError::Read { .. })
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! Dependency gates for canary weight increases
+//!
+//! A canary can list workloads it depends on (a database proxy, a shared
+//! cache) in `canary.gates`. Before each weight increase every gate is
+//! checked; if one is unhealthy the rollout holds at its current step with a
+//! message naming the dependency, and tries again on the next reconcile.
+//!
+//! - `Deployment` gates wait for a status condition to be `True` (e.g. `Available`)
+//! - `Rollout` gates wait for the Rollout to reach a phase (e.g. `Completed`)
+//!
+//! A dependency that is missing or can't be read counts as unhealthy.
+
+use crate::crd::rollout::{DependencyGate, DependencyKind, Phase, Rollout};
+use k8s_openapi::api::apps::v1::Deployment;
+use kube::api::Api;
+use kube::Client;
+
+/// Whether a Deployment has `condition` with status `True`
+pub fn deployment_condition_met(deployment: &Deployment, condition: &str) -> bool {
+    deployment
+        .status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .is_some_and(|conditions| {
+            conditions
+                .iter()
+                .any(|c| c.type_.eq_ignore_ascii_case(condition) && c.status == "True")
+        })
+}
+
+/// Whether a Rollout is in the phase named by `condition` (case-insensitive)
+pub fn rollout_phase_met(rollout: &Rollout, condition: &str) -> bool {
+    rollout
+        .status
+        .as_ref()
+        .and_then(|status| status.phase.as_ref())
+        .is_some_and(|phase| phase_name(phase).eq_ignore_ascii_case(condition))
+}
+
+fn phase_name(phase: &Phase) -> String {
+    serde_json::to_value(phase)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Human-readable reference to a gate's dependency, e.g. `Deployment db/pgbouncer`
+pub fn gate_target(gate: &DependencyGate, default_namespace: &str) -> String {
+    format!(
+        "{:?} {}/{}",
+        gate.kind,
+        gate.namespace.as_deref().unwrap_or(default_namespace),
+        gate.service
+    )
+}
+
+/// Check a single gate against the cluster
+///
+/// # Returns
+/// * `Ok(())` - Dependency is healthy
+/// * `Err(reason)` - Dependency is missing, unreadable or not in the required condition
+pub async fn check_dependency_gate(
+    gate: &DependencyGate,
+    client: &Client,
+    default_namespace: &str,
+) -> Result<(), String> {
+    let namespace = gate.namespace.as_deref().unwrap_or(default_namespace);
+    let target = gate_target(gate, default_namespace);
+
+    let met = match gate.kind {
+        DependencyKind::Deployment => {
+            let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(&gate.service)
+                .await
+                .map(|d| d.map(|d| deployment_condition_met(&d, &gate.condition)))
+        }
+        DependencyKind::Rollout => {
+            let api: Api<Rollout> = Api::namespaced(client.clone(), namespace);
+            api.get_opt(&gate.service)
+                .await
+                .map(|r| r.map(|r| rollout_phase_met(&r, &gate.condition)))
+        }
+    };
+
+    match met {
+        Ok(Some(true)) => Ok(()),
+        Ok(Some(false)) => Err(format!("{} is not {}", target, gate.condition)),
+        Ok(None) => Err(format!("{} not found", target)),
+        Err(e) => Err(format!("{} could not be read: {}", target, e)),
+    }
+}
+
+/// Check every gate, returning the reasons of those that are not healthy
+pub async fn unhealthy_dependencies(
+    gates: &[DependencyGate],
+    client: &Client,
+    default_namespace: &str,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    for gate in gates {
+        if let Err(reason) = check_dependency_gate(gate, client, default_namespace).await {
+            reasons.push(reason);
+        }
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{RolloutStatus, SimpleStrategy};
+    use k8s_openapi::api::apps::v1::{DeploymentCondition, DeploymentStatus};
+
+    fn deployment_with(conditions: &[(&str, &str)]) -> Deployment {
+        Deployment {
+            status: Some(DeploymentStatus {
+                conditions: Some(
+                    conditions
+                        .iter()
+                        .map(|(type_, status)| DeploymentCondition {
+                            type_: type_.to_string(),
+                            status: status.to_string(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn rollout_in(phase: Option<Phase>) -> Rollout {
+        let mut rollout = RolloutBuilder::new("db-proxy")
+            .namespace("data")
+            .label("app", "db-proxy")
+            .image("proxy", "db-proxy:1")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        rollout.status = Some(RolloutStatus {
+            phase,
+            ..Default::default()
+        });
+        rollout
+    }
+
+    #[test]
+    fn test_deployment_condition_met() {
+        let deployment = deployment_with(&[("Available", "True"), ("Progressing", "False")]);
+
+        assert!(deployment_condition_met(&deployment, "Available"));
+        assert!(deployment_condition_met(&deployment, "available"));
+        assert!(!deployment_condition_met(&deployment, "Progressing"));
+        assert!(!deployment_condition_met(&deployment, "ReplicaFailure"));
+        assert!(!deployment_condition_met(
+            &Deployment::default(),
+            "Available"
+        ));
+    }
+
+    #[test]
+    fn test_rollout_phase_met() {
+        assert!(rollout_phase_met(
+            &rollout_in(Some(Phase::Completed)),
+            "Completed"
+        ));
+        assert!(rollout_phase_met(
+            &rollout_in(Some(Phase::Completed)),
+            "completed"
+        ));
+        assert!(!rollout_phase_met(
+            &rollout_in(Some(Phase::Progressing)),
+            "Completed"
+        ));
+        assert!(!rollout_phase_met(&rollout_in(None), "Completed"));
+    }
+
+    #[test]
+    fn test_gate_target_defaults_namespace() {
+        let gate = DependencyGate {
+            service: "pgbouncer".to_string(),
+            namespace: None,
+            kind: DependencyKind::Deployment,
+            condition: "Available".to_string(),
+        };
+        assert_eq!(gate_target(&gate, "shop"), "Deployment shop/pgbouncer");
+
+        let gate = DependencyGate {
+            namespace: Some("data".to_string()),
+            kind: DependencyKind::Rollout,
+            ..gate
+        };
+        assert_eq!(gate_target(&gate, "shop"), "Rollout data/pgbouncer");
+    }
+}
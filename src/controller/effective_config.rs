@@ -0,0 +1,221 @@
+//! Effective configuration recorded in `status.effectiveConfig`
+//!
+//! What a rollout does depends on more than its spec: defaults for unset
+//! fields, controller-wide defaults, the template of an adopted Deployment,
+//! steps imported from a ConfigMap, weight normalization and namespace policies (pod template
+//! stripping, promotion mode, advisor guardrails). Every reconcile records
+//! the result, so `kubectl get rollout -o yaml` answers "why did it do that"
+//! without replaying the defaulting logic by hand.
+
+use crate::controller::promotion_policy::PromotionMode;
+use crate::controller::rollout::revision_history_limit;
+use crate::controller::steps_from::{steps_configmap_key, steps_configmap_ref, DEFAULT_STEPS_KEY};
+use crate::controller::strategy_math::{canary_steps, step_weight};
+use crate::crd::conversion::{DEFAULT_MAX_SURGE, DEFAULT_MAX_UNAVAILABLE};
+use crate::crd::rollout::{AdvisorLevel, EffectiveConfig, Rollout};
+
+/// Namespace policy decisions that shaped a reconcile
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AppliedPolicies {
+    /// Pod template fields stripped by the pod policy
+    pub stripped_fields: Vec<String>,
+    /// Spec fields filled from the controller defaults
+    pub defaulted_fields: Vec<String>,
+    /// Mode forced by the promotion policy, if a rule matched
+    pub promotion_mode: Option<PromotionMode>,
+    /// Advisor level after the guardrails
+    pub advisor_level: AdvisorLevel,
+}
+
+/// Effective configuration of a reconcile
+///
+/// `declared` is the Rollout as stored in the cluster, `applied` the one the
+/// strategy ran with (workload template adopted, steps imported, template
+/// sanitized).
+pub fn effective_config(
+    declared: &Rollout,
+    applied: &Rollout,
+    policies: AppliedPolicies,
+) -> EffectiveConfig {
+    let template_source = match &declared.spec.workload_ref {
+        Some(workload_ref) => format!("Deployment/{}", workload_ref.name),
+        None => "spec.template".to_string(),
+    };
+    let steps_source = match (steps_configmap_ref(declared), steps_configmap_key(declared)) {
+        (Some(reference), Some((namespace, name))) => Some(format!(
+            "ConfigMap {}/{}[{}]",
+            namespace,
+            name,
+            reference.key.as_deref().unwrap_or(DEFAULT_STEPS_KEY)
+        )),
+        _ => declared.spec.strategy.canary.as_ref().map(|_| {
+            if policies
+                .defaulted_fields
+                .iter()
+                .any(|field| field == "spec.strategy.canary.steps")
+            {
+                "controller defaults".to_string()
+            } else {
+                "spec.strategy.canary.steps".to_string()
+            }
+        }),
+    };
+    let step_weights = canary_steps(&applied.spec)
+        .iter()
+        .map(|step| step_weight(&applied.spec, step))
+        .collect();
+
+    EffectiveConfig {
+        template_source,
+        stripped_fields: policies.stripped_fields,
+        defaulted_fields: policies.defaulted_fields,
+        steps_source,
+        step_weights,
+        promotion_mode: policies.promotion_mode,
+        advisor_level: policies.advisor_level,
+        max_surge: applied
+            .spec
+            .max_surge
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MAX_SURGE.to_string()),
+        max_unavailable: applied
+            .spec
+            .max_unavailable
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MAX_UNAVAILABLE.to_string()),
+        progress_deadline_seconds: applied.spec.progress_deadline_seconds,
+        revision_history_limit: i32::try_from(revision_history_limit(applied)).unwrap_or(i32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::controller::steps_from::with_steps;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{CanaryStep, SimpleStrategy};
+
+    fn rollout() -> RolloutBuilder {
+        RolloutBuilder::new("checkout")
+            .namespace("shop")
+            .replicas(3)
+            .label("app", "checkout")
+    }
+
+    #[test]
+    fn test_effective_config_fills_defaults() {
+        let declared = rollout()
+            .workload_ref(
+                serde_json::from_value(serde_json::json!({ "name": "checkout" })).unwrap(),
+            )
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+
+        let config = effective_config(&declared, &declared, AppliedPolicies::default());
+
+        assert_eq!(config.template_source, "Deployment/checkout");
+        assert_eq!(config.steps_source, None);
+        assert!(config.step_weights.is_empty());
+        assert_eq!(config.promotion_mode, None);
+        assert_eq!(config.advisor_level, AdvisorLevel::Off);
+        assert_eq!(config.max_surge, "25%");
+        assert_eq!(config.max_unavailable, "0");
+        assert_eq!(config.progress_deadline_seconds, None);
+        assert_eq!(config.revision_history_limit, 10);
+    }
+
+    #[test]
+    fn test_effective_config_records_imported_steps_and_policies() {
+        let declared = rollout()
+            .image("app", "checkout:2")
+            .max_surge("1")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "checkout-stable",
+                    "canaryService": "checkout-canary",
+                    "stepsFrom": { "configMapRef": { "name": "step-ladders", "namespace": "platform" } },
+                    "trafficRouting": { "gatewayAPI": {
+                        "httpRoute": "checkout",
+                        "weightConstraints": { "granularity": 5, "minWeight": 10, "normalize": true }
+                    } }
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let step = |weight| CanaryStep {
+            set_weight: Some(weight),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        };
+        let applied = with_steps(&declared, vec![step(7), step(50), step(100)]);
+        let config = effective_config(
+            &declared,
+            &applied,
+            AppliedPolicies {
+                stripped_fields: vec!["securityContext.privileged".to_string()],
+                defaulted_fields: Vec::new(),
+                promotion_mode: Some(PromotionMode::Manual),
+                advisor_level: AdvisorLevel::Context,
+            },
+        );
+
+        assert_eq!(config.template_source, "spec.template");
+        assert_eq!(
+            config.steps_source.as_deref(),
+            Some("ConfigMap platform/step-ladders[steps]")
+        );
+        assert_eq!(config.step_weights, vec![10, 50, 100]);
+        assert_eq!(config.stripped_fields.len(), 1);
+        assert_eq!(config.promotion_mode, Some(PromotionMode::Manual));
+        assert_eq!(config.max_surge, "1");
+    }
+
+    #[test]
+    fn test_effective_config_records_controller_defaults() {
+        let built = rollout()
+            .image("app", "checkout:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "checkout-stable",
+                    "canaryService": "checkout-canary",
+                    "steps": [{ "setWeight": 100 }]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        // Steps left to the controller defaults
+        let declared = with_steps(&built, Vec::new());
+        let defaults = crate::controller::defaults::RolloutDefaults::parse(
+            "progressDeadlineSeconds: 600\ncanary: { steps: [{ setWeight: 20 }, { setWeight: 100 }] }",
+        )
+        .unwrap();
+        let mut applied = declared.clone();
+        let defaulted_fields = defaults.apply(&mut applied);
+
+        let config = effective_config(
+            &applied,
+            &applied,
+            AppliedPolicies {
+                defaulted_fields,
+                ..AppliedPolicies::default()
+            },
+        );
+
+        assert_eq!(config.steps_source.as_deref(), Some("controller defaults"));
+        assert_eq!(config.step_weights, vec![20, 100]);
+        assert_eq!(config.progress_deadline_seconds, Some(600));
+        assert_eq!(
+            config.defaulted_fields,
+            vec!["spec.progressDeadlineSeconds", "spec.strategy.canary.steps"]
+        );
+    }
+}
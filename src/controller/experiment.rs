@@ -0,0 +1,556 @@
+//! Experiment controller
+//!
+//! An Experiment moves through its phases as follows:
+//!
+//! - `Pending`: one ReplicaSet `{experiment}-{template}` is created per
+//!   template and scaled up. If they aren't all ready within
+//!   [`EXPERIMENT_READY_TIMEOUT`] of the Experiment's creation, it fails
+//! - `Running`: all ReplicaSets are ready (`status.availableAt`); once
+//!   `duration` has passed the analyses are evaluated
+//! - `Successful` when every analysis passed, `Failed` otherwise (an
+//!   analysis that can't be evaluated fails too). Either way the
+//!   ReplicaSets are deleted
+//!
+//! The ReplicaSets are owned by the Experiment and carry no Rollout labels,
+//! so neither the Rollout controller nor the orphan collector touches them.
+//! Their pods keep the template's labels plus [`EXPERIMENT_LABEL`],
+//! [`EXPERIMENT_TEMPLATE_LABEL`] and `rollouts.kulta.io/type: experiment`.
+//!
+//! In analysis queries `{{rollout}}` is the Rollout that launched the
+//! Experiment (the Experiment's own name when created by hand) and
+//! `{{revision}}` the Experiment's name, which the built-in templates match
+//! in `revision` (or `revisionLabelKey`).
+
+use crate::controller::orphans::ROLLOUT_ANNOTATION;
+use crate::controller::prometheus::{RevisionSelector, DEFAULT_REVISION_LABEL};
+use crate::controller::rollout::reconcile::{analysis_querier_for, render_analysis_variables};
+use crate::controller::rollout::{parse_duration, validate_experiment, Context, ReconcileError};
+use crate::crd::experiment::{
+    Experiment, ExperimentAnalysis, ExperimentAnalysisResult, ExperimentPhase, ExperimentStatus,
+    ExperimentTemplate, ExperimentTemplateStatus,
+};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::{ReplicaSet, ReplicaSetSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::api::{Api, DeleteParams, ObjectMeta, Patch, PatchParams, PostParams};
+use kube::runtime::controller::Action;
+use kube::{Resource, ResourceExt};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Label on an Experiment's ReplicaSets and pods naming the Experiment
+pub const EXPERIMENT_LABEL: &str = "kulta.io/experiment";
+
+/// Label on an Experiment's ReplicaSets and pods naming their template
+pub const EXPERIMENT_TEMPLATE_LABEL: &str = "kulta.io/experiment-template";
+
+/// How long the ReplicaSets of an Experiment may take to become ready
+pub const EXPERIMENT_READY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How often an unfinished Experiment is looked at
+const EXPERIMENT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+const TYPE_LABEL: &str = "rollouts.kulta.io/type";
+
+/// ReplicaSet type of experiment pods (`rollouts.kulta.io/type`)
+pub const EXPERIMENT_REPLICASET_TYPE: &str = "experiment";
+
+/// Name of a template's ReplicaSet: `{experiment}-{template}`
+pub fn experiment_replicaset_name(
+    experiment: &Experiment,
+    template: &ExperimentTemplate,
+) -> String {
+    format!("{}-{}", experiment.name_any(), template.name)
+}
+
+/// Build the ReplicaSet of an Experiment template
+///
+/// Selects its pods by [`EXPERIMENT_LABEL`] and [`EXPERIMENT_TEMPLATE_LABEL`];
+/// owned by the Experiment, so deleting the Experiment deletes it.
+pub fn build_experiment_replicaset(
+    experiment: &Experiment,
+    template: &ExperimentTemplate,
+) -> ReplicaSet {
+    let selector_labels = BTreeMap::from([
+        (EXPERIMENT_LABEL.to_string(), experiment.name_any()),
+        (EXPERIMENT_TEMPLATE_LABEL.to_string(), template.name.clone()),
+    ]);
+
+    let mut pod_template = template.template.clone();
+    let mut pod_metadata = pod_template.metadata.take().unwrap_or_default();
+    let mut pod_labels = pod_metadata.labels.take().unwrap_or_default();
+    pod_labels.extend(selector_labels.clone());
+    pod_labels.insert(
+        TYPE_LABEL.to_string(),
+        EXPERIMENT_REPLICASET_TYPE.to_string(),
+    );
+    pod_metadata.labels = Some(pod_labels);
+    pod_template.metadata = Some(pod_metadata);
+
+    ReplicaSet {
+        metadata: ObjectMeta {
+            name: Some(experiment_replicaset_name(experiment, template)),
+            namespace: experiment.namespace(),
+            labels: Some(selector_labels.clone()),
+            owner_references: experiment
+                .controller_owner_ref(&())
+                .map(|owner| vec![owner]),
+            ..Default::default()
+        },
+        spec: Some(ReplicaSetSpec {
+            replicas: Some(template.replicas),
+            selector: LabelSelector {
+                match_labels: Some(selector_labels),
+                ..Default::default()
+            },
+            template: Some(pod_template),
+            ..Default::default()
+        }),
+        status: None,
+    }
+}
+
+/// What an unfinished Experiment does next
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExperimentProgress {
+    /// Record the status and look again after the delay
+    Wait(ExperimentStatus, Duration),
+    /// `duration` is over: evaluate the analyses
+    Analyze(ExperimentStatus),
+    /// The ReplicaSets didn't become ready in time; the status is `Failed`
+    TimedOut(ExperimentStatus),
+}
+
+fn parse_time(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Next step of an unfinished Experiment, given its ReplicaSets' readiness
+pub fn experiment_progress(
+    experiment: &Experiment,
+    templates: Vec<ExperimentTemplateStatus>,
+    now: DateTime<Utc>,
+) -> ExperimentProgress {
+    let current = experiment.status.clone().unwrap_or_default();
+    let ready = templates
+        .iter()
+        .filter(|t| t.ready_replicas >= t.replicas)
+        .count();
+    let total = templates.len();
+    let available_at = current
+        .available_at
+        .clone()
+        .or_else(|| (ready == total).then(|| now.to_rfc3339()));
+    let mut status = ExperimentStatus {
+        template_statuses: templates,
+        available_at,
+        ..current
+    };
+
+    let Some(available) = parse_time(status.available_at.as_deref()) else {
+        let created = experiment
+            .meta()
+            .creation_timestamp
+            .as_ref()
+            .map(|t| t.0)
+            .unwrap_or(now);
+        if (now - created).to_std().unwrap_or_default() >= EXPERIMENT_READY_TIMEOUT {
+            let message = format!(
+                "Only {} of {} ReplicaSets became ready within {}s",
+                ready,
+                total,
+                EXPERIMENT_READY_TIMEOUT.as_secs()
+            );
+            return ExperimentProgress::TimedOut(finish(
+                status,
+                ExperimentPhase::Failed,
+                message,
+                now,
+            ));
+        }
+        status.phase = Some(ExperimentPhase::Pending);
+        status.message = Some(format!("{} of {} ReplicaSets ready", ready, total));
+        return ExperimentProgress::Wait(status, EXPERIMENT_POLL_INTERVAL);
+    };
+
+    status.phase = Some(ExperimentPhase::Running);
+    let duration = parse_duration(&experiment.spec.duration).unwrap_or_default();
+    let elapsed = (now - available).to_std().unwrap_or_default();
+    if elapsed >= duration {
+        return ExperimentProgress::Analyze(status);
+    }
+    status.message = Some(format!(
+        "Running; analyses are evaluated {} after all ReplicaSets became ready",
+        experiment.spec.duration
+    ));
+    ExperimentProgress::Wait(status, (duration - elapsed).min(EXPERIMENT_POLL_INTERVAL))
+}
+
+fn finish(
+    status: ExperimentStatus,
+    phase: ExperimentPhase,
+    message: String,
+    now: DateTime<Utc>,
+) -> ExperimentStatus {
+    ExperimentStatus {
+        phase: Some(phase),
+        message: Some(message),
+        finished_at: Some(now.to_rfc3339()),
+        ..status
+    }
+}
+
+/// Final status from the analysis results: `Successful` if all passed
+pub fn analyzed_status(
+    status: ExperimentStatus,
+    results: Vec<ExperimentAnalysisResult>,
+    now: DateTime<Utc>,
+) -> ExperimentStatus {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.as_str())
+        .collect();
+    let (phase, message) = if failed.is_empty() {
+        (
+            ExperimentPhase::Successful,
+            format!("{} analyses passed", results.len()),
+        )
+    } else {
+        (
+            ExperimentPhase::Failed,
+            format!("Analyses failed: {}", failed.join(", ")),
+        )
+    };
+    ExperimentStatus {
+        analysis_results: results,
+        ..finish(status, phase, message, now)
+    }
+}
+
+/// Evaluate one analysis of the Experiment
+async fn evaluate_experiment_analysis(
+    experiment: &Experiment,
+    analysis: &ExperimentAnalysis,
+    ctx: &Context,
+) -> Result<bool, ReconcileError> {
+    let name = experiment.name_any();
+    let namespace = experiment.namespace().unwrap_or_default();
+    let rollout_name = experiment
+        .annotations()
+        .get(ROLLOUT_ANNOTATION)
+        .cloned()
+        .unwrap_or_else(|| name.clone());
+    let revision = RevisionSelector {
+        label: analysis
+            .analysis
+            .revision_label_key
+            .clone()
+            .unwrap_or_else(|| DEFAULT_REVISION_LABEL.to_string()),
+        value: name.clone(),
+        baseline: String::new(),
+        baseline_replicaset: String::new(),
+    };
+    let config = render_analysis_variables(&analysis.analysis, &rollout_name, &namespace, &name)?;
+    let querier = analysis_querier_for(
+        experiment.namespace(),
+        &name,
+        config.prometheus.as_ref(),
+        ctx,
+    )
+    .await?;
+    querier
+        .evaluate_analysis(&config, &rollout_name, &revision, "")
+        .await
+        .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))
+}
+
+/// Evaluate every analysis of the Experiment, in spec order
+async fn evaluate_experiment_analyses(
+    experiment: &Experiment,
+    ctx: &Context,
+) -> Vec<ExperimentAnalysisResult> {
+    let mut results = Vec::with_capacity(experiment.spec.analyses.len());
+    for analysis in &experiment.spec.analyses {
+        let result = evaluate_experiment_analysis(experiment, analysis, ctx).await;
+        if let Err(e) = &result {
+            warn!(
+                experiment = ?experiment.name_any(),
+                analysis = ?analysis.name,
+                error = ?e,
+                "Experiment analysis could not be evaluated"
+            );
+        }
+        results.push(ExperimentAnalysisResult {
+            name: analysis.name.clone(),
+            passed: result.as_ref().is_ok_and(|passed| *passed),
+            message: result.err().map(|e| e.to_string()),
+        });
+    }
+    results
+}
+
+/// Delete the Experiment's ReplicaSets (those already gone are skipped)
+async fn delete_experiment_replicasets(
+    experiment: &Experiment,
+    rs_api: &Api<ReplicaSet>,
+) -> Result<(), ReconcileError> {
+    for template in &experiment.spec.templates {
+        let rs_name = experiment_replicaset_name(experiment, template);
+        if rs_api.get_opt(&rs_name).await?.is_some() {
+            rs_api.delete(&rs_name, &DeleteParams::background()).await?;
+            info!(replicaset = ?rs_name, "Deleted ReplicaSet of finished Experiment");
+        }
+    }
+    Ok(())
+}
+
+async fn patch_experiment_status(
+    experiment: &Experiment,
+    ctx: &Context,
+    status: &ExperimentStatus,
+) -> Result<(), ReconcileError> {
+    let api: Api<Experiment> = Api::namespaced(
+        ctx.client.clone(),
+        &experiment.namespace().unwrap_or_default(),
+    );
+    let patch = serde_json::json!({ "status": status });
+    api.patch_status(
+        &experiment.name_any(),
+        &PatchParams::default(),
+        &Patch::Merge(&patch),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Reconcile an Experiment
+///
+/// Creates its ReplicaSets, tracks their readiness and the duration,
+/// evaluates the analyses and deletes the ReplicaSets once it's finished.
+/// An invalid spec fails the Experiment right away.
+pub async fn reconcile_experiment(
+    experiment: Arc<Experiment>,
+    ctx: Arc<Context>,
+) -> Result<Action, ReconcileError> {
+    if !ctx.should_reconcile() {
+        debug!(experiment = ?experiment.name_any(), "Skipping reconciliation - not leader");
+        return Ok(Action::requeue(Duration::from_secs(5)));
+    }
+    let owner = experiment.controller_owner_ref(&());
+    if let Some(retry_after) = ctx
+        .claim_object("Experiment", experiment.meta(), owner)
+        .await?
+    {
+        return Ok(Action::requeue(retry_after));
+    }
+    let namespace = experiment
+        .namespace()
+        .ok_or(ReconcileError::MissingNamespace)?;
+    let name = experiment.name_any();
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &namespace);
+
+    let phase = experiment.status.as_ref().and_then(|s| s.phase);
+    if phase.is_some_and(ExperimentPhase::is_finished) {
+        delete_experiment_replicasets(&experiment, &rs_api).await?;
+        return Ok(Action::await_change());
+    }
+
+    let now = ctx.clock.now();
+    if let Err(e) = validate_experiment(&experiment) {
+        warn!(experiment = ?name, error = %e, "Experiment spec validation failed");
+        let status = finish(
+            experiment.status.clone().unwrap_or_default(),
+            ExperimentPhase::Failed,
+            format!("Invalid Experiment spec: {}", e),
+            now,
+        );
+        patch_experiment_status(&experiment, &ctx, &status).await?;
+        return Ok(Action::await_change());
+    }
+
+    let mut templates = Vec::with_capacity(experiment.spec.templates.len());
+    for template in &experiment.spec.templates {
+        let rs_name = experiment_replicaset_name(&experiment, template);
+        let rs = match rs_api.get_opt(&rs_name).await? {
+            Some(rs) => rs,
+            None => {
+                let rs = build_experiment_replicaset(&experiment, template);
+                let created = rs_api.create(&PostParams::default(), &rs).await?;
+                info!(experiment = ?name, replicaset = ?rs_name, "Created experiment ReplicaSet");
+                created
+            }
+        };
+        templates.push(ExperimentTemplateStatus {
+            name: template.name.clone(),
+            replica_set: rs_name,
+            replicas: template.replicas,
+            ready_replicas: rs
+                .status
+                .as_ref()
+                .and_then(|s| s.ready_replicas)
+                .unwrap_or(0),
+        });
+    }
+
+    let (status, requeue) = match experiment_progress(&experiment, templates, now) {
+        ExperimentProgress::Wait(status, delay) => (status, Some(delay)),
+        ExperimentProgress::TimedOut(status) => (status, None),
+        ExperimentProgress::Analyze(status) => {
+            let results = evaluate_experiment_analyses(&experiment, &ctx).await;
+            (analyzed_status(status, results, now), None)
+        }
+    };
+
+    if experiment.status.as_ref() != Some(&status) {
+        patch_experiment_status(&experiment, &ctx, &status).await?;
+    }
+
+    match requeue {
+        Some(delay) => Ok(Action::requeue(delay)),
+        None => {
+            info!(experiment = ?name, phase = ?status.phase, message = ?status.message, "Experiment finished");
+            delete_experiment_replicasets(&experiment, &rs_api).await?;
+            Ok(Action::await_change())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    fn experiment() -> Experiment {
+        let mut experiment: Experiment = serde_json::from_value(serde_json::json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "Experiment",
+            "metadata": { "name": "shop-exp", "namespace": "prod", "uid": "uid-1" },
+            "spec": {
+                "duration": "10m",
+                "templates": [
+                    { "name": "baseline", "template": {
+                        "metadata": { "labels": { "app": "shop" } },
+                        "spec": { "containers": [{ "name": "app", "image": "shop:1" }] }
+                    } },
+                    { "name": "canary", "replicas": 2, "template": {
+                        "spec": { "containers": [{ "name": "app", "image": "shop:2" }] }
+                    } }
+                ]
+            }
+        }))
+        .unwrap();
+        experiment.metadata.creation_timestamp = Some(Time(at(0)));
+        experiment
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn readiness(baseline: i32, canary: i32) -> Vec<ExperimentTemplateStatus> {
+        vec![
+            ExperimentTemplateStatus {
+                name: "baseline".to_string(),
+                replica_set: "shop-exp-baseline".to_string(),
+                replicas: 1,
+                ready_replicas: baseline,
+            },
+            ExperimentTemplateStatus {
+                name: "canary".to_string(),
+                replica_set: "shop-exp-canary".to_string(),
+                replicas: 2,
+                ready_replicas: canary,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_experiment_replicaset_labels_and_owner() {
+        let experiment = experiment();
+        let rs = build_experiment_replicaset(&experiment, &experiment.spec.templates[0]);
+
+        assert_eq!(rs.metadata.name.as_deref(), Some("shop-exp-baseline"));
+        assert_eq!(rs.metadata.owner_references.unwrap()[0].kind, "Experiment");
+        let spec = rs.spec.unwrap();
+        assert_eq!(spec.replicas, Some(1));
+        let selector = spec.selector.match_labels.unwrap();
+        assert_eq!(selector[EXPERIMENT_LABEL], "shop-exp");
+        assert_eq!(selector[EXPERIMENT_TEMPLATE_LABEL], "baseline");
+        let pod_labels = spec.template.unwrap().metadata.unwrap().labels.unwrap();
+        assert_eq!(pod_labels["app"], "shop");
+        assert_eq!(pod_labels[TYPE_LABEL], EXPERIMENT_REPLICASET_TYPE);
+        assert!(!pod_labels.contains_key("rollouts.kulta.io/managed"));
+    }
+
+    #[test]
+    fn test_experiment_runs_duration_after_ready() {
+        let mut experiment = experiment();
+        let ExperimentProgress::Wait(pending, _) =
+            experiment_progress(&experiment, readiness(1, 1), at(30))
+        else {
+            panic!("expected Pending");
+        };
+        assert_eq!(pending.phase, Some(ExperimentPhase::Pending));
+        assert_eq!(pending.message.as_deref(), Some("1 of 2 ReplicaSets ready"));
+
+        let ExperimentProgress::Wait(running, _) =
+            experiment_progress(&experiment, readiness(1, 2), at(60))
+        else {
+            panic!("expected Running");
+        };
+        assert_eq!(running.phase, Some(ExperimentPhase::Running));
+        assert_eq!(running.available_at, Some(at(60).to_rfc3339()));
+
+        experiment.status = Some(running);
+        assert!(matches!(
+            experiment_progress(&experiment, readiness(1, 2), at(659)),
+            ExperimentProgress::Wait(..)
+        ));
+        assert!(matches!(
+            experiment_progress(&experiment, readiness(1, 1), at(660)),
+            ExperimentProgress::Analyze(_)
+        ));
+    }
+
+    #[test]
+    fn test_experiment_fails_when_replicasets_never_ready() {
+        let experiment = experiment();
+        let ExperimentProgress::TimedOut(status) =
+            experiment_progress(&experiment, readiness(0, 2), at(600))
+        else {
+            panic!("expected a timeout");
+        };
+        assert_eq!(status.phase, Some(ExperimentPhase::Failed));
+        assert_eq!(status.finished_at, Some(at(600).to_rfc3339()));
+    }
+
+    #[test]
+    fn test_analyzed_status() {
+        let result = |name: &str, passed| ExperimentAnalysisResult {
+            name: name.to_string(),
+            passed,
+            message: None,
+        };
+        let passed = analyzed_status(
+            ExperimentStatus::default(),
+            vec![result("errors", true), result("latency", true)],
+            at(0),
+        );
+        assert_eq!(passed.phase, Some(ExperimentPhase::Successful));
+
+        let failed = analyzed_status(
+            ExperimentStatus::default(),
+            vec![result("errors", true), result("latency", false)],
+            at(0),
+        );
+        assert_eq!(failed.phase, Some(ExperimentPhase::Failed));
+        assert_eq!(failed.message.as_deref(), Some("Analyses failed: latency"));
+        assert_eq!(failed.analysis_results.len(), 2);
+    }
+}
@@ -0,0 +1,682 @@
+//! Deployment freeze calendar
+//!
+//! Release management often declares freeze windows (holidays, quarter end)
+//! during which nothing new ships. The controller reads them from a ConfigMap
+//! and/or an iCal feed and, while a window is active:
+//!
+//! - refuses to start new Rollouts or new revisions (ReplicaSets are left alone)
+//! - holds Rollouts already underway at their current step
+//!
+//! Rollbacks are never blocked. The active window is shown in
+//! `status.activeFreeze`, and a Rollout annotated `kulta.io/ignore-freeze=true`
+//! is exempt (break-glass for hotfixes).
+//!
+//! Configured via:
+//! - `KULTA_FREEZE_CONFIGMAP`: `namespace/name` of a ConfigMap whose entries
+//!   are `name: <start>/<end>` (RFC3339), or iCal text under a key ending in `.ics`
+//! - `KULTA_FREEZE_ICAL_URL`: iCal feed; each VEVENT is a window
+//!
+//! Windows are cached for a minute. When a source can't be read the last
+//! known windows are kept; with nothing loaded yet, rollouts are not frozen.
+
+use crate::controller::strategy_math::is_advancing;
+use crate::crd::rollout::{ActiveFreeze, Phase, Rollout, RolloutStatus};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::Api;
+use kube::{Client, ResourceExt};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Annotation that exempts a Rollout from freezes
+pub const IGNORE_FREEZE_ANNOTATION: &str = "kulta.io/ignore-freeze";
+
+/// How long loaded windows are reused before the sources are read again
+const FREEZE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Timeout for fetching the iCal feed
+const ICAL_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FreezeError {
+    #[error("Invalid freeze ConfigMap reference '{0}': expected namespace/name")]
+    InvalidConfigMapRef(String),
+
+    #[error("Invalid freeze window '{name}': {reason}")]
+    InvalidWindow { name: String, reason: String },
+}
+
+/// A period during which rollouts may not start or advance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FreezeWindow {
+    pub name: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl FreezeWindow {
+    /// Whether `now` falls inside the window (end exclusive)
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+/// The window in effect at `now`; overlapping windows resolve to the one ending last
+pub fn active_window(windows: &[FreezeWindow], now: DateTime<Utc>) -> Option<&FreezeWindow> {
+    windows
+        .iter()
+        .filter(|w| w.contains(now))
+        .max_by_key(|w| w.end)
+}
+
+/// Parse a `<start>/<end>` RFC3339 interval
+pub fn parse_window(name: &str, value: &str) -> Result<FreezeWindow, FreezeError> {
+    let invalid = |reason: String| FreezeError::InvalidWindow {
+        name: name.to_string(),
+        reason,
+    };
+
+    let (start, end) = value
+        .trim()
+        .split_once('/')
+        .ok_or_else(|| invalid("expected <start>/<end>".to_string()))?;
+    let parse = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts.trim())
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| invalid(format!("'{}': {}", ts.trim(), e)))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if end <= start {
+        return Err(invalid("end must be after start".to_string()));
+    }
+
+    Ok(FreezeWindow {
+        name: name.to_string(),
+        start,
+        end,
+    })
+}
+
+/// Read freeze windows from ConfigMap data
+///
+/// Keys ending in `.ics` hold iCal text; every other entry is a single
+/// `<start>/<end>` window named after its key. Invalid entries are logged and
+/// skipped so that one typo does not drop the whole calendar.
+pub fn parse_configmap_windows(data: &BTreeMap<String, String>) -> Vec<FreezeWindow> {
+    let mut windows = Vec::new();
+    for (key, value) in data {
+        if key.ends_with(".ics") {
+            windows.extend(parse_ical(value));
+            continue;
+        }
+        match parse_window(key, value) {
+            Ok(window) => windows.push(window),
+            Err(e) => warn!(error = %e, "Ignoring invalid freeze window"),
+        }
+    }
+    windows
+}
+
+/// Read freeze windows from iCal text (one per VEVENT)
+///
+/// Supports UTC date-times, floating date-times (read as UTC) and all-day
+/// dates. `TZID` parameters and recurrence rules are not interpreted: times
+/// are read as UTC and only the first occurrence counts.
+pub fn parse_ical(text: &str) -> Vec<FreezeWindow> {
+    // Unfold continuation lines (RFC 5545 section 3.1)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        let line = raw.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut windows = Vec::new();
+    let mut event: Option<BTreeMap<String, String>> = None;
+    for line in &lines {
+        match line.as_str() {
+            "BEGIN:VEVENT" => event = Some(BTreeMap::new()),
+            "END:VEVENT" => {
+                if let Some(props) = event.take() {
+                    match ical_event_window(&props) {
+                        Ok(window) => windows.push(window),
+                        Err(e) => warn!(error = %e, "Ignoring invalid iCal freeze event"),
+                    }
+                }
+            }
+            _ => {
+                let (Some(props), Some((name, value))) = (event.as_mut(), line.split_once(':'))
+                else {
+                    continue;
+                };
+                // Drop parameters (e.g., DTSTART;VALUE=DATE)
+                let name = name.split(';').next().unwrap_or_default();
+                props.insert(name.to_ascii_uppercase(), value.trim().to_string());
+            }
+        }
+    }
+    windows
+}
+
+fn ical_event_window(props: &BTreeMap<String, String>) -> Result<FreezeWindow, FreezeError> {
+    let name = props
+        .get("SUMMARY")
+        .or_else(|| props.get("UID"))
+        .cloned()
+        .unwrap_or_else(|| "unnamed freeze".to_string());
+    let invalid = |reason: String| FreezeError::InvalidWindow {
+        name: name.clone(),
+        reason,
+    };
+
+    let dtstart = props
+        .get("DTSTART")
+        .ok_or_else(|| invalid("missing DTSTART".to_string()))?;
+    let (start, all_day) = parse_ical_time(dtstart)
+        .ok_or_else(|| invalid(format!("invalid DTSTART '{}'", dtstart)))?;
+    let end = match props.get("DTEND") {
+        Some(dtend) => {
+            parse_ical_time(dtend)
+                .ok_or_else(|| invalid(format!("invalid DTEND '{}'", dtend)))?
+                .0
+        }
+        // An all-day event without DTEND lasts one day
+        None if all_day => start + chrono::Duration::days(1),
+        None => return Err(invalid("missing DTEND".to_string())),
+    };
+    if end <= start {
+        return Err(invalid("DTEND must be after DTSTART".to_string()));
+    }
+
+    Ok(FreezeWindow { name, start, end })
+}
+
+/// Parse an iCal DATE or DATE-TIME value, returning whether it was a DATE
+fn parse_ical_time(value: &str) -> Option<(DateTime<Utc>, bool)> {
+    let value = value.trim_end_matches('Z');
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((dt.and_utc(), false));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    Some((date.and_hms_opt(0, 0, 0)?.and_utc(), true))
+}
+
+struct CachedWindows {
+    loaded_at: Instant,
+    windows: Vec<FreezeWindow>,
+}
+
+/// Freeze calendar sources and the cached windows
+#[derive(Default)]
+pub struct FreezeCalendar {
+    configmap: Option<(String, String)>,
+    ical_url: Option<String>,
+    http: Option<reqwest::Client>,
+    cache: Mutex<Option<CachedWindows>>,
+}
+
+impl FreezeCalendar {
+    /// Calendar without sources (never frozen)
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create calendar from environment variables
+    ///
+    /// Uses:
+    /// - `KULTA_FREEZE_CONFIGMAP` (`namespace/name`)
+    /// - `KULTA_FREEZE_ICAL_URL`
+    pub fn from_env() -> Self {
+        let non_empty = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+        let configmap = non_empty("KULTA_FREEZE_CONFIGMAP").and_then(|value| {
+            match parse_configmap_ref(&value) {
+                Ok(reference) => Some(reference),
+                Err(e) => {
+                    warn!(error = %e, "Ignoring KULTA_FREEZE_CONFIGMAP");
+                    None
+                }
+            }
+        });
+        Self::new(configmap, non_empty("KULTA_FREEZE_ICAL_URL"))
+    }
+
+    pub fn new(configmap: Option<(String, String)>, ical_url: Option<String>) -> Self {
+        let http = ical_url.as_ref().and_then(|_| {
+            reqwest::Client::builder()
+                .timeout(ICAL_FETCH_TIMEOUT)
+                .build()
+                .map_err(|e| warn!(error = %e, "Failed to build iCal HTTP client"))
+                .ok()
+        });
+        FreezeCalendar {
+            configmap,
+            ical_url,
+            http,
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// Calendar with fixed windows (no sources to refresh from)
+    pub fn with_windows(windows: Vec<FreezeWindow>) -> Self {
+        FreezeCalendar {
+            cache: Mutex::new(Some(CachedWindows {
+                loaded_at: Instant::now(),
+                windows,
+            })),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.configmap.is_some() || self.ical_url.is_some() || self.cached().is_some()
+    }
+
+    fn cached(&self) -> Option<Vec<FreezeWindow>> {
+        match self.cache.lock() {
+            Ok(cache) => cache.as_ref().map(|c| c.windows.clone()),
+            Err(_) => None,
+        }
+    }
+
+    /// Current freeze windows, refreshed from the sources at most once a minute
+    pub async fn windows(&self, client: &Client) -> Vec<FreezeWindow> {
+        let has_sources = self.configmap.is_some() || self.ical_url.is_some();
+        if let Ok(cache) = self.cache.lock() {
+            if let Some(cached) = cache.as_ref() {
+                if !has_sources || cached.loaded_at.elapsed() < FREEZE_CACHE_TTL {
+                    return cached.windows.clone();
+                }
+            }
+        }
+        if !has_sources {
+            return Vec::new();
+        }
+
+        match self.load(client).await {
+            Some(windows) => {
+                if let Ok(mut cache) = self.cache.lock() {
+                    *cache = Some(CachedWindows {
+                        loaded_at: Instant::now(),
+                        windows: windows.clone(),
+                    });
+                }
+                windows
+            }
+            None => self.cached().unwrap_or_default(),
+        }
+    }
+
+    /// Read all sources; `None` if any of them failed
+    async fn load(&self, client: &Client) -> Option<Vec<FreezeWindow>> {
+        let mut windows = Vec::new();
+
+        if let Some((namespace, name)) = &self.configmap {
+            let api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
+            match api.get_opt(name).await {
+                Ok(Some(cm)) => {
+                    windows.extend(parse_configmap_windows(&cm.data.unwrap_or_default()))
+                }
+                Ok(None) => {
+                    warn!(configmap = %name, namespace = %namespace, "Freeze ConfigMap not found")
+                }
+                Err(e) => {
+                    warn!(error = ?e, configmap = %name, "Failed to read freeze ConfigMap");
+                    return None;
+                }
+            }
+        }
+
+        if let (Some(url), Some(http)) = (&self.ical_url, &self.http) {
+            let response = http
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            let text = match response {
+                Ok(response) => response.text().await,
+                Err(e) => Err(e),
+            };
+            match text {
+                Ok(text) => windows.extend(parse_ical(&text)),
+                Err(e) => {
+                    warn!(error = %e, "Failed to fetch freeze iCal feed");
+                    return None;
+                }
+            }
+        }
+
+        Some(windows)
+    }
+
+    /// The freeze affecting a Rollout at `now`, if any
+    ///
+    /// Rollouts annotated `kulta.io/ignore-freeze=true` are never frozen.
+    pub async fn active_freeze(
+        &self,
+        rollout: &Rollout,
+        client: &Client,
+        now: DateTime<Utc>,
+    ) -> Option<FreezeWindow> {
+        if !self.is_enabled() || ignores_freeze(rollout) {
+            return None;
+        }
+        let windows = self.windows(client).await;
+        active_window(&windows, now).cloned()
+    }
+}
+
+fn parse_configmap_ref(value: &str) -> Result<(String, String), FreezeError> {
+    match value.trim().split_once('/') {
+        Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {
+            Ok((namespace.to_string(), name.to_string()))
+        }
+        _ => Err(FreezeError::InvalidConfigMapRef(value.to_string())),
+    }
+}
+
+fn ignores_freeze(rollout: &Rollout) -> bool {
+    rollout
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(IGNORE_FREEZE_ANNOTATION))
+        .is_some_and(|value| value == "true")
+}
+
+impl From<&FreezeWindow> for ActiveFreeze {
+    fn from(window: &FreezeWindow) -> Self {
+        ActiveFreeze {
+            name: window.name.clone(),
+            until: window.end.to_rfc3339(),
+        }
+    }
+}
+
+/// Whether the reconcile would start a new Rollout or a new revision
+///
+/// New means no status yet, still initializing, or a pod template hash that
+/// differs from the recorded one.
+pub fn starts_new_revision(rollout: &Rollout, pod_template_hash: &str) -> bool {
+    match &rollout.status {
+        None => true,
+        Some(status) => {
+            matches!(
+                status.phase,
+                None | Some(Phase::Initializing) | Some(Phase::Pending)
+            ) || status
+                .pod_template_hash
+                .as_deref()
+                .is_some_and(|recorded| recorded != pod_template_hash)
+        }
+    }
+}
+
+/// Status for a Rollout whose start is refused by a freeze
+///
+/// Keeps the current status (or an `Initializing` one) and records the window.
+pub fn frozen_start_status(rollout: &Rollout, window: &FreezeWindow) -> RolloutStatus {
+    let current = rollout.status.clone().unwrap_or_else(|| RolloutStatus {
+        phase: Some(Phase::Initializing),
+        ..Default::default()
+    });
+    RolloutStatus {
+        message: Some(format!(
+            "Frozen: deployment freeze '{}' until {}, new revision not started",
+            window.name,
+            window.end.to_rfc3339()
+        )),
+        active_freeze: Some(window.into()),
+        ..current
+    }
+}
+
+/// Apply a freeze to the desired status of a Rollout already underway
+///
+/// Records the active window (or clears it) and, while frozen, holds any
+/// move to the next step or to completion. Failures and rollbacks pass.
+/// When a freeze ends, the progress deadline restarts so the time spent
+/// frozen doesn't count against it.
+pub(crate) fn apply_freeze(
+    rollout: &Rollout,
+    desired_status: RolloutStatus,
+    freeze: Option<&FreezeWindow>,
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let Some(window) = freeze else {
+        let freeze_ended = rollout
+            .status
+            .as_ref()
+            .is_some_and(|s| s.active_freeze.is_some());
+        let progress_started_at = match desired_status.progress_started_at {
+            Some(_) if freeze_ended => Some(now.to_rfc3339()),
+            other => other,
+        };
+        return RolloutStatus {
+            active_freeze: None,
+            progress_started_at,
+            ..desired_status
+        };
+    };
+
+    if let Some(current) = rollout.status.as_ref() {
+        if is_advancing(current, &desired_status) {
+            info!(
+                rollout = ?rollout.name_any(),
+                freeze = %window.name,
+                "Holding rollout: deployment freeze active"
+            );
+            return RolloutStatus {
+                message: Some(format!(
+                    "Frozen: deployment freeze '{}' until {}",
+                    window.name,
+                    window.end.to_rfc3339()
+                )),
+                active_freeze: Some(window.into()),
+                ..current.clone()
+            };
+        }
+    }
+
+    RolloutStatus {
+        active_freeze: Some(window.into()),
+        ..desired_status
+    }
+}
+
+/// How long to wait before checking a frozen Rollout again
+pub fn freeze_requeue_interval(window: &FreezeWindow, now: DateTime<Utc>) -> Duration {
+    const MIN_REQUEUE: Duration = Duration::from_secs(5);
+    const MAX_REQUEUE: Duration = Duration::from_secs(300);
+
+    let remaining = (window.end - now).to_std().unwrap_or_default();
+    remaining.clamp(MIN_REQUEUE, MAX_REQUEUE)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::SimpleStrategy;
+
+    fn ts(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn test_rollout() -> Rollout {
+        RolloutBuilder::new("app")
+            .namespace("default")
+            .replicas(3)
+            .label("app", "app")
+            .image("app", "app:2")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap()
+    }
+
+    fn holiday() -> FreezeWindow {
+        parse_window("holidays", "2026-12-20T00:00:00Z/2027-01-03T00:00:00Z").unwrap()
+    }
+
+    #[test]
+    fn test_parse_window() {
+        let window = holiday();
+        assert!(window.contains(ts("2026-12-24T12:00:00Z")));
+        assert!(!window.contains(ts("2027-01-03T00:00:00Z")));
+
+        assert!(parse_window("bad", "2026-12-20T00:00:00Z").is_err());
+        assert!(parse_window("backwards", "2027-01-03T00:00:00Z/2026-12-20T00:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_parse_configmap_windows_skips_invalid_entries() {
+        let data = BTreeMap::from([
+            (
+                "holidays".to_string(),
+                "2026-12-20T00:00:00Z/2027-01-03T00:00:00Z".to_string(),
+            ),
+            ("typo".to_string(), "next tuesday".to_string()),
+            (
+                "quarter-end.ics".to_string(),
+                "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:Q4 close\nDTSTART;VALUE=DATE:20261230\nEND:VEVENT\nEND:VCALENDAR\n"
+                    .to_string(),
+            ),
+        ]);
+
+        let windows = parse_configmap_windows(&data);
+
+        let names: Vec<&str> = windows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["holidays", "Q4 close"]);
+        assert_eq!(windows[1].end, ts("2026-12-31T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_parse_ical_events() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:Black Fri\r\n \
+                   day freeze\r\n\
+                   DTSTART:20261127T060000Z\r\n\
+                   DTEND:20261130T060000Z\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VEVENT\r\n\
+                   SUMMARY:No end\r\n\
+                   DTSTART:20261201T000000Z\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+        let windows = parse_ical(ics);
+
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].name, "Black Friday freeze");
+        assert_eq!(windows[0].start, ts("2026-11-27T06:00:00Z"));
+        assert_eq!(windows[0].end, ts("2026-11-30T06:00:00Z"));
+    }
+
+    #[test]
+    fn test_active_window_prefers_latest_end() {
+        let short = parse_window("short", "2026-12-20T00:00:00Z/2026-12-21T00:00:00Z").unwrap();
+        let windows = vec![short, holiday()];
+
+        assert_eq!(
+            active_window(&windows, ts("2026-12-20T12:00:00Z")).map(|w| w.name.as_str()),
+            Some("holidays")
+        );
+        assert_eq!(active_window(&windows, ts("2027-02-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_apply_freeze_holds_only_progression() {
+        let mut rollout = test_rollout();
+        let current = RolloutStatus {
+            phase: Some(Phase::Progressing),
+            current_step_index: Some(0),
+            current_weight: Some(20),
+            ..Default::default()
+        };
+        rollout.status = Some(current.clone());
+        let window = holiday();
+
+        let advancing = RolloutStatus {
+            current_step_index: Some(1),
+            current_weight: Some(50),
+            ..current.clone()
+        };
+        let held = apply_freeze(&rollout, advancing.clone(), Some(&window), Utc::now());
+        assert_eq!(held.current_step_index, Some(0));
+        assert_eq!(held.active_freeze.unwrap().name, "holidays");
+
+        let failed = RolloutStatus {
+            phase: Some(Phase::Failed),
+            ..current.clone()
+        };
+        let status = apply_freeze(&rollout, failed, Some(&window), Utc::now());
+        assert_eq!(
+            status.phase,
+            Some(Phase::Failed),
+            "rollback is never frozen"
+        );
+
+        let status = apply_freeze(&rollout, advancing, None, Utc::now());
+        assert_eq!(status.current_step_index, Some(1));
+        assert_eq!(status.active_freeze, None);
+    }
+
+    #[test]
+    fn test_apply_freeze_restarts_progress_deadline_when_freeze_ends() {
+        let mut rollout = test_rollout();
+        let current = RolloutStatus {
+            phase: Some(Phase::Progressing),
+            current_step_index: Some(0),
+            progress_started_at: Some("2026-12-19T00:00:00+00:00".to_string()),
+            active_freeze: Some((&holiday()).into()),
+            ..Default::default()
+        };
+        rollout.status = Some(current.clone());
+        let now = ts("2027-01-03T00:01:00Z");
+
+        let status = apply_freeze(&rollout, current, None, now);
+
+        assert_eq!(status.active_freeze, None);
+        assert_eq!(status.progress_started_at, Some(now.to_rfc3339()));
+    }
+
+    #[test]
+    fn test_starts_new_revision() {
+        let mut rollout = test_rollout();
+        assert!(starts_new_revision(&rollout, "abc"));
+
+        let window = holiday();
+        rollout.status = Some(frozen_start_status(&rollout, &window));
+        assert!(starts_new_revision(&rollout, "abc"), "still initializing");
+
+        rollout.status = Some(RolloutStatus {
+            phase: Some(Phase::Completed),
+            pod_template_hash: Some("abc".to_string()),
+            ..Default::default()
+        });
+        assert!(!starts_new_revision(&rollout, "abc"));
+        assert!(starts_new_revision(&rollout, "def"));
+    }
+
+    #[test]
+    fn test_freeze_requeue_interval() {
+        let window = holiday();
+        assert_eq!(
+            freeze_requeue_interval(&window, ts("2027-01-02T23:59:00Z")),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            freeze_requeue_interval(&window, ts("2026-12-20T00:00:00Z")),
+            Duration::from_secs(300)
+        );
+    }
+}
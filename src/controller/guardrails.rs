@@ -0,0 +1,604 @@
+//! Guardrail policy for advisor-driven and manual actions
+//!
+//! At the Planned and Driven levels an external advisor steers rollouts, and
+//! `kulta.io/set-weight` lets anyone who can annotate a Rollout move its
+//! traffic, so platform teams need limits that no Rollout spec can lift. The
+//! policy is read from the controller environment:
+//!
+//! - `KULTA_ADVISOR_MAX_WEIGHT_INCREASE`: largest weight increase a single
+//!   recommendation, or one step of an advisor plan, may make
+//! - `KULTA_ADVISOR_MIN_PAUSE`: shortest timed pause an advisor plan may set
+//! - `KULTA_ADVISOR_FORBIDDEN_ACTIONS`: recommendations never accepted
+//!   (comma-separated `continue`, `pause`, `rollback`, `advance`, `plan`)
+//! - `KULTA_ADVISOR_DRIVEN_DENIED_NAMESPACES`: namespaces where the Driven
+//!   level is lowered to Advised
+//! - `KULTA_MAX_MANUAL_WEIGHT`: highest canary weight `kulta.io/set-weight`
+//!   may set (drains to a lower weight are always allowed)
+//!
+//! Regardless of configuration, an advisor may never skip analysis: a
+//! recommendation to keep going or advance while the thresholds are failing
+//! is always rejected, and so is a plan leaving out a gated step (analysis,
+//! an experiment, an alert gate or a pause that isn't timed). Every recommendation passes
+//! [`GuardrailPolicy::check`], and every plan [`GuardrailPolicy::check_plan`],
+//! before reconcile acts on it; a Driven-level advisor's recommendation also
+//! has to pass [`GuardrailPolicy::check_drive`] before it moves the rollout.
+
+use crate::controller::rollout::parse_duration;
+use crate::crd::rollout::{
+    AdvisorLevel, CanaryStep, Pause, PlannedStep, Recommendation, RecommendedAction,
+};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::warn;
+
+/// Why a recommendation was rejected
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GuardrailViolation {
+    #[error("advisor action '{0}' is forbidden by the guardrail policy")]
+    ForbiddenAction(&'static str),
+
+    #[error("advisor would raise the weight by {increase} (policy allows at most {max})")]
+    WeightIncreaseTooLarge { increase: u32, max: u32 },
+
+    #[error("advisor would skip analysis: '{0}' while metrics breach their thresholds")]
+    SkipsAnalysis(&'static str),
+
+    #[error("manual weight {weight} exceeds the policy maximum of {max}")]
+    ManualWeightTooHigh { weight: i32, max: u32 },
+
+    #[error("advisor plan is invalid: {0}")]
+    InvalidPlan(String),
+
+    #[error("advisor would skip step {0}, which is gated (analysis, experiment, alert gate or manual pause)")]
+    SkipsGate(i32),
+
+    #[error("advisor plan pauses step {step} for less than the policy minimum of {min:?}")]
+    PauseTooShort { step: i32, min: Duration },
+}
+
+/// Name of a recommended action, as used in `KULTA_ADVISOR_FORBIDDEN_ACTIONS`
+pub fn action_name(action: &RecommendedAction) -> &'static str {
+    match action {
+        RecommendedAction::Continue => "continue",
+        RecommendedAction::Pause => "pause",
+        RecommendedAction::Rollback => "rollback",
+        RecommendedAction::Advance { .. } => "advance",
+    }
+}
+
+/// Controller-wide limits on advisor recommendations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GuardrailPolicy {
+    /// Largest weight increase per recommendation (unlimited when unset)
+    pub max_weight_increase: Option<u32>,
+    /// Action names that are always rejected
+    pub forbidden_actions: Vec<String>,
+    /// Namespaces where the Driven level falls back to Advised
+    pub driven_denied_namespaces: Vec<String>,
+    /// Highest canary weight a manual override may set (unlimited when unset)
+    pub max_manual_weight: Option<u32>,
+    /// Shortest timed pause of an advisor plan (any when unset)
+    pub min_pause: Option<Duration>,
+}
+
+impl GuardrailPolicy {
+    /// Create policy from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create policy from an arbitrary key lookup (testable without touching env)
+    ///
+    /// Invalid values are logged and ignored.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let list = |key: &str| -> Vec<String> {
+            lookup(key)
+                .map(|v| {
+                    v.split(',')
+                        .map(|item| item.trim().to_ascii_lowercase())
+                        .filter(|item| !item.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let number = |key: &str| -> Option<u32> {
+            lookup(key).and_then(|v| {
+                v.trim()
+                    .parse()
+                    .map_err(|_| warn!(value = %v, key, "Ignoring invalid guardrail limit"))
+                    .ok()
+            })
+        };
+        let max_weight_increase = number("KULTA_ADVISOR_MAX_WEIGHT_INCREASE");
+
+        let min_pause = lookup("KULTA_ADVISOR_MIN_PAUSE").and_then(|v| {
+            parse_duration(&v).or_else(|| {
+                warn!(value = %v, "Ignoring invalid KULTA_ADVISOR_MIN_PAUSE");
+                None
+            })
+        });
+
+        const ACTIONS: [&str; 5] = ["continue", "pause", "rollback", "advance", "plan"];
+        let forbidden_actions = list("KULTA_ADVISOR_FORBIDDEN_ACTIONS")
+            .into_iter()
+            .filter(|action| {
+                let known = ACTIONS.contains(&action.as_str());
+                if !known {
+                    warn!(
+                        action = %action,
+                        "Ignoring unknown action in KULTA_ADVISOR_FORBIDDEN_ACTIONS"
+                    );
+                }
+                known
+            })
+            .collect();
+
+        Self {
+            max_weight_increase,
+            forbidden_actions,
+            driven_denied_namespaces: list("KULTA_ADVISOR_DRIVEN_DENIED_NAMESPACES"),
+            max_manual_weight: number("KULTA_MAX_MANUAL_WEIGHT"),
+            min_pause,
+        }
+    }
+
+    /// Advisor level a Rollout actually runs at in a namespace
+    pub fn effective_level(&self, level: &AdvisorLevel, namespace: &str) -> AdvisorLevel {
+        if *level == AdvisorLevel::Driven
+            && self
+                .driven_denied_namespaces
+                .iter()
+                .any(|denied| denied == namespace)
+        {
+            return AdvisorLevel::Advised;
+        }
+        level.clone()
+    }
+
+    /// Check a recommendation against the policy
+    ///
+    /// # Arguments
+    /// * `recommendation` - What the advisor proposes
+    /// * `current_weight` - Canary weight before the recommendation
+    /// * `metrics_healthy` - Threshold analysis result
+    pub fn check(
+        &self,
+        recommendation: &Recommendation,
+        current_weight: i32,
+        metrics_healthy: bool,
+    ) -> Result<(), GuardrailViolation> {
+        let name = action_name(&recommendation.action);
+        if self.forbidden_actions.iter().any(|action| action == name) {
+            return Err(GuardrailViolation::ForbiddenAction(name));
+        }
+
+        match recommendation.action {
+            RecommendedAction::Continue | RecommendedAction::Advance { .. } if !metrics_healthy => {
+                Err(GuardrailViolation::SkipsAnalysis(name))
+            }
+            RecommendedAction::Advance { to_weight } => {
+                let increase = to_weight.saturating_sub(current_weight.max(0) as u32);
+                match self.max_weight_increase {
+                    Some(max) if increase > max => {
+                        Err(GuardrailViolation::WeightIncreaseTooLarge { increase, max })
+                    }
+                    _ => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check an advisor plan against the policy
+    ///
+    /// A plan may only run the Rollout's own steps after the current one, in
+    /// order, changing their weight or timed pause.
+    ///
+    /// # Arguments
+    /// * `steps` - Canary steps of the Rollout
+    /// * `from_step` - Step the rollout is at
+    /// * `current_weight` - Canary weight at that step
+    /// * `plan` - Steps the advisor wants to run next
+    /// * `metrics_healthy` - Threshold analysis result
+    pub fn check_plan(
+        &self,
+        steps: &[CanaryStep],
+        from_step: i32,
+        current_weight: i32,
+        plan: &[PlannedStep],
+        metrics_healthy: bool,
+    ) -> Result<(), GuardrailViolation> {
+        if self.forbidden_actions.iter().any(|action| action == "plan") {
+            return Err(GuardrailViolation::ForbiddenAction("plan"));
+        }
+        if !metrics_healthy {
+            return Err(GuardrailViolation::SkipsAnalysis("plan"));
+        }
+
+        let last = i32::try_from(steps.len()).unwrap_or(i32::MAX) - 1;
+        let step_at = |index: i32| usize::try_from(index).ok().and_then(|i| steps.get(i));
+        let mut previous = from_step;
+        let mut weight = current_weight;
+        for planned in plan {
+            let step = step_at(planned.step)
+                .filter(|_| planned.step > previous)
+                .ok_or_else(|| {
+                    GuardrailViolation::InvalidPlan(format!(
+                        "step {} is not a step after {} (last step is {})",
+                        planned.step, previous, last
+                    ))
+                })?;
+            for skipped in previous + 1..planned.step {
+                check_skippable(step_at(skipped), skipped)?;
+            }
+
+            let next_weight = planned.set_weight.or(step.set_weight).unwrap_or(0);
+            if !(0..=100).contains(&next_weight) {
+                return Err(GuardrailViolation::InvalidPlan(format!(
+                    "weight {} of step {} is not between 0 and 100",
+                    next_weight, planned.step
+                )));
+            }
+            self.check_weight_increase(weight, next_weight)?;
+            if let Some(pause) = &planned.pause_duration {
+                self.check_planned_pause(step, planned.step, pause)?;
+            }
+            previous = planned.step;
+            weight = next_weight;
+        }
+        // Leaving out the last step jumps to 100% when the plan runs out
+        if previous < last {
+            for skipped in previous + 1..=last {
+                check_skippable(step_at(skipped), skipped)?;
+            }
+            self.check_weight_increase(weight, 100)?;
+        }
+        Ok(())
+    }
+
+    /// Check a recommendation a Driven-level advisor would act on
+    ///
+    /// Beyond [`check`](Self::check): advancing ends the current step early,
+    /// so that step may not be gated, and the next step's weight has to stay
+    /// within the weight increase limit.
+    ///
+    /// # Arguments
+    /// * `steps` - Canary steps of the Rollout
+    /// * `current_step` - Step the rollout is at
+    /// * `current_weight` - Canary weight at that step
+    pub fn check_drive(
+        &self,
+        recommendation: &Recommendation,
+        steps: &[CanaryStep],
+        current_step: i32,
+        current_weight: i32,
+        metrics_healthy: bool,
+    ) -> Result<(), GuardrailViolation> {
+        self.check(recommendation, current_weight, metrics_healthy)?;
+        if let RecommendedAction::Advance { .. } = recommendation.action {
+            let step_at = |index: i32| usize::try_from(index).ok().and_then(|i| steps.get(i));
+            check_skippable(step_at(current_step), current_step)?;
+            // Past the last step the canary gets all traffic
+            let next_weight =
+                step_at(current_step + 1).map_or(100, |step| step.set_weight.unwrap_or(0));
+            self.check_weight_increase(current_weight, next_weight)?;
+        }
+        Ok(())
+    }
+
+    fn check_weight_increase(&self, from: i32, to: i32) -> Result<(), GuardrailViolation> {
+        let increase = to.saturating_sub(from.max(0)).max(0) as u32;
+        match self.max_weight_increase {
+            Some(max) if increase > max => {
+                Err(GuardrailViolation::WeightIncreaseTooLarge { increase, max })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_planned_pause(
+        &self,
+        step: &CanaryStep,
+        index: i32,
+        pause: &str,
+    ) -> Result<(), GuardrailViolation> {
+        // Only timed pauses are the advisor's to change
+        if step
+            .pause
+            .as_ref()
+            .is_some_and(|current| !matches!(current, Pause::Duration(_)))
+        {
+            return Err(GuardrailViolation::InvalidPlan(format!(
+                "step {} pauses until a time or a person resumes it",
+                index
+            )));
+        }
+        let duration = parse_duration(pause).ok_or_else(|| {
+            GuardrailViolation::InvalidPlan(format!(
+                "pause '{}' of step {} is not a duration",
+                pause, index
+            ))
+        })?;
+        match self.min_pause {
+            Some(min) if duration < min => {
+                Err(GuardrailViolation::PauseTooShort { step: index, min })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a manual weight override (`kulta.io/set-weight`) against the policy
+    ///
+    /// Lowering the weight is always allowed, so traffic can be drained from
+    /// a canary that is already above the limit.
+    pub fn check_manual_weight(
+        &self,
+        weight: i32,
+        current_weight: i32,
+    ) -> Result<(), GuardrailViolation> {
+        match self.max_manual_weight {
+            Some(max) if weight > current_weight && weight > max as i32 => {
+                Err(GuardrailViolation::ManualWeightTooHigh { weight, max })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A step a plan may leave out: no analysis, experiment, alert gate or pause
+/// other than a timed one
+fn check_skippable(step: Option<&CanaryStep>, index: i32) -> Result<(), GuardrailViolation> {
+    let gated = step.is_some_and(|step| {
+        step.analysis.is_some()
+            || step.experiment.is_some()
+            || step.alert_gate.is_some()
+            || step
+                .pause
+                .as_ref()
+                .is_some_and(|pause| !matches!(pause, Pause::Duration(_)))
+    });
+    if gated {
+        return Err(GuardrailViolation::SkipsGate(index));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn recommend(action: RecommendedAction) -> Recommendation {
+        Recommendation {
+            action,
+            confidence: 0.9,
+            reasoning: "test".into(),
+            plan: None,
+        }
+    }
+
+    fn policy() -> GuardrailPolicy {
+        GuardrailPolicy::from_lookup(|key| match key {
+            "KULTA_ADVISOR_MAX_WEIGHT_INCREASE" => Some("20".to_string()),
+            "KULTA_ADVISOR_FORBIDDEN_ACTIONS" => Some("Pause, teleport".to_string()),
+            "KULTA_ADVISOR_DRIVEN_DENIED_NAMESPACES" => Some("payments,".to_string()),
+            "KULTA_MAX_MANUAL_WEIGHT" => Some("50".to_string()),
+            "KULTA_ADVISOR_MIN_PAUSE" => Some("2m".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_from_lookup() {
+        let policy = policy();
+        assert_eq!(policy.max_weight_increase, Some(20));
+        assert_eq!(policy.forbidden_actions, vec!["pause".to_string()]);
+        assert_eq!(
+            policy.driven_denied_namespaces,
+            vec!["payments".to_string()]
+        );
+        assert_eq!(policy.max_manual_weight, Some(50));
+        assert_eq!(policy.min_pause, Some(Duration::from_secs(120)));
+
+        assert_eq!(
+            GuardrailPolicy::from_lookup(|_| None),
+            GuardrailPolicy::default()
+        );
+    }
+
+    #[test]
+    fn test_check_limits_weight_increase() {
+        let policy = policy();
+
+        assert!(policy
+            .check(
+                &recommend(RecommendedAction::Advance { to_weight: 40 }),
+                20,
+                true
+            )
+            .is_ok());
+        assert_eq!(
+            policy.check(
+                &recommend(RecommendedAction::Advance { to_weight: 50 }),
+                20,
+                true
+            ),
+            Err(GuardrailViolation::WeightIncreaseTooLarge {
+                increase: 30,
+                max: 20
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_rejects_forbidden_and_analysis_skipping_actions() {
+        let policy = policy();
+
+        assert_eq!(
+            policy.check(&recommend(RecommendedAction::Pause), 20, true),
+            Err(GuardrailViolation::ForbiddenAction("pause"))
+        );
+        // Never skip analysis, even with an empty policy
+        let open = GuardrailPolicy::default();
+        assert_eq!(
+            open.check(&recommend(RecommendedAction::Continue), 20, false),
+            Err(GuardrailViolation::SkipsAnalysis("continue"))
+        );
+        assert!(open
+            .check(&recommend(RecommendedAction::Rollback), 20, false)
+            .is_ok());
+    }
+
+    fn steps() -> Vec<CanaryStep> {
+        serde_json::from_value(serde_json::json!([
+            { "setWeight": 10, "pause": { "duration": "5m" } },
+            { "setWeight": 20, "pause": { "duration": "5m" } },
+            { "setWeight": 30 },
+            { "setWeight": 40, "pause": { "untilApproved": true } },
+            { "setWeight": 50, "pause": { "duration": "5m" } }
+        ]))
+        .unwrap()
+    }
+
+    fn planned(step: i32, set_weight: Option<i32>, pause: Option<&str>) -> PlannedStep {
+        PlannedStep {
+            step,
+            set_weight,
+            pause_duration: pause.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_check_plan_accepts_skips_and_longer_pauses() {
+        let policy = policy();
+
+        let plan = [
+            planned(2, None, Some("10m")),
+            planned(3, None, None),
+            planned(4, Some(60), None),
+        ];
+        assert!(policy.check_plan(&steps(), 0, 10, &plan, true).is_ok());
+    }
+
+    #[test]
+    fn test_check_plan_enforces_guardrails() {
+        let policy = policy();
+        let steps = steps();
+
+        // Too short a pause, too large a jump
+        assert_eq!(
+            policy.check_plan(&steps, 0, 10, &[planned(1, None, Some("30s"))], true),
+            Err(GuardrailViolation::PauseTooShort {
+                step: 1,
+                min: Duration::from_secs(120)
+            })
+        );
+        assert_eq!(
+            policy.check_plan(&steps, 0, 10, &[planned(2, Some(45), None)], true),
+            Err(GuardrailViolation::WeightIncreaseTooLarge {
+                increase: 35,
+                max: 20
+            })
+        );
+        // Human gates stay: neither skipped nor retimed
+        assert_eq!(
+            policy.check_plan(&steps, 2, 30, &[planned(4, None, None)], true),
+            Err(GuardrailViolation::SkipsGate(3))
+        );
+        assert!(matches!(
+            policy.check_plan(&steps, 2, 30, &[planned(3, None, Some("5m"))], true),
+            Err(GuardrailViolation::InvalidPlan(_))
+        ));
+        // Only later steps, in order
+        assert!(matches!(
+            policy.check_plan(&steps, 2, 30, &[planned(1, None, None)], true),
+            Err(GuardrailViolation::InvalidPlan(_))
+        ));
+        // Running out of steps early jumps to 100%
+        assert_eq!(
+            policy.check_plan(&steps, 3, 40, &[], true),
+            Err(GuardrailViolation::WeightIncreaseTooLarge {
+                increase: 60,
+                max: 20
+            })
+        );
+        assert_eq!(
+            policy.check_plan(&steps, 0, 10, &[planned(1, None, None)], false),
+            Err(GuardrailViolation::SkipsAnalysis("plan"))
+        );
+    }
+
+    #[test]
+    fn test_check_drive_guards_the_step_being_left() {
+        let policy = policy();
+        let steps = steps();
+        let advance = recommend(RecommendedAction::Advance { to_weight: 20 });
+
+        assert!(policy.check_drive(&advance, &steps, 0, 10, true).is_ok());
+        // Step 3 waits for an approval the advisor can't give
+        assert_eq!(
+            policy.check_drive(&advance, &steps, 3, 40, true),
+            Err(GuardrailViolation::SkipsGate(3))
+        );
+        // From the last step an advance promotes to 100%
+        assert_eq!(
+            policy.check_drive(&advance, &steps, 4, 50, true),
+            Err(GuardrailViolation::WeightIncreaseTooLarge {
+                increase: 50,
+                max: 20
+            })
+        );
+        assert_eq!(
+            policy.check_drive(&advance, &steps, 0, 10, false),
+            Err(GuardrailViolation::SkipsAnalysis("advance"))
+        );
+        // Only advancing leaves the step
+        assert!(policy
+            .check_drive(
+                &recommend(RecommendedAction::Rollback),
+                &steps,
+                3,
+                40,
+                false
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_manual_weight_allows_drains() {
+        let policy = policy();
+
+        assert!(policy.check_manual_weight(50, 20).is_ok());
+        assert_eq!(
+            policy.check_manual_weight(60, 20),
+            Err(GuardrailViolation::ManualWeightTooHigh {
+                weight: 60,
+                max: 50
+            })
+        );
+        // Above the limit already: draining some traffic is fine
+        assert!(policy.check_manual_weight(70, 80).is_ok());
+        assert!(GuardrailPolicy::default()
+            .check_manual_weight(100, 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_driven_denied_in_namespace() {
+        let policy = policy();
+
+        assert_eq!(
+            policy.effective_level(&AdvisorLevel::Driven, "payments"),
+            AdvisorLevel::Advised
+        );
+        assert_eq!(
+            policy.effective_level(&AdvisorLevel::Driven, "shop"),
+            AdvisorLevel::Driven
+        );
+        assert_eq!(
+            policy.effective_level(&AdvisorLevel::Planned, "payments"),
+            AdvisorLevel::Planned
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Heartbeats for Rollouts stuck in a long-running phase
+//!
+//! Occurrences and CDEvents are emitted on phase changes only, so a rollout
+//! whose phase never changes (analysis hanging, a preview nobody promotes)
+//! goes silent. With `KULTA_HEARTBEAT_INTERVAL_MINUTES=N`, a Rollout that
+//! stays Progressing, Experimenting or Preview emits a `rollout.heartbeat`
+//! occurrence every N minutes carrying its weight and analysis summary.
+//! `KULTA_HEARTBEAT_CDEVENTS=true` also sends a heartbeat CDEvent.
+
+use crate::crd::rollout::Phase;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::warn;
+
+/// Heartbeat schedule of the Rollouts in long-running phases
+#[derive(Debug, Default)]
+pub struct Heartbeats {
+    interval: Option<Duration>,
+    cdevents: bool,
+    /// Last heartbeat (or phase entry) per `namespace/name`
+    last: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl Heartbeats {
+    /// Heartbeats that never fire
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create heartbeats from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create heartbeats from an arbitrary key lookup (testable without touching env)
+    ///
+    /// `0` and invalid intervals disable heartbeats.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let interval = lookup("KULTA_HEARTBEAT_INTERVAL_MINUTES").and_then(|v| {
+            match v.trim().parse::<u64>() {
+                Ok(0) => None,
+                Ok(minutes) => Some(Duration::from_secs(minutes * 60)),
+                Err(_) => {
+                    warn!(value = %v, "Ignoring invalid KULTA_HEARTBEAT_INTERVAL_MINUTES");
+                    None
+                }
+            }
+        });
+        let cdevents = lookup("KULTA_HEARTBEAT_CDEVENTS")
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("true"));
+        Self {
+            interval,
+            cdevents,
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Time between heartbeats (None when disabled)
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Whether heartbeats are also sent as CDEvents
+    pub fn cdevents_enabled(&self) -> bool {
+        self.interval.is_some() && self.cdevents
+    }
+
+    /// Whether a Rollout in `phase` is due for a heartbeat at `now`
+    ///
+    /// The first sighting in a long-running phase starts the clock; leaving
+    /// the phase resets it.
+    pub fn due(&self, key: &str, phase: Option<&Phase>, now: DateTime<Utc>) -> bool {
+        let Some(interval) = self.interval else {
+            return false;
+        };
+        let Ok(mut last) = self.last.lock() else {
+            return false;
+        };
+        if !is_long_running(phase) {
+            last.remove(key);
+            return false;
+        }
+
+        let Some(previous) = last.get(key).copied() else {
+            last.insert(key.to_string(), now);
+            return false;
+        };
+        let elapsed = (now - previous).to_std().unwrap_or_default();
+        if elapsed < interval {
+            return false;
+        }
+        last.insert(key.to_string(), now);
+        true
+    }
+}
+
+/// Whether a phase can last indefinitely without a phase change
+pub fn is_long_running(phase: Option<&Phase>) -> bool {
+    matches!(
+        phase,
+        Some(Phase::Progressing | Phase::Experimenting | Phase::Preview)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(minutes * 60, 0).unwrap()
+    }
+
+    #[test]
+    fn test_from_lookup() {
+        let heartbeats = Heartbeats::from_lookup(|key| match key {
+            "KULTA_HEARTBEAT_INTERVAL_MINUTES" => Some("5".into()),
+            "KULTA_HEARTBEAT_CDEVENTS" => Some("true".into()),
+            _ => None,
+        });
+        assert_eq!(heartbeats.interval(), Some(Duration::from_secs(300)));
+        assert!(heartbeats.cdevents_enabled());
+
+        for value in ["0", "soon"] {
+            let heartbeats = Heartbeats::from_lookup(|_| Some(value.into()));
+            assert_eq!(heartbeats.interval(), None);
+            assert!(!heartbeats.cdevents_enabled());
+        }
+    }
+
+    #[test]
+    fn test_due_every_interval_while_long_running() {
+        let heartbeats = Heartbeats::from_lookup(|key| {
+            (key == "KULTA_HEARTBEAT_INTERVAL_MINUTES").then(|| "10".into())
+        });
+        let progressing = Some(&Phase::Progressing);
+
+        assert!(!heartbeats.due("shop/checkout", progressing, at(0)));
+        assert!(!heartbeats.due("shop/checkout", progressing, at(9)));
+        assert!(heartbeats.due("shop/checkout", progressing, at(10)));
+        assert!(!heartbeats.due("shop/checkout", progressing, at(15)));
+        assert!(heartbeats.due("shop/checkout", Some(&Phase::Preview), at(21)));
+
+        // Leaving the phase resets the clock
+        assert!(!heartbeats.due("shop/checkout", Some(&Phase::Paused), at(40)));
+        assert!(!heartbeats.due("shop/checkout", progressing, at(41)));
+        assert!(!heartbeats.due("shop/checkout", progressing, at(50)));
+        assert!(heartbeats.due("shop/checkout", progressing, at(51)));
+    }
+
+    #[test]
+    fn test_disabled_never_due() {
+        let heartbeats = Heartbeats::disabled();
+        assert!(!heartbeats.due("shop/checkout", Some(&Phase::Progressing), at(0)));
+        assert!(!heartbeats.due("shop/checkout", Some(&Phase::Progressing), at(600)));
+    }
+}
@@ -0,0 +1,408 @@
+//! PagerDuty and Opsgenie incidents for failed rollouts
+//!
+//! A failed rollout usually needs a human, so with a PagerDuty routing key
+//! or an Opsgenie API key configured the controller opens an incident when a
+//! Rollout enters `Failed`. The incident body is the FALSE occurrence's error
+//! context (`what_failed`, `why_it_matters`, `possible_causes`,
+//! `suggested_fix`), so responders start from the same diagnosis AHTI sees.
+//!
+//! One incident is kept per Rollout: repeated failures are deduplicated by
+//! `kulta/{namespace}/{name}`, and the incident is resolved when the Rollout
+//! later completes. Both notifiers are registered on
+//! [`Notifications`](super::notifications::Notifications) and want no other
+//! notification. Resolving a Rollout that has no open incident is a no-op
+//! for both services.
+
+use crate::controller::notifications::{
+    send_json, Notification, NotificationError, NotificationTrigger, Notifier,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::warn;
+
+/// PagerDuty Events API v2 endpoint
+pub const DEFAULT_PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Opsgenie API (use `https://api.eu.opsgenie.com` for the EU instance)
+pub const DEFAULT_OPSGENIE_API_URL: &str = "https://api.opsgenie.com";
+
+/// Longest Opsgenie alert message; longer ones are rejected
+const OPSGENIE_MESSAGE_LIMIT: usize = 130;
+
+/// Longest PagerDuty summary
+const PAGERDUTY_SUMMARY_LIMIT: usize = 1024;
+
+/// What a notification means for the Rollout's incident
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IncidentAction {
+    /// The rollout failed: open (or add to) its incident
+    Open,
+    /// The rollout completed: resolve its incident
+    Resolve,
+}
+
+impl IncidentAction {
+    /// The action a notification calls for, if any
+    pub fn for_notification(notification: &Notification) -> Option<Self> {
+        match notification.trigger {
+            NotificationTrigger::Failed => Some(IncidentAction::Open),
+            NotificationTrigger::PhaseChanged if notification.phase == "Completed" => {
+                Some(IncidentAction::Resolve)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Deduplication key of a Rollout's incident
+pub fn incident_key(notification: &Notification) -> String {
+    format!("kulta/{}/{}", notification.namespace, notification.rollout)
+}
+
+/// `text`, cut to at most `limit` characters
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(limit.saturating_sub(3)).collect();
+    cut.push_str("...");
+    cut
+}
+
+fn headline(notification: &Notification) -> String {
+    match &notification.error {
+        Some(error) => format!(
+            "Rollout {}/{} failed: {}",
+            notification.namespace, notification.rollout, error.what_failed
+        ),
+        None => notification.summary(),
+    }
+}
+
+/// PagerDuty Events API v2 event for a notification
+pub fn pagerduty_event(
+    routing_key: &str,
+    notification: &Notification,
+    action: IncidentAction,
+) -> serde_json::Value {
+    let dedup_key = incident_key(notification);
+    if action == IncidentAction::Resolve {
+        return serde_json::json!({
+            "routing_key": routing_key,
+            "event_action": "resolve",
+            "dedup_key": dedup_key,
+        });
+    }
+    serde_json::json!({
+        "routing_key": routing_key,
+        "event_action": "trigger",
+        "dedup_key": dedup_key,
+        "payload": {
+            "summary": truncate(&headline(notification), PAGERDUTY_SUMMARY_LIMIT),
+            "source": format!("{}/{}", notification.namespace, notification.rollout),
+            "severity": "error",
+            "timestamp": notification.timestamp,
+            "component": notification.rollout,
+            "group": notification.namespace,
+            "class": "rollout.failed",
+            "custom_details": {
+                "error": notification.error,
+                "strategy": notification.strategy,
+                "step": notification.step,
+                "weight": notification.weight,
+                "message": notification.message,
+            },
+        },
+    })
+}
+
+/// Opsgenie alert for a failed rollout
+pub fn opsgenie_alert(notification: &Notification) -> serde_json::Value {
+    let mut description = notification.message.clone().unwrap_or_default();
+    let mut details = serde_json::Map::new();
+    details.insert("namespace".into(), notification.namespace.clone().into());
+    details.insert("rollout".into(), notification.rollout.clone().into());
+    if let Some(strategy) = &notification.strategy {
+        details.insert("strategy".into(), strategy.clone().into());
+    }
+    if let Some(weight) = notification.weight {
+        details.insert("weight".into(), format!("{}%", weight).into());
+    }
+    if let Some(error) = &notification.error {
+        description = format!(
+            "What failed: {}\n\nWhy it matters: {}\n\nPossible causes:\n- {}\n\nSuggested fix: {}",
+            error.what_failed,
+            error.why_it_matters.as_deref().unwrap_or("-"),
+            error.possible_causes.join("\n- "),
+            error.suggested_fix.as_deref().unwrap_or("-"),
+        );
+        details.insert("what_failed".into(), error.what_failed.clone().into());
+        if let Some(fix) = &error.suggested_fix {
+            details.insert("suggested_fix".into(), fix.clone().into());
+        }
+    }
+    let mut tags = vec!["kulta".to_string()];
+    tags.extend(notification.strategy.clone());
+    serde_json::json!({
+        "message": truncate(&headline(notification), OPSGENIE_MESSAGE_LIMIT),
+        "alias": incident_key(notification),
+        "description": description,
+        "details": details,
+        "source": "kulta",
+        "entity": format!("{}/{}", notification.namespace, notification.rollout),
+        "tags": tags,
+        "priority": "P2",
+    })
+}
+
+/// Opens PagerDuty incidents through the Events API v2
+pub struct PagerDutyNotifier {
+    routing_key: String,
+    url: String,
+    http: reqwest::Client,
+}
+
+impl PagerDutyNotifier {
+    pub fn new(
+        routing_key: impl Into<String>,
+        url: impl Into<String>,
+        http: reqwest::Client,
+    ) -> Self {
+        Self {
+            routing_key: routing_key.into(),
+            url: url.into(),
+            http,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for PagerDutyNotifier {
+    fn kind(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    fn wants(&self, notification: &Notification) -> bool {
+        IncidentAction::for_notification(notification).is_some()
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        let Some(action) = IncidentAction::for_notification(notification) else {
+            return Ok(());
+        };
+        let event = pagerduty_event(&self.routing_key, notification, action);
+        send_json(self.http.post(&self.url), &event, self.kind()).await
+    }
+}
+
+/// Opens Opsgenie alerts through the Alert API
+pub struct OpsgenieNotifier {
+    api_key: String,
+    api_url: String,
+    http: reqwest::Client,
+}
+
+impl OpsgenieNotifier {
+    pub fn new(
+        api_key: impl Into<String>,
+        api_url: impl Into<String>,
+        http: reqwest::Client,
+    ) -> Self {
+        Self {
+            api_key: api_key.into(),
+            api_url: api_url.into(),
+            http,
+        }
+    }
+
+    /// `{api_url}/v2/alerts/...`, with each segment escaped
+    fn alerts_url(&self, segments: &[&str]) -> Result<reqwest::Url, NotificationError> {
+        let invalid = || NotificationError::Delivery {
+            kind: self.kind(),
+            reason: "invalid KULTA_OPSGENIE_API_URL".to_string(),
+        };
+        let mut url = reqwest::Url::parse(&self.api_url).map_err(|_| invalid())?;
+        url.path_segments_mut()
+            .map_err(|_| invalid())?
+            .pop_if_empty()
+            .extend(["v2", "alerts"])
+            .extend(segments);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl Notifier for OpsgenieNotifier {
+    fn kind(&self) -> &'static str {
+        "opsgenie"
+    }
+
+    fn wants(&self, notification: &Notification) -> bool {
+        IncidentAction::for_notification(notification).is_some()
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        let (url, body) = match IncidentAction::for_notification(notification) {
+            Some(IncidentAction::Open) => (self.alerts_url(&[])?, opsgenie_alert(notification)),
+            Some(IncidentAction::Resolve) => {
+                let mut url = self.alerts_url(&[&incident_key(notification), "close"])?;
+                url.query_pairs_mut().append_pair("identifierType", "alias");
+                let body = serde_json::json!({
+                    "source": "kulta",
+                    "note": notification.summary(),
+                });
+                (url, body)
+            }
+            None => return Ok(()),
+        };
+        let request = self
+            .http
+            .post(url)
+            .header("Authorization", format!("GenieKey {}", self.api_key));
+        send_json(request, &body, self.kind()).await
+    }
+}
+
+/// Incident notifiers configured by environment variables
+///
+/// - `KULTA_PAGERDUTY_ROUTING_KEY`: integration key of a PagerDuty service
+/// - `KULTA_PAGERDUTY_EVENTS_URL`: Events API endpoint (default: PagerDuty's)
+/// - `KULTA_OPSGENIE_API_KEY`: key of an Opsgenie API integration
+/// - `KULTA_OPSGENIE_API_URL`: Opsgenie API (default: `https://api.opsgenie.com`)
+pub fn incident_notifiers_from_env(http: &reqwest::Client) -> Vec<Arc<dyn Notifier>> {
+    incident_notifiers_from_lookup(http, |key| std::env::var(key).ok())
+}
+
+/// Incident notifiers configured through `lookup`
+pub fn incident_notifiers_from_lookup(
+    http: &reqwest::Client,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<Arc<dyn Notifier>> {
+    let get = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    if let Some(routing_key) = get("KULTA_PAGERDUTY_ROUTING_KEY") {
+        let url = get("KULTA_PAGERDUTY_EVENTS_URL")
+            .unwrap_or_else(|| DEFAULT_PAGERDUTY_EVENTS_URL.to_string());
+        notifiers.push(Arc::new(PagerDutyNotifier::new(
+            routing_key.trim(),
+            url,
+            http.clone(),
+        )));
+    }
+    if let Some(api_key) = get("KULTA_OPSGENIE_API_KEY") {
+        let api_url =
+            get("KULTA_OPSGENIE_API_URL").unwrap_or_else(|| DEFAULT_OPSGENIE_API_URL.to_string());
+        if reqwest::Url::parse(&api_url).is_err() {
+            warn!(value = %api_url, "Invalid KULTA_OPSGENIE_API_URL, Opsgenie alerts disabled");
+        } else {
+            notifiers.push(Arc::new(OpsgenieNotifier::new(
+                api_key.trim(),
+                api_url,
+                http.clone(),
+            )));
+        }
+    }
+    notifiers
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::controller::notifications::FailureContext;
+
+    fn notification(trigger: NotificationTrigger, phase: &str) -> Notification {
+        Notification {
+            trigger,
+            rollout: "checkout".to_string(),
+            namespace: "shop".to_string(),
+            strategy: Some("canary".to_string()),
+            previous_phase: Some("Progressing".to_string()),
+            phase: phase.to_string(),
+            step: Some(1),
+            weight: Some(25),
+            message: Some("error-rate above 5".to_string()),
+            error: (trigger == NotificationTrigger::Failed).then(|| FailureContext {
+                what_failed: "error-rate above 5 for checkout canary at step 2/4 (25% traffic)"
+                    .to_string(),
+                why_it_matters: Some("Degraded traffic to 25% of requests".to_string()),
+                possible_causes: vec![
+                    "error-rate above 5".to_string(),
+                    "Downstream service degradation".to_string(),
+                ],
+                suggested_fix: Some("Rollback checkout to stable".to_string()),
+            }),
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_incident_actions() {
+        let failed = notification(NotificationTrigger::Failed, "Failed");
+        let completed = notification(NotificationTrigger::PhaseChanged, "Completed");
+        let paused = notification(NotificationTrigger::AwaitingApproval, "Progressing");
+        assert_eq!(
+            IncidentAction::for_notification(&failed),
+            Some(IncidentAction::Open)
+        );
+        assert_eq!(
+            IncidentAction::for_notification(&completed),
+            Some(IncidentAction::Resolve)
+        );
+        assert_eq!(IncidentAction::for_notification(&paused), None);
+        assert_eq!(incident_key(&failed), "kulta/shop/checkout");
+    }
+
+    #[test]
+    fn test_incident_payloads_carry_failure_context() {
+        let failed = notification(NotificationTrigger::Failed, "Failed");
+
+        let trigger = pagerduty_event("R0UT1NG", &failed, IncidentAction::Open);
+        assert_eq!(trigger["event_action"], "trigger");
+        assert_eq!(trigger["dedup_key"], "kulta/shop/checkout");
+        let details = &trigger["payload"]["custom_details"];
+        assert_eq!(
+            details["error"]["possible_causes"][1],
+            "Downstream service degradation"
+        );
+        assert_eq!(
+            details["error"]["suggested_fix"],
+            "Rollback checkout to stable"
+        );
+        let resolve = pagerduty_event("R0UT1NG", &failed, IncidentAction::Resolve);
+        assert_eq!(resolve["event_action"], "resolve");
+        assert!(resolve.get("payload").is_none());
+
+        let alert = opsgenie_alert(&failed);
+        assert_eq!(alert["alias"], "kulta/shop/checkout");
+        assert!(alert["message"].as_str().unwrap().chars().count() <= OPSGENIE_MESSAGE_LIMIT);
+        let description = alert["description"].as_str().unwrap();
+        assert!(description.contains("- Downstream service degradation"));
+        assert!(description.contains("Suggested fix: Rollback checkout to stable"));
+        assert_eq!(alert["details"]["weight"], "25%");
+    }
+
+    #[test]
+    fn test_incident_notifiers_from_lookup() {
+        let http = reqwest::Client::new();
+        let none = incident_notifiers_from_lookup(&http, |_| None);
+        assert!(none.is_empty());
+
+        let both = incident_notifiers_from_lookup(&http, |key| match key {
+            "KULTA_PAGERDUTY_ROUTING_KEY" => Some("R0UT1NG".to_string()),
+            "KULTA_OPSGENIE_API_KEY" => Some("key".to_string()),
+            _ => None,
+        });
+        let kinds: Vec<&str> = both.iter().map(|n| n.kind()).collect();
+        assert_eq!(kinds, ["pagerduty", "opsgenie"]);
+
+        let opsgenie = OpsgenieNotifier::new("key", "https://api.eu.opsgenie.com/", http);
+        assert_eq!(
+            opsgenie
+                .alerts_url(&["kulta/shop/checkout", "close"])
+                .unwrap()
+                .as_str(),
+            "https://api.eu.opsgenie.com/v2/alerts/kulta%2Fshop%2Fcheckout/close"
+        );
+    }
+}
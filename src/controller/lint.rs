@@ -0,0 +1,347 @@
+//! Rollout spec linting
+//!
+//! Validation rejects specs the controller can't run; the linter flags ones
+//! it can run but probably shouldn't. Each warning names a risky pattern:
+//!
+//! - `LargeWeightJump`: a canary step raises the weight by more than
+//!   [`MAX_WEIGHT_JUMP`] points at once (e.g. 5% straight to 100%)
+//! - `NoPauseBeforeJump`: a step raises the weight by at least
+//!   [`OBSERVED_WEIGHT_JUMP`] points without a pause, step analysis, alert
+//!   gate or experiment after the previous weight, so nothing looks at the
+//!   canary first
+//! - `NoAnalysis`: a canary or blue-green rollout in a production namespace
+//!   (`KULTA_PRODUCTION_NAMESPACES`) without any metrics analysis
+//!
+//! The controller reports warnings in a `Warnings` condition, and
+//! `kulta lint` prints them. Neither blocks the rollout.
+
+use crate::crd::rollout::{CanaryStep, Rollout, RolloutCondition, RolloutStatus};
+use kube::ResourceExt;
+use std::fmt;
+
+/// Largest weight increase of a single step that isn't flagged
+pub const MAX_WEIGHT_JUMP: i32 = 50;
+
+/// Weight increase that should be preceded by something observing the canary
+pub const OBSERVED_WEIGHT_JUMP: i32 = 25;
+
+/// Condition type listing lint warnings
+pub const WARNINGS_CONDITION: &str = "Warnings";
+
+/// Reason of the `Warnings` condition
+pub const LINT_WARNINGS_REASON: &str = "RiskyConfiguration";
+
+/// Risky pattern a warning is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintRule {
+    LargeWeightJump,
+    NoPauseBeforeJump,
+    NoAnalysis,
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LintRule::LargeWeightJump => "LargeWeightJump",
+            LintRule::NoPauseBeforeJump => "NoPauseBeforeJump",
+            LintRule::NoAnalysis => "NoAnalysis",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A risky but valid part of a Rollout spec
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    /// Spec path the warning is about (e.g. `spec.strategy.canary.steps[2]`)
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.path, self.rule, self.message)
+    }
+}
+
+/// What the linter knows beyond the spec
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LintOptions {
+    /// The Rollout runs in a production namespace
+    pub production: bool,
+}
+
+/// Namespaces treated as production, from `KULTA_PRODUCTION_NAMESPACES`
+///
+/// A comma-separated list of names; a trailing `*` matches a prefix
+/// (`prod,prod-*`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProductionNamespaces {
+    patterns: Vec<String>,
+}
+
+impl ProductionNamespaces {
+    pub fn parse(spec: &str) -> Self {
+        Self {
+            patterns: spec
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Load from `KULTA_PRODUCTION_NAMESPACES` (none when unset)
+    pub fn from_env() -> Self {
+        std::env::var("KULTA_PRODUCTION_NAMESPACES")
+            .map(|spec| Self::parse(&spec))
+            .unwrap_or_default()
+    }
+
+    pub fn matches(&self, namespace: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => namespace.starts_with(prefix),
+                None => namespace == pattern,
+            })
+    }
+}
+
+/// Whether a step gives anyone a chance to look at the canary
+fn observes_canary(step: &CanaryStep) -> bool {
+    step.pause.is_some()
+        || step.analysis.is_some()
+        || step.alert_gate.is_some()
+        || step.experiment.is_some()
+}
+
+fn lint_canary_steps(steps: &[CanaryStep], warnings: &mut Vec<LintWarning>) {
+    let mut weight = 0;
+    let mut observed = false;
+    for (i, step) in steps.iter().enumerate() {
+        if let Some(next) = step.set_weight {
+            let jump = next - weight;
+            let path = format!("spec.strategy.canary.steps[{}]", i);
+            if jump > MAX_WEIGHT_JUMP {
+                warnings.push(LintWarning {
+                    rule: LintRule::LargeWeightJump,
+                    path: path.clone(),
+                    message: format!(
+                        "weight jumps from {}% to {}% in one step; add intermediate steps",
+                        weight, next
+                    ),
+                });
+            }
+            // The first weight is exposure, not a jump from an observed state
+            if jump >= OBSERVED_WEIGHT_JUMP && weight > 0 && !observed {
+                warnings.push(LintWarning {
+                    rule: LintRule::NoPauseBeforeJump,
+                    path,
+                    message: format!(
+                        "weight rises from {}% to {}% without a pause, analysis or alert gate at {}%",
+                        weight, next, weight
+                    ),
+                });
+            }
+            weight = next;
+            observed = false;
+        }
+        observed |= observes_canary(step);
+    }
+}
+
+/// Warnings about a Rollout's spec, in spec order
+///
+/// Canary steps imported with `stepsFrom` aren't known offline and aren't
+/// linted.
+pub fn lint_rollout(rollout: &Rollout, options: &LintOptions) -> Vec<LintWarning> {
+    let strategy = &rollout.spec.strategy;
+    let mut warnings = Vec::new();
+    if let Some(canary) = &strategy.canary {
+        lint_canary_steps(&canary.steps, &mut warnings);
+        let step_analysis = canary.steps.iter().any(|step| step.analysis.is_some());
+        if options.production && canary.analysis.is_none() && !step_analysis {
+            warnings.push(LintWarning {
+                rule: LintRule::NoAnalysis,
+                path: "spec.strategy.canary.analysis".to_string(),
+                message: format!(
+                    "no metrics analysis in production namespace {}; a bad canary is only caught by hand",
+                    rollout.namespace().unwrap_or_default()
+                ),
+            });
+        }
+    }
+    if let Some(blue_green) = &strategy.blue_green {
+        if options.production && blue_green.analysis.is_none() {
+            warnings.push(LintWarning {
+                rule: LintRule::NoAnalysis,
+                path: "spec.strategy.blueGreen.analysis".to_string(),
+                message: format!(
+                    "no metrics analysis in production namespace {}; the preview is promoted unchecked",
+                    rollout.namespace().unwrap_or_default()
+                ),
+            });
+        }
+    }
+    warnings
+}
+
+/// Conditions after reporting `warnings`, or None when they're already reported
+///
+/// The `Warnings` condition lists every warning in its message and is
+/// removed once there are none. `lastTransitionTime` is kept while the
+/// condition stays set.
+pub fn warnings_conditions(
+    status: Option<&RolloutStatus>,
+    warnings: &[LintWarning],
+    now: &str,
+) -> Option<Vec<RolloutCondition>> {
+    let current = status.map(|s| s.conditions.as_slice()).unwrap_or_default();
+    let existing = current.iter().find(|c| c.type_ == WARNINGS_CONDITION);
+    let message = warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ");
+    let unchanged = match existing {
+        None => warnings.is_empty(),
+        Some(c) => !warnings.is_empty() && c.message.as_deref() == Some(message.as_str()),
+    };
+    if unchanged {
+        return None;
+    }
+
+    let mut conditions: Vec<RolloutCondition> = current
+        .iter()
+        .filter(|c| c.type_ != WARNINGS_CONDITION)
+        .cloned()
+        .collect();
+    if !warnings.is_empty() {
+        conditions.push(RolloutCondition {
+            type_: WARNINGS_CONDITION.to_string(),
+            status: "True".to_string(),
+            reason: Some(LINT_WARNINGS_REASON.to_string()),
+            message: Some(message),
+            last_transition_time: existing
+                .and_then(|c| c.last_transition_time.clone())
+                .or_else(|| Some(now.to_string())),
+        });
+    }
+    Some(conditions)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::SimpleStrategy;
+
+    fn rollout(namespace: &str, strategy: serde_json::Value) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace(namespace)
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        // Lint reads steps validation rejects too (pause-only steps)
+        rollout.spec.strategy = serde_json::from_value(strategy).unwrap();
+        rollout
+    }
+
+    fn rules(warnings: &[LintWarning]) -> Vec<(LintRule, &str)> {
+        warnings.iter().map(|w| (w.rule, w.path.as_str())).collect()
+    }
+
+    #[test]
+    fn test_lint_weight_jumps() {
+        let risky = rollout(
+            "shop",
+            serde_json::json!({ "canary": {
+                "stableService": "shop", "canaryService": "shop-canary",
+                "steps": [
+                    { "setWeight": 5 },
+                    { "pause": { "duration": "5m" } },
+                    { "setWeight": 30 },
+                    { "setWeight": 100 }
+                ]
+            } }),
+        );
+        assert_eq!(
+            rules(&lint_rollout(&risky, &LintOptions::default())),
+            vec![
+                (LintRule::LargeWeightJump, "spec.strategy.canary.steps[3]"),
+                (LintRule::NoPauseBeforeJump, "spec.strategy.canary.steps[3]"),
+            ]
+        );
+
+        let careful = rollout(
+            "shop",
+            serde_json::json!({ "canary": {
+                "stableService": "shop", "canaryService": "shop-canary",
+                "steps": [
+                    { "setWeight": 20 },
+                    { "pause": {} },
+                    { "setWeight": 50, "analysis": { "metrics": [] } },
+                    { "setWeight": 100 }
+                ]
+            } }),
+        );
+        assert!(lint_rollout(&careful, &LintOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_lint_missing_analysis_only_in_production() {
+        let blue_green = rollout(
+            "prod-eu",
+            serde_json::json!({ "blueGreen": {
+                "activeService": "shop", "previewService": "shop-preview"
+            } }),
+        );
+        let production = ProductionNamespaces::parse("prod, prod-*");
+        assert!(production.matches("prod-eu"));
+        assert!(!production.matches("preprod"));
+
+        assert!(lint_rollout(&blue_green, &LintOptions::default()).is_empty());
+        assert_eq!(
+            rules(&lint_rollout(
+                &blue_green,
+                &LintOptions { production: true }
+            )),
+            vec![(LintRule::NoAnalysis, "spec.strategy.blueGreen.analysis")]
+        );
+    }
+
+    #[test]
+    fn test_warnings_condition_is_set_kept_and_removed() {
+        let warning = LintWarning {
+            rule: LintRule::LargeWeightJump,
+            path: "spec.strategy.canary.steps[0]".to_string(),
+            message: "weight jumps from 0% to 100% in one step".to_string(),
+        };
+        assert_eq!(warnings_conditions(None, &[], "t0"), None);
+
+        let conditions = warnings_conditions(None, &[warning.clone()], "t0").unwrap();
+        assert_eq!(conditions[0].type_, WARNINGS_CONDITION);
+        assert!(conditions[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .starts_with("spec.strategy.canary.steps[0] (LargeWeightJump)"));
+
+        let status = RolloutStatus {
+            conditions,
+            ..RolloutStatus::default()
+        };
+        assert_eq!(warnings_conditions(Some(&status), &[warning], "t1"), None);
+        assert_eq!(
+            warnings_conditions(Some(&status), &[], "t1"),
+            Some(Vec::new())
+        );
+    }
+}
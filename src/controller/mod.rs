@@ -1,10 +1,40 @@
 pub mod advisor;
+pub mod alertmanager;
+pub mod argocd;
+pub mod audit;
 pub mod cdevents;
+pub mod cdevents_data;
+pub mod cdevents_retry;
 pub mod clock;
+pub mod concurrency;
+pub mod defaults;
+pub mod dependency_gates;
+pub mod effective_config;
+pub mod experiment;
+pub mod freeze;
+pub mod guardrails;
+pub mod heartbeat;
+pub mod incidents;
+pub mod lint;
+pub mod notifications;
 pub mod occurrence;
+pub mod occurrence_sink;
+pub mod orphans;
+pub mod planner;
+pub mod pod_policy;
 pub mod prometheus;
 pub mod prometheus_ab;
+pub mod prometheus_auth;
+pub mod promotion_policy;
+pub mod release_hooks;
 pub mod rollout;
+pub mod steps_from;
 pub mod strategies;
+pub mod strategy_math;
+pub mod support_bundle;
+pub mod traffic_shift;
+pub mod web_metric;
+pub mod work_queue;
+pub mod workload_ref;
 
 pub use rollout::{reconcile, Context, ReconcileError};
@@ -0,0 +1,899 @@
+//! Notifications to chat and webhook channels
+//!
+//! Responders shouldn't have to watch `kubectl get rollouts` to learn that a
+//! rollout failed or waits for their approval. A [`Notifier`] delivers a
+//! [`Notification`] to one channel; Slack and Microsoft Teams incoming
+//! webhooks and plain JSON webhooks are built in. A notification is sent
+//! when a Rollout's phase changes, when it reaches a pause that waits for
+//! approval, and when it fails.
+//!
+//! Channels are configured per Rollout with annotations:
+//!
+//! - `notifications.kulta.io/slack`, `notifications.kulta.io/teams`,
+//!   `notifications.kulta.io/webhook`: incoming webhook URL of a channel
+//! - `notifications.kulta.io/subscribe`: comma-separated names of channels
+//!   from the controller's ConfigMap
+//! - `notifications.kulta.io/on`: comma-separated triggers the annotated
+//!   channels get (`PhaseChanged`, `AwaitingApproval`, `Failed`; default all)
+//!
+//! or centrally, in the ConfigMap named by `KULTA_NOTIFICATIONS_CONFIGMAP`
+//! (`namespace/name`). Each entry is a channel named after its key:
+//!
+//! ```yaml
+//! team-slack: |
+//!   type: slack                  # slack, teams or webhook
+//!   url: https://hooks.slack.com/services/T000/B000/XXX
+//!   namespaces: [shop]           # notify every Rollout here ("*": all)
+//!   on: [Failed, AwaitingApproval]
+//! ```
+//!
+//! PagerDuty and Opsgenie incidents are opened for failures when
+//! configured; see [`incidents`](super::incidents).
+//!
+//! Embedders can [`Notifications::register`] their own [`Notifier`], which
+//! gets every notification it [`wants`](Notifier::wants). The ConfigMap is cached for a minute. Delivery is best effort: failures
+//! are logged (without the URL, which is a secret for most chat webhooks)
+//! and never block the rollout.
+
+use crate::controller::cdevents_data::StrategyKind;
+use crate::controller::incidents::incident_notifiers_from_env;
+use crate::controller::occurrence::failure_error;
+use crate::controller::strategy_math::current_step;
+use crate::crd::rollout::{Pause, Phase, Rollout, RolloutStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::Api;
+use kube::{Client, ResourceExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Annotation with a Slack incoming webhook URL
+pub const SLACK_ANNOTATION: &str = "notifications.kulta.io/slack";
+
+/// Annotation with a Microsoft Teams incoming webhook URL
+pub const TEAMS_ANNOTATION: &str = "notifications.kulta.io/teams";
+
+/// Annotation with a generic JSON webhook URL
+pub const WEBHOOK_ANNOTATION: &str = "notifications.kulta.io/webhook";
+
+/// Annotation naming ConfigMap channels the Rollout subscribes to
+pub const SUBSCRIBE_ANNOTATION: &str = "notifications.kulta.io/subscribe";
+
+/// Annotation restricting the triggers of the annotated channels
+pub const TRIGGERS_ANNOTATION: &str = "notifications.kulta.io/on";
+
+/// Namespace entry matching every namespace
+const ANY_NAMESPACE: &str = "*";
+
+/// How long ConfigMap channels are reused before it is read again
+const CHANNEL_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Timeout for delivering a single notification
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error, PartialEq)]
+pub enum NotificationError {
+    #[error("Invalid notifications ConfigMap reference '{0}': expected namespace/name")]
+    InvalidConfigMapRef(String),
+
+    #[error("Invalid notification channel '{name}': {reason}")]
+    InvalidChannel { name: String, reason: String },
+
+    #[error("Failed to deliver {kind} notification: {reason}")]
+    Delivery { kind: &'static str, reason: String },
+}
+
+/// What a notification is about
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationTrigger {
+    /// The phase changed (other than to Failed)
+    PhaseChanged,
+    /// The rollout reached a pause that waits for `kulta.io/approved-by`
+    AwaitingApproval,
+    /// The rollout failed and was rolled back
+    Failed,
+}
+
+impl NotificationTrigger {
+    const ALL: [NotificationTrigger; 3] = [
+        NotificationTrigger::PhaseChanged,
+        NotificationTrigger::AwaitingApproval,
+        NotificationTrigger::Failed,
+    ];
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|trigger| format!("{:?}", trigger).eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// Kind of channel a notification is delivered to
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelKind {
+    /// Slack incoming webhook
+    Slack,
+    /// Microsoft Teams incoming webhook
+    Teams,
+    /// Any endpoint accepting the [`Notification`] as JSON
+    Webhook,
+}
+
+/// A channel notifications go to
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Channel {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ChannelKind,
+    pub url: String,
+    /// Namespaces whose Rollouts all notify this channel (ConfigMap only)
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    /// Triggers the channel gets (all when empty)
+    #[serde(default)]
+    pub on: Vec<NotificationTrigger>,
+}
+
+impl Channel {
+    /// Whether the channel wants notifications for `trigger`
+    pub fn accepts(&self, trigger: NotificationTrigger) -> bool {
+        self.on.is_empty() || self.on.contains(&trigger)
+    }
+}
+
+/// A phase change, approval wait or failure of a Rollout
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub trigger: NotificationTrigger,
+    pub rollout: String,
+    pub namespace: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// Phase before the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_phase: Option<String>,
+    pub phase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Error context of a failure, as in the FALSE occurrence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<FailureContext>,
+    /// When the change was observed (RFC3339)
+    pub timestamp: String,
+}
+
+/// The `error` block of a failed rollout's FALSE occurrence
+///
+/// Field names follow the occurrence, so incidents and occurrences about
+/// the same failure read alike.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FailureContext {
+    pub what_failed: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub why_it_matters: Option<String>,
+    pub possible_causes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_fix: Option<String>,
+}
+
+impl FailureContext {
+    /// Context of the failure recorded in `status`
+    pub fn for_failure(rollout: &Rollout, status: &RolloutStatus) -> Self {
+        let error = failure_error(
+            rollout,
+            Some(status),
+            status.strategy.map_or("canary", StrategyKind::as_str),
+        );
+        FailureContext {
+            what_failed: error.what_failed,
+            why_it_matters: error.why_it_matters,
+            possible_causes: error.possible_causes,
+            suggested_fix: error.suggested_fix,
+        }
+    }
+}
+
+impl Notification {
+    /// One-line summary for chat channels
+    pub fn summary(&self) -> String {
+        let headline = match self.trigger {
+            NotificationTrigger::Failed => "failed".to_string(),
+            NotificationTrigger::AwaitingApproval => format!(
+                "awaits approval at step {}",
+                self.step.map(|s| s.to_string()).unwrap_or_default()
+            ),
+            NotificationTrigger::PhaseChanged => format!(
+                "{} -> {}",
+                self.previous_phase.as_deref().unwrap_or("New"),
+                self.phase
+            ),
+        };
+        let mut summary = format!("Rollout {}/{} {}", self.namespace, self.rollout, headline);
+        if let Some(message) = &self.message {
+            summary.push_str(": ");
+            summary.push_str(message);
+        }
+        summary
+    }
+}
+
+fn phase_name(phase: Option<&Phase>) -> Option<String> {
+    phase.map(|p| format!("{:?}", p))
+}
+
+fn awaits_approval(rollout: &Rollout, status: &RolloutStatus) -> bool {
+    matches!(status.phase, Some(Phase::Progressing | Phase::Paused))
+        && current_step(&rollout.spec, Some(status))
+            .and_then(|step| step.pause.as_ref())
+            .is_some_and(|pause| *pause == Pause::UntilApproved)
+}
+
+/// The notification a status update calls for, if any
+///
+/// A failure or a newly reached approval pause takes precedence over the
+/// phase change that comes with it, so each update notifies once.
+pub fn notification_for(
+    rollout: &Rollout,
+    previous: Option<&RolloutStatus>,
+    current: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> Option<Notification> {
+    let previous_phase = previous.and_then(|s| s.phase.as_ref());
+    let phase_changed = previous_phase != current.phase.as_ref();
+    let newly_awaiting = awaits_approval(rollout, current)
+        && !previous.is_some_and(|previous| {
+            awaits_approval(rollout, previous)
+                && previous.current_step_index == current.current_step_index
+        });
+
+    let trigger = match current.phase {
+        Some(Phase::Failed) if phase_changed => NotificationTrigger::Failed,
+        _ if newly_awaiting => NotificationTrigger::AwaitingApproval,
+        _ if phase_changed => NotificationTrigger::PhaseChanged,
+        _ => return None,
+    };
+    Some(Notification {
+        trigger,
+        rollout: rollout.name_any(),
+        namespace: rollout.namespace().unwrap_or_default(),
+        strategy: current.strategy.map(|s| s.as_str().to_string()),
+        previous_phase: phase_name(previous_phase),
+        phase: phase_name(current.phase.as_ref()).unwrap_or_default(),
+        step: current.current_step_index,
+        weight: current.current_weight,
+        message: current.message.clone(),
+        error: (trigger == NotificationTrigger::Failed)
+            .then(|| FailureContext::for_failure(rollout, current)),
+        timestamp: now.to_rfc3339(),
+    })
+}
+
+/// Delivers notifications to one channel
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Kind of channel, for logs
+    fn kind(&self) -> &'static str;
+
+    /// Whether the notifier acts on `notification` (default: every one)
+    fn wants(&self, _notification: &Notification) -> bool {
+        true
+    }
+
+    /// Deliver one notification
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError>;
+}
+
+async fn post_json(
+    http: &reqwest::Client,
+    url: &str,
+    body: &serde_json::Value,
+    kind: &'static str,
+) -> Result<(), NotificationError> {
+    send_json(http.post(url), body, kind).await
+}
+
+/// Send `body` with `request`, failing on error statuses
+///
+/// The URL is left out of errors: for most chat webhooks it is the secret.
+pub(crate) async fn send_json(
+    request: reqwest::RequestBuilder,
+    body: &serde_json::Value,
+    kind: &'static str,
+) -> Result<(), NotificationError> {
+    request
+        .json(body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map(|_| ())
+        .map_err(|e| NotificationError::Delivery {
+            kind,
+            reason: e.without_url().to_string(),
+        })
+}
+
+/// Slack message for a notification
+pub fn slack_payload(notification: &Notification) -> serde_json::Value {
+    let icon = match notification.trigger {
+        NotificationTrigger::Failed => ":rotating_light:",
+        NotificationTrigger::AwaitingApproval => ":raised_hand:",
+        NotificationTrigger::PhaseChanged => ":arrows_counterclockwise:",
+    };
+    serde_json::json!({ "text": format!("{} {}", icon, notification.summary()) })
+}
+
+/// Microsoft Teams message card for a notification
+pub fn teams_payload(notification: &Notification) -> serde_json::Value {
+    let color = match notification.trigger {
+        NotificationTrigger::Failed => "D70000",
+        NotificationTrigger::AwaitingApproval => "FFA500",
+        NotificationTrigger::PhaseChanged => "0076D7",
+    };
+    let mut facts = vec![serde_json::json!({ "name": "Phase", "value": notification.phase })];
+    if let Some(step) = notification.step {
+        facts.push(serde_json::json!({ "name": "Step", "value": step.to_string() }));
+    }
+    if let Some(weight) = notification.weight {
+        facts.push(serde_json::json!({ "name": "Weight", "value": format!("{}%", weight) }));
+    }
+    serde_json::json!({
+        "@type": "MessageCard",
+        "@context": "https://schema.org/extensions",
+        "summary": notification.summary(),
+        "themeColor": color,
+        "title": format!("Rollout {}/{}", notification.namespace, notification.rollout),
+        "sections": [{ "text": notification.summary(), "facts": facts }],
+    })
+}
+
+/// Posts to a Slack incoming webhook
+pub struct SlackNotifier {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            url: url.into(),
+            http,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn kind(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        post_json(
+            &self.http,
+            &self.url,
+            &slack_payload(notification),
+            self.kind(),
+        )
+        .await
+    }
+}
+
+/// Posts to a Microsoft Teams incoming webhook
+pub struct TeamsNotifier {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl TeamsNotifier {
+    pub fn new(url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            url: url.into(),
+            http,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TeamsNotifier {
+    fn kind(&self) -> &'static str {
+        "teams"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        post_json(
+            &self.http,
+            &self.url,
+            &teams_payload(notification),
+            self.kind(),
+        )
+        .await
+    }
+}
+
+/// Posts the [`Notification`] as JSON
+pub struct WebhookNotifier {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            url: url.into(),
+            http,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn kind(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        let body = serde_json::to_value(notification).map_err(|e| NotificationError::Delivery {
+            kind: self.kind(),
+            reason: e.to_string(),
+        })?;
+        post_json(&self.http, &self.url, &body, self.kind()).await
+    }
+}
+
+/// The built-in notifier of a channel
+pub fn notifier_for(channel: &Channel, http: &reqwest::Client) -> Arc<dyn Notifier> {
+    match channel.kind {
+        ChannelKind::Slack => Arc::new(SlackNotifier::new(&channel.url, http.clone())),
+        ChannelKind::Teams => Arc::new(TeamsNotifier::new(&channel.url, http.clone())),
+        ChannelKind::Webhook => Arc::new(WebhookNotifier::new(&channel.url, http.clone())),
+    }
+}
+
+/// Read channels from ConfigMap data
+///
+/// Each entry is a YAML channel named after its key. Invalid entries are
+/// logged and skipped so that one typo does not silence every channel.
+pub fn parse_configmap_channels(data: &BTreeMap<String, String>) -> Vec<Channel> {
+    let mut channels = Vec::new();
+    for (name, value) in data {
+        match parse_channel(name, value) {
+            Ok(channel) => channels.push(channel),
+            Err(e) => warn!(error = %e, "Ignoring invalid notification channel"),
+        }
+    }
+    channels
+}
+
+fn parse_channel(name: &str, value: &str) -> Result<Channel, NotificationError> {
+    let invalid = |reason: String| NotificationError::InvalidChannel {
+        name: name.to_string(),
+        reason,
+    };
+    let mut channel: Channel = serde_yaml::from_str(value).map_err(|e| invalid(e.to_string()))?;
+    if !channel.url.starts_with("https://") && !channel.url.starts_with("http://") {
+        return Err(invalid("url must be http(s)".to_string()));
+    }
+    channel.name = name.to_string();
+    Ok(channel)
+}
+
+/// Channels a Rollout's annotations configure
+///
+/// Annotated URLs become channels named after their kind; subscribed names
+/// are looked up in `configured`. `notifications.kulta.io/on` applies to all
+/// of them.
+pub fn annotated_channels(rollout: &Rollout, configured: &[Channel]) -> Vec<Channel> {
+    let annotations = rollout.annotations();
+    let on: Vec<NotificationTrigger> = annotations
+        .get(TRIGGERS_ANNOTATION)
+        .map(|value| {
+            value
+                .split(',')
+                .filter(|v| !v.trim().is_empty())
+                .filter_map(|v| {
+                    let trigger = NotificationTrigger::parse(v);
+                    if trigger.is_none() {
+                        warn!(rollout = %rollout.name_any(), trigger = %v.trim(), "Ignoring unknown notification trigger");
+                    }
+                    trigger
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut channels: Vec<Channel> = [
+        (SLACK_ANNOTATION, ChannelKind::Slack),
+        (TEAMS_ANNOTATION, ChannelKind::Teams),
+        (WEBHOOK_ANNOTATION, ChannelKind::Webhook),
+    ]
+    .into_iter()
+    .filter_map(|(annotation, kind)| {
+        let url = annotations.get(annotation)?.trim();
+        (!url.is_empty()).then(|| Channel {
+            name: annotation.to_string(),
+            kind,
+            url: url.to_string(),
+            namespaces: Vec::new(),
+            on: Vec::new(),
+        })
+    })
+    .collect();
+    for name in annotations
+        .get(SUBSCRIBE_ANNOTATION)
+        .map(|value| value.split(',').map(str::trim).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| !name.is_empty())
+    {
+        match configured.iter().find(|channel| channel.name == name) {
+            Some(channel) => channels.push(channel.clone()),
+            None => {
+                warn!(rollout = %rollout.name_any(), channel = %name, "Subscribed notification channel not configured")
+            }
+        }
+    }
+    if !on.is_empty() {
+        for channel in &mut channels {
+            channel.on = on.clone();
+        }
+    }
+    channels
+}
+
+/// Channels a notification about `rollout` goes to, each once
+pub fn channels_for(
+    rollout: &Rollout,
+    configured: &[Channel],
+    trigger: NotificationTrigger,
+) -> Vec<Channel> {
+    let namespace = rollout.namespace().unwrap_or_default();
+    let mut channels = annotated_channels(rollout, configured);
+    channels.extend(
+        configured
+            .iter()
+            .filter(|channel| {
+                channel
+                    .namespaces
+                    .iter()
+                    .any(|ns| ns == ANY_NAMESPACE || *ns == namespace)
+            })
+            .cloned(),
+    );
+    let mut seen = Vec::new();
+    channels.retain(|channel| {
+        let key = (channel.kind, channel.url.clone());
+        if seen.contains(&key) {
+            return false;
+        }
+        seen.push(key);
+        channel.accepts(trigger)
+    });
+    channels
+}
+
+struct CachedChannels {
+    loaded_at: Instant,
+    channels: Vec<Channel>,
+}
+
+/// Notification channels of the controller (annotations only unless configured)
+#[derive(Default)]
+pub struct Notifications {
+    configmap: Option<(String, String)>,
+    http: Option<reqwest::Client>,
+    cache: Mutex<Option<CachedChannels>>,
+    /// Notifiers registered by embedders, sent every notification
+    registered: Vec<Arc<dyn Notifier>>,
+}
+
+impl Notifications {
+    /// Notifications that are never sent
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create notifications from environment variables
+    ///
+    /// Uses `KULTA_NOTIFICATIONS_CONFIGMAP` (`namespace/name`), and opens
+    /// incidents with the notifiers of [`incident_notifiers_from_env`].
+    pub fn from_env() -> Self {
+        let configmap = std::env::var("KULTA_NOTIFICATIONS_CONFIGMAP")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .and_then(|value| match parse_configmap_ref(&value) {
+                Ok(reference) => Some(reference),
+                Err(e) => {
+                    warn!(error = %e, "Ignoring KULTA_NOTIFICATIONS_CONFIGMAP");
+                    None
+                }
+            });
+        let mut notifications = Self::new(configmap);
+        if let Some(http) = &notifications.http {
+            let incidents = incident_notifiers_from_env(http);
+            notifications.registered.extend(incidents);
+        }
+        notifications
+    }
+
+    pub fn new(configmap: Option<(String, String)>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(NOTIFICATION_TIMEOUT)
+            .build()
+            .map_err(|e| warn!(error = %e, "Failed to build notification HTTP client"))
+            .ok();
+        Notifications {
+            configmap,
+            http,
+            cache: Mutex::new(None),
+            registered: Vec::new(),
+        }
+    }
+
+    /// Send every notification to `notifier` as well
+    pub fn register(&mut self, notifier: impl Notifier + 'static) {
+        self.registered.push(Arc::new(notifier));
+    }
+
+    /// Channels from the ConfigMap, read again at most once a minute
+    ///
+    /// When the ConfigMap can't be read the last known channels are kept.
+    async fn configured_channels(&self, client: &Client) -> Vec<Channel> {
+        let Some((namespace, name)) = &self.configmap else {
+            return Vec::new();
+        };
+        if let Ok(cache) = self.cache.lock() {
+            if let Some(cached) = cache.as_ref() {
+                if cached.loaded_at.elapsed() < CHANNEL_CACHE_TTL {
+                    return cached.channels.clone();
+                }
+            }
+        }
+
+        let api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
+        let channels = match api.get_opt(name).await {
+            Ok(Some(cm)) => parse_configmap_channels(&cm.data.unwrap_or_default()),
+            Ok(None) => {
+                warn!(configmap = %name, namespace = %namespace, "Notifications ConfigMap not found");
+                Vec::new()
+            }
+            Err(e) => {
+                warn!(error = ?e, configmap = %name, "Failed to read notifications ConfigMap");
+                return match self.cache.lock() {
+                    Ok(cache) => cache.as_ref().map(|c| c.channels.clone()),
+                    Err(_) => None,
+                }
+                .unwrap_or_default();
+            }
+        };
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = Some(CachedChannels {
+                loaded_at: Instant::now(),
+                channels: channels.clone(),
+            });
+        }
+        channels
+    }
+
+    /// Send a notification about `rollout` to each of its channels
+    ///
+    /// Failures are logged only.
+    pub async fn notify(&self, rollout: &Rollout, client: &Client, notification: &Notification) {
+        let mut notifiers: Vec<(String, Arc<dyn Notifier>)> = self
+            .registered
+            .iter()
+            .map(|notifier| (notifier.kind().to_string(), notifier.clone()))
+            .collect();
+        if let Some(http) = &self.http {
+            let configured = self.configured_channels(client).await;
+            notifiers.extend(
+                channels_for(rollout, &configured, notification.trigger)
+                    .into_iter()
+                    .map(|channel| {
+                        let notifier = notifier_for(&channel, http);
+                        (channel.name, notifier)
+                    }),
+            );
+        }
+        for (channel, notifier) in notifiers {
+            if !notifier.wants(notification) {
+                continue;
+            }
+            match notifier.send(notification).await {
+                Ok(()) => info!(
+                    rollout = %notification.rollout,
+                    channel = %channel,
+                    trigger = ?notification.trigger,
+                    "Notification delivered"
+                ),
+                Err(e) => warn!(
+                    error = %e,
+                    rollout = %notification.rollout,
+                    channel = %channel,
+                    "Failed to deliver notification"
+                ),
+            }
+        }
+    }
+}
+
+fn parse_configmap_ref(value: &str) -> Result<(String, String), NotificationError> {
+    match value.trim().split_once('/') {
+        Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {
+            Ok((namespace.to_string(), name.to_string()))
+        }
+        _ => Err(NotificationError::InvalidConfigMapRef(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(annotations: &[(&str, &str)]) -> Rollout {
+        let mut builder = RolloutBuilder::new("checkout")
+            .namespace("shop")
+            .replicas(3)
+            .label("app", "checkout")
+            .image("app", "checkout:2");
+        for (key, value) in annotations {
+            builder = builder.annotation(*key, *value);
+        }
+        builder
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "checkout", "canaryService": "checkout-canary",
+                    "steps": [
+                        { "setWeight": 10 },
+                        { "setWeight": 20, "pause": { "untilApproved": true } },
+                        { "setWeight": 50 }
+                    ]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn status(phase: Phase, step: i32) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(phase),
+            current_step_index: Some(step),
+            ..Default::default()
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_notification_triggers() {
+        let rollout = rollout(&[]);
+        let progressing = status(Phase::Progressing, 0);
+        let awaiting = status(Phase::Progressing, 1);
+
+        let changed = notification_for(&rollout, None, &progressing, at(0)).unwrap();
+        assert_eq!(changed.trigger, NotificationTrigger::PhaseChanged);
+        assert_eq!(
+            changed.summary(),
+            "Rollout shop/checkout New -> Progressing"
+        );
+        assert_eq!(
+            notification_for(&rollout, Some(&progressing), &progressing, at(1)),
+            None
+        );
+
+        let approval = notification_for(&rollout, Some(&progressing), &awaiting, at(2)).unwrap();
+        assert_eq!(approval.trigger, NotificationTrigger::AwaitingApproval);
+        assert_eq!(approval.step, Some(1));
+        assert_eq!(
+            notification_for(&rollout, Some(&awaiting), &awaiting, at(3)),
+            None
+        );
+
+        let failed = RolloutStatus {
+            message: Some("error-rate above 5".to_string()),
+            ..status(Phase::Failed, 1)
+        };
+        let notification = notification_for(&rollout, Some(&awaiting), &failed, at(4)).unwrap();
+        assert_eq!(notification.trigger, NotificationTrigger::Failed);
+        let error = notification.error.as_ref().unwrap();
+        assert!(error.what_failed.contains("checkout"));
+        assert_eq!(error.possible_causes[0], "error-rate above 5");
+        assert!(approval.error.is_none());
+        assert_eq!(
+            notification.summary(),
+            "Rollout shop/checkout failed: error-rate above 5"
+        );
+    }
+
+    #[test]
+    fn test_channels_from_annotations_and_configmap() {
+        let configured = parse_configmap_channels(&BTreeMap::from([
+            (
+                "team-slack".to_string(),
+                "type: slack\nurl: https://hooks.slack.com/services/T/B/X\non: [Failed]"
+                    .to_string(),
+            ),
+            (
+                "audit".to_string(),
+                "type: webhook\nurl: https://audit.internal/rollouts\nnamespaces: ['*']"
+                    .to_string(),
+            ),
+            (
+                "broken".to_string(),
+                "type: pager\nurl: https://pager".to_string(),
+            ),
+            (
+                "plain".to_string(),
+                "type: teams\nurl: ftp://teams".to_string(),
+            ),
+        ]));
+        let names: Vec<&str> = configured.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["audit", "team-slack"]);
+
+        let rollout = rollout(&[
+            (TEAMS_ANNOTATION, "https://example.webhook.office.com/x"),
+            (SUBSCRIBE_ANNOTATION, "team-slack, missing"),
+        ]);
+        let failed = channels_for(&rollout, &configured, NotificationTrigger::Failed);
+        let kinds: Vec<ChannelKind> = failed.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [ChannelKind::Teams, ChannelKind::Slack, ChannelKind::Webhook]
+        );
+        let changed = channels_for(&rollout, &configured, NotificationTrigger::PhaseChanged);
+        assert_eq!(changed.len(), 2);
+
+        // notifications.kulta.io/on overrides the triggers of annotated channels
+        let only_approvals = self::rollout(&[
+            (TEAMS_ANNOTATION, "https://example.webhook.office.com/x"),
+            (TRIGGERS_ANNOTATION, "awaitingapproval"),
+        ]);
+        assert!(channels_for(&only_approvals, &[], NotificationTrigger::Failed).is_empty());
+        assert_eq!(
+            channels_for(&only_approvals, &[], NotificationTrigger::AwaitingApproval).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_chat_payloads() {
+        let notification = notification_for(
+            &rollout(&[]),
+            Some(&status(Phase::Progressing, 0)),
+            &RolloutStatus {
+                current_weight: Some(20),
+                ..status(Phase::Progressing, 1)
+            },
+            at(0),
+        )
+        .unwrap();
+
+        let slack = slack_payload(&notification);
+        assert_eq!(
+            slack["text"],
+            ":raised_hand: Rollout shop/checkout awaits approval at step 1"
+        );
+        let teams = teams_payload(&notification);
+        assert_eq!(teams["@type"], "MessageCard");
+        assert_eq!(teams["sections"][0]["facts"][2]["value"], "20%");
+        let webhook = serde_json::to_value(&notification).unwrap();
+        assert_eq!(webhook["trigger"], "AwaitingApproval");
+        assert_eq!(webhook["previousPhase"], "Progressing");
+    }
+}
@@ -11,8 +11,14 @@
 //! Types are provided by the `false-protocol` crate — KULTA only contains
 //! the mapping logic from rollout state to occurrences.
 
+use crate::controller::cdevents_data::rollout_links;
 use crate::controller::clock::Clock;
-use crate::crd::rollout::{Phase, Recommendation, Rollout};
+use crate::controller::occurrence_sink::OccurrenceSink;
+use crate::controller::release_hooks::analysis_summary;
+use crate::crd::rollout::{
+    Phase, Recommendation, RecommendedAction, RollbackVerificationOutcome,
+    RollbackVerificationStatus, Rollout, RolloutStatus,
+};
 use chrono::{DateTime, Utc};
 use false_protocol::{Entity, Error as OccurrenceError, Occurrence, Outcome, Severity};
 use std::collections::HashMap;
@@ -41,14 +47,18 @@ fn phase_to_occurrence_suffix(_old_phase: Option<&Phase>, new_phase: &Phase) ->
 /// - "ab_testing" → "abtesting.rollout.*"
 /// - "simple" → "rolling.rollout.*"
 fn build_occurrence_type(strategy: &str, old_phase: Option<&Phase>, new_phase: &Phase) -> String {
-    let prefix = match strategy {
+    let suffix = phase_to_occurrence_suffix(old_phase, new_phase);
+    format!("{}.rollout.{}", strategy_prefix(strategy), suffix)
+}
+
+/// FALSE Protocol type prefix for a strategy name
+fn strategy_prefix(strategy: &str) -> &str {
+    match strategy {
         "blue_green" => "bluegreen",
         "ab_testing" => "abtesting",
         "simple" => "rolling",
         other => other, // "canary" passes through
-    };
-    let suffix = phase_to_occurrence_suffix(old_phase, new_phase);
-    format!("{}.rollout.{}", prefix, suffix)
+    }
 }
 
 /// Map phase transition to severity
@@ -72,8 +82,8 @@ fn phase_to_outcome(new_phase: &Phase) -> Outcome {
 
 /// Emit a FALSE Protocol occurrence for a rollout phase transition
 ///
-/// Writes the occurrence as JSON (one line per occurrence) to the directory
-/// specified by `KULTA_OCCURRENCE_DIR` env var (default: `/tmp/kulta`).
+/// Publishes the occurrence to `sink` (see
+/// [`occurrence_sink`](crate::controller::occurrence_sink)).
 /// Non-fatal: logs a warning on failure but never fails reconciliation.
 pub fn emit_occurrence(
     rollout: &Rollout,
@@ -81,6 +91,8 @@ pub fn emit_occurrence(
     new_phase: &Phase,
     strategy: &str,
     clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
 ) {
     let name = match rollout.metadata.name.as_deref() {
         Some(n) => n,
@@ -97,23 +109,202 @@ pub fn emit_occurrence(
         }
     };
     let now = clock.now();
-    let occurrence = match build_occurrence(rollout, old_phase, new_phase, strategy, now) {
-        Some(occ) => occ,
-        None => return,
-    };
+    let occurrence =
+        match build_occurrence(rollout, old_phase, new_phase, strategy, now, extensions) {
+            Some(occ) => occ,
+            None => return,
+        };
 
-    let json = match serde_json::to_string(&occurrence) {
-        Ok(j) => j,
-        Err(e) => {
-            warn!(error = %e, rollout = %name, namespace = %namespace,
-                "Failed to serialize FALSE Protocol occurrence");
-            return;
+    if let Err(e) = sink.publish(&occurrence) {
+        warn!(error = %e, sink = sink.name(), rollout = %name, namespace = %namespace,
+            "Failed to publish FALSE Protocol occurrence (non-fatal)");
+    }
+}
+
+/// Extra `data` block added to every occurrence
+///
+/// Lets integrators attach cross-tool correlation fields (e.g. a SYKLI
+/// pipeline run ID read from an annotation) without touching the occurrence
+/// builders. Register implementations on `Context::occurrence_extensions`.
+pub trait OccurrenceExtension: Send + Sync {
+    /// Key of the block in occurrence `data`
+    fn key(&self) -> &str;
+
+    /// Block for this Rollout, or `None` to leave it out
+    fn data(&self, rollout: &Rollout) -> Option<serde_json::Value>;
+}
+
+/// Built-in extension: the Rollout's `links.kulta.io/*` annotations
+/// (change tickets, approvals) under `links`
+struct LinksExtension;
+
+impl OccurrenceExtension for LinksExtension {
+    fn key(&self) -> &str {
+        "links"
+    }
+
+    fn data(&self, rollout: &Rollout) -> Option<serde_json::Value> {
+        let links = rollout_links(rollout);
+        (!links.is_empty()).then(|| serde_json::json!(links))
+    }
+}
+
+/// Extension copying annotations under a prefix into one data block
+///
+/// `AnnotationExtension::new("sykli", "sykli.false.systems/")` turns
+/// `sykli.false.systems/run-id: "r-42"` into `"sykli": {"run-id": "r-42"}`.
+pub struct AnnotationExtension {
+    key: String,
+    prefix: String,
+}
+
+impl AnnotationExtension {
+    pub fn new(key: impl Into<String>, prefix: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            prefix: prefix.into(),
         }
+    }
+}
+
+impl OccurrenceExtension for AnnotationExtension {
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn data(&self, rollout: &Rollout) -> Option<serde_json::Value> {
+        let fields: serde_json::Map<String, serde_json::Value> = rollout
+            .metadata
+            .annotations
+            .iter()
+            .flatten()
+            .filter_map(|(key, value)| {
+                let field = key.strip_prefix(&self.prefix)?;
+                (!field.is_empty()).then(|| (field.to_string(), value.clone().into()))
+            })
+            .collect();
+        (!fields.is_empty()).then_some(serde_json::Value::Object(fields))
+    }
+}
+
+/// Occurrence extensions registered by integrators, applied in order
+#[derive(Clone, Default)]
+pub struct OccurrenceExtensions(Vec<Arc<dyn OccurrenceExtension>>);
+
+impl OccurrenceExtensions {
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Add an extension; its block appears in every occurrence from now on
+    pub fn register(&mut self, extension: impl OccurrenceExtension + 'static) {
+        self.0.push(Arc::new(extension));
+    }
+
+    /// Insert the built-in and registered blocks into occurrence data
+    ///
+    /// Blocks never replace one already present (KULTA's own `rollout`,
+    /// `advisor` and `links`, or an earlier extension); the later one is
+    /// dropped with a warning.
+    fn apply(&self, data: &mut HashMap<String, serde_json::Value>, rollout: &Rollout) {
+        let builtin: [&dyn OccurrenceExtension; 1] = [&LinksExtension];
+        let registered = self.0.iter().map(|extension| extension.as_ref());
+        for extension in builtin.into_iter().chain(registered) {
+            let Some(block) = extension.data(rollout) else {
+                continue;
+            };
+            if data.contains_key(extension.key()) {
+                warn!(key = %extension.key(), "Occurrence extension key already in use, skipping");
+                continue;
+            }
+            data.insert(extension.key().to_string(), block);
+        }
+    }
+}
+
+impl std::fmt::Debug for OccurrenceExtensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|extension| extension.key()))
+            .finish()
+    }
+}
+
+/// Error block of a failed rollout's occurrence
+///
+/// `what_failed`, `possible_causes` and `suggested_fix` are derived from
+/// `status.message` and the traffic the rollout had reached. Also the body
+/// of incidents opened for the failure.
+pub fn failure_error(
+    rollout: &Rollout,
+    status: Option<&RolloutStatus>,
+    strategy: &str,
+) -> OccurrenceError {
+    let name = rollout.metadata.name.as_deref().unwrap_or("unknown");
+    let namespace = rollout.metadata.namespace.as_deref().unwrap_or("unknown");
+
+    let message = status
+        .and_then(|s| s.message.clone())
+        .unwrap_or_else(|| "Rollout failed".to_string());
+
+    let current_weight = status.and_then(|s| s.current_weight);
+    let current_step = status.and_then(|s| s.current_step_index);
+
+    // Build rich context: what_failed includes traffic context
+    let what_failed = match (current_weight, current_step) {
+        (Some(weight), Some(step)) => format!(
+            "{} for {} {} at step {}/{} ({}% traffic)",
+            message,
+            name,
+            strategy,
+            step + 1,
+            rollout
+                .spec
+                .strategy
+                .canary
+                .as_ref()
+                .map(|c| c.steps.len())
+                .unwrap_or(0),
+            weight,
+        ),
+        _ => format!("Rollout {} failed during {} deployment", name, strategy),
+    };
+
+    // Richer possible_causes based on failure message
+    let mut possible_causes = vec![message.clone()];
+    if message.contains("metrics exceeded") || message.contains("error rate") {
+        possible_causes.push(format!("New code path in {} handlers", name));
+        possible_causes.push("Downstream service degradation".to_string());
+    }
+    if message.contains("deadline exceeded") {
+        possible_causes.push("Pods failing readiness probes".to_string());
+        possible_causes.push("Image pull failures or resource constraints".to_string());
+    }
+
+    let suggested_fix = if message.contains("metrics exceeded") {
+        format!(
+            "Rollback {} to stable, check dependent service health, review recent changes",
+            name
+        )
+    } else {
+        format!(
+            "Check metrics and pod logs for {}, consider manual rollback",
+            name
+        )
     };
 
-    if let Err(e) = write_occurrence(&json) {
-        warn!(error = %e, rollout = %name, namespace = %namespace,
-            "Failed to write FALSE Protocol occurrence (non-fatal)");
+    OccurrenceError {
+        code: "ROLLOUT_FAILED".to_string(),
+        what_failed,
+        why_it_matters: Some(format!(
+            "Service {} in namespace {} may be serving degraded traffic to {}% of requests",
+            name,
+            namespace,
+            current_weight.unwrap_or(0),
+        )),
+        possible_causes,
+        suggested_fix: Some(suggested_fix),
+        ..Default::default()
     }
 }
 
@@ -128,6 +319,7 @@ fn build_occurrence(
     new_phase: &Phase,
     strategy: &str,
     now: DateTime<Utc>,
+    extensions: &OccurrenceExtensions,
 ) -> Option<Occurrence> {
     let name = rollout.metadata.name.as_deref().unwrap_or("unknown");
     let namespace = rollout.metadata.namespace.as_deref().unwrap_or("unknown");
@@ -150,76 +342,10 @@ fn build_occurrence(
             "phase": format!("{:?}", new_phase),
         }),
     );
+    extensions.apply(&mut data, rollout);
 
-    let error = if matches!(new_phase, Phase::Failed) {
-        let message = rollout
-            .status
-            .as_ref()
-            .and_then(|s| s.message.clone())
-            .unwrap_or_else(|| "Rollout failed".to_string());
-
-        let current_weight = rollout.status.as_ref().and_then(|s| s.current_weight);
-        let current_step = rollout.status.as_ref().and_then(|s| s.current_step_index);
-
-        // Build rich context: what_failed includes traffic context
-        let what_failed = match (current_weight, current_step) {
-            (Some(weight), Some(step)) => format!(
-                "{} for {} {} at step {}/{} ({}% traffic)",
-                message,
-                name,
-                strategy,
-                step + 1,
-                rollout
-                    .spec
-                    .strategy
-                    .canary
-                    .as_ref()
-                    .map(|c| c.steps.len())
-                    .unwrap_or(0),
-                weight,
-            ),
-            _ => format!("Rollout {} failed during {} deployment", name, strategy),
-        };
-
-        // Richer possible_causes based on failure message
-        let mut possible_causes = vec![message.clone()];
-        if message.contains("metrics exceeded") || message.contains("error rate") {
-            possible_causes.push(format!("New code path in {} handlers", name));
-            possible_causes.push("Downstream service degradation".to_string());
-        }
-        if message.contains("deadline exceeded") {
-            possible_causes.push("Pods failing readiness probes".to_string());
-            possible_causes.push("Image pull failures or resource constraints".to_string());
-        }
-
-        let suggested_fix = if message.contains("metrics exceeded") {
-            format!(
-                "Rollback {} to stable, check dependent service health, review recent changes",
-                name
-            )
-        } else {
-            format!(
-                "Check metrics and pod logs for {}, consider manual rollback",
-                name
-            )
-        };
-
-        Some(OccurrenceError {
-            code: "ROLLOUT_FAILED".to_string(),
-            what_failed,
-            why_it_matters: Some(format!(
-                "Service {} in namespace {} may be serving degraded traffic to {}% of requests",
-                name,
-                namespace,
-                current_weight.unwrap_or(0),
-            )),
-            possible_causes,
-            suggested_fix: Some(suggested_fix),
-            ..Default::default()
-        })
-    } else {
-        None
-    };
+    let error = matches!(new_phase, Phase::Failed)
+        .then(|| failure_error(rollout, rollout.status.as_ref(), strategy));
 
     let mut entity = Entity::from_k8s("rollout", uid, name, namespace, resource_version);
     entity.observed_at = now;
@@ -257,44 +383,85 @@ fn build_occurrence(
     Some(occ)
 }
 
-/// Get the occurrence output directory.
+/// Whether the advisor contradicts the threshold decision
 ///
-/// Uses `KULTA_OCCURRENCE_DIR` env var if set, otherwise defaults to `/tmp/kulta`.
-fn occurrence_dir() -> std::path::PathBuf {
-    std::env::var("KULTA_OCCURRENCE_DIR")
-        .map(std::path::PathBuf::from)
-        .unwrap_or_else(|_| std::path::PathBuf::from("/tmp/kulta"))
+/// A Rollback recommendation while thresholds are healthy, or Continue/Advance
+/// while they are not. Pause is cautious either way and never counts.
+pub fn advisor_disagrees(recommendation: &Recommendation, threshold_healthy: bool) -> bool {
+    match recommendation.action {
+        RecommendedAction::Rollback => threshold_healthy,
+        RecommendedAction::Continue | RecommendedAction::Advance { .. } => !threshold_healthy,
+        RecommendedAction::Pause => false,
+    }
 }
 
-/// Maximum occurrence file size (10 MB). Truncated when exceeded.
-const MAX_OCCURRENCE_FILE_BYTES: u64 = 10 * 1024 * 1024;
-
-/// Write occurrence JSON to file (one JSON line per occurrence)
+/// Build an advisor occurrence of the given suffix and severity
 ///
-/// Truncates the file when it exceeds 10 MB to prevent unbounded growth.
-fn write_occurrence(json: &str) -> std::io::Result<()> {
-    use std::io::Write;
-
-    let dir = occurrence_dir();
-    std::fs::create_dir_all(&dir)?;
+/// Returns None when the Rollout has no name/namespace or the occurrence
+/// can't be constructed.
+fn build_advisor_occurrence(
+    rollout: &Rollout,
+    strategy: &str,
+    recommendation: &Recommendation,
+    threshold_healthy: bool,
+    suffix: &str,
+    severity: Severity,
+    now: DateTime<Utc>,
+    extensions: &OccurrenceExtensions,
+) -> Option<Occurrence> {
+    let name = rollout.metadata.name.as_deref()?;
+    let namespace = rollout.metadata.namespace.as_deref()?;
+    let uid = rollout.metadata.uid.as_deref().unwrap_or("");
+    let resource_version = rollout.metadata.resource_version.as_deref().unwrap_or("0");
 
-    let file_path = dir.join("occurrence.json");
+    let occurrence_type = format!("{}.advisor.{}", strategy_prefix(strategy), suffix);
 
-    // Truncate if file exceeds size limit to prevent unbounded growth
-    if let Ok(metadata) = std::fs::metadata(&file_path) {
-        if metadata.len() > MAX_OCCURRENCE_FILE_BYTES {
-            warn!("Occurrence file exceeds 10MB, truncating");
-            std::fs::write(&file_path, "")?;
+    let mut occ = match Occurrence::new("kulta", &occurrence_type) {
+        Ok(o) => o,
+        Err(errs) => {
+            warn!(errors = ?errs, "Failed to construct advisor occurrence (non-fatal)");
+            return None;
         }
+    };
+
+    let mut data = HashMap::new();
+    data.insert(
+        "advisor".to_string(),
+        serde_json::json!({
+            "action": recommendation.action,
+            "confidence": recommendation.confidence,
+            "reasoning": recommendation.reasoning,
+            "threshold_healthy": threshold_healthy,
+            "threshold_prevails": true,
+        }),
+    );
+    extensions.apply(&mut data, rollout);
+
+    let mut entity = Entity::from_k8s("rollout", uid, name, namespace, resource_version);
+    entity.observed_at = now;
+
+    occ.timestamp = now;
+    occ = occ
+        .severity(severity)
+        .outcome(Outcome::InProgress)
+        .in_namespace(namespace)
+        .correlate("deployment", name)
+        .correlate("namespace", namespace)
+        .with_entity(entity)
+        .with_data(data);
+
+    if let Ok(cluster) = std::env::var("KULTA_CLUSTER_NAME") {
+        occ = occ.in_cluster(&cluster);
     }
 
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&file_path)?;
+    Some(occ)
+}
 
-    writeln!(file, "{}", json)?;
-    Ok(())
+/// Publish an occurrence to `sink`, logging failures
+fn publish(sink: &dyn OccurrenceSink, occ: &Occurrence, what: &str) {
+    if let Err(e) = sink.publish(occ) {
+        warn!(error = %e, sink = sink.name(), "Failed to publish {} occurrence (non-fatal)", what);
+    }
 }
 
 /// Emit a FALSE Protocol occurrence for an advisor consultation (Level 2+)
@@ -307,46 +474,209 @@ pub fn emit_advisor_occurrence(
     recommendation: &Recommendation,
     threshold_healthy: bool,
     clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
 ) {
-    let name = match rollout.metadata.name.as_deref() {
-        Some(n) => n,
-        None => return,
-    };
-    let namespace = match rollout.metadata.namespace.as_deref() {
-        Some(ns) => ns,
-        None => return,
-    };
+    if let Some(occ) = build_advisor_occurrence(
+        rollout,
+        strategy,
+        recommendation,
+        threshold_healthy,
+        "recommendation",
+        Severity::Info,
+        clock.now(),
+        extensions,
+    ) {
+        publish(sink, &occ, "advisor");
+    }
+}
+
+/// Emit a Warning occurrence when the advisor contradicts the thresholds
+///
+/// Emits `{strategy}.advisor.disagreement`. The threshold decision still
+/// prevails; the event flags a possible degradation the thresholds miss (or
+/// a noisy advisor) for AHTI and on-call to look into.
+pub fn emit_advisor_disagreement_occurrence(
+    rollout: &Rollout,
+    strategy: &str,
+    recommendation: &Recommendation,
+    threshold_healthy: bool,
+    clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
+) {
+    if let Some(occ) = build_advisor_occurrence(
+        rollout,
+        strategy,
+        recommendation,
+        threshold_healthy,
+        "disagreement",
+        Severity::Warning,
+        clock.now(),
+        extensions,
+    ) {
+        publish(sink, &occ, "advisor");
+    }
+}
+
+/// Report an advisor recommendation, and any disagreement with the thresholds
+///
+/// Runs for every recommendation, including ones the guardrail policy then
+/// rejects: a Continue while metrics fail is exactly the disagreement worth
+/// flagging, whether or not the advisor may act on it.
+pub fn report_advisor_recommendation(
+    rollout: &Rollout,
+    strategy: &str,
+    recommendation: &Recommendation,
+    threshold_healthy: bool,
+    clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
+) {
+    emit_advisor_occurrence(
+        rollout,
+        strategy,
+        recommendation,
+        threshold_healthy,
+        clock,
+        extensions,
+        sink,
+    );
+    if advisor_disagrees(recommendation, threshold_healthy) {
+        warn!(
+            rollout = ?rollout.metadata.name,
+            advisor_action = ?recommendation.action,
+            threshold_healthy,
+            "Advisor disagrees with threshold decision"
+        );
+        emit_advisor_disagreement_occurrence(
+            rollout,
+            strategy,
+            recommendation,
+            threshold_healthy,
+            clock,
+            extensions,
+            sink,
+        );
+    }
+}
+
+/// Build the terminal `{strategy}.rollout.removed` occurrence
+///
+/// Returns None when the Rollout has no name/namespace or the occurrence
+/// can't be constructed.
+fn build_removed_occurrence(
+    rollout: &Rollout,
+    strategy: &str,
+    now: DateTime<Utc>,
+    extensions: &OccurrenceExtensions,
+) -> Option<Occurrence> {
+    let name = rollout.metadata.name.as_deref()?;
+    let namespace = rollout.metadata.namespace.as_deref()?;
     let uid = rollout.metadata.uid.as_deref().unwrap_or("");
     let resource_version = rollout.metadata.resource_version.as_deref().unwrap_or("0");
-    let now = clock.now();
 
-    let prefix = match strategy {
-        "blue_green" => "bluegreen",
-        "ab_testing" => "abtesting",
-        "simple" => "rolling",
-        other => other,
+    let occurrence_type = format!("{}.rollout.removed", strategy_prefix(strategy));
+    let mut occ = match Occurrence::new("kulta", &occurrence_type) {
+        Ok(o) => o,
+        Err(errs) => {
+            warn!(errors = ?errs, "Failed to construct removal occurrence (non-fatal)");
+            return None;
+        }
     };
-    let occurrence_type = format!("{}.advisor.recommendation", prefix);
 
+    let status = rollout.status.as_ref();
+    let mut data = HashMap::new();
+    data.insert(
+        "rollout".to_string(),
+        serde_json::json!({
+            "name": name,
+            "namespace": namespace,
+            "strategy": strategy,
+            "replicas": rollout.spec.replicas,
+            "current_weight": status.and_then(|s| s.current_weight),
+            "phase": status.and_then(|s| s.phase.as_ref()).map(|p| format!("{:?}", p)),
+        }),
+    );
+    extensions.apply(&mut data, rollout);
+
+    let mut entity = Entity::from_k8s("rollout", uid, name, namespace, resource_version);
+    entity.observed_at = now;
+
+    occ.timestamp = now;
+    occ = occ
+        .severity(Severity::Info)
+        .outcome(Outcome::Success)
+        .in_namespace(namespace)
+        .correlate("deployment", name)
+        .correlate("namespace", namespace)
+        .with_entity(entity)
+        .with_data(data);
+
+    if let Ok(cluster) = std::env::var("KULTA_CLUSTER_NAME") {
+        occ = occ.in_cluster(&cluster);
+    }
+
+    Some(occ)
+}
+
+/// Emit the terminal occurrence for a deleted Rollout
+///
+/// Emits `{strategy}.rollout.removed` once its resources are torn down.
+pub fn emit_removed_occurrence(
+    rollout: &Rollout,
+    strategy: &str,
+    clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
+) {
+    let Some(occ) = build_removed_occurrence(rollout, strategy, clock.now(), extensions) else {
+        return;
+    };
+    publish(sink, &occ, "removal");
+}
+
+/// Build a `{strategy}.rollout.heartbeat` occurrence
+///
+/// Returns None when the Rollout has no name/namespace or the occurrence
+/// can't be constructed.
+fn build_heartbeat_occurrence(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    strategy: &str,
+    now: DateTime<Utc>,
+    extensions: &OccurrenceExtensions,
+) -> Option<Occurrence> {
+    let name = rollout.metadata.name.as_deref()?;
+    let namespace = rollout.metadata.namespace.as_deref()?;
+    let uid = rollout.metadata.uid.as_deref().unwrap_or("");
+    let resource_version = rollout.metadata.resource_version.as_deref().unwrap_or("0");
+
+    let occurrence_type = format!("{}.rollout.heartbeat", strategy_prefix(strategy));
     let mut occ = match Occurrence::new("kulta", &occurrence_type) {
         Ok(o) => o,
         Err(errs) => {
-            warn!(errors = ?errs, "Failed to construct advisor occurrence (non-fatal)");
-            return;
+            warn!(errors = ?errs, "Failed to construct heartbeat occurrence (non-fatal)");
+            return None;
         }
     };
 
     let mut data = HashMap::new();
     data.insert(
-        "advisor".to_string(),
+        "rollout".to_string(),
         serde_json::json!({
-            "action": recommendation.action,
-            "confidence": recommendation.confidence,
-            "reasoning": recommendation.reasoning,
-            "threshold_healthy": threshold_healthy,
-            "threshold_prevails": true,
+            "name": name,
+            "namespace": namespace,
+            "strategy": strategy,
+            "replicas": rollout.spec.replicas,
+            "current_weight": status.current_weight,
+            "current_step": status.current_step_index,
+            "phase": status.phase.as_ref().map(|p| format!("{:?}", p)),
+            "progress_started_at": status.progress_started_at,
+            "analysis": analysis_summary(status),
         }),
     );
+    extensions.apply(&mut data, rollout);
 
     let mut entity = Entity::from_k8s("rollout", uid, name, namespace, resource_version);
     entity.observed_at = now;
@@ -365,17 +695,125 @@ pub fn emit_advisor_occurrence(
         occ = occ.in_cluster(&cluster);
     }
 
-    let json = match serde_json::to_string(&occ) {
-        Ok(j) => j,
-        Err(e) => {
-            warn!(error = %e, "Failed to serialize advisor occurrence (non-fatal)");
-            return;
+    Some(occ)
+}
+
+/// Emit a heartbeat occurrence for a Rollout in a long-running phase
+///
+/// Emits `{strategy}.rollout.heartbeat` so a rollout whose phase never
+/// changes still shows up.
+pub fn emit_heartbeat_occurrence(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    strategy: &str,
+    clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
+) {
+    let Some(occ) = build_heartbeat_occurrence(rollout, status, strategy, clock.now(), extensions)
+    else {
+        return;
+    };
+    publish(sink, &occ, "heartbeat");
+}
+
+/// Build a `{strategy}.rollback.verified` occurrence
+///
+/// Severity follows the verdict: an `Unhealthy` stable revision is an
+/// error, since rolling back didn't help.
+///
+/// Returns None when the Rollout has no name/namespace or the occurrence
+/// can't be constructed.
+fn build_rollback_verified_occurrence(
+    rollout: &Rollout,
+    verification: &RollbackVerificationStatus,
+    strategy: &str,
+    now: DateTime<Utc>,
+    extensions: &OccurrenceExtensions,
+) -> Option<Occurrence> {
+    let name = rollout.metadata.name.as_deref()?;
+    let namespace = rollout.metadata.namespace.as_deref()?;
+    let uid = rollout.metadata.uid.as_deref().unwrap_or("");
+    let resource_version = rollout.metadata.resource_version.as_deref().unwrap_or("0");
+
+    let occurrence_type = format!("{}.rollback.verified", strategy_prefix(strategy));
+    let mut occ = match Occurrence::new("kulta", &occurrence_type) {
+        Ok(o) => o,
+        Err(errs) => {
+            warn!(errors = ?errs, "Failed to construct rollback verification occurrence (non-fatal)");
+            return None;
         }
     };
 
-    if let Err(e) = write_occurrence(&json) {
-        warn!(error = %e, "Failed to write advisor occurrence (non-fatal)");
+    let mut data = HashMap::new();
+    data.insert(
+        "rollout".to_string(),
+        serde_json::json!({
+            "name": name,
+            "namespace": namespace,
+            "strategy": strategy,
+            "replicas": rollout.spec.replicas,
+        }),
+    );
+    data.insert(
+        "rollback_verification".to_string(),
+        serde_json::json!({
+            "outcome": verification.outcome,
+            "rolled_back_at": verification.rolled_back_at,
+            "verified_at": verification.verified_at,
+            "failing_metrics": verification.failing_metrics,
+            "message": verification.message,
+        }),
+    );
+    extensions.apply(&mut data, rollout);
+
+    let mut entity = Entity::from_k8s("rollout", uid, name, namespace, resource_version);
+    entity.observed_at = now;
+
+    let (severity, outcome) = match verification.outcome {
+        RollbackVerificationOutcome::Recovered => (Severity::Info, Outcome::Success),
+        RollbackVerificationOutcome::Unhealthy => (Severity::Error, Outcome::Failure),
+        RollbackVerificationOutcome::Inconclusive => (Severity::Warning, Outcome::InProgress),
+    };
+    occ.timestamp = now;
+    occ = occ
+        .severity(severity)
+        .outcome(outcome)
+        .in_namespace(namespace)
+        .correlate("deployment", name)
+        .correlate("namespace", namespace)
+        .with_entity(entity)
+        .with_data(data);
+
+    if let Ok(cluster) = std::env::var("KULTA_CLUSTER_NAME") {
+        occ = occ.in_cluster(&cluster);
     }
+
+    Some(occ)
+}
+
+/// Emit the verdict on the stable revision after a canary rollback
+///
+/// Emits `{strategy}.rollback.verified`, telling a bad canary (`Recovered`)
+/// from a system that is unhealthy anyway (`Unhealthy`).
+pub fn emit_rollback_verified_occurrence(
+    rollout: &Rollout,
+    verification: &RollbackVerificationStatus,
+    strategy: &str,
+    clock: &Arc<dyn Clock>,
+    extensions: &OccurrenceExtensions,
+    sink: &dyn OccurrenceSink,
+) {
+    let Some(occ) = build_rollback_verified_occurrence(
+        rollout,
+        verification,
+        strategy,
+        clock.now(),
+        extensions,
+    ) else {
+        return;
+    };
+    publish(sink, &occ, "rollback verification");
 }
 
 #[cfg(test)]
@@ -383,6 +821,7 @@ pub fn emit_advisor_occurrence(
 mod tests {
     use super::*;
     use crate::controller::clock::MockClock;
+    use crate::controller::occurrence_sink::MockOccurrenceSink;
     use crate::crd::rollout::{Rollout, RolloutSpec, RolloutStrategy};
     use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
@@ -421,6 +860,11 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: None,
         }
@@ -479,7 +923,15 @@ mod tests {
         let rollout = test_rollout();
         let now = Utc::now();
 
-        let occ = build_occurrence(&rollout, None, &Phase::Progressing, "canary", now).unwrap();
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
 
         assert_eq!(occ.source, "kulta");
         assert_eq!(occ.occurrence_type, "canary.rollout.progressing");
@@ -503,6 +955,7 @@ mod tests {
             &Phase::Failed,
             "canary",
             now,
+            &OccurrenceExtensions::new(),
         )
         .unwrap();
 
@@ -521,7 +974,15 @@ mod tests {
         let rollout = test_rollout();
         let now = Utc::now();
 
-        let occ = build_occurrence(&rollout, None, &Phase::Completed, "simple", now).unwrap();
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Completed,
+            "simple",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
         let json = serde_json::to_string(&occ).expect("Should serialize");
 
         assert!(json.contains("\"source\":\"kulta\""));
@@ -535,12 +996,120 @@ mod tests {
         assert!(!json.contains("\"reasoning\""));
     }
 
+    #[test]
+    fn test_build_occurrence_includes_links() {
+        let mut rollout = test_rollout();
+        let now = Utc::now();
+
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(!json.contains("\"links\""));
+
+        rollout.metadata.annotations = Some(
+            [(
+                "links.kulta.io/change-ticket".to_string(),
+                "CHG-1234".to_string(),
+            )]
+            .into(),
+        );
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(json.contains("\"links\":{\"change-ticket\":\"CHG-1234\"}"));
+    }
+
+    struct RunIdExtension;
+
+    impl OccurrenceExtension for RunIdExtension {
+        fn key(&self) -> &str {
+            "sykli"
+        }
+
+        fn data(&self, rollout: &Rollout) -> Option<serde_json::Value> {
+            let run_id = rollout
+                .metadata
+                .annotations
+                .as_ref()?
+                .get("sykli.false.systems/run-id")?;
+            Some(serde_json::json!({ "run_id": run_id }))
+        }
+    }
+
+    #[test]
+    fn test_registered_extensions_add_data_blocks() {
+        let mut rollout = test_rollout();
+        rollout.metadata.annotations = Some(
+            [
+                ("sykli.false.systems/run-id".to_string(), "r-42".to_string()),
+                ("team.example.com/owner".to_string(), "payments".to_string()),
+            ]
+            .into(),
+        );
+        let mut extensions = OccurrenceExtensions::new();
+        extensions.register(RunIdExtension);
+        extensions.register(AnnotationExtension::new("team", "team.example.com/"));
+        // Built-in keys can't be replaced
+        extensions.register(AnnotationExtension::new("rollout", "team.example.com/"));
+
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            Utc::now(),
+            &extensions,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&occ).unwrap();
+
+        assert!(json.contains("\"sykli\":{\"run_id\":\"r-42\"}"));
+        assert!(json.contains("\"team\":{\"owner\":\"payments\"}"));
+        assert!(!json.contains("\"rollout\":{\"owner\""));
+
+        // Extensions without data for this Rollout add nothing
+        let occ = build_occurrence(
+            &test_rollout(),
+            None,
+            &Phase::Progressing,
+            "canary",
+            Utc::now(),
+            &extensions,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(!json.contains("\"sykli\""));
+        assert!(!json.contains("\"team\""));
+    }
+
     #[test]
     fn test_occurrence_id_is_ulid() {
         let rollout = test_rollout();
         let now = Utc::now();
 
-        let occ = build_occurrence(&rollout, None, &Phase::Progressing, "canary", now).unwrap();
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
 
         // ULID is 26 characters, uppercase alphanumeric
         assert_eq!(occ.id.len(), 26);
@@ -552,8 +1121,20 @@ mod tests {
         let fixed_time = Utc::now();
         let clock: Arc<dyn Clock> = Arc::new(MockClock::new(fixed_time));
 
-        // Just verify it doesn't panic - file write may fail in test env
-        emit_occurrence(&rollout, None, &Phase::Progressing, "canary", &clock);
+        let sink = MockOccurrenceSink::new();
+        emit_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            &clock,
+            &OccurrenceExtensions::new(),
+            &sink,
+        );
+
+        let published = sink.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0]["type"], "canary.rollout.progressing");
     }
 
     #[test]
@@ -562,7 +1143,15 @@ mod tests {
         rollout.metadata = ObjectMeta::default();
         let now = Utc::now();
 
-        let occ = build_occurrence(&rollout, None, &Phase::Progressing, "canary", now).unwrap();
+        let occ = build_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            now,
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
 
         assert_eq!(occ.context.entities[0].name, "unknown");
         assert_eq!(occ.context.namespace.as_deref(), Some("unknown"));
@@ -576,8 +1165,18 @@ mod tests {
         rollout.metadata.name = None;
         let clock: Arc<dyn Clock> = Arc::new(MockClock::new(Utc::now()));
 
-        // Should not panic — just logs a warning and returns
-        emit_occurrence(&rollout, None, &Phase::Progressing, "canary", &clock);
+        // Should not panic — logs a warning and publishes nothing
+        let sink = MockOccurrenceSink::new();
+        emit_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            &clock,
+            &OccurrenceExtensions::new(),
+            &sink,
+        );
+        assert!(sink.published().is_empty());
     }
 
     #[test]
@@ -586,8 +1185,18 @@ mod tests {
         rollout.metadata.namespace = None;
         let clock: Arc<dyn Clock> = Arc::new(MockClock::new(Utc::now()));
 
-        // Should not panic — logs warning and returns
-        emit_occurrence(&rollout, None, &Phase::Progressing, "canary", &clock);
+        // Should not panic — logs a warning and publishes nothing
+        let sink = MockOccurrenceSink::new();
+        emit_occurrence(
+            &rollout,
+            None,
+            &Phase::Progressing,
+            "canary",
+            &clock,
+            &OccurrenceExtensions::new(),
+            &sink,
+        );
+        assert!(sink.published().is_empty());
     }
 
     #[test]
@@ -621,6 +1230,7 @@ mod tests {
             &Phase::Failed,
             "canary",
             now,
+            &OccurrenceExtensions::new(),
         )
         .unwrap();
 
@@ -644,18 +1254,40 @@ mod tests {
                     CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     },
                     CanaryStep {
                         set_weight: Some(50),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     },
                     CanaryStep {
                         set_weight: Some(100),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     },
                 ],
                 traffic_routing: None,
                 analysis: None,
+                steps_from: None,
+                gates: vec![],
+                baseline: None,
+                rollback_verification: None,
             }),
             blue_green: None,
             simple: None,
@@ -676,6 +1308,7 @@ mod tests {
             &Phase::Failed,
             "canary",
             now,
+            &OccurrenceExtensions::new(),
         )
         .unwrap();
 
@@ -714,6 +1347,7 @@ mod tests {
             &Phase::Failed,
             "canary",
             now,
+            &OccurrenceExtensions::new(),
         )
         .unwrap();
 
@@ -735,9 +1369,185 @@ mod tests {
             action: RecommendedAction::Continue,
             confidence: 0.85,
             reasoning: "metrics look healthy, no anomalies detected".into(),
+            plan: None,
         };
 
-        // Should not panic even if file write fails in test env
-        emit_advisor_occurrence(&rollout, "canary", &recommendation, true, &clock);
+        let sink = MockOccurrenceSink::new();
+        emit_advisor_occurrence(
+            &rollout,
+            "canary",
+            &recommendation,
+            true,
+            &clock,
+            &OccurrenceExtensions::new(),
+            &sink,
+        );
+        assert_eq!(sink.published()[0]["type"], "canary.advisor.recommendation");
+    }
+
+    #[test]
+    fn test_advisor_disagrees() {
+        use crate::crd::rollout::{Recommendation, RecommendedAction};
+
+        let rec = |action| Recommendation {
+            action,
+            confidence: 0.9,
+            reasoning: String::new(),
+            plan: None,
+        };
+
+        assert!(advisor_disagrees(&rec(RecommendedAction::Rollback), true));
+        assert!(!advisor_disagrees(&rec(RecommendedAction::Rollback), false));
+        assert!(advisor_disagrees(&rec(RecommendedAction::Continue), false));
+        assert!(advisor_disagrees(
+            &rec(RecommendedAction::Advance { to_weight: 50 }),
+            false
+        ));
+        assert!(!advisor_disagrees(&rec(RecommendedAction::Continue), true));
+        assert!(!advisor_disagrees(&rec(RecommendedAction::Pause), true));
+        assert!(!advisor_disagrees(&rec(RecommendedAction::Pause), false));
+    }
+
+    #[test]
+    fn test_continue_while_metrics_fail_is_reported_as_disagreement() {
+        use crate::controller::guardrails::GuardrailPolicy;
+        use crate::crd::rollout::{Recommendation, RecommendedAction};
+
+        let rollout = test_rollout();
+        let clock: Arc<dyn Clock> = Arc::new(MockClock::new(Utc::now()));
+        let recommendation = Recommendation {
+            action: RecommendedAction::Continue,
+            confidence: 0.9,
+            reasoning: "error spike is a known batch job".into(),
+            plan: None,
+        };
+        // The guardrails never let the advisor act on it...
+        assert!(GuardrailPolicy::default()
+            .check(&recommendation, 20, false)
+            .is_err());
+
+        // ...but it is still reported
+        let sink = MockOccurrenceSink::new();
+        report_advisor_recommendation(
+            &rollout,
+            "canary",
+            &recommendation,
+            false,
+            &clock,
+            &OccurrenceExtensions::new(),
+            &sink,
+        );
+        let types: Vec<_> = sink
+            .published()
+            .iter()
+            .map(|occ| occ["type"].clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                "canary.advisor.recommendation",
+                "canary.advisor.disagreement"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_advisor_disagreement_occurrence() {
+        use crate::crd::rollout::{Recommendation, RecommendedAction};
+
+        let rollout = test_rollout();
+        let recommendation = Recommendation {
+            action: RecommendedAction::Rollback,
+            confidence: 0.8,
+            reasoning: "p99 latency creeping up".into(),
+            plan: None,
+        };
+
+        let occ = build_advisor_occurrence(
+            &rollout,
+            "blue_green",
+            &recommendation,
+            true,
+            "disagreement",
+            Severity::Warning,
+            Utc::now(),
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
+
+        assert_eq!(occ.occurrence_type, "bluegreen.advisor.disagreement");
+        assert_eq!(occ.severity, Severity::Warning);
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(json.contains("\"action\":\"Rollback\""));
+        assert!(json.contains("\"threshold_healthy\":true"));
+    }
+
+    #[test]
+    fn test_build_removed_occurrence() {
+        let rollout = test_rollout();
+
+        let occ =
+            build_removed_occurrence(&rollout, "canary", Utc::now(), &OccurrenceExtensions::new())
+                .unwrap();
+
+        assert_eq!(occ.occurrence_type, "canary.rollout.removed");
+        assert_eq!(occ.severity, Severity::Info);
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(json.contains("\"name\":\"my-app\""));
+    }
+
+    #[test]
+    fn test_build_rollback_verified_occurrence() {
+        let rollout = test_rollout();
+        let verification = RollbackVerificationStatus {
+            rolled_back_at: "2026-01-01T00:00:00+00:00".to_string(),
+            verified_at: "2026-01-01T00:02:00+00:00".to_string(),
+            outcome: RollbackVerificationOutcome::Unhealthy,
+            failing_metrics: vec!["error-rate".to_string()],
+            message: None,
+        };
+
+        let occ = build_rollback_verified_occurrence(
+            &rollout,
+            &verification,
+            "canary",
+            Utc::now(),
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
+
+        assert_eq!(occ.occurrence_type, "canary.rollback.verified");
+        assert_eq!(occ.severity, Severity::Error);
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(json.contains("\"outcome\":\"Unhealthy\""));
+        assert!(json.contains("\"failing_metrics\":[\"error-rate\"]"));
+    }
+
+    #[test]
+    fn test_build_heartbeat_occurrence() {
+        let rollout = test_rollout();
+        let status = RolloutStatus {
+            phase: Some(Phase::Progressing),
+            current_weight: Some(20),
+            current_step_index: Some(1),
+            ..Default::default()
+        };
+
+        let occ = build_heartbeat_occurrence(
+            &rollout,
+            &status,
+            "canary",
+            Utc::now(),
+            &OccurrenceExtensions::new(),
+        )
+        .unwrap();
+
+        assert_eq!(occ.occurrence_type, "canary.rollout.heartbeat");
+        assert_eq!(occ.severity, Severity::Info);
+        assert_eq!(occ.outcome, Outcome::InProgress);
+        let json = serde_json::to_string(&occ).unwrap();
+        assert!(json.contains("\"current_weight\":20"));
+        assert!(json.contains("\"phase\":\"Progressing\""));
+        assert!(json.contains("\"analysis\":{"));
     }
 }
@@ -0,0 +1,511 @@
+//! Destinations for FALSE Protocol occurrences
+//!
+//! Occurrences go to every sink listed in `KULTA_OCCURRENCE_SINKS`
+//! (default: `file`):
+//!
+//! - `file`: one JSON line per occurrence appended to
+//!   `$KULTA_OCCURRENCE_DIR/occurrence.json` (default dir `/tmp/kulta`),
+//!   truncated past 10 MB
+//! - `nats`: published to NATS JetStream so AHTI and other consumers can tail
+//!   occurrences as they happen
+//!
+//! The NATS sink publishes each occurrence to
+//! `{KULTA_NATS_SUBJECT_PREFIX}.{occurrence type}` (e.g.
+//! `kulta.occurrences.canary.rollout.failed`) with the occurrence id as
+//! `Nats-Msg-Id`, so JetStream drops duplicates. The stream named by
+//! `KULTA_NATS_STREAM` is created over `{prefix}.>` if it doesn't exist.
+//! Publishing happens on a background task: reconciles never wait for NATS,
+//! and occurrences are dropped (with a warning) while the queue is full.
+
+use false_protocol::Occurrence;
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Default NATS server
+pub const DEFAULT_NATS_URL: &str = "nats://localhost:4222";
+
+/// Default subject prefix for published occurrences
+pub const DEFAULT_NATS_SUBJECT_PREFIX: &str = "kulta.occurrences";
+
+/// Default JetStream stream holding published occurrences
+pub const DEFAULT_NATS_STREAM: &str = "KULTA_OCCURRENCES";
+
+/// Maximum occurrence file size (10 MB). Truncated when exceeded.
+const MAX_OCCURRENCE_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Occurrences waiting to be published to NATS
+const NATS_QUEUE_CAPACITY: usize = 1024;
+
+#[derive(Debug, Error)]
+pub enum OccurrenceSinkError {
+    #[error("Failed to serialize occurrence: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("Failed to write occurrence file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("NATS publish queue is full, occurrence dropped")]
+    QueueFull,
+
+    #[error("NATS publisher has stopped")]
+    Closed,
+}
+
+/// Destination for FALSE Protocol occurrences
+///
+/// `publish` is called from reconciles and must not block on the network;
+/// sinks that talk to a remote system hand the occurrence to a background
+/// task.
+pub trait OccurrenceSink: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    fn publish(&self, occurrence: &Occurrence) -> Result<(), OccurrenceSinkError>;
+}
+
+/// Appends occurrences as JSON lines to `occurrence.json` in a directory
+pub struct FileOccurrenceSink {
+    dir: PathBuf,
+}
+
+impl FileOccurrenceSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileOccurrenceSink { dir: dir.into() }
+    }
+
+    /// Write to `KULTA_OCCURRENCE_DIR` (default: `/tmp/kulta`)
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self::new(lookup("KULTA_OCCURRENCE_DIR").unwrap_or_else(|| "/tmp/kulta".to_string()))
+    }
+
+    /// The file occurrences are appended to
+    pub fn path(&self) -> PathBuf {
+        self.dir.join("occurrence.json")
+    }
+}
+
+impl OccurrenceSink for FileOccurrenceSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn publish(&self, occurrence: &Occurrence) -> Result<(), OccurrenceSinkError> {
+        use std::io::Write;
+
+        let json = serde_json::to_string(occurrence)?;
+        std::fs::create_dir_all(&self.dir)?;
+        let file_path = self.path();
+
+        // Truncate if file exceeds size limit to prevent unbounded growth
+        if let Ok(metadata) = std::fs::metadata(&file_path) {
+            if metadata.len() > MAX_OCCURRENCE_FILE_BYTES {
+                warn!("Occurrence file exceeds 10MB, truncating");
+                std::fs::write(&file_path, "")?;
+            }
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)?;
+        writeln!(file, "{}", json)?;
+        Ok(())
+    }
+}
+
+/// Connection settings of the NATS sink
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NatsSinkConfig {
+    /// Server URL(s), comma-separated
+    pub url: String,
+    /// Subject prefix; the occurrence type is appended
+    pub subject_prefix: String,
+    /// JetStream stream created over `{subject_prefix}.>` when missing
+    pub stream: String,
+    /// NATS credentials file (`.creds`), if the server requires one
+    pub credentials_file: Option<PathBuf>,
+}
+
+impl NatsSinkConfig {
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let setting = |key: &str, default: &str| {
+            lookup(key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+        NatsSinkConfig {
+            url: setting("KULTA_NATS_URL", DEFAULT_NATS_URL),
+            subject_prefix: setting("KULTA_NATS_SUBJECT_PREFIX", DEFAULT_NATS_SUBJECT_PREFIX)
+                .trim_end_matches('.')
+                .to_string(),
+            stream: setting("KULTA_NATS_STREAM", DEFAULT_NATS_STREAM),
+            credentials_file: lookup("KULTA_NATS_CREDS")
+                .map(|path| path.trim().to_string())
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    /// Subject an occurrence of `occurrence_type` is published to
+    pub fn subject(&self, occurrence_type: &str) -> String {
+        format!("{}.{}", self.subject_prefix, occurrence_type)
+    }
+}
+
+/// An occurrence queued for JetStream
+#[derive(Debug)]
+struct NatsMessage {
+    subject: String,
+    id: String,
+    payload: String,
+}
+
+/// Publishes occurrences to NATS JetStream from a background task
+pub struct NatsOccurrenceSink {
+    config: NatsSinkConfig,
+    queue: mpsc::Sender<NatsMessage>,
+}
+
+impl NatsOccurrenceSink {
+    /// Start the publisher task on the current Tokio runtime
+    pub fn spawn(config: NatsSinkConfig) -> Self {
+        let (queue, messages) = mpsc::channel(NATS_QUEUE_CAPACITY);
+        tokio::spawn(run_nats_publisher(config.clone(), messages));
+        NatsOccurrenceSink { config, queue }
+    }
+}
+
+impl OccurrenceSink for NatsOccurrenceSink {
+    fn name(&self) -> &str {
+        "nats"
+    }
+
+    fn publish(&self, occurrence: &Occurrence) -> Result<(), OccurrenceSinkError> {
+        let message = NatsMessage {
+            subject: self.config.subject(&occurrence.occurrence_type),
+            id: occurrence.id.clone(),
+            payload: serde_json::to_string(occurrence)?,
+        };
+        self.queue.try_send(message).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => OccurrenceSinkError::QueueFull,
+            mpsc::error::TrySendError::Closed(_) => OccurrenceSinkError::Closed,
+        })
+    }
+}
+
+/// Connect to NATS and publish queued occurrences until the sink is dropped
+///
+/// The client reconnects on its own once connected; while the first
+/// connection is pending, publishes wait for it.
+async fn run_nats_publisher(config: NatsSinkConfig, mut messages: mpsc::Receiver<NatsMessage>) {
+    let mut options = async_nats::ConnectOptions::new()
+        .name("kulta")
+        .retry_on_initial_connect();
+    if let Some(path) = &config.credentials_file {
+        options = match options.credentials_file(path).await {
+            Ok(options) => options,
+            Err(e) => {
+                warn!(error = %e, path = %path.display(),
+                    "Failed to read NATS credentials, occurrences won't be published to NATS");
+                return;
+            }
+        };
+    }
+    let client = match options.connect(config.url.as_str()).await {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, url = %config.url,
+                "Failed to connect to NATS, occurrences won't be published to NATS");
+            return;
+        }
+    };
+    let jetstream = async_nats::jetstream::new(client);
+
+    let stream = async_nats::jetstream::stream::Config {
+        name: config.stream.clone(),
+        subjects: vec![format!("{}.>", config.subject_prefix)],
+        ..Default::default()
+    };
+    match jetstream.get_or_create_stream(stream).await {
+        Ok(_) => info!(stream = %config.stream, url = %config.url,
+            "Publishing FALSE Protocol occurrences to NATS JetStream"),
+        // Publishing still works if the stream is managed elsewhere
+        Err(e) => warn!(error = %e, stream = %config.stream,
+            "Failed to create NATS JetStream stream for occurrences"),
+    }
+
+    while let Some(message) = messages.recv().await {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(async_nats::header::NATS_MESSAGE_ID, message.id.as_str());
+        let ack = match jetstream
+            .publish_with_headers(message.subject.clone(), headers, message.payload.into())
+            .await
+        {
+            Ok(ack) => ack,
+            Err(e) => {
+                warn!(error = %e, subject = %message.subject,
+                    "Failed to publish occurrence to NATS (non-fatal)");
+                continue;
+            }
+        };
+        if let Err(e) = ack.await {
+            warn!(error = %e, subject = %message.subject,
+                "NATS JetStream didn't acknowledge occurrence (non-fatal)");
+        }
+    }
+}
+
+/// Every configured occurrence sink
+///
+/// Publishes to each sink in turn; a failing sink is logged and doesn't
+/// keep the occurrence from the others.
+#[derive(Clone, Default)]
+pub struct OccurrenceSinks(Vec<Arc<dyn OccurrenceSink>>);
+
+impl OccurrenceSinks {
+    /// No sinks: occurrences are built and discarded
+    pub fn disabled() -> Self {
+        OccurrenceSinks(Vec::new())
+    }
+
+    /// Sinks named by `KULTA_OCCURRENCE_SINKS` (default: `file`)
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Like [`OccurrenceSinks::from_env`], reading settings from `lookup`
+    ///
+    /// The NATS sink needs a Tokio runtime and is skipped (with a warning)
+    /// outside of one.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let names = lookup("KULTA_OCCURRENCE_SINKS").unwrap_or_else(|| "file".to_string());
+        let mut sinks = OccurrenceSinks::disabled();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name {
+                "file" => sinks.register(FileOccurrenceSink::from_lookup(&lookup)),
+                "nats" if tokio::runtime::Handle::try_current().is_ok() => sinks.register(
+                    NatsOccurrenceSink::spawn(NatsSinkConfig::from_lookup(&lookup)),
+                ),
+                "nats" => warn!("NATS occurrence sink needs a Tokio runtime, ignoring it"),
+                other => warn!(
+                    sink = other,
+                    "Ignoring unknown KULTA_OCCURRENCE_SINKS entry (expected file or nats)"
+                ),
+            }
+        }
+        sinks
+    }
+
+    /// Add a sink
+    pub fn register(&mut self, sink: impl OccurrenceSink + 'static) {
+        self.0.push(Arc::new(sink));
+    }
+
+    /// Names of the registered sinks, in order
+    pub fn names(&self) -> Vec<&str> {
+        self.0.iter().map(|sink| sink.name()).collect()
+    }
+}
+
+impl OccurrenceSink for OccurrenceSinks {
+    fn name(&self) -> &str {
+        "all"
+    }
+
+    fn publish(&self, occurrence: &Occurrence) -> Result<(), OccurrenceSinkError> {
+        for sink in &self.0 {
+            if let Err(e) = sink.publish(occurrence) {
+                warn!(error = %e, sink = sink.name(),
+                    occurrence_type = %occurrence.occurrence_type,
+                    "Failed to publish FALSE Protocol occurrence (non-fatal)");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for OccurrenceSinks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OccurrenceSinks")
+            .field(&self.names())
+            .finish()
+    }
+}
+
+/// Keeps published occurrences (as JSON) in memory for assertions
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MockOccurrenceSink {
+    occurrences: Arc<std::sync::Mutex<Vec<serde_json::Value>>>,
+}
+
+#[cfg(test)]
+impl MockOccurrenceSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::unwrap_used)]
+    pub fn published(&self) -> Vec<serde_json::Value> {
+        self.occurrences.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl OccurrenceSink for MockOccurrenceSink {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    #[allow(clippy::unwrap_used)]
+    fn publish(&self, occurrence: &Occurrence) -> Result<(), OccurrenceSinkError> {
+        let json = serde_json::to_value(occurrence)?;
+        self.occurrences.lock().unwrap().push(json);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn occurrence(occurrence_type: &str) -> Occurrence {
+        Occurrence::new("kulta", occurrence_type).unwrap()
+    }
+
+    #[test]
+    fn test_nats_config_from_lookup() {
+        let defaults = NatsSinkConfig::from_lookup(|_| None);
+        assert_eq!(defaults.url, DEFAULT_NATS_URL);
+        assert_eq!(defaults.stream, DEFAULT_NATS_STREAM);
+        assert_eq!(defaults.credentials_file, None);
+        assert_eq!(
+            defaults.subject("canary.rollout.failed"),
+            "kulta.occurrences.canary.rollout.failed"
+        );
+
+        let config = NatsSinkConfig::from_lookup(|key| match key {
+            "KULTA_NATS_URL" => Some("nats://nats.messaging:4222".into()),
+            "KULTA_NATS_SUBJECT_PREFIX" => Some("prod.kulta.".into()),
+            "KULTA_NATS_STREAM" => Some(" ".into()),
+            "KULTA_NATS_CREDS" => Some("/etc/nats/kulta.creds".into()),
+            _ => None,
+        });
+        assert_eq!(config.url, "nats://nats.messaging:4222");
+        assert_eq!(config.stream, DEFAULT_NATS_STREAM);
+        assert_eq!(
+            config.credentials_file,
+            Some(PathBuf::from("/etc/nats/kulta.creds"))
+        );
+        assert_eq!(
+            config.subject("bluegreen.rollout.completed"),
+            "prod.kulta.bluegreen.rollout.completed"
+        );
+    }
+
+    #[test]
+    fn test_sinks_from_lookup() {
+        assert_eq!(OccurrenceSinks::from_lookup(|_| None).names(), vec!["file"]);
+
+        let none = OccurrenceSinks::from_lookup(|key| {
+            (key == "KULTA_OCCURRENCE_SINKS").then(|| "".to_string())
+        });
+        assert!(none.names().is_empty());
+
+        // Unknown names are ignored; nats needs a runtime
+        let sinks = OccurrenceSinks::from_lookup(|key| {
+            (key == "KULTA_OCCURRENCE_SINKS").then(|| "file, kafka, nats".to_string())
+        });
+        assert_eq!(sinks.names(), vec!["file"]);
+    }
+
+    #[tokio::test]
+    async fn test_sinks_from_lookup_starts_nats_in_runtime() {
+        let sinks = OccurrenceSinks::from_lookup(|key| match key {
+            "KULTA_OCCURRENCE_SINKS" => Some("nats".into()),
+            // Nothing listens here; the publisher task just keeps retrying
+            "KULTA_NATS_URL" => Some("nats://127.0.0.1:1".into()),
+            _ => None,
+        });
+        assert_eq!(sinks.names(), vec!["nats"]);
+    }
+
+    #[test]
+    fn test_file_sink_appends_json_lines() {
+        let dir = std::env::temp_dir().join(format!("kulta-occurrences-{}", uuid::Uuid::new_v4()));
+        let sink = FileOccurrenceSink::new(&dir);
+
+        sink.publish(&occurrence("canary.rollout.progressing"))
+            .unwrap();
+        sink.publish(&occurrence("canary.rollout.completed"))
+            .unwrap();
+
+        let contents = std::fs::read_to_string(sink.path()).unwrap();
+        let types: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                let occ: serde_json::Value = serde_json::from_str(line).unwrap();
+                occ["type"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            types,
+            vec!["canary.rollout.progressing", "canary.rollout.completed"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_nats_sink_queues_messages_by_subject() {
+        let (queue, mut messages) = mpsc::channel(1);
+        let sink = NatsOccurrenceSink {
+            config: NatsSinkConfig::from_lookup(|_| None),
+            queue,
+        };
+
+        let occ = occurrence("canary.rollout.failed");
+        sink.publish(&occ).unwrap();
+        let message = messages.try_recv().unwrap();
+        assert_eq!(message.subject, "kulta.occurrences.canary.rollout.failed");
+        assert_eq!(message.id, occ.id);
+        assert!(message.payload.contains("canary.rollout.failed"));
+
+        // A full queue drops the occurrence instead of blocking the reconcile
+        sink.publish(&occ).unwrap();
+        assert!(matches!(
+            sink.publish(&occ),
+            Err(OccurrenceSinkError::QueueFull)
+        ));
+
+        drop(messages);
+        assert!(matches!(
+            sink.publish(&occ),
+            Err(OccurrenceSinkError::Closed)
+        ));
+    }
+
+    #[test]
+    fn test_sinks_publish_to_every_sink() {
+        let first = MockOccurrenceSink::new();
+        let second = MockOccurrenceSink::new();
+        let mut sinks = OccurrenceSinks::disabled();
+        sinks.register(first.clone());
+        sinks.register(second.clone());
+
+        sinks
+            .publish(&occurrence("canary.rollout.removed"))
+            .unwrap();
+
+        assert_eq!(first.published().len(), 1);
+        assert_eq!(second.published().len(), 1);
+    }
+}
@@ -0,0 +1,406 @@
+//! Garbage collection of orphaned KULTA ReplicaSets
+//!
+//! KULTA's ReplicaSets are owned by their Rollout and go with it, but ones
+//! created by older versions carry no owner reference until the next reconcile
+//! adopts them. Deleting a Rollout before that (e.g. while the controller is
+//! down) leaves its ReplicaSets, and their pods, behind. A periodic sweep lists
+//! ReplicaSets labeled `rollouts.kulta.io/managed=true`, works out which
+//! Rollout they belong to, and deletes those whose Rollout no longer exists.
+//! A ClusterRollout counts as a Rollout of the same name in its target
+//! namespace.
+//!
+//! A ReplicaSet is only deleted after it has been seen orphaned for the whole
+//! grace period, so a Rollout that is deleted and re-applied (e.g. by GitOps)
+//! keeps its ReplicaSets. Only the leader sweeps.
+//!
+//! Configured with:
+//! - `KULTA_ORPHAN_GC_INTERVAL_SECS`: time between sweeps (default 300, 0 disables)
+//! - `KULTA_ORPHAN_GC_GRACE_SECS`: how long a ReplicaSet must stay orphaned (default 600)
+
+use crate::crd::cluster_rollout::ClusterRollout;
+use crate::crd::rollout::Rollout;
+use crate::server::{Claim, LeaderState, ObjectLeases, ShutdownSignal, ORPHAN_GC_TASK};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use kube::api::{Api, DeleteParams, ListParams};
+use kube::{Client, ResourceExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Label set on every ReplicaSet KULTA creates
+pub const MANAGED_LABEL: &str = "rollouts.kulta.io/managed";
+
+/// Annotation naming the Rollout that created a ReplicaSet
+pub const ROLLOUT_ANNOTATION: &str = "rollouts.kulta.io/rollout";
+
+/// Label holding the ReplicaSet role (stable, canary, active, simple, ...)
+const TYPE_LABEL: &str = "rollouts.kulta.io/type";
+
+/// Default time between sweeps
+pub const DEFAULT_ORPHAN_GC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default time a ReplicaSet must stay orphaned before it is deleted
+pub const DEFAULT_ORPHAN_GC_GRACE: Duration = Duration::from_secs(600);
+
+/// Orphan garbage collection configuration
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrphanGcConfig {
+    /// Time between sweeps (`None` disables the collector)
+    pub interval: Option<Duration>,
+    /// How long a ReplicaSet must stay orphaned before it is deleted
+    pub grace: Duration,
+}
+
+impl Default for OrphanGcConfig {
+    fn default() -> Self {
+        Self {
+            interval: Some(DEFAULT_ORPHAN_GC_INTERVAL),
+            grace: DEFAULT_ORPHAN_GC_GRACE,
+        }
+    }
+}
+
+impl OrphanGcConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create config from an arbitrary key lookup (testable without touching env)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+
+        let interval = match lookup("KULTA_ORPHAN_GC_INTERVAL_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => defaults.interval,
+        };
+
+        let grace = lookup("KULTA_ORPHAN_GC_GRACE_SECS")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.grace);
+
+        Self { interval, grace }
+    }
+}
+
+/// Namespace and name of a ReplicaSet or Rollout
+pub type ObjectKey = (String, String);
+
+/// Name of the Rollout a KULTA ReplicaSet belongs to
+///
+/// Read from the `rollouts.kulta.io/rollout` annotation. ReplicaSets created
+/// before the annotation existed are matched by name: `{rollout}-{type}`, or
+/// just `{rollout}` for the simple strategy.
+pub fn owning_rollout_name(rs: &ReplicaSet) -> Option<String> {
+    if let Some(name) = rs.annotations().get(ROLLOUT_ANNOTATION) {
+        return Some(name.clone());
+    }
+
+    let name = rs.metadata.name.as_deref()?;
+    match rs.labels().get(TYPE_LABEL).map(String::as_str) {
+        Some("simple") => Some(name.to_string()),
+        Some(rs_type) => name
+            .strip_suffix(rs_type)
+            .and_then(|prefix| prefix.strip_suffix('-'))
+            .filter(|rollout| !rollout.is_empty())
+            .map(str::to_string),
+        None => None,
+    }
+}
+
+/// Namespace and name a ClusterRollout's ReplicaSets are recorded under
+pub fn cluster_rollout_key(cluster_rollout: &ClusterRollout) -> ObjectKey {
+    (
+        cluster_rollout.spec.target_namespace.clone(),
+        cluster_rollout.name_any(),
+    )
+}
+
+/// KULTA ReplicaSets whose Rollout is not in `rollouts`
+///
+/// ReplicaSets with an owner reference belong to something else and are
+/// never reported, nor are those whose Rollout can't be determined.
+pub fn find_orphans(replica_sets: &[ReplicaSet], rollouts: &HashSet<ObjectKey>) -> Vec<ObjectKey> {
+    replica_sets
+        .iter()
+        .filter(|rs| rs.owner_references().is_empty())
+        .filter_map(|rs| {
+            let namespace = rs.namespace()?;
+            let rollout = owning_rollout_name(rs)?;
+            if rollouts.contains(&(namespace.clone(), rollout)) {
+                return None;
+            }
+            Some((namespace, rs.name_any()))
+        })
+        .collect()
+}
+
+/// Tracks how long ReplicaSets have been orphaned across sweeps
+#[derive(Debug)]
+pub struct OrphanCollector {
+    grace: chrono::Duration,
+    first_seen: HashMap<ObjectKey, DateTime<Utc>>,
+}
+
+impl OrphanCollector {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace: chrono::Duration::from_std(grace).unwrap_or(chrono::Duration::MAX),
+            first_seen: HashMap::new(),
+        }
+    }
+
+    /// Record this sweep's orphans and return those past the grace period
+    ///
+    /// ReplicaSets that are no longer orphaned (their Rollout came back, or
+    /// they are gone) are forgotten, so a later orphaning starts a new grace
+    /// period.
+    pub fn expired(&mut self, orphans: Vec<ObjectKey>, now: DateTime<Utc>) -> Vec<ObjectKey> {
+        let current: HashSet<ObjectKey> = orphans.into_iter().collect();
+        self.first_seen.retain(|key, _| current.contains(key));
+
+        let mut expired = Vec::new();
+        for key in current {
+            let since = *self.first_seen.entry(key.clone()).or_insert(now);
+            if now - since >= self.grace {
+                expired.push(key);
+            }
+        }
+        expired.sort();
+        expired
+    }
+
+    /// List ReplicaSets and Rollouts and delete orphans past the grace period
+    ///
+    /// # Returns
+    /// Number of ReplicaSets deleted
+    pub async fn sweep(
+        &mut self,
+        client: &Client,
+        now: DateTime<Utc>,
+    ) -> Result<usize, kube::Error> {
+        // List ReplicaSets first: a Rollout created after this point can't
+        // own any of them
+        let rs_api: Api<ReplicaSet> = Api::all(client.clone());
+        let replica_sets = rs_api
+            .list(&ListParams::default().labels(&format!("{}=true", MANAGED_LABEL)))
+            .await?
+            .items;
+
+        let rollout_api: Api<Rollout> = Api::all(client.clone());
+        let mut rollouts: HashSet<ObjectKey> = rollout_api
+            .list(&ListParams::default())
+            .await?
+            .items
+            .iter()
+            .filter_map(|rollout| Some((rollout.namespace()?, rollout.name_any())))
+            .collect();
+
+        // ClusterRollouts own ReplicaSets in their target namespace. Without
+        // the ClusterRollout CRD installed there are none.
+        let cluster_rollout_api: Api<ClusterRollout> = Api::all(client.clone());
+        match cluster_rollout_api.list(&ListParams::default()).await {
+            Ok(list) => rollouts.extend(list.items.iter().map(cluster_rollout_key)),
+            Err(kube::Error::Api(err)) if err.code == 404 => {}
+            Err(e) => return Err(e),
+        }
+
+        let orphans = find_orphans(&replica_sets, &rollouts);
+        if !orphans.is_empty() {
+            debug!(count = orphans.len(), "Found orphaned ReplicaSets");
+        }
+
+        let mut deleted = 0;
+        for (namespace, name) in self.expired(orphans, now) {
+            let api: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+            match api.delete(&name, &DeleteParams::background()).await {
+                Ok(_) => {
+                    info!(replicaset = %name, namespace = %namespace, "Deleted orphaned ReplicaSet");
+                    self.first_seen.remove(&(namespace, name));
+                    deleted += 1;
+                }
+                Err(kube::Error::Api(err)) if err.code == 404 => {
+                    self.first_seen.remove(&(namespace, name));
+                }
+                Err(e) => {
+                    warn!(replicaset = %name, namespace = %namespace, error = %e, "Failed to delete orphaned ReplicaSet");
+                }
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// Sweep for orphaned ReplicaSets until shutdown
+///
+/// The first sweep runs at startup, which is when orphans from deletions
+/// during downtime are found. Sweeps are skipped while not the leader or,
+/// with per-object leases, while another replica holds the orphan GC task
+/// Lease.
+pub async fn run_orphan_gc(
+    client: Client,
+    config: OrphanGcConfig,
+    leader_state: LeaderState,
+    object_leases: Option<Arc<ObjectLeases>>,
+    mut shutdown: ShutdownSignal,
+) {
+    let Some(interval) = config.interval else {
+        info!("Orphaned ReplicaSet collection disabled");
+        return;
+    };
+    info!(
+        interval_secs = interval.as_secs(),
+        grace_secs = config.grace.as_secs(),
+        "Starting orphaned ReplicaSet collection"
+    );
+
+    let mut collector = OrphanCollector::new(config.grace);
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                let runs_here = match &object_leases {
+                    Some(leases) => match leases.claim_task(ORPHAN_GC_TASK, Utc::now()).await {
+                        Ok(claim) => claim == Claim::Held,
+                        Err(e) => {
+                            warn!(error = %e, "Failed to claim the orphan GC task lease");
+                            false
+                        }
+                    },
+                    None => leader_state.is_leader(),
+                };
+                if !runs_here {
+                    continue;
+                }
+                if let Err(e) = collector.sweep(&client, Utc::now()).await {
+                    warn!(error = %e, "Orphaned ReplicaSet sweep failed");
+                }
+            }
+            _ = shutdown.wait() => {
+                info!("Stopping orphaned ReplicaSet collection");
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+    use kube::api::ObjectMeta;
+    use std::collections::BTreeMap;
+
+    fn replicaset(name: &str, rs_type: &str) -> ReplicaSet {
+        ReplicaSet {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("default".to_string()),
+                labels: Some(BTreeMap::from([
+                    (MANAGED_LABEL.to_string(), "true".to_string()),
+                    (TYPE_LABEL.to_string(), rs_type.to_string()),
+                ])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn key(name: &str) -> ObjectKey {
+        ("default".to_string(), name.to_string())
+    }
+
+    #[test]
+    fn test_owning_rollout_name() {
+        assert_eq!(
+            owning_rollout_name(&replicaset("my-app-stable", "stable")),
+            Some("my-app".to_string())
+        );
+        assert_eq!(
+            owning_rollout_name(&replicaset("my-app-variant-a", "variant-a")),
+            Some("my-app".to_string())
+        );
+        assert_eq!(
+            owning_rollout_name(&replicaset("my-app-stable", "simple")),
+            Some("my-app-stable".to_string())
+        );
+        assert_eq!(owning_rollout_name(&replicaset("other", "canary")), None);
+
+        let mut annotated = replicaset("renamed", "canary");
+        annotated.metadata.annotations = Some(BTreeMap::from([(
+            ROLLOUT_ANNOTATION.to_string(),
+            "my-app".to_string(),
+        )]));
+        assert_eq!(owning_rollout_name(&annotated), Some("my-app".to_string()));
+    }
+
+    #[test]
+    fn test_find_orphans() {
+        let mut owned = replicaset("gone-canary", "canary");
+        owned.metadata.owner_references = Some(vec![OwnerReference {
+            name: "something-else".to_string(),
+            ..Default::default()
+        }]);
+        let replica_sets = vec![
+            replicaset("live-stable", "stable"),
+            replicaset("gone-stable", "stable"),
+            owned,
+        ];
+        let rollouts = HashSet::from([key("live")]);
+
+        assert_eq!(
+            find_orphans(&replica_sets, &rollouts),
+            vec![key("gone-stable")]
+        );
+    }
+
+    #[test]
+    fn test_collector_waits_for_grace_period() {
+        let mut collector = OrphanCollector::new(Duration::from_secs(600));
+        let start = Utc::now();
+
+        assert!(collector.expired(vec![key("a")], start).is_empty());
+        assert!(collector
+            .expired(vec![key("a")], start + chrono::Duration::seconds(300))
+            .is_empty());
+        assert_eq!(
+            collector.expired(vec![key("a")], start + chrono::Duration::seconds(600)),
+            vec![key("a")]
+        );
+    }
+
+    #[test]
+    fn test_collector_forgets_recovered_replicasets() {
+        let mut collector = OrphanCollector::new(Duration::from_secs(600));
+        let start = Utc::now();
+
+        collector.expired(vec![key("a")], start);
+        // Rollout re-applied: no longer orphaned
+        collector.expired(vec![], start + chrono::Duration::seconds(300));
+        // Orphaned again: the grace period starts over
+        assert!(collector
+            .expired(vec![key("a")], start + chrono::Duration::seconds(700))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_config_from_lookup() {
+        let config = OrphanGcConfig::from_lookup(|_| None);
+        assert_eq!(config, OrphanGcConfig::default());
+
+        let config = OrphanGcConfig::from_lookup(|key| match key {
+            "KULTA_ORPHAN_GC_INTERVAL_SECS" => Some("0".to_string()),
+            "KULTA_ORPHAN_GC_GRACE_SECS" => Some("60".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.interval, None);
+        assert_eq!(config.grace, Duration::from_secs(60));
+    }
+}
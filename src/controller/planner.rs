@@ -0,0 +1,602 @@
+//! Offline rollout planner
+//!
+//! Simulates the phase/step sequence of a Rollout without a cluster by
+//! driving the strategy's own `compute_next_status` with a simulated clock.
+//! Timed pauses are fast-forwarded; manual gates (indefinite and approval
+//! pauses, blue-green preview, A/B conclusion) and pauses until a fixed time
+//! are recorded and then passed as if the operator had promoted immediately.
+//!
+//! Metrics analysis is not simulated: the plan shows the happy path, with
+//! each step's inline analysis passing, and each step's experiment
+//! succeeding, as soon as its `duration` allows.
+
+use crate::controller::rollout::{
+    is_step_analysis_complete, is_step_experiment_complete, parse_duration, pause_end,
+    validate_rollout, APPROVED_BY_ANNOTATION,
+};
+use crate::controller::steps_from::steps_configmap_ref;
+use crate::controller::strategies::select_strategy;
+use crate::controller::strategy_math::{canary_steps, current_step};
+use crate::crd::experiment::ExperimentPhase;
+use crate::crd::rollout::{
+    ABConclusionReason, Pause, Phase, Rollout, RolloutStatus, StepAnalysisStatus,
+    StepExperimentStatus,
+};
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PlanError {
+    #[error("Invalid Rollout spec: {0}")]
+    InvalidSpec(String),
+
+    #[error("Rollout did not complete within {0} simulated transitions")]
+    DidNotComplete(usize),
+
+    #[error(
+        "Canary steps are imported from ConfigMap {0} (stepsFrom); inline them to plan offline"
+    )]
+    StepsNotInline(String),
+}
+
+/// What the rollout waits for before it can leave a state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanGate {
+    /// A timed pause that elapses on its own
+    Timer(Duration),
+    /// Manual promotion via the `kulta.io/promote` annotation
+    Promotion,
+    /// Sign-off via the `kulta.io/approved-by` annotation (`untilApproved`)
+    Approval,
+    /// A pause until a fixed time (`until`), independent of the rollout's start
+    Until(DateTime<Utc>),
+    /// The step's inline analysis, passing after its `duration`
+    StepAnalysis(Duration),
+    /// The step's experiment, succeeding after its `duration`
+    StepExperiment(Duration),
+    /// A/B experiment reaching a conclusion (statistical analysis)
+    ExperimentConclusion,
+}
+
+/// A state the rollout passes through
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanEntry {
+    /// Time since the rollout started, assuming manual gates pass instantly
+    pub elapsed: Duration,
+    pub phase: Phase,
+    pub step_index: Option<i32>,
+    pub weight: Option<i32>,
+    pub message: Option<String>,
+    /// Set when the rollout has to wait in this state
+    pub waits_for: Option<PlanGate>,
+}
+
+/// Simulated sequence of rollout states
+#[derive(Clone, Debug, PartialEq)]
+pub struct RolloutPlan {
+    pub strategy: &'static str,
+    pub entries: Vec<PlanEntry>,
+}
+
+impl RolloutPlan {
+    /// Minimum time to completion (timed pauses, step analyses and step
+    /// experiments only)
+    pub fn total_duration(&self) -> Duration {
+        self.entries
+            .last()
+            .map(|entry| entry.elapsed)
+            .unwrap_or_default()
+    }
+
+    /// Number of states that need a human or an analysis result to continue
+    pub fn manual_gates(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.waits_for,
+                    Some(PlanGate::Promotion | PlanGate::Approval | PlanGate::ExperimentConclusion)
+                )
+            })
+            .count()
+    }
+}
+
+/// Simulate a Rollout from creation to completion
+///
+/// The spec is validated first, like the controller would. Any existing
+/// status and promote annotation on the input are ignored.
+pub fn plan_rollout(rollout: &Rollout) -> Result<RolloutPlan, PlanError> {
+    validate_rollout(rollout).map_err(PlanError::InvalidSpec)?;
+    if let Some(reference) = steps_configmap_ref(rollout) {
+        return Err(PlanError::StepsNotInline(reference.name.clone()));
+    }
+
+    let strategy = select_strategy(rollout);
+    let max_transitions = 4 * canary_steps(&rollout.spec).len() + 16;
+
+    let start = DateTime::<Utc>::default();
+    let mut now = start;
+    let mut sim = rollout.clone();
+    sim.status = None;
+    set_annotation(&mut sim, PROMOTE_ANNOTATION, None);
+    set_annotation(&mut sim, APPROVED_BY_ANNOTATION, None);
+
+    let mut entries: Vec<PlanEntry> = Vec::new();
+    for _ in 0..max_transitions {
+        let next = strategy.compute_next_status(&sim, now);
+        let changed = sim.status.as_ref() != Some(&next);
+        let moved = sim.status.as_ref().map(state_key) != Some(state_key(&next));
+        sim.status = Some(next.clone());
+
+        if moved {
+            entries.push(PlanEntry {
+                elapsed: (now - start).to_std().unwrap_or_default(),
+                phase: next.phase.clone().unwrap_or_default(),
+                step_index: next.current_step_index,
+                weight: next.current_weight,
+                message: next.message.clone(),
+                waits_for: None,
+            });
+        }
+        if matches!(next.phase, Some(Phase::Completed) | Some(Phase::Failed)) {
+            return Ok(RolloutPlan {
+                strategy: strategy.name(),
+                entries,
+            });
+        }
+        if changed {
+            // The controller removes the annotations once it has acted on them
+            set_annotation(&mut sim, PROMOTE_ANNOTATION, None);
+            set_annotation(&mut sim, APPROVED_BY_ANNOTATION, None);
+            continue;
+        }
+
+        // Status is stable: figure out what lets the rollout move on
+        if let Some(duration) = pass_step_analysis(&mut sim, now) {
+            now += chrono::Duration::from_std(duration).unwrap_or_default();
+            if let Some(last) = entries.last_mut() {
+                last.waits_for = Some(PlanGate::StepAnalysis(duration));
+            }
+            continue;
+        }
+        if let Some(duration) = pass_step_experiment(&mut sim) {
+            now += chrono::Duration::from_std(duration).unwrap_or_default();
+            if let Some(last) = entries.last_mut() {
+                last.waits_for = Some(PlanGate::StepExperiment(duration));
+            }
+            continue;
+        }
+        let gate = match pause_resume_time(&sim, &next) {
+            Some((resume_at, duration)) if resume_at > now => {
+                now = resume_at;
+                PlanGate::Timer(duration)
+            }
+            _ if next.phase == Some(Phase::Experimenting) => {
+                if let Some(ab) = sim.status.as_mut().and_then(|s| s.ab_experiment.as_mut()) {
+                    ab.conclusion_reason = Some(ABConclusionReason::SignificanceReached);
+                }
+                PlanGate::ExperimentConclusion
+            }
+            _ => {
+                let pause = current_step(&sim.spec, Some(&next)).and_then(|s| s.pause.as_ref());
+                match pause {
+                    Some(Pause::UntilApproved) => {
+                        set_annotation(&mut sim, APPROVED_BY_ANNOTATION, Some("plan"));
+                        PlanGate::Approval
+                    }
+                    Some(Pause::UntilTime(until)) => {
+                        let until = DateTime::parse_from_rfc3339(until)
+                            .map(|until| until.with_timezone(&Utc))
+                            .unwrap_or_default();
+                        set_annotation(&mut sim, PROMOTE_ANNOTATION, Some("true"));
+                        PlanGate::Until(until)
+                    }
+                    _ => {
+                        set_annotation(&mut sim, PROMOTE_ANNOTATION, Some("true"));
+                        PlanGate::Promotion
+                    }
+                }
+            }
+        };
+        if let Some(last) = entries.last_mut() {
+            last.waits_for = Some(gate);
+        }
+    }
+
+    Err(PlanError::DidNotComplete(max_transitions))
+}
+
+/// The parts of a status that make up a distinct plan entry
+fn state_key(status: &RolloutStatus) -> (Option<Phase>, Option<i32>, Option<i32>) {
+    (
+        status.phase.clone(),
+        status.current_step_index,
+        status.current_weight,
+    )
+}
+
+/// When the timed pause of the current canary step ends (and its length)
+///
+/// Only `duration` pauses: an `until` pause is tied to the wall clock, not
+/// to the simulated one.
+fn pause_resume_time(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+) -> Option<(DateTime<Utc>, Duration)> {
+    let pause = current_step(&rollout.spec, Some(status))?.pause.as_ref()?;
+    if !matches!(pause, Pause::Duration(_)) {
+        return None;
+    }
+    let started = DateTime::parse_from_rfc3339(status.pause_start_time.as_deref()?).ok()?;
+    let started = started.with_timezone(&Utc);
+    let resume_at = pause_end(pause, started)?;
+    Some((resume_at, (resume_at - started).to_std().ok()?))
+}
+
+/// Let the current step's inline analysis pass (the happy path)
+///
+/// # Returns
+/// How long the analysis takes, or `None` if the step has none pending
+fn pass_step_analysis(rollout: &mut Rollout, now: DateTime<Utc>) -> Option<Duration> {
+    let analysis = current_step(&rollout.spec, rollout.status.as_ref())?
+        .analysis
+        .clone()?;
+    let status = rollout.status.as_mut()?;
+    if is_step_analysis_complete(&analysis, status, now) {
+        return None;
+    }
+    status.step_analysis = Some(StepAnalysisStatus {
+        step_index: status.current_step_index?,
+        started_at: now.to_rfc3339(),
+        passed: analysis.count.unwrap_or(1),
+        last_measured_at: Some(now.to_rfc3339()),
+    });
+    Some(
+        analysis
+            .duration
+            .as_deref()
+            .and_then(parse_duration)
+            .unwrap_or_default(),
+    )
+}
+
+/// Let the current step's experiment succeed (the happy path)
+///
+/// # Returns
+/// The experiment's `duration`, or `None` if the step has none pending
+fn pass_step_experiment(rollout: &mut Rollout) -> Option<Duration> {
+    let experiment = current_step(&rollout.spec, rollout.status.as_ref())?
+        .experiment
+        .clone()?;
+    let status = rollout.status.as_mut()?;
+    if is_step_experiment_complete(status) {
+        return None;
+    }
+    status.step_experiment = Some(StepExperimentStatus {
+        step_index: status.current_step_index?,
+        name: "plan".to_string(),
+        phase: Some(ExperimentPhase::Successful),
+    });
+    Some(parse_duration(&experiment.duration).unwrap_or_default())
+}
+
+const PROMOTE_ANNOTATION: &str = "kulta.io/promote";
+
+fn set_annotation(rollout: &mut Rollout, key: &str, value: Option<&str>) {
+    let annotations = rollout
+        .metadata
+        .annotations
+        .get_or_insert_with(Default::default);
+    match value {
+        Some(value) => annotations.insert(key.to_string(), value.to_string()),
+        None => annotations.remove(key),
+    };
+}
+
+/// Format a duration compactly (e.g., "1h5m30s", "0s")
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    let mut out = String::new();
+    if hours > 0 {
+        out.push_str(&format!("{}h", hours));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{}m", minutes));
+    }
+    if seconds > 0 || out.is_empty() {
+        out.push_str(&format!("{}s", seconds));
+    }
+    out
+}
+
+impl fmt::Display for RolloutPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Strategy: {}", self.strategy)?;
+        writeln!(
+            f,
+            "{:<10} {:<13} {:<6} {:<7} WAITS FOR",
+            "TIME", "PHASE", "STEP", "WEIGHT"
+        )?;
+        for entry in &self.entries {
+            let step = entry
+                .step_index
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let weight = entry
+                .weight
+                .map(|w| format!("{}%", w))
+                .unwrap_or_else(|| "-".to_string());
+            let waits_for = match &entry.waits_for {
+                Some(PlanGate::Timer(d)) => format!("pause {}", format_duration(*d)),
+                Some(PlanGate::Promotion) => "kulta.io/promote".to_string(),
+                Some(PlanGate::Approval) => APPROVED_BY_ANNOTATION.to_string(),
+                Some(PlanGate::Until(until)) => format!("until {}", until.to_rfc3339()),
+                Some(PlanGate::StepAnalysis(d)) => format!("analysis {}", format_duration(*d)),
+                Some(PlanGate::StepExperiment(d)) => {
+                    format!("experiment {}", format_duration(*d))
+                }
+                Some(PlanGate::ExperimentConclusion) => "experiment conclusion".to_string(),
+                None => String::new(),
+            };
+            writeln!(
+                f,
+                "{:<10} {:<13} {:<6} {:<7} {}",
+                format_duration(entry.elapsed),
+                format!("{:?}", entry.phase),
+                step,
+                weight,
+                waits_for
+            )?;
+        }
+        writeln!(
+            f,
+            "Minimum duration: {} ({} manual gate(s))",
+            format_duration(self.total_duration()),
+            self.manual_gates()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn rollout_from_yaml(strategy: &str) -> Rollout {
+        let yaml = format!(
+            r#"
+apiVersion: kulta.io/v1alpha1
+kind: Rollout
+metadata:
+  name: plan-test
+  namespace: default
+spec:
+  replicas: 3
+  selector:
+    matchLabels:
+      app: plan-test
+  template:
+    metadata:
+      labels:
+        app: plan-test
+    spec:
+      containers:
+      - name: app
+        image: nginx:1.0
+  strategy:
+{}
+"#,
+            strategy
+        );
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_plan_canary_with_timed_and_manual_pauses() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 20
+        pause:
+          duration: 5m
+      - setWeight: 50
+        pause: {}
+      - setWeight: 100
+"#,
+        );
+
+        let plan = plan_rollout(&rollout).unwrap();
+
+        let weights: Vec<Option<i32>> = plan.entries.iter().map(|e| e.weight).collect();
+        assert_eq!(weights, vec![Some(20), Some(50), Some(100)]);
+        assert_eq!(
+            plan.entries[0].waits_for,
+            Some(PlanGate::Timer(Duration::from_secs(300)))
+        );
+        assert_eq!(plan.entries[1].waits_for, Some(PlanGate::Promotion));
+        assert_eq!(plan.entries[1].elapsed, Duration::from_secs(300));
+        assert_eq!(plan.entries[2].phase, Phase::Completed);
+        assert_eq!(plan.total_duration(), Duration::from_secs(300));
+        assert_eq!(plan.manual_gates(), 1);
+    }
+
+    #[test]
+    fn test_plan_canary_with_step_analysis() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 20
+        analysis:
+          duration: 10m
+          count: 2
+          metrics:
+          - name: error-rate
+            threshold: 1.0
+      - setWeight: 100
+"#,
+        );
+
+        let plan = plan_rollout(&rollout).unwrap();
+
+        assert_eq!(
+            plan.entries[0].waits_for,
+            Some(PlanGate::StepAnalysis(Duration::from_secs(600)))
+        );
+        assert_eq!(plan.entries[1].phase, Phase::Completed);
+        assert_eq!(plan.total_duration(), Duration::from_secs(600));
+        assert_eq!(plan.manual_gates(), 0);
+        assert!(plan.to_string().contains("analysis 10m"));
+    }
+
+    #[test]
+    fn test_plan_canary_with_step_experiment() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 10
+      - experiment:
+          duration: 15m
+          templates:
+          - name: baseline
+            specRef: stable
+          - name: canary
+            specRef: canary
+      - setWeight: 100
+"#,
+        );
+
+        let plan = plan_rollout(&rollout).unwrap();
+
+        assert_eq!(
+            plan.entries[1].waits_for,
+            Some(PlanGate::StepExperiment(Duration::from_secs(900)))
+        );
+        assert_eq!(plan.entries.last().unwrap().phase, Phase::Completed);
+        assert_eq!(plan.total_duration(), Duration::from_secs(900));
+        assert!(plan.to_string().contains("experiment 15m"));
+    }
+
+    #[test]
+    fn test_plan_canary_with_approval_and_scheduled_pauses() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 20
+        pause:
+          untilApproved: true
+      - setWeight: 50
+        pause:
+          until: "2026-03-02T09:00:00Z"
+      - setWeight: 100
+"#,
+        );
+
+        let plan = plan_rollout(&rollout).unwrap();
+
+        assert_eq!(plan.entries[0].waits_for, Some(PlanGate::Approval));
+        assert_eq!(
+            plan.entries[1].waits_for,
+            Some(PlanGate::Until(
+                DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            ))
+        );
+        assert_eq!(plan.entries[2].phase, Phase::Completed);
+        // The wall-clock pause doesn't add simulated time
+        assert_eq!(plan.total_duration(), Duration::ZERO);
+        assert_eq!(plan.manual_gates(), 1);
+    }
+
+    #[test]
+    fn test_plan_blue_green_waits_for_promotion() {
+        let rollout = rollout_from_yaml(
+            r#"
+    blueGreen:
+      activeService: app-active
+      previewService: app-preview
+"#,
+        );
+
+        let plan = plan_rollout(&rollout).unwrap();
+
+        let phases: Vec<Phase> = plan.entries.iter().map(|e| e.phase.clone()).collect();
+        assert_eq!(phases, vec![Phase::Preview, Phase::Completed]);
+        assert_eq!(plan.entries[0].waits_for, Some(PlanGate::Promotion));
+    }
+
+    #[test]
+    fn test_plan_rejects_invalid_spec() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 150
+"#,
+        );
+
+        assert!(matches!(
+            plan_rollout(&rollout),
+            Err(PlanError::InvalidSpec(_))
+        ));
+    }
+
+    #[test]
+    fn test_plan_rejects_imported_steps() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      stepsFrom:
+        configMapRef:
+          name: step-ladders
+"#,
+        );
+
+        assert!(matches!(
+            plan_rollout(&rollout),
+            Err(PlanError::StepsNotInline(name)) if name == "step-ladders"
+        ));
+    }
+
+    #[test]
+    fn test_plan_display() {
+        let rollout = rollout_from_yaml(
+            r#"
+    canary:
+      canaryService: app-canary
+      stableService: app-stable
+      steps:
+      - setWeight: 10
+        pause:
+          duration: 90m
+      - setWeight: 100
+"#,
+        );
+
+        let output = plan_rollout(&rollout).unwrap().to_string();
+
+        assert!(output.contains("Strategy: canary"), "got: {}", output);
+        assert!(output.contains("pause 1h30m"), "got: {}", output);
+        assert!(output.contains("Minimum duration: 1h30m (0 manual gate(s))"));
+    }
+}
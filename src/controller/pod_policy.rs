@@ -0,0 +1,285 @@
+//! Pod template policy for restricted namespaces
+//!
+//! KULTA creates ReplicaSets from `spec.template`, so anyone who can create a
+//! Rollout can run whatever pods the controller's service account may create.
+//! This policy gives platform teams a seam to keep dangerous pod settings out
+//! of restricted namespaces:
+//!
+//! - `hostNetwork`, `hostPID`, `hostIPC`
+//! - `securityContext.privileged` on containers and init containers
+//!
+//! Configured via `KULTA_RESTRICTED_NAMESPACES` (comma-separated names) and
+//! `KULTA_POD_POLICY_ACTION`: `reject` (default) fails validation in the
+//! admission webhook and the reconcile loop, `strip` removes the fields from
+//! the template before ReplicaSets are built.
+
+use crate::crd::rollout::Rollout;
+use k8s_openapi::api::core::v1::{Container, PodTemplateSpec};
+use kube::ResourceExt;
+use tracing::warn;
+
+/// What to do with a pod template that uses denied fields
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PodPolicyAction {
+    /// Refuse the Rollout
+    #[default]
+    Reject,
+    /// Remove the denied fields and continue
+    Strip,
+}
+
+/// Deny-list policy applied to Rollouts in restricted namespaces
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodTemplatePolicy {
+    /// Namespaces the policy applies to (empty disables the policy)
+    pub namespaces: Vec<String>,
+    pub action: PodPolicyAction,
+}
+
+impl PodTemplatePolicy {
+    /// Create policy from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create policy from an arbitrary key lookup (testable without touching env)
+    ///
+    /// Uses:
+    /// - `KULTA_RESTRICTED_NAMESPACES` (comma-separated, default none)
+    /// - `KULTA_POD_POLICY_ACTION` (`reject` or `strip`, default reject)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let namespaces = lookup("KULTA_RESTRICTED_NAMESPACES")
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|ns| !ns.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let action = match lookup("KULTA_POD_POLICY_ACTION").as_deref() {
+            Some("strip") => PodPolicyAction::Strip,
+            Some("reject") | None => PodPolicyAction::Reject,
+            Some(other) => {
+                warn!(
+                    action = %other,
+                    "Unknown KULTA_POD_POLICY_ACTION, using reject"
+                );
+                PodPolicyAction::Reject
+            }
+        };
+
+        Self { namespaces, action }
+    }
+
+    /// Whether the policy covers the given namespace
+    pub fn applies_to(&self, namespace: &str) -> bool {
+        self.namespaces.iter().any(|ns| ns == namespace)
+    }
+}
+
+/// List denied fields set in a pod template (as field paths)
+pub fn find_denied_fields(template: &PodTemplateSpec) -> Vec<String> {
+    let Some(spec) = template.spec.as_ref() else {
+        return Vec::new();
+    };
+
+    let mut denied = Vec::new();
+    for (field, value) in [
+        ("hostNetwork", spec.host_network),
+        ("hostPID", spec.host_pid),
+        ("hostIPC", spec.host_ipc),
+    ] {
+        if value == Some(true) {
+            denied.push(format!("spec.template.spec.{}", field));
+        }
+    }
+
+    let init_containers = spec.init_containers.iter().flatten();
+    for (kind, container) in spec
+        .containers
+        .iter()
+        .map(|c| ("containers", c))
+        .chain(init_containers.map(|c| ("initContainers", c)))
+    {
+        if is_privileged(container) {
+            denied.push(format!(
+                "spec.template.spec.{}[{}].securityContext.privileged",
+                kind, container.name
+            ));
+        }
+    }
+
+    denied
+}
+
+fn is_privileged(container: &Container) -> bool {
+    container
+        .security_context
+        .as_ref()
+        .and_then(|sc| sc.privileged)
+        == Some(true)
+}
+
+/// Remove denied fields from a pod template
+fn strip_denied_fields(template: &mut PodTemplateSpec) {
+    let Some(spec) = template.spec.as_mut() else {
+        return;
+    };
+
+    spec.host_network = None;
+    spec.host_pid = None;
+    spec.host_ipc = None;
+
+    let init_containers = spec.init_containers.iter_mut().flatten();
+    for container in spec.containers.iter_mut().chain(init_containers) {
+        if let Some(sc) = container.security_context.as_mut() {
+            sc.privileged = None;
+        }
+    }
+}
+
+/// Reject a pod template that uses denied fields in a restricted namespace
+///
+/// Only fails under the `reject` action; `strip` templates are fixed up by
+/// [`sanitize_rollout`] instead.
+pub fn check_pod_template(
+    template: &PodTemplateSpec,
+    namespace: &str,
+    policy: &PodTemplatePolicy,
+) -> Result<(), String> {
+    if policy.action != PodPolicyAction::Reject || !policy.applies_to(namespace) {
+        return Ok(());
+    }
+
+    let denied = find_denied_fields(template);
+    if denied.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "pod template uses fields denied in restricted namespace '{}': {}",
+        namespace,
+        denied.join(", ")
+    ))
+}
+
+/// Strip denied fields from a Rollout's pod template under the `strip` action
+///
+/// # Returns
+/// * `Some(rollout)` - Copy of the Rollout with a sanitized template
+/// * `None` - Policy doesn't apply or nothing to strip (use the original)
+pub fn sanitize_rollout(rollout: &Rollout, policy: &PodTemplatePolicy) -> Option<Rollout> {
+    if policy.action != PodPolicyAction::Strip {
+        return None;
+    }
+    let namespace = rollout.namespace()?;
+    if !policy.applies_to(&namespace) {
+        return None;
+    }
+
+    let denied = find_denied_fields(&rollout.spec.template);
+    if denied.is_empty() {
+        return None;
+    }
+
+    warn!(
+        rollout = ?rollout.name_any(),
+        namespace = %namespace,
+        fields = ?denied,
+        "Stripping denied pod template fields for restricted namespace"
+    );
+    let mut sanitized = rollout.clone();
+    strip_denied_fields(&mut sanitized.spec.template);
+    Some(sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use k8s_openapi::api::core::v1::{PodSpec, SecurityContext};
+
+    fn template(host_network: bool, privileged: bool) -> PodTemplateSpec {
+        PodTemplateSpec {
+            metadata: None,
+            spec: Some(PodSpec {
+                host_network: Some(host_network),
+                containers: vec![Container {
+                    name: "app".to_string(),
+                    image: Some("nginx".to_string()),
+                    security_context: Some(SecurityContext {
+                        privileged: Some(privileged),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn policy(action: PodPolicyAction) -> PodTemplatePolicy {
+        PodTemplatePolicy {
+            namespaces: vec!["prod".to_string()],
+            action,
+        }
+    }
+
+    #[test]
+    fn test_policy_from_lookup() {
+        let policy = PodTemplatePolicy::from_lookup(|key| match key {
+            "KULTA_RESTRICTED_NAMESPACES" => Some("prod, payments,".to_string()),
+            "KULTA_POD_POLICY_ACTION" => Some("strip".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(policy.namespaces, vec!["prod", "payments"]);
+        assert_eq!(policy.action, PodPolicyAction::Strip);
+        assert_eq!(
+            PodTemplatePolicy::from_lookup(|_| None),
+            PodTemplatePolicy::default()
+        );
+    }
+
+    #[test]
+    fn test_find_denied_fields() {
+        assert!(find_denied_fields(&template(false, false)).is_empty());
+        assert_eq!(
+            find_denied_fields(&template(true, true)),
+            vec![
+                "spec.template.spec.hostNetwork",
+                "spec.template.spec.containers[app].securityContext.privileged",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_pod_template_rejects_only_in_restricted_namespaces() {
+        let reject = policy(PodPolicyAction::Reject);
+
+        let error = check_pod_template(&template(true, false), "prod", &reject).unwrap_err();
+        assert!(error.contains("hostNetwork"), "got: {}", error);
+
+        assert!(check_pod_template(&template(true, false), "dev", &reject).is_ok());
+        assert!(check_pod_template(&template(false, false), "prod", &reject).is_ok());
+        assert!(check_pod_template(
+            &template(true, false),
+            "prod",
+            &policy(PodPolicyAction::Strip)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_strip_denied_fields() {
+        let mut stripped = template(true, true);
+        strip_denied_fields(&mut stripped);
+
+        assert!(find_denied_fields(&stripped).is_empty());
+        assert_eq!(
+            stripped.spec.unwrap().containers[0].image.as_deref(),
+            Some("nginx")
+        );
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! This module handles querying Prometheus and evaluating metrics against thresholds.
 
+use crate::controller::prometheus_auth::{ClientOptions, PrometheusAuth};
+use crate::controller::web_metric::{fetch_web_metric, web_metric_client};
+use crate::crd::rollout::{SampleWindow, WebMetric};
 use async_trait::async_trait;
 use serde::Deserialize;
+use std::time::Duration;
 use thiserror::Error;
+use tracing::{debug, warn};
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum PrometheusError {
     #[error("Prometheus HTTP error: {0}")]
     HttpError(String),
@@ -22,6 +27,12 @@ pub enum PrometheusError {
 
     #[error("Invalid metric value: {0}")]
     InvalidValue(String),
+
+    #[error("Invalid Prometheus client configuration: {0}")]
+    ConfigError(String),
+
+    #[error("Web metric error: {0}")]
+    WebMetricError(String),
 }
 
 /// Trait for querying Prometheus metrics
@@ -36,26 +47,254 @@ pub trait MetricsQuerier: Send + Sync {
     /// Downcast support for testing (allows accessing mock-specific methods)
     fn as_any(&self) -> &dyn std::any::Any;
 
-    /// Evaluate a metric by name against threshold
-    async fn evaluate_metric(
+    /// Read a metric's `web` endpoint (already rendered)
+    ///
+    /// Doesn't go through Prometheus; the default requests the URL directly.
+    async fn query_web(&self, web: &WebMetric) -> Result<f64, PrometheusError> {
+        fetch_web_metric(web_metric_client(), web).await
+    }
+
+    /// Difference between a metric and the stable revision's value, at a past
+    /// offset when given
+    ///
+    /// With an offset this serves seasonal traffic, where an absolute
+    /// threshold would trip on a normal weekly peak.
+    async fn query_baseline_delta(
         &self,
         metric_name: &str,
         rollout_name: &str,
-        revision: &str,
-        threshold: f64,
+        revision: &RevisionSelector,
+        offset: Option<&str>,
+        extra_matchers: &str,
+    ) -> Result<f64, PrometheusError> {
+        let query = build_metric_query(
+            metric_name,
+            rollout_name,
+            &revision.matcher()?,
+            extra_matchers,
+            None,
+        )?;
+        let value = self.query_instant(&query).await?;
+        let baseline_query = build_metric_query(
+            metric_name,
+            rollout_name,
+            &revision.baseline_matcher()?,
+            extra_matchers,
+            offset,
+        )?;
+        let baseline = self.query_instant(&baseline_query).await?;
+        Ok(value - baseline)
+    }
+
+    /// Requests a revision served over the built-in templates' window
+    ///
+    /// A revision without any requests has no series; that counts as 0.
+    async fn query_request_volume(
+        &self,
+        rollout_name: &str,
+        revision_matcher: &str,
+        extra_matchers: &str,
+        offset: Option<&str>,
+    ) -> Result<f64, PrometheusError> {
+        let query = build_request_volume_query(
+            rollout_name,
+            revision_matcher,
+            extra_matchers,
+            &build_offset_modifier(offset)?,
+        );
+        match self.query_instant(&query).await {
+            Err(PrometheusError::NoData) => Ok(0.0),
+            result => result,
+        }
+    }
+
+    /// Difference between a metric and the stable revision, weighted by the
+    /// request volume of both when `volumeWeighting` is set
+    async fn query_stable_comparison(
+        &self,
+        metric: &crate::crd::rollout::MetricConfig,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        extra_matchers: &str,
+    ) -> Result<f64, PrometheusError> {
+        let offset = metric.baseline_offset.as_deref();
+        let delta = self
+            .query_baseline_delta(&metric.name, rollout_name, revision, offset, extra_matchers)
+            .await?;
+        let Some(weighting) = &metric.volume_weighting else {
+            return Ok(delta);
+        };
+        let canary = self
+            .query_request_volume(rollout_name, &revision.matcher()?, extra_matchers, None)
+            .await?;
+        let stable = self
+            .query_request_volume(
+                rollout_name,
+                &revision.baseline_matcher()?,
+                extra_matchers,
+                offset,
+            )
+            .await?;
+        let weight = volume_weight(canary, stable, weighting.full_weight_requests() as f64);
+        debug!(
+            metric = %metric.name,
+            delta = delta,
+            canary_requests = canary,
+            stable_requests = stable,
+            weight = weight,
+            "Weighted stable comparison by request volume"
+        );
+        Ok(delta * weight)
+    }
+
+    /// Ratio of a metric to its value on the baseline ReplicaSet (see
+    /// [`baseline_ratio`])
+    async fn query_baseline_ratio(
+        &self,
+        metric_name: &str,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        extra_matchers: &str,
+    ) -> Result<f64, PrometheusError> {
+        let query = build_metric_query(
+            metric_name,
+            rollout_name,
+            &revision.matcher()?,
+            extra_matchers,
+            None,
+        )?;
+        let value = self.query_instant(&query).await?;
+        let baseline_query = build_metric_query(
+            metric_name,
+            rollout_name,
+            &revision.baseline_replicaset_matcher()?,
+            extra_matchers,
+            None,
+        )?;
+        let baseline = self.query_instant(&baseline_query).await?;
+        Ok(baseline_ratio(value, baseline))
+    }
+
+    /// Evaluate a configured metric, honouring `baselineOffset`,
+    /// `compareToStable`, `compareToBaseline`, `volumeWeighting`,
+    /// `comparison` and `unit`
+    ///
+    /// A custom `query` or `web` metric must already be rendered (see
+    /// [`render_query`]).
+    async fn evaluate_metric_config(
+        &self,
+        metric: &crate::crd::rollout::MetricConfig,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        extra_matchers: &str,
     ) -> Result<bool, PrometheusError> {
-        let query = match metric_name {
-            "error-rate" => build_error_rate_query(rollout_name, revision),
-            "latency-p95" => build_latency_p95_query(rollout_name, revision),
-            _ => {
+        if let Some(web) = &metric.web {
+            if !extra_matchers.is_empty() {
+                return Err(PrometheusError::WebMetricError(format!(
+                    "web metric {} can't be restricted to a zone",
+                    metric.name
+                )));
+            }
+            let value = self.query_web(web).await?;
+            return Ok(metric.is_healthy(value));
+        }
+        if let Some(query) = metric.query.as_deref() {
+            if !extra_matchers.is_empty() {
                 return Err(PrometheusError::InvalidQuery(format!(
-                    "Unknown metric template: {}",
-                    metric_name
-                )))
+                    "custom query of metric {} can't be restricted to a zone",
+                    metric.name
+                )));
             }
+            let value = self.query_instant(query).await?;
+            return Ok(metric.is_healthy(value));
+        }
+        let value = if metric.compare_to_baseline == Some(true) {
+            self.query_baseline_ratio(&metric.name, rollout_name, revision, extra_matchers)
+                .await?
+        } else if metric.compares_to_stable() {
+            self.query_stable_comparison(metric, rollout_name, revision, extra_matchers)
+                .await?
+        } else {
+            let query = build_metric_query(
+                &metric.name,
+                rollout_name,
+                &revision.matcher()?,
+                extra_matchers,
+                None,
+            )?;
+            self.query_instant(&query).await?
         };
-        let value = self.query_instant(&query).await?;
-        Ok(value < threshold)
+        Ok(metric.is_healthy(value))
+    }
+
+    /// Evaluate an analysis, combining metric results per its `aggregation`
+    ///
+    /// With `all`, evaluation stops at the first failing metric.
+    async fn evaluate_analysis(
+        &self,
+        analysis: &crate::crd::rollout::AnalysisConfig,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        extra_matchers: &str,
+    ) -> Result<bool, PrometheusError> {
+        let stop_on_failure = analysis.aggregation.unwrap_or_default()
+            == crate::crd::rollout::AnalysisAggregation::All;
+        let mut results = Vec::with_capacity(analysis.metrics.len());
+        for metric in &analysis.metrics {
+            let is_healthy = self
+                .evaluate_metric_config(metric, rollout_name, revision, extra_matchers)
+                .await?;
+            if !is_healthy && stop_on_failure {
+                return Ok(false);
+            }
+            results.push(is_healthy);
+        }
+        Ok(analysis.is_healthy(&results))
+    }
+
+    /// Evaluate every metric of an analysis, without stopping at a failure
+    ///
+    /// Returns one result per metric, in order.
+    async fn evaluate_metric_results(
+        &self,
+        analysis: &crate::crd::rollout::AnalysisConfig,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        extra_matchers: &str,
+    ) -> Result<Vec<bool>, PrometheusError> {
+        let mut results = Vec::with_capacity(analysis.metrics.len());
+        for metric in &analysis.metrics {
+            results.push(
+                self.evaluate_metric_config(metric, rollout_name, revision, extra_matchers)
+                    .await?,
+            );
+        }
+        Ok(results)
+    }
+
+    /// Evaluate an analysis separately for each zone
+    ///
+    /// Returns the zones in which the analysis fails (empty when every zone
+    /// is healthy).
+    async fn find_failing_zones(
+        &self,
+        analysis: &crate::crd::rollout::AnalysisConfig,
+        rollout_name: &str,
+        revision: &RevisionSelector,
+        zone_label: &str,
+        zones: &[String],
+    ) -> Result<Vec<String>, PrometheusError> {
+        let mut failing = Vec::new();
+        for zone in zones {
+            let matcher = build_label_matcher(zone_label, zone)?;
+            if !self
+                .evaluate_analysis(analysis, rollout_name, revision, &matcher)
+                .await?
+            {
+                failing.push(zone.clone());
+            }
+        }
+        Ok(failing)
     }
 
     /// Evaluate all metrics from analysis config
@@ -63,14 +302,14 @@ pub trait MetricsQuerier: Send + Sync {
         &self,
         metrics: &[crate::crd::rollout::MetricConfig],
         rollout_name: &str,
-        revision: &str,
+        revision: &RevisionSelector,
     ) -> Result<bool, PrometheusError> {
         if metrics.is_empty() {
             return Ok(true);
         }
         for metric in metrics {
             let is_healthy = self
-                .evaluate_metric(&metric.name, rollout_name, revision, metric.threshold)
+                .evaluate_metric_config(metric, rollout_name, revision, "")
                 .await?;
             if !is_healthy {
                 return Ok(false);
@@ -79,30 +318,392 @@ pub trait MetricsQuerier: Send + Sync {
         Ok(true)
     }
 
-    /// Query A/B variant error rate
-    async fn query_ab_error_rate(&self, service_name: &str) -> Result<f64, PrometheusError> {
-        let query = build_ab_error_rate_query(service_name);
+    /// Query A/B variant error rate, counting only samples inside `filter` when set
+    async fn query_ab_error_rate(
+        &self,
+        service_name: &str,
+        filter: Option<&SampleFilter>,
+    ) -> Result<f64, PrometheusError> {
+        let query = match filter {
+            Some(filter) => build_ab_windowed_error_rate_query(service_name, filter),
+            None => build_ab_error_rate_query(service_name),
+        };
         self.query_instant(&query).await
     }
 
-    /// Query A/B variant sample count
-    async fn query_ab_sample_count(&self, service_name: &str) -> Result<i64, PrometheusError> {
-        let query = build_ab_sample_count_query(service_name);
+    /// Query an A/B variant metric by template name (error-rate or latency-p95)
+    async fn query_ab_metric(
+        &self,
+        metric_name: &str,
+        service_name: &str,
+    ) -> Result<f64, PrometheusError> {
+        let query = match metric_name {
+            "error-rate" => build_ab_error_rate_query(service_name),
+            "latency-p95" => build_ab_latency_p95_query(service_name),
+            _ => {
+                return Err(PrometheusError::InvalidQuery(format!(
+                    "Unknown metric template: {}",
+                    metric_name
+                )))
+            }
+        };
+        self.query_instant(&query).await
+    }
+
+    /// Query A/B variant sample count, counting only samples inside `filter` when set
+    async fn query_ab_sample_count(
+        &self,
+        service_name: &str,
+        filter: Option<&SampleFilter>,
+    ) -> Result<i64, PrometheusError> {
+        let query = match filter {
+            Some(filter) => build_ab_windowed_sample_count_query(service_name, filter),
+            None => build_ab_sample_count_query(service_name),
+        };
         let count = self.query_instant(&query).await?;
         Ok(count as i64)
     }
 }
 
+/// Default Prometheus label used for per-zone analysis
+pub const DEFAULT_ZONE_LABEL: &str = "zone";
+
+/// Default Prometheus label the built-in templates select the revision by
+pub const DEFAULT_REVISION_LABEL: &str = "revision";
+
+/// Revision label value of the canary pods under the default label
+pub const CANARY_REVISION: &str = "canary";
+
+/// Revision label value of the stable pods, used as the `baselineOffset` baseline
+pub const BASELINE_REVISION: &str = "stable";
+
+/// Revision label value of the baseline ReplicaSet's pods (`canary.baseline`)
+pub const BASELINE_REPLICASET_REVISION: &str = "baseline";
+
+/// Which revision the built-in templates select, and by which label
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevisionSelector {
+    /// Prometheus label carrying the revision
+    pub label: String,
+    /// Label value of the revision under analysis
+    pub value: String,
+    /// Label value of the stable revision, the `baselineOffset` baseline
+    pub baseline: String,
+    /// Label value of the baseline ReplicaSet's pods (`compareToBaseline`)
+    pub baseline_replicaset: String,
+}
+
+impl RevisionSelector {
+    /// `revision="canary"`, compared against `revision="stable"` (or
+    /// `revision="baseline"`, the baseline ReplicaSet)
+    pub fn canary() -> Self {
+        RevisionSelector {
+            label: DEFAULT_REVISION_LABEL.to_string(),
+            value: CANARY_REVISION.to_string(),
+            baseline: BASELINE_REVISION.to_string(),
+            baseline_replicaset: BASELINE_REPLICASET_REVISION.to_string(),
+        }
+    }
+
+    /// Matcher for the revision under analysis (`,label="value"`)
+    pub fn matcher(&self) -> Result<String, PrometheusError> {
+        build_label_matcher(&self.label, &self.value)
+    }
+
+    /// Matcher for the stable revision (`,label="baseline"`)
+    pub fn baseline_matcher(&self) -> Result<String, PrometheusError> {
+        build_label_matcher(&self.label, &self.baseline)
+    }
+
+    /// Matcher for the baseline ReplicaSet (`,label="baseline_replicaset"`)
+    pub fn baseline_replicaset_matcher(&self) -> Result<String, PrometheusError> {
+        build_label_matcher(&self.label, &self.baseline_replicaset)
+    }
+}
+
+/// Label selector the built-in templates use for a rollout's revision
+///
+/// E.g. `{rollout="my-app",revision="canary"}`; templates that filter
+/// further (status codes, zones) add matchers to it.
+pub fn build_builtin_selector(
+    rollout_name: &str,
+    revision: &RevisionSelector,
+) -> Result<String, PrometheusError> {
+    Ok(format!(
+        r#"{{rollout="{}"{}}}"#,
+        rollout_name,
+        revision.matcher()?
+    ))
+}
+
+/// Build the query for a built-in metric template
+///
+/// `revision_matcher` selects the revision (e.g. `,revision="canary"`).
+/// `extra_matchers` is appended verbatim to every label selector in the query
+/// (e.g. `,zone="us-east-1a"`); pass "" for the aggregate query. `offset`
+/// shifts every range selector into the past (PromQL `offset` modifier).
+fn build_metric_query(
+    metric_name: &str,
+    rollout_name: &str,
+    revision_matcher: &str,
+    extra_matchers: &str,
+    offset: Option<&str>,
+) -> Result<String, PrometheusError> {
+    let offset_modifier = build_offset_modifier(offset)?;
+    match metric_name {
+        "error-rate" => Ok(build_error_rate_query(
+            rollout_name,
+            revision_matcher,
+            extra_matchers,
+            &offset_modifier,
+        )),
+        "latency-p95" => Ok(build_latency_p95_query(
+            rollout_name,
+            revision_matcher,
+            extra_matchers,
+            &offset_modifier,
+        )),
+        _ => Err(PrometheusError::InvalidQuery(format!(
+            "Unknown metric template: {}",
+            metric_name
+        ))),
+    }
+}
+
+/// PromQL `offset` modifier (with a leading space), or "" without an offset
+fn build_offset_modifier(offset: Option<&str>) -> Result<String, PrometheusError> {
+    match offset {
+        Some(offset) if is_valid_promql_duration(offset) => Ok(format!(" offset {}", offset)),
+        Some(offset) => Err(PrometheusError::InvalidQuery(format!(
+            "Invalid offset duration: {}",
+            offset
+        ))),
+        None => Ok(String::new()),
+    }
+}
+
+/// Variables a custom metric `query` can reference as `{{name}}`
+pub const QUERY_VARIABLES: [&str; 3] = ["rollout", "namespace", "revision"];
+
+/// Render a custom metric query
+///
+/// Replaces `{{rollout}}`, `{{namespace}}` and `{{revision}}` (whitespace
+/// inside the braces is allowed) and checks the result is well-formed.
+/// Values that would break out of a quoted label value are rejected.
+pub fn render_query(
+    template: &str,
+    rollout_name: &str,
+    namespace: &str,
+    revision: &str,
+) -> Result<String, PrometheusError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            PrometheusError::InvalidQuery(format!("Unterminated variable in query: {}", template))
+        })?;
+        let value = match after[..end].trim() {
+            "rollout" => rollout_name,
+            "namespace" => namespace,
+            "revision" => revision,
+            other => {
+                return Err(PrometheusError::InvalidQuery(format!(
+                    "Unknown query variable {{{{{}}}}} (expected one of {})",
+                    other,
+                    QUERY_VARIABLES.join(", ")
+                )))
+            }
+        };
+        if value.contains(['"', '\\', '\n']) {
+            return Err(PrometheusError::InvalidQuery(format!(
+                "Invalid value for query variable {}: {}",
+                after[..end].trim(),
+                value
+            )));
+        }
+        rendered.push_str(value);
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    check_query_syntax(&rendered)?;
+    Ok(rendered)
+}
+
+/// Check that a query is non-empty, closes its string literals and
+/// balances its brackets
+///
+/// Not a PromQL parser; catches templates mangled by rendering or YAML
+/// quoting before they reach Prometheus.
+pub fn check_query_syntax(query: &str) -> Result<(), PrometheusError> {
+    let invalid = |reason: &str| {
+        Err(PrometheusError::InvalidQuery(format!(
+            "{}: {}",
+            reason, query
+        )))
+    };
+    if query.trim().is_empty() {
+        return invalid("Empty query");
+    }
+    let mut open = Vec::new();
+    let mut chars = query.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' | '`' => {
+                let quote = c;
+                loop {
+                    match chars.next() {
+                        Some('\\') if quote != '`' => {
+                            chars.next();
+                        }
+                        Some(c) if c == quote => break,
+                        Some(_) => {}
+                        None => return invalid("Unterminated string in query"),
+                    }
+                }
+            }
+            '(' | '{' | '[' => open.push(c),
+            ')' | '}' | ']' => {
+                let expected = match c {
+                    ')' => '(',
+                    '}' => '{',
+                    _ => '[',
+                };
+                if open.pop() != Some(expected) {
+                    return invalid("Unbalanced brackets in query");
+                }
+            }
+            _ => {}
+        }
+    }
+    if !open.is_empty() {
+        return invalid("Unbalanced brackets in query");
+    }
+    Ok(())
+}
+
+/// Build an additional `,label="value"` matcher for a label selector
+///
+/// Rejects label names that are not valid Prometheus identifiers and values
+/// that would break out of the quoted string.
+fn build_label_matcher(label: &str, value: &str) -> Result<String, PrometheusError> {
+    if !is_valid_label_name(label) {
+        return Err(PrometheusError::InvalidQuery(format!(
+            "Invalid label name: {}",
+            label
+        )));
+    }
+    if value.contains('"') || value.contains('\\') || value.contains('\n') {
+        return Err(PrometheusError::InvalidQuery(format!(
+            "Invalid label value for {}: {}",
+            label, value
+        )));
+    }
+    Ok(format!(r#",{}="{}""#, label, value))
+}
+
+/// Check a Prometheus label name (`[a-zA-Z_][a-zA-Z0-9_]*`)
+pub fn is_valid_label_name(label: &str) -> bool {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check a PromQL duration such as "7d", "1h30m" or "500ms"
+pub fn is_valid_promql_duration(duration: &str) -> bool {
+    const UNITS: [&str; 7] = ["ms", "s", "m", "h", "d", "w", "y"];
+
+    let mut rest = duration;
+    if rest.is_empty() {
+        return false;
+    }
+    while !rest.is_empty() {
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return false;
+        }
+        rest = &rest[digits..];
+        // "ms" must be tried before "m"
+        match UNITS.iter().find(|unit| rest.starts_with(**unit)) {
+            Some(unit) => rest = &rest[unit.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 /// Build PromQL query for error rate metric
 ///
 /// Calculates: (5xx errors / total requests) * 100
-fn build_error_rate_query(rollout_name: &str, revision: &str) -> String {
+fn build_error_rate_query(
+    rollout_name: &str,
+    revision_matcher: &str,
+    extra_matchers: &str,
+    offset_modifier: &str,
+) -> String {
     format!(
-        r#"sum(rate(http_requests_total{{status=~"5..",rollout="{}",revision="{}"}}[2m])) / sum(rate(http_requests_total{{rollout="{}",revision="{}"}}[2m])) * 100"#,
-        rollout_name, revision, rollout_name, revision
+        r#"sum(rate(http_requests_total{{status=~"5..",rollout="{}"{}{}}}[2m]{})) / sum(rate(http_requests_total{{rollout="{}"{}{}}}[2m]{})) * 100"#,
+        rollout_name,
+        revision_matcher,
+        extra_matchers,
+        offset_modifier,
+        rollout_name,
+        revision_matcher,
+        extra_matchers,
+        offset_modifier
     )
 }
 
+/// Build PromQL query for a revision's request count
+///
+/// Counts the requests over the same 2 minute window the error rate is
+/// computed from.
+fn build_request_volume_query(
+    rollout_name: &str,
+    revision_matcher: &str,
+    extra_matchers: &str,
+    offset_modifier: &str,
+) -> String {
+    format!(
+        r#"sum(increase(http_requests_total{{rollout="{}"{}{}}}[2m]{}))"#,
+        rollout_name, revision_matcher, extra_matchers, offset_modifier
+    )
+}
+
+/// Confidence a difference to the stable revision gets from its request volume
+///
+/// The effective sample size of a difference between two samples,
+/// `canary·stable / (canary + stable)`, is dominated by the smaller one. The
+/// weight grows with its square root and reaches 1 at `full_weight_requests`.
+/// Without requests on either side the difference carries no weight.
+pub fn volume_weight(canary_requests: f64, stable_requests: f64, full_weight_requests: f64) -> f64 {
+    if canary_requests <= 0.0 || stable_requests <= 0.0 {
+        return 0.0;
+    }
+    if full_weight_requests <= 0.0 {
+        return 1.0;
+    }
+    let effective = canary_requests * stable_requests / (canary_requests + stable_requests);
+    (effective / full_weight_requests).sqrt().min(1.0)
+}
+
+/// Ratio of a canary value to the baseline ReplicaSet's
+///
+/// 1 when both are 0; infinite when only the baseline is.
+pub fn baseline_ratio(value: f64, baseline: f64) -> f64 {
+    if baseline != 0.0 {
+        value / baseline
+    } else if value == 0.0 {
+        1.0
+    } else {
+        f64::INFINITY
+    }
+}
+
 /// Build PromQL query for A/B variant error rate
 ///
 /// Queries by service name (variant_a_service or variant_b_service)
@@ -113,6 +714,104 @@ pub fn build_ab_error_rate_query(service_name: &str) -> String {
     )
 }
 
+/// Time filter for A/B queries, resolved from an experiment's `sampleWindow`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleFilter {
+    /// PromQL expression that returns a sample only inside the window
+    pub condition: String,
+    /// Subquery range the samples are summed over (e.g. "7d", "90m")
+    pub lookback: String,
+}
+
+impl SampleFilter {
+    /// Resolve a window, using `default_lookback` when it sets no `lookback`
+    ///
+    /// `default_lookback` is rounded up to whole minutes, at least 5.
+    pub fn new(window: &SampleWindow, default_lookback: Duration) -> Result<Self, PrometheusError> {
+        let lookback = match &window.lookback {
+            Some(lookback) if is_valid_promql_duration(lookback) => lookback.clone(),
+            Some(lookback) => {
+                return Err(PrometheusError::InvalidQuery(format!(
+                    "Invalid sample window lookback: {}",
+                    lookback
+                )))
+            }
+            None => format!("{}m", default_lookback.as_secs().div_ceil(60).max(5)),
+        };
+        Ok(SampleFilter {
+            condition: build_time_window_condition(window),
+            lookback,
+        })
+    }
+}
+
+/// PromQL condition that is non-empty while the current time is inside `window`
+///
+/// Uses `time()`, so inside a subquery it is evaluated at every step.
+pub fn build_time_window_condition(window: &SampleWindow) -> String {
+    let now = match window.utc_offset_hours.unwrap_or(0) {
+        0 => "vector(time())".to_string(),
+        offset => format!("vector(time() + {})", i64::from(offset) * 3600),
+    };
+
+    let mut clauses = Vec::new();
+    let start = window.start_hour.unwrap_or(0);
+    let end = window.end_hour.unwrap_or(24);
+    if start > 0 || end < 24 {
+        clauses.push(format!("(hour({}) >= {} < {})", now, start, end));
+    }
+
+    let mut days: Vec<u32> = window.days.iter().map(|day| day.promql_day()).collect();
+    days.sort_unstable();
+    days.dedup();
+    if !days.is_empty() && days.len() < 7 {
+        let matches: Vec<String> = days
+            .iter()
+            .map(|day| format!("day_of_week({}) == {}", now, day))
+            .collect();
+        clauses.push(format!("({})", matches.join(" or ")));
+    }
+
+    if clauses.is_empty() {
+        "vector(1)".to_string()
+    } else {
+        clauses.join(" and on() ")
+    }
+}
+
+/// Sum of 5 minute request increases inside the window, over the lookback
+fn windowed_increase(selector: &str, filter: &SampleFilter) -> String {
+    format!(
+        "sum_over_time((sum(increase(http_requests_total{{{}}}[5m])) and on() ({}))[{}:5m])",
+        selector, filter.condition, filter.lookback
+    )
+}
+
+/// Build PromQL query for A/B variant error rate over samples inside a time window
+pub fn build_ab_windowed_error_rate_query(service_name: &str, filter: &SampleFilter) -> String {
+    format!(
+        "{} / {}",
+        windowed_increase(
+            &format!(r#"status=~"5..",service="{}""#, service_name),
+            filter
+        ),
+        windowed_increase(&format!(r#"service="{}""#, service_name), filter)
+    )
+}
+
+/// Build PromQL query for A/B variant sample count inside a time window
+pub fn build_ab_windowed_sample_count_query(service_name: &str, filter: &SampleFilter) -> String {
+    windowed_increase(&format!(r#"service="{}""#, service_name), filter)
+}
+
+/// Build PromQL query for A/B variant latency p95 (seconds)
+pub fn build_ab_latency_p95_query(service_name: &str) -> String {
+    format!(
+        r#"histogram_quantile(0.95, sum by (le) (rate(http_request_duration_seconds_bucket{{service="{}"}}[5m])))"#,
+        service_name
+    )
+}
+
 /// Build PromQL query for A/B variant sample count
 ///
 /// Returns total request count for a service
@@ -126,10 +825,15 @@ pub fn build_ab_sample_count_query(service_name: &str) -> String {
 /// Build PromQL query for latency p95 metric
 ///
 /// Uses histogram_quantile to calculate 95th percentile
-fn build_latency_p95_query(rollout_name: &str, revision: &str) -> String {
+fn build_latency_p95_query(
+    rollout_name: &str,
+    revision_matcher: &str,
+    extra_matchers: &str,
+    offset_modifier: &str,
+) -> String {
     format!(
-        r#"histogram_quantile(0.95, rate(http_request_duration_seconds_bucket{{rollout="{}",revision="{}"}}[2m]))"#,
-        rollout_name, revision
+        r#"histogram_quantile(0.95, rate(http_request_duration_seconds_bucket{{rollout="{}"{}{}}}[2m]{}))"#,
+        rollout_name, revision_matcher, extra_matchers, offset_modifier
     )
 }
 
@@ -185,34 +889,168 @@ fn parse_prometheus_instant_query(json_response: &str) -> Result<f64, Prometheus
     Ok(value)
 }
 
+/// Timeout for one query against one Prometheus replica
+const PROMETHEUS_QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How queries are spread over Prometheus replicas
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplicaPolicy {
+    /// Try replicas in order, first successful answer wins
+    #[default]
+    Fallback,
+    /// Query every replica; a majority must answer, the median of the answers wins
+    Quorum,
+}
+
+impl ReplicaPolicy {
+    /// Parse `KULTA_PROMETHEUS_POLICY` (`fallback` or `quorum`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "fallback" => Some(ReplicaPolicy::Fallback),
+            "quorum" => Some(ReplicaPolicy::Quorum),
+            _ => None,
+        }
+    }
+
+    /// Policy from `KULTA_PROMETHEUS_POLICY` (fallback when unset or unknown)
+    pub fn from_env() -> Self {
+        match std::env::var("KULTA_PROMETHEUS_POLICY") {
+            Ok(value) if !value.trim().is_empty() => Self::parse(&value).unwrap_or_else(|| {
+                warn!(policy = %value, "Unknown KULTA_PROMETHEUS_POLICY, using fallback");
+                ReplicaPolicy::Fallback
+            }),
+            _ => ReplicaPolicy::Fallback,
+        }
+    }
+}
+
+/// Split a comma-separated list of Prometheus addresses
+///
+/// Blank entries and trailing slashes are dropped.
+pub fn parse_addresses(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|address| address.trim().trim_end_matches('/'))
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Combine the answers of all replicas under the quorum policy
+///
+/// Needs answers from a strict majority of `replicas`. The median of the
+/// answers is returned, so a single replica with stale or diverging data
+/// cannot flip a health decision on its own. Without a majority the first
+/// replica error is returned.
+fn combine_quorum(
+    results: Vec<Result<f64, PrometheusError>>,
+    replicas: usize,
+) -> Result<f64, PrometheusError> {
+    let mut values = Vec::new();
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if values.len() * 2 <= replicas {
+        return Err(first_error.unwrap_or_else(|| {
+            PrometheusError::HttpError(format!(
+                "No quorum: {} of {} Prometheus replicas answered",
+                values.len(),
+                replicas
+            ))
+        }));
+    }
+
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Ok((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Ok(values[mid])
+    }
+}
+
 /// Production Prometheus client that queries a real server
+///
+/// Several replicas of the same Prometheus (e.g. an HA pair scraping the same
+/// targets) can be configured, so one replica outage doesn't pause every
+/// rollout. See [`ReplicaPolicy`] for how their answers are used.
 #[derive(Clone)]
 pub struct HttpPrometheusClient {
-    address: String,
+    addresses: Vec<String>,
+    policy: ReplicaPolicy,
+    http: reqwest::Client,
+    auth: PrometheusAuth,
 }
 
 impl HttpPrometheusClient {
     pub fn new(address: String) -> Self {
-        Self { address }
+        Self::with_replicas(vec![address], ReplicaPolicy::Fallback)
     }
-}
 
-#[async_trait]
-impl MetricsQuerier for HttpPrometheusClient {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    /// Client for several Prometheus replicas
+    pub fn with_replicas(addresses: Vec<String>, policy: ReplicaPolicy) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(PROMETHEUS_QUERY_TIMEOUT)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Failed to build Prometheus HTTP client, using defaults");
+                reqwest::Client::new()
+            });
+        Self {
+            addresses,
+            policy,
+            http,
+            auth: PrometheusAuth::None,
+        }
     }
 
-    async fn query_instant(&self, query: &str) -> Result<f64, PrometheusError> {
-        let url = format!("{}/api/v1/query", self.address);
-        let client = reqwest::Client::new();
+    /// Client for Prometheus replicas that need credentials or custom TLS
+    ///
+    /// # Returns
+    /// * `Err(PrometheusError::ConfigError)` - A certificate or key isn't valid PEM
+    pub fn with_options(
+        addresses: Vec<String>,
+        policy: ReplicaPolicy,
+        options: &ClientOptions,
+    ) -> Result<Self, PrometheusError> {
+        let http = options
+            .tls
+            .apply(reqwest::Client::builder().timeout(PROMETHEUS_QUERY_TIMEOUT))?
+            .build()
+            .map_err(|e| PrometheusError::ConfigError(e.to_string()))?;
+        Ok(Self {
+            addresses,
+            policy,
+            http,
+            auth: options.auth.clone(),
+        })
+    }
 
-        let response = client
-            .get(&url)
-            .query(&[("query", query)])
+    /// Run an instant query against a single replica
+    async fn query_replica(&self, address: &str, query: &str) -> Result<f64, PrometheusError> {
+        let url = format!("{}/api/v1/query", address);
+
+        let request = self.http.get(&url).query(&[("query", query)]);
+        let response = self
+            .auth
+            .apply(request)?
             .send()
             .await
             .map_err(|e| PrometheusError::HttpError(format!("HTTP request failed: {}", e)))?;
+        if matches!(response.status().as_u16(), 401 | 403) {
+            return Err(PrometheusError::HttpError(format!(
+                "{} rejected the credentials ({})",
+                address,
+                response.status()
+            )));
+        }
 
         let body = response
             .text()
@@ -223,16 +1061,78 @@ impl MetricsQuerier for HttpPrometheusClient {
     }
 }
 
+#[async_trait]
+impl MetricsQuerier for HttpPrometheusClient {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn query_instant(&self, query: &str) -> Result<f64, PrometheusError> {
+        match self.policy {
+            ReplicaPolicy::Fallback => {
+                let mut last_error =
+                    PrometheusError::HttpError("No Prometheus address configured".to_string());
+                for address in &self.addresses {
+                    match self.query_replica(address, query).await {
+                        Ok(value) => return Ok(value),
+                        Err(e) => {
+                            debug!(address = %address, error = %e, "Prometheus replica query failed");
+                            last_error = e;
+                        }
+                    }
+                }
+                Err(last_error)
+            }
+            ReplicaPolicy::Quorum => {
+                let results = futures::future::join_all(
+                    self.addresses
+                        .iter()
+                        .map(|address| self.query_replica(address, query)),
+                )
+                .await;
+                combine_quorum(results, self.addresses.len())
+            }
+        }
+    }
+}
+
 /// Mock Prometheus client for testing
 ///
-/// Supports two modes:
-/// - Single response: `set_mock_response()` sets one response returned for all queries
+/// Supports three modes, checked in this order:
+/// - Scripts: `script()` answers queries containing a pattern from a
+///   sequence (healthy, healthy, unhealthy, ...), one entry per query, so
+///   multi-reconcile scenarios (flapping, warmup expiry) play out deterministically
 /// - Response queue: `enqueue_response()` / `enqueue_error()` for sequential multi-query tests
+/// - Single response: `set_mock_response()` sets one response returned for all queries
+///
+/// `set_latency()` delays every answer, e.g. to exercise timeouts.
 #[cfg(test)]
 #[derive(Clone)]
 pub struct MockPrometheusClient {
     mock_response: std::sync::Arc<std::sync::Mutex<Option<String>>>,
     response_queue: std::sync::Arc<std::sync::Mutex<Vec<Result<f64, PrometheusError>>>>,
+    scripts: std::sync::Arc<std::sync::Mutex<Vec<QueryScript>>>,
+    latency: std::sync::Arc<std::sync::Mutex<Option<std::time::Duration>>>,
+    queries: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+/// Scripted answers for the queries containing `pattern`
+#[cfg(test)]
+struct QueryScript {
+    pattern: String,
+    responses: std::collections::VecDeque<Result<f64, PrometheusError>>,
+}
+
+#[cfg(test)]
+impl QueryScript {
+    /// Next scripted answer; the last one repeats once the script runs out
+    fn next(&mut self) -> Option<Result<f64, PrometheusError>> {
+        if self.responses.len() > 1 {
+            self.responses.pop_front()
+        } else {
+            self.responses.front().cloned()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -248,6 +1148,9 @@ impl MockPrometheusClient {
         Self {
             mock_response: std::sync::Arc::new(std::sync::Mutex::new(None)),
             response_queue: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            scripts: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            latency: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            queries: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
 
@@ -270,6 +1173,48 @@ impl MockPrometheusClient {
             queue.push(Err(error));
         }
     }
+
+    /// Answer queries containing `pattern` from `responses`, one per query
+    ///
+    /// The last response repeats once the sequence is used up. Scripts are
+    /// matched in the order they were added.
+    pub fn script(
+        &self,
+        pattern: &str,
+        responses: impl IntoIterator<Item = Result<f64, PrometheusError>>,
+    ) {
+        if let Ok(mut scripts) = self.scripts.lock() {
+            scripts.push(QueryScript {
+                pattern: pattern.to_string(),
+                responses: responses.into_iter().collect(),
+            });
+        }
+    }
+
+    /// Answer queries containing `pattern` with `values`, one per query
+    pub fn script_values(&self, pattern: &str, values: &[f64]) {
+        self.script(pattern, values.iter().copied().map(Ok));
+    }
+
+    /// Delay every answer by `latency`
+    pub fn set_latency(&self, latency: std::time::Duration) {
+        if let Ok(mut current) = self.latency.lock() {
+            *current = Some(latency);
+        }
+    }
+
+    /// Queries received so far, oldest first
+    pub fn queries(&self) -> Vec<String> {
+        self.queries.lock().map(|q| q.clone()).unwrap_or_default()
+    }
+
+    fn scripted(&self, query: &str) -> Option<Result<f64, PrometheusError>> {
+        let mut scripts = self.scripts.lock().ok()?;
+        scripts
+            .iter_mut()
+            .find(|script| query.contains(&script.pattern))
+            .and_then(QueryScript::next)
+    }
 }
 
 #[cfg(test)]
@@ -279,7 +1224,23 @@ impl MetricsQuerier for MockPrometheusClient {
         self
     }
 
-    async fn query_instant(&self, _query: &str) -> Result<f64, PrometheusError> {
+    /// Answered like a query for the URL, so tests script web metrics by URL
+    async fn query_web(&self, web: &WebMetric) -> Result<f64, PrometheusError> {
+        self.query_instant(&web.url).await
+    }
+
+    async fn query_instant(&self, query: &str) -> Result<f64, PrometheusError> {
+        let latency = self.latency.lock().ok().and_then(|l| *l);
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        if let Ok(mut queries) = self.queries.lock() {
+            queries.push(query.to_string());
+        }
+
+        if let Some(answer) = self.scripted(query) {
+            return answer;
+        }
         // If queue has entries, use FIFO order
         if let Ok(mut queue) = self.response_queue.lock() {
             if !queue.is_empty() {
@@ -300,14 +1261,15 @@ impl MetricsQuerier for MockPrometheusClient {
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used)]
     use super::*;
 
     #[test]
     fn test_build_error_rate_query() {
         let rollout_name = "my-app";
-        let revision = "canary";
+        let revision = r#",revision="canary""#;
 
-        let query = build_error_rate_query(rollout_name, revision);
+        let query = build_error_rate_query(rollout_name, revision, "", "");
 
         assert!(query.contains("http_requests_total"));
         assert!(query.contains(r#"status=~"5..""#));
@@ -318,9 +1280,9 @@ mod tests {
     #[test]
     fn test_build_latency_p95_query() {
         let rollout_name = "my-app";
-        let revision = "stable";
+        let revision = r#",revision="stable""#;
 
-        let query = build_latency_p95_query(rollout_name, revision);
+        let query = build_latency_p95_query(rollout_name, revision, "", "");
 
         assert!(query.contains("histogram_quantile"));
         assert!(query.contains("0.95"));
@@ -328,6 +1290,500 @@ mod tests {
         assert!(query.contains(revision));
     }
 
+    #[test]
+    fn test_build_metric_query_with_zone_matcher() {
+        let matcher = build_label_matcher("zone", "us-east-1a").unwrap();
+        let query = build_metric_query(
+            "error-rate",
+            "my-app",
+            &RevisionSelector::canary().matcher().unwrap(),
+            &matcher,
+            None,
+        )
+        .unwrap();
+
+        // Both numerator and denominator must be restricted to the zone
+        assert_eq!(query.matches(r#"zone="us-east-1a""#).count(), 2);
+        assert!(query.contains(r#"revision="canary",zone="us-east-1a"}"#));
+    }
+
+    #[test]
+    fn test_revision_selector_with_custom_label() {
+        let revision = RevisionSelector {
+            label: "pod_template_hash".to_string(),
+            value: "5d8f9c7b6a".to_string(),
+            baseline: "1a2b3c4d5e".to_string(),
+            baseline_replicaset: "1a2b3c4d5e-baseline".to_string(),
+        };
+
+        assert_eq!(
+            build_builtin_selector("my-app", &revision).unwrap(),
+            r#"{rollout="my-app",pod_template_hash="5d8f9c7b6a"}"#
+        );
+        let query = build_metric_query(
+            "latency-p95",
+            "my-app",
+            &revision.baseline_matcher().unwrap(),
+            "",
+            None,
+        )
+        .unwrap();
+        assert!(query.contains(r#"{rollout="my-app",pod_template_hash="1a2b3c4d5e"}"#));
+        assert!(!query.contains("revision="));
+
+        assert_eq!(
+            build_builtin_selector("my-app", &RevisionSelector::canary()).unwrap(),
+            r#"{rollout="my-app",revision="canary"}"#
+        );
+    }
+
+    #[test]
+    fn test_build_label_matcher_rejects_invalid_input() {
+        assert!(build_label_matcher("topology.kubernetes.io/zone", "a").is_err());
+        assert!(build_label_matcher("1zone", "a").is_err());
+        assert!(build_label_matcher("zone", r#"a"} or vector(1) #"#).is_err());
+        assert!(build_label_matcher("topology_zone", "eu-west-1b").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_analysis_weighted_score() {
+        use crate::crd::rollout::{AnalysisAggregation, AnalysisConfig, MetricConfig};
+
+        let metric = |name: &str, weight: f64| MetricConfig {
+            name: name.to_string(),
+            threshold: 5.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: Some(weight),
+            web: None,
+        };
+        let analysis = AnalysisConfig {
+            prometheus: None,
+            failure_policy: None,
+            warmup_duration: None,
+            metrics: vec![metric("error-rate", 3.0), metric("latency-p95", 1.0)],
+            per_zone: None,
+            revision_label_key: None,
+            aggregation: Some(AnalysisAggregation::Score),
+            pass_score: Some(0.7),
+            on_resume: None,
+        };
+
+        let client = MockPrometheusClient::new();
+        // Noisy latency breaches, error rate holds: score 0.75
+        client.enqueue_response(1.0);
+        client.enqueue_response(9.0);
+        assert!(client
+            .evaluate_analysis(&analysis, "my-app", &RevisionSelector::canary(), "")
+            .await
+            .unwrap());
+
+        // Error rate breaches: score 0.25
+        client.enqueue_response(9.0);
+        client.enqueue_response(1.0);
+        assert!(!client
+            .evaluate_analysis(&analysis, "my-app", &RevisionSelector::canary(), "")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_find_failing_zones_reports_only_failing_zone() {
+        use crate::crd::rollout::{AnalysisConfig, MetricConfig};
+
+        let client = MockPrometheusClient::new();
+        // zone-a: healthy, zone-b: breach, zone-c: healthy
+        client.enqueue_response(1.0);
+        client.enqueue_response(9.0);
+        client.enqueue_response(0.5);
+
+        let analysis = AnalysisConfig {
+            prometheus: None,
+            failure_policy: None,
+            warmup_duration: None,
+            metrics: vec![MetricConfig {
+                name: "error-rate".to_string(),
+                threshold: 5.0,
+                interval: None,
+                failure_threshold: None,
+                query: None,
+                min_sample_size: None,
+                baseline_offset: None,
+                compare_to_stable: None,
+                compare_to_baseline: None,
+                volume_weighting: None,
+                comparison: None,
+                unit: None,
+                weight: None,
+                web: None,
+            }],
+            per_zone: None,
+            revision_label_key: None,
+            aggregation: None,
+            pass_score: None,
+            on_resume: None,
+        };
+        let zones = vec![
+            "zone-a".to_string(),
+            "zone-b".to_string(),
+            "zone-c".to_string(),
+        ];
+
+        let failing = client
+            .find_failing_zones(
+                &analysis,
+                "my-app",
+                &RevisionSelector::canary(),
+                DEFAULT_ZONE_LABEL,
+                &zones,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(failing, vec!["zone-b".to_string()]);
+    }
+
+    #[test]
+    fn test_build_metric_query_with_offset() {
+        let query = build_metric_query(
+            "error-rate",
+            "my-app",
+            r#",revision="stable""#,
+            "",
+            Some("7d"),
+        )
+        .unwrap();
+
+        // Every range selector must be shifted, or the ratio mixes time windows
+        assert_eq!(query.matches("[2m] offset 7d").count(), 2);
+        assert!(query.contains(r#"revision="stable""#));
+
+        let query = build_metric_query(
+            "latency-p95",
+            "my-app",
+            r#",revision="stable""#,
+            "",
+            Some("1w"),
+        )
+        .unwrap();
+        assert!(query.contains("[2m] offset 1w))"));
+    }
+
+    #[test]
+    fn test_build_metric_query_rejects_invalid_offset() {
+        let result = build_metric_query(
+            "error-rate",
+            "my-app",
+            r#",revision="stable""#,
+            "",
+            Some("7d) or vector(1"),
+        );
+        assert!(matches!(result, Err(PrometheusError::InvalidQuery(_))));
+    }
+
+    fn business_hours() -> SampleWindow {
+        use crate::crd::rollout::Weekday;
+        SampleWindow {
+            days: vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            start_hour: Some(9),
+            end_hour: Some(17),
+            utc_offset_hours: None,
+            lookback: None,
+        }
+    }
+
+    #[test]
+    fn test_build_time_window_condition() {
+        assert_eq!(
+            build_time_window_condition(&business_hours()),
+            "(hour(vector(time())) >= 9 < 17) and on() (day_of_week(vector(time())) == 1 or day_of_week(vector(time())) == 2 or day_of_week(vector(time())) == 3 or day_of_week(vector(time())) == 4 or day_of_week(vector(time())) == 5)"
+        );
+
+        let whole_day = SampleWindow {
+            days: vec![crate::crd::rollout::Weekday::Sun],
+            start_hour: None,
+            end_hour: None,
+            utc_offset_hours: Some(2),
+            lookback: None,
+        };
+        assert_eq!(
+            build_time_window_condition(&whole_day),
+            "(day_of_week(vector(time() + 7200)) == 0)"
+        );
+
+        let always = SampleWindow {
+            days: vec![],
+            ..whole_day
+        };
+        assert_eq!(build_time_window_condition(&always), "vector(1)");
+    }
+
+    #[test]
+    fn test_sample_filter_lookback() {
+        let filter =
+            SampleFilter::new(&business_hours(), Duration::from_secs(90 * 60 + 1)).unwrap();
+        assert_eq!(filter.lookback, "91m");
+
+        let filter = SampleFilter::new(&business_hours(), Duration::from_secs(10)).unwrap();
+        assert_eq!(filter.lookback, "5m");
+
+        let explicit = SampleWindow {
+            lookback: Some("7d".to_string()),
+            ..business_hours()
+        };
+        let filter = SampleFilter::new(&explicit, Duration::from_secs(60)).unwrap();
+        assert_eq!(filter.lookback, "7d");
+
+        let invalid = SampleWindow {
+            lookback: Some("a week".to_string()),
+            ..business_hours()
+        };
+        assert!(SampleFilter::new(&invalid, Duration::from_secs(60)).is_err());
+    }
+
+    #[test]
+    fn test_build_ab_windowed_queries() {
+        let filter = SampleFilter::new(&business_hours(), Duration::from_secs(3600)).unwrap();
+
+        let count = build_ab_windowed_sample_count_query("checkout-b", &filter);
+        assert_eq!(
+            count,
+            format!(
+                r#"sum_over_time((sum(increase(http_requests_total{{service="checkout-b"}}[5m])) and on() ({}))[60m:5m])"#,
+                filter.condition
+            )
+        );
+
+        let rate = build_ab_windowed_error_rate_query("checkout-b", &filter);
+        assert!(rate.starts_with(
+            r#"sum_over_time((sum(increase(http_requests_total{status=~"5..",service="checkout-b"}[5m]))"#
+        ));
+        assert!(rate.ends_with(&format!(" / {}", count)));
+    }
+
+    #[test]
+    fn test_is_valid_promql_duration() {
+        for valid in ["7d", "1w", "1h30m", "500ms", "90s", "1y"] {
+            assert!(is_valid_promql_duration(valid), "{} should be valid", valid);
+        }
+        for invalid in ["", "7", "d", "7 d", "-7d", "7days", "1.5h"] {
+            assert!(
+                !is_valid_promql_duration(invalid),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_all_metrics_with_baseline_offset() {
+        use crate::crd::rollout::MetricConfig;
+
+        let metrics = vec![MetricConfig {
+            name: "error-rate".to_string(),
+            threshold: 2.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: Some("7d".to_string()),
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }];
+
+        // Canary 6% vs 5% last week: within allowed increase of 2
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(6.0);
+        client.enqueue_response(5.0);
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(healthy, "6% should pass against a 5% seasonal baseline");
+
+        // Canary 6% vs 1% last week: increase of 5 breaches
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(6.0);
+        client.enqueue_response(1.0);
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(!healthy, "6% should fail against a 1% seasonal baseline");
+    }
+
+    #[test]
+    fn test_volume_weight() {
+        // 50 canary requests against a busy stable: the canary's volume decides
+        let quiet = volume_weight(50.0, 100_000.0, 1000.0);
+        assert!((quiet - (50.0 * 100_000.0 / 100_050.0 / 1000.0_f64).sqrt()).abs() < 1e-9);
+        assert!(quiet < 0.25);
+
+        assert_eq!(volume_weight(5000.0, 100_000.0, 1000.0), 1.0);
+        assert_eq!(volume_weight(0.0, 100_000.0, 1000.0), 0.0);
+        assert_eq!(volume_weight(50.0, 100_000.0, 0.0), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_compare_to_stable_weighted_by_request_volume() {
+        use crate::crd::rollout::{MetricConfig, VolumeWeighting};
+
+        let metrics = vec![MetricConfig {
+            name: "error-rate".to_string(),
+            threshold: 1.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: Some(true),
+            compare_to_baseline: None,
+            volume_weighting: Some(VolumeWeighting::default()),
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }];
+
+        // 2 errors in 50 canary requests (4%) vs 1%: too few requests to count
+        let client = MockPrometheusClient::new();
+        for value in [4.0, 1.0, 50.0, 100_000.0] {
+            client.enqueue_response(value);
+        }
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(healthy, "a 3 point increase over 50 requests should pass");
+        let queries = client.queries();
+        assert!(queries[1].contains(r#"revision="stable""#));
+        assert_eq!(
+            queries[2],
+            r#"sum(increase(http_requests_total{rollout="my-app",revision="canary"}[2m]))"#
+        );
+
+        // The same rates over 5000 canary requests breach
+        let client = MockPrometheusClient::new();
+        for value in [4.0, 1.0, 5000.0, 100_000.0] {
+            client.enqueue_response(value);
+        }
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(
+            !healthy,
+            "a 3 point increase over 5000 requests should fail"
+        );
+    }
+
+    #[test]
+    fn test_baseline_ratio() {
+        assert_eq!(baseline_ratio(3.0, 2.0), 1.5);
+        assert_eq!(baseline_ratio(0.0, 0.0), 1.0);
+        assert_eq!(baseline_ratio(0.5, 0.0), f64::INFINITY);
+    }
+
+    #[tokio::test]
+    async fn test_compare_to_baseline_uses_ratio_threshold() {
+        use crate::crd::rollout::MetricConfig;
+
+        // Canary error rate below 1.5x the baseline ReplicaSet's
+        let metrics = vec![MetricConfig {
+            name: "error-rate".to_string(),
+            threshold: 1.5,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: Some(true),
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }];
+
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(3.0);
+        client.enqueue_response(2.5);
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(healthy, "1.2x the baseline should pass");
+        let queries = client.queries();
+        assert!(queries[0].contains(r#"revision="canary""#));
+        assert!(queries[1].contains(r#"revision="baseline""#));
+
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(3.0);
+        client.enqueue_response(1.5);
+        let healthy = client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap();
+        assert!(!healthy, "2x the baseline should fail");
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_metric_with_comparison_and_unit() {
+        use crate::crd::rollout::{MetricComparison, MetricConfig, MetricUnit};
+
+        // p95 latency (seconds) must stay at or below 250ms
+        let metrics = vec![MetricConfig {
+            name: "latency-p95".to_string(),
+            threshold: 250.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: Some(MetricComparison::Le),
+            unit: Some(MetricUnit::Milliseconds),
+            weight: None,
+            web: None,
+        }];
+
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(0.25);
+        assert!(client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap());
+
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(0.3);
+        assert!(!client
+            .evaluate_all_metrics(&metrics, "my-app", &RevisionSelector::canary())
+            .await
+            .unwrap());
+    }
+
     #[test]
     fn test_parse_prometheus_response_with_data() {
         let json_response = r#"{
@@ -417,6 +1873,26 @@ mod tests {
         assert!(matches!(result, Err(PrometheusError::NoData)));
     }
 
+    /// `error-rate` metric with the default comparison (healthy below `threshold`)
+    fn error_rate_metric(threshold: f64) -> crate::crd::rollout::MetricConfig {
+        crate::crd::rollout::MetricConfig {
+            name: "error-rate".to_string(),
+            threshold,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_evaluate_error_rate_healthy() {
         let client = MockPrometheusClient::new();
@@ -436,11 +1912,11 @@ mod tests {
         client.set_mock_response(mock_response.to_string());
 
         let rollout_name = "my-app";
-        let revision = "canary";
-        let threshold = 5.0;
+        let revision = RevisionSelector::canary();
+        let metric = error_rate_metric(5.0);
 
         let result = client
-            .evaluate_metric("error-rate", rollout_name, revision, threshold)
+            .evaluate_metric_config(&metric, rollout_name, &revision, "")
             .await;
 
         match result {
@@ -468,11 +1944,11 @@ mod tests {
         client.set_mock_response(mock_response.to_string());
 
         let rollout_name = "my-app";
-        let revision = "canary";
-        let threshold = 5.0;
+        let revision = RevisionSelector::canary();
+        let metric = error_rate_metric(5.0);
 
         let result = client
-            .evaluate_metric("error-rate", rollout_name, revision, threshold)
+            .evaluate_metric_config(&metric, rollout_name, &revision, "")
             .await;
 
         match result {
@@ -507,22 +1983,40 @@ mod tests {
                 threshold: 5.0,
                 interval: None,
                 failure_threshold: None,
+                query: None,
                 min_sample_size: None,
+                baseline_offset: None,
+                compare_to_stable: None,
+                compare_to_baseline: None,
+                volume_weighting: None,
+                comparison: None,
+                unit: None,
+                weight: None,
+                web: None,
             },
             MetricConfig {
                 name: "latency-p95".to_string(),
                 threshold: 100.0,
                 interval: None,
                 failure_threshold: None,
+                query: None,
                 min_sample_size: None,
+                baseline_offset: None,
+                compare_to_stable: None,
+                compare_to_baseline: None,
+                volume_weighting: None,
+                comparison: None,
+                unit: None,
+                weight: None,
+                web: None,
             },
         ];
 
         let rollout_name = "my-app";
-        let revision = "canary";
+        let revision = RevisionSelector::canary();
 
         let result = client
-            .evaluate_all_metrics(&metrics, rollout_name, revision)
+            .evaluate_all_metrics(&metrics, rollout_name, &revision)
             .await;
 
         match result {
@@ -556,14 +2050,23 @@ mod tests {
             threshold: 5.0,
             interval: None,
             failure_threshold: None,
+            query: None,
             min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
         }];
 
         let rollout_name = "my-app";
-        let revision = "canary";
+        let revision = RevisionSelector::canary();
 
         let result = client
-            .evaluate_all_metrics(&metrics, rollout_name, revision)
+            .evaluate_all_metrics(&metrics, rollout_name, &revision)
             .await;
 
         match result {
@@ -581,10 +2084,10 @@ mod tests {
 
         let metrics = vec![];
         let rollout_name = "my-app";
-        let revision = "canary";
+        let revision = RevisionSelector::canary();
 
         let result = client
-            .evaluate_all_metrics(&metrics, rollout_name, revision)
+            .evaluate_all_metrics(&metrics, rollout_name, &revision)
             .await;
 
         match result {
@@ -612,11 +2115,11 @@ mod tests {
         client.set_mock_response(mock_response.to_string());
 
         let rollout_name = "my-app";
-        let revision = "canary";
-        let threshold = 5.0;
+        let revision = RevisionSelector::canary();
+        let metric = error_rate_metric(5.0);
 
         let result = client
-            .evaluate_metric("error-rate", rollout_name, revision, threshold)
+            .evaluate_metric_config(&metric, rollout_name, &revision, "")
             .await;
 
         match result {
@@ -671,4 +2174,217 @@ mod tests {
             "+Inf value should return InvalidValue error"
         );
     }
+
+    #[test]
+    fn test_parse_addresses() {
+        assert_eq!(
+            parse_addresses("http://prom-0:9090/, http://prom-1:9090,,"),
+            vec!["http://prom-0:9090", "http://prom-1:9090"]
+        );
+        assert!(parse_addresses(" ").is_empty());
+    }
+
+    #[test]
+    fn test_replica_policy_parse() {
+        assert_eq!(ReplicaPolicy::parse("Quorum"), Some(ReplicaPolicy::Quorum));
+        assert_eq!(
+            ReplicaPolicy::parse("fallback"),
+            Some(ReplicaPolicy::Fallback)
+        );
+        assert_eq!(ReplicaPolicy::parse("majority"), None);
+    }
+
+    #[test]
+    fn test_quorum_uses_median_of_majority() {
+        let value =
+            combine_quorum(vec![Ok(0.01), Ok(0.9), Err(PrometheusError::NoData)], 3).unwrap();
+        assert!((value - 0.455).abs() < 1e-9);
+
+        // A single diverging replica doesn't win against two agreeing ones
+        let value = combine_quorum(vec![Ok(0.01), Ok(0.9), Ok(0.02)], 3).unwrap();
+        assert!((value - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quorum_without_majority_fails() {
+        let result = combine_quorum(
+            vec![
+                Ok(0.01),
+                Err(PrometheusError::HttpError("down".to_string())),
+                Err(PrometheusError::NoData),
+            ],
+            3,
+        );
+        assert!(matches!(result, Err(PrometheusError::HttpError(_))));
+
+        // Half of an HA pair is not a majority
+        assert!(combine_quorum(vec![Ok(0.01), Err(PrometheusError::NoData)], 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_reports_error_when_all_replicas_fail() {
+        let client = HttpPrometheusClient::with_replicas(
+            parse_addresses("http://127.0.0.1:1,http://127.0.0.1:2"),
+            ReplicaPolicy::Fallback,
+        );
+        let result = client.query_instant("up").await;
+        assert!(matches!(result, Err(PrometheusError::HttpError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mock_scripts_play_in_order_and_repeat_last() {
+        let mock = MockPrometheusClient::new();
+        mock.script_values("http_requests_total", &[1.0, 1.0, 9.0]);
+        mock.script(
+            "histogram_quantile",
+            [Err(PrometheusError::NoData), Ok(0.2)],
+        );
+        mock.enqueue_response(42.0);
+
+        let mut error_rates = Vec::new();
+        for _ in 0..5 {
+            error_rates.push(
+                mock.query_instant("sum(rate(http_requests_total[2m]))")
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_eq!(error_rates, vec![1.0, 1.0, 9.0, 9.0, 9.0]);
+
+        assert!(matches!(
+            mock.query_instant("histogram_quantile(0.95, x)").await,
+            Err(PrometheusError::NoData)
+        ));
+        assert_eq!(
+            mock.query_instant("histogram_quantile(0.95, x)")
+                .await
+                .unwrap(),
+            0.2
+        );
+
+        // Unscripted queries fall through to the queue
+        assert_eq!(mock.query_instant("up").await.unwrap(), 42.0);
+        assert_eq!(mock.queries().len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_mock_latency_delays_answers() {
+        let mock = MockPrometheusClient::new();
+        mock.script_values("up", &[1.0]);
+        mock.set_latency(std::time::Duration::from_secs(60));
+
+        let answer = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            mock.query_instant("up"),
+        )
+        .await;
+        assert!(answer.is_err(), "query should still be in flight");
+        assert!(mock.queries().is_empty());
+    }
+
+    #[test]
+    fn test_render_query_substitutes_variables() {
+        let rendered = render_query(
+            r#"sum(rate(http_requests_total{app="{{ rollout }}",namespace="{{namespace}}",revision="{{revision}}",code=~"5.."}[2m]))"#,
+            "shop",
+            "prod",
+            "canary",
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            r#"sum(rate(http_requests_total{app="shop",namespace="prod",revision="canary",code=~"5.."}[2m]))"#
+        );
+    }
+
+    #[test]
+    fn test_render_query_rejects_malformed_templates() {
+        let render = |template: &str| render_query(template, "shop", "prod", "canary");
+        assert!(matches!(
+            render(r#"up{app="{{service}}"}"#),
+            Err(PrometheusError::InvalidQuery(_))
+        ));
+        assert!(render(r#"up{app="{{rollout"}"#).is_err());
+        assert!(render(r#"sum(rate(up{app="{{rollout}}"}[2m])"#).is_err());
+        assert!(render(r#"up{app="{{rollout}}}"#).is_err());
+        assert!(render("  ").is_err());
+        // Brackets inside label values don't count
+        assert!(render(r#"up{path="/items[(]"}"#).is_ok());
+        assert!(render_query(r#"up{app="{{rollout}}"}"#, r#"a"b"#, "prod", "canary").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_metric_config_runs_custom_query() {
+        use crate::crd::rollout::MetricConfig;
+
+        let metric = MetricConfig {
+            name: "checkout-errors".to_string(),
+            threshold: 1.0,
+            interval: None,
+            failure_threshold: None,
+            query: Some(r#"sum(rate(checkout_failures_total{app="shop"}[5m]))"#.to_string()),
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        };
+
+        let client = MockPrometheusClient::new();
+        client.enqueue_response(0.5);
+        assert!(client
+            .evaluate_metric_config(&metric, "shop", &RevisionSelector::canary(), "")
+            .await
+            .unwrap());
+        assert_eq!(
+            client.queries(),
+            vec![r#"sum(rate(checkout_failures_total{app="shop"}[5m]))"#.to_string()]
+        );
+
+        assert!(client
+            .evaluate_metric_config(&metric, "shop", &RevisionSelector::canary(), r#",zone="a""#)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_metric_config_reads_web_metric() {
+        use crate::crd::rollout::{MetricComparison, MetricConfig};
+
+        let metric = MetricConfig {
+            name: "slo-budget".to_string(),
+            threshold: 0.9,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: Some(MetricComparison::Ge),
+            unit: None,
+            weight: None,
+            web: Some(WebMetric {
+                url: "https://slo.example.com/budget/shop".to_string(),
+                method: None,
+                headers: Vec::new(),
+                body: None,
+                json_path: "$.remaining".to_string(),
+                timeout: None,
+            }),
+        };
+
+        let client = MockPrometheusClient::new();
+        client.script_values("slo.example.com", &[0.95, 0.5]);
+        let evaluate =
+            || client.evaluate_metric_config(&metric, "shop", &RevisionSelector::canary(), "");
+        assert!(evaluate().await.unwrap());
+        assert!(!evaluate().await.unwrap());
+        assert_eq!(client.queries()[0], "https://slo.example.com/budget/shop");
+    }
 }
@@ -0,0 +1,444 @@
+//! Credentials and TLS settings for Prometheus
+//!
+//! Hardened Prometheus-compatible endpoints (Thanos, Mimir, or Prometheus
+//! behind an auth proxy) want a bearer token, basic auth, a private CA, or
+//! a client certificate. The controller's own client is configured from
+//! `KULTA_PROMETHEUS_*` variables naming mounted files; a Rollout can set
+//! the same options in `analysis.prometheus`, with the values read from
+//! Secrets in its namespace. Those Secrets must opt in with the
+//! [`PROMETHEUS_SECRET_LABEL`] label: otherwise anyone who can create a
+//! Rollout could have the controller send any Secret of the namespace to an
+//! `address` of their choosing.
+//!
+//! A Rollout with options gets its own client, cached per Rollout and
+//! rebuilt when the address or a Secret value changes. Rollouts without
+//! options keep using the controller's client.
+
+use crate::controller::prometheus::{
+    parse_addresses, HttpPrometheusClient, MetricsQuerier, PrometheusError, ReplicaPolicy,
+};
+use crate::crd::rollout::{PrometheusConfig, SecretKeyRef};
+use k8s_openapi::api::core::v1::Secret;
+use kube::Api;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// How queries authenticate
+///
+/// Deliberately not `Debug`, so credentials can't end up in logs.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub enum PrometheusAuth {
+    #[default]
+    None,
+    /// `Authorization: Bearer` with a fixed token
+    Bearer(String),
+    /// `Authorization: Bearer` with a token file, re-read for every query so
+    /// rotated service account tokens are picked up
+    BearerFile(PathBuf),
+    /// HTTP basic auth
+    Basic { username: String, password: String },
+}
+
+impl PrometheusAuth {
+    /// Add the credentials to a query
+    pub fn apply(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, PrometheusError> {
+        match self {
+            PrometheusAuth::None => Ok(request),
+            PrometheusAuth::Bearer(token) => Ok(request.bearer_auth(token)),
+            PrometheusAuth::BearerFile(path) => {
+                let token = std::fs::read_to_string(path).map_err(|e| {
+                    PrometheusError::ConfigError(format!(
+                        "failed to read bearer token {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                Ok(request.bearer_auth(token.trim()))
+            }
+            PrometheusAuth::Basic { username, password } => {
+                Ok(request.basic_auth(username, Some(password)))
+            }
+        }
+    }
+}
+
+/// TLS settings of the connection (PEM encoded certificates and key)
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct PrometheusTls {
+    /// CA bundle trusted in addition to the system roots
+    pub ca: Option<Vec<u8>>,
+    /// Client certificate and key for mTLS
+    pub client_cert: Option<Vec<u8>>,
+    pub client_key: Option<Vec<u8>>,
+    pub insecure_skip_verify: bool,
+}
+
+impl PrometheusTls {
+    /// Configure an HTTP client with the settings
+    ///
+    /// # Returns
+    /// * `Err(PrometheusError::ConfigError)` - Invalid PEM, or only one of
+    ///   client certificate and key
+    pub fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, PrometheusError> {
+        if let Some(ca) = &self.ca {
+            for certificate in reqwest::Certificate::from_pem_bundle(ca)
+                .map_err(|e| PrometheusError::ConfigError(format!("invalid CA bundle: {}", e)))?
+            {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let mut pem = cert.clone();
+                pem.push(b'\n');
+                pem.extend_from_slice(key);
+                let identity = reqwest::Identity::from_pem(&pem).map_err(|e| {
+                    PrometheusError::ConfigError(format!("invalid client certificate: {}", e))
+                })?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(PrometheusError::ConfigError(
+                    "client certificate and key must be set together".to_string(),
+                ))
+            }
+        }
+        Ok(builder.danger_accept_invalid_certs(self.insecure_skip_verify))
+    }
+}
+
+/// Credentials and TLS settings of a Prometheus client
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClientOptions {
+    pub auth: PrometheusAuth,
+    pub tls: PrometheusTls,
+}
+
+fn read_env_file(var: &str) -> Result<Option<Vec<u8>>, PrometheusError> {
+    let Some(path) = std::env::var(var).ok().filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+    std::fs::read(&path)
+        .map(Some)
+        .map_err(|e| PrometheusError::ConfigError(format!("{} ({}): {}", var, path, e)))
+}
+
+impl ClientOptions {
+    /// Options of the controller's client
+    ///
+    /// - `KULTA_PROMETHEUS_BEARER_TOKEN_FILE`: bearer token file (re-read per query)
+    /// - `KULTA_PROMETHEUS_BASIC_AUTH_USERNAME` and
+    ///   `KULTA_PROMETHEUS_BASIC_AUTH_PASSWORD_FILE`: basic auth
+    /// - `KULTA_PROMETHEUS_CA_FILE`: CA bundle
+    /// - `KULTA_PROMETHEUS_CERT_FILE` and `KULTA_PROMETHEUS_KEY_FILE`: mTLS
+    /// - `KULTA_PROMETHEUS_INSECURE_SKIP_VERIFY`: `true` to skip verification
+    ///
+    /// # Returns
+    /// * `Err(PrometheusError::ConfigError)` - A named file can't be read, or
+    ///   both a bearer token and basic auth are set
+    pub fn from_env() -> Result<Self, PrometheusError> {
+        let token_file = std::env::var("KULTA_PROMETHEUS_BEARER_TOKEN_FILE")
+            .ok()
+            .filter(|p| !p.trim().is_empty());
+        let username = std::env::var("KULTA_PROMETHEUS_BASIC_AUTH_USERNAME")
+            .ok()
+            .filter(|u| !u.is_empty());
+        let auth = match (token_file, username) {
+            (Some(_), Some(_)) => {
+                return Err(PrometheusError::ConfigError(
+                    "KULTA_PROMETHEUS_BEARER_TOKEN_FILE and basic auth are mutually exclusive"
+                        .to_string(),
+                ))
+            }
+            (Some(path), None) => PrometheusAuth::BearerFile(PathBuf::from(path)),
+            (None, Some(username)) => {
+                let password =
+                    read_env_file("KULTA_PROMETHEUS_BASIC_AUTH_PASSWORD_FILE")?.unwrap_or_default();
+                PrometheusAuth::Basic {
+                    username,
+                    password: String::from_utf8_lossy(&password).trim().to_string(),
+                }
+            }
+            (None, None) => PrometheusAuth::None,
+        };
+        let tls = PrometheusTls {
+            ca: read_env_file("KULTA_PROMETHEUS_CA_FILE")?,
+            client_cert: read_env_file("KULTA_PROMETHEUS_CERT_FILE")?,
+            client_key: read_env_file("KULTA_PROMETHEUS_KEY_FILE")?,
+            insecure_skip_verify: std::env::var("KULTA_PROMETHEUS_INSECURE_SKIP_VERIFY")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+        };
+        Ok(Self { auth, tls })
+    }
+}
+
+/// Label a Secret must carry (with value `true`) to be used by `analysis.prometheus`
+pub const PROMETHEUS_SECRET_LABEL: &str = "kulta.io/prometheus-credentials";
+
+/// Whether a Secret opted in to being sent to Prometheus
+pub fn allows_prometheus(secret: &Secret) -> bool {
+    secret
+        .metadata
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(PROMETHEUS_SECRET_LABEL))
+        .is_some_and(|value| value == "true")
+}
+
+/// Read one key of a Secret that opted in with [`PROMETHEUS_SECRET_LABEL`]
+async fn read_secret_key(secrets: &Api<Secret>, key_ref: &SecretKeyRef) -> Result<Vec<u8>, String> {
+    let secret = secrets
+        .get(&key_ref.name)
+        .await
+        .map_err(|e| format!("failed to read Secret {}: {}", key_ref.name, e))?;
+    if !allows_prometheus(&secret) {
+        return Err(format!(
+            "Secret {} is not labelled {}=true",
+            key_ref.name, PROMETHEUS_SECRET_LABEL
+        ));
+    }
+    secret
+        .data
+        .and_then(|mut data| data.remove(&key_ref.key))
+        .map(|value| value.0)
+        .ok_or_else(|| format!("Secret {} has no key {}", key_ref.name, key_ref.key))
+}
+
+async fn read_optional_key(
+    secrets: &Api<Secret>,
+    key_ref: Option<&SecretKeyRef>,
+) -> Result<Option<Vec<u8>>, String> {
+    match key_ref {
+        Some(key_ref) => read_secret_key(secrets, key_ref).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Options of a Rollout's `analysis.prometheus`, with Secrets read from
+/// `namespace`
+pub async fn resolve_client_options(
+    config: &PrometheusConfig,
+    client: &kube::Client,
+    namespace: &str,
+) -> Result<ClientOptions, String> {
+    let secrets: Api<Secret> = Api::namespaced(client.clone(), namespace);
+    let text = |value: Vec<u8>| String::from_utf8_lossy(&value).trim().to_string();
+
+    let auth = if let Some(token_ref) = &config.bearer_token_secret_ref {
+        PrometheusAuth::Bearer(text(read_secret_key(&secrets, token_ref).await?))
+    } else if let Some(basic) = &config.basic_auth {
+        PrometheusAuth::Basic {
+            username: basic.username.clone(),
+            password: text(read_secret_key(&secrets, &basic.password_secret_ref).await?),
+        }
+    } else {
+        PrometheusAuth::None
+    };
+    let tls = match &config.tls {
+        Some(tls) => PrometheusTls {
+            ca: read_optional_key(&secrets, tls.ca_secret_ref.as_ref()).await?,
+            client_cert: read_optional_key(&secrets, tls.cert_secret_ref.as_ref()).await?,
+            client_key: read_optional_key(&secrets, tls.key_secret_ref.as_ref()).await?,
+            insecure_skip_verify: tls.insecure_skip_verify.unwrap_or(false),
+        },
+        None => PrometheusTls::default(),
+    };
+    Ok(ClientOptions { auth, tls })
+}
+
+/// Clients of Rollouts with their own Prometheus options
+pub struct PrometheusClients {
+    /// Controller addresses, for Rollouts that don't set `address`
+    addresses: Vec<String>,
+    policy: ReplicaPolicy,
+    /// Client per Rollout key, with the fingerprint it was built from
+    clients: Mutex<HashMap<String, (u64, Arc<dyn MetricsQuerier>)>>,
+}
+
+impl PrometheusClients {
+    pub fn new(addresses: Vec<String>, policy: ReplicaPolicy) -> Self {
+        Self {
+            addresses,
+            policy,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache for the controller's `KULTA_PROMETHEUS_ADDRESS` and
+    /// `KULTA_PROMETHEUS_POLICY`
+    pub fn from_env() -> Self {
+        Self::new(
+            parse_addresses(&std::env::var("KULTA_PROMETHEUS_ADDRESS").unwrap_or_default()),
+            ReplicaPolicy::from_env(),
+        )
+    }
+
+    /// Client for a Rollout with options
+    ///
+    /// `address` overrides the controller's addresses; the client is built
+    /// again when the address or the options change.
+    pub fn client_for(
+        &self,
+        key: &str,
+        address: Option<&str>,
+        options: &ClientOptions,
+    ) -> Result<Arc<dyn MetricsQuerier>, PrometheusError> {
+        let addresses = match address {
+            Some(address) => vec![address.trim_end_matches('/').to_string()],
+            None => self.addresses.clone(),
+        };
+        if addresses.is_empty() {
+            return Err(PrometheusError::ConfigError(
+                "no Prometheus address configured".to_string(),
+            ));
+        }
+        let mut hasher = DefaultHasher::new();
+        (&addresses, options).hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        let mut clients = self
+            .clients
+            .lock()
+            .map_err(|_| PrometheusError::ConfigError("client cache poisoned".to_string()))?;
+        if let Some((cached, client)) = clients.get(key) {
+            if *cached == fingerprint {
+                return Ok(Arc::clone(client));
+            }
+        }
+        let client: Arc<dyn MetricsQuerier> = Arc::new(HttpPrometheusClient::with_options(
+            addresses,
+            self.policy,
+            options,
+        )?);
+        clients.insert(key.to_string(), (fingerprint, Arc::clone(&client)));
+        Ok(client)
+    }
+
+    /// Forget the client of a deleted Rollout
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn options(token: &str) -> ClientOptions {
+        ClientOptions {
+            auth: PrometheusAuth::Bearer(token.to_string()),
+            tls: PrometheusTls::default(),
+        }
+    }
+
+    #[test]
+    fn test_auth_headers() {
+        let http = reqwest::Client::new();
+        let header = |auth: PrometheusAuth| {
+            let request = auth.apply(http.get("http://prom:9090")).unwrap().build();
+            request
+                .unwrap()
+                .headers()
+                .get(reqwest::header::AUTHORIZATION)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+        assert_eq!(header(PrometheusAuth::None), None);
+        assert_eq!(
+            header(PrometheusAuth::Bearer("s3cret".to_string())),
+            Some("Bearer s3cret".to_string())
+        );
+        assert_eq!(
+            header(PrometheusAuth::Basic {
+                username: "kulta".to_string(),
+                password: "pw".to_string(),
+            }),
+            Some("Basic a3VsdGE6cHc=".to_string())
+        );
+        assert!(
+            PrometheusAuth::BearerFile(PathBuf::from("/nonexistent/token"))
+                .apply(http.get("http://prom:9090"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_tls_requires_cert_and_key_together() {
+        let tls = PrometheusTls {
+            client_cert: Some(b"cert".to_vec()),
+            ..PrometheusTls::default()
+        };
+        assert!(matches!(
+            tls.apply(reqwest::Client::builder()),
+            Err(PrometheusError::ConfigError(_))
+        ));
+        let ca = PrometheusTls {
+            ca: Some(b"not a certificate".to_vec()),
+            ..PrometheusTls::default()
+        };
+        assert!(ca.apply(reqwest::Client::builder()).is_err());
+    }
+
+    #[test]
+    fn test_only_labelled_secrets_are_sent_to_prometheus() {
+        let secret = |labels: &[(&str, &str)]| Secret {
+            metadata: kube::api::ObjectMeta {
+                name: Some("thanos-reader".to_string()),
+                labels: Some(
+                    labels
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(allows_prometheus(&secret(&[(
+            "kulta.io/prometheus-credentials",
+            "true"
+        )])));
+        assert!(!allows_prometheus(&secret(&[(
+            "kulta.io/prometheus-credentials",
+            "false"
+        )])));
+        assert!(!allows_prometheus(&secret(&[("app", "shop")])));
+        assert!(!allows_prometheus(&Secret::default()));
+    }
+
+    #[test]
+    fn test_clients_are_rebuilt_when_options_change() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let clients = PrometheusClients::new(Vec::new(), ReplicaPolicy::Fallback);
+        assert!(clients
+            .client_for("prod/shop", None, &options("a"))
+            .is_err());
+
+        let first = clients
+            .client_for("prod/shop", Some("https://thanos:9090/"), &options("a"))
+            .unwrap();
+        let same = clients
+            .client_for("prod/shop", Some("https://thanos:9090"), &options("a"))
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &same));
+
+        let rotated = clients
+            .client_for("prod/shop", Some("https://thanos:9090"), &options("b"))
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &rotated));
+    }
+}
@@ -0,0 +1,224 @@
+//! Controller-level promotion policy keyed by namespace labels
+//!
+//! Platform teams can force manual promotion in sensitive environments no
+//! matter what individual Rollout specs request. The policy is a list of
+//! `label=value:mode` rules read from `KULTA_PROMOTION_POLICY`, e.g.
+//! `env=prod:manual,env=staging:auto`. The first rule whose label matches the
+//! Rollout's namespace decides the mode:
+//!
+//! - `manual`: every canary pause waits for `kulta.io/promote`, even timed ones
+//! - `auto`: the spec decides (useful to exempt a namespace before a broader rule)
+
+use k8s_openapi::api::core::v1::Namespace;
+use kube::api::Api;
+use kube::Client;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use tracing::warn;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PromotionPolicyError {
+    #[error("Invalid promotion policy rule '{0}': expected label=value:mode")]
+    InvalidRule(String),
+
+    #[error("Invalid promotion mode '{0}': expected auto or manual")]
+    InvalidMode(String),
+}
+
+/// How pauses are promoted in a namespace
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum PromotionMode {
+    /// Follow the Rollout spec (timed pauses advance on their own)
+    Auto,
+    /// Every pause requires the `kulta.io/promote` annotation
+    Manual,
+}
+
+impl std::str::FromStr for PromotionMode {
+    type Err = PromotionPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(PromotionMode::Auto),
+            "manual" => Ok(PromotionMode::Manual),
+            other => Err(PromotionPolicyError::InvalidMode(other.to_string())),
+        }
+    }
+}
+
+/// A single `label=value:mode` rule
+#[derive(Clone, Debug, PartialEq)]
+pub struct PromotionRule {
+    pub label: String,
+    pub value: String,
+    pub mode: PromotionMode,
+}
+
+/// Ordered promotion rules (first match wins)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PromotionPolicy {
+    pub rules: Vec<PromotionRule>,
+}
+
+impl PromotionPolicy {
+    /// Load the policy from `KULTA_PROMOTION_POLICY` (empty when unset)
+    ///
+    /// Invalid rules are logged and skipped so that one typo does not drop
+    /// the remaining rules.
+    pub fn from_env() -> Self {
+        let Ok(spec) = std::env::var("KULTA_PROMOTION_POLICY") else {
+            return Self::default();
+        };
+
+        let rules = spec
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| match parse_rule(entry) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    warn!(error = %e, "Ignoring invalid KULTA_PROMOTION_POLICY rule");
+                    None
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Parse a comma-separated list of `label=value:mode` rules
+    pub fn parse(spec: &str) -> Result<Self, PromotionPolicyError> {
+        let rules = spec
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(parse_rule)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Mode of the first rule matching the namespace labels
+    pub fn mode_for(&self, namespace_labels: &BTreeMap<String, String>) -> Option<PromotionMode> {
+        self.rules
+            .iter()
+            .find(|rule| namespace_labels.get(&rule.label) == Some(&rule.value))
+            .map(|rule| rule.mode)
+    }
+}
+
+fn parse_rule(entry: &str) -> Result<PromotionRule, PromotionPolicyError> {
+    let invalid = || PromotionPolicyError::InvalidRule(entry.trim().to_string());
+
+    let (selector, mode) = entry.rsplit_once(':').ok_or_else(invalid)?;
+    let (label, value) = selector.split_once('=').ok_or_else(invalid)?;
+    let (label, value) = (label.trim(), value.trim());
+    if label.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(PromotionRule {
+        label: label.to_string(),
+        value: value.to_string(),
+        mode: mode.parse()?,
+    })
+}
+
+/// Resolve the promotion mode for a namespace
+///
+/// Skips the Namespace lookup entirely when no policy is configured.
+///
+/// # Returns
+/// * `Ok(Some(mode))` - A rule matched the namespace labels
+/// * `Ok(None)` - No policy, or no rule matched (spec decides)
+/// * `Err(_)` - Namespace could not be read
+pub async fn resolve_promotion_mode(
+    policy: &PromotionPolicy,
+    client: &Client,
+    namespace: &str,
+) -> Result<Option<PromotionMode>, kube::Error> {
+    if policy.is_empty() {
+        return Ok(None);
+    }
+
+    let namespaces: Api<Namespace> = Api::all(client.clone());
+    let ns = namespaces.get(namespace).await?;
+    let labels = ns.metadata.labels.unwrap_or_default();
+    Ok(policy.mode_for(&labels))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        let policy = PromotionPolicy::parse("env=prod:manual, env=staging:Auto").unwrap();
+
+        assert_eq!(
+            policy.rules,
+            vec![
+                PromotionRule {
+                    label: "env".to_string(),
+                    value: "prod".to_string(),
+                    mode: PromotionMode::Manual,
+                },
+                PromotionRule {
+                    label: "env".to_string(),
+                    value: "staging".to_string(),
+                    mode: PromotionMode::Auto,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_policy_with_prefixed_label() {
+        let policy = PromotionPolicy::parse("kulta.io/tier=critical:manual").unwrap();
+
+        assert_eq!(policy.rules[0].label, "kulta.io/tier");
+        assert_eq!(policy.rules[0].value, "critical");
+    }
+
+    #[test]
+    fn test_parse_policy_rejects_invalid_rules() {
+        assert!(matches!(
+            PromotionPolicy::parse("env=prod"),
+            Err(PromotionPolicyError::InvalidRule(_))
+        ));
+        assert!(matches!(
+            PromotionPolicy::parse("prod:manual"),
+            Err(PromotionPolicyError::InvalidRule(_))
+        ));
+        assert_eq!(
+            PromotionPolicy::parse("env=prod:never"),
+            Err(PromotionPolicyError::InvalidMode("never".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_mode_for_first_match_wins() {
+        let policy = PromotionPolicy::parse("team=payments:auto,env=prod:manual").unwrap();
+
+        assert_eq!(
+            policy.mode_for(&labels(&[("env", "prod")])),
+            Some(PromotionMode::Manual)
+        );
+        assert_eq!(
+            policy.mode_for(&labels(&[("env", "prod"), ("team", "payments")])),
+            Some(PromotionMode::Auto)
+        );
+        assert_eq!(policy.mode_for(&labels(&[("env", "dev")])), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! Release records posted to external deployment registries on completion
+//!
+//! Release inventories (an internal registry, Backstage, ...) otherwise have
+//! to scrape CDEvents to learn what runs where. With `KULTA_RELEASE_HOOKS`
+//! set, every Rollout that reaches Completed has a [`ReleaseRecord`] POSTed
+//! as JSON to the endpoint configured for its namespace. The variable is a
+//! comma-separated list of `namespace=url` entries; `*=url` applies to
+//! namespaces without their own entry:
+//!
+//! ```text
+//! KULTA_RELEASE_HOOKS=payments=https://releases.internal/api/v1/releases,*=https://backstage.internal/api/releases
+//! ```
+//!
+//! Delivery is best effort: failures are logged and never block the rollout.
+
+use crate::crd::rollout::{Decision, DecisionAction, MetricSnapshot, Rollout, RolloutStatus};
+use chrono::{DateTime, Utc};
+use kube::ResourceExt;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Timeout for a single release hook POST
+const RELEASE_HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Namespace key matching every namespace without its own endpoint
+const ANY_NAMESPACE: &str = "*";
+
+/// Release hook endpoints per namespace (none unless configured)
+#[derive(Clone, Debug, Default)]
+pub struct ReleaseHooks {
+    endpoints: BTreeMap<String, String>,
+    http: Option<reqwest::Client>,
+}
+
+impl ReleaseHooks {
+    /// Release hooks that post nothing
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create release hooks from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create release hooks from an arbitrary key lookup (testable without touching env)
+    ///
+    /// Invalid entries are logged and skipped.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let Some(spec) = lookup("KULTA_RELEASE_HOOKS") else {
+            return Self::default();
+        };
+
+        let endpoints: BTreeMap<String, String> = spec
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| match entry.split_once('=') {
+                Some((namespace, url))
+                    if !namespace.trim().is_empty() && !url.trim().is_empty() =>
+                {
+                    Some((namespace.trim().to_string(), url.trim().to_string()))
+                }
+                _ => {
+                    warn!(entry = %entry.trim(), "Ignoring invalid KULTA_RELEASE_HOOKS entry");
+                    None
+                }
+            })
+            .collect();
+        if endpoints.is_empty() {
+            return Self::default();
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(RELEASE_HOOK_TIMEOUT)
+            .build()
+            .map_err(|e| warn!(error = %e, "Failed to build release hook HTTP client"))
+            .ok();
+        Self { endpoints, http }
+    }
+
+    /// Endpoint release records for a namespace go to
+    pub fn endpoint_for(&self, namespace: &str) -> Option<&str> {
+        self.endpoints
+            .get(namespace)
+            .or_else(|| self.endpoints.get(ANY_NAMESPACE))
+            .map(String::as_str)
+    }
+
+    /// POST a release record to its namespace's endpoint, if any
+    ///
+    /// Failures are logged only.
+    pub async fn notify(&self, record: &ReleaseRecord) {
+        let (Some(url), Some(http)) = (self.endpoint_for(&record.namespace), &self.http) else {
+            return;
+        };
+
+        let result = http
+            .post(url)
+            .json(record)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => info!(rollout = %record.rollout, url = %url, "Release record delivered"),
+            Err(e) => {
+                warn!(error = %e, rollout = %record.rollout, "Failed to deliver release record")
+            }
+        }
+    }
+}
+
+/// Image of one container in the released pod template
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ReleasedImage {
+    pub container: String,
+    pub image: String,
+}
+
+/// What analysis saw during the rollout
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisSummary {
+    /// Decisions recorded during the rollout
+    pub decisions: usize,
+    /// Steps advanced by `kulta.io/promote` rather than on their own
+    pub manual_promotions: usize,
+    /// Source of the last analysis decision (Threshold, Advisor, Human)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_decision_source: Option<String>,
+    /// Latest value of every analyzed metric
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, MetricSnapshot>,
+}
+
+/// Release record posted when a Rollout completes
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseRecord {
+    pub rollout: String,
+    pub namespace: String,
+    pub strategy: String,
+    pub images: Vec<ReleasedImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pod_template_hash: Option<String>,
+    /// When the rollout started progressing (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// When the rollout completed (RFC3339)
+    pub completed_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<i64>,
+    pub analysis: AnalysisSummary,
+}
+
+/// Build the release record for a Rollout that just completed
+pub fn release_record(
+    rollout: &Rollout,
+    completed: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> ReleaseRecord {
+    let images = rollout
+        .spec
+        .template
+        .spec
+        .iter()
+        .flat_map(|spec| &spec.containers)
+        .map(|c| ReleasedImage {
+            container: c.name.clone(),
+            image: c.image.clone().unwrap_or_default(),
+        })
+        .collect();
+
+    let started_at = completed.progress_started_at.clone();
+    let started = started_at.as_deref().and_then(parse_time);
+    let duration_seconds = started.map(|started| (now - started).num_seconds());
+
+    ReleaseRecord {
+        rollout: rollout.name_any(),
+        namespace: rollout.namespace().unwrap_or_default(),
+        strategy: completed
+            .strategy
+            .map(|s| s.as_str().to_string())
+            .unwrap_or_default(),
+        images,
+        revision: completed.revision,
+        pod_template_hash: completed.pod_template_hash.clone(),
+        started_at,
+        completed_at: now.to_rfc3339(),
+        duration_seconds,
+        analysis: analysis_summary(completed),
+    }
+}
+
+/// What analysis saw since the current rollout started progressing
+pub fn analysis_summary(status: &RolloutStatus) -> AnalysisSummary {
+    let started = status.progress_started_at.as_deref().and_then(parse_time);
+
+    // Decision history spans revisions; only decisions since the start count
+    let decisions: Vec<&Decision> = status
+        .decisions
+        .iter()
+        .filter(|d| parse_time(d.last_timestamp.as_deref().unwrap_or(&d.timestamp)) >= started)
+        .collect();
+    // Compacted entries stand for `count` decisions
+    let count = |d: &&Decision| d.count.unwrap_or(1) as usize;
+    let mut metrics = BTreeMap::new();
+    for decision in &decisions {
+        for (name, snapshot) in decision.metrics.iter().flatten() {
+            metrics.insert(name.clone(), snapshot.clone());
+        }
+    }
+
+    AnalysisSummary {
+        decisions: decisions.iter().map(count).sum(),
+        manual_promotions: decisions
+            .iter()
+            .filter(|d| d.action == DecisionAction::Promotion)
+            .map(count)
+            .sum(),
+        last_decision_source: status.last_decision_source.clone(),
+        metrics,
+    }
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::controller::cdevents_data::StrategyKind;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{DecisionReason, Phase, SimpleStrategy};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_from_lookup_endpoints() {
+        let hooks = ReleaseHooks::from_lookup(|_| {
+            Some("payments=https://registry/releases, *=https://backstage/releases,broken".into())
+        });
+
+        assert_eq!(
+            hooks.endpoint_for("payments"),
+            Some("https://registry/releases")
+        );
+        assert_eq!(
+            hooks.endpoint_for("shop"),
+            Some("https://backstage/releases")
+        );
+
+        let hooks =
+            ReleaseHooks::from_lookup(|_| Some("payments=https://registry/releases".into()));
+        assert_eq!(hooks.endpoint_for("shop"), None);
+        assert_eq!(
+            ReleaseHooks::from_lookup(|_| None).endpoint_for("shop"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_release_record() {
+        let rollout = RolloutBuilder::new("checkout")
+            .namespace("shop")
+            .replicas(2)
+            .label("app", "checkout")
+            .image("app", "checkout:2.1")
+            .image("proxy", "envoy:1.30")
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        let decision = |action: DecisionAction, error_rate: f64| Decision {
+            timestamp: "2026-01-01T12:05:00Z".to_string(),
+            action,
+            from_step: Some(0),
+            to_step: Some(1),
+            reason: DecisionReason::AnalysisPassed,
+            message: None,
+            metrics: Some(HashMap::from([(
+                "error-rate".to_string(),
+                MetricSnapshot {
+                    value: error_rate,
+                    threshold: 5.0,
+                    passed: true,
+                },
+            )])),
+            count: None,
+            last_timestamp: None,
+            approved_by: None,
+        };
+        let completed = RolloutStatus {
+            phase: Some(Phase::Completed),
+            strategy: Some(StrategyKind::Canary),
+            revision: Some(7),
+            progress_started_at: Some("2026-01-01T12:00:00Z".to_string()),
+            decisions: vec![
+                // Previous revision
+                Decision {
+                    timestamp: "2025-12-01T09:00:00Z".to_string(),
+                    ..decision(DecisionAction::Promotion, 9.0)
+                },
+                decision(DecisionAction::StepAdvance, 1.5),
+                decision(DecisionAction::Promotion, 0.5),
+            ],
+            ..Default::default()
+        };
+        let now = DateTime::parse_from_rfc3339("2026-01-01T12:10:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let record = release_record(&rollout, &completed, now);
+
+        assert_eq!(record.rollout, "checkout");
+        assert_eq!(record.namespace, "shop");
+        assert_eq!(record.strategy, "canary");
+        assert_eq!(record.images.len(), 2);
+        assert_eq!(record.images[1].image, "envoy:1.30");
+        assert_eq!(record.revision, Some(7));
+        assert_eq!(record.duration_seconds, Some(600));
+        assert_eq!(record.analysis.decisions, 2);
+        assert_eq!(record.analysis.manual_promotions, 1);
+        assert_eq!(record.analysis.metrics["error-rate"].value, 0.5);
+    }
+}
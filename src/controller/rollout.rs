@@ -1,15 +1,53 @@
+pub mod advisor_drive;
+pub mod advisor_plan;
+pub mod auxiliary;
+pub mod baseline;
+pub mod degraded;
+pub mod history;
+pub mod ingress;
+pub mod metric_failures;
+pub mod migration;
 pub mod reconcile;
+pub mod recovery;
 pub mod replicaset;
+pub mod rollback_verification;
+pub mod services;
 pub mod status;
+pub mod step_analysis;
+pub mod step_experiment;
+pub mod summary;
+pub mod teardown;
+pub mod template_change;
+pub mod template_diff;
 pub mod traffic;
 pub mod validation;
+pub mod weight_override;
 
 // Re-export everything so external API is unchanged
+pub use advisor_drive::*;
+pub use advisor_plan::*;
+pub use auxiliary::*;
+pub use baseline::*;
+pub use degraded::*;
+pub use history::*;
+pub use ingress::*;
+pub use metric_failures::*;
+pub use migration::*;
 pub use reconcile::*;
+pub use recovery::*;
 pub use replicaset::*;
+pub use rollback_verification::*;
+pub use services::*;
 pub use status::*;
+pub use step_analysis::*;
+pub use step_experiment::*;
+pub use summary::*;
+pub use teardown::*;
+pub use template_change::*;
+pub use template_diff::*;
 pub use traffic::*;
 pub use validation::*;
+pub use weight_override::*;
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)] // Tests can use unwrap/expect for brevity
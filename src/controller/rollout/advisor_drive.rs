@@ -0,0 +1,336 @@
+//! Advisor-driven rollouts (advisor level `Driven`)
+//!
+//! At the Driven level a recommendation with at least
+//! `spec.advisor.minConfidence` (default 0.8) that passes
+//! [`GuardrailPolicy::check_drive`] moves the rollout instead of only being
+//! logged:
+//!
+//! - `advance` ends the current step early and moves to the next one (or
+//!   promotes from the last step), never further
+//! - `pause` holds the rollout at its step when the thresholds would move it on
+//! - `rollback` rolls the canary back like a failed analysis
+//! - `continue` leaves the decision to the thresholds
+//!
+//! Each is recorded as a decision with reason `AdvisorRecommendation` and sets
+//! `status.lastDecisionSource` to `advisor` until the rollout moves on
+//! otherwise. Freezes, gates and promotion policies still hold an advanced
+//! rollout, and `kulta.io/promote`, `kulta.io/approved-by` and
+//! `kulta.io/abort` override the advisor.
+
+use crate::controller::guardrails::{GuardrailPolicy, GuardrailViolation};
+use crate::controller::strategy_math::{canary_steps, is_advancing};
+use crate::crd::rollout::{
+    Decision, DecisionAction, DecisionReason, Phase, Recommendation, RecommendedAction, Rollout,
+    RolloutStatus,
+};
+use chrono::{DateTime, Utc};
+
+use super::status::advance_to_next_step;
+
+/// `status.lastDecisionSource` of decisions a Driven-level advisor made
+pub const ADVISOR_DECISION_SOURCE: &str = "advisor";
+
+/// Whether a Driven-level advisor's recommendation moves the rollout
+///
+/// Recommendations below the confidence threshold, and advancing or pausing
+/// anything but a canary at a step, are left to the thresholds (`Ok(false)`).
+pub fn drives_rollout(
+    rollout: &Rollout,
+    recommendation: &Recommendation,
+    guardrails: &GuardrailPolicy,
+    metrics_healthy: bool,
+) -> Result<bool, GuardrailViolation> {
+    if recommendation.confidence < rollout.spec.advisor.driving_confidence() {
+        return Ok(false);
+    }
+    let status = rollout.status.as_ref();
+    let current_weight = status.and_then(|s| s.current_weight).unwrap_or(0);
+    let current_step = status
+        .and_then(|s| s.current_step_index)
+        .filter(|_| rollout.spec.strategy.canary.is_some());
+    match (&recommendation.action, current_step) {
+        (RecommendedAction::Continue, _) => Ok(false),
+        (RecommendedAction::Advance { .. } | RecommendedAction::Pause, None) => Ok(false),
+        (_, current_step) => guardrails
+            .check_drive(
+                recommendation,
+                canary_steps(&rollout.spec),
+                current_step.unwrap_or(-1),
+                current_weight,
+                metrics_healthy,
+            )
+            .map(|()| true),
+    }
+}
+
+fn advisor_decision(
+    action: DecisionAction,
+    from_step: Option<i32>,
+    to_step: Option<i32>,
+    recommendation: &Recommendation,
+    now: DateTime<Utc>,
+) -> Decision {
+    Decision {
+        timestamp: now.to_rfc3339(),
+        action,
+        from_step,
+        to_step,
+        reason: DecisionReason::AdvisorRecommendation,
+        message: Some(format!(
+            "Advisor ({:.0}% confident): {}",
+            recommendation.confidence * 100.0,
+            recommendation.reasoning
+        )),
+        metrics: None,
+        count: None,
+        last_timestamp: None,
+        approved_by: None,
+    }
+}
+
+/// Act on a Driven-level advisor's recommendation
+///
+/// Only applies while the threshold decision left the rollout progressing
+/// (an advance) or moved it on (a pause hold); otherwise `desired` is kept,
+/// and `lastDecisionSource` is cleared if the rollout moved on.
+///
+/// # Arguments
+/// * `rollout` - The Rollout as reconciled
+/// * `desired` - Status computed by the strategy
+/// * `driven` - Recommendation that passed [`drives_rollout`] this reconcile
+pub fn apply_advisor_drive(
+    rollout: &Rollout,
+    mut desired: RolloutStatus,
+    driven: Option<&Recommendation>,
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let Some(current) = rollout.status.as_ref() else {
+        return desired;
+    };
+    let advancing = is_advancing(current, &desired);
+    let progressing = current.phase == Some(Phase::Progressing);
+
+    match driven {
+        Some(recommendation)
+            if matches!(recommendation.action, RecommendedAction::Advance { .. })
+                && progressing
+                && !advancing
+                && desired.phase == Some(Phase::Progressing) =>
+        {
+            let mut advanced = advance_to_next_step(rollout, now);
+            let action = if advanced.phase == Some(Phase::Completed) {
+                DecisionAction::Promotion
+            } else {
+                DecisionAction::StepAdvance
+            };
+            advanced.decisions.push(advisor_decision(
+                action,
+                current.current_step_index,
+                advanced.current_step_index,
+                recommendation,
+                now,
+            ));
+            advanced.last_decision_source = Some(ADVISOR_DECISION_SOURCE.to_string());
+            advanced
+        }
+        Some(recommendation)
+            if recommendation.action == RecommendedAction::Pause && progressing && advancing =>
+        {
+            let step = current.current_step_index;
+            let mut decisions = current.decisions.clone();
+            // One decision per hold, not one per reconcile
+            let holding = decisions.last().is_some_and(|decision| {
+                decision.action == DecisionAction::Pause
+                    && decision.reason == DecisionReason::AdvisorRecommendation
+                    && decision.from_step == step
+            });
+            if !holding {
+                decisions.push(advisor_decision(
+                    DecisionAction::Pause,
+                    step,
+                    None,
+                    recommendation,
+                    now,
+                ));
+            }
+            RolloutStatus {
+                message: Some(format!(
+                    "Holding at step {}: the advisor recommends pausing",
+                    step.unwrap_or(0)
+                )),
+                decisions,
+                last_decision_source: Some(ADVISOR_DECISION_SOURCE.to_string()),
+                ..current.clone()
+            }
+        }
+        _ => {
+            if advancing {
+                desired.last_decision_source = None;
+            }
+            desired
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(step: i32, weight: i32) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 10, "pause": { "duration": "5m" } },
+                        { "setWeight": 20, "pause": { "untilApproved": true } },
+                        { "setWeight": 40, "pause": { "duration": "5m" } }
+                    ]
+                }))
+                .unwrap(),
+            )
+            .advisor(
+                serde_json::from_value(serde_json::json!({
+                    "level": "Driven", "endpoint": "http://advisor:8080"
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.status = Some(RolloutStatus {
+            phase: Some(Phase::Progressing),
+            current_step_index: Some(step),
+            current_weight: Some(weight),
+            pause_start_time: Some("2026-03-02T08:58:00Z".to_string()),
+            ..Default::default()
+        });
+        rollout
+    }
+
+    fn recommend(action: RecommendedAction, confidence: f64) -> Recommendation {
+        Recommendation {
+            action,
+            confidence,
+            reasoning: "error rate flat".to_string(),
+            plan: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-02T09:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_only_confident_recommendations_within_guardrails_drive() {
+        let policy = GuardrailPolicy::default();
+        let advance = RecommendedAction::Advance { to_weight: 20 };
+
+        assert_eq!(
+            drives_rollout(
+                &rollout(0, 10),
+                &recommend(advance.clone(), 0.9),
+                &policy,
+                true
+            ),
+            Ok(true)
+        );
+        assert_eq!(
+            drives_rollout(
+                &rollout(0, 10),
+                &recommend(advance.clone(), 0.5),
+                &policy,
+                true
+            ),
+            Ok(false)
+        );
+        // Step 1 waits for an approval
+        assert!(drives_rollout(&rollout(1, 20), &recommend(advance, 0.9), &policy, true).is_err());
+        assert_eq!(
+            drives_rollout(
+                &rollout(0, 10),
+                &recommend(RecommendedAction::Continue, 0.99),
+                &policy,
+                true
+            ),
+            Ok(false)
+        );
+
+        let mut strict = rollout(0, 10);
+        strict.spec.advisor.min_confidence = Some(0.95);
+        assert_eq!(
+            drives_rollout(
+                &strict,
+                &recommend(RecommendedAction::Rollback, 0.9),
+                &policy,
+                true
+            ),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_advance_ends_a_timed_pause_early() {
+        let rollout = rollout(0, 10);
+        let waiting = rollout.status.clone().unwrap();
+        let recommendation = recommend(RecommendedAction::Advance { to_weight: 20 }, 0.9);
+
+        let status = apply_advisor_drive(&rollout, waiting, Some(&recommendation), now());
+
+        assert_eq!(status.current_step_index, Some(1));
+        assert_eq!(status.current_weight, Some(20));
+        assert_eq!(status.last_decision_source.as_deref(), Some("advisor"));
+        let decision = status.decisions.last().unwrap();
+        assert_eq!(decision.action, DecisionAction::StepAdvance);
+        assert_eq!(decision.reason, DecisionReason::AdvisorRecommendation);
+        assert_eq!((decision.from_step, decision.to_step), (Some(0), Some(1)));
+        assert_eq!(
+            decision.message.as_deref(),
+            Some("Advisor (90% confident): error rate flat")
+        );
+    }
+
+    #[test]
+    fn test_pause_holds_the_step_once() {
+        let mut rollout = rollout(0, 10);
+        let recommendation = recommend(RecommendedAction::Pause, 0.9);
+        let advanced = RolloutStatus {
+            current_step_index: Some(1),
+            current_weight: Some(20),
+            ..rollout.status.clone().unwrap()
+        };
+
+        let held = apply_advisor_drive(&rollout, advanced.clone(), Some(&recommendation), now());
+        assert_eq!(held.current_step_index, Some(0));
+        assert_eq!(held.current_weight, Some(10));
+        assert_eq!(held.last_decision_source.as_deref(), Some("advisor"));
+        assert_eq!(held.decisions.len(), 1);
+        assert_eq!(held.decisions[0].action, DecisionAction::Pause);
+
+        // Still holding on the next reconcile: no new decision
+        rollout.status = Some(held.clone());
+        let still_held =
+            apply_advisor_drive(&rollout, advanced.clone(), Some(&recommendation), now());
+        assert_eq!(still_held, held);
+
+        // Once the advisor lets go the thresholds move it on
+        let threshold = RolloutStatus {
+            last_decision_source: held.last_decision_source.clone(),
+            ..advanced
+        };
+        let released = apply_advisor_drive(&rollout, threshold, None, now());
+        assert_eq!(released.current_step_index, Some(1));
+        assert_eq!(released.last_decision_source, None);
+        // The status merge patch clears the field
+        let patch = serde_json::json!({ "status": released });
+        assert_eq!(
+            patch["status"].get("lastDecisionSource"),
+            Some(&serde_json::Value::Null)
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Advisor step plans (advisor level `Planned`)
+//!
+//! At the Planned level the advisor may return a `plan` with its
+//! recommendation: the canary steps to run after the current one, each with
+//! an optional weight and timed pause. A plan that passes
+//! [`GuardrailPolicy::check_plan`] is recorded in `status.advisorPlan` with a
+//! `Replan`/`AdvisorPlan` decision, and from then on the rollout runs the
+//! declared steps up to `fromStep` followed by the planned ones.
+//!
+//! Plan step indexes always refer to the declared ladder (after `stepsFrom`),
+//! so a later plan is folded into the one in effect. A plan only applies to
+//! the revision it was made for and is dropped once the rollout is no longer
+//! in flight.
+
+use crate::controller::guardrails::{GuardrailPolicy, GuardrailViolation};
+use crate::controller::strategy_math::canary_steps;
+use crate::crd::rollout::{
+    AdvisorPlan, CanaryStep, Decision, DecisionAction, DecisionReason, Pause, PlannedStep, Rollout,
+    RolloutStatus,
+};
+use chrono::{DateTime, Utc};
+
+use super::template_change::is_in_flight;
+use super::validation::validate_rollout;
+
+/// Plan in `status` that still applies to its revision
+fn active_plan(status: &RolloutStatus) -> Option<&AdvisorPlan> {
+    status.advisor_plan.as_ref().filter(|plan| {
+        plan.pod_template_hash == status.pod_template_hash && is_in_flight(Some(status))
+    })
+}
+
+/// Declared canary steps up to `fromStep` followed by the planned steps
+///
+/// Returns `None` if the plan refers to steps the ladder doesn't have.
+pub fn planned_steps(steps: &[CanaryStep], plan: &AdvisorPlan) -> Option<Vec<CanaryStep>> {
+    let kept = usize::try_from(plan.from_step).ok()? + 1;
+    let mut planned = steps.get(..kept)?.to_vec();
+    for planned_step in &plan.steps {
+        let mut step = steps.get(usize::try_from(planned_step.step).ok()?)?.clone();
+        if let Some(weight) = planned_step.set_weight {
+            step.set_weight = Some(weight);
+        }
+        if let Some(duration) = &planned_step.pause_duration {
+            step.pause = Some(Pause::Duration(duration.clone()));
+        }
+        planned.push(step);
+    }
+    Some(planned)
+}
+
+/// The Rollout running `plan` instead of its declared steps
+fn with_plan(rollout: &Rollout, declared: &[CanaryStep], plan: &AdvisorPlan) -> Option<Rollout> {
+    let steps = planned_steps(declared, plan)?;
+    let mut planned = rollout.clone();
+    planned.spec.strategy.canary.as_mut()?.steps = steps;
+    Some(planned)
+}
+
+/// The Rollout running its accepted advisor plan, if one applies
+pub fn with_advisor_plan(rollout: &Rollout) -> Option<Rollout> {
+    let plan = active_plan(rollout.status.as_ref()?)?;
+    with_plan(rollout, canary_steps(&rollout.spec), plan)
+}
+
+/// Fold steps proposed at `from_step` of the ladder in effect into a plan
+/// over the declared ladder
+///
+/// Steps of the plan in effect up to `from_step` are kept; proposed steps
+/// that don't set a weight or pause inherit the ones planned before.
+fn fold_plan(
+    current: Option<&AdvisorPlan>,
+    from_step: i32,
+    proposed: &[PlannedStep],
+) -> Option<(i32, Vec<PlannedStep>)> {
+    let Some(current) = current.filter(|current| from_step >= current.from_step) else {
+        return Some((from_step, proposed.to_vec()));
+    };
+    // Ladder index i after current.from_step runs current.steps[i - from_step - 1]
+    let planned_at = |index: i32| {
+        usize::try_from(index - current.from_step - 1)
+            .ok()
+            .and_then(|offset| current.steps.get(offset))
+    };
+    let kept = usize::try_from(from_step - current.from_step).ok()?;
+    let mut steps = current.steps.get(..kept)?.to_vec();
+    for step in proposed {
+        let earlier = planned_at(step.step)?;
+        steps.push(PlannedStep {
+            step: earlier.step,
+            set_weight: step.set_weight.or(earlier.set_weight),
+            pause_duration: step
+                .pause_duration
+                .clone()
+                .or_else(|| earlier.pause_duration.clone()),
+        });
+    }
+    Some((current.from_step, steps))
+}
+
+/// Check steps an advisor proposed and turn them into the plan to record
+///
+/// # Arguments
+/// * `rollout` - The Rollout as reconciled (running the plan in effect, if any)
+/// * `declared` - Its declared canary steps
+/// * `proposed` - Steps the advisor wants to run after the current one
+/// * `guardrails` - Controller-wide limits
+/// * `metrics_healthy` - Threshold analysis result
+pub fn propose_plan(
+    rollout: &Rollout,
+    declared: &[CanaryStep],
+    proposed: &[PlannedStep],
+    guardrails: &GuardrailPolicy,
+    metrics_healthy: bool,
+    now: DateTime<Utc>,
+) -> Result<AdvisorPlan, GuardrailViolation> {
+    let invalid = |reason: &str| GuardrailViolation::InvalidPlan(reason.to_string());
+    let status = rollout
+        .status
+        .as_ref()
+        .ok_or_else(|| invalid("the rollout has not started"))?;
+    let from_step = status
+        .current_step_index
+        .ok_or_else(|| invalid("the rollout is not at a canary step"))?;
+    guardrails.check_plan(
+        canary_steps(&rollout.spec),
+        from_step,
+        status.current_weight.unwrap_or(0),
+        proposed,
+        metrics_healthy,
+    )?;
+
+    let (from_step, steps) = fold_plan(active_plan(status), from_step, proposed)
+        .ok_or_else(|| invalid("the plan in effect doesn't cover the proposed steps"))?;
+    let plan = AdvisorPlan {
+        from_step,
+        steps,
+        pod_template_hash: status.pod_template_hash.clone(),
+        accepted_at: now.to_rfc3339(),
+    };
+    // The planned ladder has to pass the checks the declared one did (e.g.
+    // weights the gateway can honour)
+    let planned = with_plan(rollout, declared, &plan)
+        .ok_or_else(|| invalid("the plan refers to steps the rollout doesn't have"))?;
+    validate_rollout(&planned).map_err(GuardrailViolation::InvalidPlan)?;
+    Ok(plan)
+}
+
+/// Human-readable list of planned steps, e.g. "step 3 at 40% pausing 10m, step 5"
+pub fn describe_plan(steps: &[PlannedStep]) -> String {
+    if steps.is_empty() {
+        return "no further steps".to_string();
+    }
+    steps
+        .iter()
+        .map(|step| {
+            let mut description = format!("step {}", step.step + 1);
+            if let Some(weight) = step.set_weight {
+                description.push_str(&format!(" at {}%", weight));
+            }
+            if let Some(duration) = &step.pause_duration {
+                description.push_str(&format!(" pausing {}", duration));
+            }
+            description
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Keep the plan in effect while it applies and record a newly accepted one
+///
+/// A new plan is only recorded while the rollout is still at the step it was
+/// proposed for (i.e. the threshold decision didn't move it on).
+///
+/// # Arguments
+/// * `rollout` - The Rollout as reconciled
+/// * `desired` - Status the rollout is about to get (with its revision)
+/// * `accepted` - Plan accepted this reconcile and the advisor's reasoning
+pub fn apply_advisor_plan(
+    rollout: &Rollout,
+    mut desired: RolloutStatus,
+    accepted: Option<(AdvisorPlan, String)>,
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let current = rollout.status.as_ref();
+    desired.advisor_plan = current
+        .and_then(|status| status.advisor_plan.clone())
+        .filter(|plan| {
+            plan.pod_template_hash == desired.pod_template_hash && is_in_flight(Some(&desired))
+        });
+
+    let Some((plan, reasoning)) = accepted else {
+        return desired;
+    };
+    let from_step = current.and_then(|status| status.current_step_index);
+    if desired.current_step_index != from_step
+        || desired.advisor_plan.as_ref().map(|plan| &plan.steps) == Some(&plan.steps)
+        || plan.pod_template_hash != desired.pod_template_hash
+        || !is_in_flight(Some(&desired))
+    {
+        return desired;
+    }
+    desired.decisions.push(Decision {
+        timestamp: now.to_rfc3339(),
+        action: DecisionAction::Replan,
+        from_step,
+        to_step: None,
+        reason: DecisionReason::AdvisorPlan,
+        message: Some(format!(
+            "Advisor plan: {} ({})",
+            describe_plan(&plan.steps),
+            reasoning
+        )),
+        metrics: None,
+        count: None,
+        last_timestamp: None,
+        approved_by: None,
+    });
+    desired.advisor_plan = Some(plan);
+    desired
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::Phase;
+
+    fn rollout() -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 10, "pause": { "duration": "5m" } },
+                        { "setWeight": 20, "pause": { "duration": "5m" } },
+                        { "setWeight": 30, "pause": { "duration": "5m" } },
+                        { "setWeight": 40, "pause": { "duration": "5m" } },
+                        { "setWeight": 50, "pause": { "duration": "5m" } }
+                    ]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.status = Some(at_step(0, 10));
+        rollout
+    }
+
+    fn at_step(step: i32, weight: i32) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(Phase::Paused),
+            current_step_index: Some(step),
+            current_weight: Some(weight),
+            pod_template_hash: Some("abc123".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn planned(step: i32, weight: Option<i32>, pause: Option<&str>) -> PlannedStep {
+        PlannedStep {
+            step,
+            set_weight: weight,
+            pause_duration: pause.map(String::from),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-02T09:00:00Z".parse().unwrap()
+    }
+
+    fn weights(rollout: &Rollout) -> Vec<Option<i32>> {
+        canary_steps(&rollout.spec)
+            .iter()
+            .map(|step| step.set_weight)
+            .collect()
+    }
+
+    #[test]
+    fn test_accepted_plan_replaces_the_remaining_steps() {
+        let mut rollout = rollout();
+        let declared = canary_steps(&rollout.spec).to_vec();
+        let proposed = [planned(2, None, Some("15m")), planned(4, None, None)];
+
+        let plan = propose_plan(
+            &rollout,
+            &declared,
+            &proposed,
+            &GuardrailPolicy::default(),
+            true,
+            now(),
+        )
+        .unwrap();
+        let status = apply_advisor_plan(
+            &rollout,
+            at_step(0, 10),
+            Some((plan, "error rate flat".to_string())),
+            now(),
+        );
+
+        let decision = status.decisions.last().unwrap();
+        assert_eq!(decision.action, DecisionAction::Replan);
+        assert_eq!(decision.reason, DecisionReason::AdvisorPlan);
+        assert_eq!(
+            decision.message.as_deref(),
+            Some("Advisor plan: step 3 pausing 15m, step 5 (error rate flat)")
+        );
+
+        rollout.status = Some(status);
+        let planned = with_advisor_plan(&rollout).unwrap();
+        assert_eq!(weights(&planned), vec![Some(10), Some(30), Some(50)]);
+        assert_eq!(
+            canary_steps(&planned.spec)[1].pause,
+            Some(Pause::Duration("15m".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_later_plans_fold_into_the_plan_in_effect() {
+        let mut rollout = rollout();
+        let declared = canary_steps(&rollout.spec).to_vec();
+        let mut status = at_step(1, 30);
+        status.advisor_plan = Some(AdvisorPlan {
+            from_step: 0,
+            steps: vec![
+                planned(2, None, Some("15m")),
+                planned(3, None, None),
+                planned(4, None, None),
+            ],
+            pod_template_hash: Some("abc123".to_string()),
+            accepted_at: now().to_rfc3339(),
+        });
+        rollout.status = Some(status);
+        let rollout = with_advisor_plan(&rollout).unwrap();
+
+        // Ladder step 3 (declared step 5) at 45%, skipping declared step 4
+        let plan = propose_plan(
+            &rollout,
+            &declared,
+            &[planned(3, Some(45), None)],
+            &GuardrailPolicy::default(),
+            true,
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.from_step, 0);
+        assert_eq!(
+            plan.steps,
+            vec![planned(2, None, Some("15m")), planned(4, Some(45), None)]
+        );
+    }
+
+    #[test]
+    fn test_plans_only_apply_to_their_revision_while_in_flight() {
+        let mut rollout = rollout();
+        let mut status = at_step(0, 10);
+        status.advisor_plan = Some(AdvisorPlan {
+            from_step: 0,
+            steps: vec![planned(4, None, None)],
+            pod_template_hash: Some("abc123".to_string()),
+            accepted_at: now().to_rfc3339(),
+        });
+        rollout.status = Some(status);
+
+        let kept = apply_advisor_plan(&rollout, at_step(1, 50), None, now());
+        assert!(kept.advisor_plan.is_some());
+
+        let mut new_revision = at_step(0, 10);
+        new_revision.pod_template_hash = Some("def456".to_string());
+        let dropped = apply_advisor_plan(&rollout, new_revision, None, now());
+        assert_eq!(dropped.advisor_plan, None);
+
+        let mut completed = at_step(1, 100);
+        completed.phase = Some(Phase::Completed);
+        let dropped = apply_advisor_plan(&rollout, completed, None, now());
+        assert_eq!(dropped.advisor_plan, None);
+    }
+
+    #[test]
+    fn test_rejected_and_stale_plans_are_not_recorded() {
+        let rollout = rollout();
+        let declared = canary_steps(&rollout.spec).to_vec();
+        let policy = GuardrailPolicy {
+            max_weight_increase: Some(20),
+            ..Default::default()
+        };
+
+        // 10% -> 50% in one go
+        let rejected = propose_plan(
+            &rollout,
+            &declared,
+            &[planned(4, None, None)],
+            &policy,
+            true,
+            now(),
+        );
+        assert!(rejected.is_err());
+
+        // The threshold decision moved the rollout on before the plan could apply
+        let plan = propose_plan(
+            &rollout,
+            &declared,
+            &[
+                planned(2, None, None),
+                planned(3, None, None),
+                planned(4, None, None),
+            ],
+            &policy,
+            true,
+            now(),
+        )
+        .unwrap();
+        let status = apply_advisor_plan(
+            &rollout,
+            at_step(1, 20),
+            Some((plan, "fine".to_string())),
+            now(),
+        );
+        assert_eq!(status.advisor_plan, None);
+        assert!(status.decisions.is_empty());
+    }
+}
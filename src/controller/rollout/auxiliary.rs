@@ -0,0 +1,225 @@
+//! Auxiliary objects and `spec.cleanupPolicy`
+//!
+//! Besides ReplicaSets, KULTA creates objects that only exist to route
+//! traffic for a Rollout, today the NGINX canary Ingress. They carry the
+//! `rollouts.kulta.io/managed` label and an owner reference to the Rollout,
+//! which is how they are found again. Without cleanup, a canary Ingress for
+//! a stable Ingress the Rollout no longer uses (or one left from before a
+//! switch to Gateway API or blue-green) lingers until the Rollout is deleted.
+//!
+//! With `cleanupPolicy: Delete` (the default) unused objects are deleted once
+//! the Rollout has completed, and all of them before its ReplicaSets when the
+//! Rollout is deleted. `Retain` keeps them, and deleting the Rollout releases
+//! them so the garbage collector leaves them alone.
+
+use super::ingress::{canary_ingress_name, get_nginx_routing};
+use super::reconcile::{Context, ReconcileError};
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::controller::orphans::MANAGED_LABEL;
+use crate::crd::rollout::{CleanupPolicy, Phase, Rollout};
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams};
+use kube::ResourceExt;
+use tracing::info;
+
+/// Cleanup policy of a Rollout (`Delete` unless set)
+pub fn cleanup_policy(rollout: &Rollout) -> CleanupPolicy {
+    rollout.spec.cleanup_policy.unwrap_or_default()
+}
+
+/// Whether an object's owner references point at the Rollout
+pub fn is_owned_by(owner_uids: &[&str], rollout: &Rollout) -> bool {
+    rollout
+        .metadata
+        .uid
+        .as_deref()
+        .is_some_and(|uid| owner_uids.contains(&uid))
+}
+
+/// Names of the Rollout's auxiliary Ingresses that its current routing doesn't use
+///
+/// Only the canary Ingress of the configured stable Ingress is in use; all
+/// of them are unused once the Rollout no longer routes through NGINX.
+pub fn unused_ingresses(rollout: &Rollout, owned: &[Ingress]) -> Vec<String> {
+    let current = get_nginx_routing(rollout)
+        .map(|routing| canary_ingress_name(&rollout.name_any(), &routing.stable_ingress));
+    owned
+        .iter()
+        .map(|ingress| ingress.name_any())
+        .filter(|name| current.as_ref() != Some(name))
+        .collect()
+}
+
+/// Auxiliary Ingresses created for the Rollout
+async fn list_owned_ingresses(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<Vec<Ingress>, kube::Error> {
+    let api: Api<Ingress> = Api::namespaced(ctx.client.clone(), namespace);
+    Ok(api
+        .list(&ListParams::default().labels(&format!("{}=true", MANAGED_LABEL)))
+        .await?
+        .items
+        .into_iter()
+        .filter(|ingress| {
+            let owners: Vec<&str> = ingress
+                .owner_references()
+                .iter()
+                .map(|owner| owner.uid.as_str())
+                .collect();
+            is_owned_by(&owners, rollout)
+        })
+        .collect())
+}
+
+/// Delete auxiliary objects a completed Rollout no longer uses
+///
+/// Nothing is deleted before the Rollout completes (the old routing may
+/// still carry traffic) or with `cleanupPolicy: Retain`.
+///
+/// # Returns
+/// Number of objects deleted
+pub async fn cleanup_auxiliary_objects(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<usize, ReconcileError> {
+    let completed = rollout
+        .status
+        .as_ref()
+        .is_some_and(|s| s.phase == Some(Phase::Completed));
+    if !completed || cleanup_policy(rollout) == CleanupPolicy::Retain {
+        return Ok(0);
+    }
+
+    let owned = list_owned_ingresses(rollout, ctx, namespace).await?;
+    let unused = unused_ingresses(rollout, &owned);
+    delete_ingresses(ctx, namespace, &unused, "no longer used by the Rollout").await?;
+    Ok(unused.len())
+}
+
+/// Apply the cleanup policy to a deleted Rollout's auxiliary objects
+///
+/// `Delete` removes them, `Retain` drops their owner reference and managed
+/// label so they outlive the Rollout.
+///
+/// # Returns
+/// Number of objects deleted or released
+pub async fn teardown_auxiliary_objects(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<usize, ReconcileError> {
+    let owned = list_owned_ingresses(rollout, ctx, namespace).await?;
+    let names: Vec<String> = owned.iter().map(|ingress| ingress.name_any()).collect();
+
+    match cleanup_policy(rollout) {
+        CleanupPolicy::Delete => {
+            delete_ingresses(ctx, namespace, &names, "rollout deleted").await?;
+        }
+        CleanupPolicy::Retain => {
+            let api: Api<Ingress> = Api::namespaced(ctx.client.clone(), namespace);
+            let patch = serde_json::json!({
+                "metadata": {
+                    "ownerReferences": null,
+                    "labels": { MANAGED_LABEL: null }
+                }
+            });
+            for name in &names {
+                api.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await?;
+                audit::record(
+                    AuditOperation::Patch,
+                    AuditObjectRef::new("Ingress", namespace, name),
+                    "released from Rollout",
+                    "cleanupPolicy Retain",
+                )
+                .await;
+                info!(ingress = ?name, "Released Ingress of deleted Rollout");
+            }
+        }
+    }
+    Ok(names.len())
+}
+
+async fn delete_ingresses(
+    ctx: &Context,
+    namespace: &str,
+    names: &[String],
+    reason: &str,
+) -> Result<(), kube::Error> {
+    let api: Api<Ingress> = Api::namespaced(ctx.client.clone(), namespace);
+    for name in names {
+        api.delete(name, &DeleteParams::background()).await?;
+        audit::record(
+            AuditOperation::Delete,
+            AuditObjectRef::new("Ingress", namespace, name),
+            "auxiliary Ingress",
+            reason,
+        )
+        .await;
+        info!(ingress = ?name, reason = reason, "Deleted auxiliary Ingress");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(routing: serde_json::Value) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop-stable",
+                    "canaryService": "shop-canary",
+                    "steps": [{ "setWeight": 100 }],
+                    "trafficRouting": routing
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.metadata.uid = Some("uid-1".to_string());
+        rollout
+    }
+
+    fn ingress(name: &str) -> Ingress {
+        serde_json::from_value(serde_json::json!({ "metadata": { "name": name } })).unwrap()
+    }
+
+    #[test]
+    fn test_unused_ingresses() {
+        let owned = [ingress("shop-web-canary"), ingress("shop-legacy-canary")];
+
+        let nginx = rollout(serde_json::json!({ "nginx": { "stableIngress": "web" } }));
+        assert_eq!(
+            unused_ingresses(&nginx, &owned),
+            vec!["shop-legacy-canary".to_string()]
+        );
+
+        // After a switch to Gateway API none of them is used
+        let gateway = rollout(serde_json::json!({ "gatewayAPI": { "httpRoute": "shop" } }));
+        assert_eq!(unused_ingresses(&gateway, &owned).len(), 2);
+    }
+
+    #[test]
+    fn test_cleanup_policy_and_ownership() {
+        let mut rollout = rollout(serde_json::json!({ "nginx": { "stableIngress": "web" } }));
+        assert_eq!(cleanup_policy(&rollout), CleanupPolicy::Delete);
+        rollout.spec.cleanup_policy = Some(CleanupPolicy::Retain);
+        assert_eq!(cleanup_policy(&rollout), CleanupPolicy::Retain);
+
+        assert!(is_owned_by(&["uid-0", "uid-1"], &rollout));
+        assert!(!is_owned_by(&["uid-2"], &rollout));
+        rollout.metadata.uid = None;
+        assert!(!is_owned_by(&["uid-1"], &rollout));
+    }
+}
@@ -0,0 +1,225 @@
+//! Fresh baseline ReplicaSet for canary comparisons
+//!
+//! With `canary.baseline`, `{rollout}-baseline` runs the stable pod template
+//! next to the canary while a rollout is in flight, so metrics with
+//! `compareToBaseline` compare two equally new sets of pods. Its pods carry
+//! `rollouts.kulta.io/type: baseline` and `kulta.io/revision:
+//! <stable hash>-baseline`, so Prometheus can tell them from the stable pods
+//! running the same template.
+//!
+//! The ReplicaSet is deleted once no rollout is in flight, and when the
+//! stable template has changed since it was created; the next rollout
+//! starts a fresh one.
+
+use super::history::rollback_template;
+use super::reconcile::ReconcileError;
+use super::replicaset::{build_replicaset, ensure_replicaset_exists, REVISION_LABEL};
+use super::template_change::is_in_flight;
+use super::template_diff::baseline_replicaset_name;
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::crd::rollout::{ManagedReplicaSet, ReplicaSetRole, Rollout};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use kube::api::{Api, DeleteParams};
+use kube::ResourceExt;
+use tracing::info;
+
+/// `rollouts.kulta.io/type` of the baseline ReplicaSet
+pub const BASELINE_REPLICASET_TYPE: &str = "baseline";
+
+/// `kulta.io/revision` of the baseline pods for a stable pod-template-hash
+pub fn baseline_pod_revision(stable_hash: &str) -> String {
+    format!("{}-{}", stable_hash, BASELINE_REPLICASET_TYPE)
+}
+
+/// Pods the baseline ReplicaSet should run
+///
+/// `canary.baseline.replicas`, or as many as the canary, while a rollout is
+/// in flight; otherwise none. `None` without `canary.baseline`.
+pub fn baseline_replicas(rollout: &Rollout, canary_replicas: i32) -> Option<i32> {
+    let baseline = rollout.spec.strategy.canary.as_ref()?.baseline.as_ref()?;
+    if !is_in_flight(rollout.status.as_ref()) {
+        return Some(0);
+    }
+    Some(baseline.replicas.unwrap_or(canary_replicas))
+}
+
+fn pod_template_hash(rs: &ReplicaSet) -> Option<&str> {
+    rs.labels().get("pod-template-hash").map(String::as_str)
+}
+
+/// Build the baseline ReplicaSet from the stable ReplicaSet's pod template
+///
+/// Name: `{rollout-name}-baseline`. It takes the stable ReplicaSet's
+/// pod-template-hash, so it is recognized as outdated once the stable
+/// template changes; the pods' `kulta.io/revision` gets the `-baseline`
+/// suffix.
+///
+/// # Returns
+/// * `Ok(Some(rs))` - The baseline ReplicaSet
+/// * `Ok(None)` - The stable ReplicaSet has no pod template
+pub fn build_baseline_replicaset(
+    rollout: &Rollout,
+    stable: &ReplicaSet,
+    replicas: i32,
+) -> Result<Option<ReplicaSet>, ReconcileError> {
+    let Some(template) = rollback_template(stable) else {
+        return Ok(None);
+    };
+    let mut copy = rollout.clone();
+    copy.spec.template = template;
+    let mut rs = build_replicaset(&copy, BASELINE_REPLICASET_TYPE, replicas)?;
+    let Some(stable_hash) = pod_template_hash(stable).map(str::to_string) else {
+        return Ok(Some(rs));
+    };
+
+    let hash_label = || ("pod-template-hash".to_string(), stable_hash.clone());
+    rs.metadata
+        .labels
+        .get_or_insert_with(Default::default)
+        .extend([hash_label()]);
+    if let Some(spec) = rs.spec.as_mut() {
+        spec.selector
+            .match_labels
+            .get_or_insert_with(Default::default)
+            .extend([hash_label()]);
+        if let Some(metadata) = spec.template.as_mut().and_then(|t| t.metadata.as_mut()) {
+            metadata
+                .labels
+                .get_or_insert_with(Default::default)
+                .extend([
+                    hash_label(),
+                    (
+                        REVISION_LABEL.to_string(),
+                        baseline_pod_revision(&stable_hash),
+                    ),
+                ]);
+        }
+    }
+    Ok(Some(rs))
+}
+
+/// Scale, create or delete the baseline ReplicaSet
+///
+/// # Returns
+/// * `Ok(Some(managed))` - The baseline ReplicaSet, at `replicas`
+/// * `Ok(None)` - No baseline runs: `replicas` is 0, there is no stable
+///   ReplicaSet to copy yet, or an outdated one was just deleted
+pub async fn reconcile_baseline_replicaset(
+    rollout: &Rollout,
+    rs_api: &Api<ReplicaSet>,
+    replicas: i32,
+) -> Result<Option<ManagedReplicaSet>, ReconcileError> {
+    let name = format!("{}-{}", rollout.name_any(), BASELINE_REPLICASET_TYPE);
+    let existing = rs_api.get_opt(&name).await?;
+    let stable = match replicas {
+        0 => None,
+        _ => rs_api.get_opt(&baseline_replicaset_name(rollout)).await?,
+    };
+
+    let outdated = existing.as_ref().is_some_and(|existing| {
+        stable.is_none()
+            || pod_template_hash(existing) != stable.as_ref().and_then(pod_template_hash)
+    });
+    if outdated {
+        rs_api.delete(&name, &DeleteParams::background()).await?;
+        audit::record(
+            AuditOperation::Delete,
+            AuditObjectRef::new(
+                "ReplicaSet",
+                rollout.namespace().as_deref().unwrap_or_default(),
+                &name,
+            ),
+            "baseline ReplicaSet",
+            "no rollout in flight, or the stable template changed",
+        )
+        .await;
+        info!(replicaset = ?name, "Deleted baseline ReplicaSet");
+        return Ok(None);
+    }
+
+    let Some(rs) = stable
+        .map(|stable| build_baseline_replicaset(rollout, &stable, replicas))
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    ensure_replicaset_exists(rs_api, &rs, ReplicaSetRole::Baseline, replicas)
+        .await
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{Phase, RolloutStatus};
+
+    fn rollout(phase: Phase, baseline: serde_json::Value) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(10)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [{ "setWeight": 100 }],
+                    "baseline": baseline
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.metadata.uid = Some("uid-1".to_string());
+        rollout.status = Some(RolloutStatus {
+            phase: Some(phase),
+            ..Default::default()
+        });
+        rollout
+    }
+
+    #[test]
+    fn test_baseline_replicas_follow_the_canary_while_in_flight() {
+        let progressing = rollout(Phase::Progressing, serde_json::json!({}));
+        assert_eq!(baseline_replicas(&progressing, 3), Some(3));
+        let fixed = rollout(Phase::Paused, serde_json::json!({ "replicas": 2 }));
+        assert_eq!(baseline_replicas(&fixed, 3), Some(2));
+        let completed = rollout(Phase::Completed, serde_json::json!({}));
+        assert_eq!(baseline_replicas(&completed, 3), Some(0));
+        let without = rollout(Phase::Progressing, serde_json::Value::Null);
+        assert_eq!(baseline_replicas(&without, 3), None);
+    }
+
+    #[test]
+    fn test_build_baseline_replicaset_from_stable_replicaset() {
+        let rollout = rollout(Phase::Progressing, serde_json::json!({}));
+        let mut old = rollout.clone();
+        old.spec.template.spec.as_mut().unwrap().containers[0].image = Some("shop:1".to_string());
+        let stable = build_replicaset(&old, "stable", 7).unwrap();
+        let stable_hash = stable.labels()["pod-template-hash"].clone();
+
+        let rs = build_baseline_replicaset(&rollout, &stable, 3)
+            .unwrap()
+            .unwrap();
+        assert_eq!(rs.name_any(), "shop-baseline");
+        assert_eq!(rs.labels()["rollouts.kulta.io/type"], "baseline");
+        assert_eq!(rs.labels()["pod-template-hash"], stable_hash);
+        let spec = rs.spec.unwrap();
+        assert_eq!(spec.replicas, Some(3));
+        assert_eq!(
+            spec.selector.match_labels.unwrap()["rollouts.kulta.io/type"],
+            "baseline"
+        );
+        let template = spec.template.unwrap();
+        assert_eq!(
+            template.metadata.unwrap().labels.unwrap()[REVISION_LABEL],
+            format!("{}-baseline", stable_hash)
+        );
+        assert_eq!(
+            template.spec.unwrap().containers[0].image.as_deref(),
+            Some("shop:1")
+        );
+    }
+}
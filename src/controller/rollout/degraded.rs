@@ -0,0 +1,161 @@
+use crate::crd::rollout::{DecisionReason, DegradedAction, Phase, Rollout, RolloutStatus};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use tracing::warn;
+
+use super::status::roll_back_to_stable;
+
+/// Name of the ReplicaSet running the new revision for the rollout's strategy
+///
+/// # Returns
+/// * `Some(name)` - canary, preview, variant-b, or the single simple ReplicaSet
+/// * `None` - Rollout has no name
+pub fn new_revision_replicaset_name(rollout: &Rollout) -> Option<String> {
+    let name = rollout.metadata.name.as_ref()?;
+    let strategy = &rollout.spec.strategy;
+    let suffix = if strategy.canary.is_some() {
+        Some("canary")
+    } else if let Some(blue_green) = &strategy.blue_green {
+        Some(blue_green.replicaset_types().1)
+    } else if strategy.ab_testing.is_some() {
+        Some("variant-b")
+    } else {
+        None
+    };
+
+    Some(match suffix {
+        Some(suffix) => format!("{}-{}", name, suffix),
+        None => name.clone(),
+    })
+}
+
+/// Why a ReplicaSet counts as degraded, if it does
+///
+/// Degraded means a `ReplicaFailure` condition is set (pods can't even be
+/// created, e.g., quota exceeded) or fewer pods are ready than desired.
+/// A ReplicaSet scaled to zero is never degraded.
+pub fn replicaset_degradation(rs: &ReplicaSet) -> Option<String> {
+    let desired = rs.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0);
+    if desired == 0 {
+        return None;
+    }
+
+    let status = rs.status.as_ref();
+    if let Some(failure) = status
+        .and_then(|s| s.conditions.as_ref())
+        .into_iter()
+        .flatten()
+        .find(|c| c.type_ == "ReplicaFailure" && c.status == "True")
+    {
+        return Some(format!(
+            "ReplicaFailure: {}",
+            failure
+                .message
+                .as_deref()
+                .or(failure.reason.as_deref())
+                .unwrap_or("pods cannot be created")
+        ));
+    }
+
+    let ready = status.and_then(|s| s.ready_replicas).unwrap_or(0);
+    if ready < desired {
+        return Some(format!("{}/{} pods ready", ready, desired));
+    }
+    None
+}
+
+/// Apply `spec.onDegraded` to the desired status
+///
+/// Tracks when degradation started in `degradedSince` and, once it has lasted
+/// `afterSeconds`, rolls the rollout back to stable (see
+/// [`roll_back_to_stable`]) or holds it at the current step. Only
+/// active rollouts are affected; finished ones (Completed, Failed, Concluded)
+/// are left alone.
+///
+/// # Arguments
+/// * `rollout` - Rollout with its current status
+/// * `desired_status` - Status computed by the strategy
+/// * `degradation` - Result of [`replicaset_degradation`] for the new revision
+/// * `now` - Current time
+///
+/// # Returns
+/// The desired status with `degradedSince` updated and the action applied
+pub(crate) fn apply_degraded_policy(
+    rollout: &Rollout,
+    desired_status: RolloutStatus,
+    degradation: Option<&str>,
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let Some(policy) = rollout.spec.on_degraded.as_ref() else {
+        return desired_status;
+    };
+    if matches!(
+        desired_status.phase,
+        Some(Phase::Completed) | Some(Phase::Failed) | Some(Phase::Concluded) | None
+    ) {
+        return RolloutStatus {
+            degraded_since: None,
+            ..desired_status
+        };
+    }
+
+    let Some(reason) = degradation else {
+        return RolloutStatus {
+            degraded_since: None,
+            ..desired_status
+        };
+    };
+
+    let since = rollout
+        .status
+        .as_ref()
+        .and_then(|s| s.degraded_since.clone())
+        .unwrap_or_else(|| now.to_rfc3339());
+    let elapsed = match DateTime::parse_from_rfc3339(&since) {
+        Ok(started) => now.signed_duration_since(started.with_timezone(&Utc)),
+        Err(e) => {
+            warn!(error = %e, timestamp = %since, "Failed to parse degraded_since timestamp");
+            chrono::Duration::zero()
+        }
+    };
+
+    let status = RolloutStatus {
+        degraded_since: Some(since),
+        ..desired_status
+    };
+    if elapsed.num_seconds() < policy.after_seconds() as i64 {
+        return status;
+    }
+
+    match policy.action {
+        DegradedAction::Rollback => roll_back_to_stable(
+            rollout,
+            status,
+            DecisionReason::Degraded,
+            format!(
+                "Rollback triggered: new revision degraded for {}s ({})",
+                elapsed.num_seconds(),
+                reason
+            ),
+            now,
+        ),
+        DegradedAction::Pause => {
+            // Hold at the current step, like a pause that the pods' recovery
+            // (or kulta.io/promote) lifts
+            let current = rollout.status.clone().unwrap_or_else(|| status.clone());
+            RolloutStatus {
+                message: Some(format!(
+                    "Holding: new revision degraded for {}s ({})",
+                    elapsed.num_seconds(),
+                    reason
+                )),
+                degraded_since: status.degraded_since,
+                ..current
+            }
+        }
+        DegradedAction::None => RolloutStatus {
+            message: Some(format!("New revision degraded: {}", reason)),
+            ..status
+        },
+    }
+}
@@ -0,0 +1,410 @@
+//! Revision history and rollback to an earlier revision
+//!
+//! Every pod template a Rollout runs is kept as a scaled-down ReplicaSet
+//! `{rollout}-{pod-template-hash}` of type `history`, annotated with its
+//! revision number. `spec.revisionHistoryLimit` (default 10) bounds how many
+//! old revisions are kept next to the current one.
+//!
+//! Annotating the Rollout with `kulta.io/rollback-to-revision: N` copies
+//! revision N's pod template back into `spec.template`, which then rolls out
+//! like any other new revision (under the next revision number).
+
+use super::reconcile::{Context, ReconcileError};
+use super::replicaset::build_history_replicaset;
+use super::template_diff::strip_injected_labels;
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::controller::orphans::{MANAGED_LABEL, ROLLOUT_ANNOTATION};
+use crate::crd::rollout::{ManagedReplicaSet, Phase, Rollout, RolloutStatus};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams};
+use kube::ResourceExt;
+use serde_json::{Map, Value};
+use tracing::info;
+
+/// ReplicaSet annotation holding the revision number of its pod template
+pub const REVISION_ANNOTATION: &str = "rollout.kulta.io/revision";
+
+/// Rollout annotation requesting a rollback to an earlier revision
+pub const ROLLBACK_TO_REVISION_ANNOTATION: &str = "kulta.io/rollback-to-revision";
+
+/// Label holding the ReplicaSet role
+const TYPE_LABEL: &str = "rollouts.kulta.io/type";
+
+/// `rollouts.kulta.io/type` of ReplicaSets kept for revision history
+pub const HISTORY_REPLICASET_TYPE: &str = "history";
+
+/// Old revisions kept when `spec.revisionHistoryLimit` is not set
+pub const DEFAULT_REVISION_HISTORY_LIMIT: usize = 10;
+
+/// Revision number for a pod template hash, given the recorded status
+///
+/// The same template keeps its number; any other template (including an
+/// earlier one being rolled back to) gets the next one. Rollouts recorded
+/// before revisions were tracked count their recorded template as revision 1.
+pub fn revision_number(current: Option<&RolloutStatus>, pod_template_hash: &str) -> i64 {
+    let Some(status) = current else {
+        return 1;
+    };
+    let recorded = status
+        .revision
+        .unwrap_or(i64::from(status.pod_template_hash.is_some()));
+    if status.pod_template_hash.as_deref() == Some(pod_template_hash) {
+        recorded.max(1)
+    } else {
+        recorded + 1
+    }
+}
+
+/// Name of the history ReplicaSet for a pod template hash
+pub fn history_replicaset_name(rollout: &Rollout, pod_template_hash: &str) -> String {
+    format!("{}-{}", rollout.name_any(), pod_template_hash)
+}
+
+/// Revision number recorded on a history ReplicaSet
+pub fn replicaset_revision(rs: &ReplicaSet) -> Option<i64> {
+    rs.annotations()
+        .get(REVISION_ANNOTATION)
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Revision requested with `kulta.io/rollback-to-revision`
+///
+/// # Returns
+/// * `None` - No rollback requested
+/// * `Some(Ok(revision))` - Rollback to this revision
+/// * `Some(Err(value))` - Annotation is not a positive revision number
+pub fn requested_rollback(rollout: &Rollout) -> Option<Result<i64, String>> {
+    let value = rollout.annotations().get(ROLLBACK_TO_REVISION_ANNOTATION)?;
+    Some(
+        value
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .filter(|revision| *revision > 0)
+            .ok_or_else(|| value.clone()),
+    )
+}
+
+/// History ReplicaSets to delete so at most `limit` old revisions remain
+///
+/// The ReplicaSet of the current template is never pruned; the oldest
+/// revisions go first.
+pub fn revisions_to_prune(history: &[ReplicaSet], current_hash: &str, limit: usize) -> Vec<String> {
+    let mut old: Vec<&ReplicaSet> = history
+        .iter()
+        .filter(|rs| rs.labels().get("pod-template-hash").map(String::as_str) != Some(current_hash))
+        .collect();
+    old.sort_by_key(|rs| std::cmp::Reverse(replicaset_revision(rs).unwrap_or(0)));
+    old.into_iter()
+        .skip(limit)
+        .map(|rs| rs.name_any())
+        .collect()
+}
+
+/// Rollout pod template stored in a history ReplicaSet
+pub fn rollback_template(rs: &ReplicaSet) -> Option<PodTemplateSpec> {
+    let mut template = rs.spec.as_ref()?.template.clone()?;
+    strip_injected_labels(&mut template);
+    Some(template)
+}
+
+/// JSON merge patch (RFC 7386) turning `old` into `new`
+///
+/// Keys missing from `new` are set to null so the patch removes them.
+pub fn merge_patch_diff(old: &Value, new: &Value) -> Value {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut patch = Map::new();
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                patch.insert(key.clone(), Value::Null);
+            }
+            for (key, value) in new {
+                match old.get(key) {
+                    Some(old_value) if old_value == value => {}
+                    Some(old_value) => {
+                        patch.insert(key.clone(), merge_patch_diff(old_value, value));
+                    }
+                    None => {
+                        patch.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Value::Object(patch)
+        }
+        _ => new.clone(),
+    }
+}
+
+/// Managed ReplicaSets created for a Rollout
+pub async fn list_owned_replicasets(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<Vec<ReplicaSet>, kube::Error> {
+    list_replicasets(rollout, ctx, namespace, &format!("{}=true", MANAGED_LABEL)).await
+}
+
+/// History ReplicaSets of a Rollout
+pub async fn list_history(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<Vec<ReplicaSet>, kube::Error> {
+    let selector = format!(
+        "{}=true,{}={}",
+        MANAGED_LABEL, TYPE_LABEL, HISTORY_REPLICASET_TYPE
+    );
+    list_replicasets(rollout, ctx, namespace, &selector).await
+}
+
+async fn list_replicasets(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+    selector: &str,
+) -> Result<Vec<ReplicaSet>, kube::Error> {
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), namespace);
+    let name = rollout.name_any();
+    Ok(rs_api
+        .list(&ListParams::default().labels(selector))
+        .await?
+        .items
+        .into_iter()
+        .filter(|rs| rs.annotations().get(ROLLOUT_ANNOTATION) == Some(&name))
+        .collect())
+}
+
+/// Keep the current revision's template for rollback
+///
+/// Creates the history ReplicaSet for the current template, or renumbers it
+/// when an earlier template comes back. Old revisions are pruned by
+/// [`cleanup_replicasets`].
+pub async fn record_history(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+    pod_template_hash: &str,
+    revision: i64,
+) -> Result<(), ReconcileError> {
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), namespace);
+    let rs_name = history_replicaset_name(rollout, pod_template_hash);
+    let audit_ref = AuditObjectRef::new("ReplicaSet", namespace, &rs_name);
+
+    match rs_api.get_opt(&rs_name).await? {
+        None => {
+            let rs = build_history_replicaset(rollout, revision)?;
+            rs_api.create(&PostParams::default(), &rs).await?;
+            audit::record(
+                AuditOperation::Create,
+                audit_ref,
+                format!("revision {}", revision),
+                "record revision history",
+            )
+            .await;
+            info!(replicaset = ?rs_name, revision = revision, "Recorded revision history");
+        }
+        Some(existing) if replicaset_revision(&existing) != Some(revision) => {
+            let patch = serde_json::json!({
+                "metadata": { "annotations": { REVISION_ANNOTATION: revision.to_string() } }
+            });
+            rs_api
+                .patch(&rs_name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await?;
+            audit::record(
+                AuditOperation::Patch,
+                audit_ref,
+                format!("revision {}", revision),
+                "revision restored",
+            )
+            .await;
+            info!(replicaset = ?rs_name, revision = revision, "Renumbered revision history");
+        }
+        Some(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Delete old revisions past the history limit and ReplicaSets left behind
+///
+/// `current` are the ReplicaSets the strategy just reconciled. History
+/// ReplicaSets beyond `spec.revisionHistoryLimit` are deleted, oldest first.
+/// ReplicaSets of another strategy's roles (e.g. `{name}-canary` after a
+/// switch to blue-green) are deleted once the Rollout has completed, so their
+/// pods keep serving until the new ones have taken over.
+///
+/// # Returns
+/// Number of ReplicaSets deleted
+pub async fn cleanup_replicasets(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+    current_hash: &str,
+    current: &[ManagedReplicaSet],
+) -> Result<usize, ReconcileError> {
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), namespace);
+    let owned = list_owned_replicasets(rollout, ctx, namespace).await?;
+    let (history, others): (Vec<ReplicaSet>, Vec<ReplicaSet>) = owned.into_iter().partition(|rs| {
+        rs.labels().get(TYPE_LABEL).map(String::as_str) == Some(HISTORY_REPLICASET_TYPE)
+    });
+
+    let completed = rollout
+        .status
+        .as_ref()
+        .is_some_and(|s| s.phase == Some(Phase::Completed));
+    let stale = revisions_to_prune(&history, current_hash, revision_history_limit(rollout))
+        .into_iter()
+        .map(|name| (name, "revision history limit"))
+        .chain(
+            leftover_replicasets(&others, current)
+                .into_iter()
+                .filter(|_| completed)
+                .map(|name| (name, "no longer used by the strategy")),
+        );
+
+    let mut deleted = 0;
+    for (name, reason) in stale {
+        rs_api.delete(&name, &DeleteParams::background()).await?;
+        audit::record(
+            AuditOperation::Delete,
+            AuditObjectRef::new("ReplicaSet", namespace, &name),
+            "stale ReplicaSet",
+            reason,
+        )
+        .await;
+        info!(replicaset = ?name, reason = reason, "Deleted stale ReplicaSet");
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// Old revisions kept for the Rollout
+pub fn revision_history_limit(rollout: &Rollout) -> usize {
+    rollout
+        .spec
+        .revision_history_limit
+        .map_or(DEFAULT_REVISION_HISTORY_LIMIT, |limit| {
+            limit.max(0) as usize
+        })
+}
+
+/// Non-history ReplicaSets the strategy no longer reconciles
+pub fn leftover_replicasets(others: &[ReplicaSet], current: &[ManagedReplicaSet]) -> Vec<String> {
+    others
+        .iter()
+        .map(|rs| rs.name_any())
+        .filter(|name| !current.iter().any(|managed| &managed.name == name))
+        .collect()
+}
+
+/// History ReplicaSet holding a revision
+pub fn find_revision(history: &[ReplicaSet], revision: i64) -> Option<&ReplicaSet> {
+    history
+        .iter()
+        .find(|rs| replicaset_revision(rs) == Some(revision))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::rollout::ReplicaSetRole;
+    use serde_json::json;
+
+    fn history_rs(name: &str, hash: &str, revision: i64) -> ReplicaSet {
+        serde_json::from_value(json!({
+            "metadata": {
+                "name": name,
+                "labels": { "pod-template-hash": hash },
+                "annotations": { REVISION_ANNOTATION: revision.to_string() }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_revision_number() {
+        let status = |hash: Option<&str>, revision: Option<i64>| RolloutStatus {
+            pod_template_hash: hash.map(String::from),
+            revision,
+            ..Default::default()
+        };
+
+        assert_eq!(revision_number(None, "aaa"), 1);
+        assert_eq!(revision_number(Some(&status(None, None)), "aaa"), 1);
+        assert_eq!(
+            revision_number(Some(&status(Some("aaa"), Some(3))), "aaa"),
+            3
+        );
+        assert_eq!(
+            revision_number(Some(&status(Some("aaa"), Some(3))), "bbb"),
+            4
+        );
+        // Recorded before revisions were tracked
+        assert_eq!(revision_number(Some(&status(Some("aaa"), None)), "aaa"), 1);
+        assert_eq!(revision_number(Some(&status(Some("aaa"), None)), "bbb"), 2);
+    }
+
+    #[test]
+    fn test_revisions_to_prune_keeps_newest_and_current() {
+        let history = vec![
+            history_rs("app-r1", "r1", 1),
+            history_rs("app-r4", "r4", 4),
+            history_rs("app-r2", "r2", 2),
+            history_rs("app-r3", "r3", 3),
+        ];
+
+        assert_eq!(revisions_to_prune(&history, "r4", 2), vec!["app-r1"]);
+        assert_eq!(
+            revisions_to_prune(&history, "r4", 0),
+            vec!["app-r3", "app-r2", "app-r1"]
+        );
+        assert!(revisions_to_prune(&history, "r4", 10).is_empty());
+        // Rolled back to revision 2: current template is never pruned
+        assert_eq!(
+            revisions_to_prune(&history, "r2", 1),
+            vec!["app-r3", "app-r1"]
+        );
+    }
+
+    #[test]
+    fn test_leftover_replicasets() {
+        let managed = |name: &str| ManagedReplicaSet {
+            name: name.to_string(),
+            role: ReplicaSetRole::Stable,
+            hash: String::new(),
+            replicas: 1,
+        };
+        let owned = vec![
+            history_rs("app-active", "r2", 2),
+            history_rs("app-preview", "r2", 2),
+            history_rs("app-canary", "r1", 1),
+        ];
+
+        assert_eq!(
+            leftover_replicasets(&owned, &[managed("app-active"), managed("app-preview")]),
+            vec!["app-canary"]
+        );
+    }
+
+    #[test]
+    fn test_merge_patch_diff_removes_missing_keys() {
+        let old = json!({
+            "metadata": { "labels": { "app": "web", "track": "canary" } },
+            "spec": { "containers": [{ "name": "web", "image": "web:2" }], "hostNetwork": true }
+        });
+        let new = json!({
+            "metadata": { "labels": { "app": "web" } },
+            "spec": { "containers": [{ "name": "web", "image": "web:1" }] }
+        });
+
+        assert_eq!(
+            merge_patch_diff(&old, &new),
+            json!({
+                "metadata": { "labels": { "track": null } },
+                "spec": { "containers": [{ "name": "web", "image": "web:1" }], "hostNetwork": null }
+            })
+        );
+    }
+}
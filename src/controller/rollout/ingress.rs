@@ -0,0 +1,252 @@
+//! NGINX Ingress canary traffic splitting
+//!
+//! The NGINX Ingress controller splits traffic between an Ingress and a
+//! second "canary" Ingress for the same host and path. KULTA builds that
+//! canary Ingress from the stable one: same rules and TLS, backends pointing
+//! at the canary service, and `canary` / `canary-weight` annotations carrying
+//! the current step weight.
+
+use super::traffic::calculate_traffic_weights;
+use crate::crd::cluster_rollout::rollout_owner_reference;
+use crate::crd::rollout::{NginxTrafficRouting, Rollout};
+use k8s_openapi::api::networking::v1::{Ingress, IngressBackend};
+use kube::api::ObjectMeta;
+use kube::ResourceExt;
+use std::collections::BTreeMap;
+
+/// Default annotation prefix of the NGINX Ingress controller
+pub const DEFAULT_NGINX_ANNOTATION_PREFIX: &str = "nginx.ingress.kubernetes.io";
+
+/// Label marking Ingresses managed by KULTA
+const MANAGED_LABEL: &str = "rollouts.kulta.io/managed";
+
+/// NGINX routing config of a canary rollout, if configured
+pub fn get_nginx_routing(rollout: &Rollout) -> Option<&NginxTrafficRouting> {
+    rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()?
+        .traffic_routing
+        .as_ref()?
+        .nginx
+        .as_ref()
+}
+
+/// Name of the canary Ingress KULTA manages for a stable Ingress
+pub fn canary_ingress_name(rollout_name: &str, stable_ingress: &str) -> String {
+    format!("{}-{}-canary", rollout_name, stable_ingress)
+}
+
+/// Point every backend for `from` at `to` instead
+fn retarget_backend(backend: &mut IngressBackend, from: &str, to: &str) {
+    if let Some(service) = backend.service.as_mut() {
+        if service.name == from {
+            service.name = to.to_string();
+        }
+    }
+}
+
+/// Build the canary Ingress for the rollout's current weight
+///
+/// # Returns
+/// * `Ok(ingress)` - Canary Ingress to create or update
+/// * `Err(reason)` - Not an NGINX-routed canary, or the stable Ingress never
+///   routes to the stable service
+pub fn build_canary_ingress(rollout: &Rollout, stable: &Ingress) -> Result<Ingress, String> {
+    let canary = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .ok_or_else(|| "NGINX traffic routing requires the canary strategy".to_string())?;
+    let routing = get_nginx_routing(rollout)
+        .ok_or_else(|| "spec.strategy.canary.trafficRouting.nginx is not set".to_string())?;
+
+    let mut spec = stable.spec.clone().unwrap_or_default();
+    let mut retargeted = false;
+    if let Some(backend) = spec.default_backend.as_mut() {
+        retarget_backend(backend, &canary.stable_service, &canary.canary_service);
+        retargeted |= backend
+            .service
+            .as_ref()
+            .is_some_and(|s| s.name == canary.canary_service);
+    }
+    for rule in spec.rules.iter_mut().flatten() {
+        for path in rule.http.iter_mut().flat_map(|http| http.paths.iter_mut()) {
+            retarget_backend(
+                &mut path.backend,
+                &canary.stable_service,
+                &canary.canary_service,
+            );
+            retargeted |= path
+                .backend
+                .service
+                .as_ref()
+                .is_some_and(|s| s.name == canary.canary_service);
+        }
+    }
+    if !retargeted {
+        return Err(format!(
+            "Ingress {} has no backend for stable service {}",
+            stable.name_any(),
+            canary.stable_service
+        ));
+    }
+
+    let (_, canary_weight) = calculate_traffic_weights(rollout);
+    let prefix = routing
+        .annotation_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_NGINX_ANNOTATION_PREFIX);
+    let annotations = BTreeMap::from([
+        (format!("{}/canary", prefix), "true".to_string()),
+        (
+            format!("{}/canary-weight", prefix),
+            canary_weight.to_string(),
+        ),
+    ]);
+    let labels = BTreeMap::from([(MANAGED_LABEL.to_string(), "true".to_string())]);
+
+    Ok(Ingress {
+        metadata: ObjectMeta {
+            name: Some(canary_ingress_name(
+                &rollout.name_any(),
+                &routing.stable_ingress,
+            )),
+            namespace: rollout.namespace(),
+            labels: Some(labels),
+            annotations: Some(annotations),
+            owner_references: rollout_owner_reference(rollout).map(|owner| vec![owner]),
+            ..Default::default()
+        },
+        spec: Some(spec),
+        status: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::RolloutStatus;
+
+    fn nginx_rollout(step: Option<i32>) -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "canaryService": "shop-canary",
+                    "stableService": "shop-stable",
+                    "steps": [{ "setWeight": 25 }, { "setWeight": 100 }],
+                    "trafficRouting": { "nginx": { "stableIngress": "shop" } }
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.metadata.uid = Some("uid-1".to_string());
+        rollout.status = Some(RolloutStatus {
+            current_step_index: step,
+            ..Default::default()
+        });
+        rollout
+    }
+
+    fn stable_ingress(service: &str) -> Ingress {
+        serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "shop", "namespace": "prod" },
+            "spec": {
+                "ingressClassName": "nginx",
+                "tls": [{ "hosts": ["shop.example.com"], "secretName": "shop-tls" }],
+                "rules": [{
+                    "host": "shop.example.com",
+                    "http": { "paths": [
+                        { "path": "/", "pathType": "Prefix",
+                          "backend": { "service": { "name": service, "port": { "number": 80 } } } },
+                        { "path": "/static", "pathType": "Prefix",
+                          "backend": { "service": { "name": "cdn", "port": { "number": 80 } } } }
+                    ] }
+                }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_canary_ingress() {
+        let ingress =
+            build_canary_ingress(&nginx_rollout(Some(0)), &stable_ingress("shop-stable")).unwrap();
+
+        assert_eq!(ingress.name_any(), "shop-shop-canary");
+        assert_eq!(ingress.namespace().as_deref(), Some("prod"));
+        let annotations = ingress.annotations();
+        assert_eq!(annotations["nginx.ingress.kubernetes.io/canary"], "true");
+        assert_eq!(
+            annotations["nginx.ingress.kubernetes.io/canary-weight"],
+            "25"
+        );
+        assert_eq!(ingress.owner_references()[0].name, "shop");
+
+        let spec = ingress.spec.unwrap();
+        assert_eq!(spec.ingress_class_name.as_deref(), Some("nginx"));
+        assert_eq!(spec.tls.unwrap().len(), 1);
+        let paths = &spec.rules.unwrap()[0].http.as_ref().unwrap().paths;
+        let services: Vec<&str> = paths
+            .iter()
+            .map(|p| p.backend.service.as_ref().unwrap().name.as_str())
+            .collect();
+        assert_eq!(services, vec!["shop-canary", "cdn"]);
+    }
+
+    #[test]
+    fn test_canary_weight_follows_status() {
+        let stable = stable_ingress("shop-stable");
+
+        let before_start = build_canary_ingress(&nginx_rollout(None), &stable).unwrap();
+        assert_eq!(
+            before_start.annotations()["nginx.ingress.kubernetes.io/canary-weight"],
+            "0"
+        );
+
+        let complete = build_canary_ingress(&nginx_rollout(Some(2)), &stable).unwrap();
+        assert_eq!(
+            complete.annotations()["nginx.ingress.kubernetes.io/canary-weight"],
+            "100"
+        );
+    }
+
+    #[test]
+    fn test_custom_annotation_prefix() {
+        let mut rollout = nginx_rollout(Some(0));
+        let routing = rollout
+            .spec
+            .strategy
+            .canary
+            .as_mut()
+            .unwrap()
+            .traffic_routing
+            .as_mut()
+            .unwrap();
+        routing.nginx.as_mut().unwrap().annotation_prefix = Some("ingress.example.com".to_string());
+
+        let ingress = build_canary_ingress(&rollout, &stable_ingress("shop-stable")).unwrap();
+
+        assert_eq!(
+            ingress.annotations()["ingress.example.com/canary-weight"],
+            "25"
+        );
+    }
+
+    #[test]
+    fn test_stable_ingress_without_stable_service_is_rejected() {
+        let error =
+            build_canary_ingress(&nginx_rollout(Some(0)), &stable_ingress("other")).unwrap_err();
+
+        assert!(error.contains("shop-stable"), "got: {}", error);
+    }
+}
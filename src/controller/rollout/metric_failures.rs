@@ -0,0 +1,191 @@
+//! Consecutive-failure counting for analysis metrics
+//!
+//! A metric with `failureThreshold: N` only counts as failed once it has
+//! breached its threshold N evaluations in a row; a passing evaluation
+//! resets its count. The counts live in `status.metricFailures` so they
+//! survive between reconciles. Metrics without a `failureThreshold` fail on
+//! a single bad sample, as before.
+//!
+//! Every status patch triggers another reconcile, so a failure is counted at
+//! most once per metric `interval` (default 30s), however often the metric
+//! is evaluated in between.
+
+use crate::crd::rollout::{AnalysisConfig, MetricConfig, MetricFailureCount};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use super::validation::parse_duration;
+
+/// Interval of a metric without `interval`
+pub const DEFAULT_METRIC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Whether any metric of the analysis tolerates consecutive failures
+pub fn uses_failure_thresholds(analysis: &AnalysisConfig) -> bool {
+    analysis
+        .metrics
+        .iter()
+        .any(|metric| metric.failure_threshold.unwrap_or(1) > 1)
+}
+
+/// Consecutive failure counts after an evaluation
+///
+/// `results` holds one result per metric, in order. Only metrics that are
+/// still failing are kept; a failure within the metric's `interval` of the
+/// last counted one keeps the previous count.
+pub fn count_consecutive_failures(
+    metrics: &[MetricConfig],
+    results: &[bool],
+    previous: &[MetricFailureCount],
+    now: DateTime<Utc>,
+) -> Vec<MetricFailureCount> {
+    metrics
+        .iter()
+        .zip(results)
+        .filter(|(_, is_healthy)| !**is_healthy)
+        .map(|(metric, _)| {
+            let before = previous.iter().find(|count| count.metric == metric.name);
+            if let Some(before) = before.filter(|before| within_interval(metric, before, now)) {
+                return before.clone();
+            }
+            MetricFailureCount {
+                metric: metric.name.clone(),
+                consecutive_failures: before
+                    .map_or(0, |count| count.consecutive_failures)
+                    .saturating_add(1),
+                last_evaluation_time: Some(now.to_rfc3339()),
+            }
+        })
+        .collect()
+}
+
+/// Whether `now` is within the metric's interval of the last counted failure
+fn within_interval(metric: &MetricConfig, count: &MetricFailureCount, now: DateTime<Utc>) -> bool {
+    let Some(last) = count
+        .last_evaluation_time
+        .as_deref()
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+    else {
+        return false;
+    };
+    let interval = metric
+        .interval
+        .as_deref()
+        .and_then(parse_duration)
+        .unwrap_or(DEFAULT_METRIC_INTERVAL);
+    now.signed_duration_since(last)
+        .to_std()
+        .is_ok_and(|elapsed| elapsed < interval)
+}
+
+/// Per-metric results with failures below the metric's `failureThreshold`
+/// counted as passing
+pub fn apply_failure_thresholds(
+    metrics: &[MetricConfig],
+    failures: &[MetricFailureCount],
+) -> Vec<bool> {
+    metrics
+        .iter()
+        .map(|metric| {
+            let count = failures
+                .iter()
+                .find(|count| count.metric == metric.name)
+                .map_or(0, |count| count.consecutive_failures);
+            count < metric.failure_threshold.unwrap_or(1).max(1)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn metric(name: &str, failure_threshold: Option<i32>) -> MetricConfig {
+        MetricConfig {
+            name: name.to_string(),
+            threshold: 5.0,
+            comparison: None,
+            unit: None,
+            interval: None,
+            failure_threshold,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            weight: None,
+            web: None,
+        }
+    }
+
+    /// `seconds` after the first evaluation
+    fn at(seconds: i64) -> DateTime<Utc> {
+        "2026-03-02T09:00:00Z".parse::<DateTime<Utc>>().unwrap()
+            + chrono::Duration::seconds(seconds)
+    }
+
+    #[test]
+    fn test_metric_fails_after_consecutive_breaches() {
+        let metrics = vec![metric("error-rate", Some(3)), metric("latency-p95", None)];
+
+        let first = count_consecutive_failures(&metrics, &[false, true], &[], at(0));
+        assert_eq!(apply_failure_thresholds(&metrics, &first), vec![true, true]);
+        let second = count_consecutive_failures(&metrics, &[false, true], &first, at(30));
+        assert_eq!(
+            apply_failure_thresholds(&metrics, &second),
+            vec![true, true]
+        );
+        let third = count_consecutive_failures(&metrics, &[false, true], &second, at(60));
+        assert_eq!(third[0].consecutive_failures, 3);
+        assert_eq!(
+            apply_failure_thresholds(&metrics, &third),
+            vec![false, true]
+        );
+
+        // Without a failureThreshold a single breach fails the metric
+        let breached = count_consecutive_failures(&metrics, &[true, false], &[], at(0));
+        assert_eq!(
+            apply_failure_thresholds(&metrics, &breached),
+            vec![true, false]
+        );
+    }
+
+    #[test]
+    fn test_passing_evaluation_resets_count() {
+        let metrics = vec![metric("error-rate", Some(2))];
+
+        let failing = count_consecutive_failures(&metrics, &[false], &[], at(0));
+        let reset = count_consecutive_failures(&metrics, &[true], &failing, at(30));
+        assert!(reset.is_empty());
+        let again = count_consecutive_failures(&metrics, &[false], &reset, at(60));
+        assert_eq!(again[0].consecutive_failures, 1);
+        assert_eq!(apply_failure_thresholds(&metrics, &again), vec![true]);
+        assert!(uses_failure_thresholds(&AnalysisConfig {
+            metrics,
+            ..AnalysisConfig::default()
+        }));
+    }
+
+    #[test]
+    fn test_failure_counted_once_per_interval() {
+        let mut metrics = vec![metric("error-rate", Some(3))];
+
+        let first = count_consecutive_failures(&metrics, &[false], &[], at(0));
+        // Reconciles triggered by the status patch evaluate again at once
+        let retriggered = count_consecutive_failures(&metrics, &[false], &first, at(2));
+        assert_eq!(retriggered, first);
+        let later = count_consecutive_failures(&metrics, &[false], &retriggered, at(30));
+        assert_eq!(later[0].consecutive_failures, 2);
+
+        metrics[0].interval = Some("1m".to_string());
+        let within = count_consecutive_failures(&metrics, &[false], &later, at(80));
+        assert_eq!(within[0].consecutive_failures, 2);
+        let next = count_consecutive_failures(&metrics, &[false], &within, at(90));
+        assert_eq!(next[0].consecutive_failures, 3);
+        assert_eq!(
+            next[0].last_evaluation_time.as_deref(),
+            Some("2026-03-02T09:01:30+00:00")
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! In-place strategy migration of live Rollouts
+//!
+//! Editing `spec.strategy` from canary to blue-green leaves the canary's
+//! ReplicaSets and Services behind and starts a second environment next to
+//! them. Annotating a canary Rollout with `kulta.io/migrate-strategy:
+//! blue-green` converts it instead: once the Rollout is Completed, the
+//! controller rewrites `spec.strategy` to a blue-green strategy that adopts
+//! the stable ReplicaSet and Service as active and the canary ReplicaSet and
+//! Service as preview (`migratedFrom: canary`), so no pod is recreated and
+//! the HTTPRoutes keep their backends.
+
+use super::validation::validate_rollout;
+use crate::crd::rollout::{
+    BlueGreenStrategy, CanaryStrategy, MigratedFrom, Phase, Rollout, RolloutStrategy,
+    TrafficRouting,
+};
+use kube::ResourceExt;
+
+/// Rollout annotation requesting a strategy migration
+pub const MIGRATE_STRATEGY_ANNOTATION: &str = "kulta.io/migrate-strategy";
+
+/// Strategy a Rollout can be migrated to
+pub const BLUE_GREEN_MIGRATION_TARGET: &str = "blue-green";
+
+/// What to do about a migration request
+#[derive(Debug, Clone)]
+pub enum MigrationPlan {
+    /// Replace `spec.strategy` with this blue-green strategy
+    Migrate(Box<BlueGreenStrategy>),
+    /// Valid, but the Rollout hasn't completed yet
+    Wait,
+    /// The request can never be applied (the reason is logged)
+    Reject(String),
+}
+
+/// Target strategy requested by the `kulta.io/migrate-strategy` annotation
+pub fn requested_migration(rollout: &Rollout) -> Option<&str> {
+    rollout
+        .annotations()
+        .get(MIGRATE_STRATEGY_ANNOTATION)
+        .map(|target| target.trim())
+}
+
+/// Blue-green strategy adopting a canary's Services and ReplicaSets
+///
+/// Steps, gates and analysis are canary-only and are not carried over; the
+/// Gateway API routing is.
+pub fn blue_green_from_canary(canary: &CanaryStrategy) -> BlueGreenStrategy {
+    BlueGreenStrategy {
+        active_service: canary.stable_service.clone(),
+        preview_service: canary.canary_service.clone(),
+        port: canary.port,
+        auto_promotion_enabled: None,
+        auto_promotion_seconds: None,
+        traffic_routing: canary
+            .traffic_routing
+            .as_ref()
+            .and_then(|routing| routing.gateway_api.clone())
+            .map(|gateway_api| TrafficRouting {
+                gateway_api: Some(gateway_api),
+                none: None,
+                nginx: None,
+            }),
+        analysis: None,
+        migrated_from: Some(MigratedFrom::Canary),
+    }
+}
+
+/// Decide how to handle a migration request to `target`
+pub fn plan_migration(rollout: &Rollout, target: &str) -> MigrationPlan {
+    if target != BLUE_GREEN_MIGRATION_TARGET {
+        return MigrationPlan::Reject(format!(
+            "unsupported migration target {:?} (only {:?})",
+            target, BLUE_GREEN_MIGRATION_TARGET
+        ));
+    }
+    let Some(canary) = &rollout.spec.strategy.canary else {
+        return MigrationPlan::Reject("only canary Rollouts can be migrated".to_string());
+    };
+    if canary
+        .traffic_routing
+        .as_ref()
+        .is_some_and(|routing| routing.nginx.is_some() || routing.none.is_some())
+    {
+        return MigrationPlan::Reject(
+            "blue-green only supports Gateway API traffic routing".to_string(),
+        );
+    }
+    if rollout.status.as_ref().and_then(|s| s.phase.as_ref()) != Some(&Phase::Completed) {
+        return MigrationPlan::Wait;
+    }
+
+    let blue_green = blue_green_from_canary(canary);
+    let mut migrated = rollout.clone();
+    migrated.spec.strategy = RolloutStrategy {
+        canary: None,
+        blue_green: Some(blue_green.clone()),
+        simple: None,
+        ab_testing: None,
+    };
+    match validate_rollout(&migrated) {
+        Ok(()) => MigrationPlan::Migrate(Box::new(blue_green)),
+        Err(e) => MigrationPlan::Reject(format!("migrated Rollout would be invalid: {}", e)),
+    }
+}
+
+/// Merge patch switching the Rollout to `blue_green` and consuming the request
+pub fn migration_patch(blue_green: &BlueGreenStrategy) -> serde_json::Value {
+    serde_json::json!({
+        "metadata": {
+            "annotations": { MIGRATE_STRATEGY_ANNOTATION: serde_json::Value::Null }
+        },
+        "spec": {
+            "strategy": {
+                "canary": serde_json::Value::Null,
+                "blueGreen": blue_green
+            }
+        }
+    })
+}
@@ -1,13 +1,72 @@
 use crate::controller::advisor::{
     resolve_advisor, AdvisorCache, AnalysisAdvisor, AnalysisContext, NoOpAdvisor,
 };
-use crate::controller::cdevents::emit_status_change_event;
-use crate::controller::occurrence::emit_occurrence;
-use crate::controller::prometheus::MetricsQuerier;
-use crate::crd::rollout::{AdvisorLevel, Phase, Rollout, RolloutStatus};
-use crate::server::LeaderState;
+use crate::controller::alertmanager::{
+    check_alert_gate, AlertGateDecision, AlertQuerier, HttpAlertmanagerClient,
+};
+use crate::controller::audit::{self, AuditLog, AuditObjectRef, AuditOperation};
+use crate::controller::cdevents::{
+    emit_heartbeat_event, emit_removed_event, emit_status_change_event, incident_subject_id,
+};
+use crate::controller::cdevents_data::StrategyKind;
+use crate::controller::concurrency::{pending_status, queue_position, ConcurrencyLimit};
+use crate::controller::defaults::RolloutDefaults;
+use crate::controller::dependency_gates::unhealthy_dependencies;
+use crate::controller::effective_config::{effective_config, AppliedPolicies};
+use crate::controller::freeze::{
+    apply_freeze, freeze_requeue_interval, frozen_start_status, starts_new_revision,
+    FreezeCalendar, FreezeWindow,
+};
+use crate::controller::guardrails::GuardrailPolicy;
+use crate::controller::heartbeat::{is_long_running, Heartbeats};
+use crate::controller::lint::{
+    lint_rollout, warnings_conditions, LintOptions, ProductionNamespaces,
+};
+use crate::controller::notifications::{notification_for, Notifications};
+use crate::controller::occurrence::{
+    emit_heartbeat_occurrence, emit_occurrence, emit_removed_occurrence,
+    emit_rollback_verified_occurrence, OccurrenceExtensions,
+};
+use crate::controller::occurrence_sink::OccurrenceSinks;
+use crate::controller::pod_policy::{
+    check_pod_template, find_denied_fields, sanitize_rollout, PodTemplatePolicy,
+};
+use crate::controller::prometheus::{
+    build_builtin_selector, render_query, MetricsQuerier, RevisionSelector, SampleFilter,
+    DEFAULT_ZONE_LABEL,
+};
+use crate::controller::prometheus_auth::{resolve_client_options, PrometheusClients};
+use crate::controller::promotion_policy::{resolve_promotion_mode, PromotionMode, PromotionPolicy};
+use crate::controller::release_hooks::{release_record, ReleaseHooks};
+use crate::controller::steps_from::StepsResolver;
+use crate::controller::strategies::{
+    managed_httproute_names, synced_httproute_statuses, RolloutStrategy, StrategyError,
+};
+use crate::controller::strategy_math::{canary_steps, increases_weight, is_advancing, step_at};
+use crate::controller::traffic_shift::TrafficShifts;
+use crate::controller::web_metric::render_web_metric;
+use crate::controller::work_queue::{
+    begin_reconcile, WorkQueue, CLUSTER_ROLLOUT_CONTROLLER, ROLLOUT_CONTROLLER,
+};
+use crate::controller::workload_ref::{
+    fetch_workload, scale_down_workload, should_scale_down, with_workload_template,
+};
+use crate::crd::cluster_rollout::{
+    rollout_owner_reference, source_cluster_rollout, yields_to, ClusterRollout,
+    CLUSTER_ROLLOUT_ANNOTATION,
+};
+use crate::crd::experiment::ExperimentPhase;
+use crate::crd::rollout::{
+    AdvisorLevel, BlueGreenStrategy, DecisionReason, HttpRouteStatus, ManagedResources,
+    MetricFailureCount, Phase, RecommendedAction, ReplicaSetRole, Rollout, RolloutCondition,
+    RolloutStatus, TemplateChangePolicy,
+};
+use crate::server::scaler::RolloutActivity;
+use crate::server::{object_lease_name, Claim, LeaderState, ObjectLeases, CONCURRENCY_TASK};
 use chrono::{DateTime, Utc};
-use kube::api::{Api, Patch, PatchParams};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use kube::api::{Api, ListParams, ObjectMeta, Patch, PatchParams};
 use kube::runtime::controller::Action;
 use kube::{Resource, ResourceExt};
 use std::sync::Arc;
@@ -15,10 +74,51 @@ use std::time::Duration;
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
 
+use super::advisor_drive::{apply_advisor_drive, drives_rollout, ADVISOR_DECISION_SOURCE};
+use super::advisor_plan::{apply_advisor_plan, propose_plan, with_advisor_plan};
+use super::auxiliary::{cleanup_auxiliary_objects, teardown_auxiliary_objects};
+use super::baseline::baseline_pod_revision;
+use super::degraded::{
+    apply_degraded_policy, new_revision_replicaset_name, replicaset_degradation,
+};
+use super::history::{
+    cleanup_replicasets, find_revision, list_history, merge_patch_diff, record_history,
+    requested_rollback, rollback_template, ROLLBACK_TO_REVISION_ANNOTATION,
+};
+use super::metric_failures::{
+    apply_failure_thresholds, count_consecutive_failures, uses_failure_thresholds,
+};
+use super::migration::{
+    migration_patch, plan_migration, requested_migration, MigrationPlan,
+    MIGRATE_STRATEGY_ANNOTATION,
+};
+use super::recovery::{is_stale_promotion, promotion_status_patch, repair_in_flight_status};
+use super::replicaset::{compute_pod_template_hash, digest_pod_template};
+use super::rollback_verification::{time_to_rollback_verification, verify_rollback};
+use super::services::{
+    has_missing_services_condition, missing_services_status, ServiceCache, DEGRADED_CONDITION,
+};
 use super::status::{
-    calculate_requeue_interval_from_rollout, has_promote_annotation, is_progress_deadline_exceeded,
+    apply_abort_or_retry, approved_by, calculate_requeue_interval_from_rollout, compact_decisions,
+    has_promote_annotation, is_awaiting_approval, is_progress_deadline_exceeded,
+    resets_analysis_on_resume, roll_back_to_stable, selector_string, APPROVED_BY_ANNOTATION,
+};
+use super::step_analysis::{measure_step_analysis, time_to_next_measurement, StepMeasurement};
+use super::step_experiment::{
+    run_step_experiment, StepExperimentOutcome, STEP_EXPERIMENT_POLL_INTERVAL,
+};
+use super::summary::status_summary;
+use super::teardown::{
+    delete_managed_replicasets, finalizers_patch, finalizers_with, finalizers_without,
+    has_finalizer, is_being_deleted, reset_traffic,
+};
+use super::template_change::{
+    in_flight_hash, in_flight_template, queued_revision_conditions, restart_for_new_template,
+    template_change_action,
 };
-use super::validation::{parse_duration, validate_rollout};
+use super::template_diff::{detect_template_change, record_revision};
+use super::validation::{parse_duration, pod_template_size_warning, validate_rollout};
+use super::weight_override::apply_weight_override;
 
 #[derive(Debug, Error)]
 pub enum ReconcileError {
@@ -45,18 +145,66 @@ pub enum ReconcileError {
 
     #[error("Strategy reconciliation failed: {0}")]
     StrategyError(#[from] crate::controller::strategies::StrategyError),
+
+    #[error("Failed to import canary steps: {0}")]
+    StepsFromError(#[from] crate::controller::steps_from::StepsFromError),
+
+    #[error("Failed to adopt workload: {0}")]
+    WorkloadRefError(#[from] crate::controller::workload_ref::WorkloadRefError),
 }
 
 pub struct Context {
     pub client: kube::Client,
     pub cdevents_sink: Arc<dyn crate::controller::cdevents::EventSink>,
     pub prometheus_client: Arc<dyn MetricsQuerier>,
+    /// Clients of Rollouts with their own Prometheus credentials or TLS
+    pub prometheus_clients: Arc<PrometheusClients>,
     pub advisor: Arc<dyn AnalysisAdvisor>,
     pub advisor_cache: AdvisorCache,
+    /// Alertmanager client for alert-gated steps
+    pub alertmanager: Arc<dyn AlertQuerier>,
+    /// Namespace-label promotion policy (forces manual promotion where configured)
+    pub promotion_policy: PromotionPolicy,
+    /// Defaults for fields Rollouts leave unset (none unless configured)
+    pub rollout_defaults: RolloutDefaults,
+    /// Limits on advisor recommendations and manual weights (none unless configured)
+    pub advisor_guardrails: GuardrailPolicy,
+    /// Pod template deny-list for restricted namespaces
+    pub pod_policy: PodTemplatePolicy,
+    /// Audit log of writes issued by reconciles (disabled unless configured)
+    pub audit_log: Arc<AuditLog>,
+    /// Deployment freeze windows (disabled unless configured)
+    pub freeze_calendar: FreezeCalendar,
+    /// Limit on active Rollouts per namespace (unlimited unless configured)
+    pub concurrency_limit: ConcurrencyLimit,
+    /// Release registries notified when a Rollout completes (none unless configured)
+    pub release_hooks: ReleaseHooks,
+    /// Heartbeats for Rollouts in long-running phases (disabled unless configured)
+    pub heartbeats: Heartbeats,
+    /// Chat and webhook channels told about phase changes, approvals and failures
+    pub notifications: Notifications,
+    /// Cached step ladders for `stepsFrom` references
+    pub steps_resolver: StepsResolver,
+    /// Referenced Services recently found to exist
+    pub service_cache: ServiceCache,
+    /// Namespaces linted as production (`KULTA_PRODUCTION_NAMESPACES`)
+    pub production_namespaces: ProductionNamespaces,
+    /// Extra data blocks for FALSE Protocol occurrences (none by default)
+    pub occurrence_extensions: OccurrenceExtensions,
+    /// Where FALSE Protocol occurrences are published (`KULTA_OCCURRENCE_SINKS`)
+    pub occurrence_sinks: OccurrenceSinks,
+    /// Patched routes watched until the gateway accepts them
+    pub traffic_shifts: Arc<TrafficShifts>,
+    /// Reconcile bookkeeping for the work queue metrics of each controller
+    pub rollout_queue: Arc<WorkQueue>,
+    pub cluster_rollout_queue: Arc<WorkQueue>,
     pub clock: Arc<dyn crate::controller::clock::Clock>,
     /// Optional leader state for multi-replica deployments
     /// When Some, reconciliation is skipped if not the leader
     pub leader_state: Option<LeaderState>,
+    /// Per-object leases when replicas share reconciles without a leader
+    /// (`KULTA_OBJECT_LEASES`, experimental)
+    pub object_leases: Option<Arc<ObjectLeases>>,
     /// Optional controller metrics for Prometheus
     /// When Some, records reconciliation counts and durations
     pub metrics: Option<crate::server::SharedMetrics>,
@@ -75,10 +223,34 @@ impl Context {
             client,
             cdevents_sink: Arc::new(cdevents_sink),
             prometheus_client: Arc::new(prometheus_client),
+            prometheus_clients: Arc::new(PrometheusClients::from_env()),
             advisor: Arc::new(NoOpAdvisor),
             advisor_cache: AdvisorCache::new(),
+            alertmanager: Arc::new(HttpAlertmanagerClient::from_env()),
+            promotion_policy: PromotionPolicy::from_env(),
+            rollout_defaults: RolloutDefaults::from_env(),
+            advisor_guardrails: GuardrailPolicy::from_env(),
+            pod_policy: PodTemplatePolicy::from_env(),
+            audit_log: Arc::new(AuditLog::from_env()),
+            freeze_calendar: FreezeCalendar::from_env(),
+            concurrency_limit: ConcurrencyLimit::from_env(),
+            release_hooks: ReleaseHooks::from_env(),
+            heartbeats: Heartbeats::from_env(),
+            notifications: Notifications::from_env(),
+            steps_resolver: StepsResolver::new(),
+            service_cache: ServiceCache::new(),
+            production_namespaces: ProductionNamespaces::from_env(),
+            occurrence_extensions: OccurrenceExtensions::new(),
+            occurrence_sinks: OccurrenceSinks::from_env(),
+            traffic_shifts: Arc::new(TrafficShifts::new(metrics.clone())),
+            rollout_queue: Arc::new(WorkQueue::new(ROLLOUT_CONTROLLER, clock.now())),
+            cluster_rollout_queue: Arc::new(WorkQueue::new(
+                CLUSTER_ROLLOUT_CONTROLLER,
+                clock.now(),
+            )),
             clock,
             leader_state: None,
+            object_leases: None,
             metrics,
         }
     }
@@ -99,10 +271,34 @@ impl Context {
             client,
             cdevents_sink: Arc::new(cdevents_sink),
             prometheus_client: Arc::new(prometheus_client),
+            prometheus_clients: Arc::new(PrometheusClients::from_env()),
             advisor: Arc::new(NoOpAdvisor),
             advisor_cache: AdvisorCache::new(),
+            alertmanager: Arc::new(HttpAlertmanagerClient::from_env()),
+            promotion_policy: PromotionPolicy::from_env(),
+            rollout_defaults: RolloutDefaults::from_env(),
+            advisor_guardrails: GuardrailPolicy::from_env(),
+            pod_policy: PodTemplatePolicy::from_env(),
+            audit_log: Arc::new(AuditLog::from_env()),
+            freeze_calendar: FreezeCalendar::from_env(),
+            concurrency_limit: ConcurrencyLimit::from_env(),
+            release_hooks: ReleaseHooks::from_env(),
+            heartbeats: Heartbeats::from_env(),
+            notifications: Notifications::from_env(),
+            steps_resolver: StepsResolver::new(),
+            service_cache: ServiceCache::new(),
+            production_namespaces: ProductionNamespaces::from_env(),
+            occurrence_extensions: OccurrenceExtensions::new(),
+            occurrence_sinks: OccurrenceSinks::from_env(),
+            traffic_shifts: Arc::new(TrafficShifts::new(metrics.clone())),
+            rollout_queue: Arc::new(WorkQueue::new(ROLLOUT_CONTROLLER, clock.now())),
+            cluster_rollout_queue: Arc::new(WorkQueue::new(
+                CLUSTER_ROLLOUT_CONTROLLER,
+                clock.now(),
+            )),
             clock,
             leader_state: Some(leader_state),
+            object_leases: None,
             metrics,
         }
     }
@@ -119,6 +315,72 @@ impl Context {
         }
     }
 
+    /// Claim an object's lease before reconciling it (per-object leases)
+    ///
+    /// Returns how long to wait when another replica holds the lease. Objects
+    /// are always claimed when per-object leases are disabled.
+    ///
+    /// # Arguments
+    /// * `kind` - Kind of the object, for the lease name
+    /// * `meta` - Its metadata (namespace and uid)
+    /// * `owner` - Owner of the lease, so it is deleted with the object
+    pub async fn claim_object(
+        &self,
+        kind: &str,
+        meta: &ObjectMeta,
+        owner: Option<OwnerReference>,
+    ) -> Result<Option<Duration>, kube::Error> {
+        let (Some(leases), Some(namespace), Some(uid)) = (
+            &self.object_leases,
+            meta.namespace.as_deref(),
+            meta.uid.as_deref(),
+        ) else {
+            return Ok(None);
+        };
+        let lease_name = object_lease_name(kind, uid);
+        match leases
+            .claim(namespace, &lease_name, owner, self.clock.now())
+            .await?
+        {
+            Claim::Held => Ok(None),
+            Claim::Busy {
+                holder,
+                retry_after,
+            } => {
+                debug!(
+                    object = ?meta.name,
+                    holder = ?holder,
+                    "Skipping reconciliation - object lease held by another replica"
+                );
+                Ok(Some(retry_after))
+            }
+        }
+    }
+
+    /// Claim a cluster-wide task's lease before running it (per-object leases)
+    ///
+    /// Like [`Context::claim_object`], for work that spans objects; see
+    /// [`ObjectLeases::claim_task`].
+    pub async fn claim_task(&self, task: &str) -> Result<Option<Duration>, kube::Error> {
+        let Some(leases) = &self.object_leases else {
+            return Ok(None);
+        };
+        match leases.claim_task(task, self.clock.now()).await? {
+            Claim::Held => Ok(None),
+            Claim::Busy {
+                holder,
+                retry_after,
+            } => {
+                debug!(
+                    task = task,
+                    holder = ?holder,
+                    "Waiting for task lease held by another replica"
+                );
+                Ok(Some(retry_after))
+            }
+        }
+    }
+
     #[cfg(test)]
     #[allow(clippy::unwrap_used)] // Test helper - panicking is acceptable
     pub fn new_mock() -> Self {
@@ -139,10 +401,34 @@ impl Context {
             client,
             cdevents_sink: Arc::new(crate::controller::cdevents::MockEventSink::new()),
             prometheus_client: Arc::new(crate::controller::prometheus::MockPrometheusClient::new()),
+            prometheus_clients: Arc::new(PrometheusClients::new(
+                Vec::new(),
+                crate::controller::prometheus::ReplicaPolicy::Fallback,
+            )),
             advisor: Arc::new(NoOpAdvisor),
             advisor_cache: AdvisorCache::new(),
+            alertmanager: Arc::new(crate::controller::alertmanager::MockAlertmanagerClient::new()),
+            promotion_policy: PromotionPolicy::default(),
+            rollout_defaults: RolloutDefaults::default(),
+            advisor_guardrails: GuardrailPolicy::default(),
+            pod_policy: PodTemplatePolicy::default(),
+            audit_log: Arc::new(AuditLog::disabled()),
+            freeze_calendar: FreezeCalendar::disabled(),
+            concurrency_limit: ConcurrencyLimit::default(),
+            release_hooks: ReleaseHooks::disabled(),
+            heartbeats: Heartbeats::disabled(),
+            notifications: Notifications::disabled(),
+            steps_resolver: StepsResolver::new(),
+            service_cache: ServiceCache::new(),
+            production_namespaces: ProductionNamespaces::default(),
+            occurrence_extensions: OccurrenceExtensions::new(),
+            occurrence_sinks: OccurrenceSinks::disabled(),
+            traffic_shifts: Arc::new(TrafficShifts::new(None)),
+            rollout_queue: Arc::new(WorkQueue::new(ROLLOUT_CONTROLLER, Utc::now())),
+            cluster_rollout_queue: Arc::new(WorkQueue::new(CLUSTER_ROLLOUT_CONTROLLER, Utc::now())),
             clock: Arc::new(crate::controller::clock::SystemClock),
             leader_state: None,
+            object_leases: None,
             metrics: None,
         }
     }
@@ -159,10 +445,31 @@ impl Context {
             client: mock.client,
             cdevents_sink: mock.cdevents_sink,
             prometheus_client: mock.prometheus_client,
+            prometheus_clients: mock.prometheus_clients,
             advisor: mock.advisor,
             advisor_cache: AdvisorCache::new(),
+            alertmanager: mock.alertmanager,
+            promotion_policy: mock.promotion_policy,
+            rollout_defaults: mock.rollout_defaults,
+            advisor_guardrails: mock.advisor_guardrails,
+            pod_policy: mock.pod_policy,
+            audit_log: mock.audit_log,
+            freeze_calendar: mock.freeze_calendar,
+            concurrency_limit: mock.concurrency_limit,
+            release_hooks: mock.release_hooks,
+            heartbeats: mock.heartbeats,
+            notifications: mock.notifications,
+            steps_resolver: mock.steps_resolver,
+            service_cache: mock.service_cache,
+            production_namespaces: mock.production_namespaces,
+            occurrence_extensions: mock.occurrence_extensions,
+            occurrence_sinks: mock.occurrence_sinks,
+            traffic_shifts: mock.traffic_shifts,
+            rollout_queue: mock.rollout_queue,
+            cluster_rollout_queue: mock.cluster_rollout_queue,
             clock: mock.clock,
             leader_state: Some(leader_state),
+            object_leases: None,
             metrics: None,
         }
     }
@@ -186,6 +493,55 @@ impl Context {
 /// * `Ok(Action)` - Requeue action with interval based on rollout state
 /// * `Err(ReconcileError)` - Reconciliation error
 pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Action, ReconcileError> {
+    // A user-set ClusterRollout marker would redirect status writes to a
+    // cluster-scoped object, so it is only honoured on stand-ins built below
+    let rollout = if source_cluster_rollout(&rollout).is_some() {
+        let mut rollout = (*rollout).clone();
+        if let Some(annotations) = rollout.metadata.annotations.as_mut() {
+            annotations.remove(CLUSTER_ROLLOUT_ANNOTATION);
+        }
+        Arc::new(rollout)
+    } else {
+        rollout
+    };
+
+    // Counts as running (for the work queue metrics) until it returns
+    let _in_flight = begin_reconcile(
+        &ctx.rollout_queue,
+        rollout.meta(),
+        ctx.clock.now(),
+        ctx.metrics.as_ref(),
+    );
+
+    // Every write below is attributed to this reconcile in the audit log
+    let audit_log = ctx.audit_log.clone();
+    audit::with_reconcile_scope(audit_log, reconcile_rollout(rollout, ctx)).await
+}
+
+/// Reconcile a ClusterRollout resource
+///
+/// Reconciles the ClusterRollout as a Rollout of the same name in its
+/// `targetNamespace`; status (and promote annotation removal) is written back
+/// to the ClusterRollout.
+pub async fn reconcile_cluster_rollout(
+    cluster_rollout: Arc<ClusterRollout>,
+    ctx: Arc<Context>,
+) -> Result<Action, ReconcileError> {
+    let _in_flight = begin_reconcile(
+        &ctx.cluster_rollout_queue,
+        cluster_rollout.meta(),
+        ctx.clock.now(),
+        ctx.metrics.as_ref(),
+    );
+    let rollout = Arc::new(cluster_rollout.to_rollout());
+    let audit_log = ctx.audit_log.clone();
+    audit::with_reconcile_scope(audit_log, reconcile_rollout(rollout, ctx)).await
+}
+
+async fn reconcile_rollout(
+    rollout: Arc<Rollout>,
+    ctx: Arc<Context>,
+) -> Result<Action, ReconcileError> {
     // Check if we should reconcile (leader election)
     if !ctx.should_reconcile() {
         // Not the leader - skip reconciliation, requeue later to check again
@@ -193,11 +549,32 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
 
         // Record skipped metric
         if let Some(ref metrics) = ctx.metrics {
-            metrics.record_reconciliation_skipped();
+            let strategy = crate::controller::strategies::select_strategy(&rollout);
+            metrics.record_reconciliation_skipped(
+                rollout.namespace().as_deref().unwrap_or_default(),
+                strategy.name(),
+            );
         }
 
         return Ok(Action::requeue(Duration::from_secs(5)));
     }
+    let owner = rollout_owner_reference(&rollout);
+    if let Some(retry_after) = ctx.claim_object("Rollout", rollout.meta(), owner).await? {
+        if let Some(ref metrics) = ctx.metrics {
+            let strategy = crate::controller::strategies::select_strategy(&rollout);
+            metrics.record_reconciliation_skipped(
+                rollout.namespace().as_deref().unwrap_or_default(),
+                strategy.name(),
+            );
+        }
+        return Ok(Action::requeue(retry_after));
+    }
+
+    // A ClusterRollout and a Rollout of the same name in its targetNamespace
+    // would manage the same ReplicaSets: the newer one is left alone
+    if let Some(owner) = colliding_owner(&rollout, &ctx).await? {
+        return yield_to_colliding_owner(&rollout, &ctx, &owner).await;
+    }
 
     // Start timing for metrics
     let start_time = std::time::Instant::now();
@@ -214,6 +591,27 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
         "Reconciling Rollout"
     );
 
+    // Deleted Rollouts tear down their resources before the finalizer is released
+    if is_being_deleted(&rollout) {
+        if has_finalizer(&rollout) {
+            return finalize_rollout(&rollout, &ctx, &namespace).await;
+        }
+        return Ok(Action::await_change());
+    }
+    if !has_finalizer(&rollout) {
+        add_finalizer(&ctx, &rollout).await?;
+    }
+
+    // Fill fields the spec leaves unset from the controller defaults
+    let mut defaulted = (*rollout).clone();
+    let defaulted_fields = ctx.rollout_defaults.apply(&mut defaulted);
+    let rollout = if defaulted_fields.is_empty() {
+        rollout
+    } else {
+        debug!(rollout = ?name, fields = ?defaulted_fields, "Applied controller defaults");
+        Arc::new(defaulted)
+    };
+
     // Validate Rollout spec (runtime constraints beyond what the CRD schema enforces)
     if let Err(validation_error) = validate_rollout(&rollout) {
         error!(
@@ -224,29 +622,328 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
         return Err(ReconcileError::ValidationError(validation_error));
     }
 
+    // kulta.io/rollback-to-revision restores an earlier template into the spec
+    if let Some(request) = requested_rollback(&rollout) {
+        return apply_rollback_request(&rollout, &ctx, &namespace, request).await;
+    }
+
+    // kulta.io/migrate-strategy converts a completed canary to blue-green in place
+    if let Some(target) = requested_migration(&rollout) {
+        match plan_migration(&rollout, target) {
+            MigrationPlan::Migrate(blue_green) => {
+                return apply_migration(&rollout, &ctx, &blue_green).await;
+            }
+            MigrationPlan::Wait => {
+                debug!(rollout = ?name, "Strategy migration waits for the Rollout to complete");
+            }
+            MigrationPlan::Reject(reason) => {
+                warn!(rollout = ?name, reason = %reason, "Ignoring strategy migration request");
+                remove_request_annotation(
+                    &ctx,
+                    &rollout,
+                    MIGRATE_STRATEGY_ANNOTATION,
+                    "migration rejected",
+                )
+                .await;
+            }
+        }
+    }
+
+    // Adopt the pod template of the referenced Deployment (workloadRef)
+    let declared = rollout.clone();
+    let (rollout, workload_replicas) = match fetch_workload(&rollout, &ctx.client).await {
+        Ok(Some(workload)) => (
+            Arc::new(with_workload_template(&rollout, workload.template)),
+            Some(workload.replicas),
+        ),
+        Ok(None) => (rollout, None),
+        Err(e) => {
+            error!(rollout = ?name, error = %e, "Failed to read workload Deployment");
+            return Err(e.into());
+        }
+    };
+
+    // Enforce the pod template policy for restricted namespaces
+    if let Err(policy_error) =
+        check_pod_template(&rollout.spec.template, &namespace, &ctx.pod_policy)
+    {
+        error!(
+            rollout = ?name,
+            error = ?policy_error,
+            "Rollout rejected by pod template policy"
+        );
+        return Err(ReconcileError::ValidationError(policy_error));
+    }
+    let (rollout, stripped_fields) = match sanitize_rollout(&rollout, &ctx.pod_policy) {
+        Some(sanitized) => (
+            Arc::new(sanitized),
+            find_denied_fields(&rollout.spec.template),
+        ),
+        None => (rollout, Vec::new()),
+    };
+
+    // Import shared canary steps (stepsFrom) and validate them like inline steps
+    let rollout = match ctx.steps_resolver.resolve(&rollout, &ctx.client).await {
+        Ok(Some(resolved)) => {
+            if let Err(validation_error) = validate_rollout(&resolved) {
+                error!(
+                    rollout = ?name,
+                    error = ?validation_error,
+                    "Imported canary steps failed validation"
+                );
+                return Err(ReconcileError::ValidationError(validation_error));
+            }
+            Arc::new(resolved)
+        }
+        Ok(None) => rollout,
+        Err(e) => {
+            error!(rollout = ?name, error = %e, "Failed to import canary steps");
+            return Err(e.into());
+        }
+    };
+
+    // Run the remaining steps of an accepted advisor plan (advisor level Planned)
+    let unplanned = Arc::clone(&rollout);
+    let rollout = match with_advisor_plan(&rollout) {
+        Some(planned) => Arc::new(planned),
+        None => rollout,
+    };
+
     // Select strategy handler based on rollout spec
     let strategy = crate::controller::strategies::select_strategy(&rollout);
     info!(rollout = ?name, strategy = strategy.name(), "Selected deployment strategy");
 
+    // Reject configuration the strategy would silently ignore
+    if let Err(support_error) =
+        crate::controller::strategies::check_feature_support(&rollout, strategy.as_ref())
+    {
+        error!(
+            rollout = ?name,
+            error = ?support_error,
+            "Rollout uses a feature its strategy does not support"
+        );
+        return Err(ReconcileError::ValidationError(support_error));
+    }
+
+    // Traffic must not be routed to Services that don't exist
+    let rollout = match ctx
+        .service_cache
+        .missing_services(&rollout, &ctx.client)
+        .await
+    {
+        Ok(missing) if !missing.is_empty() => {
+            return hold_missing_services(&rollout, &ctx, &missing).await;
+        }
+        Ok(_) if has_missing_services_condition(rollout.status.as_ref()) => {
+            info!(rollout = ?name, "Referenced Services found, clearing Degraded condition");
+            let mut recovered = (*rollout).clone();
+            let conditions = match recovered.status.as_mut() {
+                Some(status) => {
+                    status.conditions.retain(|c| c.type_ != DEGRADED_CONDITION);
+                    status.conditions.clone()
+                }
+                None => Vec::new(),
+            };
+            merge_patch_rollout(
+                &ctx,
+                &rollout,
+                &serde_json::json!({ "status": { "conditions": conditions } }),
+                true,
+            )
+            .await?;
+            Arc::new(recovered)
+        }
+        Ok(_) => rollout,
+        Err(e) => {
+            warn!(error = %e, rollout = ?name, "Failed to look up referenced Services (non-fatal)");
+            rollout
+        }
+    };
+
+    // Risky but valid specs are reported in a Warnings condition, not rejected
+    let lint_options = LintOptions {
+        production: ctx
+            .production_namespaces
+            .matches(rollout.namespace().as_deref().unwrap_or_default()),
+    };
+    let warnings = lint_rollout(&rollout, &lint_options);
+    let now = ctx.clock.now().to_rfc3339();
+    let rollout = match warnings_conditions(rollout.status.as_ref(), &warnings, &now) {
+        Some(conditions) => {
+            if !warnings.is_empty() {
+                info!(rollout = ?name, warnings = warnings.len(), "Rollout spec has lint warnings");
+            }
+            report_conditions(&ctx, rollout, conditions, "lint warnings").await
+        }
+        None => rollout,
+    };
+
+    // A promotion applied right before a restart must not be applied twice
+    let stale_promotion = is_stale_promotion(&rollout);
+    let rollout = if stale_promotion {
+        warn!(rollout = ?name, "kulta.io/promote was already applied, removing it");
+        remove_request_annotation(
+            &ctx,
+            &rollout,
+            "kulta.io/promote",
+            "promotion already applied",
+        )
+        .await;
+        let mut unpromoted = (*rollout).clone();
+        if let Some(annotations) = unpromoted.metadata.annotations.as_mut() {
+            annotations.remove("kulta.io/promote");
+        }
+        Arc::new(unpromoted)
+    } else {
+        rollout
+    };
+
+    // Re-derive step state a previous controller left inconsistent, so a
+    // timed pause can't hang and the canary is sized for its step
+    let rollout = match repair_in_flight_status(&rollout, ctx.clock.now()) {
+        Some(repaired) => {
+            warn!(
+                rollout = ?name,
+                current_step = ?repaired.current_step_index,
+                "Repairing in-flight status"
+            );
+            patch_rollout_status(&ctx, &rollout, &repaired).await?;
+            audit_status_patch(&rollout, &repaired, "in-flight status repaired").await;
+            let mut resumed = (*rollout).clone();
+            resumed.status = Some(repaired);
+            Arc::new(resumed)
+        }
+        None => rollout,
+    };
+
+    // Detect a new revision before ReplicaSets are touched (baseline still reflects it)
+    let template_digest = digest_pod_template(&rollout.spec.template)?;
+    if let Some(warning) = pod_template_size_warning(template_digest.size) {
+        warn!(rollout = ?name, size_bytes = template_digest.size, "{}", warning);
+    }
+    let pod_template_hash = template_digest.hash;
+
+    // A template changed mid-rollout replaces the in-flight revision or waits
+    // for it to finish (spec.onTemplateChange)
+    let (rollout, pod_template_hash, queued_hash) =
+        resolve_template_change(rollout, &ctx, pod_template_hash).await;
+    let rollout = match queued_revision_conditions(
+        rollout.status.as_ref(),
+        queued_hash.as_deref(),
+        &pod_template_hash,
+        &now,
+    ) {
+        Some(conditions) => report_conditions(&ctx, rollout, conditions, "queued revision").await,
+        None => rollout,
+    };
+
+    let template_diff = detect_template_change(&rollout, &ctx.client, &pod_template_hash).await;
+
+    // Deployment freeze: new Rollouts and new revisions wait until it ends
+    let freeze = ctx
+        .freeze_calendar
+        .active_freeze(&rollout, &ctx.client, ctx.clock.now())
+        .await;
+    if let Some(window) = &freeze {
+        if starts_new_revision(&rollout, &pod_template_hash) {
+            return hold_frozen_start(&rollout, &ctx, &namespace, window).await;
+        }
+    }
+
+    // Concurrency limit: new revisions queue in Pending while the namespace is full
+    // (simple rollouts complete at once and never hold a slot)
+    if let Some(max_active) = ctx.concurrency_limit.max_active {
+        if strategy.name() != "simple" && starts_new_revision(&rollout, &pod_template_hash) {
+            // With per-object leases, one replica at a time decides on starts
+            if let Some(retry_after) = ctx.claim_task(CONCURRENCY_TASK).await? {
+                return Ok(Action::requeue(retry_after));
+            }
+            let rollouts: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
+            let others = rollouts.list(&ListParams::default()).await?.items;
+            if let Some(position) = queue_position(max_active, &rollout, &others) {
+                return hold_pending_start(&rollout, &ctx, max_active, position).await;
+            }
+        }
+    }
+
     // Reconcile ReplicaSets using strategy-specific logic
-    strategy.reconcile_replicasets(&rollout, &ctx).await?;
+    let replica_sets = strategy.reconcile_replicasets(&rollout, &ctx).await?;
+    if let Err(e) = cleanup_replicasets(
+        &rollout,
+        &ctx,
+        &namespace,
+        &pod_template_hash,
+        &replica_sets,
+    )
+    .await
+    {
+        warn!(error = ?e, rollout = ?name, "Failed to clean up stale ReplicaSets (non-fatal)");
+    }
+    if let Err(e) = cleanup_auxiliary_objects(&rollout, &ctx, &namespace).await {
+        warn!(error = ?e, rollout = ?name, "Failed to clean up auxiliary objects (non-fatal)");
+    }
 
     // Reconcile traffic routing using strategy-specific logic
-    strategy.reconcile_traffic(&rollout, &ctx).await?;
+    if let Err(e) = strategy.reconcile_traffic(&rollout, &ctx).await {
+        if let StrategyError::HttpRoutesOutOfSync { routes } = &e {
+            record_httproute_failures(&rollout, &ctx, routes).await;
+        }
+        return Err(e.into());
+    }
 
     // Evaluate metrics and trigger rollback if unhealthy (only for strategies that support it)
+    let mut step_analysis = None;
+    let mut step_experiment = None;
+    let mut metric_failures = None;
+    let mut accepted_plan = None;
+    let mut driven = None;
     if strategy.supports_metrics_analysis() {
         if let Some(current_status) = &rollout.status {
             if current_status.phase == Some(Phase::Progressing) {
-                let is_healthy = evaluate_rollout_metrics(&rollout, &ctx).await?;
+                let verdict = evaluate_rollout_analysis(&rollout, &ctx).await?;
+                let mut is_healthy = verdict.is_healthy;
+                metric_failures = verdict.metric_failures;
+                // The current step's inline analysis, measured while the step is active
+                if is_healthy {
+                    match measure_step_analysis(&rollout, &ctx).await? {
+                        StepMeasurement::Passed(progress) => step_analysis = Some(progress),
+                        StepMeasurement::Failed => is_healthy = false,
+                        StepMeasurement::NotDue => {}
+                    }
+                }
+                // The current step's experiment, launched when the step starts
+                if is_healthy {
+                    match run_step_experiment(&rollout, &ctx).await? {
+                        StepExperimentOutcome::Observed(progress) => {
+                            step_experiment = Some(progress)
+                        }
+                        StepExperimentOutcome::Failed => is_healthy = false,
+                        StepExperimentOutcome::NotAtStep => {}
+                    }
+                }
 
-                // Consult advisor at Level 2+ (advisory only — threshold still decides)
+                // Consult advisor at Level 2+ (threshold still decides; at Level 3
+                // the advisor may also replan the remaining steps, and at Level 4
+                // confident recommendations drive the rollout)
                 // Skip if endpoint is not configured to avoid misleading no-op events
+                let advisor_level = ctx
+                    .advisor_guardrails
+                    .effective_level(&rollout.spec.advisor.level, &namespace);
+                if advisor_level != rollout.spec.advisor.level {
+                    debug!(
+                        rollout = ?name,
+                        level = ?advisor_level,
+                        "Advisor level lowered by guardrail policy"
+                    );
+                }
                 if matches!(
-                    rollout.spec.advisor.level,
+                    advisor_level,
                     AdvisorLevel::Advised | AdvisorLevel::Planned | AdvisorLevel::Driven
                 ) && rollout.spec.advisor.endpoint.is_some()
                 {
+                    let plans =
+                        matches!(advisor_level, AdvisorLevel::Planned | AdvisorLevel::Driven)
+                            && rollout.spec.strategy.canary.is_some();
                     let analysis_ctx = AnalysisContext {
                         rollout_name: name.clone(),
                         namespace: namespace.clone(),
@@ -264,28 +961,93 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                             .iter()
                             .map(|d| format!("{}: {:?}", d.timestamp, d.action))
                             .collect(),
+                        steps: if plans {
+                            canary_steps(&rollout.spec).to_vec()
+                        } else {
+                            Vec::new()
+                        },
                     };
 
                     let advisor =
                         resolve_advisor(&rollout.spec.advisor, &ctx.advisor, &ctx.advisor_cache);
                     match advisor.advise(&analysis_ctx).await {
                         Ok(recommendation) => {
-                            info!(
-                                rollout = ?name,
-                                advisor_action = ?recommendation.action,
-                                confidence = recommendation.confidence,
-                                reasoning = %recommendation.reasoning,
-                                threshold_healthy = is_healthy,
-                                "Advisor recommendation received (threshold decision prevails)"
-                            );
-                            // Emit advisor recommendation occurrence
-                            crate::controller::occurrence::emit_advisor_occurrence(
+                            // Reported whatever the guardrails allow: they only
+                            // decide whether the recommendation is acted on
+                            crate::controller::occurrence::report_advisor_recommendation(
                                 &rollout,
                                 strategy.name(),
                                 &recommendation,
                                 is_healthy,
                                 &ctx.clock,
+                                &ctx.occurrence_extensions,
+                                &ctx.occurrence_sinks,
                             );
+                            if let Err(violation) = ctx.advisor_guardrails.check(
+                                &recommendation,
+                                current_status.current_weight.unwrap_or(0),
+                                is_healthy,
+                            ) {
+                                warn!(
+                                    rollout = ?name,
+                                    advisor_action = ?recommendation.action,
+                                    violation = %violation,
+                                    "Advisor recommendation rejected by guardrail policy"
+                                );
+                            } else {
+                                let drives = advisor_level == AdvisorLevel::Driven
+                                    && match drives_rollout(
+                                        &rollout,
+                                        &recommendation,
+                                        &ctx.advisor_guardrails,
+                                        is_healthy,
+                                    ) {
+                                        Ok(drives) => drives,
+                                        Err(violation) => {
+                                            warn!(
+                                                rollout = ?name,
+                                                advisor_action = ?recommendation.action,
+                                                violation = %violation,
+                                                "Advisor may not drive the rollout: guardrail policy"
+                                            );
+                                            false
+                                        }
+                                    };
+                                info!(
+                                    rollout = ?name,
+                                    advisor_action = ?recommendation.action,
+                                    confidence = recommendation.confidence,
+                                    reasoning = %recommendation.reasoning,
+                                    threshold_healthy = is_healthy,
+                                    advisor_drives = drives,
+                                    "Advisor recommendation received"
+                                );
+                                if drives {
+                                    driven = Some(recommendation.clone());
+                                }
+                                if let Some(proposed) =
+                                    recommendation.plan.as_deref().filter(|_| plans)
+                                {
+                                    match propose_plan(
+                                        &rollout,
+                                        canary_steps(&unplanned.spec),
+                                        proposed,
+                                        &ctx.advisor_guardrails,
+                                        is_healthy,
+                                        ctx.clock.now(),
+                                    ) {
+                                        Ok(plan) => {
+                                            accepted_plan =
+                                                Some((plan, recommendation.reasoning.clone()))
+                                        }
+                                        Err(violation) => warn!(
+                                            rollout = ?name,
+                                            violation = %violation,
+                                            "Advisor plan rejected by guardrail policy"
+                                        ),
+                                    }
+                                }
+                            }
                         }
                         Err(e) => {
                             warn!(
@@ -297,17 +1059,56 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                     }
                 }
 
-                if !is_healthy {
-                    warn!(rollout = ?name, "Metrics unhealthy, triggering rollback");
-
-                    let failed_status = RolloutStatus {
-                        phase: Some(Phase::Failed),
-                        message: Some(
-                            "Rollback triggered: metrics exceeded thresholds".to_string(),
-                        ),
-                        ..current_status.clone()
+                // A Driven-level advisor may also roll back a canary whose metrics pass
+                let advisor_rollback = driven
+                    .as_ref()
+                    .filter(|recommendation| recommendation.action == RecommendedAction::Rollback)
+                    .filter(|_| is_healthy);
+                if !is_healthy || advisor_rollback.is_some() {
+                    let (reason, message, cause) = match advisor_rollback {
+                        Some(recommendation) => {
+                            warn!(
+                                rollout = ?name,
+                                reasoning = %recommendation.reasoning,
+                                "Advisor triggering rollback"
+                            );
+                            (
+                                DecisionReason::AdvisorRecommendation,
+                                format!(
+                                    "Rollback triggered by advisor ({:.0}% confident): {}",
+                                    recommendation.confidence * 100.0,
+                                    recommendation.reasoning
+                                ),
+                                "advisor recommended rollback",
+                            )
+                        }
+                        None => {
+                            warn!(rollout = ?name, "Metrics unhealthy, triggering rollback");
+                            (
+                                DecisionReason::AnalysisFailed,
+                                "Rollback triggered: metrics exceeded thresholds".to_string(),
+                                "metrics exceeded thresholds",
+                            )
+                        }
                     };
 
+                    let failed_status = roll_back_to_stable(
+                        &rollout,
+                        RolloutStatus {
+                            open_incident: Some(incident_subject_id(&name, &pod_template_hash)),
+                            metric_failures: metric_failures.map_or_else(
+                                || current_status.metric_failures.clone(),
+                                |f| Some(f).filter(|f| !f.is_empty()),
+                            ),
+                            last_decision_source: advisor_rollback
+                                .map(|_| ADVISOR_DECISION_SOURCE.to_string()),
+                            ..current_status.clone()
+                        },
+                        reason,
+                        message,
+                        ctx.clock.now(),
+                    );
+
                     // Emit rollback CDEvent (non-fatal)
                     if let Err(e) = emit_status_change_event(
                         &rollout,
@@ -327,21 +1128,16 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                         &Phase::Failed,
                         strategy.name(),
                         &ctx.clock,
+                        &ctx.occurrence_extensions,
+                        &ctx.occurrence_sinks,
                     );
 
                     // Patch status to Failed
-                    let rollout_api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
-                    rollout_api
-                        .patch_status(
-                            &name,
-                            &PatchParams::default(),
-                            &Patch::Merge(&serde_json::json!({
-                                "status": failed_status
-                            })),
-                        )
-                        .await?;
-
-                    info!(rollout = ?name, "Rollout marked as Failed due to unhealthy metrics");
+                    patch_rollout_status(&ctx, &rollout, &failed_status).await?;
+                    audit_status_patch(&rollout, &failed_status, cause).await;
+                    roll_back_resources(&rollout, &failed_status, strategy.as_ref(), &ctx).await;
+
+                    info!(rollout = ?name, cause, "Rollout marked as Failed");
                     return Ok(Action::requeue(Duration::from_secs(30)));
                 }
             }
@@ -402,19 +1198,14 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                         &Phase::Concluded,
                         strategy.name(),
                         &ctx.clock,
+                        &ctx.occurrence_extensions,
+                        &ctx.occurrence_sinks,
                     );
 
                     // Patch status to Concluded
-                    let rollout_api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
-                    rollout_api
-                        .patch_status(
-                            &name,
-                            &PatchParams::default(),
-                            &Patch::Merge(&serde_json::json!({
-                                "status": concluded_status
-                            })),
-                        )
-                        .await?;
+                    patch_rollout_status(&ctx, &rollout, &concluded_status).await?;
+                    audit_status_patch(&rollout, &concluded_status, "A/B experiment concluded")
+                        .await;
 
                     info!(rollout = ?name, "A/B experiment marked as Concluded");
                     return Ok(Action::requeue(Duration::from_secs(30)));
@@ -424,7 +1215,12 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
     }
 
     // Check progress deadline (for Progressing or Preview phases with deadline configured)
-    if let Some(deadline_seconds) = rollout.spec.progress_deadline_seconds {
+    // A rollout held by a deployment freeze isn't stuck; the deadline restarts after it
+    if let Some(deadline_seconds) = rollout
+        .spec
+        .progress_deadline_seconds
+        .filter(|_| freeze.is_none())
+    {
         if let Some(current_status) = &rollout.status {
             if (current_status.phase == Some(Phase::Progressing)
                 || current_status.phase == Some(Phase::Preview))
@@ -436,14 +1232,16 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                     "Progress deadline exceeded, marking rollout as Failed"
                 );
 
-                let failed_status = RolloutStatus {
-                    phase: Some(Phase::Failed),
-                    message: Some(format!(
+                let failed_status = roll_back_to_stable(
+                    &rollout,
+                    current_status.clone(),
+                    DecisionReason::Timeout,
+                    format!(
                         "Progress deadline exceeded: no progress made in {} seconds",
                         deadline_seconds
-                    )),
-                    ..current_status.clone()
-                };
+                    ),
+                    ctx.clock.now(),
+                );
 
                 // Emit rollback CDEvent (non-fatal)
                 if let Err(e) = emit_status_change_event(
@@ -465,19 +1263,14 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                     &Phase::Failed,
                     strategy.name(),
                     &ctx.clock,
+                    &ctx.occurrence_extensions,
+                    &ctx.occurrence_sinks,
                 );
 
                 // Patch status to Failed
-                let rollout_api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
-                rollout_api
-                    .patch_status(
-                        &name,
-                        &PatchParams::default(),
-                        &Patch::Merge(&serde_json::json!({
-                            "status": failed_status
-                        })),
-                    )
-                    .await?;
+                patch_rollout_status(&ctx, &rollout, &failed_status).await?;
+                audit_status_patch(&rollout, &failed_status, "progress deadline exceeded").await;
+                roll_back_resources(&rollout, &failed_status, strategy.as_ref(), &ctx).await;
 
                 info!(
                     rollout = ?name,
@@ -487,7 +1280,7 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
                 // Record metrics for the failure
                 if let Some(ref metrics) = ctx.metrics {
                     let duration_secs = start_time.elapsed().as_secs_f64();
-                    metrics.record_reconciliation_error(&name, duration_secs);
+                    metrics.record_reconciliation_error(&namespace, strategy.name(), duration_secs);
                 }
 
                 return Ok(Action::requeue(Duration::from_secs(30)));
@@ -497,19 +1290,169 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
 
     // Check for promote annotation before computing status (avoid race condition)
     let had_promote_annotation = has_promote_annotation(&rollout);
-    let was_paused_before = rollout
-        .status
-        .as_ref()
-        .map(|s| s.phase == Some(Phase::Paused))
-        .unwrap_or(false);
+    let approval_pending = is_awaiting_approval(&rollout) && approved_by(&rollout).is_some();
 
     // Compute desired status using strategy-specific logic
-    let desired_status = strategy.compute_next_status(&rollout, ctx.clock.now());
+    let mut desired_status = strategy.compute_next_status(&rollout, ctx.clock.now());
+
+    // A Driven-level advisor may advance or hold the step (people override it)
+    if !had_promote_annotation && !approval_pending {
+        desired_status =
+            apply_advisor_drive(&rollout, desired_status, driven.as_ref(), ctx.clock.now());
+    }
+
+    // Hold alert-gated steps while matching alerts are firing (manual promotion overrides)
+    if !had_promote_annotation {
+        if let Some(held_status) = hold_for_alert_gate(&rollout, &desired_status, &ctx).await {
+            desired_status = held_status;
+        }
+    }
+
+    // Dependency gates hold weight increases while a dependency is unhealthy
+    if !had_promote_annotation && raises_gated_weight(&rollout, &desired_status) {
+        let gates = rollout
+            .spec
+            .strategy
+            .canary
+            .as_ref()
+            .map(|canary| canary.gates.as_slice())
+            .unwrap_or_default();
+        let unhealthy = unhealthy_dependencies(gates, &ctx.client, &namespace).await;
+        if let Some(held_status) = hold_for_dependency_gates(&rollout, &unhealthy) {
+            desired_status = held_status;
+        }
+    }
+
+    // Namespace promotion policy can turn timed pauses into manual ones
+    let promotion_mode =
+        resolve_promotion_mode(&ctx.promotion_policy, &ctx.client, &namespace).await?;
+    if !had_promote_annotation {
+        if let Some(held_status) =
+            hold_for_promotion_policy(&rollout, &desired_status, promotion_mode)
+        {
+            desired_status = held_status;
+        }
+    }
+    // Deployment freeze holds rollouts already underway at their current step
+    desired_status = apply_freeze(&rollout, desired_status, freeze.as_ref(), ctx.clock.now());
+    // Degraded new revision (pods never ready, quota exhausted) can fail or hold the rollout
+    if !had_promote_annotation && rollout.spec.on_degraded.is_some() {
+        let degradation = new_revision_degradation(&rollout, &ctx, &namespace).await;
+        desired_status = apply_degraded_policy(
+            &rollout,
+            desired_status,
+            degradation.as_deref(),
+            ctx.clock.now(),
+        );
+    }
+    // kulta.io/abort and kulta.io/retry override every hold above
+    let (user_status, applied_request) =
+        apply_abort_or_retry(&rollout, desired_status, ctx.clock.now());
+    desired_status = user_status;
+    // kulta.io/set-weight replaces the step's weight until the rollout leaves the step
+    let (overridden_status, weight_request) = apply_weight_override(
+        &rollout,
+        desired_status,
+        &ctx.advisor_guardrails,
+        ctx.clock.now(),
+    );
+    desired_status = overridden_status;
+    // Record the step analysis measurement unless the rollout left the step
+    if let Some(progress) = step_analysis {
+        if desired_status.current_step_index == Some(progress.step_index)
+            && desired_status.phase == Some(Phase::Progressing)
+        {
+            desired_status.step_analysis = Some(progress);
+        }
+    }
+    // Likewise the step experiment's phase
+    if let Some(progress) = step_experiment {
+        if desired_status.current_step_index == Some(progress.step_index)
+            && desired_status.phase == Some(Phase::Progressing)
+        {
+            desired_status.step_experiment = Some(progress);
+        }
+    }
+    // Record consecutive metric failures while the rollout keeps progressing,
+    // unless resuming from a pause restarted the analysis
+    let analysis_restarted = resets_analysis_on_resume(&rollout)
+        && desired_status.current_step_index
+            != rollout.status.as_ref().and_then(|s| s.current_step_index);
+    if let Some(failures) = metric_failures.filter(|_| !analysis_restarted) {
+        if desired_status.phase == Some(Phase::Progressing) {
+            desired_status.metric_failures = Some(failures).filter(|f| !f.is_empty());
+        }
+    }
+    // A completed rollout resolves the incident opened by an earlier analysis breach
+    if desired_status.phase == Some(Phase::Completed) {
+        desired_status.open_incident = None;
+        desired_status.metric_failures = None;
+    }
+    // Once a rolled-back canary's verification is due, measure the stable revision
+    let rollback_verified = verify_rollback(&rollout, &desired_status, &ctx).await;
+    if let Some(verification) = &rollback_verified {
+        desired_status.rollback_verification = Some(verification.clone());
+    }
+    desired_status.http_routes = synced_httproute_statuses(&rollout);
+    desired_status.resources = Some(ManagedResources {
+        replica_sets,
+        http_routes: managed_httproute_names(&rollout),
+    });
+    desired_status.strategy = Some(StrategyKind::from_rollout(&rollout));
+    desired_status.observed_generation = rollout.metadata.generation;
+    desired_status.effective_config = Some(effective_config(
+        &declared,
+        &rollout,
+        AppliedPolicies {
+            stripped_fields,
+            defaulted_fields,
+            promotion_mode,
+            advisor_level: ctx
+                .advisor_guardrails
+                .effective_level(&rollout.spec.advisor.level, &namespace),
+        },
+    ));
+    // Scale subresource: the replica count and the pods an HPA measures
+    desired_status.replicas = rollout.spec.replicas;
+    desired_status.selector = selector_string(&rollout.spec.selector);
+    record_revision(
+        &mut desired_status,
+        rollout.status.as_ref(),
+        pod_template_hash,
+        template_diff,
+    );
+    // An advisor plan holds for its revision while the rollout is in flight
+    desired_status = apply_advisor_plan(&rollout, desired_status, accepted_plan, ctx.clock.now());
+    desired_status.decisions = compact_decisions(desired_status.decisions);
+    if let (Some(hash), Some(revision)) =
+        (&desired_status.pod_template_hash, desired_status.revision)
+    {
+        if let Err(e) = record_history(&rollout, &ctx, &namespace, hash, revision).await {
+            warn!(error = ?e, rollout = ?name, "Failed to record revision history (non-fatal)");
+        }
+    }
 
-    // Determine if we progressed due to the annotation
+    // Any advance while kulta.io/promote is set consumes it (an untilApproved
+    // pause is moved on by its approval instead)
     let progressed_due_to_annotation = had_promote_annotation
-        && was_paused_before
-        && rollout.status.as_ref() != Some(&desired_status);
+        && !approval_pending
+        && rollout
+            .status
+            .as_ref()
+            .is_some_and(|current| is_advancing(current, &desired_status));
+    // Remember the promotion until its annotation is gone (see is_stale_promotion)
+    if progressed_due_to_annotation {
+        desired_status.promoted_at = Some(ctx.clock.now().to_rfc3339());
+        desired_status.promoted_version = rollout.resource_version();
+    } else if !had_promote_annotation && !stale_promotion {
+        desired_status.promoted_at = None;
+        desired_status.promoted_version = None;
+    }
+    // An untilApproved pause consumes its approval when the rollout moves on
+    let approval_applied = approval_pending
+        && rollout.status.as_ref().and_then(|s| s.current_step_index)
+            != desired_status.current_step_index;
+    desired_status.summary = Some(status_summary(&rollout, &desired_status));
 
     // Update Rollout status if it changed
     if rollout.status.as_ref() != Some(&desired_status) {
@@ -536,55 +1479,105 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
         // Emit FALSE Protocol occurrence (non-fatal)
         let old_phase = rollout.status.as_ref().and_then(|s| s.phase.as_ref());
         if let Some(new_phase) = &desired_status.phase {
-            emit_occurrence(&rollout, old_phase, new_phase, strategy.name(), &ctx.clock);
+            emit_occurrence(
+                &rollout,
+                old_phase,
+                new_phase,
+                strategy.name(),
+                &ctx.clock,
+                &ctx.occurrence_extensions,
+                &ctx.occurrence_sinks,
+            );
         }
 
-        // Patch status subresource
-        let rollout_api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
-
-        match rollout_api
-            .patch_status(
-                &name,
-                &PatchParams::default(),
-                &Patch::Merge(&serde_json::json!({
-                    "status": desired_status
-                })),
+        // Patch status subresource (a promotion only if the Rollout is unchanged)
+        let patched = if progressed_due_to_annotation {
+            merge_patch_rollout(
+                &ctx,
+                &rollout,
+                &promotion_status_patch(&desired_status),
+                true,
             )
             .await
-        {
+        } else {
+            patch_rollout_status(&ctx, &rollout, &desired_status).await
+        };
+        match patched {
             Ok(_) => {
                 info!(rollout = ?name, "Status updated successfully");
+                audit_status_patch(
+                    &rollout,
+                    &desired_status,
+                    desired_status.message.as_deref().unwrap_or("status update"),
+                )
+                .await;
 
                 // Remove promote annotation if it was used for progression
                 if progressed_due_to_annotation {
-                    info!(
-                        rollout = ?name,
-                        "Removing promote annotation after successful promotion"
+                    remove_request_annotation(
+                        &ctx,
+                        &rollout,
+                        "kulta.io/promote",
+                        "promotion applied",
+                    )
+                    .await;
+                }
+                for annotation in applied_request.into_iter().chain(weight_request) {
+                    remove_request_annotation(&ctx, &rollout, annotation, "request applied").await;
+                }
+                // A rollback decided above (degraded new revision, kulta.io/abort)
+                // scales the canary down now rather than on the next reconcile
+                let rolled_back = desired_status.aborted_at.is_some()
+                    && rollout
+                        .status
+                        .as_ref()
+                        .map_or(true, |s| s.aborted_at.is_none());
+                if rolled_back {
+                    roll_back_resources(&rollout, &desired_status, strategy.as_ref(), &ctx).await;
+                }
+                if approval_applied {
+                    remove_request_annotation(
+                        &ctx,
+                        &rollout,
+                        APPROVED_BY_ANNOTATION,
+                        "approval applied",
+                    )
+                    .await;
+                }
+                let was_completed = rollout
+                    .status
+                    .as_ref()
+                    .is_some_and(|s| s.phase == Some(Phase::Completed));
+                if desired_status.phase == Some(Phase::Completed) && !was_completed {
+                    let record = release_record(&rollout, &desired_status, ctx.clock.now());
+                    ctx.release_hooks.notify(&record).await;
+                }
+                if let Some(notification) = notification_for(
+                    &rollout,
+                    rollout.status.as_ref(),
+                    &desired_status,
+                    ctx.clock.now(),
+                ) {
+                    ctx.notifications
+                        .notify(&rollout, &ctx.client, &notification)
+                        .await;
+                }
+                if let Some(verification) = &rollback_verified {
+                    emit_rollback_verified_occurrence(
+                        &rollout,
+                        verification,
+                        strategy.name(),
+                        &ctx.clock,
+                        &ctx.occurrence_extensions,
+                        &ctx.occurrence_sinks,
                     );
-
-                    match rollout_api
-                        .patch(
-                            &name,
-                            &PatchParams::default(),
-                            &Patch::Merge(&serde_json::json!({
-                                "metadata": {
-                                    "annotations": {
-                                        "kulta.io/promote": serde_json::Value::Null
-                                    }
-                                }
-                            })),
-                        )
-                        .await
-                    {
-                        Ok(_) => {
-                            info!(rollout = ?name, "Promote annotation removed successfully")
-                        }
-                        Err(e) => {
-                            warn!(error = ?e, rollout = ?name, "Failed to remove promote annotation (non-fatal)")
-                        }
-                    }
                 }
             }
+            Err(kube::Error::Api(e)) if e.code == 409 && progressed_due_to_annotation => {
+                // Read from a stale cache, or promoted by another reconcile already
+                info!(rollout = ?name, "Rollout changed before kulta.io/promote was applied, re-reading it");
+                return Ok(Action::requeue(Duration::from_secs(1)));
+            }
             Err(e) => {
                 error!(error = ?e, rollout = ?name, "Failed to update status");
                 return Err(ReconcileError::KubeError(e));
@@ -592,24 +1585,936 @@ pub async fn reconcile(rollout: Arc<Rollout>, ctx: Arc<Context>) -> Result<Actio
         }
     }
 
+    // Hand over from the adopted Deployment once the Rollout has completed
+    if let (Some(workload_ref), Some(replicas)) = (&rollout.spec.workload_ref, workload_replicas) {
+        if should_scale_down(workload_ref, replicas, desired_status.phase.as_ref()) {
+            scale_down_workload(&ctx.client, &namespace, workload_ref).await?;
+        }
+    }
+
+    // Heartbeat while the phase stays the same (non-fatal)
+    let heartbeat_key = format!("{}/{}", namespace, name);
+    if ctx.heartbeats.due(
+        &heartbeat_key,
+        desired_status.phase.as_ref(),
+        ctx.clock.now(),
+    ) {
+        emit_heartbeat_occurrence(
+            &rollout,
+            &desired_status,
+            strategy.name(),
+            &ctx.clock,
+            &ctx.occurrence_extensions,
+            &ctx.occurrence_sinks,
+        );
+        if ctx.heartbeats.cdevents_enabled() {
+            if let Err(e) = emit_heartbeat_event(
+                &rollout,
+                &desired_status,
+                ctx.clock.now(),
+                ctx.cdevents_sink.as_ref(),
+            )
+            .await
+            {
+                warn!(error = ?e, rollout = ?name, "Failed to emit heartbeat CDEvent (non-fatal)");
+            }
+        }
+    }
+
     // Calculate requeue interval and return
-    let requeue_interval =
+    let mut requeue_interval =
         calculate_requeue_interval_from_rollout(&rollout, &desired_status, ctx.clock.now());
+    // Come back for the next step analysis measurement (at most every 5s)
+    if desired_status.phase == Some(Phase::Progressing) {
+        if let Some(delay) = time_to_next_measurement(&rollout, &desired_status, ctx.clock.now()) {
+            requeue_interval = requeue_interval.min(delay.max(Duration::from_secs(5)));
+        }
+        // and to see whether the step's experiment has finished
+        if desired_status
+            .step_experiment
+            .as_ref()
+            .is_some_and(|e| !e.phase.is_some_and(ExperimentPhase::is_finished))
+        {
+            requeue_interval = requeue_interval.min(STEP_EXPERIMENT_POLL_INTERVAL);
+        }
+    }
+    // Come back when the rollback of a failed canary is due for verification
+    if let Some(delay) = time_to_rollback_verification(&rollout, &desired_status, ctx.clock.now()) {
+        requeue_interval = requeue_interval.min(delay.max(Duration::from_secs(5)));
+    }
+    if let Some(interval) = ctx.heartbeats.interval() {
+        if is_long_running(desired_status.phase.as_ref()) {
+            requeue_interval = requeue_interval.min(interval);
+        }
+    }
 
     // Record success metrics
     if let Some(ref metrics) = ctx.metrics {
         let duration_secs = start_time.elapsed().as_secs_f64();
-        metrics.record_reconciliation_success(strategy.name(), duration_secs);
+        metrics.record_reconciliation_success(&namespace, strategy.name(), duration_secs);
 
         // Update traffic weight gauge
         if let Some(weight) = desired_status.current_weight {
             metrics.set_traffic_weight(&namespace, &name, weight as i64);
         }
+        metrics.record_rollout_activity(RolloutActivity::new(&rollout, &desired_status));
     }
 
     Ok(Action::requeue(requeue_interval))
 }
 
+/// Scale the canary down and route traffic to stable right after a rollback
+///
+/// Reconciles ReplicaSets and traffic against the rolled-back status instead
+/// of waiting for the next reconcile. Failures are logged; the next reconcile
+/// retries from the patched status.
+async fn roll_back_resources(
+    rollout: &Rollout,
+    failed_status: &RolloutStatus,
+    strategy: &dyn RolloutStrategy,
+    ctx: &Context,
+) {
+    let name = rollout.name_any();
+    let mut rolled_back = rollout.clone();
+    rolled_back.status = Some(failed_status.clone());
+
+    if let Err(e) = strategy.reconcile_replicasets(&rolled_back, ctx).await {
+        warn!(error = ?e, rollout = ?name, "Failed to scale down ReplicaSets after rollback");
+    }
+    if let Err(e) = strategy.reconcile_traffic(&rolled_back, ctx).await {
+        warn!(error = ?e, rollout = ?name, "Failed to route traffic to stable after rollback");
+    }
+    info!(rollout = ?name, "Rolled back to stable");
+}
+
+/// Add the KULTA finalizer so deleting the Rollout runs its teardown
+async fn add_finalizer(ctx: &Context, rollout: &Rollout) -> Result<(), kube::Error> {
+    info!(rollout = ?rollout.name_any(), "Adding finalizer");
+    merge_patch_rollout(
+        ctx,
+        rollout,
+        &finalizers_patch(rollout, finalizers_with(rollout)),
+        false,
+    )
+    .await?;
+    audit::record(
+        AuditOperation::Patch,
+        rollout_audit_ref(rollout),
+        "metadata.finalizers",
+        "add finalizer",
+    )
+    .await;
+    Ok(())
+}
+
+/// Tear down a deleted Rollout's resources, then release its finalizer
+///
+/// Traffic goes back to the stable Service before the ReplicaSets are
+/// deleted, so no request is routed to pods on their way out. Any failure
+/// keeps the finalizer and the teardown is retried on the next reconcile.
+async fn finalize_rollout(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<Action, ReconcileError> {
+    let name = rollout.name_any();
+    info!(rollout = ?name, "Rollout deleted, tearing down managed resources");
+
+    reset_traffic(rollout, ctx).await?;
+    teardown_auxiliary_objects(rollout, ctx, namespace).await?;
+    let deleted = delete_managed_replicasets(rollout, ctx, namespace).await?;
+    ctx.prometheus_clients
+        .remove(&format!("{}/{}", namespace, name));
+    if let Some(metrics) = &ctx.metrics {
+        metrics.forget_rollout_activity(namespace, &name);
+    }
+
+    // Emit terminal CDEvent (non-fatal)
+    let status = rollout.status.clone().unwrap_or_default();
+    if let Err(e) = emit_removed_event(rollout, &status, ctx.cdevents_sink.as_ref()).await {
+        warn!(error = ?e, rollout = ?name, "Failed to emit removal CDEvent (non-fatal)");
+    }
+
+    // Emit FALSE Protocol occurrence (non-fatal)
+    let strategy = crate::controller::strategies::select_strategy(rollout);
+    emit_removed_occurrence(
+        rollout,
+        strategy.name(),
+        &ctx.clock,
+        &ctx.occurrence_extensions,
+        &ctx.occurrence_sinks,
+    );
+
+    merge_patch_rollout(
+        ctx,
+        rollout,
+        &finalizers_patch(rollout, finalizers_without(rollout)),
+        false,
+    )
+    .await?;
+    audit::record(
+        AuditOperation::Patch,
+        rollout_audit_ref(rollout),
+        "metadata.finalizers",
+        "rollout deleted",
+    )
+    .await;
+    info!(rollout = ?name, replicasets = deleted, "Teardown complete, finalizer removed");
+    Ok(Action::await_change())
+}
+
+/// The same-named ClusterRollout or Rollout this Rollout yields to, if any
+///
+/// See [`yields_to`]. Returns a description like `Rollout ns/name`.
+async fn colliding_owner(rollout: &Rollout, ctx: &Context) -> Result<Option<String>, kube::Error> {
+    let name = rollout.name_any();
+    let namespace = rollout.namespace().unwrap_or_default();
+    if source_cluster_rollout(rollout).is_some() {
+        let api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
+        Ok(api
+            .get_opt(&name)
+            .await?
+            .filter(|other| yields_to(rollout, &other.metadata))
+            .map(|_| format!("Rollout {}/{}", namespace, name)))
+    } else {
+        let api: Api<ClusterRollout> = Api::all(ctx.client.clone());
+        Ok(api
+            .get_opt(&name)
+            .await?
+            .filter(|other| {
+                other.spec.target_namespace == namespace && yields_to(rollout, &other.metadata)
+            })
+            .map(|_| format!("ClusterRollout {}", name)))
+    }
+}
+
+/// Leave a Rollout's resources to the same-named object it collides with
+///
+/// Only the status message is written. A deleted Rollout releases its
+/// finalizer without tearing anything down: the ReplicaSets and routes
+/// belong to `owner`.
+async fn yield_to_colliding_owner(
+    rollout: &Rollout,
+    ctx: &Context,
+    owner: &str,
+) -> Result<Action, ReconcileError> {
+    if is_being_deleted(rollout) {
+        if has_finalizer(rollout) {
+            merge_patch_rollout(
+                ctx,
+                rollout,
+                &finalizers_patch(rollout, finalizers_without(rollout)),
+                false,
+            )
+            .await?;
+        }
+        return Ok(Action::await_change());
+    }
+
+    let message = format!(
+        "Not reconciled: {} of the same name manages these ReplicaSets",
+        owner
+    );
+    warn!(rollout = ?rollout.name_any(), owner = %owner, "Name collides with another rollout");
+    let current = rollout.status.as_ref().and_then(|s| s.message.as_deref());
+    if current != Some(message.as_str()) {
+        patch_rollout_status(ctx, rollout, &serde_json::json!({ "message": message })).await?;
+    }
+    Ok(Action::requeue(Duration::from_secs(60)))
+}
+
+/// Switch a completed canary Rollout to blue-green in place
+///
+/// The new strategy adopts the canary's ReplicaSets, so nothing is recreated;
+/// it takes over on the reconcile triggered by the spec change.
+async fn apply_migration(
+    rollout: &Rollout,
+    ctx: &Context,
+    blue_green: &BlueGreenStrategy,
+) -> Result<Action, ReconcileError> {
+    merge_patch_rollout(ctx, rollout, &migration_patch(blue_green), false).await?;
+    audit::record(
+        AuditOperation::Patch,
+        rollout_audit_ref(rollout),
+        "spec.strategy canary -> blue-green",
+        "strategy migration",
+    )
+    .await;
+    info!(
+        rollout = ?rollout.name_any(),
+        active_service = %blue_green.active_service,
+        preview_service = %blue_green.preview_service,
+        "Migrated Rollout from canary to blue-green"
+    );
+    Ok(Action::requeue(Duration::from_secs(5)))
+}
+
+/// Copy a previous revision's pod template back into `spec.template`
+///
+/// The annotation is removed either way; an unknown or invalid revision is
+/// logged and otherwise ignored. The restored template rolls out on the
+/// reconcile triggered by the spec change.
+async fn apply_rollback_request(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+    request: Result<i64, String>,
+) -> Result<Action, ReconcileError> {
+    let name = rollout.name_any();
+    let revision = match request {
+        Ok(revision) => revision,
+        Err(value) => {
+            warn!(rollout = ?name, value = %value, "Ignoring invalid rollback-to-revision annotation");
+            remove_request_annotation(
+                ctx,
+                rollout,
+                ROLLBACK_TO_REVISION_ANNOTATION,
+                "invalid revision",
+            )
+            .await;
+            return Ok(Action::requeue(Duration::from_secs(5)));
+        }
+    };
+
+    let history = list_history(rollout, ctx, namespace).await?;
+    match find_revision(&history, revision).and_then(rollback_template) {
+        Some(template) if template != rollout.spec.template => {
+            let current = serde_json::to_value(&rollout.spec.template)
+                .map_err(|e| ReconcileError::SerializationError(e.to_string()))?;
+            let target = serde_json::to_value(&template)
+                .map_err(|e| ReconcileError::SerializationError(e.to_string()))?;
+            let patch = serde_json::json!({
+                "spec": { "template": merge_patch_diff(&current, &target) }
+            });
+            merge_patch_rollout(ctx, rollout, &patch, false).await?;
+            audit::record(
+                AuditOperation::Patch,
+                rollout_audit_ref(rollout),
+                format!("spec.template from revision {}", revision),
+                "rollback to revision",
+            )
+            .await;
+            info!(rollout = ?name, revision = revision, "Rolled back pod template to revision");
+        }
+        Some(_) => {
+            info!(rollout = ?name, revision = revision, "Rollout already runs the requested revision");
+        }
+        None => {
+            warn!(rollout = ?name, revision = revision, "Rollback revision not found in history, ignoring");
+        }
+    }
+
+    remove_request_annotation(
+        ctx,
+        rollout,
+        ROLLBACK_TO_REVISION_ANNOTATION,
+        "rollback requested",
+    )
+    .await;
+    Ok(Action::requeue(Duration::from_secs(5)))
+}
+
+/// Remove an annotation the controller acted on, so it isn't applied twice
+///
+/// Failures are logged and otherwise ignored.
+async fn remove_request_annotation(
+    ctx: &Context,
+    rollout: &Rollout,
+    annotation: &str,
+    reason: &str,
+) {
+    let name = rollout.name_any();
+    info!(rollout = ?name, annotation = annotation, "Removing applied request annotation");
+
+    match merge_patch_rollout(
+        ctx,
+        rollout,
+        &serde_json::json!({
+            "metadata": {
+                "annotations": {
+                    annotation: serde_json::Value::Null
+                }
+            }
+        }),
+        false,
+    )
+    .await
+    {
+        Ok(_) => {
+            info!(rollout = ?name, annotation = annotation, "Annotation removed successfully");
+            audit::record(
+                AuditOperation::Patch,
+                rollout_audit_ref(rollout),
+                format!("removed annotation {}", annotation),
+                reason,
+            )
+            .await;
+        }
+        Err(e) => {
+            warn!(error = ?e, rollout = ?name, annotation = annotation, "Failed to remove annotation (non-fatal)")
+        }
+    }
+}
+
+/// Merge-patch `status.conditions` and return the Rollout carrying them
+///
+/// Later status patches are computed from the returned Rollout, so they
+/// don't revert the conditions. A failed patch is logged and the Rollout
+/// returned unchanged.
+async fn report_conditions(
+    ctx: &Context,
+    rollout: Arc<Rollout>,
+    conditions: Vec<RolloutCondition>,
+    what: &str,
+) -> Arc<Rollout> {
+    let patch = serde_json::json!({ "status": { "conditions": conditions } });
+    match merge_patch_rollout(ctx, &rollout, &patch, true).await {
+        Ok(()) => {
+            let mut reported = (*rollout).clone();
+            if let Some(status) = reported.status.as_mut() {
+                status.conditions = conditions;
+            }
+            Arc::new(reported)
+        }
+        Err(e) => {
+            warn!(error = %e, rollout = ?rollout.name_any(), "Failed to report {} (non-fatal)", what);
+            rollout
+        }
+    }
+}
+
+/// Apply `spec.onTemplateChange` to a template that changed mid-rollout
+///
+/// A restart isn't patched here: the strategy initializes the status from
+/// the `Initializing` phase, and the reconcile writes that as usual.
+///
+/// # Returns
+/// The Rollout and pod template hash the rest of the reconcile works with,
+/// and the hash of a queued template:
+/// * replace - restarted status, the new hash, nothing queued
+/// * queue - the in-flight template and hash, the new hash queued
+/// * no change in flight - everything as it was
+async fn resolve_template_change(
+    rollout: Arc<Rollout>,
+    ctx: &Context,
+    pod_template_hash: String,
+) -> (Arc<Rollout>, String, Option<String>) {
+    let Some(in_flight) = in_flight_hash(&rollout, &pod_template_hash).map(str::to_string) else {
+        return (rollout, pod_template_hash, None);
+    };
+    let name = rollout.name_any();
+
+    if template_change_action(&rollout) == TemplateChangePolicy::Queue {
+        match in_flight_template(&rollout, &ctx.client, &in_flight).await {
+            Some(template) => {
+                debug!(
+                    rollout = ?name,
+                    in_flight = %in_flight,
+                    queued = %pod_template_hash,
+                    "Template change queued behind the in-flight revision"
+                );
+                let mut queued = (*rollout).clone();
+                queued.spec.template = template;
+                return (Arc::new(queued), in_flight, Some(pod_template_hash));
+            }
+            None => {
+                warn!(
+                    rollout = ?name,
+                    in_flight = %in_flight,
+                    "In-flight template not in revision history, replacing it instead of queueing"
+                );
+            }
+        }
+    }
+
+    info!(
+        rollout = ?name,
+        in_flight = %in_flight,
+        new = %pod_template_hash,
+        "Template changed mid-rollout, restarting with the new revision"
+    );
+    (
+        Arc::new(restart_for_new_template(&rollout)),
+        pod_template_hash,
+        None,
+    )
+}
+
+/// Record a refused start and wait for the freeze to end
+///
+/// ReplicaSets and traffic are left untouched, so nothing of the new revision
+/// runs until the window closes (or the Rollout is annotated
+/// `kulta.io/ignore-freeze=true`).
+async fn hold_frozen_start(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+    window: &FreezeWindow,
+) -> Result<Action, ReconcileError> {
+    let name = rollout.name_any();
+    info!(
+        rollout = ?name,
+        freeze = %window.name,
+        until = %window.end.to_rfc3339(),
+        "Deployment freeze active, not starting new revision"
+    );
+
+    let mut frozen_status = frozen_start_status(rollout, window);
+    frozen_status.summary = Some(status_summary(rollout, &frozen_status));
+    if rollout.status.as_ref() != Some(&frozen_status) {
+        patch_rollout_status(ctx, rollout, &frozen_status).await?;
+        audit_status_patch(rollout, &frozen_status, "deployment freeze").await;
+    }
+
+    Ok(Action::requeue(freeze_requeue_interval(
+        window,
+        ctx.clock.now(),
+    )))
+}
+
+/// Hold a Rollout whose Services are missing, with a `Degraded` condition
+async fn hold_missing_services(
+    rollout: &Rollout,
+    ctx: &Context,
+    missing: &[String],
+) -> Result<Action, ReconcileError> {
+    warn!(
+        rollout = ?rollout.name_any(),
+        services = ?missing,
+        "Referenced Services not found, holding rollout"
+    );
+
+    let mut degraded_status = missing_services_status(rollout, missing, ctx.clock.now());
+    degraded_status.summary = Some(status_summary(rollout, &degraded_status));
+    if rollout.status.as_ref() != Some(&degraded_status) {
+        patch_rollout_status(ctx, rollout, &degraded_status).await?;
+        audit_status_patch(rollout, &degraded_status, "referenced Services missing").await;
+    }
+
+    Ok(Action::requeue(Duration::from_secs(30)))
+}
+
+/// Keep a new revision waiting until the namespace has a free slot
+async fn hold_pending_start(
+    rollout: &Rollout,
+    ctx: &Context,
+    max_active: usize,
+    position: usize,
+) -> Result<Action, ReconcileError> {
+    info!(
+        rollout = ?rollout.name_any(),
+        limit = max_active,
+        position = position,
+        "Concurrent rollout limit reached, not starting new revision"
+    );
+
+    let mut waiting_status = pending_status(rollout, max_active, position);
+    waiting_status.summary = Some(status_summary(rollout, &waiting_status));
+    if rollout.status.as_ref() != Some(&waiting_status) {
+        patch_rollout_status(ctx, rollout, &waiting_status).await?;
+        audit_status_patch(rollout, &waiting_status, "concurrent rollout limit").await;
+    }
+
+    Ok(Action::requeue(Duration::from_secs(30)))
+}
+
+/// Look up why the new revision's ReplicaSet is degraded, if it is
+///
+/// A missing ReplicaSet or a failed lookup counts as not degraded; the lookup
+/// error is logged and retried on the next reconcile.
+async fn new_revision_degradation(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Option<String> {
+    let rs_name = new_revision_replicaset_name(rollout)?;
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), namespace);
+    match rs_api.get_opt(&rs_name).await {
+        Ok(rs) => rs.as_ref().and_then(replicaset_degradation),
+        Err(e) => {
+            warn!(error = ?e, replicaset = %rs_name, "Failed to check ReplicaSet health (non-fatal)");
+            None
+        }
+    }
+}
+
+/// Report per-route failures when HTTPRoutes end up with different weights
+///
+/// Reconcile bails out before computing the next status, so the route states
+/// are patched on their own. Failure to record them is logged only; the
+/// traffic error itself is what gets retried.
+async fn record_httproute_failures(rollout: &Rollout, ctx: &Context, routes: &[HttpRouteStatus]) {
+    let current = rollout.status.as_ref().map(|s| s.http_routes.as_slice());
+    if current == Some(routes) {
+        return;
+    }
+
+    let name = rollout.name_any();
+    let patch = serde_json::json!({ "httpRoutes": routes });
+    if let Err(e) = patch_rollout_status(ctx, rollout, &patch).await {
+        warn!(error = ?e, rollout = ?name, "Failed to record HTTPRoute sync state (non-fatal)");
+        return;
+    }
+    audit::record(
+        AuditOperation::PatchStatus,
+        rollout_audit_ref(rollout),
+        format!("httpRoutes ({} route(s))", routes.len()),
+        "HTTPRoute weights out of sync",
+    )
+    .await;
+}
+
+/// Merge-patch a Rollout, or the ClusterRollout it stands in for
+///
+/// With `status`, the status subresource is patched instead of the object.
+async fn merge_patch_rollout(
+    ctx: &Context,
+    rollout: &Rollout,
+    patch: &serde_json::Value,
+    status: bool,
+) -> Result<(), kube::Error> {
+    let params = PatchParams::default();
+    let patch = Patch::Merge(patch);
+    match source_cluster_rollout(rollout) {
+        Some(cluster_rollout) => {
+            let api: Api<ClusterRollout> = Api::all(ctx.client.clone());
+            if status {
+                api.patch_status(cluster_rollout, &params, &patch).await?;
+            } else {
+                api.patch(cluster_rollout, &params, &patch).await?;
+            }
+        }
+        None => {
+            let namespace = rollout.namespace().unwrap_or_default();
+            let api: Api<Rollout> = Api::namespaced(ctx.client.clone(), &namespace);
+            if status {
+                api.patch_status(&rollout.name_any(), &params, &patch)
+                    .await?;
+            } else {
+                api.patch(&rollout.name_any(), &params, &patch).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merge-patch the status of a Rollout, or of the ClusterRollout it stands in for
+async fn patch_rollout_status(
+    ctx: &Context,
+    rollout: &Rollout,
+    status: &impl serde::Serialize,
+) -> Result<(), kube::Error> {
+    merge_patch_rollout(ctx, rollout, &serde_json::json!({ "status": status }), true).await
+}
+
+fn rollout_audit_ref(rollout: &Rollout) -> AuditObjectRef {
+    match source_cluster_rollout(rollout) {
+        Some(cluster_rollout) => AuditObjectRef::new("ClusterRollout", "", cluster_rollout),
+        None => AuditObjectRef::new(
+            "Rollout",
+            rollout.namespace().as_deref().unwrap_or_default(),
+            &rollout.name_any(),
+        ),
+    }
+}
+
+/// Summarize a status change for the audit log
+///
+/// # Returns
+/// Changed phase/step/weight (e.g., "phase Progressing -> Failed, weight 20 -> 0"),
+/// or "status unchanged" when only other fields moved
+pub(crate) fn status_change_summary(
+    current: Option<&RolloutStatus>,
+    desired: &RolloutStatus,
+) -> String {
+    fn show<T: std::fmt::Debug>(value: Option<T>) -> String {
+        value.map_or_else(|| "-".to_string(), |v| format!("{:?}", v))
+    }
+
+    let mut changes = Vec::new();
+    let phase = current.and_then(|s| s.phase.clone());
+    if phase != desired.phase {
+        changes.push(format!(
+            "phase {} -> {}",
+            show(phase),
+            show(desired.phase.clone())
+        ));
+    }
+    let step = current.and_then(|s| s.current_step_index);
+    if step != desired.current_step_index {
+        changes.push(format!(
+            "step {} -> {}",
+            show(step),
+            show(desired.current_step_index)
+        ));
+    }
+    let weight = current.and_then(|s| s.current_weight);
+    if weight != desired.current_weight {
+        changes.push(format!(
+            "weight {} -> {}",
+            show(weight),
+            show(desired.current_weight)
+        ));
+    }
+
+    if changes.is_empty() {
+        "status unchanged".to_string()
+    } else {
+        changes.join(", ")
+    }
+}
+
+async fn audit_status_patch(rollout: &Rollout, desired: &RolloutStatus, reason: &str) {
+    audit::record(
+        AuditOperation::PatchStatus,
+        rollout_audit_ref(rollout),
+        status_change_summary(rollout.status.as_ref(), desired),
+        reason,
+    )
+    .await;
+}
+
+/// Check the alert gate of the current step before letting the rollout advance
+///
+/// Only applies when the desired status moves past the current step (to the
+/// next step or to completion). Leaves everything else untouched.
+///
+/// # Returns
+/// * `Some(status)` - Current status with a waiting message; progression is held
+/// * `None` - No gate, not advancing, or gate open
+pub(crate) async fn hold_for_alert_gate(
+    rollout: &Rollout,
+    desired_status: &RolloutStatus,
+    ctx: &Context,
+) -> Option<RolloutStatus> {
+    let current_status = rollout.status.as_ref()?;
+    let step_index = current_status.current_step_index?;
+    if !is_advancing(current_status, desired_status) {
+        return None;
+    }
+
+    let gate = step_at(&rollout.spec, step_index)?.alert_gate.as_ref()?;
+
+    match check_alert_gate(gate, ctx.alertmanager.as_ref()).await {
+        AlertGateDecision::Open => None,
+        AlertGateDecision::Blocked(alerts) => {
+            info!(
+                rollout = ?rollout.name_any(),
+                step = step_index,
+                alerts = ?alerts,
+                "Holding step: matching alerts are firing"
+            );
+            Some(RolloutStatus {
+                message: Some(format!(
+                    "Waiting at step {}: alerts firing: {}",
+                    step_index,
+                    alerts.join(", ")
+                )),
+                ..current_status.clone()
+            })
+        }
+    }
+}
+
+/// Whether the desired status raises the canary weight of a rollout with dependency gates
+pub(crate) fn raises_gated_weight(rollout: &Rollout, desired_status: &RolloutStatus) -> bool {
+    let has_gates = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .is_some_and(|canary| !canary.gates.is_empty());
+    has_gates
+        && rollout
+            .status
+            .as_ref()
+            .is_some_and(|current| increases_weight(current, desired_status))
+}
+
+/// Hold the rollout at its current step while dependencies are unhealthy
+///
+/// # Returns
+/// * `Some(status)` - Current status with a message naming the dependencies
+/// * `None` - All dependencies healthy
+pub(crate) fn hold_for_dependency_gates(
+    rollout: &Rollout,
+    unhealthy: &[String],
+) -> Option<RolloutStatus> {
+    if unhealthy.is_empty() {
+        return None;
+    }
+    let current_status = rollout.status.as_ref()?;
+
+    info!(
+        rollout = ?rollout.name_any(),
+        step = ?current_status.current_step_index,
+        dependencies = ?unhealthy,
+        "Holding weight increase: dependencies unhealthy"
+    );
+    Some(RolloutStatus {
+        message: Some(format!(
+            "Waiting to increase canary weight: dependency unhealthy: {}",
+            unhealthy.join("; ")
+        )),
+        ..current_status.clone()
+    })
+}
+
+/// Hold a paused canary step when the namespace policy requires manual promotion
+///
+/// A timed pause would normally advance once its duration elapses; under a
+/// `manual` policy the step waits for `kulta.io/promote` like an indefinite
+/// pause. Steps without a pause are not affected.
+///
+/// # Returns
+/// * `Some(status)` - Current status with a waiting message; progression is held
+/// * `None` - Policy not manual, not advancing, or current step has no pause
+pub(crate) fn hold_for_promotion_policy(
+    rollout: &Rollout,
+    desired_status: &RolloutStatus,
+    mode: Option<PromotionMode>,
+) -> Option<RolloutStatus> {
+    if mode != Some(PromotionMode::Manual) {
+        return None;
+    }
+
+    let current_status = rollout.status.as_ref()?;
+    let step_index = current_status.current_step_index?;
+    if !is_advancing(current_status, desired_status) {
+        return None;
+    }
+
+    // An approved untilApproved pause already had its manual sign-off
+    let pause = step_at(&rollout.spec, step_index)?.pause.as_ref()?;
+    if *pause == Pause::UntilApproved {
+        return None;
+    }
+
+    info!(
+        rollout = ?rollout.name_any(),
+        step = step_index,
+        "Holding paused step: namespace promotion policy requires manual promotion"
+    );
+    Some(RolloutStatus {
+        message: Some(format!(
+            "Waiting at step {}: manual promotion required by namespace policy (kulta.io/promote)",
+            step_index
+        )),
+        ..current_status.clone()
+    })
+}
+
+/// Client the analysis queries Prometheus with
+///
+/// The controller's client, unless `analysis.prometheus` sets credentials or
+/// TLS options; those are read from Secrets in the Rollout's namespace.
+pub(crate) async fn analysis_querier(
+    rollout: &Rollout,
+    config: Option<&crate::crd::rollout::PrometheusConfig>,
+    ctx: &Context,
+) -> Result<Arc<dyn MetricsQuerier>, ReconcileError> {
+    analysis_querier_for(rollout.namespace(), &rollout.name_any(), config, ctx).await
+}
+
+/// Client the analyses of the object `namespace/name` query Prometheus with
+///
+/// Like [`analysis_querier`], for objects other than Rollouts (Experiments).
+pub(crate) async fn analysis_querier_for(
+    namespace: Option<String>,
+    name: &str,
+    config: Option<&crate::crd::rollout::PrometheusConfig>,
+    ctx: &Context,
+) -> Result<Arc<dyn MetricsQuerier>, ReconcileError> {
+    let Some(config) = config.filter(|c| c.has_client_options()) else {
+        return Ok(Arc::clone(&ctx.prometheus_client));
+    };
+    let namespace = namespace.ok_or(ReconcileError::MissingNamespace)?;
+    let options = resolve_client_options(config, &ctx.client, &namespace)
+        .await
+        .map_err(ReconcileError::MetricsEvaluationFailed)?;
+    ctx.prometheus_clients
+        .client_for(
+            &format!("{}/{}", namespace, name),
+            config.address.as_deref(),
+            &options,
+        )
+        .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))
+}
+
+/// Revision selector for a canary's built-in metric queries
+///
+/// With `revisionLabelKey` set, the canary is matched by the pod-template-hash
+/// of the current template and the baseline by the stable ReplicaSet's hash
+/// (as last recorded in status). The baseline ReplicaSet's pods carry that
+/// hash with a `-baseline` suffix.
+pub(crate) fn analysis_revision_selector(
+    rollout: &Rollout,
+    analysis: &crate::crd::rollout::AnalysisConfig,
+) -> Result<RevisionSelector, ReconcileError> {
+    let Some(label) = &analysis.revision_label_key else {
+        return Ok(RevisionSelector::canary());
+    };
+    let baseline = rollout
+        .status
+        .as_ref()
+        .and_then(|s| s.resources.as_ref())
+        .and_then(|r| {
+            r.replica_sets
+                .iter()
+                .find(|rs| rs.role == ReplicaSetRole::Stable)
+        })
+        .map(|rs| rs.hash.clone())
+        .unwrap_or_default();
+
+    Ok(RevisionSelector {
+        label: label.clone(),
+        value: compute_pod_template_hash(&rollout.spec.template)?,
+        baseline_replicaset: baseline_pod_revision(&baseline),
+        baseline,
+    })
+}
+
+/// The analysis with its custom metric queries and web metrics rendered
+/// for the rollout
+///
+/// `{{revision}}` is the revision label value under analysis.
+pub(crate) fn render_analysis_queries(
+    rollout: &Rollout,
+    analysis: &crate::crd::rollout::AnalysisConfig,
+    revision: &RevisionSelector,
+) -> Result<crate::crd::rollout::AnalysisConfig, ReconcileError> {
+    render_analysis_variables(
+        analysis,
+        &rollout.name_any(),
+        rollout.namespace().as_deref().unwrap_or_default(),
+        &revision.value,
+    )
+}
+
+/// The analysis with `{{rollout}}`, `{{namespace}}` and `{{revision}}`
+/// replaced in its custom metric queries and web metrics
+pub(crate) fn render_analysis_variables(
+    analysis: &crate::crd::rollout::AnalysisConfig,
+    rollout_name: &str,
+    namespace: &str,
+    revision: &str,
+) -> Result<crate::crd::rollout::AnalysisConfig, ReconcileError> {
+    let mut rendered = analysis.clone();
+    for metric in &mut rendered.metrics {
+        if let Some(template) = &metric.query {
+            let query = render_query(template, rollout_name, namespace, revision)
+                .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+            metric.query = Some(query);
+        }
+        if let Some(web) = &metric.web {
+            let web = render_web_metric(web, rollout_name, namespace, revision)
+                .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+            metric.web = Some(web);
+        }
+    }
+    Ok(rendered)
+}
+
 /// Evaluate rollout metrics against Prometheus thresholds
 ///
 /// Checks if the canary revision is healthy based on the analysis config.
@@ -627,18 +2532,49 @@ pub(crate) async fn evaluate_rollout_metrics(
     rollout: &Rollout,
     ctx: &Context,
 ) -> Result<bool, ReconcileError> {
+    Ok(evaluate_rollout_analysis(rollout, ctx).await?.is_healthy)
+}
+
+/// Outcome of [`evaluate_rollout_analysis`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnalysisVerdict {
+    /// Whether the analysis passes
+    pub is_healthy: bool,
+    /// Consecutive failure counts to record in `status.metricFailures`
+    /// (None when they weren't evaluated and stay as they are)
+    pub metric_failures: Option<Vec<MetricFailureCount>>,
+}
+
+impl AnalysisVerdict {
+    fn healthy() -> Self {
+        Self {
+            is_healthy: true,
+            metric_failures: None,
+        }
+    }
+}
+
+/// Evaluate the canary analysis, counting consecutive metric failures
+///
+/// Like [`evaluate_rollout_metrics`], except that a metric with a
+/// `failureThreshold` only fails the analysis after that many breaches in a
+/// row (counted from `status.metricFailures`).
+pub(crate) async fn evaluate_rollout_analysis(
+    rollout: &Rollout,
+    ctx: &Context,
+) -> Result<AnalysisVerdict, ReconcileError> {
     // Check if rollout has canary strategy with analysis config
     let analysis_config = match &rollout.spec.strategy.canary {
         Some(canary_strategy) => match &canary_strategy.analysis {
             Some(analysis) => analysis,
             None => {
                 // No analysis config - consider healthy (no constraints)
-                return Ok(true);
+                return Ok(AnalysisVerdict::healthy());
             }
         },
         None => {
             // No canary strategy - no metrics to check
-            return Ok(true);
+            return Ok(AnalysisVerdict::healthy());
         }
     };
 
@@ -667,7 +2603,7 @@ pub(crate) async fn evaluate_rollout_metrics(
                         warmup_remaining_secs = remaining,
                         "Skipping metrics analysis - warmup period not elapsed"
                     );
-                    return Ok(true);
+                    return Ok(AnalysisVerdict::healthy());
                 }
             } else {
                 // Warmup is configured but step_start_time is missing or invalid.
@@ -676,22 +2612,101 @@ pub(crate) async fn evaluate_rollout_metrics(
                     rollout = rollout.name_any(),
                     "Warmup duration is configured but step_start_time is missing or invalid; skipping metrics analysis and treating warmup as just started"
                 );
-                return Ok(true);
+                return Ok(AnalysisVerdict::healthy());
             }
         }
     }
 
     // Get rollout name for Prometheus labels
     let rollout_name = rollout.name_any();
+    let revision = analysis_revision_selector(rollout, analysis_config)?;
+    let rendered = render_analysis_queries(rollout, analysis_config, &revision)?;
+    let analysis_config = &rendered;
+    if let Ok(selector) = build_builtin_selector(&rollout_name, &revision) {
+        debug!(
+            rollout = rollout_name,
+            selector = selector,
+            "Evaluating built-in metric templates"
+        );
+    }
 
-    // Evaluate all metrics
-    let is_healthy = ctx
-        .prometheus_client
-        .evaluate_all_metrics(&analysis_config.metrics, &rollout_name, "canary")
-        .await
-        .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+    // Evaluate all metrics, combined per the analysis aggregation
+    let querier = analysis_querier(rollout, analysis_config.prometheus.as_ref(), ctx).await?;
+    let mut metric_failures = None;
+    let is_healthy = if uses_failure_thresholds(analysis_config) {
+        let results = querier
+            .evaluate_metric_results(analysis_config, &rollout_name, &revision, "")
+            .await
+            .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+        let previous = rollout
+            .status
+            .as_ref()
+            .and_then(|s| s.metric_failures.as_deref())
+            .unwrap_or_default();
+        let failures = count_consecutive_failures(
+            &analysis_config.metrics,
+            &results,
+            previous,
+            ctx.clock.now(),
+        );
+        if !failures.is_empty() {
+            debug!(
+                rollout = rollout_name,
+                failures = ?failures,
+                "Metrics failing below their failureThreshold"
+            );
+        }
+        let is_healthy = analysis_config.is_healthy(&apply_failure_thresholds(
+            &analysis_config.metrics,
+            &failures,
+        ));
+        metric_failures = Some(failures);
+        is_healthy
+    } else {
+        querier
+            .evaluate_analysis(analysis_config, &rollout_name, &revision, "")
+            .await
+            .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?
+    };
 
-    Ok(is_healthy)
+    if !is_healthy {
+        return Ok(AnalysisVerdict {
+            is_healthy: false,
+            metric_failures,
+        });
+    }
+
+    // Aggregate is healthy - check each zone so a single bad zone isn't averaged away
+    if let Some(per_zone) = &analysis_config.per_zone {
+        let zone_label = per_zone.label.as_deref().unwrap_or(DEFAULT_ZONE_LABEL);
+        let breached_zones = querier
+            .find_failing_zones(
+                analysis_config,
+                &rollout_name,
+                &revision,
+                zone_label,
+                &per_zone.zones,
+            )
+            .await
+            .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+
+        if !breached_zones.is_empty() {
+            warn!(
+                rollout = rollout_name,
+                zones = ?breached_zones,
+                "Metrics breached threshold in individual zones"
+            );
+            return Ok(AnalysisVerdict {
+                is_healthy: false,
+                metric_failures,
+            });
+        }
+    }
+
+    Ok(AnalysisVerdict {
+        is_healthy: true,
+        metric_failures,
+    })
 }
 
 /// Result of A/B experiment evaluation
@@ -710,6 +2725,29 @@ pub struct ABExperimentEvaluation {
     pub sample_size_b: Option<i64>,
 }
 
+/// Names of the guardrails variant B currently breaches
+///
+/// A guardrail whose metric can't be queried is logged and treated as
+/// holding, so a Prometheus outage doesn't end the experiment.
+pub(crate) async fn breached_ab_guardrails(
+    guardrails: &[crate::crd::rollout::ABGuardrail],
+    service_b: &str,
+    querier: &dyn MetricsQuerier,
+) -> Vec<String> {
+    let mut breached = Vec::new();
+    for guardrail in guardrails {
+        match querier.query_ab_metric(&guardrail.name, service_b).await {
+            Ok(value) if !guardrail.holds(value) => breached.push(guardrail.name.clone()),
+            Ok(_) => {}
+            Err(e) => {
+                warn!(error = %e, guardrail = %guardrail.name, service = %service_b,
+                    "Failed to query A/B guardrail metric (treated as holding)");
+            }
+        }
+    }
+    breached
+}
+
 /// Evaluate A/B experiment for conclusion conditions
 ///
 /// Checks duration constraints and statistical significance.
@@ -766,6 +2804,41 @@ pub async fn evaluate_ab_experiment(
         });
     }
 
+    let querier = analysis_querier(
+        rollout,
+        ab_strategy
+            .analysis
+            .as_ref()
+            .and_then(|a| a.prometheus.as_ref()),
+        ctx,
+    )
+    .await?;
+
+    // Guardrails stop a harmful variant B at any time, before any statistics
+    if let Some(analysis) = &ab_strategy.analysis {
+        let breached = breached_ab_guardrails(
+            &analysis.guardrails,
+            &ab_strategy.variant_b_service,
+            querier.as_ref(),
+        )
+        .await;
+        if !breached.is_empty() {
+            warn!(
+                rollout = rollout.name_any(),
+                guardrails = ?breached,
+                "A/B experiment guardrail breached by variant B"
+            );
+            return Ok(ABExperimentEvaluation {
+                should_conclude: true,
+                winner: Some(crate::crd::rollout::ABVariant::A),
+                reason: Some(ABConclusionReason::GuardrailBreached),
+                results: vec![],
+                sample_size_a: None,
+                sample_size_b: None,
+            });
+        }
+    }
+
     // Get experiment start time
     let started_at = rollout
         .status
@@ -853,7 +2926,29 @@ pub async fn evaluate_ab_experiment(
         sample_size_b: None,
     };
 
-    let sample_a = match ctx.prometheus_client.query_ab_sample_count(service_a).await {
+    // Business-hours style windows count only samples taken inside the window
+    let sample_filter = match &analysis_config.sample_window {
+        Some(window) => {
+            let since_start = elapsed
+                .and_then(|e| e.to_std().ok())
+                .unwrap_or(Duration::ZERO);
+            match SampleFilter::new(window, since_start) {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    warn!(error = %e, rollout = rollout.name_any(),
+                        "Invalid A/B sample window");
+                    return Ok(inconclusive);
+                }
+            }
+        }
+        None => None,
+    };
+    let sample_filter = sample_filter.as_ref();
+
+    let sample_a = match querier
+        .query_ab_sample_count(service_a, sample_filter)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
             warn!(error = %e, service = %service_a, rollout = rollout.name_any(),
@@ -861,7 +2956,10 @@ pub async fn evaluate_ab_experiment(
             return Ok(inconclusive);
         }
     };
-    let sample_b = match ctx.prometheus_client.query_ab_sample_count(service_b).await {
+    let sample_b = match querier
+        .query_ab_sample_count(service_b, sample_filter)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
             warn!(error = %e, service = %service_b, rollout = rollout.name_any(),
@@ -891,7 +2989,7 @@ pub async fn evaluate_ab_experiment(
     }
 
     // Query error rates for both variants
-    let rate_a = match ctx.prometheus_client.query_ab_error_rate(service_a).await {
+    let rate_a = match querier.query_ab_error_rate(service_a, sample_filter).await {
         Ok(v) => v,
         Err(e) => {
             warn!(error = %e, service = %service_a, rollout = rollout.name_any(),
@@ -906,7 +3004,7 @@ pub async fn evaluate_ab_experiment(
             });
         }
     };
-    let rate_b = match ctx.prometheus_client.query_ab_error_rate(service_b).await {
+    let rate_b = match querier.query_ab_error_rate(service_b, sample_filter).await {
         Ok(v) => v,
         Err(e) => {
             warn!(error = %e, service = %service_b, rollout = rollout.name_any(),
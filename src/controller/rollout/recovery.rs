@@ -0,0 +1,99 @@
+//! Resuming in-flight rollouts after a controller restart
+//!
+//! The controller keeps no rollout state in memory: the current step, pause
+//! timers and canary scale are read from `status` and the managed resources
+//! on every reconcile, so a restarted controller continues where the last
+//! one stopped. What a restart can expose is a status left behind between
+//! two writes:
+//!
+//! - A timed pause without a usable `pauseStartTime` would never end, and a
+//!   `currentWeight` that no longer matches its step sizes the canary wrong.
+//!   [`repair_in_flight_status`] re-derives both from the step index.
+//! - A `kulta.io/promote` that was applied just before the controller
+//!   stopped is still on the Rollout and would promote a second time.
+//!   `status.promotedAt` marks it as applied, see [`is_stale_promotion`].
+//!
+//! The same annotation can also be read twice while the controller runs: a
+//! reconcile triggered by a child object may see the Rollout from before
+//! the promotion's status update reached the watch cache. The update is
+//! conditional on the version the promotion was read at, see
+//! [`promotion_status_patch`], so only one of them advances a step.
+
+use super::status::{has_promote_annotation, pause_end};
+use crate::controller::strategy_math::{current_step, current_weight};
+use crate::crd::rollout::{Phase, Rollout, RolloutStatus};
+use chrono::{DateTime, Utc};
+
+/// Status of a progressing canary with its step state re-derived
+///
+/// - `currentWeight` is recomputed from the current step
+/// - A timed pause without a valid `pauseStartTime` starts at `stepStartTime`,
+///   or at `now` when that is missing too (the pause restarts rather than
+///   never ending)
+/// - `pausedUntil` follows the pause start; both are cleared at steps
+///   without a pause
+///
+/// # Returns
+/// * `Some(status)` - Repaired status, to be written before acting on it
+/// * `None` - Not an in-flight canary, or nothing to repair
+pub fn repair_in_flight_status(rollout: &Rollout, now: DateTime<Utc>) -> Option<RolloutStatus> {
+    let status = rollout.status.as_ref()?;
+    if status.phase != Some(Phase::Progressing) || status.aborted_at.is_some() {
+        return None;
+    }
+    let step = current_step(&rollout.spec, Some(status))?;
+
+    let mut repaired = status.clone();
+    repaired.current_weight = Some(current_weight(&rollout.spec, Some(status)));
+    match &step.pause {
+        Some(pause) => {
+            let started = parse_time(status.pause_start_time.as_deref());
+            let timed = pause_end(pause, started.unwrap_or(now)).is_some();
+            if timed && started.is_none() {
+                let restarted = parse_time(status.step_start_time.as_deref()).unwrap_or(now);
+                repaired.pause_start_time = Some(restarted.to_rfc3339());
+            }
+            repaired.paused_until = parse_time(repaired.pause_start_time.as_deref())
+                .and_then(|started| pause_end(pause, started))
+                .map(|end| end.to_rfc3339());
+        }
+        None => {
+            repaired.pause_start_time = None;
+            repaired.paused_until = None;
+        }
+    }
+
+    (&repaired != status).then_some(repaired)
+}
+
+/// Whether the Rollout carries a `kulta.io/promote` that was already applied
+///
+/// The controller records `status.promotedAt` with the promotion and clears
+/// it once the annotation is gone. Both set means it stopped before removing
+/// the annotation; acting on it again would skip another step.
+pub fn is_stale_promotion(rollout: &Rollout) -> bool {
+    has_promote_annotation(rollout)
+        && rollout
+            .status
+            .as_ref()
+            .is_some_and(|status| status.promoted_at.is_some())
+}
+
+/// Merge patch of the status update that applies a `kulta.io/promote`
+///
+/// Conditional on `status.promotedVersion`, the `resourceVersion` the
+/// promotion was read at: the update conflicts if the Rollout changed
+/// since, e.g. because another reconcile already applied the promotion.
+pub fn promotion_status_patch(status: &RolloutStatus) -> serde_json::Value {
+    let mut patch = serde_json::json!({ "status": status });
+    if let Some(version) = &status.promoted_version {
+        patch["metadata"] = serde_json::json!({ "resourceVersion": version });
+    }
+    patch
+}
+
+fn parse_time(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
@@ -1,32 +1,92 @@
+use super::history::{history_replicaset_name, HISTORY_REPLICASET_TYPE, REVISION_ANNOTATION};
 use super::reconcile::ReconcileError;
-use crate::crd::rollout::Rollout;
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::controller::orphans::ROLLOUT_ANNOTATION;
+use crate::crd::cluster_rollout::rollout_owner_reference;
+use crate::crd::rollout::{ManagedReplicaSet, ReplicaSetRole, Rollout};
 use k8s_openapi::api::apps::v1::{ReplicaSet, ReplicaSetSpec};
 use k8s_openapi::api::core::v1::PodTemplateSpec;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::api::{Api, ObjectMeta, Patch, PatchParams, PostParams};
+use std::collections::BTreeMap;
 use tracing::{debug, error, info};
 
+/// Pod label carrying the pod-template-hash, for Prometheus relabeling
+///
+/// Only set on pods, not in ReplicaSet selectors (which are immutable).
+pub const REVISION_LABEL: &str = "kulta.io/revision";
+
+/// Serialized size and hash of a PodTemplateSpec
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateDigest {
+    /// 10-character pod-template-hash
+    pub hash: String,
+    /// Size of the template serialized as JSON, in bytes
+    pub size: usize,
+}
+
+/// FNV-1a over everything written to it
+///
+/// Lets the template be hashed while it is serialized, without holding the
+/// whole JSON document in memory.
+struct Fnv1aWriter {
+    hash: u64,
+    len: usize,
+}
+
+impl Fnv1aWriter {
+    fn new() -> Self {
+        Fnv1aWriter {
+            hash: 0xcbf29ce484222325,
+            len: 0,
+        }
+    }
+}
+
+impl std::io::Write for Fnv1aWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(0x100000001b3);
+        }
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hash and measure a PodTemplateSpec in a single streaming pass
+///
+/// The template is serialized straight into the hasher, so giant templates
+/// (thousands of env vars or volumes) never exist as one JSON string.
+///
+/// # Errors
+/// Returns SerializationError if PodTemplateSpec cannot be serialized to JSON
+pub fn digest_pod_template(template: &PodTemplateSpec) -> Result<TemplateDigest, ReconcileError> {
+    let mut writer = Fnv1aWriter::new();
+    serde_json::to_writer(&mut writer, template)
+        .map_err(|e| ReconcileError::SerializationError(e.to_string()))?;
+
+    Ok(TemplateDigest {
+        hash: format!("{:x}", writer.hash)[..10].to_string(),
+        size: writer.len,
+    })
+}
+
 /// Compute a stable 10-character hash for a PodTemplateSpec
 ///
 /// Inspired by Kubernetes' pod-template-hash label concept, using FNV-1a:
 /// - Serialize the template to JSON (deterministic)
-/// - Hash the JSON bytes
+/// - Hash the JSON bytes as they are written (see [`digest_pod_template`])
 /// - Return 10-character hex string
 ///
 /// # Errors
 /// Returns SerializationError if PodTemplateSpec cannot be serialized to JSON
 pub fn compute_pod_template_hash(template: &PodTemplateSpec) -> Result<String, ReconcileError> {
-    let json = serde_json::to_string(template)
-        .map_err(|e| ReconcileError::SerializationError(e.to_string()))?;
-
-    // FNV-1a (deterministic across processes, unlike DefaultHasher/SipHash)
-    let mut hash: u64 = 0xcbf29ce484222325;
-    for byte in json.as_bytes() {
-        hash ^= *byte as u64;
-        hash = hash.wrapping_mul(0x100000001b3);
-    }
-
-    Ok(format!("{hash:x}")[..10].to_string())
+    digest_pod_template(template).map(|digest| digest.hash)
 }
 
 /// Calculate how to split total replicas between stable and canary
@@ -184,18 +244,67 @@ pub fn calculate_replica_split_with_surge(
     (stable_replicas, canary_replicas)
 }
 
+/// Calculate replica split that approximates a traffic weight by endpoint share
+///
+/// Used when there is no traffic router (`trafficRouting.none.replicaWeighting`):
+/// a Service selecting both ReplicaSets sends each ready pod roughly the same
+/// share of connections, so the canary weight is canary_replicas / total_replicas.
+/// The total never exceeds `total_replicas` (surge would dilute the split).
+///
+/// Rounds to the nearest replica, but keeps at least one canary pod for any
+/// non-zero weight and at least one stable pod for any weight below 100.
+///
+/// # Returns
+/// Tuple of (stable_replicas, canary_replicas)
+///
+/// # Examples
+/// ```ignore
+/// assert_eq!(calculate_replica_weighted_split(10, 25), (7, 3)); // 30% effective
+/// assert_eq!(calculate_replica_weighted_split(4, 5), (3, 1));   // 25% effective
+/// ```
+pub fn calculate_replica_weighted_split(total_replicas: i32, canary_weight: i32) -> (i32, i32) {
+    if total_replicas <= 0 {
+        return (0, 0);
+    }
+
+    let canary_replicas = match canary_weight {
+        w if w <= 0 => 0,
+        w if w >= 100 => total_replicas,
+        w => {
+            let nearest = ((total_replicas as f64 * w as f64) / 100.0).round() as i32;
+            // A single replica can't be split; it stays stable until 100%
+            nearest.max(1).min(total_replicas - 1)
+        }
+    };
+
+    (total_replicas - canary_replicas, canary_replicas)
+}
+
+/// Traffic percentage actually reaching the canary under replica weighting
+pub fn effective_replica_weight(stable_replicas: i32, canary_replicas: i32) -> i32 {
+    let total = stable_replicas + canary_replicas;
+    if total <= 0 {
+        return 0;
+    }
+    ((canary_replicas as f64 * 100.0) / total as f64).round() as i32
+}
+
 /// Ensure a ReplicaSet exists (create if missing)
 ///
 /// This function is idempotent - it will:
-/// - Return Ok if ReplicaSet already exists
+/// - Scale the ReplicaSet if it already exists
 /// - Create ReplicaSet if it doesn't exist (404)
 /// - Return Err on other API errors
+///
+/// # Returns
+/// The ReplicaSet as it is now managed, for `status.resources`
 pub async fn ensure_replicaset_exists(
     rs_api: &Api<ReplicaSet>,
     rs: &ReplicaSet,
-    rs_type: &str,
+    role: ReplicaSetRole,
     replicas: i32,
-) -> Result<(), ReconcileError> {
+) -> Result<ManagedReplicaSet, ReconcileError> {
+    let rs_type = role.as_str();
     let rs_name = rs
         .metadata
         .name
@@ -230,6 +339,13 @@ pub async fn ensure_replicaset_exists(
                         &Patch::Merge(&scale_patch),
                     )
                     .await?;
+                audit::record(
+                    AuditOperation::Patch,
+                    replicaset_audit_ref(rs, rs_name),
+                    format!("replicas {} -> {}", current_replicas, replicas),
+                    format!("scale {} ReplicaSet", rs_type),
+                )
+                .await;
 
                 info!(
                     replicaset = ?rs_name,
@@ -246,6 +362,33 @@ pub async fn ensure_replicaset_exists(
                     "ReplicaSet already at correct scale"
                 );
             }
+
+            // ReplicaSets created before owner references were set get adopted
+            if existing.metadata.owner_references.is_none() {
+                if let Some(owners) = &rs.metadata.owner_references {
+                    let owner_patch = serde_json::json!({
+                        "metadata": { "ownerReferences": owners }
+                    });
+                    rs_api
+                        .patch(
+                            rs_name,
+                            &PatchParams::default(),
+                            &Patch::Merge(&owner_patch),
+                        )
+                        .await?;
+                    audit::record(
+                        AuditOperation::Patch,
+                        replicaset_audit_ref(rs, rs_name),
+                        "ownerReferences",
+                        format!("adopt {} ReplicaSet", rs_type),
+                    )
+                    .await;
+                    info!(replicaset = ?rs_name, rs_type = rs_type, "Adopted ReplicaSet");
+                }
+            }
+
+            // Existing ReplicaSets keep the revision they were created with
+            Ok(managed_replicaset(&existing, role, replicas))
         }
         Err(kube::Error::Api(err)) if err.code == 404 => {
             // Not found, create it
@@ -257,12 +400,21 @@ pub async fn ensure_replicaset_exists(
             );
 
             rs_api.create(&PostParams::default(), rs).await?;
+            audit::record(
+                AuditOperation::Create,
+                replicaset_audit_ref(rs, rs_name),
+                format!("replicas {}", replicas),
+                format!("ensure {} ReplicaSet", rs_type),
+            )
+            .await;
 
             info!(
                 replicaset = ?rs_name,
                 rs_type = rs_type,
                 "ReplicaSet created successfully"
             );
+
+            Ok(managed_replicaset(rs, role, replicas))
         }
         Err(e) => {
             error!(
@@ -271,17 +423,49 @@ pub async fn ensure_replicaset_exists(
                 rs_type = rs_type,
                 "Failed to get ReplicaSet"
             );
-            return Err(ReconcileError::KubeError(e));
+            Err(ReconcileError::KubeError(e))
         }
     }
+}
+
+/// Describe a ReplicaSet for `status.resources`
+///
+/// The hash is read from the ReplicaSet's `pod-template-hash` label (empty
+/// when missing).
+pub fn managed_replicaset(
+    rs: &ReplicaSet,
+    role: ReplicaSetRole,
+    replicas: i32,
+) -> ManagedReplicaSet {
+    ManagedReplicaSet {
+        name: rs.metadata.name.clone().unwrap_or_default(),
+        hash: rs
+            .metadata
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get("pod-template-hash"))
+            .cloned()
+            .unwrap_or_default(),
+        role,
+        replicas,
+    }
+}
 
-    Ok(())
+fn replicaset_audit_ref(rs: &ReplicaSet, rs_name: &str) -> AuditObjectRef {
+    AuditObjectRef::new(
+        "ReplicaSet",
+        rs.metadata.namespace.as_deref().unwrap_or_default(),
+        rs_name,
+    )
 }
 
 /// Core ReplicaSet builder used by all strategy-specific builders
 ///
 /// Creates a ReplicaSet with:
 /// - Labels: pod-template-hash, rollouts.kulta.io/type, rollouts.kulta.io/managed
+/// - Pod label: kulta.io/revision (the pod-template-hash)
+/// - Annotation: rollouts.kulta.io/rollout (owning Rollout name)
+/// - Owner reference: the Rollout, or the ClusterRollout it stands in for
 /// - Name: `{rollout-name}-{rs_type}` if `with_suffix` is true, else `{rollout-name}`
 /// - Spec: from Rollout's template
 ///
@@ -313,8 +497,11 @@ fn build_replicaset_core(
     labels.insert("rollouts.kulta.io/type".to_string(), rs_type.to_string());
     labels.insert("rollouts.kulta.io/managed".to_string(), "true".to_string());
 
+    let mut pod_labels = labels.clone();
+    pod_labels.insert(REVISION_LABEL.to_string(), pod_template_hash);
+
     let mut template_metadata = template.metadata.take().unwrap_or_default();
-    template_metadata.labels = Some(labels.clone());
+    template_metadata.labels = Some(pod_labels);
     template.metadata = Some(template_metadata);
 
     let selector = LabelSelector {
@@ -333,6 +520,13 @@ fn build_replicaset_core(
             name: Some(rs_name),
             namespace,
             labels: Some(labels),
+            // Lets the orphan collector find the Rollout without relying on the name
+            annotations: Some(BTreeMap::from([(
+                ROLLOUT_ANNOTATION.to_string(),
+                rollout_name.clone(),
+            )])),
+            // Deleting the Rollout (or ClusterRollout) deletes its ReplicaSets
+            owner_references: rollout_owner_reference(rollout).map(|owner| vec![owner]),
             ..Default::default()
         },
         spec: Some(ReplicaSetSpec {
@@ -371,15 +565,42 @@ pub fn build_replicaset_for_simple(
 /// Creates two full-size ReplicaSets:
 /// - Active: `{rollout-name}-active` (receives production traffic)
 /// - Preview: `{rollout-name}-preview` (for testing before promotion)
+///
+/// Rollouts migrated from canary keep `{rollout-name}-stable` as active and
+/// `{rollout-name}-canary` as preview.
 pub fn build_replicasets_for_blue_green(
     rollout: &Rollout,
     replicas: i32,
 ) -> Result<(ReplicaSet, ReplicaSet), ReconcileError> {
-    let active_rs = build_replicaset_core(rollout, "active", replicas, true)?;
-    let preview_rs = build_replicaset_core(rollout, "preview", replicas, true)?;
+    let (active_type, preview_type) = rollout
+        .spec
+        .strategy
+        .blue_green
+        .as_ref()
+        .map_or(("active", "preview"), |bg| bg.replicaset_types());
+    let active_rs = build_replicaset_core(rollout, active_type, replicas, true)?;
+    let preview_rs = build_replicaset_core(rollout, preview_type, replicas, true)?;
     Ok((active_rs, preview_rs))
 }
 
+/// Build the scaled-down ReplicaSet that keeps a revision for rollback
+///
+/// Name: `{rollout-name}-{pod-template-hash}`, type `history`, 0 replicas,
+/// annotated with the revision number (`rollout.kulta.io/revision`).
+pub fn build_history_replicaset(
+    rollout: &Rollout,
+    revision: i64,
+) -> Result<ReplicaSet, ReconcileError> {
+    let mut rs = build_replicaset_core(rollout, HISTORY_REPLICASET_TYPE, 0, true)?;
+    let hash = compute_pod_template_hash(&rollout.spec.template)?;
+    rs.metadata.name = Some(history_replicaset_name(rollout, &hash));
+    rs.metadata
+        .annotations
+        .get_or_insert_with(Default::default)
+        .insert(REVISION_ANNOTATION.to_string(), revision.to_string());
+    Ok(rs)
+}
+
 /// Build ReplicaSets for A/B testing strategy
 ///
 /// Creates two full-size ReplicaSets:
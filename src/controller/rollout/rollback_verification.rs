@@ -0,0 +1,318 @@
+//! Verification of the stable revision after a canary rollback
+//!
+//! With `canary.rollbackVerification`, a failed canary's rollback is
+//! followed by one measurement of `canary.analysis` against the stable
+//! revision, `delay` after `abortedAt`. The verdict in
+//! `status.rollbackVerification` tells responders whether error rates
+//! recovered once the canary was gone (`Recovered`: the canary was bad) or
+//! the stable revision breaches the same thresholds (`Unhealthy`: the
+//! system is unhealthy beyond the canary).
+//!
+//! Metrics that compare to the stable revision or the baseline ReplicaSet
+//! have nothing to compare against once the canary is gone and are left
+//! out. Each rollback is verified once; `kulta.io/retry` clears the verdict
+//! along with the rest of the status.
+
+use super::reconcile::{
+    analysis_querier, analysis_revision_selector, render_analysis_queries, Context, ReconcileError,
+};
+use super::validation::parse_duration;
+use crate::controller::prometheus::RevisionSelector;
+use crate::crd::rollout::{
+    AnalysisConfig, MetricConfig, Phase, RollbackVerificationOutcome, RollbackVerificationStatus,
+    Rollout, RolloutStatus,
+};
+use chrono::{DateTime, Utc};
+use kube::ResourceExt;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Delay between a rollback and its verification when none is set
+pub const DEFAULT_ROLLBACK_VERIFICATION_DELAY: Duration = Duration::from_secs(120);
+
+fn parse_time(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// When the rollback recorded in `status` is due for verification
+///
+/// # Returns
+/// * `Some(due)` - The canary was rolled back at `abortedAt` and that
+///   rollback hasn't been verified yet
+/// * `None` - No `rollbackVerification`, no rollback, or already verified
+pub fn verification_due_at(rollout: &Rollout, status: &RolloutStatus) -> Option<DateTime<Utc>> {
+    let verification = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()?
+        .rollback_verification
+        .as_ref()?;
+    if status.phase != Some(Phase::Failed) {
+        return None;
+    }
+    let aborted_at = status.aborted_at.as_deref()?;
+    if status
+        .rollback_verification
+        .as_ref()
+        .is_some_and(|verified| verified.rolled_back_at == aborted_at)
+    {
+        return None;
+    }
+    let delay = verification
+        .delay
+        .as_deref()
+        .and_then(parse_duration)
+        .unwrap_or(DEFAULT_ROLLBACK_VERIFICATION_DELAY);
+    Some(parse_time(Some(aborted_at))? + chrono::Duration::from_std(delay).ok()?)
+}
+
+/// How long until the pending rollback verification is due
+///
+/// # Returns
+/// * `Some(delay)` - A rollback awaits verification (zero when it is due)
+/// * `None` - Nothing to verify
+pub fn time_to_rollback_verification(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let due = verification_due_at(rollout, status)?;
+    Some((due - now).to_std().unwrap_or_default())
+}
+
+/// The canary analysis, without metrics that need a canary or a baseline
+///
+/// Metrics that compare to the stable revision or the baseline ReplicaSet
+/// are dropped, and so is `perZone`: the stable revision is judged as a
+/// whole.
+pub fn stable_verification_config(analysis: &AnalysisConfig) -> AnalysisConfig {
+    AnalysisConfig {
+        metrics: analysis
+            .metrics
+            .iter()
+            .filter(|metric| {
+                !metric.compares_to_stable() && metric.compare_to_baseline != Some(true)
+            })
+            .cloned()
+            .collect(),
+        per_zone: None,
+        ..analysis.clone()
+    }
+}
+
+/// Verdict from one result per metric, in order
+pub fn rollback_verdict(
+    rolled_back_at: &str,
+    now: DateTime<Utc>,
+    metrics: &[MetricConfig],
+    results: &[bool],
+) -> RollbackVerificationStatus {
+    let failing_metrics: Vec<String> = metrics
+        .iter()
+        .zip(results)
+        .filter(|(_, is_healthy)| !**is_healthy)
+        .map(|(metric, _)| metric.name.clone())
+        .collect();
+    let (outcome, message) = if metrics.is_empty() {
+        (
+            RollbackVerificationOutcome::Inconclusive,
+            "No analysis metric applies to the stable revision on its own".to_string(),
+        )
+    } else if failing_metrics.is_empty() {
+        (
+            RollbackVerificationOutcome::Recovered,
+            "The stable revision passes the analysis; the canary was the problem".to_string(),
+        )
+    } else {
+        (
+            RollbackVerificationOutcome::Unhealthy,
+            format!(
+                "The stable revision fails {} too; the system is unhealthy beyond the canary",
+                failing_metrics.join(", ")
+            ),
+        )
+    };
+    RollbackVerificationStatus {
+        rolled_back_at: rolled_back_at.to_string(),
+        verified_at: now.to_rfc3339(),
+        outcome,
+        failing_metrics,
+        message: Some(message),
+    }
+}
+
+/// One result per metric of `config`, measured against the stable revision
+async fn measure_stable_revision(
+    rollout: &Rollout,
+    config: &AnalysisConfig,
+    ctx: &Context,
+) -> Result<Vec<bool>, ReconcileError> {
+    let revision = analysis_revision_selector(rollout, config)?;
+    let stable = RevisionSelector {
+        value: revision.baseline.clone(),
+        ..revision
+    };
+    let config = render_analysis_queries(rollout, config, &stable)?;
+    let querier = analysis_querier(rollout, config.prometheus.as_ref(), ctx).await?;
+    querier
+        .evaluate_metric_results(&config, &rollout.name_any(), &stable, "")
+        .await
+        .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))
+}
+
+/// Measure the stable revision if a rollback verification is due
+///
+/// Query errors don't fail the reconcile: they make the verdict
+/// `Inconclusive`, since the rollback is done either way.
+///
+/// # Returns
+/// * `Some(verdict)` - The verification to record in status
+/// * `None` - No verification due
+pub(crate) async fn verify_rollback(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    ctx: &Context,
+) -> Option<RollbackVerificationStatus> {
+    let now = ctx.clock.now();
+    if verification_due_at(rollout, status)? > now {
+        return None;
+    }
+    let rolled_back_at = status.aborted_at.as_deref()?;
+    let analysis = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .and_then(|canary| canary.analysis.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    let config = stable_verification_config(&analysis);
+    let rollout_name = rollout.name_any();
+
+    let results = measure_stable_revision(rollout, &config, ctx).await;
+    let verdict = match results {
+        Ok(results) => rollback_verdict(rolled_back_at, now, &config.metrics, &results),
+        Err(e) => {
+            warn!(
+                rollout = rollout_name,
+                error = ?e,
+                "Failed to measure the stable revision after rollback"
+            );
+            RollbackVerificationStatus {
+                message: Some(format!("The stable revision couldn't be measured: {}", e)),
+                ..rollback_verdict(rolled_back_at, now, &[], &[])
+            }
+        }
+    };
+    info!(
+        rollout = rollout_name,
+        outcome = ?verdict.outcome,
+        failing = ?verdict.failing_metrics,
+        "Verified stable revision after rollback"
+    );
+    Some(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(verification: serde_json::Value) -> Rollout {
+        RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [{ "setWeight": 100 }],
+                    "analysis": { "metrics": [
+                        { "name": "error-rate", "threshold": 5.0 },
+                        { "name": "latency-p95", "threshold": 100.0, "compareToStable": true },
+                        { "name": "latency-p99", "threshold": 200.0 }
+                    ] },
+                    "rollbackVerification": verification
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn failed_at(secs: i64) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(Phase::Failed),
+            aborted_at: Some(at(secs).to_rfc3339()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rollback_verification_is_due_once_per_rollback() {
+        let rollout = rollout(serde_json::json!({ "delay": "1m" }));
+        let mut status = failed_at(0);
+        assert_eq!(verification_due_at(&rollout, &status), Some(at(60)));
+        assert_eq!(
+            time_to_rollback_verification(&rollout, &status, at(20)),
+            Some(Duration::from_secs(40))
+        );
+
+        status.rollback_verification = Some(rollback_verdict(
+            status.aborted_at.as_deref().unwrap(),
+            at(60),
+            &[],
+            &[],
+        ));
+        assert_eq!(verification_due_at(&rollout, &status), None);
+
+        // A later rollback is verified again
+        status.aborted_at = Some(at(600).to_rfc3339());
+        assert_eq!(verification_due_at(&rollout, &status), Some(at(660)));
+
+        let defaulted = self::rollout(serde_json::json!({}));
+        assert_eq!(
+            verification_due_at(&defaulted, &failed_at(0)),
+            Some(at(120))
+        );
+        let without = self::rollout(serde_json::Value::Null);
+        assert_eq!(verification_due_at(&without, &failed_at(0)), None);
+        let progressing = RolloutStatus {
+            phase: Some(Phase::Progressing),
+            ..failed_at(0)
+        };
+        assert_eq!(verification_due_at(&rollout, &progressing), None);
+    }
+
+    #[test]
+    fn test_rollback_verdict_tells_bad_canary_from_unhealthy_system() {
+        let rollout = rollout(serde_json::json!({}));
+        let analysis = rollout.spec.strategy.canary.unwrap().analysis.unwrap();
+        let config = stable_verification_config(&analysis);
+        let names: Vec<&str> = config.metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["error-rate", "latency-p99"]);
+
+        let recovered = rollback_verdict("t0", at(0), &config.metrics, &[true, true]);
+        assert_eq!(recovered.outcome, RollbackVerificationOutcome::Recovered);
+        assert!(recovered.failing_metrics.is_empty());
+
+        let unhealthy = rollback_verdict("t0", at(0), &config.metrics, &[false, true]);
+        assert_eq!(unhealthy.outcome, RollbackVerificationOutcome::Unhealthy);
+        assert_eq!(unhealthy.failing_metrics, ["error-rate"]);
+
+        let inconclusive = rollback_verdict("t0", at(0), &[], &[]);
+        assert_eq!(
+            inconclusive.outcome,
+            RollbackVerificationOutcome::Inconclusive
+        );
+    }
+}
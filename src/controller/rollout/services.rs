@@ -0,0 +1,253 @@
+//! Existence of the Services a Rollout routes to
+//!
+//! `stableService`/`canaryService`, `activeService`/`previewService` and the
+//! A/B variant Services are only names in the spec; traffic rules pointing
+//! at a Service that doesn't exist send requests nowhere while the rollout
+//! happily progresses. Before any ReplicaSet or route is touched, the
+//! Services are looked up; a missing one holds the rollout with a `Degraded`
+//! condition naming it, which is removed again once they all exist.
+//!
+//! Services found are remembered for [`SERVICE_CACHE_TTL`], so a running
+//! rollout doesn't read them on every reconcile. Missing ones are looked up
+//! again each time, so creating them unblocks the rollout right away.
+
+use crate::crd::rollout::{Rollout, RolloutCondition, RolloutStatus};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::Service;
+use kube::api::Api;
+use kube::{Client, ResourceExt};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a Service that was found counts as existing
+pub const SERVICE_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Condition type set while referenced Services are missing
+pub const DEGRADED_CONDITION: &str = "Degraded";
+
+/// Reason of the `Degraded` condition for missing Services
+pub const SERVICE_NOT_FOUND_REASON: &str = "ServiceNotFound";
+
+/// Services the Rollout's strategy routes to, in spec order
+pub fn referenced_services(rollout: &Rollout) -> Vec<&str> {
+    let strategy = &rollout.spec.strategy;
+    let mut services = Vec::new();
+    if let Some(canary) = &strategy.canary {
+        services.extend([
+            canary.stable_service.as_str(),
+            canary.canary_service.as_str(),
+        ]);
+    }
+    if let Some(blue_green) = &strategy.blue_green {
+        services.extend([
+            blue_green.active_service.as_str(),
+            blue_green.preview_service.as_str(),
+        ]);
+    }
+    if let Some(ab) = &strategy.ab_testing {
+        services.extend([ab.variant_a_service.as_str(), ab.variant_b_service.as_str()]);
+    }
+    let mut seen = Vec::new();
+    services.retain(|service| {
+        let first = !service.is_empty() && !seen.contains(service);
+        seen.push(*service);
+        first
+    });
+    services
+}
+
+/// Services found recently, per `namespace/name`
+#[derive(Default)]
+pub struct ServiceCache {
+    found: Mutex<HashMap<String, Instant>>,
+}
+
+impl ServiceCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_known(&self, key: &str) -> bool {
+        self.found
+            .lock()
+            .ok()
+            .and_then(|found| found.get(key).copied())
+            .is_some_and(|at| at.elapsed() < SERVICE_CACHE_TTL)
+    }
+
+    fn remember(&self, key: String, exists: bool) {
+        if let Ok(mut found) = self.found.lock() {
+            if exists {
+                found.insert(key, Instant::now());
+            } else {
+                found.remove(&key);
+            }
+        }
+    }
+
+    /// Referenced Services that don't exist in the Rollout's namespace
+    ///
+    /// # Returns
+    /// * `Ok(names)` - The missing Services (empty when all exist)
+    /// * `Err(_)` - A lookup failed; nothing is known about that Service
+    pub async fn missing_services(
+        &self,
+        rollout: &Rollout,
+        client: &Client,
+    ) -> Result<Vec<String>, kube::Error> {
+        let namespace = rollout.namespace().unwrap_or_default();
+        let api: Api<Service> = Api::namespaced(client.clone(), &namespace);
+        let mut missing = Vec::new();
+        for service in referenced_services(rollout) {
+            let key = format!("{}/{}", namespace, service);
+            if self.is_known(&key) {
+                continue;
+            }
+            let exists = api.get_opt(service).await?.is_some();
+            self.remember(key, exists);
+            if !exists {
+                missing.push(service.to_string());
+            }
+        }
+        Ok(missing)
+    }
+}
+
+/// Whether the status carries the `Degraded` condition for missing Services
+pub fn has_missing_services_condition(status: Option<&RolloutStatus>) -> bool {
+    status.is_some_and(|status| {
+        status.conditions.iter().any(|c| {
+            c.type_ == DEGRADED_CONDITION && c.reason.as_deref() == Some(SERVICE_NOT_FOUND_REASON)
+        })
+    })
+}
+
+/// Status of a Rollout held because Services are missing
+///
+/// The phase is kept; `lastTransitionTime` too while the condition was
+/// already set.
+pub fn missing_services_status(
+    rollout: &Rollout,
+    missing: &[String],
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let current = rollout.status.clone().unwrap_or_default();
+    let message = format!(
+        "Referenced Service{} not found in namespace {}: {}",
+        if missing.len() == 1 { "" } else { "s" },
+        rollout.namespace().unwrap_or_default(),
+        missing.join(", ")
+    );
+    let since = current
+        .conditions
+        .iter()
+        .find(|c| c.type_ == DEGRADED_CONDITION && c.status == "True")
+        .and_then(|c| c.last_transition_time.clone())
+        .unwrap_or_else(|| now.to_rfc3339());
+
+    let mut conditions: Vec<RolloutCondition> = current
+        .conditions
+        .iter()
+        .filter(|c| c.type_ != DEGRADED_CONDITION)
+        .cloned()
+        .collect();
+    conditions.push(RolloutCondition {
+        type_: DEGRADED_CONDITION.to_string(),
+        status: "True".to_string(),
+        reason: Some(SERVICE_NOT_FOUND_REASON.to_string()),
+        message: Some(message.clone()),
+        last_transition_time: Some(since),
+    });
+    RolloutStatus {
+        message: Some(message),
+        conditions,
+        ..current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::SimpleStrategy;
+
+    fn rollout() -> RolloutBuilder {
+        RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+    }
+
+    fn canary_rollout() -> Rollout {
+        rollout()
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [{ "setWeight": 100 }]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_referenced_services_per_strategy() {
+        assert_eq!(
+            referenced_services(&canary_rollout()),
+            vec!["shop", "shop-canary"]
+        );
+
+        let blue_green = rollout()
+            .blue_green(
+                serde_json::from_value(serde_json::json!({
+                    "activeService": "shop", "previewService": "shop"
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(referenced_services(&blue_green), vec!["shop"]);
+
+        let simple = rollout()
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+        assert!(referenced_services(&simple).is_empty());
+    }
+
+    #[test]
+    fn test_missing_services_status_keeps_transition_time() {
+        let mut shop = canary_rollout();
+        assert!(!has_missing_services_condition(shop.status.as_ref()));
+
+        let held = missing_services_status(&shop, &["shop-canary".to_string()], at(0));
+        assert_eq!(
+            held.message.as_deref(),
+            Some("Referenced Service not found in namespace prod: shop-canary")
+        );
+        assert!(has_missing_services_condition(Some(&held)));
+
+        shop.status = Some(held);
+        let still = missing_services_status(
+            &shop,
+            &["shop".to_string(), "shop-canary".to_string()],
+            at(60),
+        );
+        assert_eq!(still.conditions.len(), 1);
+        let condition = &still.conditions[0];
+        assert_eq!(condition.last_transition_time, Some(at(0).to_rfc3339()));
+        assert!(condition
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("shop, shop-canary"));
+    }
+}
@@ -1,8 +1,15 @@
-use crate::crd::rollout::{Phase, Rollout, RolloutStatus};
+use crate::controller::strategy_math::{current_step, next_step, step_at, step_weight};
+use crate::crd::rollout::{
+    AnalysisOnResume, Decision, DecisionAction, DecisionReason, Pause, Phase, Rollout,
+    RolloutStatus,
+};
 use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use std::time::Duration;
 use tracing::warn;
 
+use super::step_analysis::is_step_analysis_complete;
+use super::step_experiment::is_step_experiment_complete;
 use super::validation::parse_duration;
 
 /// Check if progress deadline has been exceeded
@@ -108,11 +115,14 @@ pub fn initialize_rollout_status(rollout: &Rollout, now: DateTime<Utc>) -> Rollo
     let first_step = canary_strategy.steps.first();
 
     // Get weight from first step (step 0)
-    let first_step_weight = first_step.and_then(|step| step.set_weight).unwrap_or(0);
+    let first_step_weight = first_step
+        .map(|step| step_weight(&rollout.spec, step))
+        .unwrap_or(0);
 
     let pause_start_time = first_step
         .filter(|step| step.pause.is_some())
         .map(|_| now.to_rfc3339());
+    let paused_until = first_step.and_then(|step| paused_until(step.pause.as_ref(), now));
 
     RolloutStatus {
         current_step_index: Some(0),
@@ -123,6 +133,7 @@ pub fn initialize_rollout_status(rollout: &Rollout, now: DateTime<Utc>) -> Rollo
             first_step_weight
         )),
         pause_start_time,
+        paused_until,
         progress_started_at: Some(now.to_rfc3339()),
         ..Default::default()
     }
@@ -132,9 +143,11 @@ pub fn initialize_rollout_status(rollout: &Rollout, now: DateTime<Utc>) -> Rollo
 ///
 /// Returns true if:
 /// - Current step has no pause defined
-/// - Phase is not "Paused"
-/// - Promote annotation is present (manual override)
-/// - Timed pause duration has elapsed
+/// - Phase is not "Paused" and the rollout wasn't aborted
+/// - The step's inline `analysis` has passed (or the promote annotation is set)
+/// - Promote annotation is present (manual override, except `untilApproved`)
+/// - `kulta.io/approved-by` is set at an `untilApproved` pause
+/// - Timed pause (`duration` or `until`) has ended
 ///
 /// # Arguments
 /// * `rollout` - The Rollout to check
@@ -153,57 +166,57 @@ pub fn should_progress_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> bo
         return false;
     }
 
-    // Get current step index
-    let current_step_index = match status.current_step_index {
-        Some(idx) => idx,
-        None => return false, // No step index, can't progress
-    };
-
-    // Get canary strategy
-    let canary_strategy = match &rollout.spec.strategy.canary {
-        Some(strategy) => strategy,
-        None => return false, // No canary strategy
-    };
+    // An aborted rollout stays on stable until retried
+    if status.aborted_at.is_some() {
+        return false;
+    }
 
-    // Get current step
-    let current_step = match canary_strategy.steps.get(current_step_index as usize) {
+    // Get current step (no canary strategy, no step index, or invalid index: can't progress)
+    let current_step = match current_step(&rollout.spec, Some(status)) {
         Some(step) => step,
-        None => return false, // Invalid step index
+        None => return false,
     };
 
+    // The step's inline analysis must pass first (manual promotion skips the wait)
+    if let Some(analysis) = &current_step.analysis {
+        if !has_promote_annotation(rollout) && !is_step_analysis_complete(analysis, status, now) {
+            return false;
+        }
+    }
+
+    // Likewise the step's experiment must have succeeded
+    if current_step.experiment.is_some()
+        && !has_promote_annotation(rollout)
+        && !is_step_experiment_complete(status)
+    {
+        return false;
+    }
+
     // Check if current step has pause
     if let Some(pause) = &current_step.pause {
-        // Check for manual promotion annotation
-        if has_promote_annotation(rollout) {
-            return true; // Manual promotion overrides pause
+        match pause {
+            // Only an explicit approval resumes, not kulta.io/promote
+            Pause::UntilApproved => return approved_by(rollout).is_some(),
+            // Manual promotion overrides every other pause
+            _ if has_promote_annotation(rollout) => return true,
+            Pause::Indefinite => return false,
+            Pause::Duration(_) | Pause::UntilTime(_) => {}
         }
 
-        // If pause has duration, check if elapsed
-        if let Some(duration_str) = &pause.duration {
-            if let Some(duration) = parse_duration(duration_str) {
-                // Check if pause started
-                if let Some(pause_start_str) = &status.pause_start_time {
-                    // Parse pause start time (RFC3339)
-                    match DateTime::parse_from_rfc3339(pause_start_str) {
-                        Ok(pause_start) => {
-                            let elapsed = now.signed_duration_since(pause_start);
-
-                            // If duration elapsed, can progress
-                            if elapsed.num_seconds() >= duration.as_secs() as i64 {
-                                return true;
-                            }
-                        }
-                        Err(e) => {
-                            warn!(error = %e, timestamp = %pause_start_str,
-                                "Failed to parse pause_start_time timestamp, treating as still paused");
-                        }
-                    }
-                }
+        // Timed pause: progress once it has ended
+        let Some(pause_start_str) = &status.pause_start_time else {
+            return false;
+        };
+        return match DateTime::parse_from_rfc3339(pause_start_str) {
+            Ok(pause_start) => {
+                pause_end(pause, pause_start.with_timezone(&Utc)).is_some_and(|end| now >= end)
             }
-        }
-
-        // Pause is active and duration not elapsed
-        return false;
+            Err(e) => {
+                warn!(error = %e, timestamp = %pause_start_str,
+                    "Failed to parse pause_start_time timestamp, treating as still paused");
+                false
+            }
+        };
     }
 
     // No pause - can progress
@@ -216,7 +229,7 @@ pub fn should_progress_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> bo
 /// should be written to K8s. It orchestrates initialization and progression.
 ///
 /// Logic:
-/// - If no status: initialize with step 0
+/// - If no status (or still Initializing): initialize with step 0
 /// - If status exists and should progress: advance to next step
 /// - Otherwise: keep current status
 ///
@@ -226,8 +239,12 @@ pub fn should_progress_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> bo
 /// # Returns
 /// The desired RolloutStatus that should be written to K8s
 pub fn compute_desired_status(rollout: &Rollout, now: DateTime<Utc>) -> RolloutStatus {
-    // If no status, initialize
-    if rollout.status.is_none() {
+    // If no status (or never started, e.g., held by a freeze or the concurrency limit), initialize
+    let initialized = rollout
+        .status
+        .as_ref()
+        .is_some_and(|s| !matches!(s.phase, Some(Phase::Initializing) | Some(Phase::Pending)));
+    if !initialized {
         return initialize_rollout_status(rollout, now);
     }
 
@@ -263,37 +280,48 @@ pub fn advance_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> RolloutSta
         }
     };
 
-    // Get current step index
-    let current_step_index = current_status.current_step_index.unwrap_or(-1);
-    let next_step_index = current_step_index + 1;
-
-    // Get canary strategy
-    let canary_strategy = match &rollout.spec.strategy.canary {
-        Some(strategy) => strategy,
+    let next = match next_step(&rollout.spec, Some(current_status)) {
+        Some(next) => next,
         None => {
             // No canary strategy - return current status
             return current_status.clone();
         }
     };
 
-    // Check if next step exists
-    if next_step_index as usize >= canary_strategy.steps.len() {
-        // Reached end of steps - mark as completed
+    // Leaving an untilApproved pause: record who signed it off
+    let approver = approved_by(rollout).filter(|_| is_awaiting_approval(rollout));
+    let mut decisions = current_status.decisions.clone();
+    if let Some(approver) = approver {
+        decisions.push(Decision {
+            timestamp: now.to_rfc3339(),
+            action: DecisionAction::Resume,
+            from_step: current_status.current_step_index,
+            to_step: Some(next.index),
+            reason: DecisionReason::ManualApproval,
+            message: Some(format!("Pause approved by {}", approver)),
+            metrics: None,
+            count: None,
+            last_timestamp: None,
+            approved_by: Some(approver.to_string()),
+        });
+    }
+
+    // Past the last step - mark as completed
+    let Some(step) = step_at(&rollout.spec, next.index) else {
         return RolloutStatus {
-            current_step_index: Some(next_step_index),
+            current_step_index: Some(next.index),
             current_weight: Some(100),
             phase: Some(Phase::Completed),
             message: Some("Rollout completed: 100% traffic to canary".to_string()),
+            decisions,
+            step_analysis: None,
+            step_experiment: None,
             ..current_status.clone()
         };
-    }
-
-    // Get weight from next step
-    let next_step = &canary_strategy.steps[next_step_index as usize];
-    let next_weight = next_step.set_weight.unwrap_or(0);
+    };
 
     // Check if this is the final step (100% canary)
-    let (phase, message) = if next_weight == 100 {
+    let (phase, mut message) = if next.completes {
         (
             Phase::Completed,
             "Rollout completed: 100% traffic to canary".to_string(),
@@ -301,15 +329,24 @@ pub fn advance_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> RolloutSta
     } else {
         (
             Phase::Progressing,
-            format!(
-                "Advanced to step {} ({}% traffic)",
-                next_step_index, next_weight
-            ),
+            format!("Advanced to step {} ({}% traffic)", next.index, next.weight),
+        )
+    };
+    if let Some(approver) = approver {
+        message.push_str(&format!(", approved by {}", approver));
+    }
+    let (step_start_time, metric_failures) = if resets_analysis_on_resume(rollout) {
+        message.push_str(", analysis restarted");
+        (Some(now.to_rfc3339()), None)
+    } else {
+        (
+            current_status.step_start_time.clone(),
+            current_status.metric_failures.clone(),
         )
     };
 
     // Check if next step has pause - set pause start time
-    let pause_start_time = if next_step.pause.is_some() {
+    let pause_start_time = if step.pause.is_some() {
         // Set pause start time to now (RFC3339)
         Some(now.to_rfc3339())
     } else {
@@ -318,15 +355,51 @@ pub fn advance_to_next_step(rollout: &Rollout, now: DateTime<Utc>) -> RolloutSta
     };
 
     RolloutStatus {
-        current_step_index: Some(next_step_index),
-        current_weight: Some(next_weight),
+        current_step_index: Some(next.index),
+        current_weight: Some(next.weight),
         phase: Some(phase),
         message: Some(message),
         pause_start_time,
+        paused_until: paused_until(step.pause.as_ref(), now),
+        step_start_time,
+        metric_failures,
+        decisions,
+        step_analysis: None,
+        step_experiment: None,
         ..current_status.clone()
     }
 }
 
+/// Whether the rollout is leaving its current step's pause by hand
+///
+/// An `untilApproved` pause is resumed by `kulta.io/approved-by`, any other
+/// pause by `kulta.io/promote`.
+pub fn resumes_from_manual_pause(rollout: &Rollout) -> bool {
+    current_step(&rollout.spec, rollout.status.as_ref())
+        .and_then(|step| step.pause.as_ref())
+        .is_some_and(|pause| match pause {
+            Pause::UntilApproved => approved_by(rollout).is_some(),
+            _ => has_promote_annotation(rollout),
+        })
+}
+
+/// Whether resuming from the current pause restarts the canary analysis
+///
+/// True with `analysis.onResume: Reset` while [`resumes_from_manual_pause`]:
+/// the warmup counts again from the resume and `status.metricFailures` is
+/// cleared. With `Continue` (the default) both carry over.
+pub fn resets_analysis_on_resume(rollout: &Rollout) -> bool {
+    let on_resume = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .and_then(|canary| canary.analysis.as_ref())
+        .and_then(|analysis| analysis.on_resume)
+        .unwrap_or_default();
+    on_resume == AnalysisOnResume::Reset && resumes_from_manual_pause(rollout)
+}
+
 /// Calculate optimal requeue interval based on rollout pause state
 ///
 /// This function reduces unnecessary API calls by calculating the next check time
@@ -395,22 +468,59 @@ pub(crate) fn calculate_requeue_interval_from_rollout(
         .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
         .map(|dt| dt.with_timezone(&Utc));
 
-    // Get current step's pause duration
-    let pause_duration = status.current_step_index.and_then(|step_index| {
-        rollout
-            .spec
-            .strategy
-            .canary
-            .as_ref()
-            .and_then(|canary| canary.steps.get(step_index as usize))
-            .and_then(|step| step.pause.as_ref())
-            .and_then(|pause| pause.duration.as_ref())
-            .and_then(|dur_str| parse_duration(dur_str))
+    // Get current step's pause length (timed pauses only)
+    let pause = current_step(&rollout.spec, Some(status)).and_then(|step| step.pause.as_ref());
+    let pause_duration = pause.zip(pause_start).and_then(|(pause, start)| {
+        pause_end(pause, start).and_then(|end| (end - start).to_std().ok())
     });
 
     calculate_requeue_interval(pause_start.as_ref(), pause_duration, now)
 }
 
+/// When a pause that started at `started` ends on its own
+///
+/// # Returns
+/// * `Some(end)` - For `duration` and `until` pauses
+/// * `None` - The pause waits for promotion or approval (or is invalid)
+pub fn pause_end(pause: &Pause, started: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match pause {
+        Pause::Duration(duration) => {
+            let duration = chrono::Duration::from_std(parse_duration(duration)?).ok()?;
+            Some(started + duration)
+        }
+        Pause::UntilTime(until) => DateTime::parse_from_rfc3339(until)
+            .ok()
+            .map(|until| until.with_timezone(&Utc)),
+        Pause::UntilApproved | Pause::Indefinite => None,
+    }
+}
+
+/// `status.pausedUntil` for a step entered at `now`
+fn paused_until(pause: Option<&Pause>, now: DateTime<Utc>) -> Option<String> {
+    pause_end(pause?, now).map(|end| end.to_rfc3339())
+}
+
+/// Annotation approving an `untilApproved` pause; the value names the approver
+pub const APPROVED_BY_ANNOTATION: &str = "kulta.io/approved-by";
+
+/// Approver named by `kulta.io/approved-by`, if the annotation is set
+pub fn approved_by(rollout: &Rollout) -> Option<&str> {
+    rollout
+        .metadata
+        .annotations
+        .as_ref()?
+        .get(APPROVED_BY_ANNOTATION)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+}
+
+/// Whether the rollout is at a step that pauses until approved
+pub fn is_awaiting_approval(rollout: &Rollout) -> bool {
+    current_step(&rollout.spec, rollout.status.as_ref())
+        .and_then(|step| step.pause.as_ref())
+        .is_some_and(|pause| *pause == Pause::UntilApproved)
+}
+
 /// Check if Rollout has the promote annotation (kulta.io/promote=true)
 ///
 /// This annotation is used to manually promote a rollout that is paused.
@@ -433,3 +543,171 @@ pub fn has_promote_annotation(rollout: &Rollout) -> bool {
         .map(|value| value == "true")
         .unwrap_or(false)
 }
+
+/// Annotation asking the controller to abort an unfinished rollout
+pub const ABORT_ANNOTATION: &str = "kulta.io/abort";
+
+/// Annotation asking the controller to restart a failed rollout from its first step
+pub const RETRY_ANNOTATION: &str = "kulta.io/retry";
+
+/// Check if Rollout has the abort annotation (kulta.io/abort=true)
+pub fn has_abort_annotation(rollout: &Rollout) -> bool {
+    has_true_annotation(rollout, ABORT_ANNOTATION)
+}
+
+fn has_true_annotation(rollout: &Rollout, key: &str) -> bool {
+    rollout
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(key))
+        .is_some_and(|value| value == "true")
+}
+
+/// Fail a rollout and send it back to stable
+///
+/// Sets `abortedAt`, which routes all traffic to stable and scales the
+/// canary (or blue-green preview) to zero until `kulta.io/retry`, and
+/// records a `Rollback` decision from the step the rollout was at.
+pub fn roll_back_to_stable(
+    rollout: &Rollout,
+    status: RolloutStatus,
+    reason: DecisionReason,
+    message: String,
+    now: DateTime<Utc>,
+) -> RolloutStatus {
+    let mut decisions = status.decisions.clone();
+    decisions.push(Decision {
+        timestamp: now.to_rfc3339(),
+        action: DecisionAction::Rollback,
+        from_step: rollout.status.as_ref().and_then(|s| s.current_step_index),
+        to_step: None,
+        reason,
+        message: Some(message.clone()),
+        metrics: None,
+        count: None,
+        last_timestamp: None,
+        approved_by: None,
+    });
+    RolloutStatus {
+        phase: Some(Phase::Failed),
+        current_weight: Some(0),
+        message: Some(message),
+        aborted_at: Some(now.to_rfc3339()),
+        decisions,
+        ..status
+    }
+}
+
+/// Apply a `kulta.io/abort` or `kulta.io/retry` request to the desired status
+///
+/// Abort fails a rollout that hasn't completed or failed and rolls it back
+/// with [`roll_back_to_stable`]. Retry restarts a Failed rollout from its
+/// first step, keeping the decision history. A request that doesn't fit the
+/// current phase is ignored.
+///
+/// # Returns
+/// The resulting status, and the annotation that was applied (to be removed)
+pub fn apply_abort_or_retry(
+    rollout: &Rollout,
+    desired: RolloutStatus,
+    now: DateTime<Utc>,
+) -> (RolloutStatus, Option<&'static str>) {
+    let phase = rollout.status.as_ref().and_then(|s| s.phase.as_ref());
+    let finished = matches!(phase, Some(Phase::Completed) | Some(Phase::Failed));
+
+    if has_abort_annotation(rollout) && !finished {
+        let message = format!("Aborted: {} annotation set", ABORT_ANNOTATION);
+        let aborted = roll_back_to_stable(
+            rollout,
+            desired,
+            DecisionReason::ManualRollback,
+            message,
+            now,
+        );
+        return (aborted, Some(ABORT_ANNOTATION));
+    }
+    if has_true_annotation(rollout, RETRY_ANNOTATION) && phase == Some(&Phase::Failed) {
+        let retried = RolloutStatus {
+            decisions: desired.decisions.clone(),
+            ..initialize_rollout_status(rollout, now)
+        };
+        return (retried, Some(RETRY_ANNOTATION));
+    }
+    (desired, None)
+}
+
+/// A pod selector in label selector string form (`status.selector`)
+///
+/// The scale subresource exposes it so an HPA can find the pods whose
+/// metrics it averages: stable and canary (or active and preview) alike.
+/// None for an empty selector, which would match every pod in the namespace.
+pub fn selector_string(selector: &LabelSelector) -> Option<String> {
+    let mut terms: Vec<String> = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    for expression in selector.match_expressions.iter().flatten() {
+        let values = expression.values.as_deref().unwrap_or_default().join(",");
+        terms.push(match expression.operator.as_str() {
+            "In" => format!("{} in ({})", expression.key, values),
+            "NotIn" => format!("{} notin ({})", expression.key, values),
+            "Exists" => expression.key.clone(),
+            "DoesNotExist" => format!("!{}", expression.key),
+            _ => continue,
+        });
+    }
+    (!terms.is_empty()).then(|| terms.join(","))
+}
+
+/// Decisions kept in `status.decisions` before it is compacted
+pub const MAX_DECISIONS: usize = 50;
+
+/// Keep the decision history within [`MAX_DECISIONS`] entries
+///
+/// Once the list outgrows the limit, runs of consecutive decisions with the
+/// same action, reason and steps (a rollback retried over and over) collapse
+/// into one entry with a `count` and the `lastTimestamp` of the run, keeping
+/// the latest message and metrics. If the list is still too long, the oldest
+/// entries are dropped.
+pub fn compact_decisions(decisions: Vec<Decision>) -> Vec<Decision> {
+    if decisions.len() <= MAX_DECISIONS {
+        return decisions;
+    }
+
+    let mut compacted: Vec<Decision> = Vec::with_capacity(decisions.len());
+    for decision in decisions {
+        match compacted.last_mut() {
+            Some(last) if is_similar_decision(last, &decision) => merge_decision(last, decision),
+            _ => compacted.push(decision),
+        }
+    }
+    let excess = compacted.len().saturating_sub(MAX_DECISIONS);
+    compacted.drain(..excess);
+    compacted
+}
+
+fn is_similar_decision(a: &Decision, b: &Decision) -> bool {
+    a.action == b.action
+        && a.reason == b.reason
+        && a.from_step == b.from_step
+        && a.to_step == b.to_step
+        && a.approved_by == b.approved_by
+}
+
+fn merge_decision(into: &mut Decision, next: Decision) {
+    into.count = Some(
+        into.count
+            .unwrap_or(1)
+            .saturating_add(next.count.unwrap_or(1)),
+    );
+    into.last_timestamp = Some(next.last_timestamp.unwrap_or(next.timestamp));
+    if next.message.is_some() {
+        into.message = next.message;
+    }
+    if next.metrics.is_some() {
+        into.metrics = next.metrics;
+    }
+}
@@ -0,0 +1,273 @@
+//! Inline analysis of canary steps
+//!
+//! A step's `analysis` is measured only while that step is active, on top of
+//! the strategy's analysis that runs for the whole rollout, so each traffic
+//! level can have its own thresholds. `count` measurements (one by default)
+//! are spread evenly over `duration` and tracked in `status.stepAnalysis`.
+//! A failing measurement rolls the canary back like the strategy's analysis
+//! does; the step doesn't advance before `count` measurements have passed
+//! and `duration` has elapsed. `kulta.io/promote` skips the wait.
+
+use super::reconcile::{
+    analysis_querier, analysis_revision_selector, render_analysis_queries, Context, ReconcileError,
+};
+use super::validation::parse_duration;
+use crate::controller::strategy_math::current_step;
+use crate::crd::rollout::{
+    AnalysisConfig, Rollout, RolloutStatus, StepAnalysis, StepAnalysisStatus,
+};
+use chrono::{DateTime, Utc};
+use kube::ResourceExt;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Outcome of [`measure_step_analysis`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepMeasurement {
+    /// No analysis at the current step, or no measurement due yet
+    NotDue,
+    /// The measurement passed; the progress to record in status
+    Passed(StepAnalysisStatus),
+    /// The measurement failed
+    Failed,
+}
+
+/// Analysis the step's metrics are evaluated with
+///
+/// Prometheus settings and `revisionLabelKey` come from the strategy's
+/// analysis; the step's metrics must all pass.
+pub fn step_analysis_config(rollout: &Rollout, analysis: &StepAnalysis) -> AnalysisConfig {
+    let strategy = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .and_then(|canary| canary.analysis.as_ref());
+    AnalysisConfig {
+        prometheus: strategy.and_then(|a| a.prometheus.clone()),
+        revision_label_key: strategy.and_then(|a| a.revision_label_key.clone()),
+        metrics: analysis.metrics.clone(),
+        ..AnalysisConfig::default()
+    }
+}
+
+/// Measurements recorded for the step the rollout is at
+fn current_progress(status: &RolloutStatus) -> Option<&StepAnalysisStatus> {
+    status
+        .step_analysis
+        .as_ref()
+        .filter(|progress| Some(progress.step_index) == status.current_step_index)
+}
+
+fn analysis_duration(analysis: &StepAnalysis) -> Duration {
+    analysis
+        .duration
+        .as_deref()
+        .and_then(parse_duration)
+        .unwrap_or_default()
+}
+
+/// Time between measurements: `duration` split over `count`
+fn measurement_interval(analysis: &StepAnalysis) -> Duration {
+    let count = u32::try_from(analysis.count.unwrap_or(1).max(1)).unwrap_or(1);
+    analysis_duration(analysis) / count
+}
+
+fn parse_time(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// When the next measurement of the step is due
+///
+/// The first measurement is due right away.
+pub fn next_measurement_at(
+    analysis: &StepAnalysis,
+    status: &RolloutStatus,
+) -> Option<DateTime<Utc>> {
+    let last = parse_time(current_progress(status)?.last_measured_at.as_deref())?;
+    let interval = chrono::Duration::from_std(measurement_interval(analysis)).ok()?;
+    Some(last + interval)
+}
+
+/// Whether the step's analysis should be measured now
+pub fn is_measurement_due(
+    analysis: &StepAnalysis,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> bool {
+    match next_measurement_at(analysis, status) {
+        Some(due) => now >= due,
+        None => true,
+    }
+}
+
+/// Progress after a passing measurement at the current step
+pub fn record_passed_measurement(status: &RolloutStatus, now: DateTime<Utc>) -> StepAnalysisStatus {
+    match current_progress(status) {
+        Some(progress) => StepAnalysisStatus {
+            passed: progress.passed.saturating_add(1),
+            last_measured_at: Some(now.to_rfc3339()),
+            ..progress.clone()
+        },
+        None => StepAnalysisStatus {
+            step_index: status.current_step_index.unwrap_or(0),
+            started_at: now.to_rfc3339(),
+            passed: 1,
+            last_measured_at: Some(now.to_rfc3339()),
+        },
+    }
+}
+
+/// Whether the step's analysis lets it advance
+///
+/// `count` measurements must have passed and `duration` have elapsed since
+/// the first one.
+pub fn is_step_analysis_complete(
+    analysis: &StepAnalysis,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(progress) = current_progress(status) else {
+        return false;
+    };
+    let Some(started) = parse_time(Some(&progress.started_at)) else {
+        return false;
+    };
+    let elapsed = now
+        .signed_duration_since(started)
+        .to_std()
+        .unwrap_or_default();
+    progress.passed >= analysis.count.unwrap_or(1) && elapsed >= analysis_duration(analysis)
+}
+
+/// How long until the current step's next measurement is due
+///
+/// # Returns
+/// * `Some(delay)` - The step has an analysis (zero when a measurement is due)
+/// * `None` - No analysis at the current step
+pub fn time_to_next_measurement(
+    rollout: &Rollout,
+    status: &RolloutStatus,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let analysis = current_step(&rollout.spec, Some(status))?
+        .analysis
+        .as_ref()?;
+    Some(
+        next_measurement_at(analysis, status)
+            .and_then(|due| (due - now).to_std().ok())
+            .unwrap_or_default(),
+    )
+}
+
+/// Measure the current step's analysis if a measurement is due
+///
+/// # Returns
+/// * `Ok(StepMeasurement)` - Whether a measurement was taken, and its result
+/// * `Err(_)` - Query execution failed
+pub(crate) async fn measure_step_analysis(
+    rollout: &Rollout,
+    ctx: &Context,
+) -> Result<StepMeasurement, ReconcileError> {
+    let Some(status) = rollout.status.as_ref() else {
+        return Ok(StepMeasurement::NotDue);
+    };
+    let Some(analysis) =
+        current_step(&rollout.spec, Some(status)).and_then(|s| s.analysis.as_ref())
+    else {
+        return Ok(StepMeasurement::NotDue);
+    };
+    let now = ctx.clock.now();
+    if !is_measurement_due(analysis, status, now) {
+        return Ok(StepMeasurement::NotDue);
+    }
+
+    let rollout_name = rollout.name_any();
+    let config = step_analysis_config(rollout, analysis);
+    let revision = analysis_revision_selector(rollout, &config)?;
+    let config = render_analysis_queries(rollout, &config, &revision)?;
+    let querier = analysis_querier(rollout, config.prometheus.as_ref(), ctx).await?;
+    let is_healthy = querier
+        .evaluate_analysis(&config, &rollout_name, &revision, "")
+        .await
+        .map_err(|e| ReconcileError::MetricsEvaluationFailed(e.to_string()))?;
+
+    if !is_healthy {
+        warn!(
+            rollout = rollout_name,
+            step = ?status.current_step_index,
+            "Step analysis failed"
+        );
+        return Ok(StepMeasurement::Failed);
+    }
+    let progress = record_passed_measurement(status, now);
+    debug!(
+        rollout = rollout_name,
+        step = progress.step_index,
+        passed = progress.passed,
+        "Step analysis measurement passed"
+    );
+    Ok(StepMeasurement::Passed(progress))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn analysis(duration: Option<&str>, count: Option<i32>) -> StepAnalysis {
+        StepAnalysis {
+            metrics: Vec::new(),
+            duration: duration.map(str::to_string),
+            count,
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn status_at_step(step: i32) -> RolloutStatus {
+        RolloutStatus {
+            current_step_index: Some(step),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_measurements_spread_over_duration() {
+        let analysis = analysis(Some("10m"), Some(2));
+        let mut status = status_at_step(1);
+        assert!(is_measurement_due(&analysis, &status, at(0)));
+        assert!(!is_step_analysis_complete(&analysis, &status, at(0)));
+
+        status.step_analysis = Some(record_passed_measurement(&status, at(0)));
+        assert_eq!(status.step_analysis.as_ref().unwrap().passed, 1);
+        assert!(!is_measurement_due(&analysis, &status, at(299)));
+        assert!(is_measurement_due(&analysis, &status, at(300)));
+
+        status.step_analysis = Some(record_passed_measurement(&status, at(300)));
+        let progress = status.step_analysis.as_ref().unwrap();
+        assert_eq!(progress.passed, 2);
+        assert_eq!(progress.started_at, at(0).to_rfc3339());
+        // Both measurements passed, but the 10m aren't over yet
+        assert!(!is_step_analysis_complete(&analysis, &status, at(300)));
+        assert!(is_step_analysis_complete(&analysis, &status, at(600)));
+    }
+
+    #[test]
+    fn test_progress_of_another_step_is_ignored() {
+        let analysis = analysis(None, None);
+        let mut status = status_at_step(1);
+        status.step_analysis = Some(record_passed_measurement(&status, at(0)));
+        assert!(is_step_analysis_complete(&analysis, &status, at(0)));
+
+        status.current_step_index = Some(2);
+        assert!(is_measurement_due(&analysis, &status, at(1)));
+        assert!(!is_step_analysis_complete(&analysis, &status, at(1)));
+        let restarted = record_passed_measurement(&status, at(1));
+        assert_eq!((restarted.step_index, restarted.passed), (2, 1));
+    }
+}
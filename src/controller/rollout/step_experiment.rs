@@ -0,0 +1,299 @@
+//! Experiments launched by canary steps
+//!
+//! When a step with `experiment` becomes active, an Experiment
+//! `{rollout}-{pod-template-hash}-{step}` is created with each template's
+//! pod spec taken from the stable ReplicaSet (`specRef: stable`) or
+//! `spec.template` (`specRef: canary`). The step waits for it in
+//! `status.stepExperiment`: it advances once the Experiment has succeeded,
+//! and a failed Experiment rolls the canary back like a failed analysis.
+//! `kulta.io/promote` skips the wait.
+//!
+//! The Experiment is owned by the Rollout, so it is deleted with it. A
+//! finished Experiment the status doesn't record (the step is tried again
+//! after `kulta.io/retry`) is deleted and launched anew.
+
+use super::history::rollback_template;
+use super::reconcile::{Context, ReconcileError};
+use super::replicaset::compute_pod_template_hash;
+use super::template_diff::baseline_replicaset_name;
+use crate::controller::orphans::ROLLOUT_ANNOTATION;
+use crate::controller::strategy_math::current_step;
+use crate::crd::cluster_rollout::rollout_owner_reference;
+use crate::crd::experiment::{Experiment, ExperimentPhase, ExperimentSpec, ExperimentTemplate};
+use crate::crd::rollout::{
+    ExperimentSpecRef, ExperimentStep, Rollout, RolloutStatus, StepExperimentStatus,
+};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{Api, DeleteParams, ObjectMeta, PostParams};
+use kube::ResourceExt;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// How often a Rollout waiting for its step's Experiment looks at it
+pub const STEP_EXPERIMENT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Outcome of [`run_step_experiment`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepExperimentOutcome {
+    /// No experiment at the current step
+    NotAtStep,
+    /// The step's Experiment, as last read; the progress to record in status
+    Observed(StepExperimentStatus),
+    /// The step's Experiment failed
+    Failed,
+}
+
+/// Name of the Experiment a step launches for a revision
+pub fn step_experiment_name(rollout: &Rollout, pod_template_hash: &str, step_index: i32) -> String {
+    format!(
+        "{}-{}-{}",
+        rollout.name_any(),
+        pod_template_hash,
+        step_index
+    )
+}
+
+/// Build the Experiment of a canary step
+///
+/// # Returns
+/// * `Ok(experiment)` - Owned by the Rollout, annotated with its name
+/// * `Err(message)` - A template refers to the stable pod template, but
+///   there is none (no stable ReplicaSet yet)
+pub fn build_step_experiment(
+    rollout: &Rollout,
+    step: &ExperimentStep,
+    name: &str,
+    stable: Option<&PodTemplateSpec>,
+) -> Result<Experiment, String> {
+    let templates = step
+        .templates
+        .iter()
+        .map(|template| {
+            let pod_template = match template.spec_ref {
+                ExperimentSpecRef::Canary => rollout.spec.template.clone(),
+                ExperimentSpecRef::Stable => stable.cloned().ok_or_else(|| {
+                    format!(
+                        "experiment template {} needs the stable pod template, but there is no stable ReplicaSet",
+                        template.name
+                    )
+                })?,
+            };
+            Ok(ExperimentTemplate {
+                name: template.name.clone(),
+                replicas: template.replicas,
+                template: pod_template,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(Experiment {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            namespace: rollout.namespace(),
+            annotations: Some(BTreeMap::from([(
+                ROLLOUT_ANNOTATION.to_string(),
+                rollout.name_any(),
+            )])),
+            owner_references: rollout_owner_reference(rollout).map(|owner| vec![owner]),
+            ..Default::default()
+        },
+        spec: ExperimentSpec {
+            templates,
+            duration: step.duration.clone(),
+            analyses: step.analyses.clone(),
+        },
+        status: None,
+    })
+}
+
+/// Whether the current step's Experiment has succeeded
+pub fn is_step_experiment_complete(status: &RolloutStatus) -> bool {
+    status.step_experiment.as_ref().is_some_and(|experiment| {
+        Some(experiment.step_index) == status.current_step_index
+            && experiment.phase == Some(ExperimentPhase::Successful)
+    })
+}
+
+/// Pod template of the stable ReplicaSet, if there is one
+async fn stable_template(
+    rollout: &Rollout,
+    rs_api: &Api<ReplicaSet>,
+) -> Result<Option<PodTemplateSpec>, ReconcileError> {
+    let stable = rs_api.get_opt(&baseline_replicaset_name(rollout)).await?;
+    Ok(stable.as_ref().and_then(rollback_template))
+}
+
+/// Launch or check the current step's Experiment
+///
+/// # Returns
+/// * `Ok(StepExperimentOutcome)` - The Experiment's phase (`Observed` with no
+///   phase right after it was created)
+/// * `Err(_)` - The Experiment couldn't be read or created
+pub(crate) async fn run_step_experiment(
+    rollout: &Rollout,
+    ctx: &Context,
+) -> Result<StepExperimentOutcome, ReconcileError> {
+    let Some(status) = rollout.status.as_ref() else {
+        return Ok(StepExperimentOutcome::NotAtStep);
+    };
+    let Some(step) = current_step(&rollout.spec, Some(status)).and_then(|s| s.experiment.as_ref())
+    else {
+        return Ok(StepExperimentOutcome::NotAtStep);
+    };
+    let namespace = rollout
+        .namespace()
+        .ok_or(ReconcileError::MissingNamespace)?;
+    let step_index = status.current_step_index.unwrap_or(0);
+    let hash = compute_pod_template_hash(&rollout.spec.template)?;
+    let name = step_experiment_name(rollout, &hash, step_index);
+
+    let api: Api<Experiment> = Api::namespaced(ctx.client.clone(), &namespace);
+    let recorded = status
+        .step_experiment
+        .as_ref()
+        .is_some_and(|e| e.step_index == step_index && e.name == name);
+    let phase = match api.get_opt(&name).await? {
+        // Left over from an earlier attempt at the step (before a retry):
+        // replaced by a fresh Experiment on the next reconcile
+        Some(existing)
+            if !recorded
+                && existing
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.phase)
+                    .is_some_and(ExperimentPhase::is_finished) =>
+        {
+            api.delete(&name, &DeleteParams::background()).await?;
+            info!(
+                rollout = ?rollout.name_any(),
+                experiment = ?name,
+                "Deleted finished experiment of an earlier attempt at the step"
+            );
+            None
+        }
+        Some(existing) => existing.status.and_then(|s| s.phase),
+        None => {
+            let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &namespace);
+            let stable = stable_template(rollout, &rs_api).await?;
+            let experiment = build_step_experiment(rollout, step, &name, stable.as_ref())
+                .map_err(ReconcileError::ValidationError)?;
+            api.create(&PostParams::default(), &experiment).await?;
+            info!(
+                rollout = ?rollout.name_any(),
+                step = step_index,
+                experiment = ?name,
+                "Launched step experiment"
+            );
+            None
+        }
+    };
+
+    if phase == Some(ExperimentPhase::Failed) {
+        warn!(
+            rollout = ?rollout.name_any(),
+            step = step_index,
+            experiment = ?name,
+            "Step experiment failed"
+        );
+        return Ok(StepExperimentOutcome::Failed);
+    }
+    Ok(StepExperimentOutcome::Observed(StepExperimentStatus {
+        step_index,
+        name,
+        phase,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout() -> Rollout {
+        let mut rollout = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 10 },
+                        { "setWeight": 20, "experiment": {
+                            "duration": "10m",
+                            "templates": [
+                                { "name": "baseline", "specRef": "stable", "replicas": 2 },
+                                { "name": "canary", "specRef": "canary" }
+                            ]
+                        } }
+                    ]
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+        rollout.metadata.uid = Some("uid-1".to_string());
+        rollout
+    }
+
+    fn experiment_step(rollout: &Rollout) -> &ExperimentStep {
+        rollout.spec.strategy.canary.as_ref().unwrap().steps[1]
+            .experiment
+            .as_ref()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_build_step_experiment_from_stable_and_canary() {
+        let rollout = rollout();
+        let stable: PodTemplateSpec = serde_json::from_value(serde_json::json!({
+            "spec": { "containers": [{ "name": "app", "image": "shop:1" }] }
+        }))
+        .unwrap();
+        let name = step_experiment_name(&rollout, "abc123", 1);
+        assert_eq!(name, "shop-abc123-1");
+
+        let experiment =
+            build_step_experiment(&rollout, experiment_step(&rollout), &name, Some(&stable))
+                .unwrap();
+        assert_eq!(experiment.annotations()[ROLLOUT_ANNOTATION], "shop");
+        assert_eq!(
+            experiment.metadata.owner_references.unwrap()[0].kind,
+            "Rollout"
+        );
+        let templates = &experiment.spec.templates;
+        assert_eq!((templates[0].replicas, templates[1].replicas), (2, 1));
+        assert_eq!(templates[0].template, stable);
+        assert_eq!(templates[1].template, rollout.spec.template);
+
+        let err =
+            build_step_experiment(&rollout, experiment_step(&rollout), &name, None).unwrap_err();
+        assert!(err.contains("no stable ReplicaSet"));
+    }
+
+    #[test]
+    fn test_step_experiment_completes_only_at_its_step() {
+        let mut status = RolloutStatus {
+            current_step_index: Some(1),
+            step_experiment: Some(StepExperimentStatus {
+                step_index: 1,
+                name: "shop-abc123-1".to_string(),
+                phase: Some(ExperimentPhase::Running),
+            }),
+            ..Default::default()
+        };
+        assert!(!is_step_experiment_complete(&status));
+
+        if let Some(experiment) = status.step_experiment.as_mut() {
+            experiment.phase = Some(ExperimentPhase::Successful);
+        }
+        assert!(is_step_experiment_complete(&status));
+
+        status.current_step_index = Some(2);
+        assert!(!is_step_experiment_complete(&status));
+    }
+}
@@ -0,0 +1,258 @@
+//! One-line status summary shown by `kubectl get rollouts`
+//!
+//! `status.summary` condenses what Phase alone hides: where the rollout is
+//! (weight and step), how its analysis looks, and what it waits for, e.g.
+//! `canary 40% (step 3/6), analysis healthy, next advance at
+//! 2026-03-02T09:00:00Z`.
+//!
+//! Only status and spec go into it, never the current time: a countdown
+//! would change the status on every reconcile, and every status change is
+//! patched and emitted as a CDEvent and an occurrence.
+
+use crate::controller::cdevents_data::StrategyKind;
+use crate::controller::strategy_math::{canary_steps, current_step};
+use crate::crd::rollout::{Pause, Phase, Rollout, RolloutStatus};
+
+/// Summarize `status` of `rollout` in one line
+pub fn status_summary(rollout: &Rollout, status: &RolloutStatus) -> String {
+    let label = strategy_label(status.strategy);
+    let mut parts = vec![position(rollout, status, label)];
+    if in_flight(status) {
+        if status.weight_override.is_some() {
+            parts.push("weight set manually".to_string());
+        }
+        parts.extend(analysis(rollout, status));
+        parts.extend(next(rollout, status));
+    }
+    parts.join(", ")
+}
+
+/// How the strategy is named in the summary
+fn strategy_label(strategy: Option<StrategyKind>) -> &'static str {
+    match strategy {
+        Some(StrategyKind::AbTesting) => "a/b test",
+        Some(StrategyKind::Simple) => "rolling",
+        Some(strategy) => strategy.as_str(),
+        None => "rollout",
+    }
+}
+
+fn in_flight(status: &RolloutStatus) -> bool {
+    status.aborted_at.is_none()
+        && matches!(
+            status.phase,
+            Some(Phase::Progressing | Phase::Paused | Phase::Preview | Phase::Experimenting)
+        )
+}
+
+/// Weight and step, or what the phase means
+fn position(rollout: &Rollout, status: &RolloutStatus, label: &str) -> String {
+    let weight = status.current_weight.unwrap_or(0);
+    let steps = canary_steps(&rollout.spec).len();
+    let step = status
+        .current_step_index
+        .and_then(|index| usize::try_from(index).ok())
+        .filter(|index| *index < steps)
+        .map(|index| format!("step {}/{}", index + 1, steps));
+    let at = |head: String| match &step {
+        Some(step) => format!("{} ({})", head, step),
+        None => head,
+    };
+
+    if status.aborted_at.is_some() {
+        return format!("{} aborted", label);
+    }
+    match status.phase {
+        None | Some(Phase::Initializing) => match &status.active_freeze {
+            Some(freeze) => format!("{} held by freeze {}", label, freeze.name),
+            None => format!("{} initializing", label),
+        },
+        Some(Phase::Pending) => format!("{} waiting for a rollout slot", label),
+        Some(Phase::Progressing) => at(format!("{} {}%", label, weight)),
+        Some(Phase::Paused) => at(format!("{} paused at {}%", label, weight)),
+        Some(Phase::Preview) => format!("{} preview ready", label),
+        Some(Phase::Experimenting) => format!("{} experimenting", label),
+        Some(Phase::Concluded) => format!("{} concluded", label),
+        Some(Phase::Completed) => format!("{} completed", label),
+        Some(Phase::Failed) => match &step {
+            Some(step) => format!("{} failed at {}", label, step),
+            None => format!("{} failed", label),
+        },
+    }
+}
+
+/// Analysis verdict so far, if the rollout is analyzed
+fn analysis(rollout: &Rollout, status: &RolloutStatus) -> Option<String> {
+    let strategy = &rollout.spec.strategy;
+    let analyzed = strategy
+        .canary
+        .as_ref()
+        .is_some_and(|canary| canary.analysis.is_some())
+        || strategy
+            .blue_green
+            .as_ref()
+            .is_some_and(|blue_green| blue_green.analysis.is_some());
+    let failing: Vec<String> = status
+        .metric_failures
+        .iter()
+        .flatten()
+        .filter(|failure| failure.consecutive_failures > 0)
+        .map(|failure| format!("{} x{}", failure.metric, failure.consecutive_failures))
+        .collect();
+
+    if !failing.is_empty() {
+        Some(format!("analysis failing ({})", failing.join(", ")))
+    } else if analyzed {
+        Some("analysis healthy".to_string())
+    } else {
+        None
+    }
+}
+
+/// What the rollout waits for before moving on
+fn next(rollout: &Rollout, status: &RolloutStatus) -> Option<String> {
+    if status.phase == Some(Phase::Preview) {
+        return Some("awaiting promotion".to_string());
+    }
+    let step = current_step(&rollout.spec, Some(status))?;
+    if step.analysis.is_some() {
+        let passed = status
+            .step_analysis
+            .as_ref()
+            .filter(|progress| Some(progress.step_index) == status.current_step_index)
+            .map_or(0, |progress| progress.passed);
+        return Some(format!("step analysis running ({} passed)", passed));
+    }
+    if step.experiment.is_some() {
+        return Some("step experiment running".to_string());
+    }
+    match step.pause.as_ref()? {
+        Pause::UntilApproved => Some("awaiting approval".to_string()),
+        Pause::Indefinite => Some("awaiting promotion".to_string()),
+        Pause::Duration(_) | Pause::UntilTime(_) => status
+            .paused_until
+            .as_ref()
+            .map(|until| format!("next advance at {}", until)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{ActiveFreeze, MetricFailureCount};
+
+    fn rollout() -> Rollout {
+        RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 20, "pause": { "duration": "5m" } },
+                        { "setWeight": 40, "pause": {} },
+                        { "setWeight": 60, "pause": { "untilApproved": true } },
+                        { "setWeight": 100 }
+                    ],
+                    "analysis": { "metrics": [] }
+                }))
+                .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn status(phase: Phase, step: i32, weight: i32) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(phase),
+            current_step_index: Some(step),
+            current_weight: Some(weight),
+            strategy: Some(StrategyKind::Canary),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_canary_summary_by_pause_kind() {
+        let rollout = rollout();
+
+        let mut timed = status(Phase::Progressing, 0, 20);
+        timed.paused_until = Some("2026-03-02T09:05:00+00:00".to_string());
+        assert_eq!(
+            status_summary(&rollout, &timed),
+            "canary 20% (step 1/4), analysis healthy, next advance at 2026-03-02T09:05:00+00:00"
+        );
+        assert_eq!(
+            status_summary(&rollout, &status(Phase::Paused, 1, 40)),
+            "canary paused at 40% (step 2/4), analysis healthy, awaiting promotion"
+        );
+        assert_eq!(
+            status_summary(&rollout, &status(Phase::Paused, 2, 60)),
+            "canary paused at 60% (step 3/4), analysis healthy, awaiting approval"
+        );
+    }
+
+    #[test]
+    fn test_summary_lists_failing_metrics() {
+        let mut failing = status(Phase::Progressing, 3, 100);
+        failing.metric_failures = Some(vec![MetricFailureCount {
+            metric: "error-rate".to_string(),
+            consecutive_failures: 2,
+            last_evaluation_time: None,
+        }]);
+
+        assert_eq!(
+            status_summary(&rollout(), &failing),
+            "canary 100% (step 4/4), analysis failing (error-rate x2)"
+        );
+    }
+
+    #[test]
+    fn test_summary_of_settled_phases() {
+        let rollout = rollout();
+
+        assert_eq!(
+            status_summary(&rollout, &status(Phase::Completed, 4, 100)),
+            "canary completed"
+        );
+        assert_eq!(
+            status_summary(&rollout, &status(Phase::Failed, 1, 0)),
+            "canary failed at step 2/4"
+        );
+
+        let mut aborted = status(Phase::Progressing, 1, 0);
+        aborted.aborted_at = Some("2026-03-02T09:00:00Z".to_string());
+        assert_eq!(status_summary(&rollout, &aborted), "canary aborted");
+
+        let mut frozen = status(Phase::Initializing, 0, 0);
+        frozen.active_freeze = Some(ActiveFreeze {
+            name: "black-friday".to_string(),
+            until: "2026-11-30T00:00:00Z".to_string(),
+        });
+        assert_eq!(
+            status_summary(&rollout, &frozen),
+            "canary held by freeze black-friday"
+        );
+    }
+
+    #[test]
+    fn test_summary_names_other_strategies() {
+        let rollout = rollout();
+
+        let mut preview = status(Phase::Preview, 0, 0);
+        preview.strategy = Some(StrategyKind::BlueGreen);
+        preview.current_step_index = None;
+        assert_eq!(
+            status_summary(&rollout, &preview),
+            "blue-green preview ready, analysis healthy, awaiting promotion"
+        );
+
+        let mut rolling = status(Phase::Completed, 0, 100);
+        rolling.strategy = Some(StrategyKind::Simple);
+        assert_eq!(status_summary(&rollout, &rolling), "rolling completed");
+    }
+}
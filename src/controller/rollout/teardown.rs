@@ -0,0 +1,194 @@
+//! Finalizer-based teardown of a deleted Rollout's managed resources
+//!
+//! Every Rollout carries the `kulta.io/finalizer` finalizer. When it is
+//! deleted, the controller resets the HTTPRoutes it manages to the stable
+//! (active, variant A) Service alone, deletes the Rollout's ReplicaSets and
+//! emits a terminal `service.removed` CDEvent and `rollout.removed`
+//! occurrence before releasing the finalizer. Without it the routes would
+//! keep splitting traffic to a canary or preview Service with no pods behind
+//! it.
+
+use super::history::list_owned_replicasets;
+use super::reconcile::{Context, ReconcileError};
+use super::traffic::{default_service_port, CanaryStepRoutes};
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::controller::strategies::ab_testing::build_ab_testing_httproute_rules;
+use crate::controller::strategies::{
+    gateway_route_api_resource, get_gateway_api_routing, managed_httproute_names,
+    patch_httproute_with_retry, route_patch,
+};
+use crate::crd::rollout::Rollout;
+use gateway_api::apis::standard::httproutes::{HTTPRouteRules, HTTPRouteRulesBackendRefs};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use kube::api::{Api, DeleteParams};
+use kube::core::DynamicObject;
+use kube::ResourceExt;
+use tracing::{info, warn};
+
+/// Finalizer holding a deleted Rollout until its resources are torn down
+pub const FINALIZER: &str = "kulta.io/finalizer";
+
+/// Whether the Rollout carries the KULTA finalizer
+pub fn has_finalizer(rollout: &Rollout) -> bool {
+    rollout.finalizers().iter().any(|f| f == FINALIZER)
+}
+
+/// Whether the Rollout has been deleted and waits for its finalizers
+pub fn is_being_deleted(rollout: &Rollout) -> bool {
+    rollout.metadata.deletion_timestamp.is_some()
+}
+
+/// The Rollout's finalizers with the KULTA finalizer added
+pub fn finalizers_with(rollout: &Rollout) -> Vec<String> {
+    let mut finalizers = rollout.finalizers().to_vec();
+    if !has_finalizer(rollout) {
+        finalizers.push(FINALIZER.to_string());
+    }
+    finalizers
+}
+
+/// The Rollout's finalizers with the KULTA finalizer removed
+pub fn finalizers_without(rollout: &Rollout) -> Vec<String> {
+    rollout
+        .finalizers()
+        .iter()
+        .filter(|f| *f != FINALIZER)
+        .cloned()
+        .collect()
+}
+
+/// Merge patch setting the Rollout's finalizers
+///
+/// Carries the observed `resourceVersion` so a concurrent change to the list
+/// (another controller's finalizer) conflicts instead of being overwritten.
+pub fn finalizers_patch(rollout: &Rollout, finalizers: Vec<String>) -> serde_json::Value {
+    let mut metadata = serde_json::json!({ "finalizers": finalizers });
+    if let Some(resource_version) = rollout.resource_version() {
+        metadata["resourceVersion"] = serde_json::Value::String(resource_version);
+    }
+    serde_json::json!({ "metadata": metadata })
+}
+
+/// Backend refs left on a canary or blue-green HTTPRoute after teardown
+///
+/// Only the stable (canary) or active (blue-green) Service remains, at
+/// weight 100. Empty for strategies without weighted routing.
+pub fn teardown_backend_refs(rollout: &Rollout) -> Vec<HTTPRouteRulesBackendRefs> {
+    let strategy = &rollout.spec.strategy;
+    let (service, port) = if let Some(canary) = &strategy.canary {
+        (&canary.stable_service, canary.port)
+    } else if let Some(blue_green) = &strategy.blue_green {
+        (&blue_green.active_service, blue_green.port)
+    } else {
+        return vec![];
+    };
+
+    vec![HTTPRouteRulesBackendRefs {
+        name: service.clone(),
+        port: Some(default_service_port(port)),
+        weight: Some(100),
+        kind: Some("Service".to_string()),
+        group: Some("".to_string()),
+        namespace: None,
+        filters: None,
+    }]
+}
+
+/// Rules left on an A/B testing HTTPRoute after teardown
+///
+/// Only the default rule to variant A remains; the header and cookie matches
+/// for variant B are dropped. Empty for other strategies.
+pub fn teardown_ab_rules(rollout: &Rollout) -> Vec<HTTPRouteRules> {
+    rollout
+        .spec
+        .strategy
+        .ab_testing
+        .as_ref()
+        .and_then(|ab| build_ab_testing_httproute_rules(ab).pop())
+        .into_iter()
+        .collect()
+}
+
+/// Point the Rollout's HTTPRoutes (or TLSRoutes/UDPRoutes) back at the stable Service alone
+///
+/// HTTPRoutes that no longer exist are skipped, so a route deleted before
+/// the Rollout never blocks its deletion.
+pub async fn reset_traffic(rollout: &Rollout, ctx: &Context) -> Result<(), ReconcileError> {
+    let namespace = rollout
+        .namespace()
+        .ok_or(ReconcileError::MissingNamespace)?;
+
+    let patch_json = if rollout.spec.strategy.ab_testing.is_some() {
+        serde_json::json!({ "spec": { "rules": teardown_ab_rules(rollout) } })
+    } else {
+        serde_json::json!({
+            "spec": { "rules": [{ "backendRefs": teardown_backend_refs(rollout) }] }
+        })
+    };
+
+    // Canary and blue-green may route through TLSRoutes or UDPRoutes
+    let kind = get_gateway_api_routing(rollout)
+        .map(|routing| routing.kind)
+        .unwrap_or_default();
+    let ar = gateway_route_api_resource(kind);
+    let httproute_api: Api<DynamicObject> =
+        Api::namespaced_with(ctx.client.clone(), &namespace, &ar);
+
+    for httproute_name in managed_httproute_names(rollout) {
+        // A route limited to a section only has that rule reset
+        let patch = match get_gateway_api_routing(rollout) {
+            Some(routing) if rollout.spec.strategy.ab_testing.is_none() => {
+                route_patch(
+                    &httproute_api,
+                    &httproute_name,
+                    routing,
+                    &patch_json,
+                    &CanaryStepRoutes::default(),
+                    &teardown_backend_refs(rollout),
+                )
+                .await?
+            }
+            _ => Some(patch_json.clone()),
+        };
+        let Some(patch) = patch else {
+            warn!(httproute = ?httproute_name, "HTTPRoute not found - skipping teardown");
+            continue;
+        };
+        if patch_httproute_with_retry(&httproute_api, &httproute_name, &patch)
+            .await?
+            .is_some()
+        {
+            info!(httproute = ?httproute_name, "Reset HTTPRoute of deleted Rollout");
+        } else {
+            warn!(httproute = ?httproute_name, "HTTPRoute not found - skipping teardown");
+        }
+    }
+    Ok(())
+}
+
+/// Delete every ReplicaSet the Rollout manages, history included
+///
+/// # Returns
+/// Number of ReplicaSets deleted
+pub async fn delete_managed_replicasets(
+    rollout: &Rollout,
+    ctx: &Context,
+    namespace: &str,
+) -> Result<usize, ReconcileError> {
+    let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), namespace);
+    let owned = list_owned_replicasets(rollout, ctx, namespace).await?;
+
+    for rs in &owned {
+        let rs_name = rs.name_any();
+        rs_api.delete(&rs_name, &DeleteParams::background()).await?;
+        audit::record(
+            AuditOperation::Delete,
+            AuditObjectRef::new("ReplicaSet", namespace, &rs_name),
+            "managed ReplicaSet",
+            "rollout deleted",
+        )
+        .await;
+        info!(replicaset = ?rs_name, "Deleted ReplicaSet of deleted Rollout");
+    }
+    Ok(owned.len())
+}
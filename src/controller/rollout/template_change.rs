@@ -0,0 +1,304 @@
+//! Pod template changes while a revision is in flight
+//!
+//! `spec.onTemplateChange` decides what a `spec.template` edit does to a
+//! rollout that has started but neither completed nor failed:
+//!
+//! - `replace` (default): the in-flight revision is abandoned and the new
+//!   template starts over from the first step, as the next revision
+//! - `queue`: the in-flight revision keeps rolling out; the new template
+//!   starts once it has completed or failed. A `RevisionQueued` condition
+//!   names the waiting template
+//! - `reject`: the admission webhook denies the edit. One that gets past it
+//!   anyway (webhook not installed) is queued
+//!
+//! A queued change needs the in-flight template, which is read back from
+//! its history ReplicaSet. Without it the change replaces the revision.
+
+use super::history::{history_replicaset_name, rollback_template, ROLLBACK_TO_REVISION_ANNOTATION};
+use super::replicaset::compute_pod_template_hash;
+use crate::crd::rollout::{Phase, Rollout, RolloutCondition, RolloutStatus, TemplateChangePolicy};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::Api;
+use kube::{Client, ResourceExt};
+use tracing::warn;
+
+/// Condition type set while a template change waits for the in-flight revision
+pub const REVISION_QUEUED_CONDITION: &str = "RevisionQueued";
+
+/// Reason of the `RevisionQueued` condition
+pub const TEMPLATE_CHANGED_REASON: &str = "TemplateChangedMidRollout";
+
+/// Whether a revision is being rolled out: started, but not yet completed or failed
+pub fn is_in_flight(status: Option<&RolloutStatus>) -> bool {
+    status.is_some_and(|status| {
+        matches!(
+            status.phase,
+            Some(
+                Phase::Progressing
+                    | Phase::Paused
+                    | Phase::Preview
+                    | Phase::Experimenting
+                    | Phase::Concluded
+            )
+        )
+    })
+}
+
+/// Hash of the in-flight revision, if `pod_template_hash` is a different template
+pub fn in_flight_hash<'a>(rollout: &'a Rollout, pod_template_hash: &str) -> Option<&'a str> {
+    rollout
+        .status
+        .as_ref()
+        .filter(|status| is_in_flight(Some(*status)))
+        .and_then(|status| status.pod_template_hash.as_deref())
+        .filter(|recorded| *recorded != pod_template_hash)
+}
+
+/// What the controller does with a template change: replace or queue
+///
+/// `reject` is enforced by the webhook; a change the controller still sees
+/// is queued, so the in-flight revision isn't lost to it.
+pub fn template_change_action(rollout: &Rollout) -> TemplateChangePolicy {
+    match rollout.spec.on_template_change.unwrap_or_default() {
+        TemplateChangePolicy::Reject => TemplateChangePolicy::Queue,
+        policy => policy,
+    }
+}
+
+/// The Rollout as if its in-flight revision had never started
+///
+/// The status goes back to `Initializing`, so the strategy starts the new
+/// template from its first step. The recorded hash, revision and conditions
+/// stay, so the template still counts (and is diffed) as the next revision.
+pub fn restart_for_new_template(rollout: &Rollout) -> Rollout {
+    let mut restarted = rollout.clone();
+    if let Some(status) = restarted.status.as_mut() {
+        *status = RolloutStatus {
+            phase: Some(Phase::Initializing),
+            pod_template_hash: status.pod_template_hash.take(),
+            revision: status.revision,
+            template_diff: status.template_diff.take(),
+            conditions: std::mem::take(&mut status.conditions),
+            ..Default::default()
+        };
+    }
+    restarted
+}
+
+/// Pod template of the in-flight revision, from its history ReplicaSet
+///
+/// # Returns
+/// * `Some(template)` - The stored template, which hashes to `hash`
+/// * `None` - No history ReplicaSet for `hash`, or it can't be read (logged)
+pub async fn in_flight_template(
+    rollout: &Rollout,
+    client: &Client,
+    hash: &str,
+) -> Option<PodTemplateSpec> {
+    let namespace = rollout.namespace()?;
+    let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+    let rs_name = history_replicaset_name(rollout, hash);
+    let history = match rs_api.get_opt(&rs_name).await {
+        Ok(history) => history?,
+        Err(e) => {
+            warn!(
+                rollout = ?rollout.name_any(),
+                replicaset = ?rs_name,
+                error = ?e,
+                "Failed to read the in-flight revision's history ReplicaSet"
+            );
+            return None;
+        }
+    };
+    rollback_template(&history)
+        .filter(|template| compute_pod_template_hash(template).is_ok_and(|h| h == hash))
+}
+
+/// Conditions after reporting a queued template, or None when already reported
+///
+/// `queued` is the hash of the waiting template; `None` removes the
+/// `RevisionQueued` condition once the queued template has started.
+pub fn queued_revision_conditions(
+    status: Option<&RolloutStatus>,
+    queued: Option<&str>,
+    in_flight: &str,
+    now: &str,
+) -> Option<Vec<RolloutCondition>> {
+    let current = status.map(|s| s.conditions.as_slice()).unwrap_or_default();
+    let existing = current
+        .iter()
+        .find(|c| c.type_ == REVISION_QUEUED_CONDITION);
+    let message = queued.map(|queued| {
+        format!(
+            "Pod template {} waits for revision {} to finish",
+            queued, in_flight
+        )
+    });
+    if existing.and_then(|c| c.message.clone()) == message {
+        return None;
+    }
+
+    let mut conditions: Vec<RolloutCondition> = current
+        .iter()
+        .filter(|c| c.type_ != REVISION_QUEUED_CONDITION)
+        .cloned()
+        .collect();
+    if let Some(message) = message {
+        conditions.push(RolloutCondition {
+            type_: REVISION_QUEUED_CONDITION.to_string(),
+            status: "True".to_string(),
+            reason: Some(TEMPLATE_CHANGED_REASON.to_string()),
+            message: Some(message),
+            last_transition_time: existing
+                .and_then(|c| c.last_transition_time.clone())
+                .or_else(|| Some(now.to_string())),
+        });
+    }
+    Some(conditions)
+}
+
+/// Check an update against `onTemplateChange: reject`
+///
+/// # Returns
+/// * `Ok(())` - Allowed: another policy, no revision in flight, the same
+///   template, or the controller restoring a revision for
+///   `kulta.io/rollback-to-revision`
+/// * `Err(message)` - The update would change the in-flight revision's template
+pub fn check_template_change(old: &Rollout, new: &Rollout) -> Result<(), String> {
+    if new.spec.on_template_change != Some(TemplateChangePolicy::Reject)
+        || !is_in_flight(old.status.as_ref())
+        || old.spec.template == new.spec.template
+        || old
+            .annotations()
+            .contains_key(ROLLBACK_TO_REVISION_ANNOTATION)
+    {
+        return Ok(());
+    }
+    let phase = old
+        .status
+        .as_ref()
+        .and_then(|s| s.phase.as_ref())
+        .map(|phase| format!("{:?}", phase))
+        .unwrap_or_default();
+    Err(format!(
+        "spec.template cannot change while the rollout is {} (onTemplateChange: reject); \
+         wait for it to complete, abort it, or change onTemplateChange",
+        phase
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(policy: Option<TemplateChangePolicy>, phase: Phase, image: &str) -> Rollout {
+        let mut builder = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", image)
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 10 },
+                        { "setWeight": 30 },
+                        { "setWeight": 50 },
+                        { "setWeight": 100 }
+                    ]
+                }))
+                .unwrap(),
+            );
+        if let Some(policy) = policy {
+            builder = builder.on_template_change(policy);
+        }
+        let mut rollout = builder.build().unwrap();
+        rollout.status = Some(RolloutStatus {
+            phase: Some(phase),
+            current_step_index: Some(2),
+            current_weight: Some(50),
+            pod_template_hash: Some("aaa".to_string()),
+            revision: Some(4),
+            ..Default::default()
+        });
+        rollout
+    }
+
+    #[test]
+    fn test_in_flight_template_change() {
+        let progressing = rollout(None, Phase::Progressing, "shop:2");
+        assert_eq!(in_flight_hash(&progressing, "bbb"), Some("aaa"));
+        assert_eq!(in_flight_hash(&progressing, "aaa"), None);
+        assert_eq!(
+            in_flight_hash(&rollout(None, Phase::Completed, "shop:2"), "bbb"),
+            None
+        );
+
+        assert_eq!(
+            template_change_action(&progressing),
+            TemplateChangePolicy::Replace
+        );
+        let rejecting = rollout(Some(TemplateChangePolicy::Reject), Phase::Paused, "shop:2");
+        assert_eq!(
+            template_change_action(&rejecting),
+            TemplateChangePolicy::Queue
+        );
+
+        let restarted = restart_for_new_template(&progressing);
+        let status = restarted.status.unwrap();
+        assert_eq!(status.phase, Some(Phase::Initializing));
+        assert_eq!(status.current_step_index, None);
+        assert_eq!(status.pod_template_hash.as_deref(), Some("aaa"));
+        assert_eq!(status.revision, Some(4));
+    }
+
+    #[test]
+    fn test_queued_revision_condition_is_set_and_removed() {
+        assert_eq!(queued_revision_conditions(None, None, "aaa", "t0"), None);
+
+        let conditions = queued_revision_conditions(None, Some("bbb"), "aaa", "t0").unwrap();
+        assert_eq!(conditions[0].type_, REVISION_QUEUED_CONDITION);
+        assert_eq!(conditions[0].last_transition_time.as_deref(), Some("t0"));
+
+        let status = RolloutStatus {
+            conditions,
+            ..RolloutStatus::default()
+        };
+        assert_eq!(
+            queued_revision_conditions(Some(&status), Some("bbb"), "aaa", "t1"),
+            None
+        );
+        assert_eq!(
+            queued_revision_conditions(Some(&status), None, "aaa", "t1"),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_reject_policy_denies_template_change_in_flight() {
+        let old = rollout(Some(TemplateChangePolicy::Reject), Phase::Paused, "shop:1");
+        let new = rollout(Some(TemplateChangePolicy::Reject), Phase::Paused, "shop:2");
+        let err = check_template_change(&old, &new).unwrap_err();
+        assert!(err.contains("while the rollout is Paused"));
+
+        // Other edits, finished rollouts and other policies pass
+        assert!(check_template_change(&old, &old.clone()).is_ok());
+        let completed = rollout(
+            Some(TemplateChangePolicy::Reject),
+            Phase::Completed,
+            "shop:1",
+        );
+        assert!(check_template_change(&completed, &new).is_ok());
+        let queueing = rollout(Some(TemplateChangePolicy::Queue), Phase::Paused, "shop:2");
+        assert!(check_template_change(&old, &queueing).is_ok());
+
+        // The controller restoring a revision for rollback-to-revision
+        let mut rolling_back = old.clone();
+        rolling_back.metadata.annotations =
+            Some([(ROLLBACK_TO_REVISION_ANNOTATION.to_string(), "3".to_string())].into());
+        assert!(check_template_change(&rolling_back, &new).is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+use super::history::revision_number;
+use crate::crd::rollout::{ImageChange, Rollout, RolloutStatus, TemplateDiff};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::core::v1::{Container, PodTemplateSpec};
+use kube::api::Api;
+use kube::{Client, ResourceExt};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// Labels KULTA adds to ReplicaSet pod templates (ignored when diffing)
+const INJECTED_LABELS: [&str; 4] = [
+    "pod-template-hash",
+    "kulta.io/revision",
+    "rollouts.kulta.io/type",
+    "rollouts.kulta.io/managed",
+];
+
+/// Most entries kept per list in a template diff
+///
+/// The diff is stored in status and sent with CDEvents; templates with
+/// hundreds of containers would otherwise bloat both.
+pub const MAX_TEMPLATE_DIFF_ENTRIES: usize = 20;
+
+/// Summarize what changed between two pod templates
+///
+/// Reports image, env and resource changes per container, added/removed
+/// containers (init containers included), and whether anything else in the
+/// template changed. Labels injected by KULTA into ReplicaSet templates are
+/// ignored, so `old` can be taken straight from a running ReplicaSet. Each
+/// list keeps at most [`MAX_TEMPLATE_DIFF_ENTRIES`] entries; `truncated` is
+/// set when any was cut.
+pub fn summarize_template_diff(
+    old: &PodTemplateSpec,
+    new: &PodTemplateSpec,
+    from_hash: &str,
+    to_hash: &str,
+) -> TemplateDiff {
+    let old_containers = containers_by_name(old);
+    let new_containers = containers_by_name(new);
+
+    let mut diff = TemplateDiff {
+        from_hash: from_hash.to_string(),
+        to_hash: to_hash.to_string(),
+        ..Default::default()
+    };
+
+    for (name, new_c) in &new_containers {
+        let Some(old_c) = old_containers.get(name) else {
+            diff.containers_added.push(name.clone());
+            continue;
+        };
+
+        if old_c.image != new_c.image {
+            diff.images.push(ImageChange {
+                container: name.clone(),
+                from: old_c.image.clone().unwrap_or_default(),
+                to: new_c.image.clone().unwrap_or_default(),
+            });
+        }
+        if old_c.env != new_c.env || old_c.env_from != new_c.env_from {
+            diff.env_changed.push(name.clone());
+        }
+        if old_c.resources != new_c.resources {
+            diff.resources_changed.push(name.clone());
+        }
+    }
+
+    diff.containers_removed = old_containers
+        .keys()
+        .filter(|name| !new_containers.contains_key(*name))
+        .cloned()
+        .collect();
+
+    diff.other_changes = normalize_template(old) != normalize_template(new);
+    diff.truncated = cap_entries(&mut diff.images)
+        | cap_entries(&mut diff.env_changed)
+        | cap_entries(&mut diff.resources_changed)
+        | cap_entries(&mut diff.containers_added)
+        | cap_entries(&mut diff.containers_removed);
+    diff
+}
+
+/// Cut a list to [`MAX_TEMPLATE_DIFF_ENTRIES`], returning whether anything was dropped
+fn cap_entries<T>(entries: &mut Vec<T>) -> bool {
+    let truncated = entries.len() > MAX_TEMPLATE_DIFF_ENTRIES;
+    entries.truncate(MAX_TEMPLATE_DIFF_ENTRIES);
+    truncated
+}
+
+/// Containers and init containers keyed by name (sorted for stable output)
+fn containers_by_name(template: &PodTemplateSpec) -> BTreeMap<String, Container> {
+    template
+        .spec
+        .iter()
+        .flat_map(|spec| {
+            spec.containers
+                .iter()
+                .chain(spec.init_containers.iter().flatten())
+        })
+        .map(|c| (c.name.clone(), c.clone()))
+        .collect()
+}
+
+/// Strip everything the summary already reports on, leaving "other" changes
+///
+/// Added/removed containers are dropped too, so a pure container addition
+/// doesn't also count as an other change.
+fn normalize_template(template: &PodTemplateSpec) -> PodTemplateSpec {
+    let mut normalized = template.clone();
+    strip_injected_labels(&mut normalized);
+
+    if let Some(spec) = normalized.spec.as_mut() {
+        spec.containers = Vec::new();
+        spec.init_containers = None;
+    }
+
+    normalized
+}
+
+/// Remove the labels KULTA injects into ReplicaSet pod templates
+///
+/// Turns a ReplicaSet's pod template back into the Rollout template it was
+/// built from.
+pub(crate) fn strip_injected_labels(template: &mut PodTemplateSpec) {
+    if let Some(labels) = template.metadata.as_mut().and_then(|m| m.labels.as_mut()) {
+        for label in INJECTED_LABELS {
+            labels.remove(label);
+        }
+        if labels.is_empty() {
+            if let Some(metadata) = template.metadata.as_mut() {
+                metadata.labels = None;
+            }
+        }
+    }
+}
+
+/// Name of the ReplicaSet running the currently serving revision
+///
+/// Canary: `{name}-stable`, blue-green: `{name}-active` (`{name}-stable` when
+/// migrated from canary), A/B testing: `{name}-variant-a`, simple: `{name}`.
+pub fn baseline_replicaset_name(rollout: &Rollout) -> String {
+    let name = rollout.name_any();
+    let strategy = &rollout.spec.strategy;
+    if strategy.canary.is_some() {
+        format!("{}-stable", name)
+    } else if let Some(blue_green) = &strategy.blue_green {
+        format!("{}-{}", name, blue_green.replicaset_types().0)
+    } else if strategy.ab_testing.is_some() {
+        format!("{}-variant-a", name)
+    } else {
+        name
+    }
+}
+
+/// Detect a new revision and summarize its pod template changes
+///
+/// A new revision is a `spec.template` whose hash differs from the
+/// `status.podTemplateHash` recorded on a previous reconcile. The previous
+/// template is read from the baseline ReplicaSet (what is actually running).
+///
+/// # Returns
+/// * `Some(diff)` - New revision detected and summarized
+/// * `None` - No recorded revision, same revision, or baseline ReplicaSet
+///   unavailable (logged, non-fatal)
+pub async fn detect_template_change(
+    rollout: &Rollout,
+    client: &Client,
+    new_hash: &str,
+) -> Option<TemplateDiff> {
+    let recorded_hash = rollout
+        .status
+        .as_ref()
+        .and_then(|s| s.pod_template_hash.as_deref())?;
+    if recorded_hash == new_hash {
+        return None;
+    }
+
+    let namespace = rollout.namespace()?;
+    let rs_name = baseline_replicaset_name(rollout);
+    let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+
+    let baseline = match rs_api.get(&rs_name).await {
+        Ok(rs) => rs,
+        Err(e) => {
+            warn!(
+                rollout = ?rollout.name_any(),
+                replicaset = ?rs_name,
+                error = ?e,
+                "Failed to read baseline ReplicaSet, skipping template diff (non-fatal)"
+            );
+            return None;
+        }
+    };
+
+    let old_template = baseline.spec.and_then(|s| s.template)?;
+    // The running ReplicaSet's own hash label is more accurate than the recorded one
+    let from_hash = baseline
+        .metadata
+        .labels
+        .as_ref()
+        .and_then(|l| l.get("pod-template-hash").cloned())
+        .unwrap_or_else(|| recorded_hash.to_string());
+
+    let diff = summarize_template_diff(&old_template, &rollout.spec.template, &from_hash, new_hash);
+
+    info!(
+        rollout = ?rollout.name_any(),
+        from_hash = %diff.from_hash,
+        to_hash = %diff.to_hash,
+        images_changed = diff.images.len(),
+        env_changed = diff.env_changed.len(),
+        resources_changed = diff.resources_changed.len(),
+        other_changes = diff.other_changes,
+        "New revision detected"
+    );
+
+    Some(diff)
+}
+
+/// Record the current revision (and its diff, if new) on the desired status
+///
+/// Strategies compute status without knowledge of revisions, so this is
+/// applied on top. A previously recorded diff is kept until the next revision.
+pub fn record_revision(
+    desired: &mut RolloutStatus,
+    current: Option<&RolloutStatus>,
+    pod_template_hash: String,
+    new_diff: Option<TemplateDiff>,
+) {
+    desired.revision = Some(revision_number(current, &pod_template_hash));
+    desired.pod_template_hash = Some(pod_template_hash);
+    desired.template_diff = new_diff
+        .or_else(|| desired.template_diff.take())
+        .or_else(|| current.and_then(|s| s.template_diff.clone()));
+}
@@ -1,3 +1,6 @@
+use crate::controller::strategy_math::{
+    canary_steps, current_header_route, current_mirror_percent, current_weight,
+};
 use crate::crd::rollout::{Phase, Rollout};
 use serde::{Deserialize, Serialize};
 
@@ -133,6 +136,218 @@ pub fn build_gateway_api_backend_refs(
     ]
 }
 
+/// RequestMirror filter duplicating traffic to the canary service
+///
+/// Set on the HTTPRoute rule while the current step has `setMirror`; the
+/// weight patch leaves it out otherwise, which removes it again.
+///
+/// # Returns
+/// * `Some(filter)` - Gateway API `RequestMirror` filter for the canary service
+/// * `None` - Not a canary, or the current step doesn't mirror
+pub fn build_gateway_api_mirror_filter(rollout: &Rollout) -> Option<serde_json::Value> {
+    let canary = rollout.spec.strategy.canary.as_ref()?;
+    let percent = current_mirror_percent(&rollout.spec, rollout.status.as_ref())?;
+    Some(serde_json::json!({
+        "type": "RequestMirror",
+        "requestMirror": {
+            "backendRef": {
+                "kind": "Service",
+                "group": "",
+                "name": canary.canary_service,
+                "port": default_service_port(canary.port),
+            },
+            "percent": percent,
+        }
+    }))
+}
+
+/// HTTPRoute rule sending requests with the step's headers to the canary
+///
+/// Written ahead of the weighted rule while a `setHeaderRoute` applies, so
+/// matching requests (e.g., internal users) reach the canary at any weight.
+/// The weight patch leaves it out otherwise, which removes it again.
+///
+/// # Returns
+/// * `Some(rule)` - Rule with the header matches and the canary service as its only backend
+/// * `None` - Not a canary, or no header route at the current step
+pub fn build_gateway_api_header_route_rule(rollout: &Rollout) -> Option<serde_json::Value> {
+    let canary = rollout.spec.strategy.canary.as_ref()?;
+    let route = current_header_route(&rollout.spec, rollout.status.as_ref())?;
+    let headers: Vec<serde_json::Value> = route
+        .matches
+        .iter()
+        .map(|header| {
+            serde_json::json!({
+                "name": header.name,
+                "value": header.value,
+                "type": header.match_type.clone().unwrap_or_default(),
+            })
+        })
+        .collect();
+    Some(serde_json::json!({
+        "matches": [{ "headers": headers }],
+        "backendRefs": [{
+            "kind": "Service",
+            "group": "",
+            "name": canary.canary_service,
+            "port": default_service_port(canary.port),
+            "weight": 100,
+        }],
+    }))
+}
+
+/// HTTPRoute additions of the current canary step
+///
+/// Empty for other strategies and for steps without `setMirror` or `setHeaderRoute`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CanaryStepRoutes {
+    /// RequestMirror filter of the weighted rule
+    pub mirror_filter: Option<serde_json::Value>,
+    /// Header rule written ahead of the weighted rule
+    pub header_rule: Option<serde_json::Value>,
+}
+
+impl CanaryStepRoutes {
+    pub fn for_rollout(rollout: &Rollout) -> Self {
+        CanaryStepRoutes {
+            mirror_filter: build_gateway_api_mirror_filter(rollout),
+            header_rule: build_gateway_api_header_route_rule(rollout),
+        }
+    }
+
+    /// `spec.rules` of the route patch: the header rule, then the weighted rule
+    pub fn rules(
+        self,
+        backend_refs: &[gateway_api::apis::standard::httproutes::HTTPRouteRulesBackendRefs],
+    ) -> Vec<serde_json::Value> {
+        let mut weighted = serde_json::json!({ "backendRefs": backend_refs });
+        if let Some(filter) = self.mirror_filter {
+            weighted["filters"] = serde_json::json!([filter]);
+        }
+        self.header_rule.into_iter().chain([weighted]).collect()
+    }
+
+    /// `spec.rules` of a route whose weights are limited to the rule named `section`
+    ///
+    /// The section rule keeps its matches and other settings and gets the
+    /// weighted backends and the step's mirror filter (in place of any other). A header route is written ahead
+    /// of it as `{section}-header`, with the section's matches narrowed to the
+    /// header, and is removed again when the step has none. Other rules are
+    /// left untouched.
+    ///
+    /// # Returns
+    /// * `Ok(rules)` - The route's rules with the section updated
+    /// * `Err(reason)` - The route has no rule named `section`
+    pub fn section_rules(
+        self,
+        current: &[serde_json::Value],
+        section: &str,
+        backend_refs: &[gateway_api::apis::standard::httproutes::HTTPRouteRulesBackendRefs],
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let header_name = format!("{}-header", section);
+        let is_named = |rule: &serde_json::Value, name: &str| rule["name"].as_str() == Some(name);
+        if !current.iter().any(|rule| is_named(rule, section)) {
+            return Err(format!("no rule named {}", section));
+        }
+
+        let mut rules = Vec::with_capacity(current.len() + 1);
+        for rule in current.iter().filter(|rule| !is_named(rule, &header_name)) {
+            if !is_named(rule, section) {
+                rules.push(rule.clone());
+                continue;
+            }
+            if let Some(mut header_rule) = self.header_rule.clone() {
+                let headers = header_rule["matches"][0]["headers"].clone();
+                let matches = match rule["matches"].as_array() {
+                    Some(matches) if !matches.is_empty() => matches
+                        .iter()
+                        .map(|m| {
+                            let mut narrowed = m.clone();
+                            let mut all = m["headers"].as_array().cloned().unwrap_or_default();
+                            all.extend(headers.as_array().cloned().unwrap_or_default());
+                            narrowed["headers"] = serde_json::Value::Array(all);
+                            narrowed
+                        })
+                        .collect(),
+                    _ => vec![serde_json::json!({ "headers": headers })],
+                };
+                header_rule["name"] = serde_json::json!(header_name);
+                header_rule["matches"] = serde_json::Value::Array(matches);
+                rules.push(header_rule);
+            }
+            let mut weighted = rule.clone();
+            weighted["backendRefs"] = serde_json::json!(backend_refs);
+            let mut filters: Vec<serde_json::Value> = rule["filters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|filter| filter["type"] != "RequestMirror")
+                .cloned()
+                .collect();
+            filters.extend(self.mirror_filter.clone());
+            if filters.is_empty() {
+                if let Some(fields) = weighted.as_object_mut() {
+                    fields.remove("filters");
+                }
+            } else {
+                weighted["filters"] = serde_json::Value::Array(filters);
+            }
+            rules.push(weighted);
+        }
+        Ok(rules)
+    }
+}
+
+/// Preview the backendRefs a canary would get at `step_index`, without a cluster
+///
+/// Dry run of a promotion: weights are computed exactly as during reconcile
+/// (including `weightConstraints` normalization) for a hypothetical
+/// `status.currentStepIndex`. An index equal to the number of steps previews
+/// the completed rollout.
+///
+/// # Returns
+/// * `Ok(refs)` - backendRefs that would be written to the HTTPRoute(s)
+/// * `Err(reason)` - Not a Gateway API canary, or the step index is out of range
+pub fn preview_backend_refs(
+    rollout: &Rollout,
+    step_index: i32,
+) -> Result<Vec<gateway_api::apis::standard::httproutes::HTTPRouteRulesBackendRefs>, String> {
+    let canary = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .ok_or_else(|| "weight preview requires the canary strategy".to_string())?;
+    if canary
+        .traffic_routing
+        .as_ref()
+        .and_then(|routing| routing.gateway_api.as_ref())
+        .is_none()
+    {
+        return Err(
+            "weight preview requires spec.strategy.canary.trafficRouting.gatewayAPI".to_string(),
+        );
+    }
+
+    let steps = canary_steps(&rollout.spec).len();
+    if steps == 0 && canary.steps_from.is_some() {
+        return Err("steps are imported with stepsFrom; preview needs them inline".to_string());
+    }
+    if !usize::try_from(step_index).is_ok_and(|index| index <= steps) {
+        return Err(format!(
+            "step index {} is out of range (0-{})",
+            step_index, steps
+        ));
+    }
+
+    let mut preview = rollout.clone();
+    preview
+        .status
+        .get_or_insert_with(Default::default)
+        .current_step_index = Some(step_index);
+    Ok(build_gateway_api_backend_refs(&preview))
+}
+
 /// Calculate traffic weights for blue-green strategy
 ///
 /// Returns (active_weight, preview_weight):
@@ -187,36 +402,12 @@ pub fn update_httproute_backends(
 /// Returns (stable_weight, canary_weight) as percentages
 ///
 /// # Logic
-/// - If no status or no currentStepIndex: 100% stable, 0% canary
+/// - If no canary strategy, no status or no currentStepIndex: 100% stable, 0% canary
 /// - If currentStepIndex >= steps.len(): 100% canary, 0% stable (rollout complete)
 /// - Otherwise: Use setWeight from steps[currentStepIndex]
+///
+/// See [`current_weight`] for the canary side.
 pub fn calculate_traffic_weights(rollout: &Rollout) -> (i32, i32) {
-    // Get canary strategy
-    let canary_strategy = match &rollout.spec.strategy.canary {
-        Some(strategy) => strategy,
-        None => return (100, 0), // No canary strategy, 100% stable
-    };
-
-    // Get current step index from status
-    let current_step_index = match &rollout.status {
-        Some(status) => status.current_step_index.unwrap_or(-1),
-        None => -1, // No status yet, 100% stable
-    };
-
-    // If no step is active, default to 100% stable
-    if current_step_index < 0 {
-        return (100, 0);
-    }
-
-    // If step index is beyond available steps, rollout is complete (100% canary)
-    if current_step_index as usize >= canary_strategy.steps.len() {
-        return (0, 100);
-    }
-
-    let canary_weight = canary_strategy.steps[current_step_index as usize]
-        .set_weight
-        .unwrap_or(0);
-    let stable_weight = 100 - canary_weight;
-
-    (stable_weight, canary_weight)
+    let canary_weight = current_weight(&rollout.spec, rollout.status.as_ref());
+    (100 - canary_weight, canary_weight)
 }
@@ -1,4 +1,14 @@
-use crate::crd::rollout::Rollout;
+use crate::controller::prometheus::{is_valid_label_name, is_valid_promql_duration, render_query};
+use crate::controller::strategy_math::{
+    is_supported_weight, normalize_weight, partial_weight_range,
+};
+use crate::controller::web_metric::validate_web_metric;
+use crate::crd::experiment::{Experiment, ExperimentAnalysis};
+use crate::crd::rollout::{
+    AnalysisAggregation, CanaryStep, GatewayAPIRouting, GatewayRouteKind, MetricConfig, Pause,
+    PrometheusConfig, Rollout, WeightConstraints,
+};
+use chrono::DateTime;
 use std::time::Duration;
 
 /// Validate Rollout specification
@@ -9,9 +19,53 @@ use std::time::Duration;
 /// # Validation Rules
 /// - `spec.replicas` must be >= 0
 /// - Canary strategy: `canaryService` and `stableService` cannot be empty
-/// - Canary strategy: `steps` must have at least one step
+/// - Canary strategy: `steps` must have at least one step, unless imported
+///   with `stepsFrom` (which excludes `steps` and needs a ConfigMap name)
 /// - Each step's `setWeight` must be 0-100
-/// - `pause.duration` must be valid format (e.g., "30s", "5m")
+/// - `setMirror` must be 1-100 and needs `gatewayAPI` routing through an HTTPRoute
+/// - `setHeaderRoute` matches need a header name and value, and need
+///   `gatewayAPI` routing through an HTTPRoute
+/// - `setCanaryScale` sets exactly one of `replicas` (>= 0), `weight` (0-100)
+///   and `matchTrafficWeight: true`, and can't be combined with replica weighting
+/// - `pause.duration` must be valid format (e.g., "30s", "5m"), `pause.until`
+///   an RFC3339 time
+/// - A step's `experiment` is checked like an Experiment (see
+///   [`validate_experiment`])
+/// - `alertGate` needs at least one matcher with valid label names and an http(s) address
+/// - A step's `analysis` needs at least one metric, a valid `duration` and a
+///   `count` >= 1; its metrics' `baselineOffset` must be a PromQL duration
+/// - `analysis.perZone` must list zones and use a valid Prometheus label name
+/// - `analysis.revisionLabelKey` must be a valid Prometheus label name
+/// - `gatewayAPI` must name at least one HTTPRoute (`httpRoute` or `httpRoutes`)
+/// - `gatewayAPI.kind` TLSRoute and UDPRoute are canary and blue-green only
+/// - Canary step weights must fit `gatewayAPI.weightConstraints` unless `normalize` is set
+/// - `gatewayAPI.sections` name managed HTTPRoutes once each with a valid rule
+///   name, and aren't supported for abTesting
+/// - `trafficRouting.none` is canary-only, excludes `gatewayAPI`, and replica
+///   weighting needs at least 2 replicas
+/// - `analysis.metrics[].baselineOffset` must be a PromQL duration (e.g., "7d")
+/// - `analysis.metrics[].weight` must be >= 0; `analysis.passScore` (0.0-1.0)
+///   is required with, and only allowed with, `aggregation: score`
+/// - `analysis.metrics[].failureThreshold` must be >= 1
+/// - `analysis.metrics[].volumeWeighting` needs `compareToStable` or
+///   `baselineOffset`, and `fullWeightRequests` >= 1
+/// - `analysis.metrics[].compareToBaseline` excludes `baselineOffset` and
+///   `compareToStable`, is canary-only and needs `canary.baseline`, whose
+///   `replicas` must be >= 0
+/// - A metric's custom `query` may only use `{{rollout}}`, `{{namespace}}`
+///   and `{{revision}}`, must be well-formed, and excludes `baselineOffset`,
+///   `compareToStable`, `compareToBaseline` and `analysis.perZone`
+/// - A `web` metric excludes `query`, the stable and baseline comparisons
+///   and `analysis.perZone`,
+///   and needs an http(s) `url`, a supported `jsonPath`, and `method: POST`
+///   for a `body`
+/// - `analysis.prometheus` sets at most one of `bearerTokenSecretRef` and
+///   `basicAuth`, its `tls` client certificate and key together, and Secret
+///   refs need a name and key
+/// - `canary.rollbackVerification` needs `canary.analysis` with metrics, and
+///   its `delay` must be a valid duration
+/// - `onDegraded.afterSeconds` must be >= 0
+/// - `workloadRef` needs a name and excludes an inline `template` with containers
 ///
 /// # Arguments
 /// * `rollout` - The Rollout resource to validate
@@ -40,11 +94,29 @@ pub fn validate_rollout(rollout: &Rollout) -> Result<(), String> {
             return Err("spec.strategy.canary.stableService cannot be empty".to_string());
         }
 
-        // Validate at least one step exists
-        if canary.steps.is_empty() {
+        // Validate at least one step exists (imported steps are checked once resolved)
+        if let Some(steps_from) = &canary.steps_from {
+            if !canary.steps.is_empty() {
+                return Err(
+                    "spec.strategy.canary.steps and stepsFrom are mutually exclusive".to_string(),
+                );
+            }
+            if steps_from.config_map_ref.name.is_empty() {
+                return Err(
+                    "spec.strategy.canary.stepsFrom.configMapRef.name cannot be empty".to_string(),
+                );
+            }
+        } else if canary.steps.is_empty() {
             return Err("spec.strategy.canary.steps must have at least one step".to_string());
         }
 
+        // Mirroring and header routes are HTTPRoute rule features
+        let http_route = canary
+            .traffic_routing
+            .as_ref()
+            .and_then(|routing| routing.gateway_api.as_ref())
+            .is_some_and(|gateway_api| gateway_api.kind == GatewayRouteKind::HTTPRoute);
+
         // Validate each step
         for (i, step) in canary.steps.iter().enumerate() {
             // Validate setWeight is required and in 0-100 range
@@ -62,26 +134,593 @@ pub fn validate_rollout(rollout: &Rollout) -> Result<(), String> {
                 }
             }
 
-            // Validate pause duration if present
-            if let Some(pause) = &step.pause {
-                if let Some(duration) = &pause.duration {
+            // Validate setMirror if present
+            if let Some(mirror) = step.set_mirror {
+                if !(1..=100).contains(&mirror) {
+                    return Err(format!(
+                        "steps[{}].setMirror must be 1-100, got {}",
+                        i, mirror
+                    ));
+                }
+                if !http_route {
+                    return Err(format!(
+                        "steps[{}].setMirror requires trafficRouting.gatewayAPI with an HTTPRoute",
+                        i
+                    ));
+                }
+            }
+
+            // Validate setHeaderRoute if present (an empty match removes the route)
+            if let Some(route) = &step.set_header_route {
+                if route
+                    .matches
+                    .iter()
+                    .any(|header| header.name.is_empty() || header.value.is_empty())
+                {
+                    return Err(format!(
+                        "steps[{}].setHeaderRoute.match entries need a header name and value",
+                        i
+                    ));
+                }
+                if !http_route {
+                    return Err(format!(
+                        "steps[{}].setHeaderRoute requires trafficRouting.gatewayAPI with an HTTPRoute",
+                        i
+                    ));
+                }
+            }
+
+            // Validate setCanaryScale if present
+            if let Some(scale) = &step.set_canary_scale {
+                let set = [
+                    scale.replicas.is_some(),
+                    scale.weight.is_some(),
+                    scale.match_traffic_weight.is_some(),
+                ];
+                if set.iter().filter(|&&set| set).count() != 1 {
+                    return Err(format!(
+                        "steps[{}].setCanaryScale: set exactly one of replicas, weight and matchTrafficWeight",
+                        i
+                    ));
+                }
+                if scale.replicas.is_some_and(|replicas| replicas < 0) {
+                    return Err(format!("steps[{}].setCanaryScale.replicas must be >= 0", i));
+                }
+                if scale
+                    .weight
+                    .is_some_and(|weight| !(0..=100).contains(&weight))
+                {
+                    return Err(format!("steps[{}].setCanaryScale.weight must be 0-100", i));
+                }
+                if scale.match_traffic_weight == Some(false) {
+                    return Err(format!(
+                        "steps[{}].setCanaryScale.matchTrafficWeight must be true when set",
+                        i
+                    ));
+                }
+                // With replica weighting the pod split is the traffic split
+                if canary
+                    .traffic_routing
+                    .as_ref()
+                    .is_some_and(|routing| routing.uses_replica_weighting())
+                {
+                    return Err(format!(
+                        "steps[{}].setCanaryScale can't be used with trafficRouting.none.replicaWeighting",
+                        i
+                    ));
+                }
+            }
+
+            // Validate pause if present
+            match &step.pause {
+                Some(Pause::Duration(duration)) if parse_duration(duration).is_none() => {
+                    return Err(format!("steps[{}].pause.duration invalid: {}", i, duration));
+                }
+                Some(Pause::UntilTime(until)) if DateTime::parse_from_rfc3339(until).is_err() => {
+                    return Err(format!(
+                        "steps[{}].pause.until must be an RFC3339 time, got {}",
+                        i, until
+                    ));
+                }
+                _ => {}
+            }
+
+            // Validate inline step analysis if present
+            if let Some(analysis) = &step.analysis {
+                if analysis.metrics.is_empty() {
+                    return Err(format!(
+                        "steps[{}].analysis.metrics must have at least one metric",
+                        i
+                    ));
+                }
+                if let Some(duration) = &analysis.duration {
                     if parse_duration(duration).is_none() {
-                        return Err(format!("steps[{}].pause.duration invalid: {}", i, duration));
+                        return Err(format!(
+                            "steps[{}].analysis.duration invalid: {}",
+                            i, duration
+                        ));
+                    }
+                }
+                if analysis.count.is_some_and(|count| count < 1) {
+                    return Err(format!("steps[{}].analysis.count must be >= 1", i));
+                }
+                if let Some((j, offset)) = analysis.metrics.iter().enumerate().find_map(|(j, m)| {
+                    m.baseline_offset
+                        .as_ref()
+                        .filter(|offset| !is_valid_promql_duration(offset))
+                        .map(|offset| (j, offset))
+                }) {
+                    return Err(format!(
+                        "steps[{}].analysis.metrics[{}].baselineOffset invalid: '{}' (expected a PromQL duration like '7d')",
+                        i, j, offset
+                    ));
+                }
+                for (j, metric) in analysis.metrics.iter().enumerate() {
+                    validate_metric_query(
+                        &format!("steps[{}].analysis.metrics[{}]", i, j),
+                        metric,
+                    )?;
+                }
+            }
+
+            // Validate the step's experiment if present
+            if let Some(experiment) = &step.experiment {
+                validate_experiment_parts(
+                    &format!("steps[{}].experiment", i),
+                    experiment
+                        .templates
+                        .iter()
+                        .map(|t| (t.name.as_str(), t.replicas)),
+                    &experiment.duration,
+                    &experiment.analyses,
+                )?;
+            }
+
+            // Validate alert gate if present
+            if let Some(gate) = &step.alert_gate {
+                if gate.matchers.is_empty() {
+                    return Err(format!(
+                        "steps[{}].alertGate.matchers must have at least one matcher",
+                        i
+                    ));
+                }
+                if let Some(name) = gate.matchers.keys().find(|k| !is_valid_label_name(k)) {
+                    return Err(format!(
+                        "steps[{}].alertGate.matchers key '{}' is not a valid label name",
+                        i, name
+                    ));
+                }
+                if let Some(address) = &gate.address {
+                    if !address.starts_with("http://") && !address.starts_with("https://") {
+                        return Err(format!(
+                            "steps[{}].alertGate.address must be an http(s) URL, got '{}'",
+                            i, address
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Validate dependency gates
+        for (i, gate) in canary.gates.iter().enumerate() {
+            if gate.service.is_empty() {
+                return Err(format!(
+                    "spec.strategy.canary.gates[{}].service cannot be empty",
+                    i
+                ));
+            }
+            if gate.condition.is_empty() {
+                return Err(format!(
+                    "spec.strategy.canary.gates[{}].condition cannot be empty",
+                    i
+                ));
+            }
+        }
+
+        // compareToBaseline needs the baseline ReplicaSet to compare with
+        if let Some(replicas) = canary.baseline.as_ref().and_then(|b| b.replicas) {
+            if replicas < 0 {
+                return Err(format!(
+                    "spec.strategy.canary.baseline.replicas must be >= 0, got {}",
+                    replicas
+                ));
+            }
+        }
+        if canary.baseline.is_none() {
+            let step_analyses = canary.steps.iter().enumerate().filter_map(|(i, step)| {
+                step.analysis
+                    .as_ref()
+                    .map(|a| (format!("steps[{}].analysis", i), a))
+            });
+            let analyses = canary
+                .analysis
+                .as_ref()
+                .map(|a| ("spec.strategy.canary.analysis".to_string(), a))
+                .into_iter()
+                .chain(step_analyses);
+            for (path, analysis) in analyses {
+                if let Some(j) = analysis
+                    .metrics
+                    .iter()
+                    .position(|m| m.compare_to_baseline == Some(true))
+                {
+                    return Err(format!(
+                        "{}.metrics[{}].compareToBaseline requires spec.strategy.canary.baseline",
+                        path, j
+                    ));
+                }
+            }
+        }
+
+        // rollbackVerification measures canary.analysis against the stable revision
+        if let Some(verification) = &canary.rollback_verification {
+            if let Some(delay) = &verification.delay {
+                if parse_duration(delay).is_none() {
+                    return Err(format!(
+                        "spec.strategy.canary.rollbackVerification.delay invalid: {}",
+                        delay
+                    ));
+                }
+            }
+            if !canary
+                .analysis
+                .as_ref()
+                .is_some_and(|a| !a.metrics.is_empty())
+            {
+                return Err(
+                    "spec.strategy.canary.rollbackVerification requires spec.strategy.canary.analysis with metrics"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Validate per-zone analysis if present
+        if let Some(per_zone) = canary.analysis.as_ref().and_then(|a| a.per_zone.as_ref()) {
+            if per_zone.zones.is_empty() {
+                return Err(
+                    "spec.strategy.canary.analysis.perZone.zones must list at least one zone"
+                        .to_string(),
+                );
+            }
+            if let Some(label) = &per_zone.label {
+                if !is_valid_label_name(label) {
+                    return Err(format!(
+                        "spec.strategy.canary.analysis.perZone.label '{}' is not a valid Prometheus label name",
+                        label
+                    ));
+                }
+            }
+            if let Some(zone) = per_zone
+                .zones
+                .iter()
+                .find(|z| z.is_empty() || z.contains(['"', '\\', '\n']))
+            {
+                return Err(format!(
+                    "spec.strategy.canary.analysis.perZone.zones contains invalid zone '{}'",
+                    zone
+                ));
+            }
+        }
+
+        if let Some(key) = canary
+            .analysis
+            .as_ref()
+            .and_then(|a| a.revision_label_key.as_ref())
+        {
+            if !is_valid_label_name(key) {
+                return Err(format!(
+                    "spec.strategy.canary.analysis.revisionLabelKey '{}' is not a valid Prometheus label name",
+                    key
+                ));
+            }
+        }
+
+        // Validate metric baseline offsets if present
+        if let Some(analysis) = &canary.analysis {
+            for (i, metric) in analysis.metrics.iter().enumerate() {
+                let path = format!("spec.strategy.canary.analysis.metrics[{}]", i);
+                validate_metric_query(&path, metric)?;
+                if metric.query.is_some() && analysis.per_zone.is_some() {
+                    return Err(format!(
+                        "{}.query can't be combined with analysis.perZone",
+                        path
+                    ));
+                }
+                if metric.web.is_some() && analysis.per_zone.is_some() {
+                    return Err(format!(
+                        "{}.web can't be combined with analysis.perZone",
+                        path
+                    ));
+                }
+                if let Some(offset) = &metric.baseline_offset {
+                    if !is_valid_promql_duration(offset) {
+                        return Err(format!(
+                            "spec.strategy.canary.analysis.metrics[{}].baselineOffset invalid: '{}' (expected a PromQL duration like '7d')",
+                            i, offset
+                        ));
+                    }
+                }
+                if let Some(weight) = metric.weight {
+                    if !weight.is_finite() || weight < 0.0 {
+                        return Err(format!(
+                            "spec.strategy.canary.analysis.metrics[{}].weight must be a non-negative number, got {}",
+                            i, weight
+                        ));
                     }
                 }
+                if let Some(threshold) = metric.failure_threshold {
+                    if threshold < 1 {
+                        return Err(format!(
+                            "spec.strategy.canary.analysis.metrics[{}].failureThreshold must be at least 1, got {}",
+                            i, threshold
+                        ));
+                    }
+                }
+            }
+
+            // Validate score aggregation
+            let is_score = analysis.aggregation == Some(AnalysisAggregation::Score);
+            match analysis.pass_score {
+                Some(score) if !is_score => {
+                    return Err(format!(
+                        "spec.strategy.canary.analysis.passScore ({}) requires aggregation: score",
+                        score
+                    ));
+                }
+                Some(score) if !(0.0..=1.0).contains(&score) => {
+                    return Err(format!(
+                        "spec.strategy.canary.analysis.passScore must be 0.0-1.0, got {}",
+                        score
+                    ));
+                }
+                None if is_score => {
+                    return Err(
+                        "spec.strategy.canary.analysis.aggregation: score requires passScore"
+                            .to_string(),
+                    );
+                }
+                _ => {}
             }
         }
 
         // Validate traffic routing if present
         if let Some(traffic_routing) = &canary.traffic_routing {
+            if traffic_routing.none.is_some() && traffic_routing.gateway_api.is_some() {
+                return Err(
+                    "spec.strategy.canary.trafficRouting: none and gatewayAPI are mutually exclusive"
+                        .to_string(),
+                );
+            }
+            if let Some(nginx) = &traffic_routing.nginx {
+                if traffic_routing.none.is_some() || traffic_routing.gateway_api.is_some() {
+                    return Err(
+                        "spec.strategy.canary.trafficRouting: nginx is mutually exclusive with none and gatewayAPI"
+                            .to_string(),
+                    );
+                }
+                if nginx.stable_ingress.is_empty() {
+                    return Err(
+                        "spec.strategy.canary.trafficRouting.nginx.stableIngress cannot be empty"
+                            .to_string(),
+                    );
+                }
+            }
+            if traffic_routing.uses_replica_weighting() && rollout.spec.replicas < 2 {
+                return Err(format!(
+                    "spec.strategy.canary.trafficRouting.none.replicaWeighting needs at least 2 replicas, got {}",
+                    rollout.spec.replicas
+                ));
+            }
             if let Some(gateway) = &traffic_routing.gateway_api {
-                // Validate HTTPRoute name is not empty
-                if gateway.http_route.is_empty() {
+                // Validate at least one HTTPRoute is named
+                if gateway.route_names().is_empty() {
                     return Err(
-                        "spec.strategy.canary.trafficRouting.gatewayAPI.httpRoute cannot be empty"
+                        "spec.strategy.canary.trafficRouting.gatewayAPI.httpRoute cannot be empty (set httpRoute or httpRoutes)"
                             .to_string(),
                     );
                 }
+                if gateway.http_routes.iter().any(|r| r.is_empty()) {
+                    return Err(
+                        "spec.strategy.canary.trafficRouting.gatewayAPI.httpRoutes cannot contain empty names"
+                            .to_string(),
+                    );
+                }
+                if let Some(constraints) = &gateway.weight_constraints {
+                    validate_weight_constraints(constraints, &canary.steps)?;
+                }
+                validate_route_sections("spec.strategy.canary.trafficRouting.gatewayAPI", gateway)?;
+            }
+        }
+    }
+
+    // Prometheus credentials and TLS settings of each strategy's analysis
+    let strategy = &rollout.spec.strategy;
+    let prometheus_configs = [
+        (
+            "simple",
+            strategy
+                .simple
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref())
+                .and_then(|a| a.prometheus.as_ref()),
+        ),
+        (
+            "canary",
+            strategy
+                .canary
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref())
+                .and_then(|a| a.prometheus.as_ref()),
+        ),
+        (
+            "blueGreen",
+            strategy
+                .blue_green
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref())
+                .and_then(|a| a.prometheus.as_ref()),
+        ),
+        (
+            "abTesting",
+            strategy
+                .ab_testing
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref())
+                .and_then(|a| a.prometheus.as_ref()),
+        ),
+    ];
+    for (name, config) in prometheus_configs {
+        if let Some(config) = config {
+            validate_prometheus_config(
+                &format!("spec.strategy.{}.analysis.prometheus", name),
+                config,
+            )?;
+        }
+    }
+
+    // Only the canary strategy runs a baseline ReplicaSet
+    let non_canary_analyses = [
+        (
+            "simple",
+            strategy.simple.as_ref().and_then(|s| s.analysis.as_ref()),
+        ),
+        (
+            "blueGreen",
+            strategy
+                .blue_green
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref()),
+        ),
+        (
+            "abTesting",
+            strategy
+                .ab_testing
+                .as_ref()
+                .and_then(|s| s.analysis.as_ref()),
+        ),
+    ];
+    for (name, analysis) in non_canary_analyses {
+        if let Some(j) = analysis.and_then(|a| {
+            a.metrics
+                .iter()
+                .position(|m| m.compare_to_baseline == Some(true))
+        }) {
+            return Err(format!(
+                "spec.strategy.{}.analysis.metrics[{}].compareToBaseline is only supported for canary",
+                name, j
+            ));
+        }
+    }
+
+    // Replica weighting and NGINX canary weights only make sense for weight-based canary steps
+    let non_canary_routing = [
+        (
+            "blueGreen",
+            rollout
+                .spec
+                .strategy
+                .blue_green
+                .as_ref()
+                .and_then(|s| s.traffic_routing.as_ref()),
+        ),
+        (
+            "abTesting",
+            rollout
+                .spec
+                .strategy
+                .ab_testing
+                .as_ref()
+                .and_then(|s| s.traffic_routing.as_ref()),
+        ),
+    ];
+    for (strategy, routing) in non_canary_routing {
+        if routing.is_some_and(|r| r.none.is_some()) {
+            return Err(format!(
+                "spec.strategy.{}.trafficRouting.none is only supported for canary",
+                strategy
+            ));
+        }
+        if routing.is_some_and(|r| r.nginx.is_some()) {
+            return Err(format!(
+                "spec.strategy.{}.trafficRouting.nginx is only supported for canary",
+                strategy
+            ));
+        }
+        if let Some(gateway) = routing.and_then(|r| r.gateway_api.as_ref()) {
+            let path = format!("spec.strategy.{}.trafficRouting.gatewayAPI", strategy);
+            if strategy == "abTesting" && !gateway.sections.is_empty() {
+                return Err(format!(
+                    "{}.sections is not supported for abTesting (variants own the whole route)",
+                    path
+                ));
+            }
+            validate_route_sections(&path, gateway)?;
+        }
+    }
+
+    // A/B variants are selected by header and cookie matches, which only HTTPRoutes have
+    if let Some(gateway) = rollout
+        .spec
+        .strategy
+        .ab_testing
+        .as_ref()
+        .and_then(|ab| ab.traffic_routing.as_ref())
+        .and_then(|routing| routing.gateway_api.as_ref())
+    {
+        if gateway.kind != GatewayRouteKind::HTTPRoute {
+            return Err(format!(
+                "spec.strategy.abTesting.trafficRouting.gatewayAPI.kind must be HTTPRoute, got {:?}",
+                gateway.kind
+            ));
+        }
+    }
+
+    // A/B guardrails must use a metric template that can be queried per variant
+    if let Some(analysis) = rollout
+        .spec
+        .strategy
+        .ab_testing
+        .as_ref()
+        .and_then(|ab| ab.analysis.as_ref())
+    {
+        for (i, guardrail) in analysis.guardrails.iter().enumerate() {
+            if !matches!(guardrail.name.as_str(), "error-rate" | "latency-p95") {
+                return Err(format!(
+                    "spec.strategy.abTesting.analysis.guardrails[{}].name must be error-rate or latency-p95, got '{}'",
+                    i, guardrail.name
+                ));
+            }
+            if !guardrail.threshold.is_finite() {
+                return Err(format!(
+                    "spec.strategy.abTesting.analysis.guardrails[{}].threshold must be a finite number",
+                    i
+                ));
+            }
+        }
+        if let Some(window) = &analysis.sample_window {
+            let start = window.start_hour.unwrap_or(0);
+            let end = window.end_hour.unwrap_or(24);
+            if start > 23 || end > 24 || start >= end {
+                return Err(format!(
+                    "spec.strategy.abTesting.analysis.sampleWindow hours must satisfy 0 <= startHour < endHour <= 24, got {}-{}",
+                    start, end
+                ));
+            }
+            if let Some(offset) = window.utc_offset_hours {
+                if !(-12..=14).contains(&offset) {
+                    return Err(format!(
+                        "spec.strategy.abTesting.analysis.sampleWindow.utcOffsetHours must be -12 to 14, got {}",
+                        offset
+                    ));
+                }
+            }
+            if let Some(lookback) = &window.lookback {
+                if !is_valid_promql_duration(lookback) {
+                    return Err(format!(
+                        "spec.strategy.abTesting.analysis.sampleWindow.lookback invalid: {}",
+                        lookback
+                    ));
+                }
             }
         }
     }
@@ -114,9 +753,402 @@ pub fn validate_rollout(rollout: &Rollout) -> Result<(), String> {
         }
     }
 
+    if let Some(limit) = rollout.spec.revision_history_limit {
+        if limit < 0 {
+            return Err(format!(
+                "spec.revisionHistoryLimit must be >= 0, got {}",
+                limit
+            ));
+        }
+    }
+
+    if let Some(after) = rollout
+        .spec
+        .on_degraded
+        .as_ref()
+        .and_then(|policy| policy.after_seconds)
+    {
+        if after < 0 {
+            return Err(format!(
+                "spec.onDegraded.afterSeconds must be >= 0, got {}",
+                after
+            ));
+        }
+    }
+
+    if let Some(confidence) = rollout.spec.advisor.min_confidence {
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(format!(
+                "spec.advisor.minConfidence must be between 0 and 1, got {}",
+                confidence
+            ));
+        }
+    }
+
+    if let Some(workload_ref) = &rollout.spec.workload_ref {
+        if workload_ref.name.is_empty() {
+            return Err("spec.workloadRef.name cannot be empty".to_string());
+        }
+        let inline_containers = rollout
+            .spec
+            .template
+            .spec
+            .as_ref()
+            .is_some_and(|spec| !spec.containers.is_empty());
+        if inline_containers {
+            return Err("spec.workloadRef and spec.template are mutually exclusive".to_string());
+        }
+    }
+
     Ok(())
 }
 
+/// Validate an Experiment specification
+///
+/// # Validation Rules
+/// - `templates` must not be empty; each needs a unique name of lowercase
+///   letters, digits and `-`, and `replicas` >= 1
+/// - `duration` must be a valid duration (e.g., "10m")
+/// - Each analysis needs a unique name and at least one metric; custom
+///   queries and web metrics are checked as in a Rollout's analysis
+pub fn validate_experiment(experiment: &Experiment) -> Result<(), String> {
+    validate_experiment_parts(
+        "spec",
+        experiment
+            .spec
+            .templates
+            .iter()
+            .map(|t| (t.name.as_str(), t.replicas)),
+        &experiment.spec.duration,
+        &experiment.spec.analyses,
+    )
+}
+
+/// Check the templates (name, replicas), duration and analyses of an
+/// Experiment or a step's `experiment`
+fn validate_experiment_parts<'a>(
+    path: &str,
+    templates: impl Iterator<Item = (&'a str, i32)>,
+    duration: &str,
+    analyses: &[ExperimentAnalysis],
+) -> Result<(), String> {
+    let mut names = Vec::new();
+    for (i, (name, replicas)) in templates.enumerate() {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid {
+            return Err(format!(
+                "{}.templates[{}].name '{}' must be lowercase letters, digits and '-'",
+                path, i, name
+            ));
+        }
+        if names.contains(&name) {
+            return Err(format!(
+                "{}.templates[{}].name '{}' is used twice",
+                path, i, name
+            ));
+        }
+        if replicas < 1 {
+            return Err(format!(
+                "{}.templates[{}].replicas must be >= 1, got {}",
+                path, i, replicas
+            ));
+        }
+        names.push(name);
+    }
+    if names.is_empty() {
+        return Err(format!(
+            "{}.templates must have at least one template",
+            path
+        ));
+    }
+    if parse_duration(duration).is_none() {
+        return Err(format!("{}.duration invalid: {}", path, duration));
+    }
+    let mut analysis_names = Vec::new();
+    for (i, analysis) in analyses.iter().enumerate() {
+        if analysis.name.is_empty() || analysis_names.contains(&analysis.name.as_str()) {
+            return Err(format!(
+                "{}.analyses[{}].name must be set and unique",
+                path, i
+            ));
+        }
+        analysis_names.push(analysis.name.as_str());
+        if analysis.analysis.metrics.is_empty() {
+            return Err(format!(
+                "{}.analyses[{}].metrics must have at least one metric",
+                path, i
+            ));
+        }
+        for (j, metric) in analysis.analysis.metrics.iter().enumerate() {
+            let metric_path = format!("{}.analyses[{}].metrics[{}]", path, i, j);
+            if metric.compare_to_baseline == Some(true) {
+                return Err(format!(
+                    "{}.compareToBaseline is not supported in experiments",
+                    metric_path
+                ));
+            }
+            validate_metric_query(&metric_path, metric)?;
+        }
+        if let Some(prometheus) = &analysis.analysis.prometheus {
+            validate_prometheus_config(
+                &format!("{}.analyses[{}].prometheus", path, i),
+                prometheus,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Check a metric's custom `query` or `web` endpoint: known variables,
+/// well-formed once rendered, and no comparison with the stable revision
+/// or the baseline ReplicaSet
+fn validate_metric_query(path: &str, metric: &MetricConfig) -> Result<(), String> {
+    validate_volume_weighting(path, metric)?;
+    let stable_comparison = if metric.baseline_offset.is_some() {
+        Some("baselineOffset")
+    } else if metric.compare_to_stable == Some(true) {
+        Some("compareToStable")
+    } else {
+        None
+    };
+    if metric.compare_to_baseline == Some(true) {
+        if let Some(field) = stable_comparison {
+            return Err(format!(
+                "{}.compareToBaseline can't be combined with {}",
+                path, field
+            ));
+        }
+    }
+    let stable_comparison = stable_comparison
+        .or((metric.compare_to_baseline == Some(true)).then_some("compareToBaseline"));
+    if let Some(web) = &metric.web {
+        if metric.query.is_some() {
+            return Err(format!("{}: query and web are mutually exclusive", path));
+        }
+        if let Some(field) = stable_comparison {
+            return Err(format!("{}.web can't be combined with {}", path, field));
+        }
+        return validate_web_metric(web).map_err(|e| format!("{}.web: {}", path, e));
+    }
+    let Some(query) = &metric.query else {
+        return Ok(());
+    };
+    if let Some(field) = stable_comparison {
+        return Err(format!("{}.query can't be combined with {}", path, field));
+    }
+    render_query(query, "rollout", "namespace", "revision")
+        .map(|_| ())
+        .map_err(|e| format!("{}.query invalid: {}", path, e))
+}
+
+/// Check `volumeWeighting`: it weights a stable comparison, so needs one
+fn validate_volume_weighting(path: &str, metric: &MetricConfig) -> Result<(), String> {
+    let Some(weighting) = &metric.volume_weighting else {
+        return Ok(());
+    };
+    if !metric.compares_to_stable() {
+        return Err(format!(
+            "{}.volumeWeighting requires compareToStable or baselineOffset",
+            path
+        ));
+    }
+    if weighting.full_weight_requests() < 1 {
+        return Err(format!(
+            "{}.volumeWeighting.fullWeightRequests must be >= 1",
+            path
+        ));
+    }
+    Ok(())
+}
+
+/// Check the credentials and TLS settings of `analysis.prometheus`
+fn validate_prometheus_config(path: &str, config: &PrometheusConfig) -> Result<(), String> {
+    if config.bearer_token_secret_ref.is_some() && config.basic_auth.is_some() {
+        return Err(format!(
+            "{}: bearerTokenSecretRef and basicAuth are mutually exclusive",
+            path
+        ));
+    }
+    if config
+        .basic_auth
+        .as_ref()
+        .is_some_and(|basic| basic.username.is_empty())
+    {
+        return Err(format!("{}.basicAuth.username must not be empty", path));
+    }
+    let tls = config.tls.as_ref();
+    if tls.is_some_and(|tls| tls.cert_secret_ref.is_some() != tls.key_secret_ref.is_some()) {
+        return Err(format!(
+            "{}.tls: certSecretRef and keySecretRef must be set together",
+            path
+        ));
+    }
+    let refs = [
+        (
+            "bearerTokenSecretRef",
+            config.bearer_token_secret_ref.as_ref(),
+        ),
+        (
+            "basicAuth.passwordSecretRef",
+            config.basic_auth.as_ref().map(|b| &b.password_secret_ref),
+        ),
+        (
+            "tls.caSecretRef",
+            tls.and_then(|t| t.ca_secret_ref.as_ref()),
+        ),
+        (
+            "tls.certSecretRef",
+            tls.and_then(|t| t.cert_secret_ref.as_ref()),
+        ),
+        (
+            "tls.keySecretRef",
+            tls.and_then(|t| t.key_secret_ref.as_ref()),
+        ),
+    ];
+    for (field, key_ref) in refs {
+        if key_ref.is_some_and(|r| r.name.is_empty() || r.key.is_empty()) {
+            return Err(format!("{}.{} needs a name and key", path, field));
+        }
+    }
+    Ok(())
+}
+
+/// Check `gatewayAPI.sections`: HTTPRoutes only, each a managed route listed
+/// once, with a valid rule name
+fn validate_route_sections(path: &str, gateway: &GatewayAPIRouting) -> Result<(), String> {
+    if gateway.sections.is_empty() {
+        return Ok(());
+    }
+    if gateway.kind != GatewayRouteKind::HTTPRoute {
+        return Err(format!(
+            "{}.sections requires kind HTTPRoute, got {:?}",
+            path, gateway.kind
+        ));
+    }
+    let routes = gateway.route_names();
+    for (i, section) in gateway.sections.iter().enumerate() {
+        if !routes.contains(&section.http_route.as_str()) {
+            return Err(format!(
+                "{}.sections[{}].httpRoute '{}' is not one of the managed HTTPRoutes",
+                path, i, section.http_route
+            ));
+        }
+        if gateway.sections[..i]
+            .iter()
+            .any(|other| other.http_route == section.http_route)
+        {
+            return Err(format!(
+                "{}.sections[{}]: HTTPRoute '{}' is listed more than once",
+                path, i, section.http_route
+            ));
+        }
+        // The header route rule is written as `{sectionName}-header`
+        let header_rule = format!("{}-header", section.section_name);
+        if !is_valid_section_name(&section.section_name) || !is_valid_section_name(&header_rule) {
+            return Err(format!(
+                "{}.sections[{}].sectionName '{}' must be a lowercase DNS name of at most 56 characters per label",
+                path, i, section.section_name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` is a Gateway API SectionName (a lowercase RFC 1123 subdomain)
+fn is_valid_section_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        })
+}
+
+/// Check canary step weights against the gateway's weight constraints
+///
+/// Unless `normalize` is set, every step weight (and its stable complement)
+/// must be one the gateway can honour; otherwise the route update would be
+/// rejected or silently altered by the proxy.
+fn validate_weight_constraints(
+    constraints: &WeightConstraints,
+    steps: &[CanaryStep],
+) -> Result<(), String> {
+    const FIELD: &str = "spec.strategy.canary.trafficRouting.gatewayAPI.weightConstraints";
+
+    if let Some(granularity) = constraints.granularity {
+        if !(1..=100).contains(&granularity) || 100 % granularity != 0 {
+            return Err(format!(
+                "{}.granularity must divide 100 (e.g., 5, 10, 25), got {}",
+                FIELD, granularity
+            ));
+        }
+    }
+    if let Some(min_weight) = constraints.min_weight {
+        if !(1..=50).contains(&min_weight) {
+            return Err(format!(
+                "{}.minWeight must be 1-50, got {}",
+                FIELD, min_weight
+            ));
+        }
+    }
+    if partial_weight_range(constraints).is_none() {
+        return Err(format!(
+            "{}: granularity and minWeight leave no canary weight between 0 and 100",
+            FIELD
+        ));
+    }
+
+    if constraints.normalize == Some(true) {
+        return Ok(());
+    }
+    for (i, step) in steps.iter().enumerate() {
+        let weight = step.set_weight.unwrap_or(0);
+        if !is_supported_weight(weight, constraints) {
+            return Err(format!(
+                "steps[{}].setWeight {} is not supported by the gateway ({}: granularity {}, minWeight {}); use {} or set normalize: true",
+                i,
+                weight,
+                FIELD,
+                constraints.granularity.unwrap_or(1),
+                constraints.min_weight.unwrap_or(1),
+                normalize_weight(weight, constraints)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Serialized pod template size above which a warning is raised
+///
+/// Each ReplicaSet embeds a full copy of the template and Kubernetes rejects
+/// objects over ~1.5 MiB, so templates this large are close to failing.
+pub const POD_TEMPLATE_WARN_BYTES: usize = 256 * 1024;
+
+/// Warning for an oversized pod template, if it is one
+///
+/// Large templates are allowed; the warning is surfaced as an admission
+/// warning and logged on reconcile.
+///
+/// # Arguments
+/// * `size` - Serialized template size in bytes (see `digest_pod_template`)
+pub fn pod_template_size_warning(size: usize) -> Option<String> {
+    (size > POD_TEMPLATE_WARN_BYTES).then(|| {
+        format!(
+            "spec.template is {} KiB (warning threshold {} KiB); ReplicaSets copy the template and objects over ~1.5 MiB are rejected by the API server",
+            size / 1024,
+            POD_TEMPLATE_WARN_BYTES / 1024
+        )
+    })
+}
+
 /// Parse a duration string like "5m", "30s", "1h" into std::time::Duration
 ///
 /// Supported formats:
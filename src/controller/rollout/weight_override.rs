@@ -0,0 +1,298 @@
+//! Manual canary weight overrides
+//!
+//! `kulta.io/set-weight: "35"` sets the canary weight of an in-flight canary
+//! rollout outside its step plan, to drain traffic in an emergency or try a
+//! weight without editing the steps. The override is recorded in
+//! `status.weightOverride` with a `SetWeight`/`ManualOverride` decision and
+//! holds until the rollout leaves the step (a timed pause, analysis and
+//! promotion move it on as usual); the annotation is removed once applied.
+//!
+//! Raising the weight above `KULTA_MAX_MANUAL_WEIGHT` is refused (see
+//! [`GuardrailPolicy::check_manual_weight`]), and so are weights the gateway
+//! constraints can't honour unless they normalize weights. A refused request
+//! is removed as well, with the reason in the status message.
+
+use crate::controller::guardrails::{GuardrailPolicy, GuardrailViolation};
+use crate::controller::strategy_math::{
+    canary_steps, is_supported_weight, normalize_weight, step_at, step_weight, weight_constraints,
+};
+use crate::crd::rollout::{
+    Decision, DecisionAction, DecisionReason, Phase, Rollout, RolloutStatus, WeightOverride,
+};
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// Rollout annotation setting the canary weight; the value is a percentage
+pub const SET_WEIGHT_ANNOTATION: &str = "kulta.io/set-weight";
+
+/// Why a `kulta.io/set-weight` request was refused
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WeightOverrideError {
+    #[error("'{0}' is not a weight between 0 and 100")]
+    InvalidWeight(String),
+
+    #[error("only canary rollouts have a traffic weight")]
+    NotCanary,
+
+    #[error("the rollout is not at a canary step")]
+    NotInProgress,
+
+    #[error("the gateway weight constraints don't allow {0}%")]
+    UnsupportedWeight(i32),
+
+    #[error(transparent)]
+    Guardrail(#[from] GuardrailViolation),
+}
+
+/// Value of `kulta.io/set-weight`, if the annotation is set
+pub fn requested_weight(rollout: &Rollout) -> Option<&str> {
+    rollout
+        .metadata
+        .annotations
+        .as_ref()?
+        .get(SET_WEIGHT_ANNOTATION)
+        .map(|value| value.trim())
+}
+
+/// Whether a status is at a canary step whose weight can be overridden
+fn is_overridable(rollout: &Rollout, status: &RolloutStatus) -> bool {
+    let steps = canary_steps(&rollout.spec).len();
+    status.aborted_at.is_none()
+        && matches!(status.phase, Some(Phase::Progressing | Phase::Paused))
+        && status
+            .current_step_index
+            .and_then(|index| usize::try_from(index).ok())
+            .is_some_and(|index| index < steps)
+}
+
+/// Weight a `kulta.io/set-weight` value sets, if the request is allowed
+///
+/// # Arguments
+/// * `value` - Annotation value
+/// * `desired` - Status the rollout is about to get
+/// * `guardrails` - Controller-wide limits
+pub fn validate_weight_request(
+    rollout: &Rollout,
+    desired: &RolloutStatus,
+    value: &str,
+    guardrails: &GuardrailPolicy,
+) -> Result<i32, WeightOverrideError> {
+    if rollout.spec.strategy.canary.is_none() {
+        return Err(WeightOverrideError::NotCanary);
+    }
+    if !is_overridable(rollout, desired) {
+        return Err(WeightOverrideError::NotInProgress);
+    }
+    let weight = value
+        .parse::<i32>()
+        .ok()
+        .filter(|weight| (0..=100).contains(weight))
+        .ok_or_else(|| WeightOverrideError::InvalidWeight(value.to_string()))?;
+    let weight = match weight_constraints(&rollout.spec) {
+        Some(constraints) if constraints.normalize == Some(true) => {
+            normalize_weight(weight, constraints)
+        }
+        Some(constraints) if !is_supported_weight(weight, constraints) => {
+            return Err(WeightOverrideError::UnsupportedWeight(weight));
+        }
+        _ => weight,
+    };
+    guardrails.check_manual_weight(weight, desired.current_weight.unwrap_or(0))?;
+    Ok(weight)
+}
+
+/// Keep an earlier override while the rollout stays at its step and apply a
+/// `kulta.io/set-weight` request to the desired status
+///
+/// # Returns
+/// The resulting status, and the annotation that was handled (to be removed)
+pub fn apply_weight_override(
+    rollout: &Rollout,
+    mut desired: RolloutStatus,
+    guardrails: &GuardrailPolicy,
+    now: DateTime<Utc>,
+) -> (RolloutStatus, Option<&'static str>) {
+    desired.weight_override = rollout
+        .status
+        .as_ref()
+        .and_then(|status| status.weight_override.clone())
+        .filter(|current| {
+            Some(current.step_index) == desired.current_step_index
+                && is_overridable(rollout, &desired)
+        });
+    if let Some(current) = &desired.weight_override {
+        desired.current_weight = Some(current.weight);
+    }
+
+    let Some(value) = requested_weight(rollout) else {
+        return (desired, None);
+    };
+    let weight = match validate_weight_request(rollout, &desired, value, guardrails) {
+        Ok(weight) => weight,
+        Err(e) => {
+            desired.message = Some(format!("{} ignored: {}", SET_WEIGHT_ANNOTATION, e));
+            return (desired, Some(SET_WEIGHT_ANNOTATION));
+        }
+    };
+
+    // is_overridable guarantees a step
+    let step_index = desired.current_step_index.unwrap_or_default();
+    let planned = step_at(&rollout.spec, step_index)
+        .map(|step| step_weight(&rollout.spec, step))
+        .unwrap_or_default();
+    let message = format!(
+        "Canary weight set to {}% by {} (step weight {}%)",
+        weight, SET_WEIGHT_ANNOTATION, planned
+    );
+    desired.decisions.push(Decision {
+        timestamp: now.to_rfc3339(),
+        action: DecisionAction::SetWeight,
+        from_step: Some(step_index),
+        to_step: Some(step_index),
+        reason: DecisionReason::ManualOverride,
+        message: Some(message.clone()),
+        metrics: None,
+        count: None,
+        last_timestamp: None,
+        approved_by: None,
+    });
+    desired.weight_override = Some(WeightOverride {
+        weight,
+        step_index,
+        set_at: now.to_rfc3339(),
+    });
+    desired.current_weight = Some(weight);
+    desired.message = Some(message);
+    (desired, Some(SET_WEIGHT_ANNOTATION))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+
+    fn rollout(annotation: Option<&str>, weight_constraints: serde_json::Value) -> Rollout {
+        let mut builder = RolloutBuilder::new("shop")
+            .namespace("prod")
+            .replicas(3)
+            .label("app", "shop")
+            .image("app", "shop:2")
+            .canary(
+                serde_json::from_value(serde_json::json!({
+                    "stableService": "shop", "canaryService": "shop-canary",
+                    "steps": [
+                        { "setWeight": 20, "pause": {} },
+                        { "setWeight": 60 }
+                    ],
+                    "trafficRouting": { "gatewayAPI": {
+                        "httpRoute": "shop",
+                        "weightConstraints": weight_constraints
+                    } }
+                }))
+                .unwrap(),
+            );
+        if let Some(value) = annotation {
+            builder = builder.annotation(SET_WEIGHT_ANNOTATION, value);
+        }
+        let mut rollout = builder.build().unwrap();
+        rollout.status = Some(at_step(0, 20));
+        rollout
+    }
+
+    fn at_step(step: i32, weight: i32) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(Phase::Paused),
+            current_step_index: Some(step),
+            current_weight: Some(weight),
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-02T09:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_set_weight_records_override_and_decision() {
+        let rollout = rollout(Some("35"), serde_json::json!({}));
+
+        let (status, handled) =
+            apply_weight_override(&rollout, at_step(0, 20), &GuardrailPolicy::default(), now());
+
+        assert_eq!(handled, Some(SET_WEIGHT_ANNOTATION));
+        assert_eq!(status.current_weight, Some(35));
+        assert_eq!(
+            status.weight_override,
+            Some(WeightOverride {
+                weight: 35,
+                step_index: 0,
+                set_at: now().to_rfc3339(),
+            })
+        );
+        let decision = status.decisions.last().unwrap();
+        assert_eq!(decision.action, DecisionAction::SetWeight);
+        assert_eq!(decision.reason, DecisionReason::ManualOverride);
+    }
+
+    #[test]
+    fn test_override_holds_until_the_step_changes() {
+        let mut rollout = rollout(None, serde_json::json!({}));
+        let mut overridden = at_step(0, 35);
+        overridden.weight_override = Some(WeightOverride {
+            weight: 35,
+            step_index: 0,
+            set_at: now().to_rfc3339(),
+        });
+        rollout.status = Some(overridden);
+        let policy = GuardrailPolicy::default();
+
+        let (same_step, handled) = apply_weight_override(&rollout, at_step(0, 20), &policy, now());
+        assert_eq!(handled, None);
+        assert_eq!(same_step.current_weight, Some(35));
+
+        let (next_step, _) = apply_weight_override(&rollout, at_step(1, 60), &policy, now());
+        assert_eq!(next_step.current_weight, Some(60));
+        assert_eq!(next_step.weight_override, None);
+    }
+
+    #[test]
+    fn test_refused_requests_leave_the_weight_alone() {
+        let policy = GuardrailPolicy {
+            max_manual_weight: Some(50),
+            ..Default::default()
+        };
+        let refuse = |value: &str, constraints: serde_json::Value| {
+            let rollout = rollout(Some(value), constraints);
+            let (status, handled) = apply_weight_override(&rollout, at_step(0, 20), &policy, now());
+            assert_eq!(handled, Some(SET_WEIGHT_ANNOTATION));
+            assert_eq!(status.current_weight, Some(20));
+            assert!(status.decisions.is_empty());
+            status.message.unwrap()
+        };
+
+        assert!(refuse("lots", serde_json::json!({})).contains("not a weight"));
+        assert!(refuse("120", serde_json::json!({})).contains("not a weight"));
+        assert!(refuse("80", serde_json::json!({})).contains("policy maximum of 50"));
+        assert!(refuse("33", serde_json::json!({ "granularity": 5 })).contains("33%"));
+
+        let mut aborted = at_step(0, 0);
+        aborted.aborted_at = Some(now().to_rfc3339());
+        let rollout = rollout(Some("10"), serde_json::json!({}));
+        let (status, _) = apply_weight_override(&rollout, aborted, &policy, now());
+        assert!(status.message.unwrap().contains("not at a canary step"));
+    }
+
+    #[test]
+    fn test_set_weight_normalizes_when_constraints_ask() {
+        let rollout = rollout(
+            Some("33"),
+            serde_json::json!({ "granularity": 5, "normalize": true }),
+        );
+
+        let (status, _) =
+            apply_weight_override(&rollout, at_step(0, 20), &GuardrailPolicy::default(), now());
+
+        assert_eq!(status.current_weight, Some(35));
+    }
+}
@@ -1,12 +1,17 @@
 use super::*;
 use crate::controller::clock::MockClock;
-use crate::controller::prometheus::MockPrometheusClient;
+use crate::controller::prometheus::{MockPrometheusClient, RevisionSelector};
+use crate::controller::strategy_math::current_weight;
 use crate::crd::rollout::{
     ABAnalysisConfig, ABConclusionReason, ABExperimentStatus, ABHeaderMatch, ABMatch, ABStrategy,
-    ABVariant, CanaryStep, CanaryStrategy, GatewayAPIRouting, PauseDuration, Phase, Rollout,
-    RolloutSpec, RolloutStatus, RolloutStrategy, SimpleStrategy, TrafficRouting,
+    ABVariant, CanaryStep, CanaryStrategy, Decision, DecisionAction, DecisionReason,
+    DegradedAction, DegradedPolicy, GatewayAPIRouting, ManagedReplicaSet, ManagedResources,
+    MigratedFrom, NoTrafficRouting, Pause, Phase, ReplicaSetRole, Rollout, RolloutSpec,
+    RolloutStatus, RolloutStrategy, RouteSection, SetCanaryScale, SetHeaderRoute, SimpleStrategy,
+    TrafficRouting, WeightConstraints,
 };
 use chrono::Utc;
+use k8s_openapi::api::apps::v1::ReplicaSet;
 use kube::api::ObjectMeta;
 use std::sync::Arc;
 use std::time::Duration;
@@ -65,6 +70,8 @@ fn create_ab_rollout_with_analysis(
                         min_duration: min_duration.map(|s| s.to_string()),
                         min_sample_size,
                         confidence_level,
+                        guardrails: vec![],
+                        sample_window: None,
                     }),
                 }),
             },
@@ -72,6 +79,11 @@ fn create_ab_rollout_with_analysis(
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             phase: Some(phase),
@@ -136,6 +148,11 @@ fn create_test_rollout_with_simple() -> Rollout {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     }
@@ -190,6 +207,7 @@ fn create_test_rollout_with_blue_green() -> Rollout {
                     auto_promotion_seconds: None,
                     traffic_routing: None,
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -197,6 +215,11 @@ fn create_test_rollout_with_blue_green() -> Rollout {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     }
@@ -287,6 +310,11 @@ fn test_ab_testing_creates_variant_replicasets() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -431,12 +459,21 @@ fn create_test_rollout_with_canary() -> Rollout {
                     steps: vec![], // Tests will set their own steps
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
             max_surge: None,
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     }
@@ -491,14 +528,30 @@ async fn test_reconcile_creates_stable_replicaset() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None, // No HTTPRoute for ReplicaSet unit tests
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -506,6 +559,11 @@ async fn test_reconcile_creates_stable_replicaset() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -569,6 +627,49 @@ async fn test_compute_pod_template_hash() {
     assert_ne!(hash1, hash3);
 }
 
+#[test]
+fn test_digest_pod_template_matches_serialized_json() {
+    use k8s_openapi::api::core::v1::EnvVar;
+
+    let mut container = k8s_openapi::api::core::v1::Container {
+        name: "app".to_string(),
+        image: Some("nginx:1.0".to_string()),
+        ..Default::default()
+    };
+    container.env = Some(
+        (0..2000)
+            .map(|i| EnvVar {
+                name: format!("SETTING_{}", i),
+                value: Some("x".repeat(64)),
+                ..Default::default()
+            })
+            .collect(),
+    );
+    let template = k8s_openapi::api::core::v1::PodTemplateSpec {
+        metadata: None,
+        spec: Some(k8s_openapi::api::core::v1::PodSpec {
+            containers: vec![container],
+            ..Default::default()
+        }),
+    };
+
+    let digest = digest_pod_template(&template).unwrap();
+
+    // Streaming must hash exactly the bytes of the serialized template
+    let json = serde_json::to_string(&template).unwrap();
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in json.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    assert_eq!(digest.size, json.len());
+    assert_eq!(digest.hash, format!("{hash:x}")[..10]);
+    assert_eq!(compute_pod_template_hash(&template).unwrap(), digest.hash);
+
+    assert!(pod_template_size_warning(POD_TEMPLATE_WARN_BYTES + 1).is_some());
+    assert!(pod_template_size_warning(1024).is_none());
+}
+
 #[tokio::test]
 async fn test_build_replicaset_spec() {
     // Test that we can build a ReplicaSet from a Rollout
@@ -617,6 +718,10 @@ async fn test_build_replicaset_spec() {
                     steps: vec![],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -624,6 +729,11 @@ async fn test_build_replicaset_spec() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -704,9 +814,19 @@ async fn test_reconcile_creates_canary_replicaset() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None, // No HTTPRoute for ReplicaSet unit tests
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -714,6 +834,11 @@ async fn test_reconcile_creates_canary_replicaset() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -803,6 +928,10 @@ async fn test_replicaset_has_kulta_managed_label() {
                     steps: vec![],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -810,6 +939,11 @@ async fn test_replicaset_has_kulta_managed_label() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -876,6 +1010,30 @@ async fn test_replicaset_has_kulta_managed_label() {
     );
 }
 
+#[test]
+fn test_replicaset_pods_carry_revision_label() {
+    let rollout = create_test_rollout_with_canary();
+    let canary_rs = build_replicaset(&rollout, "canary", 1).unwrap();
+    let spec = canary_rs.spec.as_ref().unwrap();
+    let hash = compute_pod_template_hash(&rollout.spec.template).unwrap();
+
+    let pod_labels = spec
+        .template
+        .as_ref()
+        .unwrap()
+        .metadata
+        .as_ref()
+        .unwrap()
+        .labels
+        .as_ref()
+        .unwrap();
+    assert_eq!(pod_labels.get(REVISION_LABEL), Some(&hash));
+
+    // Selectors are immutable, so the label stays out of them
+    let selector_labels = spec.selector.match_labels.as_ref().unwrap();
+    assert!(!selector_labels.contains_key(REVISION_LABEL));
+}
+
 #[tokio::test]
 async fn test_build_both_stable_and_canary_replicasets() {
     // Test that we can build both stable and canary ReplicaSets
@@ -925,6 +1083,10 @@ async fn test_build_both_stable_and_canary_replicasets() {
                     steps: vec![],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -932,6 +1094,11 @@ async fn test_build_both_stable_and_canary_replicasets() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -1049,18 +1216,40 @@ async fn test_calculate_traffic_weights_step0() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(100),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None, // No HTTPRoute for ReplicaSet unit tests
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1068,6 +1257,11 @@ async fn test_calculate_traffic_weights_step0() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0), // First step: 20% canary
@@ -1107,14 +1301,30 @@ async fn test_calculate_traffic_weights_step1() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1122,6 +1332,11 @@ async fn test_calculate_traffic_weights_step1() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(1), // Second step: 50% canary
@@ -1160,9 +1375,19 @@ async fn test_calculate_traffic_weights_no_step() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1170,6 +1395,11 @@ async fn test_calculate_traffic_weights_no_step() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None, // No status yet, default to 100% stable
     };
@@ -1206,14 +1436,30 @@ async fn test_calculate_traffic_weights_complete() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(100),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1221,6 +1467,11 @@ async fn test_calculate_traffic_weights_complete() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(1), // Last step: 100% canary
@@ -1259,9 +1510,19 @@ async fn test_calculate_traffic_weights_beyond_steps() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1269,6 +1530,11 @@ async fn test_calculate_traffic_weights_beyond_steps() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(5), // Beyond available steps (only 1 step)
@@ -1307,9 +1573,19 @@ async fn test_build_httproute_backend_weights() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None, // No HTTPRoute for ReplicaSet unit tests
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1317,6 +1593,11 @@ async fn test_build_httproute_backend_weights() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0), // 20% canary
@@ -1369,13 +1650,29 @@ async fn test_convert_to_gateway_api_backend_refs() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "test-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1383,6 +1680,11 @@ async fn test_convert_to_gateway_api_backend_refs() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0), // 20% canary
@@ -1441,6 +1743,11 @@ async fn test_gateway_api_backend_refs_no_canary_strategy() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -1478,14 +1785,30 @@ async fn test_initialize_rollout_status() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1493,6 +1816,11 @@ async fn test_initialize_rollout_status() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None, // No status yet - should be initialized
     };
@@ -1538,9 +1866,19 @@ async fn test_initialize_sets_progress_started_at() {
                     steps: vec![CanaryStep {
                         set_weight: Some(20),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1548,6 +1886,11 @@ async fn test_initialize_sets_progress_started_at() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -1594,14 +1937,30 @@ async fn test_should_progress_to_next_step() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None, // No pause - should progress immediately
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1609,6 +1968,11 @@ async fn test_should_progress_to_next_step() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -1650,17 +2014,31 @@ async fn test_should_not_progress_when_paused() {
                     steps: vec![
                         CanaryStep {
                             set_weight: Some(20),
-                            pause: Some(crate::crd::rollout::PauseDuration {
-                                duration: Some("5m".to_string()),
-                            }),
+                            pause: Some(Pause::Duration("5m".to_string())),
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1668,6 +2046,11 @@ async fn test_should_not_progress_when_paused() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -1706,14 +2089,30 @@ async fn test_advance_to_next_step() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1721,6 +2120,11 @@ async fn test_advance_to_next_step() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -1772,14 +2176,30 @@ async fn test_advance_preserves_progress_started_at() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1787,6 +2207,11 @@ async fn test_advance_preserves_progress_started_at() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -1832,14 +2257,30 @@ async fn test_advance_to_final_step() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(100), // Final step: 100% canary
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1847,6 +2288,11 @@ async fn test_advance_to_final_step() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -1898,14 +2344,30 @@ async fn test_compute_desired_status_for_new_rollout() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1913,6 +2375,11 @@ async fn test_compute_desired_status_for_new_rollout() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None, // No status - should be initialized
     };
@@ -1952,14 +2419,30 @@ async fn test_compute_desired_status_progresses_step() {
                         CanaryStep {
                             set_weight: Some(20),
                             pause: None, // No pause - should progress
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1967,6 +2450,11 @@ async fn test_compute_desired_status_progresses_step() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -2008,17 +2496,31 @@ async fn test_compute_desired_status_respects_pause() {
                     steps: vec![
                         CanaryStep {
                             set_weight: Some(20),
-                            pause: Some(crate::crd::rollout::PauseDuration {
-                                duration: Some("5m".to_string()),
-                            }),
+                            pause: Some(Pause::Duration("5m".to_string())),
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            experiment: None,
+                            analysis: None,
                         },
                     ],
                     analysis: None,
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -2026,6 +2528,11 @@ async fn test_compute_desired_status_respects_pause() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -2192,7 +2699,7 @@ fn test_parse_duration_reasonable_values_accepted() {
 
 #[test]
 fn test_should_progress_when_pause_duration_elapsed() {
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
     use chrono::{Duration, Utc};
 
     // Create a rollout with a step that has a 5m pause
@@ -2203,13 +2710,23 @@ fn test_should_progress_when_pause_duration_elapsed() {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration {
-                    duration: Some("5m".to_string()),
-                }),
+                pause: Some(Pause::Duration("5m".to_string())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2234,7 +2751,7 @@ fn test_should_progress_when_pause_duration_elapsed() {
 
 #[test]
 fn test_should_not_progress_when_pause_duration_not_elapsed() {
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
     use chrono::{Duration, Utc};
 
     // Create a rollout with a step that has a 5m pause
@@ -2245,13 +2762,23 @@ fn test_should_not_progress_when_pause_duration_not_elapsed() {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration {
-                    duration: Some("5m".to_string()),
-                }),
+                pause: Some(Pause::Duration("5m".to_string())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2274,9 +2801,205 @@ fn test_should_not_progress_when_pause_duration_not_elapsed() {
     );
 }
 
+#[test]
+fn test_until_time_pause_resumes_at_time() {
+    use crate::crd::rollout::{CanaryStep, Pause};
+    use chrono::{Duration, Utc};
+
+    let now = Utc::now();
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.steps = vec![
+            CanaryStep {
+                set_weight: Some(20),
+                pause: Some(Pause::UntilTime((now + Duration::hours(1)).to_rfc3339())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+            CanaryStep {
+                set_weight: Some(100),
+                pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+        ];
+    }
+    rollout.status = Some(initialize_rollout_status(&rollout, now));
+    assert_eq!(
+        rollout.status.as_ref().unwrap().paused_until,
+        Some((now + Duration::hours(1)).to_rfc3339())
+    );
+
+    assert!(!should_progress_to_next_step(&rollout, now));
+    assert!(should_progress_to_next_step(
+        &rollout,
+        now + Duration::hours(1)
+    ));
+}
+
+#[test]
+fn test_until_approved_pause_waits_for_approval_not_promote() {
+    use crate::crd::rollout::{CanaryStep, Pause};
+
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.steps = vec![
+            CanaryStep {
+                set_weight: Some(20),
+                pause: Some(Pause::UntilApproved),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+            CanaryStep {
+                set_weight: Some(100),
+                pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+        ];
+    }
+    rollout.status = Some(initialize_rollout_status(&rollout, Utc::now()));
+    assert_eq!(rollout.status.as_ref().unwrap().paused_until, None);
+    assert!(is_awaiting_approval(&rollout));
+
+    // kulta.io/promote doesn't resume it
+    rollout.metadata.annotations = Some(
+        [("kulta.io/promote".to_string(), "true".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    assert!(!should_progress_to_next_step(&rollout, Utc::now()));
+
+    // kulta.io/approved-by does, and the approver ends up in the message
+    rollout.metadata.annotations = Some(
+        [(APPROVED_BY_ANNOTATION.to_string(), "alice".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(approved_by(&rollout), Some("alice"));
+    let status = compute_desired_status(&rollout, Utc::now());
+    assert_eq!(status.current_step_index, Some(1));
+    assert!(
+        status
+            .message
+            .as_deref()
+            .unwrap()
+            .ends_with("approved by alice"),
+        "got: {:?}",
+        status.message
+    );
+
+    // ...and in a ManualApproval decision
+    let decision = status.decisions.last().unwrap();
+    assert_eq!(decision.action, DecisionAction::Resume);
+    assert_eq!(decision.reason, DecisionReason::ManualApproval);
+    assert_eq!((decision.from_step, decision.to_step), (Some(0), Some(1)));
+    assert_eq!(decision.approved_by.as_deref(), Some("alice"));
+}
+
+#[test]
+fn test_analysis_on_resume_resets_warmup_and_failures_after_manual_resume() {
+    use crate::crd::rollout::{AnalysisConfig, AnalysisOnResume, MetricFailureCount};
+
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.steps = vec![
+            CanaryStep {
+                set_weight: Some(20),
+                pause: Some(Pause::Indefinite),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+            CanaryStep {
+                set_weight: Some(50),
+                pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            },
+        ];
+        canary.analysis = Some(AnalysisConfig {
+            warmup_duration: Some("1m".to_string()),
+            ..AnalysisConfig::default()
+        });
+    }
+    let paused_at = Utc::now() - chrono::Duration::hours(1);
+    let mut status = initialize_rollout_status(&rollout, paused_at);
+    status.step_start_time = Some(paused_at.to_rfc3339());
+    status.metric_failures = Some(vec![MetricFailureCount {
+        metric: "error-rate".to_string(),
+        consecutive_failures: 2,
+        last_evaluation_time: None,
+    }]);
+    rollout.status = Some(status);
+    rollout.metadata.annotations = Some(
+        [("kulta.io/promote".to_string(), "true".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    let now = Utc::now();
+
+    // Continue (the default): the step's warmup and failure streak carry over
+    assert!(resumes_from_manual_pause(&rollout));
+    assert!(!resets_analysis_on_resume(&rollout));
+    let status = compute_desired_status(&rollout, now);
+    assert_eq!(status.current_step_index, Some(1));
+    assert_eq!(status.step_start_time, Some(paused_at.to_rfc3339()));
+    assert_eq!(status.metric_failures.as_ref().map(Vec::len), Some(1));
+
+    // Reset: warmup counts from the resume and the failures are cleared
+    if let Some(analysis) = rollout
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .and_then(|canary| canary.analysis.as_mut())
+    {
+        analysis.on_resume = Some(AnalysisOnResume::Reset);
+    }
+    assert!(resets_analysis_on_resume(&rollout));
+    let status = compute_desired_status(&rollout, now);
+    assert_eq!(status.current_step_index, Some(1));
+    assert_eq!(status.step_start_time, Some(now.to_rfc3339()));
+    assert_eq!(status.metric_failures, None);
+    assert!(status
+        .message
+        .as_deref()
+        .unwrap()
+        .ends_with("analysis restarted"));
+
+    // A pause that isn't resumed by hand doesn't reset anything
+    rollout.metadata.annotations = None;
+    assert!(!resumes_from_manual_pause(&rollout));
+    assert!(!resets_analysis_on_resume(&rollout));
+}
+
 #[test]
 fn test_advance_sets_pause_start_time() {
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
 
     // Create rollout with step that has pause
     let mut rollout = create_test_rollout_with_canary();
@@ -2286,13 +3009,23 @@ fn test_advance_sets_pause_start_time() {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration {
-                    duration: Some("5m".to_string()),
-                }),
+                pause: Some(Pause::Duration("5m".to_string())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2327,7 +3060,7 @@ fn test_advance_sets_pause_start_time() {
 
 #[test]
 fn test_advance_clears_pause_start_time_when_no_pause() {
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
 
     // Create rollout with step that has no pause
     let mut rollout = create_test_rollout_with_canary();
@@ -2337,13 +3070,23 @@ fn test_advance_clears_pause_start_time_when_no_pause() {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration {
-                    duration: Some("5m".to_string()),
-                }),
+                pause: Some(Pause::Duration("5m".to_string())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2392,16 +3135,28 @@ fn test_has_promote_annotation() {
     // which calls it internally
 
     // Add a pause step
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
     if let Some(ref mut canary) = rollout.spec.strategy.canary {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration { duration: None }), // Indefinite pause
+                pause: Some(Pause::Indefinite),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2422,9 +3177,109 @@ fn test_has_promote_annotation() {
     );
 }
 
+#[test]
+fn test_abort_annotation_fails_unfinished_rollout() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.metadata.annotations = Some(
+        [(ABORT_ANNOTATION.to_string(), "true".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(1),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+    let desired = rollout.status.clone().unwrap();
+
+    let (status, applied) = apply_abort_or_retry(&rollout, desired.clone(), Utc::now());
+    assert_eq!(status.phase, Some(Phase::Failed));
+    assert_eq!(status.current_step_index, Some(1));
+    assert_eq!(status.current_weight, Some(0));
+    assert!(status.aborted_at.is_some());
+    assert_eq!(applied, Some(ABORT_ANNOTATION));
+    let decision = status.decisions.last().unwrap();
+    assert_eq!(decision.action, DecisionAction::Rollback);
+    assert_eq!(decision.reason, DecisionReason::ManualRollback);
+    assert_eq!(decision.from_step, Some(1));
+
+    // All traffic back on stable, and the rollout holds there
+    let mut aborted = rollout.clone();
+    aborted.metadata.annotations = None;
+    aborted.status = Some(status);
+    assert_eq!(calculate_traffic_weights(&aborted), (100, 0));
+    assert!(!should_progress_to_next_step(&aborted, Utc::now()));
+
+    // A completed rollout can't be aborted
+    rollout.status.as_mut().unwrap().phase = Some(Phase::Completed);
+    let (status, applied) = apply_abort_or_retry(&rollout, desired.clone(), Utc::now());
+    assert_eq!(status, desired);
+    assert_eq!(applied, None);
+}
+
+#[test]
+fn test_roll_back_to_stable_holds_failed_rollout() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(1),
+        current_weight: Some(50),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+
+    let status = roll_back_to_stable(
+        &rollout,
+        rollout.status.clone().unwrap(),
+        DecisionReason::AnalysisFailed,
+        "Rollback triggered: metrics exceeded thresholds".to_string(),
+        Utc::now(),
+    );
+    assert_eq!(status.phase, Some(Phase::Failed));
+    assert_eq!(status.current_weight, Some(0));
+    assert!(status.aborted_at.is_some());
+    let decision = status.decisions.last().unwrap();
+    assert_eq!(decision.action, DecisionAction::Rollback);
+    assert_eq!(decision.reason, DecisionReason::AnalysisFailed);
+    assert_eq!(decision.from_step, Some(1));
+
+    // Traffic stays on stable and the failed rollout doesn't advance again
+    rollout.status = Some(status.clone());
+    assert_eq!(calculate_traffic_weights(&rollout), (100, 0));
+    assert_eq!(compute_desired_status(&rollout, Utc::now()), status);
+}
+
+#[test]
+fn test_retry_annotation_restarts_failed_rollout() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.metadata.annotations = Some(
+        [(RETRY_ANNOTATION.to_string(), "true".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(2),
+        phase: Some(Phase::Failed),
+        message: Some("Aborted: kulta.io/abort annotation set".to_string()),
+        aborted_at: Some("2026-01-01T00:00:00Z".to_string()),
+        ..Default::default()
+    });
+    let desired = rollout.status.clone().unwrap();
+
+    let (status, applied) = apply_abort_or_retry(&rollout, desired.clone(), Utc::now());
+    assert_eq!(status.phase, Some(Phase::Progressing));
+    assert_eq!(status.current_step_index, Some(0));
+    assert_eq!(status.aborted_at, None);
+    assert_eq!(applied, Some(RETRY_ANNOTATION));
+
+    // Only failed rollouts are retried
+    rollout.status.as_mut().unwrap().phase = Some(Phase::Progressing);
+    let (_, applied) = apply_abort_or_retry(&rollout, desired, Utc::now());
+    assert_eq!(applied, None);
+}
+
 #[test]
 fn test_should_progress_when_promoted() {
-    use crate::crd::rollout::{CanaryStep, PauseDuration, RolloutStatus};
+    use crate::crd::rollout::{CanaryStep, Pause, RolloutStatus};
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
     use std::collections::BTreeMap;
 
@@ -2435,11 +3290,23 @@ fn test_should_progress_when_promoted() {
         canary.steps = vec![
             CanaryStep {
                 set_weight: Some(20),
-                pause: Some(PauseDuration { duration: None }), // Indefinite pause
+                pause: Some(Pause::Indefinite),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
     }
@@ -2520,6 +3387,31 @@ fn test_calculate_replica_split_large_count() {
     assert_eq!(stable, 7, "Remaining should be 7 stable replicas");
 }
 
+#[test]
+fn test_calculate_replica_weighted_split() {
+    // Nearest replica, total never exceeds spec.replicas
+    assert_eq!(calculate_replica_weighted_split(10, 25), (7, 3));
+    assert_eq!(calculate_replica_weighted_split(10, 24), (8, 2));
+    assert_eq!(calculate_replica_weighted_split(4, 50), (2, 2));
+
+    // Non-zero weight keeps one canary, weight below 100 keeps one stable
+    assert_eq!(calculate_replica_weighted_split(4, 5), (3, 1));
+    assert_eq!(calculate_replica_weighted_split(4, 95), (1, 3));
+
+    // Edges
+    assert_eq!(calculate_replica_weighted_split(4, 0), (4, 0));
+    assert_eq!(calculate_replica_weighted_split(4, 100), (0, 4));
+    assert_eq!(calculate_replica_weighted_split(1, 50), (1, 0));
+    assert_eq!(calculate_replica_weighted_split(0, 50), (0, 0));
+}
+
+#[test]
+fn test_effective_replica_weight() {
+    assert_eq!(effective_replica_weight(7, 3), 30);
+    assert_eq!(effective_replica_weight(2, 1), 33);
+    assert_eq!(effective_replica_weight(0, 0), 0);
+}
+
 // TDD Cycle 2: RED - Test that reconcile scales ReplicaSets based on status
 #[tokio::test]
 async fn test_build_replicasets_with_canary_weight() {
@@ -2555,6 +3447,33 @@ async fn test_build_replicasets_with_canary_weight() {
     );
 }
 
+#[test]
+fn test_managed_replicaset_reports_revision_hash() {
+    let rollout = create_test_rollout_with_canary();
+    let canary_rs = build_replicaset(&rollout, "canary", 2).unwrap();
+    let hash = compute_pod_template_hash(&rollout.spec.template).unwrap();
+
+    let managed = managed_replicaset(&canary_rs, ReplicaSetRole::Canary, 2);
+
+    assert_eq!(
+        managed,
+        ManagedReplicaSet {
+            name: "test-rollout-canary".to_string(),
+            hash,
+            role: ReplicaSetRole::Canary,
+            replicas: 2,
+        }
+    );
+
+    // ReplicaSets without the label (e.g., created by hand) report an empty hash
+    let mut unlabeled = canary_rs.clone();
+    unlabeled.metadata.labels = None;
+    assert_eq!(
+        managed_replicaset(&unlabeled, ReplicaSetRole::Canary, 2).hash,
+        ""
+    );
+}
+
 #[tokio::test]
 async fn test_build_replicasets_at_initialization() {
     // ARRANGE: Create rollout with no status (initialization)
@@ -2645,10 +3564,22 @@ async fn test_replicaset_scaling_on_weight_change() {
         CanaryStep {
             set_weight: Some(20), // Step 0: 20% canary
             pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
         },
         CanaryStep {
             set_weight: Some(50), // Step 1: 50% canary
             pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
         },
     ];
 
@@ -2790,6 +3721,12 @@ async fn test_validate_rollout_weight_out_of_range() {
     rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
         set_weight: Some(150), // Invalid: > 100
         pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
     }];
 
     // ACT: Validate rollout
@@ -2812,6 +3749,12 @@ async fn test_validate_rollout_negative_weight() {
     rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
         set_weight: Some(-10), // Invalid: < 0
         pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
     }];
 
     // ACT: Validate rollout
@@ -2833,9 +3776,13 @@ async fn test_validate_rollout_invalid_pause_duration() {
     let mut rollout = create_test_rollout_with_canary();
     rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
         set_weight: Some(50),
-        pause: Some(PauseDuration {
-            duration: Some("invalid".to_string()), // Invalid format
-        }),
+        pause: Some(Pause::Duration("invalid".to_string())), // Invalid format
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
     }];
 
     // ACT: Validate rollout
@@ -2851,6 +3798,23 @@ async fn test_validate_rollout_invalid_pause_duration() {
     );
 }
 
+#[test]
+fn test_validate_rollout_pause_kinds() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].pause =
+        Some(Pause::UntilTime("2026-03-02T09:00:00Z".to_string()));
+    assert!(validate_rollout(&rollout).is_ok());
+
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].pause =
+        Some(Pause::UntilTime("tomorrow".to_string()));
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("pause.until must be an RFC3339 time"),
+        "got: {}",
+        error
+    );
+}
+
 #[tokio::test]
 async fn test_validate_rollout_empty_canary_service() {
     // ARRANGE: Create rollout with empty canary service name
@@ -2909,6 +3873,12 @@ async fn test_validate_rollout_empty_httproute() {
     rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
         set_weight: Some(50),
         pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
     }];
     rollout
         .spec
@@ -2919,7 +3889,13 @@ async fn test_validate_rollout_empty_httproute() {
         .traffic_routing = Some(TrafficRouting {
         gateway_api: Some(GatewayAPIRouting {
             http_route: String::new(), // Empty HTTPRoute name
+            http_routes: vec![],
+            weight_constraints: None,
+            sections: Vec::new(),
+            kind: Default::default(),
         }),
+        none: None,
+        nginx: None,
     });
 
     // ACT: Validate rollout
@@ -2935,93 +3911,721 @@ async fn test_validate_rollout_empty_httproute() {
     );
 }
 
-#[tokio::test]
-async fn test_validate_rollout_valid_rollout() {
-    // ARRANGE: Create valid rollout
+#[test]
+fn test_validate_rollout_accepts_http_routes_list() {
     let mut rollout = create_test_rollout_with_canary();
-    rollout.spec.replicas = 5;
-    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![
-        CanaryStep {
-            set_weight: Some(20),
-            pause: Some(PauseDuration {
-                duration: Some("30s".to_string()),
-            }),
-        },
-        CanaryStep {
-            set_weight: Some(100),
-            pause: None,
-        },
-    ];
-    rollout
-        .spec
-        .strategy
-        .canary
-        .as_mut()
-        .unwrap()
-        .traffic_routing = Some(TrafficRouting {
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(50),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    canary.traffic_routing = Some(TrafficRouting {
         gateway_api: Some(GatewayAPIRouting {
-            http_route: "my-httproute".to_string(),
+            http_route: String::new(),
+            http_routes: vec!["internal-route".to_string(), "external-route".to_string()],
+            weight_constraints: None,
+            sections: Vec::new(),
+            kind: Default::default(),
         }),
+        none: None,
+        nginx: None,
     });
 
-    // ACT: Validate rollout
-    let result = validate_rollout(&rollout);
+    assert!(validate_rollout(&rollout).is_ok());
 
-    // ASSERT: Should pass validation
-    assert!(
-        result.is_ok(),
-        "Expected valid rollout to pass, got error: {:?}",
-        result
-    );
+    // Statuses are reported per route once there is more than one
+    let statuses = crate::controller::strategies::synced_httproute_statuses(&rollout);
+    let names: Vec<&str> = statuses.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["internal-route", "external-route"]);
+    assert!(statuses.iter().all(|r| r.synced));
 }
 
-#[tokio::test]
-async fn test_validate_rollout_rejects_empty_canary_steps() {
-    // ARRANGE: Create rollout with empty steps
+#[test]
+fn test_validate_rollout_rejects_empty_name_in_http_routes() {
     let mut rollout = create_test_rollout_with_canary();
-    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![];
-
-    // ACT: Validate rollout
-    let result = validate_rollout(&rollout);
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(50),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    canary.traffic_routing = Some(TrafficRouting {
+        gateway_api: Some(GatewayAPIRouting {
+            http_route: "external-route".to_string(),
+            http_routes: vec![String::new()],
+            weight_constraints: None,
+            sections: Vec::new(),
+            kind: Default::default(),
+        }),
+        none: None,
+        nginx: None,
+    });
 
-    // ASSERT: Should fail validation - empty steps causes instant completion
-    assert!(
-        result.is_err(),
-        "Expected empty canary steps to be rejected"
-    );
-    let error = result.unwrap_err();
-    assert!(
-        error.contains("at least one step"),
-        "Error should mention empty steps, got: {}",
-        error
-    );
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("httpRoutes"), "got: {}", error);
 }
 
-#[tokio::test]
-async fn test_validate_rollout_requires_set_weight_on_steps() {
-    // ARRANGE: Create rollout with step missing setWeight
+fn replica_weighted_canary(replicas: i32) -> Rollout {
     let mut rollout = create_test_rollout_with_canary();
-    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
-        set_weight: None, // Missing setWeight
-        pause: Some(PauseDuration {
-            duration: Some("30s".to_string()),
-        }),
+    rollout.spec.replicas = replicas;
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(25),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
     }];
+    canary.traffic_routing = Some(TrafficRouting {
+        gateway_api: None,
+        none: Some(NoTrafficRouting {
+            replica_weighting: Some(true),
+        }),
+        nginx: None,
+    });
+    rollout
+}
 
-    // ACT: Validate rollout
-    let result = validate_rollout(&rollout);
-
-    // ASSERT: Should fail validation - setWeight is required
-    assert!(result.is_err(), "Expected missing setWeight to be rejected");
-    let error = result.unwrap_err();
-    assert!(
-        error.contains("setWeight is required"),
-        "Error should mention required setWeight, got: {}",
-        error
-    );
+#[test]
+fn test_validate_rollout_replica_weighting() {
+    assert!(validate_rollout(&replica_weighted_canary(4)).is_ok());
+
+    let error = validate_rollout(&replica_weighted_canary(1)).unwrap_err();
+    assert!(error.contains("at least 2 replicas"), "got: {}", error);
+
+    let mut with_gateway = replica_weighted_canary(4);
+    let routing = with_gateway.spec.strategy.canary.as_mut().unwrap();
+    routing.traffic_routing.as_mut().unwrap().gateway_api = Some(GatewayAPIRouting {
+        http_route: "route".to_string(),
+        http_routes: vec![],
+        weight_constraints: None,
+        sections: Vec::new(),
+        kind: Default::default(),
+    });
+    let error = validate_rollout(&with_gateway).unwrap_err();
+    assert!(error.contains("mutually exclusive"), "got: {}", error);
 }
 
-// ============================================================================
+#[test]
+fn test_validate_rollout_nginx_routing() {
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.traffic_routing = Some(TrafficRouting {
+        gateway_api: None,
+        none: None,
+        nginx: Some(NginxTrafficRouting {
+            stable_ingress: "shop".to_string(),
+            annotation_prefix: None,
+        }),
+    });
+    assert!(validate_rollout(&rollout).is_ok());
+
+    let mut with_gateway = rollout.clone();
+    let routing = with_gateway.spec.strategy.canary.as_mut().unwrap();
+    routing.traffic_routing.as_mut().unwrap().gateway_api = Some(GatewayAPIRouting {
+        http_route: "route".to_string(),
+        http_routes: vec![],
+        weight_constraints: None,
+        sections: Vec::new(),
+        kind: Default::default(),
+    });
+    let error = validate_rollout(&with_gateway).unwrap_err();
+    assert!(error.contains("mutually exclusive"), "got: {}", error);
+
+    let mut unnamed = rollout;
+    let routing = unnamed.spec.strategy.canary.as_mut().unwrap();
+    routing.traffic_routing.as_mut().unwrap().nginx = Some(NginxTrafficRouting {
+        stable_ingress: String::new(),
+        annotation_prefix: None,
+    });
+    let error = validate_rollout(&unnamed).unwrap_err();
+    assert!(error.contains("stableIngress"), "got: {}", error);
+}
+
+fn weight_constrained_canary(weights: &[i32], constraints: WeightConstraints) -> Rollout {
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = weights
+        .iter()
+        .map(|&weight| CanaryStep {
+            set_weight: Some(weight),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        })
+        .collect();
+    canary.traffic_routing = Some(TrafficRouting {
+        gateway_api: Some(GatewayAPIRouting {
+            http_route: "route".to_string(),
+            http_routes: vec![],
+            weight_constraints: Some(constraints),
+            sections: Vec::new(),
+            kind: Default::default(),
+        }),
+        none: None,
+        nginx: None,
+    });
+    rollout
+}
+
+#[test]
+fn test_validate_rollout_weight_constraints() {
+    let constraints = WeightConstraints {
+        granularity: Some(5),
+        min_weight: Some(10),
+        normalize: None,
+    };
+    assert!(validate_rollout(&weight_constrained_canary(
+        &[10, 50, 100],
+        constraints.clone()
+    ))
+    .is_ok());
+
+    let error =
+        validate_rollout(&weight_constrained_canary(&[7, 100], constraints.clone())).unwrap_err();
+    assert!(error.contains("steps[0].setWeight 7"), "got: {}", error);
+    assert!(error.contains("use 10"), "got: {}", error);
+
+    // Stable side at 3% is below minWeight too
+    let error =
+        validate_rollout(&weight_constrained_canary(&[97], constraints.clone())).unwrap_err();
+    assert!(error.contains("setWeight 97"), "got: {}", error);
+
+    let normalized = WeightConstraints {
+        normalize: Some(true),
+        ..constraints
+    };
+    assert!(validate_rollout(&weight_constrained_canary(&[7, 100], normalized)).is_ok());
+}
+
+#[test]
+fn test_preview_backend_refs() {
+    let rollout = weight_constrained_canary(
+        &[7, 50],
+        WeightConstraints {
+            granularity: Some(5),
+            min_weight: Some(10),
+            normalize: Some(true),
+        },
+    );
+    let weights = |step: i32| -> Vec<(String, Option<i32>)> {
+        preview_backend_refs(&rollout, step)
+            .unwrap()
+            .into_iter()
+            .map(|backend| (backend.name, backend.weight))
+            .collect()
+    };
+
+    // setWeight 7 is normalized to the gateway's minimum, as during reconcile
+    assert_eq!(
+        weights(0),
+        vec![
+            ("test-app-stable".to_string(), Some(90)),
+            ("test-app-canary".to_string(), Some(10)),
+        ]
+    );
+    assert_eq!(weights(2)[1].1, Some(100));
+    // The rollout itself is not modified
+    assert!(rollout.status.is_none());
+
+    let error = preview_backend_refs(&rollout, 3).unwrap_err();
+    assert!(error.contains("out of range"), "got: {}", error);
+    assert!(preview_backend_refs(&rollout, -1).is_err());
+
+    let error = preview_backend_refs(&replica_weighted_canary(4), 0).unwrap_err();
+    assert!(error.contains("gatewayAPI"), "got: {}", error);
+}
+
+#[test]
+fn test_mirror_step_filter_and_validation() {
+    let mut rollout = weight_constrained_canary(&[0, 50, 100], WeightConstraints::default());
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].set_mirror = Some(25);
+    assert!(validate_rollout(&rollout).is_ok());
+
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        ..Default::default()
+    });
+    let filter = build_gateway_api_mirror_filter(&rollout).unwrap();
+    assert_eq!(filter["type"], "RequestMirror");
+    assert_eq!(
+        filter["requestMirror"]["backendRef"]["name"],
+        "test-app-canary"
+    );
+    assert_eq!(filter["requestMirror"]["backendRef"]["port"], 80);
+    assert_eq!(filter["requestMirror"]["percent"], 25);
+    // Users still only reach stable
+    let backend_refs = build_gateway_api_backend_refs(&rollout);
+    assert_eq!(backend_refs[1].weight, Some(0));
+
+    // The next step drops the filter
+    rollout.status.as_mut().unwrap().current_step_index = Some(1);
+    assert!(build_gateway_api_mirror_filter(&rollout).is_none());
+
+    let mut out_of_range = rollout.clone();
+    out_of_range.spec.strategy.canary.as_mut().unwrap().steps[0].set_mirror = Some(0);
+    let error = validate_rollout(&out_of_range).unwrap_err();
+    assert!(error.contains("setMirror must be 1-100"), "got: {}", error);
+
+    let mut replica_weighted = replica_weighted_canary(4);
+    replica_weighted
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .unwrap()
+        .steps[0]
+        .set_mirror = Some(25);
+    let error = validate_rollout(&replica_weighted).unwrap_err();
+    assert!(error.contains("setMirror requires"), "got: {}", error);
+}
+
+#[test]
+fn test_header_route_step_rules_and_validation() {
+    let header = |name: &str, value: &str| ABHeaderMatch {
+        name: name.to_string(),
+        value: value.to_string(),
+        match_type: None,
+    };
+    let mut rollout = weight_constrained_canary(&[0, 50, 100], WeightConstraints::default());
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].set_header_route =
+        Some(SetHeaderRoute {
+            matches: vec![header("X-Canary", "internal")],
+        });
+    assert!(validate_rollout(&rollout).is_ok());
+
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        ..Default::default()
+    });
+    let rule = build_gateway_api_header_route_rule(&rollout).unwrap();
+    assert_eq!(rule["matches"][0]["headers"][0]["name"], "X-Canary");
+    assert_eq!(rule["matches"][0]["headers"][0]["type"], "Exact");
+    assert_eq!(rule["backendRefs"][0]["name"], "test-app-canary");
+    assert_eq!(rule["backendRefs"][0]["weight"], 100);
+
+    // The header rule goes ahead of the weighted rule, which still sends nothing to the canary
+    let backend_refs = build_gateway_api_backend_refs(&rollout);
+    let rules = CanaryStepRoutes::for_rollout(&rollout).rules(&backend_refs);
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0], rule);
+    assert_eq!(rules[1]["backendRefs"][1]["weight"], 0);
+
+    // Later steps keep the route
+    rollout.status.as_mut().unwrap().current_step_index = Some(1);
+    assert!(build_gateway_api_header_route_rule(&rollout).is_some());
+
+    let mut unnamed = rollout.clone();
+    unnamed.spec.strategy.canary.as_mut().unwrap().steps[0].set_header_route =
+        Some(SetHeaderRoute {
+            matches: vec![header("", "internal")],
+        });
+    let error = validate_rollout(&unnamed).unwrap_err();
+    assert!(
+        error.contains("need a header name and value"),
+        "got: {}",
+        error
+    );
+
+    let mut replica_weighted = replica_weighted_canary(4);
+    replica_weighted
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .unwrap()
+        .steps[0]
+        .set_header_route = Some(SetHeaderRoute {
+        matches: vec![header("X-Canary", "internal")],
+    });
+    let error = validate_rollout(&replica_weighted).unwrap_err();
+    assert!(error.contains("setHeaderRoute requires"), "got: {}", error);
+}
+
+#[test]
+fn test_section_rules_only_weight_the_named_rule() {
+    let mut rollout = weight_constrained_canary(&[20, 100], WeightConstraints::default());
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].set_header_route =
+        Some(SetHeaderRoute {
+            matches: vec![ABHeaderMatch {
+                name: "X-Canary".to_string(),
+                value: "internal".to_string(),
+                match_type: None,
+            }],
+        });
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        ..Default::default()
+    });
+    let current = vec![
+        serde_json::json!({
+            "name": "internal",
+            "backendRefs": [{ "name": "test-app-stable", "port": 80, "weight": 100 }],
+        }),
+        serde_json::json!({
+            "name": "production",
+            "matches": [{ "path": { "type": "PathPrefix", "value": "/shop" } }],
+            "backendRefs": [{ "name": "test-app-stable", "port": 80, "weight": 100 }],
+        }),
+    ];
+    let backend_refs = build_gateway_api_backend_refs(&rollout);
+
+    let rules = CanaryStepRoutes::for_rollout(&rollout)
+        .section_rules(&current, "production", &backend_refs)
+        .unwrap();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0], current[0]);
+    // The header route is narrowed to the section's matches
+    assert_eq!(rules[1]["name"], "production-header");
+    assert_eq!(rules[1]["matches"][0]["path"]["value"], "/shop");
+    assert_eq!(rules[1]["matches"][0]["headers"][0]["name"], "X-Canary");
+    assert_eq!(rules[2]["name"], "production");
+    assert_eq!(rules[2]["matches"], current[1]["matches"]);
+    assert_eq!(rules[2]["backendRefs"][1]["weight"], 20);
+
+    // Next step without a header route: the header rule is removed again
+    rollout.status.as_mut().unwrap().current_step_index = Some(1);
+    let rules = CanaryStepRoutes::for_rollout(&rollout)
+        .section_rules(&rules, "production", &backend_refs)
+        .unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[1]["name"], "production");
+
+    assert!(CanaryStepRoutes::default()
+        .section_rules(&current, "public", &backend_refs)
+        .is_err());
+}
+
+#[test]
+fn test_validate_rollout_route_sections() {
+    let with_sections = |sections: Vec<RouteSection>| {
+        let mut rollout = weight_constrained_canary(&[20, 100], WeightConstraints::default());
+        let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+        let gateway = canary
+            .traffic_routing
+            .as_mut()
+            .unwrap()
+            .gateway_api
+            .as_mut()
+            .unwrap();
+        gateway.weight_constraints = None;
+        gateway.sections = sections;
+        validate_rollout(&rollout)
+    };
+    let section = |route: &str, name: &str| RouteSection {
+        http_route: route.to_string(),
+        section_name: name.to_string(),
+    };
+
+    assert!(with_sections(vec![section("route", "production")]).is_ok());
+    let error = with_sections(vec![section("other", "production")]).unwrap_err();
+    assert!(error.contains("sections[0].httpRoute"), "got: {}", error);
+    let error = with_sections(vec![section("route", "Production")]).unwrap_err();
+    assert!(error.contains("sections[0].sectionName"), "got: {}", error);
+    let error = with_sections(vec![
+        section("route", "production"),
+        section("route", "internal"),
+    ])
+    .unwrap_err();
+    assert!(error.contains("more than once"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_prometheus_credentials() {
+    use crate::crd::rollout::{
+        AnalysisConfig, PrometheusBasicAuth, PrometheusConfig, PrometheusTlsConfig, SecretKeyRef,
+    };
+
+    let with_prometheus = |prometheus: PrometheusConfig| {
+        let mut rollout = weight_constrained_canary(&[20, 100], WeightConstraints::default());
+        let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+        canary.analysis = Some(AnalysisConfig {
+            prometheus: Some(prometheus),
+            ..AnalysisConfig::default()
+        });
+        validate_rollout(&rollout)
+    };
+    let key = |name: &str| SecretKeyRef {
+        name: name.to_string(),
+        key: "token".to_string(),
+    };
+    let config = || PrometheusConfig {
+        address: Some("https://thanos:9090".to_string()),
+        bearer_token_secret_ref: Some(key("thanos")),
+        basic_auth: None,
+        tls: Some(PrometheusTlsConfig {
+            ca_secret_ref: Some(key("thanos-ca")),
+            ..PrometheusTlsConfig::default()
+        }),
+    };
+
+    assert!(with_prometheus(config()).is_ok());
+    let error = with_prometheus(PrometheusConfig {
+        basic_auth: Some(PrometheusBasicAuth {
+            username: "kulta".to_string(),
+            password_secret_ref: key("thanos"),
+        }),
+        ..config()
+    })
+    .unwrap_err();
+    assert!(error.contains("mutually exclusive"), "got: {}", error);
+    let error = with_prometheus(PrometheusConfig {
+        tls: Some(PrometheusTlsConfig {
+            cert_secret_ref: Some(key("client")),
+            ..PrometheusTlsConfig::default()
+        }),
+        ..config()
+    })
+    .unwrap_err();
+    assert!(error.contains("set together"), "got: {}", error);
+    let error = with_prometheus(PrometheusConfig {
+        bearer_token_secret_ref: Some(key("")),
+        ..config()
+    })
+    .unwrap_err();
+    assert!(error.contains("bearerTokenSecretRef"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_set_canary_scale() {
+    let with_scale = |mut rollout: Rollout, scale: SetCanaryScale| {
+        rollout.spec.strategy.canary.as_mut().unwrap().steps[0].set_canary_scale = Some(scale);
+        validate_rollout(&rollout)
+    };
+    let scale = |replicas, weight, match_traffic_weight| SetCanaryScale {
+        replicas,
+        weight,
+        match_traffic_weight,
+    };
+    let routed = || weight_constrained_canary(&[5, 100], WeightConstraints::default());
+
+    assert!(with_scale(routed(), scale(Some(2), None, None)).is_ok());
+    assert!(with_scale(routed(), scale(None, Some(50), None)).is_ok());
+    assert!(with_scale(routed(), scale(None, None, Some(true))).is_ok());
+
+    for (invalid, expected) in [
+        (scale(Some(2), Some(50), None), "exactly one"),
+        (scale(None, None, None), "exactly one"),
+        (scale(Some(-1), None, None), "replicas must be >= 0"),
+        (scale(None, Some(150), None), "weight must be 0-100"),
+        (scale(None, None, Some(false)), "must be true"),
+    ] {
+        let error = with_scale(routed(), invalid).unwrap_err();
+        assert!(error.contains(expected), "got: {}", error);
+    }
+
+    let error = with_scale(replica_weighted_canary(4), scale(Some(2), None, None)).unwrap_err();
+    assert!(error.contains("replicaWeighting"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rejects_impossible_weight_constraints() {
+    let error = validate_rollout(&weight_constrained_canary(
+        &[100],
+        WeightConstraints {
+            granularity: Some(30),
+            ..Default::default()
+        },
+    ))
+    .unwrap_err();
+    assert!(
+        error.contains("granularity must divide 100"),
+        "got: {}",
+        error
+    );
+
+    let error = validate_rollout(&weight_constrained_canary(
+        &[100],
+        WeightConstraints {
+            granularity: Some(20),
+            min_weight: Some(45),
+            normalize: None,
+        },
+    ))
+    .unwrap_err();
+    assert!(error.contains("no canary weight"), "got: {}", error);
+}
+
+#[test]
+fn test_normalized_weights_drive_status() {
+    let rollout = weight_constrained_canary(
+        &[7, 100],
+        WeightConstraints {
+            granularity: Some(5),
+            min_weight: None,
+            normalize: Some(true),
+        },
+    );
+
+    let status = initialize_rollout_status(&rollout, Utc::now());
+
+    assert_eq!(status.current_weight, Some(5));
+    assert_eq!(
+        calculate_traffic_weights(&Rollout {
+            status: Some(status),
+            ..rollout
+        }),
+        (95, 5)
+    );
+}
+
+#[tokio::test]
+async fn test_validate_rollout_valid_rollout() {
+    // ARRANGE: Create valid rollout
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.spec.replicas = 5;
+    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![
+        CanaryStep {
+            set_weight: Some(20),
+            pause: Some(Pause::Duration("30s".to_string())),
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+        CanaryStep {
+            set_weight: Some(100),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+    ];
+    rollout
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .unwrap()
+        .traffic_routing = Some(TrafficRouting {
+        gateway_api: Some(GatewayAPIRouting {
+            http_route: "my-httproute".to_string(),
+            http_routes: vec![],
+            weight_constraints: None,
+            sections: Vec::new(),
+            kind: Default::default(),
+        }),
+        none: None,
+        nginx: None,
+    });
+
+    // ACT: Validate rollout
+    let result = validate_rollout(&rollout);
+
+    // ASSERT: Should pass validation
+    assert!(
+        result.is_ok(),
+        "Expected valid rollout to pass, got error: {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn test_validate_rollout_rejects_empty_canary_steps() {
+    // ARRANGE: Create rollout with empty steps
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![];
+
+    // ACT: Validate rollout
+    let result = validate_rollout(&rollout);
+
+    // ASSERT: Should fail validation - empty steps causes instant completion
+    assert!(
+        result.is_err(),
+        "Expected empty canary steps to be rejected"
+    );
+    let error = result.unwrap_err();
+    assert!(
+        error.contains("at least one step"),
+        "Error should mention empty steps, got: {}",
+        error
+    );
+}
+
+#[test]
+fn test_validate_rollout_steps_from() {
+    use crate::crd::rollout::{StepsConfigMapRef, StepsFrom};
+
+    let steps_from = |name: &str| StepsFrom {
+        config_map_ref: StepsConfigMapRef {
+            name: name.to_string(),
+            namespace: None,
+            key: Some("prod-default".to_string()),
+        },
+    };
+
+    // Imported steps replace inline ones
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![];
+    canary.steps_from = Some(steps_from("step-ladders"));
+    assert!(validate_rollout(&rollout).is_ok());
+
+    let mut both = create_test_rollout_with_canary();
+    both.spec.strategy.canary.as_mut().unwrap().steps_from = Some(steps_from("step-ladders"));
+    let error = validate_rollout(&both).unwrap_err();
+    assert!(error.contains("mutually exclusive"), "got: {}", error);
+
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps_from = Some(steps_from(""));
+    assert!(validate_rollout(&rollout).is_err());
+}
+
+#[tokio::test]
+async fn test_validate_rollout_requires_set_weight_on_steps() {
+    // ARRANGE: Create rollout with step missing setWeight
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
+        set_weight: None, // Missing setWeight
+        pause: Some(Pause::Duration("30s".to_string())),
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+
+    // ACT: Validate rollout
+    let result = validate_rollout(&rollout);
+
+    // ASSERT: Should fail validation - setWeight is required
+    assert!(result.is_err(), "Expected missing setWeight to be rejected");
+    let error = result.unwrap_err();
+    assert!(
+        error.contains("setWeight is required"),
+        "Error should mention required setWeight, got: {}",
+        error
+    );
+}
+
+// ============================================================================
 // Dynamic Requeue Interval Tests (TDD - RED Phase)
 // ============================================================================
 
@@ -3155,10 +4759,19 @@ async fn test_evaluate_rollout_metrics_healthy() {
                     steps: vec![CanaryStep {
                         set_weight: Some(10),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: Some(AnalysisConfig {
                         prometheus: Some(PrometheusConfig {
                             address: Some("http://prometheus:9090".to_string()),
+                            bearer_token_secret_ref: None,
+                            basic_auth: None,
+                            tls: None,
                         }),
                         failure_policy: None,
                         warmup_duration: None,
@@ -3167,10 +4780,28 @@ async fn test_evaluate_rollout_metrics_healthy() {
                             threshold: 5.0,
                             interval: None,
                             failure_threshold: None,
+                            query: None,
                             min_sample_size: None,
+                            baseline_offset: None,
+                            compare_to_stable: None,
+                            compare_to_baseline: None,
+                            volume_weighting: None,
+                            comparison: None,
+                            unit: None,
+                            weight: None,
+                            web: None,
                         }],
+                        per_zone: None,
+                        revision_label_key: None,
+                        aggregation: None,
+                        pass_score: None,
+                        on_resume: None,
                     }),
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -3178,6 +4809,11 @@ async fn test_evaluate_rollout_metrics_healthy() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -3244,10 +4880,19 @@ async fn test_evaluate_rollout_metrics_unhealthy() {
                     steps: vec![CanaryStep {
                         set_weight: Some(10),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: Some(AnalysisConfig {
                         prometheus: Some(PrometheusConfig {
                             address: Some("http://prometheus:9090".to_string()),
+                            bearer_token_secret_ref: None,
+                            basic_auth: None,
+                            tls: None,
                         }),
                         failure_policy: None,
                         warmup_duration: None,
@@ -3256,10 +4901,28 @@ async fn test_evaluate_rollout_metrics_unhealthy() {
                             threshold: 5.0,
                             interval: None,
                             failure_threshold: None,
+                            query: None,
                             min_sample_size: None,
+                            baseline_offset: None,
+                            compare_to_stable: None,
+                            compare_to_baseline: None,
+                            volume_weighting: None,
+                            comparison: None,
+                            unit: None,
+                            weight: None,
+                            web: None,
                         }],
+                        per_zone: None,
+                        revision_label_key: None,
+                        aggregation: None,
+                        pass_score: None,
+                        on_resume: None,
                     }),
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -3267,6 +4930,11 @@ async fn test_evaluate_rollout_metrics_unhealthy() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -3331,9 +4999,19 @@ async fn test_evaluate_rollout_metrics_no_analysis_config() {
                     steps: vec![CanaryStep {
                         set_weight: Some(10),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
                     }],
                     analysis: None, // No analysis config
                     traffic_routing: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -3341,6 +5019,11 @@ async fn test_evaluate_rollout_metrics_no_analysis_config() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             current_step_index: Some(0),
@@ -3401,7 +5084,13 @@ async fn test_evaluate_rollout_metrics_skips_during_warmup() {
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "test-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: Some(AnalysisConfig {
                         prometheus: None,
@@ -3410,11 +5099,29 @@ async fn test_evaluate_rollout_metrics_skips_during_warmup() {
                             threshold: 0.05,
                             interval: None,
                             failure_threshold: None,
+                            query: None,
                             min_sample_size: None,
+                            baseline_offset: None,
+                            compare_to_stable: None,
+                            compare_to_baseline: None,
+                            volume_weighting: None,
+                            comparison: None,
+                            unit: None,
+                            weight: None,
+                            web: None,
                         }],
                         failure_policy: None,
                         warmup_duration: Some("60s".to_string()), // 60 second warmup
+                        per_zone: None,
+                        revision_label_key: None,
+                        aggregation: None,
+                        pass_score: None,
+                        on_resume: None,
                     }),
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
                 blue_green: None,
                 ab_testing: None,
@@ -3424,6 +5131,11 @@ async fn test_evaluate_rollout_metrics_skips_during_warmup() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             replicas: 3,
@@ -3483,7 +5195,13 @@ async fn test_evaluate_rollout_metrics_runs_after_warmup() {
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "test-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: Some(AnalysisConfig {
                         prometheus: None,
@@ -3492,11 +5210,29 @@ async fn test_evaluate_rollout_metrics_runs_after_warmup() {
                             threshold: 0.05,
                             interval: None,
                             failure_threshold: None,
+                            query: None,
                             min_sample_size: None,
+                            baseline_offset: None,
+                            compare_to_stable: None,
+                            compare_to_baseline: None,
+                            volume_weighting: None,
+                            comparison: None,
+                            unit: None,
+                            weight: None,
+                            web: None,
                         }],
                         failure_policy: None,
                         warmup_duration: Some("60s".to_string()), // 60 second warmup
+                        per_zone: None,
+                        revision_label_key: None,
+                        aggregation: None,
+                        pass_score: None,
+                        on_resume: None,
                     }),
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
                 blue_green: None,
                 ab_testing: None,
@@ -3506,6 +5242,11 @@ async fn test_evaluate_rollout_metrics_runs_after_warmup() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             replicas: 3,
@@ -3564,7 +5305,13 @@ async fn test_evaluate_rollout_metrics_no_warmup_configured() {
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "test-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: Some(AnalysisConfig {
                         prometheus: None,
@@ -3573,11 +5320,29 @@ async fn test_evaluate_rollout_metrics_no_warmup_configured() {
                             threshold: 0.05,
                             interval: None,
                             failure_threshold: None,
+                            query: None,
                             min_sample_size: None,
+                            baseline_offset: None,
+                            compare_to_stable: None,
+                            compare_to_baseline: None,
+                            volume_weighting: None,
+                            comparison: None,
+                            unit: None,
+                            weight: None,
+                            web: None,
                         }],
                         failure_policy: None,
                         warmup_duration: None, // No warmup
+                        per_zone: None,
+                        revision_label_key: None,
+                        aggregation: None,
+                        pass_score: None,
+                        on_resume: None,
                     }),
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
                 blue_green: None,
                 ab_testing: None,
@@ -3587,6 +5352,11 @@ async fn test_evaluate_rollout_metrics_no_warmup_configured() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             replicas: 3,
@@ -3616,6 +5386,78 @@ async fn test_evaluate_rollout_metrics_no_warmup_configured() {
     );
 }
 
+/// Scripted metrics across reconciles: warmup expiry, then a flapping canary
+#[tokio::test]
+async fn test_evaluate_rollout_metrics_scripted_across_reconciles() {
+    use crate::crd::rollout::{AnalysisConfig, MetricConfig};
+
+    let now = Utc::now();
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(20),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    canary.analysis = Some(AnalysisConfig {
+        prometheus: None,
+        metrics: vec![MetricConfig {
+            name: "error-rate".to_string(),
+            threshold: 5.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }],
+        failure_policy: None,
+        warmup_duration: Some("60s".to_string()),
+        per_zone: None,
+        revision_label_key: None,
+        aggregation: None,
+        pass_score: None,
+        on_resume: None,
+    });
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        step_start_time: Some(now.to_rfc3339()),
+        ..Default::default()
+    });
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.script_values("http_requests_total", &[1.0, 9.0, 1.0, 9.0]);
+    let clock = Arc::new(MockClock::new(now));
+    let mut ctx = create_test_context_with_prometheus(prometheus.clone(), now);
+    ctx.clock = clock.clone();
+
+    // Warmup: healthy without asking Prometheus
+    assert!(evaluate_rollout_metrics(&rollout, &ctx).await.unwrap());
+    assert!(prometheus.queries().is_empty());
+
+    // Warmup over: each reconcile consumes the next scripted answer
+    clock.advance(chrono::Duration::seconds(61));
+    let mut verdicts = Vec::new();
+    for _ in 0..5 {
+        verdicts.push(evaluate_rollout_metrics(&rollout, &ctx).await.unwrap());
+    }
+    assert_eq!(verdicts, vec![true, false, true, false, false]);
+    assert_eq!(prometheus.queries().len(), 5);
+}
+
 // =============================================================================
 // HTTPRoute Traffic Splitting Tests
 // =============================================================================
@@ -3648,9 +5490,16 @@ async fn test_blue_green_builds_httproute_backend_refs() {
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "bg-app-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -3659,6 +5508,11 @@ async fn test_blue_green_builds_httproute_backend_refs() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             phase: Some(Phase::Preview),
@@ -3725,9 +5579,16 @@ async fn test_blue_green_httproute_after_promotion() {
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(GatewayAPIRouting {
                             http_route: "bg-app-route".to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -3736,6 +5597,11 @@ async fn test_blue_green_httproute_after_promotion() {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: Some(RolloutStatus {
             phase: Some(Phase::Completed),
@@ -4026,14 +5892,173 @@ async fn test_evaluate_ab_manual_conclude_annotation() {
     assert_eq!(result.reason, Some(ABConclusionReason::ManualConclusion));
 }
 
-/// Max duration exceeded → conclude with MaxDurationExceeded
+fn with_guardrail(mut rollout: Rollout, name: &str, threshold: f64) -> Rollout {
+    let ab = rollout.spec.strategy.ab_testing.as_mut().unwrap();
+    ab.analysis.as_mut().unwrap().guardrails = vec![crate::crd::rollout::ABGuardrail {
+        name: name.to_string(),
+        threshold,
+        comparison: None,
+    }];
+    rollout
+}
+
+/// Guardrail breach concludes immediately with variant A, before minDuration
 #[tokio::test]
-async fn test_evaluate_ab_max_duration_exceeded() {
+async fn test_evaluate_ab_guardrail_breach_concludes_with_a() {
     let now = Utc::now();
-    let started_2h_ago = (now - chrono::Duration::hours(2)).to_rfc3339();
-    let rollout = create_ab_rollout_with_analysis(
-        &started_2h_ago,
-        Phase::Experimenting,
+    let rollout = with_guardrail(
+        create_ab_rollout_with_analysis(
+            &(now - chrono::Duration::minutes(5)).to_rfc3339(),
+            Phase::Experimenting,
+            Some("1h"),
+            None,
+            None,
+            None,
+        ),
+        "error-rate",
+        0.05,
+    );
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_response(0.2);
+    let ctx = create_test_context_with_prometheus(prometheus, now);
+
+    let result = evaluate_ab_experiment(&rollout, &ctx).await.unwrap();
+
+    assert!(result.should_conclude);
+    assert_eq!(result.winner, Some(ABVariant::A));
+    assert_eq!(result.reason, Some(ABConclusionReason::GuardrailBreached));
+}
+
+/// Guardrail within limits (or unqueryable) leaves the experiment running
+#[tokio::test]
+async fn test_evaluate_ab_guardrail_holding_continues() {
+    let now = Utc::now();
+    let rollout = with_guardrail(
+        create_ab_rollout_with_analysis(
+            &(now - chrono::Duration::minutes(5)).to_rfc3339(),
+            Phase::Experimenting,
+            Some("1h"),
+            None,
+            None,
+            None,
+        ),
+        "latency-p95",
+        0.5,
+    );
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_response(0.2);
+    let ctx = create_test_context_with_prometheus(prometheus, now);
+    let result = evaluate_ab_experiment(&rollout, &ctx).await.unwrap();
+    assert!(!result.should_conclude);
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_error(crate::controller::prometheus::PrometheusError::HttpError(
+        "down".to_string(),
+    ));
+    let ctx = create_test_context_with_prometheus(prometheus, now);
+    let result = evaluate_ab_experiment(&rollout, &ctx).await.unwrap();
+    assert!(!result.should_conclude);
+}
+
+#[test]
+fn test_validate_rollout_tls_route_kind_not_for_ab_testing() {
+    let tls_routing = TrafficRouting {
+        gateway_api: Some(GatewayAPIRouting {
+            kind: crate::crd::rollout::GatewayRouteKind::TLSRoute,
+            http_route: "checkout-tls".to_string(),
+            http_routes: vec![],
+            weight_constraints: None,
+            sections: Vec::new(),
+        }),
+        none: None,
+        nginx: None,
+    };
+    let mut rollout = create_ab_rollout_with_analysis(
+        &Utc::now().to_rfc3339(),
+        Phase::Experimenting,
+        None,
+        None,
+        None,
+        None,
+    );
+    rollout
+        .spec
+        .strategy
+        .ab_testing
+        .as_mut()
+        .unwrap()
+        .traffic_routing = Some(tls_routing.clone());
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("gatewayAPI.kind"), "got: {}", error);
+
+    // Canary may route through a TLSRoute
+    let mut canary = create_test_rollout_with_canary();
+    let strategy = canary.spec.strategy.canary.as_mut().unwrap();
+    strategy.steps = vec![CanaryStep {
+        set_weight: Some(20),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    strategy.traffic_routing = Some(tls_routing);
+    assert!(validate_rollout(&canary).is_ok());
+}
+
+#[test]
+fn test_validate_rollout_rejects_unknown_guardrail_metric() {
+    let rollout = with_guardrail(
+        create_ab_rollout_with_analysis(
+            &Utc::now().to_rfc3339(),
+            Phase::Experimenting,
+            None,
+            None,
+            None,
+            None,
+        ),
+        "conversion-rate",
+        0.05,
+    );
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("guardrails[0]"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rejects_inverted_sample_window() {
+    let mut rollout = create_ab_rollout_with_analysis(
+        &Utc::now().to_rfc3339(),
+        Phase::Experimenting,
+        None,
+        None,
+        None,
+        None,
+    );
+    let ab = rollout.spec.strategy.ab_testing.as_mut().unwrap();
+    ab.analysis.as_mut().unwrap().sample_window = Some(crate::crd::rollout::SampleWindow {
+        days: vec![],
+        start_hour: Some(17),
+        end_hour: Some(9),
+        utc_offset_hours: None,
+        lookback: None,
+    });
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("sampleWindow"), "got: {}", error);
+}
+
+/// Max duration exceeded → conclude with MaxDurationExceeded
+#[tokio::test]
+async fn test_evaluate_ab_max_duration_exceeded() {
+    let now = Utc::now();
+    let started_2h_ago = (now - chrono::Duration::hours(2)).to_rfc3339();
+    let rollout = create_ab_rollout_with_analysis(
+        &started_2h_ago,
+        Phase::Experimenting,
         None,
         Some("1h"), // max 1 hour, but 2 hours have passed
         None,
@@ -4287,6 +6312,99 @@ fn test_validate_rollout_negative_deadline_rejected() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_validate_rollout_negative_revision_history_limit_rejected() {
+    let mut rollout = create_test_rollout_with_simple();
+    rollout.spec.revision_history_limit = Some(-1);
+    assert!(validate_rollout(&rollout).is_err());
+
+    rollout.spec.revision_history_limit = Some(0);
+    assert!(validate_rollout(&rollout).is_ok());
+}
+
+#[test]
+fn test_validate_rollout_advisor_min_confidence_range() {
+    let mut rollout = create_test_rollout_with_simple();
+    rollout.spec.advisor.min_confidence = Some(1.5);
+    assert!(validate_rollout(&rollout).is_err());
+
+    rollout.spec.advisor.min_confidence = Some(0.95);
+    assert!(validate_rollout(&rollout).is_ok());
+}
+
+// =============================================
+// Revision history tests
+// =============================================
+
+#[test]
+fn test_build_history_replicaset() {
+    let rollout = create_test_rollout_with_canary();
+    let hash = compute_pod_template_hash(&rollout.spec.template).unwrap();
+
+    let rs = build_history_replicaset(&rollout, 3).unwrap();
+
+    assert_eq!(rs.metadata.name, Some(format!("test-rollout-{}", hash)));
+    assert_eq!(replicaset_revision(&rs), Some(3));
+    assert_eq!(rs.spec.as_ref().unwrap().replicas, Some(0));
+    let labels = rs.metadata.labels.as_ref().unwrap();
+    assert_eq!(labels.get("rollouts.kulta.io/type").unwrap(), "history");
+    assert_eq!(labels.get("pod-template-hash"), Some(&hash));
+
+    // The stored template is the Rollout template once KULTA's labels are stripped
+    assert_eq!(rollback_template(&rs), Some(rollout.spec.template.clone()));
+}
+
+#[test]
+fn test_build_replicaset_is_owned_by_rollout() {
+    let mut rollout = create_test_rollout_with_canary();
+    let rs = build_replicaset(&rollout, "stable", 1).unwrap();
+    assert_eq!(rs.metadata.owner_references, None);
+
+    rollout.metadata.uid = Some("3f8a".to_string());
+    let rs = build_replicaset(&rollout, "stable", 1).unwrap();
+    let owners = rs.metadata.owner_references.unwrap();
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].kind, "Rollout");
+    assert_eq!(owners[0].name, "test-rollout");
+    assert_eq!(owners[0].uid, "3f8a");
+    assert_eq!(owners[0].controller, Some(true));
+}
+
+#[test]
+fn test_requested_rollback() {
+    use std::collections::BTreeMap;
+
+    let mut rollout = create_test_rollout_with_canary();
+    assert_eq!(requested_rollback(&rollout), None);
+
+    let mut request = |value: &str| {
+        rollout.metadata.annotations = Some(BTreeMap::from([(
+            ROLLBACK_TO_REVISION_ANNOTATION.to_string(),
+            value.to_string(),
+        )]));
+        requested_rollback(&rollout)
+    };
+    assert_eq!(request("3"), Some(Ok(3)));
+    assert_eq!(request("0"), Some(Err("0".to_string())));
+    assert_eq!(request("latest"), Some(Err("latest".to_string())));
+}
+
+#[test]
+fn test_record_revision_numbers_new_templates() {
+    let current = RolloutStatus {
+        pod_template_hash: Some("aaa".to_string()),
+        revision: Some(2),
+        ..Default::default()
+    };
+
+    let mut desired = current.clone();
+    record_revision(&mut desired, Some(&current), "aaa".to_string(), None);
+    assert_eq!(desired.revision, Some(2));
+
+    record_revision(&mut desired, Some(&current), "bbb".to_string(), None);
+    assert_eq!(desired.revision, Some(3));
+}
+
 // =============================================
 // Status: A/B initialization test
 // =============================================
@@ -4322,3 +6440,1677 @@ fn test_progress_deadline_with_invalid_timestamp() {
     let is_stuck = is_progress_deadline_exceeded(&status, 600, Utc::now());
     assert!(!is_stuck);
 }
+
+// =============================================
+// Per-zone analysis tests
+// =============================================
+
+fn create_canary_rollout_with_zone_analysis(zones: Vec<&str>) -> Rollout {
+    use crate::crd::rollout::{AnalysisConfig, MetricConfig, ZoneAnalysisConfig};
+
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(20),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    canary.analysis = Some(AnalysisConfig {
+        prometheus: None,
+        failure_policy: None,
+        warmup_duration: None,
+        metrics: vec![MetricConfig {
+            name: "error-rate".to_string(),
+            threshold: 5.0,
+            interval: None,
+            failure_threshold: None,
+            query: None,
+            min_sample_size: None,
+            baseline_offset: None,
+            compare_to_stable: None,
+            compare_to_baseline: None,
+            volume_weighting: None,
+            comparison: None,
+            unit: None,
+            weight: None,
+            web: None,
+        }],
+        per_zone: Some(ZoneAnalysisConfig {
+            label: Some("topology_zone".to_string()),
+            zones: zones.into_iter().map(String::from).collect(),
+        }),
+        revision_label_key: None,
+        aggregation: None,
+        pass_score: None,
+        on_resume: None,
+    });
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+    rollout
+}
+
+#[tokio::test]
+async fn test_evaluate_rollout_metrics_fails_on_single_zone_breach() {
+    let rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a", "zone-b"]);
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_response(2.0); // aggregate: healthy
+    prometheus.enqueue_response(0.5); // zone-a: healthy
+    prometheus.enqueue_response(12.0); // zone-b: breach
+    let ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+
+    let is_healthy = evaluate_rollout_metrics(&rollout, &ctx).await.unwrap();
+
+    assert!(!is_healthy, "A breach in one zone should fail the analysis");
+}
+
+#[tokio::test]
+async fn test_evaluate_rollout_metrics_healthy_when_all_zones_pass() {
+    let rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a", "zone-b"]);
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_response(2.0);
+    prometheus.enqueue_response(1.5);
+    prometheus.enqueue_response(2.5);
+    let ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+
+    let is_healthy = evaluate_rollout_metrics(&rollout, &ctx).await.unwrap();
+
+    assert!(is_healthy);
+}
+
+#[tokio::test]
+async fn test_evaluate_rollout_metrics_skips_zones_when_aggregate_fails() {
+    let rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.enqueue_response(9.0); // aggregate already unhealthy
+                                      // No zone responses queued: a zone query would fail with "No mock response set"
+    let ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+
+    let is_healthy = evaluate_rollout_metrics(&rollout, &ctx).await.unwrap();
+
+    assert!(!is_healthy);
+}
+
+#[test]
+fn test_validate_rollout_rejects_empty_zone_list() {
+    let rollout = create_canary_rollout_with_zone_analysis(vec![]);
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("perZone.zones"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rejects_invalid_zone_label() {
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    if let Some(analysis) = rollout
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .and_then(|c| c.analysis.as_mut())
+    {
+        analysis.per_zone.as_mut().unwrap().label = Some("topology.kubernetes.io/zone".to_string());
+    }
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("perZone.label"), "got: {}", error);
+}
+
+#[test]
+fn test_revision_label_key_selects_by_pod_template_hash() {
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    let mut analysis = rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()
+        .unwrap()
+        .analysis
+        .clone()
+        .unwrap();
+    assert_eq!(
+        analysis_revision_selector(&rollout, &analysis).unwrap(),
+        RevisionSelector::canary()
+    );
+
+    analysis.revision_label_key = Some("kulta_io_revision".to_string());
+    rollout.status = Some(RolloutStatus {
+        resources: Some(ManagedResources {
+            replica_sets: vec![ManagedReplicaSet {
+                name: "my-app-stable".to_string(),
+                hash: "1a2b3c4d5e".to_string(),
+                role: ReplicaSetRole::Stable,
+                replicas: 3,
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let revision = analysis_revision_selector(&rollout, &analysis).unwrap();
+    assert_eq!(revision.label, "kulta_io_revision");
+    assert_eq!(
+        revision.value,
+        compute_pod_template_hash(&rollout.spec.template).unwrap()
+    );
+    assert_eq!(revision.baseline, "1a2b3c4d5e");
+
+    if let Some(analysis) = rollout
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .and_then(|c| c.analysis.as_mut())
+    {
+        analysis.revision_label_key = Some("kulta.io/revision".to_string());
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("revisionLabelKey"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rejects_invalid_baseline_offset() {
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    if let Some(analysis) = rollout
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .and_then(|c| c.analysis.as_mut())
+    {
+        analysis.metrics[0].baseline_offset = Some("7 days".to_string());
+    }
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("baselineOffset"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_volume_weighting_needs_stable_comparison() {
+    use crate::crd::rollout::{MetricConfig, VolumeWeighting};
+
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    fn metric(rollout: &mut Rollout) -> Option<&mut MetricConfig> {
+        rollout
+            .spec
+            .strategy
+            .canary
+            .as_mut()
+            .and_then(|c| c.analysis.as_mut())
+            .map(|analysis| &mut analysis.metrics[0])
+    }
+    if let Some(metric) = metric(&mut rollout) {
+        metric.volume_weighting = Some(VolumeWeighting::default());
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("volumeWeighting requires compareToStable"),
+        "got: {}",
+        error
+    );
+
+    if let Some(metric) = metric(&mut rollout) {
+        metric.compare_to_stable = Some(true);
+    }
+    assert!(validate_rollout(&rollout).is_ok());
+
+    if let Some(metric) = metric(&mut rollout) {
+        metric.volume_weighting = Some(VolumeWeighting {
+            full_weight_requests: Some(0),
+        });
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("fullWeightRequests"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_compare_to_baseline_needs_baseline_replicaset() {
+    use crate::crd::rollout::CanaryBaseline;
+
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    fn canary(rollout: &mut Rollout) -> Option<&mut CanaryStrategy> {
+        rollout.spec.strategy.canary.as_mut()
+    }
+    if let Some(analysis) = canary(&mut rollout).and_then(|c| c.analysis.as_mut()) {
+        analysis.metrics[0].compare_to_baseline = Some(true);
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("compareToBaseline requires spec.strategy.canary.baseline"),
+        "got: {}",
+        error
+    );
+
+    if let Some(canary) = canary(&mut rollout) {
+        canary.baseline = Some(CanaryBaseline::default());
+    }
+    assert!(validate_rollout(&rollout).is_ok());
+
+    if let Some(analysis) = canary(&mut rollout).and_then(|c| c.analysis.as_mut()) {
+        analysis.metrics[0].compare_to_stable = Some(true);
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("compareToBaseline can't be combined with compareToStable"),
+        "got: {}",
+        error
+    );
+
+    if let Some(canary) = canary(&mut rollout) {
+        if let Some(analysis) = canary.analysis.as_mut() {
+            analysis.metrics[0].compare_to_stable = None;
+        }
+        canary.baseline = Some(CanaryBaseline { replicas: Some(-1) });
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("baseline.replicas"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rollback_verification() {
+    use crate::crd::rollout::RollbackVerification;
+
+    let mut rollout = create_canary_rollout_with_zone_analysis(vec!["zone-a"]);
+    fn canary(rollout: &mut Rollout) -> Option<&mut CanaryStrategy> {
+        rollout.spec.strategy.canary.as_mut()
+    }
+    if let Some(canary) = canary(&mut rollout) {
+        canary.rollback_verification = Some(RollbackVerification {
+            delay: Some("90s".to_string()),
+        });
+    }
+    assert!(validate_rollout(&rollout).is_ok());
+
+    if let Some(canary) = canary(&mut rollout) {
+        canary.rollback_verification = Some(RollbackVerification {
+            delay: Some("soon".to_string()),
+        });
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("rollbackVerification.delay invalid"),
+        "got: {}",
+        error
+    );
+
+    if let Some(canary) = canary(&mut rollout) {
+        canary.rollback_verification = Some(RollbackVerification::default());
+        canary.analysis = None;
+    }
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(
+        error.contains("rollbackVerification requires spec.strategy.canary.analysis"),
+        "got: {}",
+        error
+    );
+}
+
+// =============================================
+// Template diff tests
+// =============================================
+
+fn create_template(
+    containers: Vec<k8s_openapi::api::core::v1::Container>,
+) -> k8s_openapi::api::core::v1::PodTemplateSpec {
+    k8s_openapi::api::core::v1::PodTemplateSpec {
+        metadata: Some(ObjectMeta {
+            labels: Some([("app".to_string(), "test-app".to_string())].into()),
+            ..Default::default()
+        }),
+        spec: Some(k8s_openapi::api::core::v1::PodSpec {
+            containers,
+            ..Default::default()
+        }),
+    }
+}
+
+fn create_container(name: &str, image: &str) -> k8s_openapi::api::core::v1::Container {
+    k8s_openapi::api::core::v1::Container {
+        name: name.to_string(),
+        image: Some(image.to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_template_diff_reports_image_change() {
+    let old = create_template(vec![create_container("app", "my-app:1.0")]);
+    let new = create_template(vec![create_container("app", "my-app:2.0")]);
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert_eq!(diff.from_hash, "old");
+    assert_eq!(diff.to_hash, "new");
+    assert_eq!(diff.images.len(), 1);
+    assert_eq!(diff.images[0].container, "app");
+    assert_eq!(diff.images[0].from, "my-app:1.0");
+    assert_eq!(diff.images[0].to, "my-app:2.0");
+    assert!(diff.env_changed.is_empty());
+    assert!(diff.resources_changed.is_empty());
+    assert!(!diff.other_changes);
+}
+
+#[test]
+fn test_template_diff_reports_env_and_resources() {
+    use k8s_openapi::api::core::v1::{EnvVar, ResourceRequirements};
+    use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+    let old = create_template(vec![create_container("app", "my-app:1.0")]);
+    let mut changed = create_container("app", "my-app:1.0");
+    changed.env = Some(vec![EnvVar {
+        name: "LOG_LEVEL".to_string(),
+        value: Some("debug".to_string()),
+        ..Default::default()
+    }]);
+    changed.resources = Some(ResourceRequirements {
+        limits: Some([("cpu".to_string(), Quantity("500m".to_string()))].into()),
+        ..Default::default()
+    });
+    let new = create_template(vec![changed]);
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert!(diff.images.is_empty());
+    assert_eq!(diff.env_changed, vec!["app".to_string()]);
+    assert_eq!(diff.resources_changed, vec!["app".to_string()]);
+    assert!(!diff.other_changes);
+}
+
+#[test]
+fn test_template_diff_reports_added_and_removed_containers() {
+    let old = create_template(vec![
+        create_container("app", "my-app:1.0"),
+        create_container("proxy", "envoy:1.0"),
+    ]);
+    let new = create_template(vec![
+        create_container("app", "my-app:1.0"),
+        create_container("sidecar", "log-agent:1.0"),
+    ]);
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert_eq!(diff.containers_added, vec!["sidecar".to_string()]);
+    assert_eq!(diff.containers_removed, vec!["proxy".to_string()]);
+    assert!(diff.images.is_empty());
+    assert!(!diff.other_changes);
+}
+
+// The baseline template comes from a ReplicaSet, which carries KULTA's own labels
+#[test]
+fn test_template_diff_ignores_injected_replicaset_labels() {
+    let mut old = create_template(vec![create_container("app", "my-app:1.0")]);
+    if let Some(labels) = old.metadata.as_mut().and_then(|m| m.labels.as_mut()) {
+        labels.insert("pod-template-hash".to_string(), "abc".to_string());
+        labels.insert("rollouts.kulta.io/type".to_string(), "stable".to_string());
+        labels.insert("rollouts.kulta.io/managed".to_string(), "true".to_string());
+    }
+    let new = create_template(vec![create_container("app", "my-app:1.0")]);
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert!(!diff.other_changes);
+}
+
+#[test]
+fn test_template_diff_flags_other_changes() {
+    let old = create_template(vec![create_container("app", "my-app:1.0")]);
+    let mut new = create_template(vec![create_container("app", "my-app:1.0")]);
+    if let Some(spec) = new.spec.as_mut() {
+        spec.service_account_name = Some("my-app".to_string());
+    }
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert!(diff.other_changes);
+    assert!(diff.images.is_empty());
+}
+
+#[test]
+fn test_template_diff_truncates_long_lists() {
+    let containers = |image: &str| {
+        (0..MAX_TEMPLATE_DIFF_ENTRIES + 5)
+            .map(|i| create_container(&format!("sidecar-{:02}", i), image))
+            .collect::<Vec<_>>()
+    };
+    let old = create_template(containers("proxy:1.0"));
+    let new = create_template(containers("proxy:2.0"));
+
+    let diff = summarize_template_diff(&old, &new, "old", "new");
+
+    assert_eq!(diff.images.len(), MAX_TEMPLATE_DIFF_ENTRIES);
+    assert_eq!(diff.images[0].container, "sidecar-00");
+    assert!(diff.truncated);
+
+    let small = summarize_template_diff(
+        &create_template(vec![create_container("app", "my-app:1.0")]),
+        &create_template(vec![create_container("app", "my-app:2.0")]),
+        "old",
+        "new",
+    );
+    assert!(!small.truncated);
+}
+
+#[test]
+fn test_baseline_replicaset_name_per_strategy() {
+    assert_eq!(
+        baseline_replicaset_name(&create_test_rollout_with_canary()),
+        "test-rollout-stable"
+    );
+    assert_eq!(
+        baseline_replicaset_name(&create_test_rollout_with_blue_green()),
+        "blue-green-rollout-active"
+    );
+    assert_eq!(
+        baseline_replicaset_name(&create_test_rollout_with_simple()),
+        "simple-rollout"
+    );
+}
+
+#[test]
+fn test_record_revision_sets_hash_and_new_diff() {
+    use crate::crd::rollout::TemplateDiff;
+
+    let mut desired = RolloutStatus::default();
+    let diff = TemplateDiff {
+        from_hash: "a".to_string(),
+        to_hash: "b".to_string(),
+        ..Default::default()
+    };
+
+    record_revision(&mut desired, None, "b".to_string(), Some(diff.clone()));
+
+    assert_eq!(desired.pod_template_hash.as_deref(), Some("b"));
+    assert_eq!(desired.template_diff, Some(diff));
+}
+
+// Strategies that rebuild status from scratch must not drop the last diff
+#[test]
+fn test_record_revision_keeps_previous_diff() {
+    use crate::crd::rollout::TemplateDiff;
+
+    let previous = TemplateDiff {
+        from_hash: "a".to_string(),
+        to_hash: "b".to_string(),
+        ..Default::default()
+    };
+    let current = RolloutStatus {
+        pod_template_hash: Some("b".to_string()),
+        template_diff: Some(previous.clone()),
+        ..Default::default()
+    };
+    let mut desired = RolloutStatus::default();
+
+    record_revision(&mut desired, Some(&current), "b".to_string(), None);
+
+    assert_eq!(desired.template_diff, Some(previous));
+}
+
+// =============================================
+// Alert gate tests
+// =============================================
+
+fn create_alert_gated_rollout() -> Rollout {
+    use crate::crd::rollout::{AlertGate, AlertGateErrorPolicy};
+
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![
+        CanaryStep {
+            set_weight: Some(20),
+            pause: None,
+            alert_gate: Some(AlertGate {
+                matchers: [("service".to_string(), "checkout".to_string())].into(),
+                address: None,
+                on_error: AlertGateErrorPolicy::Block,
+            }),
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+        CanaryStep {
+            set_weight: Some(100),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+    ];
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        ..Default::default()
+    });
+    rollout
+}
+
+fn create_context_with_alerts(
+    alerts: crate::controller::alertmanager::MockAlertmanagerClient,
+) -> Context {
+    let mut ctx = Context::new_mock();
+    ctx.alertmanager = Arc::new(alerts);
+    ctx
+}
+
+#[tokio::test]
+async fn test_alert_gate_holds_step_while_alerts_firing() {
+    let alerts = crate::controller::alertmanager::MockAlertmanagerClient::new();
+    alerts.fire("HighErrorRate", &[("service", "checkout")]);
+    let ctx = create_context_with_alerts(alerts);
+    let rollout = create_alert_gated_rollout();
+    let desired = advance_to_next_step(&rollout, Utc::now());
+
+    let held = hold_for_alert_gate(&rollout, &desired, &ctx).await.unwrap();
+
+    assert_eq!(held.current_step_index, Some(0));
+    assert_eq!(held.current_weight, Some(20));
+    assert!(held.message.unwrap().contains("HighErrorRate"));
+}
+
+#[tokio::test]
+async fn test_alert_gate_ignores_unrelated_alerts() {
+    let alerts = crate::controller::alertmanager::MockAlertmanagerClient::new();
+    alerts.fire("HighErrorRate", &[("service", "payments")]);
+    let ctx = create_context_with_alerts(alerts);
+    let rollout = create_alert_gated_rollout();
+    let desired = advance_to_next_step(&rollout, Utc::now());
+
+    assert!(hold_for_alert_gate(&rollout, &desired, &ctx)
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_alert_gate_not_checked_when_not_advancing() {
+    let alerts = crate::controller::alertmanager::MockAlertmanagerClient::new();
+    alerts.fire("HighErrorRate", &[("service", "checkout")]);
+    let ctx = create_context_with_alerts(alerts);
+    let rollout = create_alert_gated_rollout();
+    let desired = rollout.status.clone().unwrap();
+
+    assert!(hold_for_alert_gate(&rollout, &desired, &ctx)
+        .await
+        .is_none());
+}
+
+#[test]
+fn test_validate_rollout_rejects_alert_gate_without_matchers() {
+    let mut rollout = create_alert_gated_rollout();
+    if let Some(gate) = rollout.spec.strategy.canary.as_mut().unwrap().steps[0]
+        .alert_gate
+        .as_mut()
+    {
+        gate.matchers.clear();
+    }
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("alertGate.matchers"), "got: {}", error);
+}
+
+#[test]
+fn test_validate_rollout_rejects_alert_gate_bad_address() {
+    let mut rollout = create_alert_gated_rollout();
+    if let Some(gate) = rollout.spec.strategy.canary.as_mut().unwrap().steps[0]
+        .alert_gate
+        .as_mut()
+    {
+        gate.address = Some("alertmanager:9093".to_string());
+    }
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("alertGate.address"), "got: {}", error);
+}
+
+// =============================================
+// Dependency gate tests
+// =============================================
+
+fn create_dependency_gated_rollout() -> Rollout {
+    use crate::crd::rollout::{DependencyGate, DependencyKind};
+
+    let mut rollout = create_alert_gated_rollout();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps[0].alert_gate = None;
+    canary.gates = vec![DependencyGate {
+        service: "pgbouncer".to_string(),
+        namespace: Some("data".to_string()),
+        kind: DependencyKind::Deployment,
+        condition: "Available".to_string(),
+    }];
+    rollout
+}
+
+#[test]
+fn test_dependency_gates_checked_only_on_weight_increase() {
+    let rollout = create_dependency_gated_rollout();
+    let desired = advance_to_next_step(&rollout, Utc::now());
+    assert!(raises_gated_weight(&rollout, &desired));
+
+    let unchanged = rollout.status.clone().unwrap();
+    assert!(!raises_gated_weight(&rollout, &unchanged));
+
+    let mut ungated = rollout.clone();
+    ungated.spec.strategy.canary.as_mut().unwrap().gates.clear();
+    assert!(!raises_gated_weight(&ungated, &desired));
+}
+
+#[test]
+fn test_dependency_gates_hold_while_unhealthy() {
+    let rollout = create_dependency_gated_rollout();
+
+    let held = hold_for_dependency_gates(
+        &rollout,
+        &["Deployment data/pgbouncer is not Available".to_string()],
+    )
+    .unwrap();
+
+    assert_eq!(held.current_step_index, Some(0));
+    assert_eq!(held.current_weight, Some(20));
+    assert!(held.message.unwrap().contains("data/pgbouncer"));
+    assert!(hold_for_dependency_gates(&rollout, &[]).is_none());
+}
+
+#[test]
+fn test_validate_rollout_rejects_dependency_gate_without_condition() {
+    let mut rollout = create_dependency_gated_rollout();
+    rollout.spec.strategy.canary.as_mut().unwrap().gates[0]
+        .condition
+        .clear();
+
+    let error = validate_rollout(&rollout).unwrap_err();
+    assert!(error.contains("gates[0].condition"), "got: {}", error);
+}
+
+// =============================================
+// Promotion policy tests
+// =============================================
+
+fn create_rollout_with_elapsed_timed_pause() -> Rollout {
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![
+        CanaryStep {
+            set_weight: Some(20),
+            pause: Some(Pause::Duration("1m".to_string())),
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+        CanaryStep {
+            set_weight: Some(100),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        },
+    ];
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        pause_start_time: Some((Utc::now() - chrono::Duration::minutes(5)).to_rfc3339()),
+        ..Default::default()
+    });
+    rollout
+}
+
+#[test]
+fn test_manual_promotion_policy_holds_elapsed_timed_pause() {
+    use crate::controller::promotion_policy::PromotionMode;
+
+    let rollout = create_rollout_with_elapsed_timed_pause();
+    let desired = compute_desired_status(&rollout, Utc::now());
+    assert_eq!(
+        desired.current_step_index,
+        Some(1),
+        "spec alone would advance"
+    );
+
+    let held = hold_for_promotion_policy(&rollout, &desired, Some(PromotionMode::Manual)).unwrap();
+
+    assert_eq!(held.current_step_index, Some(0));
+    assert_eq!(held.current_weight, Some(20));
+    assert!(held.message.unwrap().contains("manual promotion"));
+}
+
+#[test]
+fn test_auto_or_missing_promotion_policy_follows_spec() {
+    use crate::controller::promotion_policy::PromotionMode;
+
+    let rollout = create_rollout_with_elapsed_timed_pause();
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    assert!(hold_for_promotion_policy(&rollout, &desired, Some(PromotionMode::Auto)).is_none());
+    assert!(hold_for_promotion_policy(&rollout, &desired, None).is_none());
+}
+
+#[test]
+fn test_manual_promotion_policy_ignores_steps_without_pause() {
+    use crate::controller::promotion_policy::PromotionMode;
+
+    let mut rollout = create_rollout_with_elapsed_timed_pause();
+    rollout.spec.strategy.canary.as_mut().unwrap().steps[0].pause = None;
+    let desired = advance_to_next_step(&rollout, Utc::now());
+
+    assert!(hold_for_promotion_policy(&rollout, &desired, Some(PromotionMode::Manual)).is_none());
+}
+
+// =============================================
+// onDegraded policy tests
+// =============================================
+
+fn degraded_replicaset(replicas: i32, ready: i32, failure: Option<&str>) -> ReplicaSet {
+    use k8s_openapi::api::apps::v1::{ReplicaSetCondition, ReplicaSetSpec, ReplicaSetStatus};
+
+    ReplicaSet {
+        spec: Some(ReplicaSetSpec {
+            replicas: Some(replicas),
+            ..Default::default()
+        }),
+        status: Some(ReplicaSetStatus {
+            replicas,
+            ready_replicas: Some(ready),
+            conditions: failure.map(|message| {
+                vec![ReplicaSetCondition {
+                    type_: "ReplicaFailure".to_string(),
+                    status: "True".to_string(),
+                    reason: Some("FailedCreate".to_string()),
+                    message: Some(message.to_string()),
+                    last_transition_time: None,
+                }]
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn create_degradable_rollout(action: DegradedAction, degraded_for_secs: Option<i64>) -> Rollout {
+    let mut rollout = create_rollout_with_elapsed_timed_pause();
+    rollout.spec.on_degraded = Some(DegradedPolicy {
+        action,
+        after_seconds: Some(120),
+    });
+    if let Some(status) = rollout.status.as_mut() {
+        status.degraded_since =
+            degraded_for_secs.map(|s| (Utc::now() - chrono::Duration::seconds(s)).to_rfc3339());
+    }
+    rollout
+}
+
+#[test]
+fn test_replicaset_degradation() {
+    assert_eq!(
+        replicaset_degradation(&degraded_replicaset(3, 3, None)),
+        None
+    );
+    assert_eq!(
+        replicaset_degradation(&degraded_replicaset(0, 0, None)),
+        None
+    );
+    assert_eq!(
+        replicaset_degradation(&degraded_replicaset(3, 1, None)).as_deref(),
+        Some("1/3 pods ready")
+    );
+
+    let quota = replicaset_degradation(&degraded_replicaset(3, 0, Some("exceeded quota")));
+    assert_eq!(quota.as_deref(), Some("ReplicaFailure: exceeded quota"));
+}
+
+#[test]
+fn test_new_revision_replicaset_name() {
+    let canary = create_test_rollout_with_canary();
+    assert_eq!(
+        new_revision_replicaset_name(&canary).as_deref(),
+        Some("test-rollout-canary")
+    );
+
+    let simple = create_test_rollout_with_simple();
+    assert_eq!(
+        new_revision_replicaset_name(&simple),
+        simple.metadata.name.clone()
+    );
+}
+
+#[test]
+fn test_degraded_policy_starts_grace_period() {
+    let rollout = create_degradable_rollout(DegradedAction::Rollback, None);
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    let status = apply_degraded_policy(
+        &rollout,
+        desired.clone(),
+        Some("0/3 pods ready"),
+        Utc::now(),
+    );
+
+    assert!(status.degraded_since.is_some());
+    assert_eq!(status.phase, desired.phase);
+    assert_eq!(status.current_step_index, desired.current_step_index);
+}
+
+#[test]
+fn test_degraded_policy_rollback_after_grace_period() {
+    let rollout = create_degradable_rollout(DegradedAction::Rollback, Some(300));
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    let status = apply_degraded_policy(&rollout, desired, Some("0/3 pods ready"), Utc::now());
+
+    assert_eq!(status.phase, Some(Phase::Failed));
+    let message = status.message.unwrap();
+    assert!(
+        message.starts_with("Rollback triggered"),
+        "got: {}",
+        message
+    );
+    assert!(message.contains("0/3 pods ready"), "got: {}", message);
+}
+
+#[test]
+fn test_degraded_rollback_sends_the_canary_back_to_stable() {
+    let rollout = create_degradable_rollout(DegradedAction::Rollback, Some(300));
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    let status = apply_degraded_policy(&rollout, desired, Some("0/3 pods ready"), Utc::now());
+
+    assert!(status.aborted_at.is_some());
+    assert_eq!(status.current_weight, Some(0));
+    assert_eq!(current_weight(&rollout.spec, Some(&status)), 0);
+    let decision = status.decisions.last().unwrap();
+    assert_eq!(decision.action, DecisionAction::Rollback);
+    assert_eq!(decision.reason, DecisionReason::Degraded);
+
+    // The canary ReplicaSet rebuilt from the rolled-back status has no pods
+    let (_, canary_replicas) = calculate_replica_split_with_surge(
+        rollout.spec.replicas,
+        status.current_weight.unwrap_or(0),
+        rollout.spec.max_surge.as_deref(),
+        rollout.spec.max_unavailable.as_deref(),
+    );
+    assert_eq!(canary_replicas, 0);
+    let mut rolled_back = rollout.clone();
+    rolled_back.status = Some(status);
+    let canary = build_replicaset(&rolled_back, "canary", canary_replicas).unwrap();
+    assert_eq!(canary.spec.unwrap().replicas, Some(0));
+}
+
+#[test]
+fn test_degraded_policy_pause_holds_current_step() {
+    let rollout = create_degradable_rollout(DegradedAction::Pause, Some(300));
+    let desired = compute_desired_status(&rollout, Utc::now());
+    assert_eq!(
+        desired.current_step_index,
+        Some(1),
+        "spec alone would advance"
+    );
+
+    let status = apply_degraded_policy(&rollout, desired, Some("0/3 pods ready"), Utc::now());
+
+    assert_eq!(status.phase, Some(Phase::Progressing));
+    assert_eq!(status.current_step_index, Some(0));
+    assert_eq!(status.current_weight, Some(20));
+    assert!(status.message.unwrap().contains("degraded"));
+}
+
+#[test]
+fn test_degraded_policy_none_only_reports() {
+    let rollout = create_degradable_rollout(DegradedAction::None, Some(300));
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    let status = apply_degraded_policy(
+        &rollout,
+        desired.clone(),
+        Some("0/3 pods ready"),
+        Utc::now(),
+    );
+
+    assert_eq!(status.phase, desired.phase);
+    assert_eq!(status.current_step_index, desired.current_step_index);
+    assert!(status.degraded_since.is_some());
+}
+
+#[test]
+fn test_degraded_policy_clears_on_recovery() {
+    let rollout = create_degradable_rollout(DegradedAction::Rollback, Some(300));
+    let desired = compute_desired_status(&rollout, Utc::now());
+
+    let status = apply_degraded_policy(&rollout, desired, None, Utc::now());
+
+    assert_eq!(status.degraded_since, None);
+    assert_ne!(status.phase, Some(Phase::Failed));
+}
+
+// =============================================
+// Audit summaries
+// =============================================
+
+#[test]
+fn test_status_change_summary_lists_changed_fields() {
+    let current = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        ..Default::default()
+    };
+    let desired = RolloutStatus {
+        phase: Some(Phase::Progressing),
+        current_step_index: Some(1),
+        current_weight: Some(50),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        status_change_summary(Some(&current), &desired),
+        "step 0 -> 1, weight 20 -> 50"
+    );
+    assert_eq!(
+        status_change_summary(Some(&current), &current),
+        "status unchanged"
+    );
+    assert_eq!(
+        status_change_summary(None, &current),
+        "phase - -> Progressing, step - -> 0, weight - -> 20"
+    );
+}
+
+// =============================================
+// Deployment freeze
+// =============================================
+
+#[test]
+fn test_compute_desired_status_initializes_rollout_held_by_freeze() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.spec.strategy.canary.as_mut().unwrap().steps = vec![CanaryStep {
+        set_weight: Some(20),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Initializing),
+        message: Some("Frozen".to_string()),
+        ..Default::default()
+    });
+
+    let status = compute_desired_status(&rollout, Utc::now());
+
+    assert_eq!(status.phase, Some(Phase::Progressing));
+    assert_eq!(status.current_step_index, Some(0));
+    assert_eq!(status.current_weight, Some(20));
+}
+
+// =============================================
+// Finalizer teardown
+// =============================================
+
+#[test]
+fn test_finalizers_with_and_without() {
+    let mut rollout = create_test_rollout_with_canary();
+    rollout.metadata.finalizers = Some(vec!["example.com/keep".to_string()]);
+    rollout.metadata.resource_version = Some("42".to_string());
+    assert!(!has_finalizer(&rollout));
+
+    let added = finalizers_with(&rollout);
+    assert_eq!(added, vec!["example.com/keep", FINALIZER]);
+
+    rollout.metadata.finalizers = Some(added);
+    assert!(has_finalizer(&rollout));
+    assert_eq!(finalizers_with(&rollout).len(), 2);
+    assert_eq!(finalizers_without(&rollout), vec!["example.com/keep"]);
+
+    let patch = finalizers_patch(&rollout, finalizers_without(&rollout));
+    assert_eq!(
+        patch,
+        serde_json::json!({
+            "metadata": { "finalizers": ["example.com/keep"], "resourceVersion": "42" }
+        })
+    );
+}
+
+#[test]
+fn test_teardown_routes_everything_to_stable() {
+    let canary = teardown_backend_refs(&create_test_rollout_with_canary());
+    assert_eq!(canary.len(), 1);
+    assert_eq!(canary[0].name, "test-app-stable");
+    assert_eq!(canary[0].weight, Some(100));
+
+    let blue_green = teardown_backend_refs(&create_test_rollout_with_blue_green());
+    assert_eq!(blue_green.len(), 1);
+    assert_eq!(blue_green[0].name, "my-app-active");
+
+    let ab = create_ab_rollout_with_analysis(
+        "2026-01-01T00:00:00Z",
+        Phase::Experimenting,
+        None,
+        None,
+        None,
+        None,
+    );
+    assert!(teardown_backend_refs(&ab).is_empty());
+    let rules = teardown_ab_rules(&ab);
+    assert_eq!(rules.len(), 1);
+    assert!(rules[0].matches.is_none());
+    assert_eq!(rules[0].backend_refs.as_ref().unwrap()[0].name, "svc-a");
+}
+
+// =============================================
+// Strategy migration
+// =============================================
+
+fn create_canary_rollout_for_migration(phase: Phase) -> Rollout {
+    let mut rollout = create_test_rollout_with_canary();
+    let canary = rollout.spec.strategy.canary.as_mut().unwrap();
+    canary.steps = vec![CanaryStep {
+        set_weight: Some(50),
+        pause: None,
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    }];
+    canary.traffic_routing = Some(TrafficRouting {
+        gateway_api: Some(GatewayAPIRouting {
+            http_route: "test-app-route".to_string(),
+            http_routes: vec![],
+            weight_constraints: None,
+            sections: Vec::new(),
+            kind: Default::default(),
+        }),
+        none: None,
+        nginx: None,
+    });
+    rollout.status = Some(RolloutStatus {
+        phase: Some(phase),
+        ..Default::default()
+    });
+    rollout
+}
+
+#[test]
+fn test_plan_migration_adopts_canary_services() {
+    let rollout = create_canary_rollout_for_migration(Phase::Completed);
+
+    let MigrationPlan::Migrate(blue_green) = plan_migration(&rollout, "blue-green") else {
+        panic!("expected migration");
+    };
+    assert_eq!(blue_green.active_service, "test-app-stable");
+    assert_eq!(blue_green.preview_service, "test-app-canary");
+    assert_eq!(blue_green.migrated_from, Some(MigratedFrom::Canary));
+    let routing = blue_green.traffic_routing.as_ref().unwrap();
+    assert_eq!(
+        routing.gateway_api.as_ref().unwrap().http_route,
+        "test-app-route"
+    );
+
+    let patch = migration_patch(&blue_green);
+    assert_eq!(patch["spec"]["strategy"]["canary"], serde_json::Value::Null);
+    assert_eq!(
+        patch["spec"]["strategy"]["blueGreen"]["migratedFrom"],
+        "canary"
+    );
+    assert_eq!(
+        patch["metadata"]["annotations"][MIGRATE_STRATEGY_ANNOTATION],
+        serde_json::Value::Null
+    );
+}
+
+#[test]
+fn test_plan_migration_waits_or_rejects() {
+    let progressing = create_canary_rollout_for_migration(Phase::Progressing);
+    assert!(matches!(
+        plan_migration(&progressing, "blue-green"),
+        MigrationPlan::Wait
+    ));
+
+    let completed = create_canary_rollout_for_migration(Phase::Completed);
+    assert!(matches!(
+        plan_migration(&completed, "ab-testing"),
+        MigrationPlan::Reject(_)
+    ));
+    assert!(matches!(
+        plan_migration(&create_test_rollout_with_blue_green(), "blue-green"),
+        MigrationPlan::Reject(_)
+    ));
+
+    let mut plain_service = completed.clone();
+    plain_service
+        .spec
+        .strategy
+        .canary
+        .as_mut()
+        .unwrap()
+        .traffic_routing
+        .as_mut()
+        .unwrap()
+        .none = Some(Default::default());
+    assert!(matches!(
+        plan_migration(&plain_service, "blue-green"),
+        MigrationPlan::Reject(_)
+    ));
+}
+
+#[test]
+fn test_migrated_blue_green_keeps_canary_replicasets() {
+    let mut rollout = create_canary_rollout_for_migration(Phase::Completed);
+    let MigrationPlan::Migrate(blue_green) = plan_migration(&rollout, "blue-green") else {
+        panic!("expected migration");
+    };
+    rollout.spec.strategy.canary = None;
+    rollout.spec.strategy.blue_green = Some(*blue_green);
+
+    let (active, preview) = build_replicasets_for_blue_green(&rollout, 3).unwrap();
+    assert_eq!(active.metadata.name.as_deref(), Some("test-rollout-stable"));
+    assert_eq!(
+        preview.metadata.name.as_deref(),
+        Some("test-rollout-canary")
+    );
+    assert_eq!(
+        active.metadata.labels.as_ref().unwrap()["rollouts.kulta.io/type"],
+        "stable"
+    );
+    assert_eq!(baseline_replicaset_name(&rollout), "test-rollout-stable");
+    assert_eq!(
+        new_revision_replicaset_name(&rollout).as_deref(),
+        Some("test-rollout-canary")
+    );
+}
+
+fn rollback_decision(timestamp: &str, from_step: i32) -> Decision {
+    Decision {
+        timestamp: timestamp.to_string(),
+        action: DecisionAction::Rollback,
+        from_step: Some(from_step),
+        to_step: None,
+        reason: DecisionReason::AnalysisFailed,
+        message: Some(format!("rollback at {}", timestamp)),
+        metrics: None,
+        count: None,
+        last_timestamp: None,
+        approved_by: None,
+    }
+}
+
+#[test]
+fn test_compact_decisions_leaves_short_history_alone() {
+    let decisions = vec![
+        rollback_decision("2026-01-01T12:00:00Z", 1),
+        rollback_decision("2026-01-01T12:01:00Z", 1),
+    ];
+    assert_eq!(compact_decisions(decisions.clone()), decisions);
+}
+
+#[test]
+fn test_compact_decisions_merges_similar_runs() {
+    let mut decisions = vec![rollback_decision("2026-01-01T11:00:00Z", 2)];
+    for minute in 0..MAX_DECISIONS {
+        decisions.push(rollback_decision(
+            &format!("2026-01-01T12:{:02}:00Z", minute),
+            1,
+        ));
+    }
+
+    let compacted = compact_decisions(decisions);
+
+    assert_eq!(compacted.len(), 2);
+    assert_eq!(compacted[0].from_step, Some(2));
+    assert_eq!(compacted[0].count, None);
+    let run = &compacted[1];
+    assert_eq!(run.count, Some(MAX_DECISIONS as u32));
+    assert_eq!(run.timestamp, "2026-01-01T12:00:00Z");
+    assert_eq!(run.last_timestamp.as_deref(), Some("2026-01-01T12:49:00Z"));
+    assert_eq!(
+        run.message.as_deref(),
+        Some("rollback at 2026-01-01T12:49:00Z")
+    );
+
+    // Already compact: nothing changes on the next pass
+    assert_eq!(compact_decisions(compacted.clone()), compacted);
+}
+
+#[test]
+fn test_compact_decisions_drops_oldest_when_still_too_long() {
+    let decisions: Vec<Decision> = (0..MAX_DECISIONS as i32 + 5)
+        .map(|step| rollback_decision("2026-01-01T12:00:00Z", step))
+        .collect();
+
+    let compacted = compact_decisions(decisions);
+
+    assert_eq!(compacted.len(), MAX_DECISIONS);
+    assert_eq!(compacted[0].from_step, Some(5));
+    assert!(compacted.iter().all(|d| d.count.is_none()));
+}
+
+#[test]
+fn test_selector_string() {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement};
+
+    let selector = LabelSelector {
+        match_labels: Some(
+            [("tier", "web"), ("app", "checkout")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ),
+        match_expressions: Some(vec![
+            LabelSelectorRequirement {
+                key: "track".to_string(),
+                operator: "In".to_string(),
+                values: Some(vec!["a".to_string(), "b".to_string()]),
+            },
+            LabelSelectorRequirement {
+                key: "legacy".to_string(),
+                operator: "DoesNotExist".to_string(),
+                values: None,
+            },
+        ]),
+    };
+    assert_eq!(
+        selector_string(&selector).as_deref(),
+        Some("app=checkout,tier=web,track in (a,b),!legacy")
+    );
+    assert_eq!(selector_string(&LabelSelector::default()), None);
+}
+
+// Helper: canary at a 5m timed pause at step 1, as a crashed controller left it
+fn create_interrupted_canary(now: chrono::DateTime<Utc>) -> Rollout {
+    let step = |weight: i32, pause: Option<&str>| CanaryStep {
+        set_weight: Some(weight),
+        pause: pause.map(|duration| Pause::Duration(duration.to_string())),
+        alert_gate: None,
+        set_mirror: None,
+        set_canary_scale: None,
+        set_header_route: None,
+        experiment: None,
+        analysis: None,
+    };
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.steps = vec![step(10, None), step(30, Some("5m")), step(100, None)];
+    }
+    rollout.status = Some(advance_to_next_step(
+        &Rollout {
+            status: Some(initialize_rollout_status(&rollout, now)),
+            ..rollout.clone()
+        },
+        now,
+    ));
+    rollout
+}
+
+#[test]
+fn test_repair_in_flight_status_restarts_lost_pause_timer() {
+    use chrono::Duration;
+
+    let now = Utc::now();
+    let mut rollout = create_interrupted_canary(now);
+    assert_eq!(repair_in_flight_status(&rollout, now), None);
+
+    // Status written without the pause start: the pause would never end
+    let status = rollout.status.as_mut().unwrap();
+    status.pause_start_time = None;
+    status.paused_until = None;
+    assert!(!should_progress_to_next_step(
+        &rollout,
+        now + Duration::hours(1)
+    ));
+
+    let restart = now + Duration::minutes(2);
+    let repaired = repair_in_flight_status(&rollout, restart).unwrap();
+    assert_eq!(repaired.current_step_index, Some(1));
+    assert_eq!(repaired.pause_start_time, Some(restart.to_rfc3339()));
+    assert_eq!(
+        repaired.paused_until,
+        Some((restart + Duration::minutes(5)).to_rfc3339())
+    );
+
+    rollout.status = Some(repaired);
+    assert_eq!(repair_in_flight_status(&rollout, restart), None);
+    assert!(!should_progress_to_next_step(
+        &rollout,
+        restart + Duration::minutes(4)
+    ));
+    assert!(should_progress_to_next_step(
+        &rollout,
+        restart + Duration::minutes(5)
+    ));
+
+    // With a step start recorded, the pause keeps its original end
+    let status = rollout.status.as_mut().unwrap();
+    status.pause_start_time = Some("not-a-timestamp".to_string());
+    status.step_start_time = Some(now.to_rfc3339());
+    let repaired = repair_in_flight_status(&rollout, restart).unwrap();
+    assert_eq!(repaired.pause_start_time, Some(now.to_rfc3339()));
+}
+
+#[test]
+fn test_repair_in_flight_status_rederives_weight() {
+    let now = Utc::now();
+    let mut rollout = create_interrupted_canary(now);
+    rollout.status.as_mut().unwrap().current_weight = Some(10);
+
+    let repaired = repair_in_flight_status(&rollout, now).unwrap();
+    assert_eq!(repaired.current_weight, Some(30));
+    assert_eq!(
+        repaired.pause_start_time,
+        rollout.status.as_ref().unwrap().pause_start_time
+    );
+
+    // Settled and aborted rollouts are left alone
+    for status in [
+        RolloutStatus {
+            phase: Some(Phase::Completed),
+            ..rollout.status.clone().unwrap()
+        },
+        RolloutStatus {
+            aborted_at: Some(now.to_rfc3339()),
+            ..rollout.status.clone().unwrap()
+        },
+    ] {
+        rollout.status = Some(status);
+        assert_eq!(repair_in_flight_status(&rollout, now), None);
+    }
+}
+
+#[test]
+fn test_applied_promotion_is_not_applied_twice() {
+    let now = Utc::now();
+    let mut rollout = create_interrupted_canary(now);
+    rollout.metadata.annotations = Some(
+        [("kulta.io/promote".to_string(), "true".to_string())]
+            .into_iter()
+            .collect(),
+    );
+    assert!(!is_stale_promotion(&rollout));
+
+    // Promotion to step 2 written, then the controller stopped before
+    // removing the annotation
+    let mut promoted = advance_to_next_step(&rollout, now);
+    promoted.promoted_at = Some(now.to_rfc3339());
+    rollout.status = Some(promoted);
+    assert!(is_stale_promotion(&rollout));
+
+    rollout.metadata.annotations = None;
+    assert!(!is_stale_promotion(&rollout));
+}
+
+#[test]
+fn test_promotion_status_patch_is_conditional_on_read_version() {
+    let now = Utc::now();
+    let rollout = create_interrupted_canary(now);
+    let mut promoted = advance_to_next_step(&rollout, now);
+    promoted.promoted_at = Some(now.to_rfc3339());
+    promoted.promoted_version = Some("4711".to_string());
+
+    // A reconcile that read the Rollout before the promotion landed
+    // presents the old version and conflicts instead of advancing again
+    let patch = promotion_status_patch(&promoted);
+    assert_eq!(patch["metadata"]["resourceVersion"], "4711");
+    assert_eq!(patch["status"]["promotedVersion"], "4711");
+    assert_eq!(patch["status"]["currentStepIndex"], 2);
+
+    promoted.promoted_version = None;
+    let patch = promotion_status_patch(&promoted);
+    assert!(patch.get("metadata").is_none());
+    assert!(patch["status"]["promotedVersion"].is_null());
+}
+
+#[test]
+fn test_step_analysis_gates_progression() {
+    let now = Utc::now();
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.steps = serde_json::from_value(serde_json::json!([
+            {
+                "setWeight": 20,
+                "analysis": {
+                    "count": 2,
+                    "metrics": [{ "name": "error-rate", "threshold": 1.0 }]
+                }
+            },
+            { "setWeight": 100 }
+        ]))
+        .unwrap();
+    }
+    assert!(validate_rollout(&rollout).is_ok());
+    rollout.status = Some(initialize_rollout_status(&rollout, now));
+
+    // Not measured yet, then one of two measurements passed
+    assert!(!should_progress_to_next_step(&rollout, now));
+    let status = rollout.status.clone().unwrap();
+    rollout.status.as_mut().unwrap().step_analysis = Some(record_passed_measurement(&status, now));
+    assert!(!should_progress_to_next_step(&rollout, now));
+
+    let status = rollout.status.clone().unwrap();
+    rollout.status.as_mut().unwrap().step_analysis = Some(record_passed_measurement(&status, now));
+    assert!(should_progress_to_next_step(&rollout, now));
+
+    // Advancing leaves the measurements behind
+    let advanced = advance_to_next_step(&rollout, now);
+    assert_eq!(advanced.current_step_index, Some(1));
+    assert_eq!(advanced.step_analysis, None);
+
+    // Step analysis needs metrics and a positive count
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        if let Some(analysis) = canary.steps[0].analysis.as_mut() {
+            analysis.count = Some(0);
+        }
+    }
+    assert!(validate_rollout(&rollout)
+        .unwrap_err()
+        .contains("steps[0].analysis.count"));
+}
+
+#[tokio::test]
+async fn test_evaluate_rollout_analysis_counts_consecutive_failures() {
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.analysis = serde_json::from_value(serde_json::json!({
+            "metrics": [{ "name": "error-rate", "threshold": 5.0, "failureThreshold": 2 }]
+        }))
+        .unwrap();
+    }
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.script_values("http_requests_total", &[9.0, 1.0, 9.0, 9.0]);
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let mut ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+    ctx.clock = clock.clone();
+
+    // Each verdict's counts are recorded in status before the next reconcile,
+    // one metric interval apart
+    let mut verdicts = Vec::new();
+    for _ in 0..4 {
+        clock.advance(chrono::Duration::seconds(30));
+        let verdict = evaluate_rollout_analysis(&rollout, &ctx).await.unwrap();
+        rollout.status.as_mut().unwrap().metric_failures = verdict.metric_failures.clone();
+        verdicts.push((
+            verdict.is_healthy,
+            verdict
+                .metric_failures
+                .unwrap()
+                .first()
+                .map_or(0, |count| count.consecutive_failures),
+        ));
+    }
+
+    // A pass resets the count; the second breach in a row fails the analysis
+    assert_eq!(verdicts, vec![(true, 1), (true, 0), (true, 1), (false, 2)]);
+}
+
+#[tokio::test]
+async fn test_back_to_back_reconciles_count_one_failure_per_interval() {
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.analysis = serde_json::from_value(serde_json::json!({
+            "metrics": [{
+                "name": "error-rate", "threshold": 5.0, "failureThreshold": 2, "interval": "1m"
+            }]
+        }))
+        .unwrap();
+    }
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.script_values("http_requests_total", &[9.0, 9.0, 9.0]);
+    let clock = Arc::new(MockClock::new(Utc::now()));
+    let mut ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+    ctx.clock = clock.clone();
+
+    // The status patch of each reconcile triggers the next one right away
+    let mut counts = Vec::new();
+    for _ in 0..3 {
+        let verdict = evaluate_rollout_analysis(&rollout, &ctx).await.unwrap();
+        assert!(verdict.is_healthy);
+        rollout.status.as_mut().unwrap().metric_failures = verdict.metric_failures.clone();
+        counts.push(verdict.metric_failures.unwrap()[0].consecutive_failures);
+        clock.advance(chrono::Duration::seconds(1));
+    }
+    assert_eq!(counts, vec![1, 1, 1]);
+}
+
+#[tokio::test]
+async fn test_custom_metric_query_is_rendered_and_validated() {
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.analysis = serde_json::from_value(serde_json::json!({
+            "metrics": [{
+                "name": "checkout-errors",
+                "threshold": 1.0,
+                "query": "sum(rate(checkout_failures_total{app=\"{{rollout}}\",namespace=\"{{namespace}}\"}[5m]))"
+            }]
+        }))
+        .unwrap();
+    }
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+    assert!(validate_rollout(&rollout).is_ok());
+
+    let prometheus = MockPrometheusClient::new();
+    prometheus.script_values("checkout_failures_total", &[0.5]);
+    let ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+    let verdict = evaluate_rollout_analysis(&rollout, &ctx).await.unwrap();
+    assert!(verdict.is_healthy);
+
+    // Unknown variables and unbalanced queries are rejected up front
+    let set_query = |rollout: &mut Rollout, query: &str| {
+        if let Some(ref mut canary) = rollout.spec.strategy.canary {
+            if let Some(analysis) = canary.analysis.as_mut() {
+                analysis.metrics[0].query = Some(query.to_string());
+            }
+        }
+    };
+    set_query(
+        &mut rollout,
+        "sum(rate(errors_total{app=\"{{service}}\"}[5m]))",
+    );
+    assert!(validate_rollout(&rollout)
+        .unwrap_err()
+        .contains("analysis.metrics[0].query"));
+    set_query(
+        &mut rollout,
+        "sum(rate(errors_total{app=\"{{rollout}}\"}[5m])",
+    );
+    assert!(validate_rollout(&rollout).is_err());
+}
+
+#[tokio::test]
+async fn test_web_metric_is_rendered_and_validated() {
+    let mut rollout = create_test_rollout_with_canary();
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        canary.analysis = serde_json::from_value(serde_json::json!({
+            "metrics": [{
+                "name": "slo-burn-rate",
+                "threshold": 1.0,
+                "web": {
+                    "url": "https://slo.example.com/api/{{namespace}}/{{rollout}}",
+                    "jsonPath": "$.burnRate"
+                }
+            }]
+        }))
+        .unwrap();
+    }
+    rollout.status = Some(RolloutStatus {
+        current_step_index: Some(0),
+        current_weight: Some(20),
+        phase: Some(Phase::Progressing),
+        ..Default::default()
+    });
+    assert!(validate_rollout(&rollout).is_ok());
+
+    // The rendered URL reaches the querier
+    let prometheus = MockPrometheusClient::new();
+    prometheus.script_values("slo.example.com/api/default/test-rollout", &[2.5]);
+    let ctx = create_test_context_with_prometheus(prometheus, Utc::now());
+    let verdict = evaluate_rollout_analysis(&rollout, &ctx).await.unwrap();
+    assert!(!verdict.is_healthy);
+
+    let set_web = |rollout: &mut Rollout, web: serde_json::Value| {
+        if let Some(ref mut canary) = rollout.spec.strategy.canary {
+            if let Some(analysis) = canary.analysis.as_mut() {
+                analysis.metrics[0].web = Some(serde_json::from_value(web).unwrap());
+            }
+        }
+    };
+    set_web(
+        &mut rollout,
+        serde_json::json!({ "url": "https://slo.example.com", "jsonPath": "$.items[*]" }),
+    );
+    assert!(validate_rollout(&rollout)
+        .unwrap_err()
+        .contains("analysis.metrics[0].web"));
+    set_web(
+        &mut rollout,
+        serde_json::json!({ "url": "https://slo.example.com", "jsonPath": "$.burnRate" }),
+    );
+    if let Some(ref mut canary) = rollout.spec.strategy.canary {
+        if let Some(analysis) = canary.analysis.as_mut() {
+            analysis.metrics[0].query = Some("up".to_string());
+        }
+    }
+    assert!(validate_rollout(&rollout)
+        .unwrap_err()
+        .contains("mutually exclusive"));
+}
@@ -0,0 +1,389 @@
+//! Canary steps imported from a ConfigMap
+//!
+//! Platform teams keep standard step ladders (e.g. a "prod-default"
+//! progression) in a ConfigMap and Rollouts reference them with
+//! `strategy.canary.stepsFrom.configMapRef` instead of listing `steps`.
+//!
+//! The referenced key holds a YAML (or JSON) list of canary steps, in the
+//! same format as `spec.strategy.canary.steps`. ConfigMaps are cached per
+//! `namespace/name`; the controller watches ConfigMaps and drops the cached
+//! copy when one changes, so every Rollout referencing it is re-reconciled
+//! with the new ladder. A Rollout already underway continues from its
+//! current step index on the updated ladder.
+
+use crate::crd::rollout::{CanaryStep, Rollout, StepsConfigMapRef};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::Api;
+use kube::runtime::reflector::ObjectRef;
+use kube::{Client, ResourceExt};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// Key read when `configMapRef.key` is not set
+pub const DEFAULT_STEPS_KEY: &str = "steps";
+
+/// How long a ConfigMap is reused without a change notification
+const STEPS_CACHE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error, PartialEq)]
+pub enum StepsFromError {
+    #[error("steps ConfigMap {namespace}/{name} not found")]
+    NotFound { namespace: String, name: String },
+
+    #[error("steps ConfigMap {namespace}/{name} has no key '{key}'")]
+    MissingKey {
+        namespace: String,
+        name: String,
+        key: String,
+    },
+
+    #[error("invalid steps in ConfigMap {namespace}/{name} key '{key}': {reason}")]
+    InvalidSteps {
+        namespace: String,
+        name: String,
+        key: String,
+        reason: String,
+    },
+
+    #[error("failed to read steps ConfigMap {namespace}/{name}: {reason}")]
+    Read {
+        namespace: String,
+        name: String,
+        reason: String,
+    },
+}
+
+/// Parse a step ladder (YAML or JSON list of canary steps)
+pub fn parse_steps(data: &str) -> Result<Vec<CanaryStep>, String> {
+    let steps: Vec<CanaryStep> = serde_yaml::from_str(data).map_err(|e| e.to_string())?;
+    if steps.is_empty() {
+        return Err("step list is empty".to_string());
+    }
+    Ok(steps)
+}
+
+/// The ConfigMap reference of a Rollout, if it imports its steps
+pub fn steps_configmap_ref(rollout: &Rollout) -> Option<&StepsConfigMapRef> {
+    rollout
+        .spec
+        .strategy
+        .canary
+        .as_ref()?
+        .steps_from
+        .as_ref()
+        .map(|steps_from| &steps_from.config_map_ref)
+}
+
+/// Namespace and name of the ConfigMap a Rollout imports its steps from
+///
+/// The ConfigMap defaults to the Rollout's own namespace.
+pub fn steps_configmap_key(rollout: &Rollout) -> Option<(String, String)> {
+    let reference = steps_configmap_ref(rollout)?;
+    let namespace = reference
+        .namespace
+        .clone()
+        .or_else(|| rollout.namespace())?;
+    Some((namespace, reference.name.clone()))
+}
+
+struct CachedConfigMap {
+    loaded_at: Instant,
+    data: BTreeMap<String, String>,
+}
+
+/// Resolves `stepsFrom` references, caching the ConfigMaps it reads
+#[derive(Default)]
+pub struct StepsResolver {
+    cache: Mutex<HashMap<(String, String), CachedConfigMap>>,
+}
+
+impl StepsResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolver preloaded with ConfigMap data (no API reads for these entries)
+    pub fn with_configmap(namespace: &str, name: &str, data: BTreeMap<String, String>) -> Self {
+        let resolver = Self::new();
+        resolver.store(namespace, name, data);
+        resolver
+    }
+
+    fn store(&self, namespace: &str, name: &str, data: BTreeMap<String, String>) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(
+                (namespace.to_string(), name.to_string()),
+                CachedConfigMap {
+                    loaded_at: Instant::now(),
+                    data,
+                },
+            );
+        }
+    }
+
+    fn cached(&self, namespace: &str, name: &str) -> Option<BTreeMap<String, String>> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .get(&(namespace.to_string(), name.to_string()))
+            .filter(|c| c.loaded_at.elapsed() < STEPS_CACHE_TTL)
+            .map(|c| c.data.clone())
+    }
+
+    /// Forget a cached ConfigMap (called when it changes)
+    ///
+    /// # Returns
+    /// Whether the ConfigMap was cached
+    pub fn invalidate(&self, namespace: &str, name: &str) -> bool {
+        match self.cache.lock() {
+            Ok(mut cache) => cache
+                .remove(&(namespace.to_string(), name.to_string()))
+                .is_some(),
+            Err(_) => false,
+        }
+    }
+
+    async fn configmap_data(
+        &self,
+        client: &Client,
+        namespace: &str,
+        name: &str,
+    ) -> Result<BTreeMap<String, String>, StepsFromError> {
+        if let Some(data) = self.cached(namespace, name) {
+            return Ok(data);
+        }
+
+        let api: Api<ConfigMap> = Api::namespaced(client.clone(), namespace);
+        match api.get_opt(name).await {
+            Ok(Some(cm)) => {
+                let data = cm.data.unwrap_or_default();
+                self.store(namespace, name, data.clone());
+                debug!(configmap = %name, namespace = %namespace, "Loaded steps ConfigMap");
+                Ok(data)
+            }
+            Ok(None) => Err(StepsFromError::NotFound {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+            }),
+            Err(e) => {
+                warn!(error = ?e, configmap = %name, namespace = %namespace, "Failed to read steps ConfigMap");
+                Err(StepsFromError::Read {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    reason: e.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Rollout with its imported steps filled in
+    ///
+    /// # Returns
+    /// * `Ok(Some(rollout))` - Copy with `steps` set from the ConfigMap and `stepsFrom` cleared
+    /// * `Ok(None)` - The Rollout doesn't import steps
+    /// * `Err(StepsFromError)` - ConfigMap or key missing, or the steps don't parse
+    pub async fn resolve(
+        &self,
+        rollout: &Rollout,
+        client: &Client,
+    ) -> Result<Option<Rollout>, StepsFromError> {
+        let (Some(reference), Some((namespace, name))) =
+            (steps_configmap_ref(rollout), steps_configmap_key(rollout))
+        else {
+            return Ok(None);
+        };
+        let key = reference.key.as_deref().unwrap_or(DEFAULT_STEPS_KEY);
+
+        let data = self.configmap_data(client, &namespace, &name).await?;
+        let Some(value) = data.get(key) else {
+            return Err(StepsFromError::MissingKey {
+                namespace,
+                name,
+                key: key.to_string(),
+            });
+        };
+        let steps = parse_steps(value).map_err(|reason| StepsFromError::InvalidSteps {
+            namespace: namespace.clone(),
+            name: name.clone(),
+            key: key.to_string(),
+            reason,
+        })?;
+
+        Ok(Some(with_steps(rollout, steps)))
+    }
+}
+
+/// Rollouts importing their steps from a ConfigMap
+///
+/// Used to re-reconcile them when the ConfigMap changes.
+pub fn rollouts_importing_steps(
+    rollouts: &[Arc<Rollout>],
+    namespace: &str,
+    name: &str,
+) -> Vec<ObjectRef<Rollout>> {
+    rollouts
+        .iter()
+        .filter(|rollout| {
+            steps_configmap_key(rollout).is_some_and(|(ns, n)| ns == namespace && n == name)
+        })
+        .map(|rollout| ObjectRef::from_obj(rollout.as_ref()))
+        .collect()
+}
+
+/// Copy of a canary Rollout with `steps` replaced and `stepsFrom` cleared
+pub fn with_steps(rollout: &Rollout, steps: Vec<CanaryStep>) -> Rollout {
+    let mut resolved = rollout.clone();
+    if let Some(canary) = resolved.spec.strategy.canary.as_mut() {
+        canary.steps = steps;
+        canary.steps_from = None;
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::{CanaryStrategy, Pause, StepsConfigMapRef, StepsFrom};
+
+    const PROD_DEFAULT: &str = r#"
+- setWeight: 10
+  pause:
+    duration: 5m
+- setWeight: 50
+  pause: {}
+- setWeight: 100
+"#;
+
+    fn rollout_with_steps_from(reference: StepsConfigMapRef) -> Rollout {
+        RolloutBuilder::new("app")
+            .namespace("payments")
+            .replicas(3)
+            .label("app", "app")
+            .image("app", "app:2")
+            .canary(CanaryStrategy {
+                canary_service: "app-canary".to_string(),
+                stable_service: "app-stable".to_string(),
+                port: None,
+                steps: vec![],
+                steps_from: Some(StepsFrom {
+                    config_map_ref: reference,
+                }),
+                traffic_routing: None,
+                analysis: None,
+                gates: vec![],
+                baseline: None,
+                rollback_verification: None,
+            })
+            .build()
+            .unwrap()
+    }
+
+    fn reference(namespace: Option<&str>, key: Option<&str>) -> StepsConfigMapRef {
+        StepsConfigMapRef {
+            name: "ladders".to_string(),
+            namespace: namespace.map(str::to_string),
+            key: key.map(str::to_string),
+        }
+    }
+
+    fn mock_client() -> Client {
+        crate::controller::rollout::Context::new_mock().client
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let steps = parse_steps(PROD_DEFAULT).unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].set_weight, Some(10));
+        assert_eq!(steps[0].pause, Some(Pause::Duration("5m".to_string())));
+
+        // JSON is valid YAML
+        let steps = parse_steps(r#"[{"setWeight": 20}, {"setWeight": 100}]"#).unwrap();
+        assert_eq!(steps.len(), 2);
+
+        assert!(parse_steps("[]").is_err());
+        assert!(parse_steps("setWeight: 10").is_err());
+    }
+
+    #[test]
+    fn test_configmap_defaults_to_rollout_namespace() {
+        let rollout = rollout_with_steps_from(reference(None, None));
+        assert_eq!(
+            steps_configmap_key(&rollout),
+            Some(("payments".to_string(), "ladders".to_string()))
+        );
+
+        let rollout = rollout_with_steps_from(reference(Some("platform"), None));
+        assert_eq!(
+            steps_configmap_key(&rollout),
+            Some(("platform".to_string(), "ladders".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fills_steps_from_cached_configmap() {
+        let resolver = StepsResolver::with_configmap(
+            "platform",
+            "ladders",
+            [("prod-default".to_string(), PROD_DEFAULT.to_string())].into(),
+        );
+        let rollout = rollout_with_steps_from(reference(Some("platform"), Some("prod-default")));
+
+        let resolved = resolver
+            .resolve(&rollout, &mock_client())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let canary = resolved.spec.strategy.canary.unwrap();
+        assert_eq!(canary.steps.len(), 3);
+        assert!(canary.steps_from.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_reports_missing_key() {
+        let resolver = StepsResolver::with_configmap("platform", "ladders", BTreeMap::new());
+        let rollout = rollout_with_steps_from(reference(Some("platform"), None));
+
+        let err = resolver
+            .resolve(&rollout, &mock_client())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            StepsFromError::MissingKey {
+                namespace: "platform".to_string(),
+                name: "ladders".to_string(),
+                key: DEFAULT_STEPS_KEY.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rollouts_importing_steps() {
+        let importing = Arc::new(rollout_with_steps_from(reference(Some("platform"), None)));
+        let mut inline = rollout_with_steps_from(reference(Some("platform"), None));
+        inline.metadata.name = Some("inline".to_string());
+        inline.spec.strategy.canary.as_mut().unwrap().steps_from = None;
+        let rollouts = vec![importing, Arc::new(inline)];
+
+        let refs = rollouts_importing_steps(&rollouts, "platform", "ladders");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].name, "app");
+        assert!(rollouts_importing_steps(&rollouts, "payments", "ladders").is_empty());
+    }
+
+    #[test]
+    fn test_invalidate_drops_cached_configmap() {
+        let resolver = StepsResolver::with_configmap("platform", "ladders", BTreeMap::new());
+
+        assert!(resolver.invalidate("platform", "ladders"));
+        assert!(resolver.cached("platform", "ladders").is_none());
+        assert!(!resolver.invalidate("platform", "ladders"));
+    }
+}
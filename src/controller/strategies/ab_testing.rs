@@ -4,11 +4,13 @@
 //! Unlike canary (weight-based), A/B testing uses deterministic routing.
 //! Both variants run at full capacity for fair comparison.
 
-use super::{RolloutStrategy, StrategyError};
+use super::{patch_httproute_with_retry, RolloutStrategy, StrategyError};
 use crate::controller::rollout::{
     build_replicasets_for_ab_testing, default_service_port, ensure_replicaset_exists, Context,
 };
-use crate::crd::rollout::{ABMatchType, ABStrategy, Phase, Rollout, RolloutStatus};
+use crate::crd::rollout::{
+    ABMatchType, ABStrategy, ManagedReplicaSet, Phase, ReplicaSetRole, Rollout, RolloutStatus,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use gateway_api::apis::standard::httproutes::{
@@ -16,7 +18,7 @@ use gateway_api::apis::standard::httproutes::{
     HTTPRouteRulesMatchesHeadersType,
 };
 use k8s_openapi::api::apps::v1::ReplicaSet;
-use kube::api::{Api, Patch, PatchParams};
+use kube::api::Api;
 use kube::core::DynamicObject;
 use kube::discovery::ApiResource;
 use kube::{Client, ResourceExt};
@@ -39,7 +41,7 @@ impl RolloutStrategy for ABTestingStrategyHandler {
         &self,
         rollout: &Rollout,
         ctx: &Context,
-    ) -> Result<(), StrategyError> {
+    ) -> Result<Vec<ManagedReplicaSet>, StrategyError> {
         let namespace = rollout
             .namespace()
             .ok_or_else(|| StrategyError::MissingField("namespace".to_string()))?;
@@ -61,14 +63,24 @@ impl RolloutStrategy for ABTestingStrategyHandler {
         let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &namespace);
 
         // Ensure variant-a ReplicaSet exists
-        ensure_replicaset_exists(&rs_api, &variant_a_rs, "variant-a", rollout.spec.replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let variant_a_managed = ensure_replicaset_exists(
+            &rs_api,
+            &variant_a_rs,
+            ReplicaSetRole::VariantA,
+            rollout.spec.replicas,
+        )
+        .await
+        .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         // Ensure variant-b ReplicaSet exists
-        ensure_replicaset_exists(&rs_api, &variant_b_rs, "variant-b", rollout.spec.replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let variant_b_managed = ensure_replicaset_exists(
+            &rs_api,
+            &variant_b_rs,
+            ReplicaSetRole::VariantB,
+            rollout.spec.replicas,
+        )
+        .await
+        .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         info!(
             rollout = ?name,
@@ -77,7 +89,7 @@ impl RolloutStrategy for ABTestingStrategyHandler {
             "A/B testing strategy ReplicaSets reconciled successfully"
         );
 
-        Ok(())
+        Ok(vec![variant_a_managed, variant_b_managed])
     }
 
     async fn reconcile_traffic(
@@ -118,20 +130,22 @@ impl RolloutStrategy for ABTestingStrategyHandler {
         // Build HTTPRoute rules for A/B testing
         let rules = build_ab_testing_httproute_rules(ab_strategy);
 
-        // Patch the HTTPRoute with header-based rules
-        patch_httproute_with_rules(
-            &ctx.client,
-            &namespace,
-            &rollout.name_any(),
-            &gateway_api_routing.http_route,
-            &rules,
-        )
-        .await?;
+        // Patch every HTTPRoute with the same header-based rules
+        for httproute_name in gateway_api_routing.route_names() {
+            patch_httproute_with_rules(
+                &ctx.client,
+                &namespace,
+                &rollout.name_any(),
+                httproute_name,
+                &rules,
+            )
+            .await?;
+        }
 
         Ok(())
     }
 
-    fn compute_next_status(&self, rollout: &Rollout, _now: DateTime<Utc>) -> RolloutStatus {
+    fn compute_next_status(&self, rollout: &Rollout, now: DateTime<Utc>) -> RolloutStatus {
         let current_status = rollout.status.as_ref();
         let current_phase = current_status.and_then(|s| s.phase.clone());
 
@@ -187,24 +201,21 @@ impl RolloutStrategy for ABTestingStrategyHandler {
             }
 
             // Initial state or unknown - start experimenting
-            _ => {
-                let now = chrono::Utc::now().to_rfc3339();
-                RolloutStatus {
-                    phase: Some(Phase::Experimenting),
-                    message: Some("A/B experiment started".to_string()),
-                    ab_experiment: Some(crate::crd::rollout::ABExperimentStatus {
-                        started_at: now,
-                        concluded_at: None,
-                        sample_size_a: None,
-                        sample_size_b: None,
-                        results: vec![],
-                        winner: None,
-                        conclusion_reason: None,
-                    }),
-                    last_decision_source: None,
-                    ..Default::default()
-                }
-            }
+            _ => RolloutStatus {
+                phase: Some(Phase::Experimenting),
+                message: Some("A/B experiment started".to_string()),
+                ab_experiment: Some(crate::crd::rollout::ABExperimentStatus {
+                    started_at: now.to_rfc3339(),
+                    concluded_at: None,
+                    sample_size_a: None,
+                    sample_size_b: None,
+                    results: vec![],
+                    winner: None,
+                    conclusion_reason: None,
+                }),
+                last_decision_source: None,
+                ..Default::default()
+            },
         }
     }
 
@@ -353,15 +364,8 @@ pub async fn patch_httproute_with_rules(
         "Patching HTTPRoute with A/B testing rules"
     );
 
-    match httproute_api
-        .patch(
-            httproute_name,
-            &PatchParams::default(),
-            &Patch::Merge(&patch_json),
-        )
-        .await
-    {
-        Ok(_) => {
+    match patch_httproute_with_retry(&httproute_api, httproute_name, &patch_json).await {
+        Ok(Some(_)) => {
             info!(
                 rollout = rollout_name,
                 httproute = httproute_name,
@@ -369,7 +373,7 @@ pub async fn patch_httproute_with_rules(
             );
             Ok(())
         }
-        Err(kube::Error::Api(err)) if err.code == 404 => {
+        Ok(None) => {
             warn!(
                 rollout = rollout_name,
                 httproute = httproute_name,
@@ -384,7 +388,7 @@ pub async fn patch_httproute_with_rules(
                 error = ?e,
                 "Failed to patch HTTPRoute"
             );
-            Err(StrategyError::TrafficReconciliationFailed(e.to_string()))
+            Err(e)
         }
     }
 }
@@ -440,7 +444,11 @@ mod tests {
                             }),
                             cookie: None,
                         },
-                        traffic_routing: Some(TrafficRouting { gateway_api: None }),
+                        traffic_routing: Some(TrafficRouting {
+                            gateway_api: None,
+                            none: None,
+                            nginx: None,
+                        }),
                         max_duration: Some("7d".to_string()),
                         analysis: Some(ABAnalysisConfig {
                             prometheus: None,
@@ -448,6 +456,8 @@ mod tests {
                             min_duration: Some("1h".to_string()),
                             min_sample_size: Some(1000),
                             confidence_level: Some(0.95),
+                            guardrails: vec![],
+                            sample_window: None,
                         }),
                     }),
                 },
@@ -455,6 +465,11 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: phase.map(|p| RolloutStatus {
                 phase: Some(p),
@@ -486,12 +501,15 @@ mod tests {
         let rollout = create_ab_testing_rollout(3, None);
         let strategy = ABTestingStrategyHandler;
 
-        let status = strategy.compute_next_status(&rollout, Utc::now());
+        let now = Utc::now();
+        let status = strategy.compute_next_status(&rollout, now);
 
         assert_eq!(status.phase, Some(Phase::Experimenting));
         assert!(status.ab_experiment.is_some());
         let ab_exp = status.ab_experiment.unwrap();
         assert!(!ab_exp.started_at.is_empty());
+        // Started at the time passed in, not the wall clock
+        assert_eq!(ab_exp.started_at, now.to_rfc3339());
         assert!(ab_exp.winner.is_none());
     }
 
@@ -7,7 +7,7 @@ use super::{reconcile_gateway_api_traffic, RolloutStrategy, StrategyError};
 use crate::controller::rollout::{
     build_replicasets_for_blue_green, ensure_replicaset_exists, has_promote_annotation, Context,
 };
-use crate::crd::rollout::{Phase, Rollout, RolloutStatus};
+use crate::crd::rollout::{ManagedReplicaSet, Phase, ReplicaSetRole, Rollout, RolloutStatus};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use k8s_openapi::api::apps::v1::ReplicaSet;
@@ -15,6 +15,23 @@ use kube::api::Api;
 use kube::ResourceExt;
 use tracing::info;
 
+/// Whether the rollout was rolled back to the active environment
+fn is_rolled_back(rollout: &Rollout) -> bool {
+    rollout
+        .status
+        .as_ref()
+        .is_some_and(|s| s.aborted_at.is_some())
+}
+
+/// Preview ReplicaSet size: full size, or zero once rolled back
+fn preview_replicas(rollout: &Rollout) -> i32 {
+    if is_rolled_back(rollout) {
+        0
+    } else {
+        rollout.spec.replicas
+    }
+}
+
 /// Blue-Green strategy handler
 ///
 /// Implements blue-green deployment:
@@ -34,7 +51,7 @@ impl RolloutStrategy for BlueGreenStrategyHandler {
         &self,
         rollout: &Rollout,
         ctx: &Context,
-    ) -> Result<(), StrategyError> {
+    ) -> Result<Vec<ManagedReplicaSet>, StrategyError> {
         let namespace = rollout
             .namespace()
             .ok_or_else(|| StrategyError::MissingField("namespace".to_string()))?;
@@ -47,32 +64,46 @@ impl RolloutStrategy for BlueGreenStrategyHandler {
             "Reconciling blue-green strategy ReplicaSets"
         );
 
-        // Build both ReplicaSets (active + preview) at full size
-        let (active_rs, preview_rs) =
+        // Build both ReplicaSets (active + preview) at full size, unless rolled back
+        let preview_replicas = preview_replicas(rollout);
+        let (active_rs, mut preview_rs) =
             build_replicasets_for_blue_green(rollout, rollout.spec.replicas)
                 .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        if let Some(spec) = preview_rs.spec.as_mut() {
+            spec.replicas = Some(preview_replicas);
+        }
 
         // Create ReplicaSet API client
         let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &namespace);
 
         // Ensure active ReplicaSet exists
-        ensure_replicaset_exists(&rs_api, &active_rs, "active", rollout.spec.replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let active_managed = ensure_replicaset_exists(
+            &rs_api,
+            &active_rs,
+            ReplicaSetRole::Active,
+            rollout.spec.replicas,
+        )
+        .await
+        .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         // Ensure preview ReplicaSet exists
-        ensure_replicaset_exists(&rs_api, &preview_rs, "preview", rollout.spec.replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let preview_managed = ensure_replicaset_exists(
+            &rs_api,
+            &preview_rs,
+            ReplicaSetRole::Preview,
+            preview_replicas,
+        )
+        .await
+        .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         info!(
             rollout = ?name,
             active_replicas = rollout.spec.replicas,
-            preview_replicas = rollout.spec.replicas,
+            preview_replicas = preview_replicas,
             "Blue-green strategy ReplicaSets reconciled successfully"
         );
 
-        Ok(())
+        Ok(vec![active_managed, preview_managed])
     }
 
     async fn reconcile_traffic(
@@ -89,6 +120,11 @@ impl RolloutStrategy for BlueGreenStrategyHandler {
         let current_phase = rollout.status.as_ref().and_then(|s| s.phase.clone());
 
         match current_phase {
+            // Rolled back - stay on active until kulta.io/retry
+            Some(Phase::Failed) if is_rolled_back(rollout) => {
+                rollout.status.as_ref().cloned().unwrap_or_default()
+            }
+
             // Already completed - stay completed
             Some(Phase::Completed) => RolloutStatus {
                 phase: Some(Phase::Completed),
@@ -183,9 +219,16 @@ mod tests {
                         traffic_routing: Some(TrafficRouting {
                             gateway_api: Some(GatewayAPIRouting {
                                 http_route: "app-route".to_string(),
+                                http_routes: vec![],
+                                weight_constraints: None,
+                                sections: Vec::new(),
+                                kind: Default::default(),
                             }),
+                            none: None,
+                            nginx: None,
                         }),
                         analysis: None,
+                        migrated_from: None,
                     }),
                     ab_testing: None,
                 },
@@ -194,6 +237,11 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: None,
         }
@@ -304,6 +352,27 @@ mod tests {
         assert_eq!(status.phase, Some(Phase::Completed));
     }
 
+    #[test]
+    fn test_blue_green_strategy_holds_rolled_back_rollout() {
+        let mut rollout = create_blue_green_rollout(5);
+        assert_eq!(preview_replicas(&rollout), 5);
+
+        rollout.status = Some(RolloutStatus {
+            phase: Some(Phase::Failed),
+            message: Some("Progress deadline exceeded".to_string()),
+            aborted_at: Some("2026-01-01T00:00:00+00:00".to_string()),
+            replicas: 5,
+            ..Default::default()
+        });
+
+        let strategy = BlueGreenStrategyHandler;
+        let status = strategy.compute_next_status(&rollout, Utc::now());
+
+        // Stays Failed instead of starting a new preview, with the preview scaled down
+        assert_eq!(rollout.status.as_ref(), Some(&status));
+        assert_eq!(preview_replicas(&rollout), 0);
+    }
+
     // Note: reconcile_replicasets() and reconcile_traffic() require K8s API
     // These are tested in integration tests
 }
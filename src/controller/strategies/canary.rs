@@ -2,12 +2,19 @@
 //!
 //! Progressive traffic shifting with gradual rollout through defined steps.
 
-use super::{reconcile_gateway_api_traffic, RolloutStrategy, StrategyError};
+use super::{
+    reconcile_gateway_api_traffic, reconcile_nginx_traffic, RolloutStrategy, StrategyError,
+};
 use crate::controller::rollout::{
-    build_replicaset, calculate_replica_split_with_surge, compute_desired_status,
-    ensure_replicaset_exists, Context,
+    baseline_replicas, build_replicaset, calculate_replica_split_with_surge,
+    calculate_replica_weighted_split, compute_desired_status, effective_replica_weight,
+    ensure_replicaset_exists, reconcile_baseline_replicaset, Context,
+};
+use crate::controller::strategy_math::{
+    current_canary_scale, current_header_route, current_mirror_percent, mirror_replicas,
+    scaled_canary_replicas,
 };
-use crate::crd::rollout::{Rollout, RolloutStatus};
+use crate::crd::rollout::{ManagedReplicaSet, ReplicaSetRole, Rollout, RolloutStatus};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use k8s_openapi::api::apps::v1::ReplicaSet;
@@ -34,7 +41,7 @@ impl RolloutStrategy for CanaryStrategyHandler {
         &self,
         rollout: &Rollout,
         ctx: &Context,
-    ) -> Result<(), StrategyError> {
+    ) -> Result<Vec<ManagedReplicaSet>, StrategyError> {
         let namespace = rollout
             .namespace()
             .ok_or_else(|| StrategyError::MissingField("namespace".to_string()))?;
@@ -47,13 +54,53 @@ impl RolloutStrategy for CanaryStrategyHandler {
             .and_then(|s| s.current_weight)
             .unwrap_or(0);
 
+        let replica_weighting = rollout
+            .spec
+            .strategy
+            .canary
+            .as_ref()
+            .and_then(|c| c.traffic_routing.as_ref())
+            .is_some_and(|t| t.uses_replica_weighting());
+
         // Calculate replica split based on weight and surge settings
-        let (stable_replicas, canary_replicas) = calculate_replica_split_with_surge(
-            rollout.spec.replicas,
-            current_weight,
-            rollout.spec.max_surge.as_deref(),
-            rollout.spec.max_unavailable.as_deref(),
-        );
+        // (replica weighting: the split itself is the traffic split, so no surge)
+        let (stable_replicas, canary_replicas) = if replica_weighting {
+            let split = calculate_replica_weighted_split(rollout.spec.replicas, current_weight);
+            let effective_weight = effective_replica_weight(split.0, split.1);
+            if effective_weight != current_weight {
+                info!(
+                    rollout = ?name,
+                    current_weight = current_weight,
+                    effective_weight = effective_weight,
+                    "Replica weighting cannot express the step weight exactly"
+                );
+            }
+            split
+        } else {
+            calculate_replica_split_with_surge(
+                rollout.spec.replicas,
+                current_weight,
+                rollout.spec.max_surge.as_deref(),
+                rollout.spec.max_unavailable.as_deref(),
+            )
+        };
+
+        // setCanaryScale sizes the canary independently of its traffic weight
+        let canary_replicas = current_canary_scale(&rollout.spec, rollout.status.as_ref())
+            .and_then(|scale| scaled_canary_replicas(rollout.spec.replicas, scale))
+            .unwrap_or(canary_replicas);
+
+        // Mirror steps send no user traffic but still need canary pods to receive the copies
+        let canary_replicas = match current_mirror_percent(&rollout.spec, rollout.status.as_ref()) {
+            Some(percent) => canary_replicas.max(mirror_replicas(rollout.spec.replicas, percent)),
+            None => canary_replicas,
+        };
+
+        // Header-routed requests reach the canary at any weight, so keep a pod to serve them
+        let canary_replicas = match current_header_route(&rollout.spec, rollout.status.as_ref()) {
+            Some(_) => canary_replicas.max(rollout.spec.replicas.min(1)),
+            None => canary_replicas,
+        };
 
         info!(
             rollout = ?name,
@@ -72,17 +119,29 @@ impl RolloutStrategy for CanaryStrategyHandler {
         let stable_rs = build_replicaset(rollout, "stable", stable_replicas)
             .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
-        ensure_replicaset_exists(&rs_api, &stable_rs, "stable", stable_replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let stable_managed =
+            ensure_replicaset_exists(&rs_api, &stable_rs, ReplicaSetRole::Stable, stable_replicas)
+                .await
+                .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         // Build and ensure canary ReplicaSet exists
         let canary_rs = build_replicaset(rollout, "canary", canary_replicas)
             .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
-        ensure_replicaset_exists(&rs_api, &canary_rs, "canary", canary_replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let canary_managed =
+            ensure_replicaset_exists(&rs_api, &canary_rs, ReplicaSetRole::Canary, canary_replicas)
+                .await
+                .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+
+        let mut managed = vec![stable_managed, canary_managed];
+
+        // canary.baseline: stable template pods as new as the canary's
+        if let Some(replicas) = baseline_replicas(rollout, canary_replicas) {
+            let baseline_managed = reconcile_baseline_replicaset(rollout, &rs_api, replicas)
+                .await
+                .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+            managed.extend(baseline_managed);
+        }
 
         info!(
             rollout = ?name,
@@ -91,7 +150,7 @@ impl RolloutStrategy for CanaryStrategyHandler {
             "Canary strategy ReplicaSets reconciled successfully"
         );
 
-        Ok(())
+        Ok(managed)
     }
 
     async fn reconcile_traffic(
@@ -99,8 +158,9 @@ impl RolloutStrategy for CanaryStrategyHandler {
         rollout: &Rollout,
         ctx: &Context,
     ) -> Result<(), StrategyError> {
-        // Use shared helper for Gateway API traffic routing
-        reconcile_gateway_api_traffic(rollout, ctx, "canary").await
+        // Use shared helpers for Gateway API and NGINX Ingress traffic routing
+        reconcile_gateway_api_traffic(rollout, ctx, "canary").await?;
+        reconcile_nginx_traffic(rollout, ctx).await
     }
 
     fn compute_next_status(&self, rollout: &Rollout, now: DateTime<Utc>) -> RolloutStatus {
@@ -127,7 +187,7 @@ impl RolloutStrategy for CanaryStrategyHandler {
 mod tests {
     use super::*;
     use crate::crd::rollout::{
-        CanaryStep, CanaryStrategy, GatewayAPIRouting, PauseDuration, Phase, RolloutSpec,
+        CanaryStep, CanaryStrategy, GatewayAPIRouting, Pause, Phase, RolloutSpec,
         RolloutStrategy as RolloutStrategySpec, TrafficRouting,
     };
     use k8s_openapi::api::core::v1::PodTemplateSpec;
@@ -158,9 +218,19 @@ mod tests {
                         traffic_routing: Some(TrafficRouting {
                             gateway_api: Some(GatewayAPIRouting {
                                 http_route: "app-route".to_string(),
+                                http_routes: vec![],
+                                weight_constraints: None,
+                                sections: Vec::new(),
+                                kind: Default::default(),
                             }),
+                            none: None,
+                            nginx: None,
                         }),
                         analysis: None,
+                        steps_from: None,
+                        gates: vec![],
+                        baseline: None,
+                        rollback_verification: None,
                     }),
                     blue_green: None,
                     ab_testing: None,
@@ -170,6 +240,11 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: current_weight.map(|weight| crate::crd::rollout::RolloutStatus {
                 phase: Some(Phase::Progressing),
@@ -179,12 +254,34 @@ mod tests {
                 ready_replicas: 0,
                 updated_replicas: 0,
                 message: None,
+                summary: None,
                 pause_start_time: None,
                 step_start_time: None,
                 progress_started_at: None,
                 decisions: vec![],
                 ab_experiment: None,
                 last_decision_source: None,
+                pod_template_hash: None,
+                revision: None,
+                template_diff: None,
+                http_routes: vec![],
+                degraded_since: None,
+                open_incident: None,
+                active_freeze: None,
+                aborted_at: None,
+                resources: None,
+                strategy: None,
+                paused_until: None,
+                promoted_at: None,
+                promoted_version: None,
+                step_analysis: None,
+                step_experiment: None,
+                rollback_verification: None,
+                metric_failures: None,
+                conditions: vec![],
+                observed_generation: None,
+                selector: None,
+                effective_config: None,
             }),
         }
     }
@@ -213,12 +310,22 @@ mod tests {
             CanaryStep {
                 set_weight: Some(10),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(50),
-                pause: Some(PauseDuration {
-                    duration: Some("30s".to_string()),
-                }),
+                pause: Some(Pause::Duration("30s".to_string())),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
         let rollout = create_canary_rollout(3, None, steps);
@@ -238,10 +345,22 @@ mod tests {
             CanaryStep {
                 set_weight: Some(10),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
             CanaryStep {
                 set_weight: Some(100),
                 pause: None,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
             },
         ];
         let rollout = create_canary_rollout(3, Some(10), steps);
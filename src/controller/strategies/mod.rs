@@ -11,15 +11,29 @@ pub mod blue_green;
 pub mod canary;
 pub mod simple;
 
-use crate::controller::rollout::{build_gateway_api_backend_refs, Context};
-use crate::crd::rollout::{GatewayAPIRouting, Rollout, RolloutStatus};
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::controller::guardrails::GuardrailPolicy;
+use crate::controller::rollout::{
+    apply_abort_or_retry, apply_weight_override, build_canary_ingress,
+    build_gateway_api_backend_refs, get_nginx_routing, has_abort_annotation,
+    has_promote_annotation, CanaryStepRoutes, Context,
+};
+use crate::controller::traffic_shift::TrafficShifts;
+use crate::crd::rollout::{
+    GatewayAPIRouting, GatewayRouteKind, HttpRouteStatus, ManagedReplicaSet, Rollout, RolloutSpec,
+    RolloutStatus,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use gateway_api::apis::standard::httproutes::HTTPRouteRulesBackendRefs;
-use kube::api::{Api, Patch, PatchParams};
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::{Api, ObjectMeta, Patch, PatchParams};
 use kube::core::DynamicObject;
 use kube::discovery::ApiResource;
 use kube::{Client, ResourceExt};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tracing::{error, info, warn};
 
@@ -37,89 +51,400 @@ pub enum StrategyError {
 
     #[error("Missing required field: {0}")]
     MissingField(String),
+
+    #[error("HTTPRoute {route} patch still conflicting after {attempts} attempts")]
+    HttpRouteConflict { route: String, attempts: u32 },
+
+    #[error("HTTPRoute {route} patch rejected by API server: {message}")]
+    HttpRouteRejected { route: String, message: String },
+
+    #[error("HTTPRoutes out of sync: {}", unsynced_route_names(routes))]
+    HttpRoutesOutOfSync { routes: Vec<HttpRouteStatus> },
+}
+
+/// Comma-separated names of routes that failed to sync (for error messages)
+fn unsynced_route_names(routes: &[HttpRouteStatus]) -> String {
+    routes
+        .iter()
+        .filter(|r| !r.synced)
+        .map(|r| r.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Maximum attempts for an HTTPRoute patch that keeps hitting 409 Conflict
+pub const HTTPROUTE_PATCH_MAX_ATTEMPTS: u32 = 3;
+
+/// Base delay between conflict retries (multiplied by the attempt number)
+const HTTPROUTE_PATCH_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Classification of a failed HTTPRoute patch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpRoutePatchFailure {
+    /// 404: the route doesn't exist (non-fatal, traffic routing is optional)
+    NotFound,
+    /// 409: concurrent modification, safe to retry
+    Conflict,
+    /// 422: the patched object failed API server validation, retrying won't help
+    Invalid,
+    /// Any other API or transport error
+    Other,
+}
+
+impl HttpRoutePatchFailure {
+    /// Classify an HTTP status code returned by the API server
+    pub fn from_status_code(code: u16) -> Self {
+        match code {
+            404 => HttpRoutePatchFailure::NotFound,
+            409 => HttpRoutePatchFailure::Conflict,
+            422 => HttpRoutePatchFailure::Invalid,
+            _ => HttpRoutePatchFailure::Other,
+        }
+    }
+
+    /// Classify a kube client error
+    pub fn classify(err: &kube::Error) -> Self {
+        match err {
+            kube::Error::Api(api_err) => Self::from_status_code(api_err.code),
+            _ => HttpRoutePatchFailure::Other,
+        }
+    }
 }
 
-/// Patch HTTPRoute with weighted backend refs
+/// API resource of a Gateway API route kind
+///
+/// TLSRoute and UDPRoute are still experimental and served as v1alpha2.
+pub fn gateway_route_api_resource(kind: GatewayRouteKind) -> ApiResource {
+    let (version, kind, plural) = match kind {
+        GatewayRouteKind::HTTPRoute => ("v1", "HTTPRoute", "httproutes"),
+        GatewayRouteKind::TLSRoute => ("v1alpha2", "TLSRoute", "tlsroutes"),
+        GatewayRouteKind::UDPRoute => ("v1alpha2", "UDPRoute", "udproutes"),
+    };
+    ApiResource {
+        group: "gateway.networking.k8s.io".to_string(),
+        version: version.to_string(),
+        api_version: format!("gateway.networking.k8s.io/{}", version),
+        kind: kind.to_string(),
+        plural: plural.to_string(),
+    }
+}
+
+/// Apply a merge patch to an HTTPRoute, retrying 409 conflicts a bounded number of times
+///
+/// # Returns
+/// * `Ok(Some(route))` - Patch applied; the route as patched
+/// * `Ok(None)` - HTTPRoute not found (callers treat this as non-fatal)
+/// * `Err(StrategyError::HttpRouteConflict)` - Still conflicting after all attempts
+/// * `Err(StrategyError::HttpRouteRejected)` - 422, the patched route is invalid
+/// * `Err(StrategyError::TrafficReconciliationFailed)` - Any other error
+pub async fn patch_httproute_with_retry(
+    httproute_api: &Api<DynamicObject>,
+    httproute_name: &str,
+    patch_json: &serde_json::Value,
+) -> Result<Option<DynamicObject>, StrategyError> {
+    let mut attempt = 1;
+    loop {
+        let err = match httproute_api
+            .patch(
+                httproute_name,
+                &PatchParams::default(),
+                &Patch::Merge(patch_json),
+            )
+            .await
+        {
+            Ok(patched) => {
+                audit::record(
+                    AuditOperation::Patch,
+                    AuditObjectRef::new(
+                        patched
+                            .types
+                            .as_ref()
+                            .map_or("HTTPRoute", |types| types.kind.as_str()),
+                        patched.namespace().as_deref().unwrap_or_default(),
+                        httproute_name,
+                    ),
+                    backend_weights_summary(patch_json),
+                    "traffic weights",
+                )
+                .await;
+                return Ok(Some(patched));
+            }
+            Err(e) => e,
+        };
+
+        match HttpRoutePatchFailure::classify(&err) {
+            HttpRoutePatchFailure::NotFound => return Ok(None),
+            HttpRoutePatchFailure::Conflict if attempt < HTTPROUTE_PATCH_MAX_ATTEMPTS => {
+                warn!(
+                    httproute = ?httproute_name,
+                    attempt = attempt,
+                    max_attempts = HTTPROUTE_PATCH_MAX_ATTEMPTS,
+                    "HTTPRoute patch conflicted, retrying"
+                );
+                tokio::time::sleep(HTTPROUTE_PATCH_RETRY_DELAY * attempt).await;
+                attempt += 1;
+            }
+            HttpRoutePatchFailure::Conflict => {
+                return Err(StrategyError::HttpRouteConflict {
+                    route: httproute_name.to_string(),
+                    attempts: attempt,
+                });
+            }
+            HttpRoutePatchFailure::Invalid => {
+                let message = match &err {
+                    kube::Error::Api(api_err) => api_err.message.clone(),
+                    other => other.to_string(),
+                };
+                return Err(StrategyError::HttpRouteRejected {
+                    route: httproute_name.to_string(),
+                    message,
+                });
+            }
+            HttpRoutePatchFailure::Other => {
+                return Err(StrategyError::TrafficReconciliationFailed(err.to_string()));
+            }
+        }
+    }
+}
+
+/// Backend weights set by an HTTPRoute patch (e.g., "app-stable=80, app-canary=20")
+///
+/// Rules are separated by "; " so A/B header routes stay distinguishable.
+/// Mirrored backends are listed as "mirror app-canary=25%".
+fn backend_weights_summary(patch_json: &serde_json::Value) -> String {
+    let rules = patch_json["spec"]["rules"].as_array().into_iter().flatten();
+    rules
+        .map(|rule| {
+            let backends = rule["backendRefs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|backend| {
+                    format!(
+                        "{}={}",
+                        backend["name"].as_str().unwrap_or("?"),
+                        backend["weight"].as_i64().unwrap_or(1)
+                    )
+                });
+            let mirrors = rule["filters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|filter| filter.get("requestMirror"))
+                .map(|mirror| {
+                    format!(
+                        "mirror {}={}%",
+                        mirror["backendRef"]["name"].as_str().unwrap_or("?"),
+                        mirror["percent"].as_i64().unwrap_or(100)
+                    )
+                });
+            backends.chain(mirrors).collect::<Vec<_>>().join(", ")
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Patch HTTPRoutes with weighted backend refs
 ///
 /// Shared helper used by both canary and blue-green strategies to update
 /// Gateway API HTTPRoute resources with traffic weights.
 ///
+/// With a single route a missing HTTPRoute is non-fatal. With several routes
+/// every route is attempted and any route left behind (including a missing
+/// one) fails the whole update, so the rollout doesn't advance while routes
+/// disagree on weights.
+///
+/// On a route listed in `sections` only the named rule is updated, see
+/// [`route_patch`].
+///
 /// # Arguments
 /// * `client` - Kubernetes client
-/// * `namespace` - Namespace of the HTTPRoute
+/// * `namespace` - Namespace of the HTTPRoutes
 /// * `rollout_name` - Name of the rollout (for logging)
-/// * `gateway_api_routing` - Gateway API routing config containing HTTPRoute name
+/// * `gateway_api_routing` - Gateway API routing config containing HTTPRoute names
 /// * `backend_refs` - Weighted backend refs to apply
+/// * `step_routes` - Mirror filter and header rule of the current canary step
 /// * `strategy_name` - Strategy name for logging ("canary" or "blue-green")
+/// * `traffic_shifts` - Watches patched routes until the gateway accepts them
 ///
 /// # Returns
-/// * `Ok(())` - HTTPRoute patched or not found (non-fatal)
-/// * `Err(StrategyError)` - API error other than 404
+/// * `Ok(())` - All HTTPRoutes patched (single route: or not found)
+/// * `Err(StrategyError::HttpRoutesOutOfSync)` - Multiple routes, at least one failed
+/// * `Err(StrategyError)` - Single route: API error other than 404 (409 only after retries)
 pub async fn patch_httproute_weights(
     client: &Client,
     namespace: &str,
     rollout_name: &str,
     gateway_api_routing: &GatewayAPIRouting,
     backend_refs: &[HTTPRouteRulesBackendRefs],
+    step_routes: CanaryStepRoutes,
     strategy_name: &str,
+    traffic_shifts: &Arc<TrafficShifts>,
 ) -> Result<(), StrategyError> {
-    let httproute_name = &gateway_api_routing.http_route;
-
-    info!(
-        rollout = ?rollout_name,
-        httproute = ?httproute_name,
-        strategy = strategy_name,
-        "Updating HTTPRoute with weighted backends"
-    );
+    let route_names = gateway_api_routing.route_names();
 
-    // Create JSON patch to update HTTPRoute's first rule's backendRefs
+    // Create JSON patch replacing the HTTPRoute's rules with the weighted rule
+    // (rules are replaced as a whole, so an omitted filter or header rule is removed)
     let patch_json = serde_json::json!({
         "spec": {
-            "rules": [{
-                "backendRefs": backend_refs
-            }]
+            "rules": step_routes.clone().rules(backend_refs)
         }
     });
 
-    // Create HTTPRoute API client using DynamicObject
-    let ar = ApiResource {
-        group: "gateway.networking.k8s.io".to_string(),
-        version: "v1".to_string(),
-        api_version: "gateway.networking.k8s.io/v1".to_string(),
-        kind: "HTTPRoute".to_string(),
-        plural: "httproutes".to_string(),
-    };
+    // Create route API client (HTTPRoute, TLSRoute or UDPRoute) using DynamicObject
+    let ar = gateway_route_api_resource(gateway_api_routing.kind);
 
     let httproute_api: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, &ar);
 
-    // Apply the patch
-    match httproute_api
-        .patch(
+    let mut results = Vec::with_capacity(route_names.len());
+    for httproute_name in route_names {
+        let result = match route_patch(
+            &httproute_api,
             httproute_name,
-            &PatchParams::default(),
-            &Patch::Merge(&patch_json),
+            gateway_api_routing,
+            &patch_json,
+            &step_routes,
+            backend_refs,
         )
         .await
-    {
-        Ok(_) => {
-            info!(
-                rollout = ?rollout_name,
-                httproute = ?httproute_name,
-                weight_1 = backend_refs.first().and_then(|b| b.weight),
-                weight_2 = backend_refs.get(1).and_then(|b| b.weight),
-                strategy = strategy_name,
-                "HTTPRoute updated successfully"
-            );
-            Ok(())
-        }
-        Err(kube::Error::Api(err)) if err.code == 404 => {
-            // HTTPRoute not found - non-fatal, traffic routing is optional
-            warn!(
-                rollout = ?rollout_name,
-                httproute = ?httproute_name,
-                "HTTPRoute not found - skipping traffic routing update"
-            );
-            Ok(())
+        {
+            Ok(Some(patch)) => patch_httproute(
+                &httproute_api,
+                rollout_name,
+                httproute_name,
+                &patch,
+                backend_refs,
+                strategy_name,
+            )
+            .await
+            .map(|patched| match patched {
+                Some(route) => {
+                    traffic_shifts.track(&httproute_api, &route);
+                    true
+                }
+                None => false,
+            }),
+            Ok(None) => Ok(false),
+            Err(e) => Err(e),
+        };
+        results.push((httproute_name, result));
+    }
+
+    if results.len() == 1 {
+        return match results.pop() {
+            Some((httproute_name, Ok(false))) => {
+                // HTTPRoute not found - non-fatal, traffic routing is optional
+                warn!(
+                    rollout = ?rollout_name,
+                    httproute = ?httproute_name,
+                    "HTTPRoute not found - skipping traffic routing update"
+                );
+                Ok(())
+            }
+            Some((_, result)) => result.map(|_| ()),
+            None => Ok(()),
+        };
+    }
+
+    let routes: Vec<HttpRouteStatus> = results
+        .into_iter()
+        .map(|(httproute_name, result)| HttpRouteStatus {
+            name: httproute_name.to_string(),
+            synced: matches!(result, Ok(true)),
+            message: match result {
+                Ok(true) => None,
+                Ok(false) => Some("HTTPRoute not found".to_string()),
+                Err(e) => Some(e.to_string()),
+            },
+        })
+        .collect();
+
+    if routes.iter().all(|r| r.synced) {
+        Ok(())
+    } else {
+        Err(StrategyError::HttpRoutesOutOfSync { routes })
+    }
+}
+
+/// Weight patch for one route
+///
+/// `whole_route` (all rules replaced) unless the route is limited to a
+/// section: then the route is read and only that rule is updated, see
+/// [`CanaryStepRoutes::section_rules`]. The patch carries the version that
+/// was read, so a concurrent edit of the route conflicts instead of being
+/// overwritten (and is picked up by the next reconcile).
+///
+/// # Returns
+/// * `Ok(Some(patch))` - The patch to apply
+/// * `Ok(None)` - The sectioned route doesn't exist
+/// * `Err(StrategyError::HttpRouteRejected)` - The route has no rule named by the section
+pub async fn route_patch(
+    httproute_api: &Api<DynamicObject>,
+    httproute_name: &str,
+    gateway_api_routing: &GatewayAPIRouting,
+    whole_route: &serde_json::Value,
+    step_routes: &CanaryStepRoutes,
+    backend_refs: &[HTTPRouteRulesBackendRefs],
+) -> Result<Option<serde_json::Value>, StrategyError> {
+    let Some(section) = gateway_api_routing.section_name(httproute_name) else {
+        return Ok(Some(whole_route.clone()));
+    };
+    let route = match httproute_api.get_opt(httproute_name).await {
+        Ok(Some(route)) => route,
+        Ok(None) => return Ok(None),
+        Err(e) => return Err(StrategyError::TrafficReconciliationFailed(e.to_string())),
+    };
+    let current = route.data["spec"]["rules"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let rules = step_routes
+        .clone()
+        .section_rules(&current, section, backend_refs)
+        .map_err(|message| StrategyError::HttpRouteRejected {
+            route: httproute_name.to_string(),
+            message,
+        })?;
+
+    let mut patch = serde_json::json!({ "spec": { "rules": rules } });
+    if let Some(resource_version) = route.resource_version() {
+        patch["metadata"] = serde_json::json!({ "resourceVersion": resource_version });
+    }
+    Ok(Some(patch))
+}
+
+/// Apply a weight patch to one HTTPRoute (logging only)
+///
+/// Returns `Ok(None)` when the route doesn't exist.
+async fn patch_httproute(
+    httproute_api: &Api<DynamicObject>,
+    rollout_name: &str,
+    httproute_name: &str,
+    patch_json: &serde_json::Value,
+    backend_refs: &[HTTPRouteRulesBackendRefs],
+    strategy_name: &str,
+) -> Result<Option<DynamicObject>, StrategyError> {
+    info!(
+        rollout = ?rollout_name,
+        httproute = ?httproute_name,
+        strategy = strategy_name,
+        "Updating HTTPRoute with weighted backends"
+    );
+
+    // Apply the patch (409 conflicts are retried a bounded number of times)
+    match patch_httproute_with_retry(httproute_api, httproute_name, patch_json).await {
+        Ok(patched) => {
+            if patched.is_some() {
+                info!(
+                    rollout = ?rollout_name,
+                    httproute = ?httproute_name,
+                    weight_1 = backend_refs.first().and_then(|b| b.weight),
+                    weight_2 = backend_refs.get(1).and_then(|b| b.weight),
+                    strategy = strategy_name,
+                    "HTTPRoute updated successfully"
+                );
+            }
+            Ok(patched)
         }
         Err(e) => {
             error!(
@@ -128,11 +453,59 @@ pub async fn patch_httproute_weights(
                 httproute = ?httproute_name,
                 "Failed to patch HTTPRoute"
             );
-            Err(StrategyError::TrafficReconciliationFailed(e.to_string()))
+            Err(e)
         }
     }
 }
 
+/// Sync state to record for a rollout whose traffic update succeeded
+///
+/// Only reported with multiple HTTPRoutes; a single route has nothing to
+/// disagree with.
+pub fn synced_httproute_statuses(rollout: &Rollout) -> Vec<HttpRouteStatus> {
+    let Some(routing) = get_gateway_api_routing(rollout) else {
+        return Vec::new();
+    };
+    let names = routing.route_names();
+    if names.len() < 2 {
+        return Vec::new();
+    }
+    names
+        .into_iter()
+        .map(|name| HttpRouteStatus {
+            name: name.to_string(),
+            synced: true,
+            message: None,
+        })
+        .collect()
+}
+
+/// Names of the HTTPRoutes the controller writes for this rollout
+///
+/// Covers canary and blue-green weights as well as A/B testing rules.
+pub fn managed_httproute_names(rollout: &Rollout) -> Vec<String> {
+    let routing = get_gateway_api_routing(rollout).or_else(|| {
+        rollout
+            .spec
+            .strategy
+            .ab_testing
+            .as_ref()?
+            .traffic_routing
+            .as_ref()?
+            .gateway_api
+            .as_ref()
+    });
+    routing
+        .map(|routing| {
+            routing
+                .route_names()
+                .into_iter()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Extract Gateway API routing config from rollout
 ///
 /// Returns None if traffic routing is not configured (which is valid).
@@ -181,8 +554,9 @@ pub async fn reconcile_gateway_api_traffic(
         }
     };
 
-    // Build the weighted backend refs
+    // Build the weighted backend refs (plus the mirror filter and header rule of the step)
     let backend_refs = build_gateway_api_backend_refs(rollout);
+    let step_routes = CanaryStepRoutes::for_rollout(rollout);
 
     // Patch HTTPRoute with weights
     patch_httproute_weights(
@@ -191,11 +565,85 @@ pub async fn reconcile_gateway_api_traffic(
         &name,
         gateway_api_routing,
         &backend_refs,
+        step_routes,
         strategy_name,
+        &ctx.traffic_shifts,
     )
     .await
 }
 
+/// Reconcile the NGINX canary Ingress of a canary rollout
+///
+/// Creates the canary Ingress on first use and afterwards keeps its rules and
+/// `canary-weight` annotation in step with the stable Ingress and the current
+/// weight. A missing stable Ingress is logged and skipped, like a missing
+/// HTTPRoute.
+pub async fn reconcile_nginx_traffic(
+    rollout: &Rollout,
+    ctx: &Context,
+) -> Result<(), StrategyError> {
+    let Some(routing) = get_nginx_routing(rollout) else {
+        return Ok(());
+    };
+    let namespace = rollout
+        .namespace()
+        .ok_or_else(|| StrategyError::MissingField("namespace".to_string()))?;
+    let name = rollout.name_any();
+
+    let ingress_api: Api<Ingress> = Api::namespaced(ctx.client.clone(), &namespace);
+    let Some(stable) = ingress_api.get_opt(&routing.stable_ingress).await? else {
+        warn!(
+            rollout = ?name,
+            ingress = ?routing.stable_ingress,
+            "Stable Ingress not found - skipping traffic routing update"
+        );
+        return Ok(());
+    };
+
+    let canary = build_canary_ingress(rollout, &stable)
+        .map_err(StrategyError::TrafficReconciliationFailed)?;
+    let canary_name = canary.name_any();
+    let weight_summary = format!(
+        "canary-weight={}",
+        canary
+            .annotations()
+            .iter()
+            .find(|(key, _)| key.ends_with("/canary-weight"))
+            .map(|(_, weight)| weight.as_str())
+            .unwrap_or_default()
+    );
+
+    let operation = if ingress_api.get_opt(&canary_name).await?.is_some() {
+        let patch = serde_json::json!({
+            "metadata": { "annotations": canary.metadata.annotations },
+            "spec": canary.spec,
+        });
+        ingress_api
+            .patch(&canary_name, &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        AuditOperation::Patch
+    } else {
+        ingress_api
+            .create(&kube::api::PostParams::default(), &canary)
+            .await?;
+        AuditOperation::Create
+    };
+    audit::record(
+        operation,
+        AuditObjectRef::new("Ingress", &namespace, &canary_name),
+        weight_summary,
+        "traffic weights",
+    )
+    .await;
+
+    info!(
+        rollout = ?name,
+        ingress = ?canary_name,
+        "NGINX canary Ingress reconciled"
+    );
+    Ok(())
+}
+
 /// Strategy trait for different rollout types
 ///
 /// Each deployment strategy (Simple, Canary, Blue-Green) implements this trait
@@ -233,7 +681,7 @@ pub trait RolloutStrategy: Send + Sync {
     /// * `ctx` - Controller context with k8s client
     ///
     /// # Returns
-    /// * `Ok(())` - ReplicaSets reconciled successfully
+    /// * `Ok(replica_sets)` - ReplicaSets reconciled successfully (reported in `status.resources`)
     /// * `Err(StrategyError)` - Reconciliation failed
     ///
     /// # Idempotency
@@ -243,7 +691,7 @@ pub trait RolloutStrategy: Send + Sync {
         &self,
         rollout: &Rollout,
         ctx: &Context,
-    ) -> Result<(), StrategyError>;
+    ) -> Result<Vec<ManagedReplicaSet>, StrategyError>;
 
     /// Update traffic routing (HTTPRoute) for this strategy
     ///
@@ -278,13 +726,16 @@ pub trait RolloutStrategy: Send + Sync {
     ///
     /// # Arguments
     /// * `rollout` - The Rollout resource
+    /// * `now` - Current time, from the controller's clock
     ///
     /// # Returns
     /// The desired RolloutStatus
     ///
     /// # Purity
     /// This function is pure - it has no side effects and always returns
-    /// the same output for the same input.
+    /// the same output for the same input. It reads only the Rollout's spec,
+    /// status and annotations, and takes the time from `now`; see
+    /// [`simulate_next_status`].
     fn compute_next_status(&self, rollout: &Rollout, now: DateTime<Utc>) -> RolloutStatus;
 
     /// Does this strategy support metrics-based analysis?
@@ -338,8 +789,110 @@ pub fn select_strategy(rollout: &Rollout) -> Box<dyn RolloutStrategy> {
     }
 }
 
+/// Predict the controller's next status transition, without a cluster
+///
+/// Runs the strategy selected for `spec` on a Rollout with the given status
+/// and annotations at `now`, then applies `kulta.io/abort`/`kulta.io/retry`
+/// and `kulta.io/set-weight` (checked against the default guardrails) like
+/// the reconcile loop does. `kulta.io/promote` and `kulta.io/approved-by`
+/// are honored by the strategy itself. The result is the status the
+/// controller would write on the next reconcile.
+///
+/// What the reconcile loop reads from the cluster (analysis verdicts, freeze
+/// windows, promotion policies, a degraded new revision under `onDegraded`,
+/// step analyses and experiments) is not part of it: those arrive as status
+/// fields on the following call.
+///
+/// # Example
+/// ```ignore
+/// let next = simulate_next_status(&rollout.spec, rollout.status.as_ref(), Utc::now(), &annotations);
+/// println!("next phase: {:?}", next.phase);
+/// ```
+pub fn simulate_next_status(
+    spec: &RolloutSpec,
+    status: Option<&RolloutStatus>,
+    now: DateTime<Utc>,
+    annotations: &BTreeMap<String, String>,
+) -> RolloutStatus {
+    let rollout = Rollout {
+        metadata: ObjectMeta {
+            annotations: Some(annotations.clone()),
+            ..Default::default()
+        },
+        spec: spec.clone(),
+        status: status.cloned(),
+    };
+    let desired = select_strategy(&rollout).compute_next_status(&rollout, now);
+    let (desired, _) = apply_abort_or_retry(&rollout, desired, now);
+    let (desired, _) = apply_weight_override(&rollout, desired, &GuardrailPolicy::default(), now);
+    desired
+}
+
+/// Reject fields the selected strategy would silently ignore
+///
+/// Checks the spec against the strategy's capabilities, so a configuration
+/// that does nothing fails loudly instead:
+/// - `analysis` on simple or blue-green (metrics are never evaluated)
+/// - `kulta.io/promote` on strategies without manual promotion (simple)
+/// - `kulta.io/abort` on those strategies (they complete immediately), or
+///   together with `kulta.io/promote`
+///
+/// # Returns
+/// * `Ok(())` - Every configured feature is supported
+/// * `Err(String)` - Validation error naming the unsupported field
+pub fn check_feature_support(
+    rollout: &Rollout,
+    strategy: &dyn RolloutStrategy,
+) -> Result<(), String> {
+    if !strategy.supports_metrics_analysis() {
+        let spec = &rollout.spec.strategy;
+        let analysis_field = if spec.simple.as_ref().is_some_and(|s| s.analysis.is_some()) {
+            Some("spec.strategy.simple.analysis")
+        } else if spec
+            .blue_green
+            .as_ref()
+            .is_some_and(|bg| bg.analysis.is_some())
+        {
+            Some("spec.strategy.blueGreen.analysis")
+        } else {
+            None
+        };
+        if let Some(field) = analysis_field {
+            return Err(format!(
+                "{} is not supported by the {} strategy (metrics are never evaluated); remove it or use canary",
+                field,
+                strategy.name()
+            ));
+        }
+    }
+
+    if !strategy.supports_manual_promotion() && has_promote_annotation(rollout) {
+        return Err(format!(
+            "annotation kulta.io/promote is not supported by the {} strategy (it has no steps to promote); remove it",
+            strategy.name()
+        ));
+    }
+
+    if has_abort_annotation(rollout) {
+        if !strategy.supports_manual_promotion() {
+            return Err(format!(
+                "annotation kulta.io/abort is not supported by the {} strategy (it completes immediately); remove it",
+                strategy.name()
+            ));
+        }
+        if has_promote_annotation(rollout) {
+            return Err(
+                "annotations kulta.io/abort and kulta.io/promote conflict; remove one".to_string(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used)]
     use super::*;
     use crate::crd::rollout::{
         BlueGreenStrategy, CanaryStrategy, RolloutSpec, RolloutStrategy as RolloutStrategySpec,
@@ -365,11 +918,135 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: None,
         }
     }
 
+    #[test]
+    fn test_backend_weights_summary() {
+        let patch_json = serde_json::json!({
+            "spec": {
+                "rules": [{
+                    "backendRefs": [
+                        { "name": "app-stable", "port": 80, "weight": 80 },
+                        { "name": "app-canary", "port": 80, "weight": 20 }
+                    ]
+                }]
+            }
+        });
+
+        assert_eq!(
+            backend_weights_summary(&patch_json),
+            "app-stable=80, app-canary=20"
+        );
+
+        let mirrored = serde_json::json!({
+            "spec": {
+                "rules": [{
+                    "backendRefs": [
+                        { "name": "app-stable", "port": 80, "weight": 100 },
+                        { "name": "app-canary", "port": 80, "weight": 0 }
+                    ],
+                    "filters": [{
+                        "type": "RequestMirror",
+                        "requestMirror": {
+                            "backendRef": { "name": "app-canary", "port": 80 },
+                            "percent": 25
+                        }
+                    }]
+                }]
+            }
+        });
+        assert_eq!(
+            backend_weights_summary(&mirrored),
+            "app-stable=100, app-canary=0, mirror app-canary=25%"
+        );
+    }
+
+    #[test]
+    fn test_gateway_route_api_resource() {
+        let http = gateway_route_api_resource(GatewayRouteKind::HTTPRoute);
+        assert_eq!(http.api_version, "gateway.networking.k8s.io/v1");
+        assert_eq!(http.plural, "httproutes");
+
+        let tls = gateway_route_api_resource(GatewayRouteKind::TLSRoute);
+        assert_eq!(tls.api_version, "gateway.networking.k8s.io/v1alpha2");
+        assert_eq!(tls.kind, "TLSRoute");
+        assert_eq!(tls.plural, "tlsroutes");
+
+        let udp = gateway_route_api_resource(GatewayRouteKind::UDPRoute);
+        assert_eq!(udp.plural, "udproutes");
+    }
+
+    #[test]
+    fn test_classify_httproute_patch_status_codes() {
+        assert_eq!(
+            HttpRoutePatchFailure::from_status_code(404),
+            HttpRoutePatchFailure::NotFound
+        );
+        assert_eq!(
+            HttpRoutePatchFailure::from_status_code(409),
+            HttpRoutePatchFailure::Conflict
+        );
+        assert_eq!(
+            HttpRoutePatchFailure::from_status_code(422),
+            HttpRoutePatchFailure::Invalid
+        );
+        assert_eq!(
+            HttpRoutePatchFailure::from_status_code(500),
+            HttpRoutePatchFailure::Other
+        );
+        assert_eq!(
+            HttpRoutePatchFailure::from_status_code(403),
+            HttpRoutePatchFailure::Other
+        );
+    }
+
+    #[test]
+    fn test_httproute_error_messages_name_the_route() {
+        let conflict = StrategyError::HttpRouteConflict {
+            route: "my-route".to_string(),
+            attempts: HTTPROUTE_PATCH_MAX_ATTEMPTS,
+        };
+        assert_eq!(
+            conflict.to_string(),
+            "HTTPRoute my-route patch still conflicting after 3 attempts"
+        );
+
+        let rejected = StrategyError::HttpRouteRejected {
+            route: "my-route".to_string(),
+            message: "spec.rules[0].backendRefs[0].weight: Invalid value".to_string(),
+        };
+        assert!(rejected.to_string().contains("my-route"));
+        assert!(rejected.to_string().contains("Invalid value"));
+    }
+
+    #[test]
+    fn test_httproutes_out_of_sync_lists_failed_routes() {
+        let error = StrategyError::HttpRoutesOutOfSync {
+            routes: vec![
+                HttpRouteStatus {
+                    name: "internal".to_string(),
+                    synced: true,
+                    message: None,
+                },
+                HttpRouteStatus {
+                    name: "external".to_string(),
+                    synced: false,
+                    message: Some("HTTPRoute not found".to_string()),
+                },
+            ],
+        };
+
+        assert_eq!(error.to_string(), "HTTPRoutes out of sync: external");
+    }
+
     #[test]
     fn test_select_strategy_simple() {
         let rollout = create_test_rollout(RolloutStrategySpec {
@@ -396,6 +1073,7 @@ mod tests {
                 auto_promotion_seconds: None,
                 traffic_routing: None,
                 analysis: None,
+                migrated_from: None,
             }),
             ab_testing: None,
         });
@@ -415,6 +1093,10 @@ mod tests {
                 steps: vec![],
                 traffic_routing: None,
                 analysis: None,
+                steps_from: None,
+                gates: vec![],
+                baseline: None,
+                rollback_verification: None,
             }),
             blue_green: None,
             ab_testing: None,
@@ -466,4 +1148,133 @@ mod tests {
         let strategy = select_strategy(&rollout);
         assert_eq!(strategy.name(), "ab-testing");
     }
+
+    #[test]
+    fn test_managed_httproute_names() {
+        use crate::crd::rollout::{
+            ABHeaderMatch, ABMatch, ABStrategy, GatewayAPIRouting, TrafficRouting,
+        };
+
+        let no_routing = create_test_rollout(RolloutStrategySpec {
+            simple: Some(SimpleStrategy { analysis: None }),
+            canary: None,
+            blue_green: None,
+            ab_testing: None,
+        });
+        assert!(managed_httproute_names(&no_routing).is_empty());
+
+        let ab_testing = create_test_rollout(RolloutStrategySpec {
+            simple: None,
+            canary: None,
+            blue_green: None,
+            ab_testing: Some(ABStrategy {
+                variant_a_service: "app-variant-a".to_string(),
+                variant_b_service: "app-variant-b".to_string(),
+                port: None,
+                variant_b_match: ABMatch {
+                    header: Some(ABHeaderMatch {
+                        name: "X-Variant".to_string(),
+                        value: "B".to_string(),
+                        match_type: None,
+                    }),
+                    cookie: None,
+                },
+                traffic_routing: Some(TrafficRouting {
+                    gateway_api: Some(GatewayAPIRouting {
+                        http_route: "app-public".to_string(),
+                        http_routes: vec!["app-public".to_string(), "app-internal".to_string()],
+                        weight_constraints: None,
+                        sections: Vec::new(),
+                        kind: Default::default(),
+                    }),
+                    none: None,
+                    nginx: None,
+                }),
+                max_duration: None,
+                analysis: None,
+            }),
+        });
+        assert_eq!(
+            managed_httproute_names(&ab_testing),
+            vec!["app-public".to_string(), "app-internal".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_feature_support_rejects_ignored_analysis() {
+        let analysis = crate::crd::rollout::AnalysisConfig {
+            prometheus: None,
+            failure_policy: None,
+            warmup_duration: None,
+            metrics: vec![],
+            per_zone: None,
+            revision_label_key: None,
+            aggregation: None,
+            pass_score: None,
+            on_resume: None,
+        };
+        let rollout = create_test_rollout(RolloutStrategySpec {
+            simple: Some(SimpleStrategy {
+                analysis: Some(analysis),
+            }),
+            canary: None,
+            blue_green: None,
+            ab_testing: None,
+        });
+
+        let strategy = select_strategy(&rollout);
+        let err = check_feature_support(&rollout, strategy.as_ref()).unwrap_err();
+        assert!(err.contains("spec.strategy.simple.analysis"), "{}", err);
+
+        let rollout = create_test_rollout(RolloutStrategySpec {
+            simple: Some(SimpleStrategy { analysis: None }),
+            canary: None,
+            blue_green: None,
+            ab_testing: None,
+        });
+        assert!(check_feature_support(&rollout, strategy.as_ref()).is_ok());
+    }
+
+    #[test]
+    fn test_feature_support_rejects_promote_without_manual_promotion() {
+        let mut rollout = create_test_rollout(RolloutStrategySpec {
+            simple: Some(SimpleStrategy { analysis: None }),
+            canary: None,
+            blue_green: None,
+            ab_testing: None,
+        });
+        rollout.metadata.annotations = Some(
+            [("kulta.io/promote".to_string(), "true".to_string())]
+                .into_iter()
+                .collect(),
+        );
+
+        let strategy = select_strategy(&rollout);
+        let err = check_feature_support(&rollout, strategy.as_ref()).unwrap_err();
+        assert!(err.contains("kulta.io/promote"), "{}", err);
+    }
+
+    #[test]
+    fn test_feature_support_rejects_abort_with_promote() {
+        // Empty strategy defaults to canary
+        let mut rollout = create_test_rollout(RolloutStrategySpec {
+            simple: None,
+            canary: None,
+            blue_green: None,
+            ab_testing: None,
+        });
+        rollout.metadata.annotations = Some(
+            [("kulta.io/abort".to_string(), "true".to_string())]
+                .into_iter()
+                .collect(),
+        );
+        let strategy = select_strategy(&rollout);
+        assert!(check_feature_support(&rollout, strategy.as_ref()).is_ok());
+
+        if let Some(annotations) = rollout.metadata.annotations.as_mut() {
+            annotations.insert("kulta.io/promote".to_string(), "true".to_string());
+        }
+        let err = check_feature_support(&rollout, strategy.as_ref()).unwrap_err();
+        assert!(err.contains("conflict"), "{}", err);
+    }
 }
@@ -5,7 +5,7 @@
 
 use super::{RolloutStrategy, StrategyError};
 use crate::controller::rollout::{build_replicaset_for_simple, ensure_replicaset_exists, Context};
-use crate::crd::rollout::{Phase, Rollout, RolloutStatus};
+use crate::crd::rollout::{ManagedReplicaSet, Phase, ReplicaSetRole, Rollout, RolloutStatus};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use k8s_openapi::api::apps::v1::ReplicaSet;
@@ -32,7 +32,7 @@ impl RolloutStrategy for SimpleStrategyHandler {
         &self,
         rollout: &Rollout,
         ctx: &Context,
-    ) -> Result<(), StrategyError> {
+    ) -> Result<Vec<ManagedReplicaSet>, StrategyError> {
         let namespace = rollout
             .namespace()
             .ok_or_else(|| StrategyError::MissingField("namespace".to_string()))?;
@@ -53,9 +53,10 @@ impl RolloutStrategy for SimpleStrategyHandler {
         let rs_api: Api<ReplicaSet> = Api::namespaced(ctx.client.clone(), &namespace);
 
         // Ensure ReplicaSet exists (idempotent)
-        ensure_replicaset_exists(&rs_api, &rs, "simple", rollout.spec.replicas)
-            .await
-            .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
+        let simple_managed =
+            ensure_replicaset_exists(&rs_api, &rs, ReplicaSetRole::Simple, rollout.spec.replicas)
+                .await
+                .map_err(|e| StrategyError::ReplicaSetReconciliationFailed(e.to_string()))?;
 
         info!(
             rollout = ?name,
@@ -63,7 +64,7 @@ impl RolloutStrategy for SimpleStrategyHandler {
             "Simple strategy ReplicaSets reconciled successfully"
         );
 
-        Ok(())
+        Ok(vec![simple_managed])
     }
 
     async fn reconcile_traffic(
@@ -86,6 +87,7 @@ impl RolloutStrategy for SimpleStrategyHandler {
                 "Simple rollout completed: {} replicas updated",
                 rollout.spec.replicas
             )),
+            summary: None,
             replicas: rollout.spec.replicas,
             ready_replicas: 0,
             updated_replicas: 0,
@@ -95,6 +97,27 @@ impl RolloutStrategy for SimpleStrategyHandler {
             decisions: vec![],
             ab_experiment: None,
             last_decision_source: None,
+            pod_template_hash: None,
+            revision: None,
+            template_diff: None,
+            http_routes: vec![],
+            degraded_since: None,
+            open_incident: None,
+            active_freeze: None,
+            aborted_at: None,
+            resources: None,
+            strategy: None,
+            paused_until: None,
+            promoted_at: None,
+            promoted_version: None,
+            step_analysis: None,
+            step_experiment: None,
+            rollback_verification: None,
+            metric_failures: None,
+            conditions: vec![],
+            observed_generation: None,
+            selector: None,
+            effective_config: None,
         }
     }
 
@@ -102,7 +125,7 @@ impl RolloutStrategy for SimpleStrategyHandler {
         // Simple strategy doesn't support metrics analysis because:
         // 1. It always completes immediately (no Progressing phase)
         // 2. Metrics are only evaluated during Progressing phase
-        // Note: Even if analysis config exists in spec, it won't be used
+        // An analysis config in the spec is rejected by check_feature_support
         false
     }
 
@@ -127,6 +150,9 @@ mod tests {
             Some(AnalysisConfig {
                 prometheus: Some(PrometheusConfig {
                     address: Some("http://prometheus:9090".to_string()),
+                    bearer_token_secret_ref: None,
+                    basic_auth: None,
+                    tls: None,
                 }),
                 failure_policy: None,
                 warmup_duration: None,
@@ -135,8 +161,22 @@ mod tests {
                     threshold: 5.0,
                     interval: None,
                     failure_threshold: None,
+                    query: None,
                     min_sample_size: None,
+                    baseline_offset: None,
+                    compare_to_stable: None,
+                    compare_to_baseline: None,
+                    volume_weighting: None,
+                    comparison: None,
+                    unit: None,
+                    weight: None,
+                    web: None,
                 }],
+                per_zone: None,
+                revision_label_key: None,
+                aggregation: None,
+                pass_score: None,
+                on_resume: None,
             })
         } else {
             None
@@ -163,6 +203,11 @@ mod tests {
                 max_unavailable: None,
                 progress_deadline_seconds: None,
                 advisor: Default::default(),
+                on_degraded: None,
+                on_template_change: None,
+                revision_history_limit: None,
+                cleanup_policy: None,
+                workload_ref: None,
             },
             status: None,
         }
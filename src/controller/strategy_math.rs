@@ -0,0 +1,603 @@
+//! Step, weight and phase math for canary rollouts
+//!
+//! Pure functions over the Rollout spec and status, shared by the status
+//! state machine, traffic routing, the reconcile overlays and the offline
+//! planner. Nothing here talks to the cluster or reads the clock.
+//!
+//! Step indexes follow `status.currentStepIndex`: `None` (or negative) means
+//! no step has started, an index past the last step means the rollout is
+//! complete.
+
+use crate::crd::rollout::{
+    CanaryStep, Phase, RolloutSpec, RolloutStatus, SetCanaryScale, SetHeaderRoute,
+    WeightConstraints,
+};
+
+/// The step a rollout moves to when it advances
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NextStep {
+    pub index: i32,
+    /// Canary traffic weight at that step (100 past the last step)
+    pub weight: i32,
+    /// Reaching this step completes the rollout
+    pub completes: bool,
+}
+
+/// Canary steps of a spec (empty for other strategies)
+pub fn canary_steps(spec: &RolloutSpec) -> &[CanaryStep] {
+    spec.strategy
+        .canary
+        .as_ref()
+        .map(|canary| canary.steps.as_slice())
+        .unwrap_or_default()
+}
+
+/// Gateway weight constraints of a canary spec, if configured
+pub fn weight_constraints(spec: &RolloutSpec) -> Option<&WeightConstraints> {
+    spec.strategy
+        .canary
+        .as_ref()?
+        .traffic_routing
+        .as_ref()?
+        .gateway_api
+        .as_ref()?
+        .weight_constraints
+        .as_ref()
+}
+
+/// Lowest and highest canary weight (besides 0 and 100) the constraints allow
+///
+/// # Returns
+/// * `Some((lowest, highest))` - Range of partial splits
+/// * `None` - Constraints leave no weight between 0 and 100
+pub fn partial_weight_range(constraints: &WeightConstraints) -> Option<(i32, i32)> {
+    let granularity = constraints.granularity.unwrap_or(1).max(1);
+    let min_weight = constraints.min_weight.unwrap_or(1).max(1);
+    // Round the minimum up to the granularity
+    let lowest = (min_weight + granularity - 1) / granularity * granularity;
+    let highest = 100 - lowest;
+    (lowest <= highest).then_some((lowest, highest))
+}
+
+/// Whether the gateway can honour a canary weight (and its stable complement)
+pub fn is_supported_weight(weight: i32, constraints: &WeightConstraints) -> bool {
+    if weight == 0 || weight == 100 {
+        return true;
+    }
+    let granularity = constraints.granularity.unwrap_or(1).max(1);
+    partial_weight_range(constraints).is_some_and(|(lowest, highest)| {
+        weight % granularity == 0 && (lowest..=highest).contains(&weight)
+    })
+}
+
+/// Nearest weight the gateway can honour
+///
+/// 0 and 100 are kept. Other weights are rounded to the granularity and then
+/// clamped so neither backend drops below `minWeight`; a partial split never
+/// becomes 0% or 100%.
+pub fn normalize_weight(weight: i32, constraints: &WeightConstraints) -> i32 {
+    if weight <= 0 || weight >= 100 {
+        return weight;
+    }
+    let granularity = constraints.granularity.unwrap_or(1).max(1);
+    let rounded = (weight + granularity / 2) / granularity * granularity;
+    match partial_weight_range(constraints) {
+        Some((lowest, highest)) => rounded.clamp(lowest, highest),
+        // No partial split possible (rejected by validation)
+        None => weight,
+    }
+}
+
+/// Canary weight of a step, normalized when the gateway constraints ask for it
+pub fn step_weight(spec: &RolloutSpec, step: &CanaryStep) -> i32 {
+    let weight = step.set_weight.unwrap_or(0);
+    match weight_constraints(spec) {
+        Some(constraints) if constraints.normalize == Some(true) => {
+            normalize_weight(weight, constraints)
+        }
+        _ => weight,
+    }
+}
+
+/// Step at `index`, if it exists
+pub fn step_at(spec: &RolloutSpec, index: i32) -> Option<&CanaryStep> {
+    canary_steps(spec).get(usize::try_from(index).ok()?)
+}
+
+/// Step the rollout is currently at, if any
+pub fn current_step<'a>(
+    spec: &'a RolloutSpec,
+    status: Option<&RolloutStatus>,
+) -> Option<&'a CanaryStep> {
+    step_at(spec, status?.current_step_index?)
+}
+
+/// Canary traffic weight for the current step
+///
+/// # Returns
+/// * `0` - Not a canary, no status yet, no step started, or aborted
+/// * `100` - Step index past the last step (rollout complete)
+/// * The `status.weightOverride` weight while it applies to the current step
+/// * Otherwise the step's `setWeight` (0 when unset, see [`step_weight`])
+pub fn current_weight(spec: &RolloutSpec, status: Option<&RolloutStatus>) -> i32 {
+    if spec.strategy.canary.is_none() {
+        return 0;
+    }
+    if status.is_some_and(|s| s.aborted_at.is_some()) {
+        return 0;
+    }
+    let Some(index) = status.and_then(|s| s.current_step_index) else {
+        return 0;
+    };
+    if index < 0 {
+        return 0;
+    }
+    if let Some(weight_override) = status
+        .and_then(|s| s.weight_override.as_ref())
+        .filter(|weight_override| weight_override.step_index == index)
+    {
+        return weight_override.weight;
+    }
+
+    match step_at(spec, index) {
+        Some(step) => step_weight(spec, step),
+        None => 100,
+    }
+}
+
+/// Percentage of requests mirrored to the canary at the current step
+///
+/// # Returns
+/// * `Some(percent)` - The current step has `setMirror`
+/// * `None` - No mirror step, or the rollout was aborted or has failed
+pub fn current_mirror_percent(spec: &RolloutSpec, status: Option<&RolloutStatus>) -> Option<i32> {
+    let status = status?;
+    if status.aborted_at.is_some() || status.phase == Some(Phase::Failed) {
+        return None;
+    }
+    current_step(spec, Some(status))?.set_mirror
+}
+
+/// Canary replicas needed to absorb mirrored traffic
+///
+/// Sized like a weighted split at the mirror percentage, at least one pod
+/// unless the rollout is scaled to zero.
+pub fn mirror_replicas(total_replicas: i32, mirror_percent: i32) -> i32 {
+    if total_replicas <= 0 {
+        return 0;
+    }
+    let percent = mirror_percent.clamp(0, 100);
+    ((total_replicas * percent + 99) / 100).max(1)
+}
+
+/// `setCanaryScale` in effect at the current step
+///
+/// The latest step up to the current one that sets it wins.
+///
+/// # Returns
+/// * `Some(scale)` - Canary replicas are decoupled from the weight
+/// * `None` - Canary replicas follow the weight (no scale set, `matchTrafficWeight`,
+///   no step started, rollout complete or aborted)
+pub fn current_canary_scale<'a>(
+    spec: &'a RolloutSpec,
+    status: Option<&RolloutStatus>,
+) -> Option<&'a SetCanaryScale> {
+    let status = status?;
+    if status.aborted_at.is_some() {
+        return None;
+    }
+    let index = usize::try_from(status.current_step_index?).ok()?;
+    let steps = canary_steps(spec);
+    if index >= steps.len() {
+        return None;
+    }
+    steps[..=index]
+        .iter()
+        .rev()
+        .find_map(|step| step.set_canary_scale.as_ref())
+        .filter(|scale| scale.match_traffic_weight != Some(true))
+}
+
+/// Canary replicas a `setCanaryScale` asks for
+///
+/// # Returns
+/// * `Some(replicas)` - `replicas` (capped at the total) or `weight` percent
+///   of the total, rounded up
+/// * `None` - `matchTrafficWeight`, the canary follows the weight
+pub fn scaled_canary_replicas(total_replicas: i32, scale: &SetCanaryScale) -> Option<i32> {
+    let total = total_replicas.max(0);
+    if let Some(replicas) = scale.replicas {
+        return Some(replicas.clamp(0, total));
+    }
+    let weight = scale.weight?.clamp(0, 100);
+    Some((total * weight + 99) / 100)
+}
+
+/// `setHeaderRoute` in effect at the current step
+///
+/// The latest step up to the current one that sets it wins.
+///
+/// # Returns
+/// * `Some(route)` - Matching requests go to the canary
+/// * `None` - No header route (none set, an empty `match`, no step started,
+///   rollout complete or aborted)
+pub fn current_header_route<'a>(
+    spec: &'a RolloutSpec,
+    status: Option<&RolloutStatus>,
+) -> Option<&'a SetHeaderRoute> {
+    let status = status?;
+    if status.aborted_at.is_some() {
+        return None;
+    }
+    let index = usize::try_from(status.current_step_index?).ok()?;
+    let steps = canary_steps(spec);
+    if index >= steps.len() {
+        return None;
+    }
+    steps[..=index]
+        .iter()
+        .rev()
+        .find_map(|step| step.set_header_route.as_ref())
+        .filter(|route| !route.matches.is_empty())
+}
+
+/// Whether reaching step `index` completes the rollout
+///
+/// True past the last step, or at a step that sends 100% to the canary.
+pub fn is_final_step(spec: &RolloutSpec, index: i32) -> bool {
+    match step_at(spec, index) {
+        Some(step) => step_weight(spec, step) == 100,
+        None => true,
+    }
+}
+
+/// The step after the current one
+///
+/// # Returns
+/// * `Some(next)` - Index, weight and whether it completes the rollout
+/// * `None` - Not a canary rollout
+pub fn next_step(spec: &RolloutSpec, status: Option<&RolloutStatus>) -> Option<NextStep> {
+    spec.strategy.canary.as_ref()?;
+
+    let index = status.and_then(|s| s.current_step_index).unwrap_or(-1) + 1;
+    let weight = step_at(spec, index).map_or(100, |step| step_weight(spec, step));
+    Some(NextStep {
+        index,
+        weight,
+        completes: is_final_step(spec, index),
+    })
+}
+
+/// Whether the desired status moves past the current step
+///
+/// Advancing means a different step index, or completing a rollout that was
+/// not completed yet.
+pub fn is_advancing(current: &RolloutStatus, desired: &RolloutStatus) -> bool {
+    desired.current_step_index != current.current_step_index
+        || (desired.phase == Some(Phase::Completed) && current.phase != Some(Phase::Completed))
+}
+
+/// Whether the desired status sends more traffic to the canary than the current one
+pub fn increases_weight(current: &RolloutStatus, desired: &RolloutStatus) -> bool {
+    desired.current_weight.unwrap_or(0) > current.current_weight.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::rollout::{
+        ABHeaderMatch, CanaryStrategy, GatewayAPIRouting, Pause, RolloutStrategy, TrafficRouting,
+        WeightOverride,
+    };
+
+    fn canary_spec(weights: &[i32]) -> RolloutSpec {
+        let steps = weights
+            .iter()
+            .map(|&weight| CanaryStep {
+                set_weight: Some(weight),
+                pause: Some(Pause::Indefinite),
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: None,
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            })
+            .collect();
+        RolloutSpec {
+            replicas: 3,
+            selector: Default::default(),
+            template: Default::default(),
+            strategy: RolloutStrategy {
+                simple: None,
+                canary: Some(CanaryStrategy {
+                    canary_service: "app-canary".to_string(),
+                    stable_service: "app-stable".to_string(),
+                    port: None,
+                    steps,
+                    traffic_routing: None,
+                    analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
+                }),
+                blue_green: None,
+                ab_testing: None,
+            },
+            max_surge: None,
+            max_unavailable: None,
+            progress_deadline_seconds: None,
+            advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
+        }
+    }
+
+    fn at_step(index: Option<i32>) -> RolloutStatus {
+        RolloutStatus {
+            phase: Some(Phase::Progressing),
+            current_step_index: index,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_current_weight() {
+        let spec = canary_spec(&[10, 50]);
+
+        assert_eq!(current_weight(&spec, None), 0);
+        assert_eq!(current_weight(&spec, Some(&at_step(None))), 0);
+        assert_eq!(current_weight(&spec, Some(&at_step(Some(-1)))), 0);
+        assert_eq!(current_weight(&spec, Some(&at_step(Some(1)))), 50);
+        assert_eq!(current_weight(&spec, Some(&at_step(Some(2)))), 100);
+
+        // A manual override replaces the weight of its step only
+        let mut overridden = at_step(Some(1));
+        overridden.weight_override = Some(WeightOverride {
+            weight: 5,
+            step_index: 1,
+            set_at: "2026-03-02T09:00:00Z".to_string(),
+        });
+        assert_eq!(current_weight(&spec, Some(&overridden)), 5);
+        overridden.current_step_index = Some(2);
+        assert_eq!(current_weight(&spec, Some(&overridden)), 100);
+    }
+
+    #[test]
+    fn test_next_step() {
+        let spec = canary_spec(&[10, 50, 100]);
+
+        assert_eq!(
+            next_step(&spec, None),
+            Some(NextStep {
+                index: 0,
+                weight: 10,
+                completes: false
+            })
+        );
+        assert_eq!(
+            next_step(&spec, Some(&at_step(Some(1)))),
+            Some(NextStep {
+                index: 2,
+                weight: 100,
+                completes: true
+            })
+        );
+
+        let no_full_step = canary_spec(&[10, 50]);
+        assert_eq!(
+            next_step(&no_full_step, Some(&at_step(Some(1)))),
+            Some(NextStep {
+                index: 2,
+                weight: 100,
+                completes: true
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_final_step() {
+        let spec = canary_spec(&[10, 100]);
+
+        assert!(!is_final_step(&spec, 0));
+        assert!(is_final_step(&spec, 1));
+        assert!(is_final_step(&spec, 2));
+    }
+
+    #[test]
+    fn test_non_canary_spec() {
+        let mut spec = canary_spec(&[10]);
+        spec.strategy.canary = None;
+
+        assert!(canary_steps(&spec).is_empty());
+        assert_eq!(current_weight(&spec, Some(&at_step(Some(0)))), 0);
+        assert_eq!(next_step(&spec, None), None);
+    }
+
+    #[test]
+    fn test_weight_constraints() {
+        let constraints = WeightConstraints {
+            granularity: Some(5),
+            min_weight: Some(8),
+            normalize: None,
+        };
+
+        assert!(is_supported_weight(0, &constraints));
+        assert!(is_supported_weight(10, &constraints));
+        assert!(is_supported_weight(90, &constraints));
+        assert!(!is_supported_weight(5, &constraints), "below minWeight");
+        assert!(
+            !is_supported_weight(95, &constraints),
+            "stable below minWeight"
+        );
+        assert!(
+            !is_supported_weight(12, &constraints),
+            "not a multiple of 5"
+        );
+
+        assert_eq!(normalize_weight(12, &constraints), 10);
+        assert_eq!(normalize_weight(13, &constraints), 15);
+        assert_eq!(normalize_weight(1, &constraints), 10);
+        assert_eq!(normalize_weight(97, &constraints), 90);
+        assert_eq!(normalize_weight(100, &constraints), 100);
+    }
+
+    #[test]
+    fn test_step_weight_normalized_only_when_enabled() {
+        let mut spec = canary_spec(&[7, 100]);
+        spec.strategy.canary.as_mut().unwrap().traffic_routing = Some(TrafficRouting {
+            gateway_api: Some(GatewayAPIRouting {
+                http_route: "app".to_string(),
+                http_routes: vec![],
+                weight_constraints: Some(WeightConstraints {
+                    granularity: Some(5),
+                    min_weight: None,
+                    normalize: Some(false),
+                }),
+                sections: Vec::new(),
+                kind: Default::default(),
+            }),
+            none: None,
+            nginx: None,
+        });
+        assert_eq!(next_step(&spec, None).unwrap().weight, 7);
+
+        let routing = spec
+            .strategy
+            .canary
+            .as_mut()
+            .unwrap()
+            .traffic_routing
+            .as_mut();
+        let gateway_api = routing.unwrap().gateway_api.as_mut().unwrap();
+        gateway_api.weight_constraints.as_mut().unwrap().normalize = Some(true);
+        assert_eq!(next_step(&spec, None).unwrap().weight, 5);
+        assert_eq!(current_weight(&spec, Some(&at_step(Some(0)))), 5);
+    }
+
+    #[test]
+    fn test_current_mirror_percent() {
+        let mut spec = canary_spec(&[0, 50]);
+        let canary = spec.strategy.canary.as_mut().unwrap();
+        canary.steps[0].set_mirror = Some(25);
+
+        assert_eq!(current_mirror_percent(&spec, None), None);
+        assert_eq!(current_mirror_percent(&spec, Some(&at_step(None))), None);
+        assert_eq!(
+            current_mirror_percent(&spec, Some(&at_step(Some(0)))),
+            Some(25)
+        );
+        assert_eq!(current_mirror_percent(&spec, Some(&at_step(Some(1)))), None);
+        let aborted = RolloutStatus {
+            aborted_at: Some("2026-01-01T12:00:00Z".to_string()),
+            ..at_step(Some(0))
+        };
+        assert_eq!(current_mirror_percent(&spec, Some(&aborted)), None);
+    }
+
+    #[test]
+    fn test_mirror_replicas() {
+        assert_eq!(mirror_replicas(10, 25), 3);
+        assert_eq!(mirror_replicas(4, 100), 4);
+        assert_eq!(mirror_replicas(3, 1), 1);
+        assert_eq!(mirror_replicas(0, 50), 0);
+    }
+
+    #[test]
+    fn test_current_canary_scale_carries_forward() {
+        let mut spec = canary_spec(&[5, 20, 50]);
+        let canary = spec.strategy.canary.as_mut().unwrap();
+        canary.steps[0].set_canary_scale = Some(SetCanaryScale {
+            replicas: Some(2),
+            weight: None,
+            match_traffic_weight: None,
+        });
+        canary.steps[2].set_canary_scale = Some(SetCanaryScale {
+            replicas: None,
+            weight: None,
+            match_traffic_weight: Some(true),
+        });
+
+        assert_eq!(current_canary_scale(&spec, Some(&at_step(None))), None);
+        let scale = current_canary_scale(&spec, Some(&at_step(Some(0)))).unwrap();
+        assert_eq!(scale.replicas, Some(2));
+        // Step 1 doesn't set a scale, so step 0's still applies
+        assert!(current_canary_scale(&spec, Some(&at_step(Some(1)))).is_some());
+        assert_eq!(current_canary_scale(&spec, Some(&at_step(Some(2)))), None);
+        assert_eq!(current_canary_scale(&spec, Some(&at_step(Some(3)))), None);
+    }
+
+    #[test]
+    fn test_scaled_canary_replicas() {
+        let scale = |replicas, weight| SetCanaryScale {
+            replicas,
+            weight,
+            match_traffic_weight: None,
+        };
+        assert_eq!(scaled_canary_replicas(10, &scale(Some(4), None)), Some(4));
+        assert_eq!(scaled_canary_replicas(10, &scale(Some(20), None)), Some(10));
+        assert_eq!(scaled_canary_replicas(10, &scale(None, Some(25))), Some(3));
+        assert_eq!(scaled_canary_replicas(10, &scale(None, None)), None);
+    }
+
+    #[test]
+    fn test_current_header_route_carries_forward() {
+        let mut spec = canary_spec(&[0, 20, 50]);
+        let canary = spec.strategy.canary.as_mut().unwrap();
+        canary.steps[0].set_header_route = Some(SetHeaderRoute {
+            matches: vec![ABHeaderMatch {
+                name: "X-Canary".to_string(),
+                value: "internal".to_string(),
+                match_type: None,
+            }],
+        });
+        canary.steps[2].set_header_route = Some(SetHeaderRoute::default());
+
+        assert!(current_header_route(&spec, Some(&at_step(None))).is_none());
+        let route = current_header_route(&spec, Some(&at_step(Some(1)))).unwrap();
+        assert_eq!(route.matches[0].value, "internal");
+        assert!(current_header_route(&spec, Some(&at_step(Some(2)))).is_none());
+        let aborted = RolloutStatus {
+            aborted_at: Some("2026-01-01T12:00:00Z".to_string()),
+            ..at_step(Some(0))
+        };
+        assert!(current_header_route(&spec, Some(&aborted)).is_none());
+    }
+
+    #[test]
+    fn test_increases_weight() {
+        let at_10 = RolloutStatus {
+            current_weight: Some(10),
+            ..at_step(Some(0))
+        };
+        let at_50 = RolloutStatus {
+            current_weight: Some(50),
+            ..at_step(Some(1))
+        };
+
+        assert!(increases_weight(&at_step(None), &at_10));
+        assert!(increases_weight(&at_10, &at_50));
+        assert!(!increases_weight(&at_50, &at_10));
+        assert!(!increases_weight(&at_10, &at_10));
+    }
+
+    #[test]
+    fn test_is_advancing() {
+        let current = at_step(Some(0));
+
+        assert!(!is_advancing(&current, &current));
+        assert!(is_advancing(&current, &at_step(Some(1))));
+        assert!(is_advancing(
+            &current,
+            &RolloutStatus {
+                phase: Some(Phase::Completed),
+                ..current.clone()
+            }
+        ));
+    }
+}
@@ -0,0 +1,471 @@
+//! Support bundles for bug reports
+//!
+//! `kulta support-bundle <rollout> -n <namespace>` collects what is asked
+//! for first when a rollout misbehaves, as one JSON document: the Rollout,
+//! its ReplicaSets and routes, recent Events of those objects, the
+//! controller log lines mentioning the Rollout, and its analysis history
+//! (decisions, step analysis progress, consecutive metric failures).
+//!
+//! Only the Rollout itself is required. Anything else that can't be read
+//! (missing RBAC, no controller pods) is listed under `errors` and the
+//! bundle is produced anyway. Environment variable values in pod templates
+//! are redacted and `managedFields` dropped, so the bundle can be attached
+//! to a public issue.
+
+use crate::controller::orphans::{MANAGED_LABEL, ROLLOUT_ANNOTATION};
+use crate::controller::strategies::{
+    gateway_route_api_resource, get_gateway_api_routing, managed_httproute_names,
+};
+use crate::crd::rollout::{
+    ABExperimentStatus, Decision, MetricFailureCount, Rollout, StepAnalysisStatus,
+};
+use crate::server::build_info::{BuildInfo, BUILD_INFO};
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::api::{Api, ListParams, LogParams};
+use kube::core::DynamicObject;
+use kube::{Client, ResourceExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Most recent Events kept in a bundle
+pub const MAX_BUNDLE_EVENTS: usize = 100;
+
+/// Most recent controller log lines about the Rollout kept in a bundle
+pub const MAX_BUNDLE_LOG_LINES: usize = 500;
+
+/// Placeholder for redacted values
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Error)]
+pub enum SupportBundleError {
+    #[error("rollout {0} not found")]
+    NotFound(String),
+
+    #[error("failed to read rollout: {0}")]
+    Kube(#[from] kube::Error),
+
+    #[error("failed to serialize {0}: {1}")]
+    Serialize(&'static str, serde_json::Error),
+}
+
+/// Where the controller runs, for its logs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SupportBundleOptions {
+    /// Namespace of the controller Deployment
+    pub controller_namespace: String,
+    /// Label selector of the controller pods
+    pub controller_selector: String,
+    /// Log lines read per controller pod before filtering
+    pub log_tail_lines: i64,
+}
+
+impl Default for SupportBundleOptions {
+    fn default() -> Self {
+        Self {
+            controller_namespace: "kulta-system".to_string(),
+            controller_selector: "app=kulta-controller".to_string(),
+            log_tail_lines: 5000,
+        }
+    }
+}
+
+/// A Kubernetes Event about the Rollout or one of its objects
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEvent {
+    /// When the Event last occurred (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// `Kind/name` of the object the Event is about
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i32>,
+}
+
+/// Analysis state recorded in the Rollout's status
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisHistory {
+    pub decisions: Vec<Decision>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_decision_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_analysis: Option<StepAnalysisStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric_failures: Option<Vec<MetricFailureCount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ab_experiment: Option<ABExperimentStatus>,
+}
+
+/// Everything collected about one Rollout
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    /// When the bundle was collected (RFC3339)
+    pub generated_at: String,
+    /// Build of the `kulta` binary that collected it
+    pub collected_by: BuildInfo,
+    pub rollout: Value,
+    pub replica_sets: Vec<Value>,
+    /// HTTPRoutes (or TLSRoutes/UDPRoutes) the Rollout writes
+    pub routes: Vec<Value>,
+    /// Oldest first, at most [`MAX_BUNDLE_EVENTS`]
+    pub events: Vec<BundleEvent>,
+    /// Controller log lines mentioning the Rollout, prefixed with the pod name
+    pub controller_logs: Vec<String>,
+    pub analysis: AnalysisHistory,
+    /// What couldn't be collected, and why
+    pub errors: Vec<String>,
+}
+
+/// Replace `env[].value` of every container with a placeholder
+///
+/// `pod_spec` is a pod spec as JSON; `valueFrom` references are kept.
+pub fn redact_pod_spec(pod_spec: &mut Value) {
+    for containers in ["initContainers", "containers", "ephemeralContainers"] {
+        let Some(containers) = pod_spec[containers].as_array_mut() else {
+            continue;
+        };
+        for env in containers
+            .iter_mut()
+            .filter_map(|container| container["env"].as_array_mut())
+        {
+            for var in env.iter_mut().filter_map(Value::as_object_mut) {
+                if var.contains_key("value") {
+                    var.insert("value".to_string(), Value::from(REDACTED));
+                }
+            }
+        }
+    }
+}
+
+/// A Rollout or ReplicaSet as JSON, safe to share
+///
+/// `managedFields` and the last-applied annotation are dropped, and
+/// environment variable values in `spec.template` redacted.
+pub fn sanitize_object(mut object: Value) -> Value {
+    if let Some(metadata) = object["metadata"].as_object_mut() {
+        metadata.remove("managedFields");
+        if let Some(annotations) = metadata
+            .get_mut("annotations")
+            .and_then(Value::as_object_mut)
+        {
+            annotations.remove("kubectl.kubernetes.io/last-applied-configuration");
+        }
+    }
+    if let Some(pod_spec) = object
+        .get_mut("spec")
+        .and_then(|spec| spec.get_mut("template"))
+        .and_then(|template| template.get_mut("spec"))
+    {
+        redact_pod_spec(pod_spec);
+    }
+    object
+}
+
+/// Whether `token` occurs in `line` not followed by more of a name
+fn contains_name(line: &str, token: &str) -> bool {
+    line.match_indices(token).any(|(at, _)| {
+        !line[at + token.len()..]
+            .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    })
+}
+
+/// Whether a controller log line is about the Rollout
+///
+/// Matches the `rollout` field as the controller logs it (quoted or not)
+/// and `namespace/name` references.
+pub fn mentions_rollout(line: &str, namespace: &str, name: &str) -> bool {
+    line.contains(&format!("rollout=\"{}\"", name))
+        || contains_name(line, &format!("rollout={}", name))
+        || contains_name(line, &format!("{}/{}", namespace, name))
+}
+
+/// Log lines of one controller pod about the Rollout, prefixed with the pod name
+pub fn log_excerpt(pod: &str, logs: &str, namespace: &str, name: &str) -> Vec<String> {
+    logs.lines()
+        .filter(|line| mentions_rollout(line, namespace, name))
+        .map(|line| format!("[{}] {}", pod, line))
+        .collect()
+}
+
+fn event_time(event: &Event) -> Option<DateTime<Utc>> {
+    event
+        .event_time
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| event.last_timestamp.as_ref().map(|t| t.0))
+        .or_else(|| event.first_timestamp.as_ref().map(|t| t.0))
+        .or_else(|| event.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Events about any of `objects` (`Kind/name`), oldest first, at most `limit`
+pub fn recent_events(
+    events: &[Event],
+    objects: &HashSet<String>,
+    limit: usize,
+) -> Vec<BundleEvent> {
+    let mut matching: Vec<(Option<DateTime<Utc>>, BundleEvent)> = events
+        .iter()
+        .filter_map(|event| {
+            let involved = &event.involved_object;
+            let object = format!(
+                "{}/{}",
+                involved.kind.as_deref().unwrap_or_default(),
+                involved.name.as_deref().unwrap_or_default()
+            );
+            if !objects.contains(&object) {
+                return None;
+            }
+            let time = event_time(event);
+            Some((
+                time,
+                BundleEvent {
+                    time: time.map(|t| t.to_rfc3339()),
+                    type_: event.type_.clone(),
+                    reason: event.reason.clone(),
+                    object,
+                    message: event.message.clone(),
+                    count: event.count,
+                },
+            ))
+        })
+        .collect();
+    matching.sort_by_key(|(time, _)| *time);
+    let skip = matching.len().saturating_sub(limit);
+    matching.into_iter().skip(skip).map(|(_, e)| e).collect()
+}
+
+/// Analysis state from the Rollout's status
+pub fn analysis_history(rollout: &Rollout) -> AnalysisHistory {
+    let status = rollout.status.clone().unwrap_or_default();
+    AnalysisHistory {
+        decisions: status.decisions,
+        last_decision_source: status.last_decision_source,
+        step_analysis: status.step_analysis,
+        metric_failures: status.metric_failures,
+        ab_experiment: status.ab_experiment,
+    }
+}
+
+fn to_value<T: Serialize>(what: &'static str, object: &T) -> Result<Value, SupportBundleError> {
+    serde_json::to_value(object).map_err(|e| SupportBundleError::Serialize(what, e))
+}
+
+/// Controller log lines about the Rollout, across all controller pods
+async fn controller_logs(
+    client: &Client,
+    options: &SupportBundleOptions,
+    namespace: &str,
+    name: &str,
+) -> Result<Vec<String>, kube::Error> {
+    let pod_api: Api<Pod> = Api::namespaced(client.clone(), &options.controller_namespace);
+    let pods = pod_api
+        .list(&ListParams::default().labels(&options.controller_selector))
+        .await?;
+    let params = LogParams {
+        tail_lines: Some(options.log_tail_lines),
+        timestamps: true,
+        ..LogParams::default()
+    };
+    let mut lines = Vec::new();
+    for pod in pods.items {
+        let pod_name = pod.name_any();
+        let logs = pod_api.logs(&pod_name, &params).await?;
+        lines.extend(log_excerpt(&pod_name, &logs, namespace, name));
+    }
+    let skip = lines.len().saturating_sub(MAX_BUNDLE_LOG_LINES);
+    Ok(lines.split_off(skip))
+}
+
+/// Collect the support bundle of a Rollout
+///
+/// # Returns
+/// * `Ok(bundle)` - The bundle; parts that couldn't be read are in `errors`
+/// * `Err(_)` - The Rollout couldn't be read
+pub async fn collect_support_bundle(
+    client: &Client,
+    namespace: &str,
+    name: &str,
+    options: &SupportBundleOptions,
+) -> Result<SupportBundle, SupportBundleError> {
+    let rollout_api: Api<Rollout> = Api::namespaced(client.clone(), namespace);
+    let rollout = rollout_api
+        .get_opt(name)
+        .await?
+        .ok_or_else(|| SupportBundleError::NotFound(format!("{}/{}", namespace, name)))?;
+    let mut errors = Vec::new();
+    let mut objects = HashSet::from([format!("Rollout/{}", name)]);
+
+    let rs_api: Api<ReplicaSet> = Api::namespaced(client.clone(), namespace);
+    let mut replica_sets = Vec::new();
+    match rs_api
+        .list(&ListParams::default().labels(&format!("{}=true", MANAGED_LABEL)))
+        .await
+    {
+        Ok(list) => {
+            for rs in list.items.into_iter().filter(|rs| {
+                rs.annotations().get(ROLLOUT_ANNOTATION).map(String::as_str) == Some(name)
+            }) {
+                objects.insert(format!("ReplicaSet/{}", rs.name_any()));
+                replica_sets.push(sanitize_object(to_value("ReplicaSet", &rs)?));
+            }
+        }
+        Err(e) => errors.push(format!("replicaSets: {}", e)),
+    }
+
+    let kind = get_gateway_api_routing(&rollout)
+        .map(|routing| routing.kind)
+        .unwrap_or_default();
+    let ar = gateway_route_api_resource(kind);
+    let route_api: Api<DynamicObject> = Api::namespaced_with(client.clone(), namespace, &ar);
+    let mut routes = Vec::new();
+    for route_name in managed_httproute_names(&rollout) {
+        match route_api.get_opt(&route_name).await {
+            Ok(Some(route)) => {
+                objects.insert(format!("{}/{}", ar.kind, route_name));
+                routes.push(sanitize_object(to_value("route", &route)?));
+            }
+            Ok(None) => errors.push(format!("routes: {} {} not found", ar.kind, route_name)),
+            Err(e) => errors.push(format!("routes: {}: {}", route_name, e)),
+        }
+    }
+
+    let event_api: Api<Event> = Api::namespaced(client.clone(), namespace);
+    let events = match event_api.list(&ListParams::default()).await {
+        Ok(list) => recent_events(&list.items, &objects, MAX_BUNDLE_EVENTS),
+        Err(e) => {
+            errors.push(format!("events: {}", e));
+            Vec::new()
+        }
+    };
+
+    let controller_logs = match controller_logs(client, options, namespace, name).await {
+        Ok(lines) => lines,
+        Err(e) => {
+            errors.push(format!("controllerLogs: {}", e));
+            Vec::new()
+        }
+    };
+
+    Ok(SupportBundle {
+        generated_at: Utc::now().to_rfc3339(),
+        collected_by: BUILD_INFO,
+        analysis: analysis_history(&rollout),
+        rollout: sanitize_object(to_value("Rollout", &rollout)?),
+        replica_sets,
+        routes,
+        events,
+        controller_logs,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use k8s_openapi::api::core::v1::ObjectReference;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    #[test]
+    fn test_sanitize_object_redacts_env_values() {
+        let rollout = serde_json::json!({
+            "metadata": {
+                "name": "shop",
+                "managedFields": [{ "manager": "kubectl" }],
+                "annotations": {
+                    "kubectl.kubernetes.io/last-applied-configuration": "{}",
+                    "team": "payments"
+                }
+            },
+            "spec": { "template": { "spec": { "containers": [{
+                "name": "app",
+                "env": [
+                    { "name": "DB_PASSWORD", "value": "hunter2" },
+                    { "name": "TOKEN", "valueFrom": { "secretKeyRef": { "name": "t", "key": "k" } } }
+                ]
+            }] } } }
+        });
+
+        let sanitized = sanitize_object(rollout);
+        assert!(sanitized["metadata"].get("managedFields").is_none());
+        assert_eq!(
+            sanitized["metadata"]["annotations"],
+            serde_json::json!({ "team": "payments" })
+        );
+        let env = &sanitized["spec"]["template"]["spec"]["containers"][0]["env"];
+        assert_eq!(env[0]["value"], REDACTED);
+        assert_eq!(env[1]["valueFrom"]["secretKeyRef"]["name"], "t");
+        assert!(!sanitized.to_string().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_log_excerpt_matches_rollout_field() {
+        let logs = [
+            r#"2026-10-16T10:00:00Z  INFO kulta: Step advanced rollout="shop" step=1"#,
+            r#"2026-10-16T10:00:01Z  INFO kulta: Step advanced rollout="shop-admin" step=1"#,
+            r#"2026-10-16T10:00:02Z  WARN kulta: Gateway rejected route=prod/shop"#,
+            r#"2026-10-16T10:00:02Z  WARN kulta: Gateway rejected route=prod/shop-admin"#,
+            r#"2026-10-16T10:00:03Z  INFO kulta: Requeue rollout=shop"#,
+            r#"2026-10-16T10:00:04Z  INFO kulta: Requeue rollout=shop-admin"#,
+        ]
+        .join("\n");
+
+        let excerpt = log_excerpt("kulta-0", &logs, "prod", "shop");
+        assert_eq!(excerpt.len(), 3);
+        assert!(excerpt.iter().all(|line| line.starts_with("[kulta-0] ")));
+        assert!(!excerpt.iter().any(|line| line.contains("shop-admin")));
+    }
+
+    #[test]
+    fn test_recent_events_filters_and_keeps_latest() {
+        let event = |kind: &str, name: &str, secs: i64, reason: &str| Event {
+            involved_object: ObjectReference {
+                kind: Some(kind.to_string()),
+                name: Some(name.to_string()),
+                ..ObjectReference::default()
+            },
+            last_timestamp: Some(Time(
+                DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            )),
+            reason: Some(reason.to_string()),
+            ..Event::default()
+        };
+        let events = vec![
+            event("ReplicaSet", "shop-canary", 20, "SuccessfulCreate"),
+            event("Rollout", "shop", 10, "Progressing"),
+            event("Rollout", "other", 15, "Progressing"),
+            event("Rollout", "shop", 30, "Paused"),
+        ];
+        let objects = HashSet::from([
+            "Rollout/shop".to_string(),
+            "ReplicaSet/shop-canary".to_string(),
+        ]);
+
+        let reasons = |events: Vec<BundleEvent>| {
+            events
+                .into_iter()
+                .map(|e| e.reason.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            reasons(recent_events(&events, &objects, 10)),
+            vec!["Progressing", "SuccessfulCreate", "Paused"]
+        );
+        assert_eq!(
+            reasons(recent_events(&events, &objects, 2)),
+            vec!["SuccessfulCreate", "Paused"]
+        );
+    }
+}
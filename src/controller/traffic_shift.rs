@@ -0,0 +1,276 @@
+//! Latency of traffic shifts
+//!
+//! A weight patch only changes the HTTPRoute; traffic moves once the gateway
+//! controller has programmed the route, which it reports per parent Gateway
+//! in `status.parents[].conditions`. A slow or broken gateway controller
+//! means analysis starts measuring before the canary actually gets its
+//! share of traffic.
+//!
+//! After a patch that leaves the route at a generation the gateway hasn't
+//! accepted yet, the route is polled until every parent reports `Accepted`
+//! for that generation, or one rejects it. The time from the patch is
+//! exported as `kulta_traffic_shift_duration_seconds` with the outcome
+//! (`accepted`, `rejected`, or `timeout` after [`TRAFFIC_SHIFT_TIMEOUT`]).
+//! A newer patch of the same route supersedes the one being watched.
+
+use crate::server::SharedMetrics;
+use kube::api::Api;
+use kube::core::DynamicObject;
+use kube::ResourceExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// How often a patched route's status is read
+pub const TRAFFIC_SHIFT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the gateway has to accept a patched route
+pub const TRAFFIC_SHIFT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What the route's parent Gateways report for its current generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteAcceptance {
+    /// Not every parent has reported on the current generation yet
+    Pending,
+    /// Every parent accepted the current generation
+    Accepted,
+    /// A parent rejected the current generation (its reason)
+    Rejected(String),
+}
+
+/// Acceptance of a route's current generation
+///
+/// An `Accepted` condition without `observedGeneration` is taken as
+/// current, since there's nothing to compare.
+pub fn route_acceptance(route: &DynamicObject) -> RouteAcceptance {
+    let generation = route.metadata.generation.unwrap_or(0);
+    let parents = route.data["status"]["parents"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if parents.is_empty() {
+        return RouteAcceptance::Pending;
+    }
+
+    let mut all_accepted = true;
+    for parent in parents {
+        let accepted = parent["conditions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|condition| condition["type"] == "Accepted")
+            .filter(|condition| match condition["observedGeneration"].as_i64() {
+                Some(observed) => observed >= generation,
+                None => true,
+            });
+        match accepted.map(|condition| condition["status"].as_str()) {
+            Some(Some("True")) => {}
+            Some(Some("False")) => {
+                let reason = accepted
+                    .and_then(|condition| condition["reason"].as_str())
+                    .unwrap_or("Unknown");
+                return RouteAcceptance::Rejected(reason.to_string());
+            }
+            _ => all_accepted = false,
+        }
+    }
+    if all_accepted {
+        RouteAcceptance::Accepted
+    } else {
+        RouteAcceptance::Pending
+    }
+}
+
+/// Traffic shifts waiting for the gateway
+pub struct TrafficShifts {
+    metrics: Option<SharedMetrics>,
+    /// Latest generation watched, per route key (`namespace/name`)
+    watched: Mutex<HashMap<String, i64>>,
+}
+
+impl TrafficShifts {
+    pub fn new(metrics: Option<SharedMetrics>) -> Self {
+        Self {
+            metrics,
+            watched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Note a patch that left `key` at `generation`
+    ///
+    /// # Returns
+    /// Whether the shift should be watched: false when this or a newer
+    /// generation already was (a re-patch with nothing new to apply, which
+    /// must not restart the clock)
+    pub fn begin(&self, key: &str, generation: i64) -> bool {
+        let Ok(mut watched) = self.watched.lock() else {
+            return false;
+        };
+        if watched.get(key).is_some_and(|latest| *latest >= generation) {
+            return false;
+        }
+        watched.insert(key.to_string(), generation);
+        true
+    }
+
+    /// Whether `generation` is still the latest watched for `key`
+    pub fn is_current(&self, key: &str, generation: i64) -> bool {
+        self.watched
+            .lock()
+            .map(|watched| watched.get(key) == Some(&generation))
+            .unwrap_or(false)
+    }
+
+    /// Forget a deleted route
+    pub fn forget(&self, key: &str) {
+        if let Ok(mut watched) = self.watched.lock() {
+            watched.remove(key);
+        }
+    }
+
+    fn observe(&self, namespace: &str, outcome: &str, elapsed: Duration) {
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_traffic_shift(namespace, outcome, elapsed.as_secs_f64());
+        }
+    }
+
+    /// Watch a route that was just patched, until the gateway acts on it
+    ///
+    /// Nothing is watched when the patch response is already accepted (no
+    /// new generation) or the generation is watched already.
+    pub fn track(self: &Arc<Self>, api: &Api<DynamicObject>, patched: &DynamicObject) {
+        if route_acceptance(patched) != RouteAcceptance::Pending {
+            return;
+        }
+        let namespace = patched.namespace().unwrap_or_default();
+        let name = patched.name_any();
+        let key = format!("{}/{}", namespace, name);
+        let generation = patched.metadata.generation.unwrap_or(0);
+        if !self.begin(&key, generation) {
+            return;
+        }
+
+        let shifts = Arc::clone(self);
+        let api = api.clone();
+        let patched_at = Instant::now();
+        tokio::spawn(async move {
+            let outcome = loop {
+                if patched_at.elapsed() >= TRAFFIC_SHIFT_TIMEOUT {
+                    break "timeout";
+                }
+                tokio::time::sleep(TRAFFIC_SHIFT_POLL_INTERVAL).await;
+                if !shifts.is_current(&key, generation) {
+                    // Superseded by a newer patch
+                    return;
+                }
+                match api.get_opt(&name).await {
+                    Ok(Some(route)) if route.metadata.generation.unwrap_or(0) > generation => {
+                        // Changed by someone else; its acceptance says nothing about ours
+                        return;
+                    }
+                    Ok(Some(route)) => match route_acceptance(&route) {
+                        RouteAcceptance::Pending => {}
+                        RouteAcceptance::Accepted => break "accepted",
+                        RouteAcceptance::Rejected(reason) => {
+                            warn!(route = %key, generation, reason = %reason,
+                                "Gateway rejected the traffic shift");
+                            break "rejected";
+                        }
+                    },
+                    Ok(None) => {
+                        shifts.forget(&key);
+                        return;
+                    }
+                    Err(e) => {
+                        debug!(error = %e, route = %key, "Failed to read route status");
+                    }
+                }
+            };
+            let elapsed = patched_at.elapsed();
+            if outcome == "timeout" {
+                warn!(route = %key, generation, timeout_secs = TRAFFIC_SHIFT_TIMEOUT.as_secs(),
+                    "Gateway hasn't accepted the traffic shift");
+            } else {
+                debug!(route = %key, generation, outcome, elapsed_secs = elapsed.as_secs_f64(),
+                    "Traffic shift observed");
+            }
+            shifts.observe(&namespace, outcome, elapsed);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn route(generation: i64, parents: serde_json::Value) -> DynamicObject {
+        let mut route: DynamicObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "gateway.networking.k8s.io/v1",
+            "kind": "HTTPRoute",
+            "metadata": { "name": "shop", "namespace": "prod" },
+            "spec": {},
+            "status": { "parents": parents }
+        }))
+        .unwrap();
+        route.metadata.generation = Some(generation);
+        route
+    }
+
+    fn parent(status: &str, observed: i64) -> serde_json::Value {
+        serde_json::json!({
+            "parentRef": { "name": "gateway" },
+            "conditions": [
+                { "type": "ResolvedRefs", "status": "True", "observedGeneration": observed },
+                { "type": "Accepted", "status": status, "reason": "NotAllowedByListeners",
+                  "observedGeneration": observed }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_route_acceptance_needs_every_parent_at_generation() {
+        assert_eq!(
+            route_acceptance(&route(3, serde_json::json!([]))),
+            RouteAcceptance::Pending
+        );
+        // One parent still reports on the previous generation
+        let lagging = route(3, serde_json::json!([parent("True", 3), parent("True", 2)]));
+        assert_eq!(route_acceptance(&lagging), RouteAcceptance::Pending);
+
+        let accepted = route(3, serde_json::json!([parent("True", 3), parent("True", 3)]));
+        assert_eq!(route_acceptance(&accepted), RouteAcceptance::Accepted);
+
+        let rejected = route(
+            3,
+            serde_json::json!([parent("True", 3), parent("False", 3)]),
+        );
+        assert_eq!(
+            route_acceptance(&rejected),
+            RouteAcceptance::Rejected("NotAllowedByListeners".to_string())
+        );
+
+        // A stale rejection doesn't count against the new generation
+        let stale = route(4, serde_json::json!([parent("False", 3)]));
+        assert_eq!(route_acceptance(&stale), RouteAcceptance::Pending);
+    }
+
+    #[test]
+    fn test_each_generation_is_watched_once() {
+        let shifts = TrafficShifts::new(None);
+        assert!(shifts.begin("prod/shop", 3));
+        // Re-patch of the same weights
+        assert!(!shifts.begin("prod/shop", 3));
+        assert!(shifts.is_current("prod/shop", 3));
+
+        // A newer patch supersedes the watched one
+        assert!(shifts.begin("prod/shop", 4));
+        assert!(!shifts.is_current("prod/shop", 3));
+        assert!(!shifts.begin("prod/shop", 3));
+
+        // A recreated route starts over at generation 1
+        shifts.forget("prod/shop");
+        assert!(shifts.begin("prod/shop", 1));
+    }
+}
@@ -0,0 +1,331 @@
+//! Generic HTTP ("web") metric provider
+//!
+//! A metric with `web` set is read from an arbitrary JSON endpoint instead
+//! of Prometheus: the URL is requested (GET, or POST with `body`), the value
+//! selected with `jsonPath`, then compared to the threshold like any other
+//! metric. It covers systems without a first-class provider, e.g. an
+//! internal SLO service or a vendor API.
+//!
+//! Only a JSONPath subset is supported: child names (`.name` or
+//! `['name']`) and array indices (`[0]`, `[-1]` from the end), starting at
+//! `$`. Filters, wildcards and recursive descent would select several
+//! values, and a metric has exactly one.
+
+use crate::controller::prometheus::{PrometheusError, QUERY_VARIABLES};
+use crate::controller::rollout::parse_duration;
+use crate::crd::rollout::{WebMetric, WebMetricMethod};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::debug;
+
+/// Timeout of a web metric request without `timeout`
+pub const DEFAULT_WEB_METRIC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most characters of a failed response kept in the error
+const MAX_ERROR_BODY: usize = 256;
+
+/// One step of a JSONPath
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Member of an object
+    Child(String),
+    /// Element of an array; negative counts from the end
+    Index(i64),
+}
+
+/// Parse a JSONPath of the supported subset
+pub fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| format!("JSONPath must start with $: {}", path))?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let name = &after[..end];
+            if name.is_empty() || name == "*" {
+                return Err(format!("unsupported JSONPath segment in {}", path));
+            }
+            segments.push(PathSegment::Child(name.to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after
+                .find(']')
+                .ok_or_else(|| format!("unterminated [ in JSONPath {}", path))?;
+            let inner = after[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|i| i.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|i| i.strip_suffix('"')));
+            let segment =
+                match quoted {
+                    Some(name) => PathSegment::Child(name.to_string()),
+                    None => PathSegment::Index(inner.parse().map_err(|_| {
+                        format!("unsupported JSONPath index [{}] in {}", inner, path)
+                    })?),
+                };
+            segments.push(segment);
+            rest = &after[end + 1..];
+        } else {
+            return Err(format!("unexpected '{}' in JSONPath {}", rest, path));
+        }
+    }
+    Ok(segments)
+}
+
+/// The number at `path` in a JSON document
+///
+/// Strings holding a number (as some APIs return them) are parsed.
+pub fn extract_value(document: &Value, path: &str) -> Result<f64, PrometheusError> {
+    let segments = parse_json_path(path).map_err(PrometheusError::WebMetricError)?;
+    let mut current = document;
+    for segment in &segments {
+        let next = match segment {
+            PathSegment::Child(name) => current.get(name),
+            PathSegment::Index(index) => current.as_array().and_then(|items| {
+                let len = i64::try_from(items.len()).ok()?;
+                let at = if *index < 0 { len + index } else { *index };
+                items.get(usize::try_from(at).ok()?)
+            }),
+        };
+        current = next.ok_or(PrometheusError::NoData)?;
+    }
+    match current {
+        Value::Number(number) => number
+            .as_f64()
+            .ok_or_else(|| PrometheusError::InvalidValue(number.to_string())),
+        Value::String(text) => text
+            .trim()
+            .parse()
+            .map_err(|_| PrometheusError::InvalidValue(text.clone())),
+        Value::Null => Err(PrometheusError::NoData),
+        other => Err(PrometheusError::InvalidValue(other.to_string())),
+    }
+}
+
+/// Replace `{{rollout}}`, `{{namespace}}` and `{{revision}}` in a web
+/// metric's URL, body or header value
+pub fn render_template(
+    template: &str,
+    rollout_name: &str,
+    namespace: &str,
+    revision: &str,
+) -> Result<String, PrometheusError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            PrometheusError::WebMetricError(format!("Unterminated variable in {}", template))
+        })?;
+        rendered.push_str(match after[..end].trim() {
+            "rollout" => rollout_name,
+            "namespace" => namespace,
+            "revision" => revision,
+            other => {
+                return Err(PrometheusError::WebMetricError(format!(
+                    "Unknown variable {{{{{}}}}} (expected one of {})",
+                    other,
+                    QUERY_VARIABLES.join(", ")
+                )))
+            }
+        });
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// A web metric with its variables replaced
+pub fn render_web_metric(
+    web: &WebMetric,
+    rollout_name: &str,
+    namespace: &str,
+    revision: &str,
+) -> Result<WebMetric, PrometheusError> {
+    let render = |template: &str| render_template(template, rollout_name, namespace, revision);
+    let mut rendered = web.clone();
+    rendered.url = render(&web.url)?;
+    rendered.body = web.body.as_deref().map(render).transpose()?;
+    for header in &mut rendered.headers {
+        header.value = render(&header.value)?;
+    }
+    Ok(rendered)
+}
+
+/// Check a web metric: http(s) URL, body only with POST, supported
+/// JSONPath, known variables and a valid timeout
+pub fn validate_web_metric(web: &WebMetric) -> Result<(), String> {
+    let rendered =
+        render_web_metric(web, "rollout", "namespace", "revision").map_err(|e| e.to_string())?;
+    let parsed = reqwest::Url::parse(&rendered.url)
+        .map_err(|e| format!("url '{}' invalid: {}", web.url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("url '{}' must be http:// or https://", web.url));
+    }
+    if web.body.is_some() && web.method.unwrap_or_default() != WebMetricMethod::Post {
+        return Err("body needs method POST".to_string());
+    }
+    if let Some(header) = web
+        .headers
+        .iter()
+        .find(|h| reqwest::header::HeaderName::from_bytes(h.name.as_bytes()).is_err())
+    {
+        return Err(format!("header name '{}' invalid", header.name));
+    }
+    parse_json_path(&web.json_path).map_err(|e| format!("jsonPath invalid: {}", e))?;
+    if let Some(timeout) = &web.timeout {
+        if parse_duration(timeout).is_none() {
+            return Err(format!("timeout invalid: {}", timeout));
+        }
+    }
+    Ok(())
+}
+
+/// HTTP client shared by all web metrics
+pub fn web_metric_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Request a rendered web metric and extract its value
+pub async fn fetch_web_metric(
+    client: &reqwest::Client,
+    web: &WebMetric,
+) -> Result<f64, PrometheusError> {
+    let timeout = web
+        .timeout
+        .as_deref()
+        .and_then(parse_duration)
+        .unwrap_or(DEFAULT_WEB_METRIC_TIMEOUT);
+    let mut request = match web.method.unwrap_or_default() {
+        WebMetricMethod::Get => client.get(&web.url),
+        WebMetricMethod::Post => client.post(&web.url),
+    }
+    .timeout(timeout);
+    for header in &web.headers {
+        request = request.header(&header.name, &header.value);
+    }
+    if let Some(body) = &web.body {
+        request = request.body(body.clone());
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| PrometheusError::WebMetricError(format!("{}: {}", web.url, e)))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| PrometheusError::WebMetricError(format!("{}: {}", web.url, e)))?;
+    if !status.is_success() {
+        let excerpt: String = text.chars().take(MAX_ERROR_BODY).collect();
+        return Err(PrometheusError::WebMetricError(format!(
+            "{} returned {}: {}",
+            web.url, status, excerpt
+        )));
+    }
+    let document: Value = serde_json::from_str(&text)
+        .map_err(|e| PrometheusError::ParseError(format!("{}: {}", web.url, e)))?;
+    let value = extract_value(&document, &web.json_path)?;
+    debug!(url = %web.url, json_path = %web.json_path, value, "Web metric fetched");
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::rollout::WebMetricHeader;
+
+    fn web(url: &str, json_path: &str) -> WebMetric {
+        WebMetric {
+            url: url.to_string(),
+            method: None,
+            headers: Vec::new(),
+            body: None,
+            json_path: json_path.to_string(),
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_json_path() {
+        assert_eq!(
+            parse_json_path("$.data['error-rate'].samples[-1]").unwrap(),
+            vec![
+                PathSegment::Child("data".to_string()),
+                PathSegment::Child("error-rate".to_string()),
+                PathSegment::Child("samples".to_string()),
+                PathSegment::Index(-1),
+            ]
+        );
+        assert_eq!(parse_json_path("$").unwrap(), vec![]);
+        assert!(parse_json_path("data.value").is_err());
+        assert!(parse_json_path("$..value").is_err());
+        assert!(parse_json_path("$.items[*]").is_err());
+        assert!(parse_json_path("$.items[0").is_err());
+    }
+
+    #[test]
+    fn test_extract_value() {
+        let document = serde_json::json!({
+            "slo": { "burnRate": 0.4, "budget": "0.97" },
+            "results": [{ "value": 1 }, { "value": 3 }],
+            "empty": null,
+            "flag": true
+        });
+        assert_eq!(extract_value(&document, "$.slo.burnRate").unwrap(), 0.4);
+        assert_eq!(extract_value(&document, "$.slo.budget").unwrap(), 0.97);
+        assert_eq!(
+            extract_value(&document, "$.results[-1].value").unwrap(),
+            3.0
+        );
+        assert!(matches!(
+            extract_value(&document, "$.results[2].value"),
+            Err(PrometheusError::NoData)
+        ));
+        assert!(matches!(
+            extract_value(&document, "$.empty"),
+            Err(PrometheusError::NoData)
+        ));
+        assert!(matches!(
+            extract_value(&document, "$.flag"),
+            Err(PrometheusError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_and_validate_web_metric() {
+        let mut metric = web(
+            "https://slo.example.com/api/{{namespace}}/{{ rollout }}?rev={{revision}}",
+            "$.value",
+        );
+        metric.headers.push(WebMetricHeader {
+            name: "X-Service".to_string(),
+            value: "{{rollout}}".to_string(),
+        });
+        let rendered = render_web_metric(&metric, "shop", "prod", "canary").unwrap();
+        assert_eq!(
+            rendered.url,
+            "https://slo.example.com/api/prod/shop?rev=canary"
+        );
+        assert_eq!(rendered.headers[0].value, "shop");
+        assert!(validate_web_metric(&metric).is_ok());
+
+        let unknown = web("https://slo.example.com/{{service}}", "$.value");
+        assert!(validate_web_metric(&unknown).is_err());
+        assert!(validate_web_metric(&web("ftp://slo.example.com", "$.value")).is_err());
+        assert!(validate_web_metric(&web("https://slo.example.com", "value")).is_err());
+
+        let mut get_with_body = web("https://slo.example.com", "$.value");
+        get_with_body.body = Some("{}".to_string());
+        assert!(validate_web_metric(&get_with_body).is_err());
+        get_with_body.method = Some(WebMetricMethod::Post);
+        assert!(validate_web_metric(&get_with_body).is_ok());
+    }
+}
@@ -0,0 +1,257 @@
+//! Work queue depth and reconcile lag of the controllers
+//!
+//! kube-runtime doesn't expose its scheduler, so the backlog is derived from
+//! what the controller has reconciled: an object in the watch cache whose
+//! `resourceVersion` hasn't been picked up by a reconcile yet is waiting in
+//! the queue. Lag is measured from the object's last write (the latest
+//! `managedFields` time) to the start of the reconcile that picks it up, for
+//! writes made while the controller was running. Requeued reconciles of
+//! unchanged objects don't count.
+//!
+//! Together with the age of the oldest reconcile still running, this shows
+//! a controller falling behind before rollouts visibly stall.
+
+use crate::server::SharedMetrics;
+use chrono::{DateTime, Utc};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::runtime::reflector::Store;
+use kube::Resource;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// `controller` label of the Rollout controller
+pub const ROLLOUT_CONTROLLER: &str = "rollout";
+
+/// `controller` label of the ClusterRollout controller
+pub const CLUSTER_ROLLOUT_CONTROLLER: &str = "cluster_rollout";
+
+/// How often the queue gauges are refreshed
+pub const QUEUE_METRICS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reconcile bookkeeping of one controller
+#[derive(Debug)]
+pub struct WorkQueue {
+    /// `controller` label of the metrics
+    controller: &'static str,
+    /// Writes before this were made while the controller wasn't running
+    started_at: DateTime<Utc>,
+    /// `resourceVersion` last picked up by a reconcile, per object key
+    reconciled: Mutex<HashMap<String, String>>,
+    /// Start of every reconcile in progress
+    in_flight: Mutex<HashMap<u64, Instant>>,
+    next_id: AtomicU64,
+}
+
+/// A reconcile in progress; dropping it marks the reconcile as finished
+#[derive(Debug)]
+pub struct InFlightReconcile {
+    queue: Arc<WorkQueue>,
+    id: u64,
+}
+
+impl Drop for InFlightReconcile {
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.queue.in_flight.lock() {
+            in_flight.remove(&self.id);
+        }
+    }
+}
+
+impl WorkQueue {
+    pub fn new(controller: &'static str, started_at: DateTime<Utc>) -> Self {
+        Self {
+            controller,
+            started_at,
+            reconciled: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// `controller` label of the metrics
+    pub fn controller(&self) -> &'static str {
+        self.controller
+    }
+
+    /// Note the start of a reconcile of `key` at `resource_version`
+    ///
+    /// # Returns
+    /// The in-flight marker, and the lag since `last_write` when the object
+    /// changed since its last reconcile (None for a requeue of an unchanged
+    /// object, or a write from before the controller started)
+    pub fn begin(
+        self: &Arc<Self>,
+        key: String,
+        resource_version: Option<&str>,
+        last_write: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> (InFlightReconcile, Option<Duration>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.insert(id, Instant::now());
+        }
+
+        let changed = match (self.reconciled.lock(), resource_version) {
+            (Ok(mut reconciled), Some(version)) => {
+                reconciled.insert(key, version.to_string()).as_deref() != Some(version)
+            }
+            _ => false,
+        };
+        let lag = last_write
+            .filter(|written| changed && *written >= self.started_at)
+            .and_then(|written| now.signed_duration_since(written).to_std().ok());
+
+        (
+            InFlightReconcile {
+                queue: Arc::clone(self),
+                id,
+            },
+            lag,
+        )
+    }
+
+    /// Number of objects changed since their last reconcile
+    ///
+    /// `objects` are the `(key, resourceVersion)` of every object in the
+    /// watch cache; keys no longer in it are forgotten.
+    pub fn depth(&self, objects: impl IntoIterator<Item = (String, Option<String>)>) -> usize {
+        let Ok(mut reconciled) = self.reconciled.lock() else {
+            return 0;
+        };
+        let mut live = HashSet::new();
+        let mut depth = 0;
+        for (key, version) in objects {
+            if reconciled.get(&key) != version.as_ref() {
+                depth += 1;
+            }
+            live.insert(key);
+        }
+        reconciled.retain(|key, _| live.contains(key));
+        depth
+    }
+
+    /// How long the oldest reconcile still running has been going
+    pub fn longest_running(&self) -> Option<Duration> {
+        let in_flight = self.in_flight.lock().ok()?;
+        in_flight.values().min().map(Instant::elapsed)
+    }
+}
+
+/// Key of an object in a [`WorkQueue`] (`namespace/name`, or `name`)
+pub fn object_key(meta: &ObjectMeta) -> String {
+    let name = meta.name.clone().unwrap_or_default();
+    match &meta.namespace {
+        Some(namespace) => format!("{}/{}", namespace, name),
+        None => name,
+    }
+}
+
+/// When the object was last written: its latest `managedFields` time, or
+/// its creation
+pub fn last_write_time(meta: &ObjectMeta) -> Option<DateTime<Utc>> {
+    meta.managed_fields
+        .iter()
+        .flatten()
+        .filter_map(|entry| entry.time.as_ref().map(|t| t.0))
+        .max()
+        .or_else(|| meta.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+/// Start a reconcile of `meta`, observing its lag
+///
+/// The reconcile counts as running until the returned marker is dropped.
+pub fn begin_reconcile(
+    queue: &Arc<WorkQueue>,
+    meta: &ObjectMeta,
+    now: DateTime<Utc>,
+    metrics: Option<&SharedMetrics>,
+) -> InFlightReconcile {
+    let (in_flight, lag) = queue.begin(
+        object_key(meta),
+        meta.resource_version.as_deref(),
+        last_write_time(meta),
+        now,
+    );
+    if let (Some(lag), Some(metrics)) = (lag, metrics) {
+        metrics.observe_reconcile_lag(queue.controller(), lag.as_secs_f64());
+    }
+    in_flight
+}
+
+/// Refresh the queue gauges of a controller from its watch cache, forever
+pub async fn report_queue_metrics<K>(queue: Arc<WorkQueue>, store: Store<K>, metrics: SharedMetrics)
+where
+    K: Resource + Clone + 'static,
+    K::DynamicType: Eq + Hash + Clone,
+{
+    let mut interval = tokio::time::interval(QUEUE_METRICS_INTERVAL);
+    loop {
+        interval.tick().await;
+        let depth = queue.depth(store.state().iter().map(|object| {
+            (
+                object_key(object.meta()),
+                object.meta().resource_version.clone(),
+            )
+        }));
+        let longest_running = queue.longest_running().unwrap_or_default();
+        metrics.set_work_queue(
+            queue.controller(),
+            i64::try_from(depth).unwrap_or(i64::MAX),
+            longest_running.as_secs_f64(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_lag_is_observed_for_changes_only() {
+        let queue = Arc::new(WorkQueue::new("rollout", at(0)));
+        let key = || "prod/shop".to_string();
+
+        // Written before the controller started: no lag sample
+        let (_, lag) = queue.begin(key(), Some("1"), Some(at(-60)), at(5));
+        assert_eq!(lag, None);
+
+        let (_, lag) = queue.begin(key(), Some("2"), Some(at(10)), at(13));
+        assert_eq!(lag, Some(Duration::from_secs(3)));
+
+        // Requeue of the unchanged object
+        let (_, lag) = queue.begin(key(), Some("2"), Some(at(10)), at(40));
+        assert_eq!(lag, None);
+    }
+
+    #[test]
+    fn test_depth_counts_unreconciled_changes() {
+        let queue = Arc::new(WorkQueue::new("rollout", at(0)));
+        let in_flight = queue.begin("prod/a".to_string(), Some("1"), None, at(0)).0;
+        assert!(queue.longest_running().is_some());
+        drop(in_flight);
+        assert_eq!(queue.longest_running(), None);
+
+        let cache = |a: &str| {
+            vec![
+                ("prod/a".to_string(), Some(a.to_string())),
+                ("prod/b".to_string(), Some("7".to_string())),
+            ]
+        };
+        // b was never reconciled
+        assert_eq!(queue.depth(cache("1")), 1);
+        // a changed again
+        assert_eq!(queue.depth(cache("2")), 2);
+
+        // Deleted objects are forgotten
+        assert_eq!(queue.depth(Vec::new()), 0);
+        assert_eq!(queue.depth(cache("1")), 2);
+    }
+}
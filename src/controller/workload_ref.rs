@@ -0,0 +1,290 @@
+//! Existing Deployments adopted through `spec.workloadRef`
+//!
+//! Teams migrating to KULTA can point a Rollout at the Deployment they
+//! already run instead of copying its pod template into `spec.template`.
+//! The controller reads the Deployment's template on every reconcile (and
+//! watches Deployments, so editing the Deployment's image starts a new
+//! rollout), then scales the Deployment to zero once the Rollout first
+//! reaches Completed and its own pods serve the traffic. With
+//! `scaleDown: Never` the Deployment is left running, e.g. while both are
+//! observed side by side.
+
+use crate::controller::audit::{self, AuditObjectRef, AuditOperation};
+use crate::crd::rollout::{Phase, Rollout, WorkloadRef, WorkloadScaleDown};
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::{Api, Patch, PatchParams};
+use kube::runtime::reflector::ObjectRef;
+use kube::{Client, ResourceExt};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+
+#[derive(Debug, Error, PartialEq)]
+pub enum WorkloadRefError {
+    #[error("workload Deployment {namespace}/{name} not found")]
+    NotFound { namespace: String, name: String },
+
+    #[error("workload Deployment {namespace}/{name} has no pod template")]
+    MissingTemplate { namespace: String, name: String },
+
+    #[error("failed to read workload Deployment {namespace}/{name}: {reason}")]
+    Read {
+        namespace: String,
+        name: String,
+        reason: String,
+    },
+}
+
+/// Pod template and replica count read from an adopted Deployment
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workload {
+    pub template: PodTemplateSpec,
+    /// `spec.replicas` of the Deployment (Kubernetes defaults it to 1)
+    pub replicas: i32,
+}
+
+impl Workload {
+    /// Pod template and replicas of a Deployment
+    pub fn from_deployment(
+        deployment: &Deployment,
+        namespace: &str,
+    ) -> Result<Self, WorkloadRefError> {
+        let spec = deployment
+            .spec
+            .as_ref()
+            .filter(|spec| spec.template.spec.is_some())
+            .ok_or_else(|| WorkloadRefError::MissingTemplate {
+                namespace: namespace.to_string(),
+                name: deployment.name_any(),
+            })?;
+        Ok(Workload {
+            template: spec.template.clone(),
+            replicas: spec.replicas.unwrap_or(1),
+        })
+    }
+}
+
+/// Read the Deployment a Rollout references, if it references one
+///
+/// # Returns
+/// * `Ok(Some(workload))` - The Deployment's pod template and replicas
+/// * `Ok(None)` - The Rollout has an inline template
+/// * `Err(WorkloadRefError)` - Deployment missing, unreadable or without a template
+pub async fn fetch_workload(
+    rollout: &Rollout,
+    client: &Client,
+) -> Result<Option<Workload>, WorkloadRefError> {
+    let (Some(workload_ref), Some(namespace)) = (&rollout.spec.workload_ref, rollout.namespace())
+    else {
+        return Ok(None);
+    };
+
+    let api: Api<Deployment> = Api::namespaced(client.clone(), &namespace);
+    match api.get_opt(&workload_ref.name).await {
+        Ok(Some(deployment)) => Workload::from_deployment(&deployment, &namespace).map(Some),
+        Ok(None) => Err(WorkloadRefError::NotFound {
+            namespace,
+            name: workload_ref.name.clone(),
+        }),
+        Err(e) => {
+            warn!(error = ?e, deployment = %workload_ref.name, namespace = %namespace, "Failed to read workload Deployment");
+            Err(WorkloadRefError::Read {
+                namespace,
+                name: workload_ref.name.clone(),
+                reason: e.to_string(),
+            })
+        }
+    }
+}
+
+/// Copy of a Rollout with `template` replaced by the workload's pod template
+///
+/// `workloadRef` is kept so the workload can be scaled down later.
+pub fn with_workload_template(rollout: &Rollout, template: PodTemplateSpec) -> Rollout {
+    let mut resolved = rollout.clone();
+    resolved.spec.template = template;
+    resolved
+}
+
+/// Whether the adopted workload should be scaled to zero now
+///
+/// Only `scaleDown: OnSuccess` workloads that still run pods are scaled
+/// down, and only once the Rollout is Completed.
+pub fn should_scale_down(
+    workload_ref: &WorkloadRef,
+    workload_replicas: i32,
+    phase: Option<&Phase>,
+) -> bool {
+    workload_ref.scale_down == WorkloadScaleDown::OnSuccess
+        && workload_replicas > 0
+        && phase == Some(&Phase::Completed)
+}
+
+/// Scale the adopted Deployment to zero replicas
+pub async fn scale_down_workload(
+    client: &Client,
+    namespace: &str,
+    workload_ref: &WorkloadRef,
+) -> Result<(), kube::Error> {
+    let api: Api<Deployment> = Api::namespaced(client.clone(), namespace);
+    let patch = serde_json::json!({ "spec": { "replicas": 0 } });
+    api.patch(
+        &workload_ref.name,
+        &PatchParams::default(),
+        &Patch::Merge(&patch),
+    )
+    .await?;
+    audit::record(
+        AuditOperation::Patch,
+        AuditObjectRef::new("Deployment", namespace, &workload_ref.name),
+        "spec.replicas=0",
+        "adopted by Rollout",
+    )
+    .await;
+    info!(deployment = %workload_ref.name, namespace = %namespace, "Scaled down adopted Deployment");
+    Ok(())
+}
+
+/// Rollouts adopting a Deployment
+///
+/// Used to re-reconcile them when the Deployment changes.
+pub fn rollouts_referencing_workload(
+    rollouts: &[Arc<Rollout>],
+    namespace: &str,
+    name: &str,
+) -> Vec<ObjectRef<Rollout>> {
+    rollouts
+        .iter()
+        .filter(|rollout| {
+            rollout.namespace().as_deref() == Some(namespace)
+                && rollout
+                    .spec
+                    .workload_ref
+                    .as_ref()
+                    .is_some_and(|workload_ref| workload_ref.name == name)
+        })
+        .map(|rollout| ObjectRef::from_obj(rollout.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::controller::rollout::validate_rollout;
+    use crate::crd::builder::RolloutBuilder;
+    use crate::crd::rollout::SimpleStrategy;
+
+    fn rollout(namespace: &str, workload: Option<&str>) -> Rollout {
+        let builder = RolloutBuilder::new("checkout")
+            .namespace(namespace)
+            .replicas(3)
+            .label("app", "checkout")
+            .simple(SimpleStrategy { analysis: None });
+        let builder = match workload {
+            Some(name) => builder.workload_ref(WorkloadRef {
+                kind: Default::default(),
+                name: name.to_string(),
+                scale_down: WorkloadScaleDown::OnSuccess,
+            }),
+            None => builder.image("app", "checkout:2.1"),
+        };
+        builder.build().unwrap()
+    }
+
+    fn deployment() -> Deployment {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "checkout", "namespace": "shop" },
+            "spec": {
+                "replicas": 3,
+                "selector": { "matchLabels": { "app": "checkout" } },
+                "template": {
+                    "metadata": { "labels": { "app": "checkout" } },
+                    "spec": { "containers": [{ "name": "app", "image": "checkout:2.1" }] }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_workload_ref_deserializes_with_defaults() {
+        let workload_ref: WorkloadRef =
+            serde_json::from_value(serde_json::json!({ "name": "checkout" })).unwrap();
+        assert_eq!(workload_ref.kind, Default::default());
+        assert_eq!(workload_ref.scale_down, WorkloadScaleDown::OnSuccess);
+    }
+
+    #[test]
+    fn test_workload_template_replaces_inline_template() {
+        let workload = Workload::from_deployment(&deployment(), "shop").unwrap();
+        assert_eq!(workload.replicas, 3);
+
+        let resolved =
+            with_workload_template(&rollout("shop", Some("checkout")), workload.template);
+        let containers = &resolved.spec.template.spec.as_ref().unwrap().containers;
+        assert_eq!(containers[0].image.as_deref(), Some("checkout:2.1"));
+        assert!(resolved.spec.workload_ref.is_some());
+
+        let mut no_template = deployment();
+        no_template.spec.as_mut().unwrap().template.spec = None;
+        assert_eq!(
+            Workload::from_deployment(&no_template, "shop").unwrap_err(),
+            WorkloadRefError::MissingTemplate {
+                namespace: "shop".to_string(),
+                name: "checkout".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_validation_rejects_template_with_workload_ref() {
+        assert!(validate_rollout(&rollout("shop", Some("checkout"))).is_ok());
+        let mut unnamed = rollout("shop", Some("checkout"));
+        unnamed.spec.workload_ref.as_mut().unwrap().name.clear();
+        assert!(validate_rollout(&unnamed)
+            .unwrap_err()
+            .contains("workloadRef.name"));
+
+        let workload = Workload::from_deployment(&deployment(), "shop").unwrap();
+        let both = with_workload_template(&rollout("shop", Some("checkout")), workload.template);
+        assert!(validate_rollout(&both)
+            .unwrap_err()
+            .contains("mutually exclusive"));
+    }
+
+    #[test]
+    fn test_should_scale_down() {
+        let workload_ref = rollout("shop", Some("checkout")).spec.workload_ref.unwrap();
+        let completed = Some(&Phase::Completed);
+
+        assert!(should_scale_down(&workload_ref, 3, completed));
+        assert!(!should_scale_down(&workload_ref, 0, completed));
+        assert!(!should_scale_down(
+            &workload_ref,
+            3,
+            Some(&Phase::Progressing)
+        ));
+        let never = WorkloadRef {
+            scale_down: WorkloadScaleDown::Never,
+            ..workload_ref
+        };
+        assert!(!should_scale_down(&never, 3, completed));
+    }
+
+    #[test]
+    fn test_rollouts_referencing_workload() {
+        let rollouts = vec![
+            Arc::new(rollout("shop", Some("checkout"))),
+            Arc::new(rollout("payments", Some("checkout"))),
+            Arc::new(rollout("shop", None)),
+        ];
+        let refs = rollouts_referencing_workload(&rollouts, "shop", "checkout");
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].namespace.as_deref(), Some("shop"));
+        assert!(rollouts_referencing_workload(&rollouts, "shop", "cart").is_empty());
+    }
+}
@@ -0,0 +1,717 @@
+//! Conversion of Argo Rollouts manifests into KULTA Rollouts
+//!
+//! Teams moving from Argo Rollouts can convert their `argoproj.io/v1alpha1`
+//! Rollouts with [`convert_argo_rollout`] (or `kulta convert -f`) instead of
+//! rewriting them by hand. The pod template (or `workloadRef`), selector,
+//! replicas and the canary or blue-green strategy are mapped; everything
+//! KULTA has no equivalent for (AnalysisTemplates, experiments, mesh
+//! routers, anti-affinity, ...) is dropped and reported as a warning, so the
+//! result can be reviewed before it is applied.
+//!
+//! Mapping notes:
+//! - A `pause` step without `setWeight` is folded into the step before it
+//!   (KULTA pauses belong to a weight step); a leading pause holds at 0%.
+//!   A `setCanaryScale` step is carried by the next step, or by the folded
+//!   pause step it precedes.
+//! - A canary without `trafficRouting` becomes `trafficRouting.none` with
+//!   replica weighting, Argo's basic canary.
+//! - The Gateway API plugin (`argoproj-labs/gatewayAPI`) becomes
+//!   `trafficRouting.gatewayAPI` (`kind: TLSRoute` for `tlsRoutes`); NGINX
+//!   keeps its stable Ingress.
+
+use super::rollout::{
+    BlueGreenStrategy, CanaryStep, CanaryStrategy, GatewayAPIRouting, GatewayRouteKind,
+    NginxTrafficRouting, NoTrafficRouting, Pause, Rollout, RolloutSpec, RolloutStrategy,
+    SetCanaryScale, TrafficRouting, WorkloadKind, WorkloadRef, WorkloadScaleDown,
+};
+use crate::controller::rollout::validate_rollout;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::api::ObjectMeta;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// API version of the Rollouts this module converts
+pub const ARGO_API_VERSION: &str = "argoproj.io/v1alpha1";
+
+/// Traffic router plugin name of the Argo Rollouts Gateway API plugin
+const GATEWAY_API_PLUGIN: &str = "argoproj-labs/gatewayAPI";
+
+/// Annotation prefix owned by the Argo Rollouts controller
+const ARGO_ANNOTATION_PREFIX: &str = "rollout.argoproj.io/";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ArgoConversionError {
+    #[error(
+        "not an Argo Rollout (expected argoproj.io/v1alpha1 Rollout, got {api_version} {kind})"
+    )]
+    NotArgoRollout { api_version: String, kind: String },
+
+    #[error("invalid Argo Rollout: {0}")]
+    Parse(String),
+
+    #[error("Argo Rollout has no {0}, which KULTA requires")]
+    MissingField(&'static str),
+
+    #[error("Argo Rollout uses neither a canary nor a blueGreen strategy")]
+    UnsupportedStrategy,
+
+    #[error("converted Rollout is invalid: {0}")]
+    InvalidSpec(String),
+}
+
+/// A converted Rollout and what was lost in the conversion
+#[derive(Clone, Debug)]
+pub struct ArgoConversion {
+    pub rollout: Rollout,
+    /// One entry per Argo field that was dropped or approximated
+    pub warnings: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ArgoRollout {
+    #[serde(rename = "apiVersion", default)]
+    api_version: String,
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    metadata: ObjectMeta,
+    spec: ArgoRolloutSpec,
+}
+
+#[derive(Deserialize)]
+struct ArgoRolloutSpec {
+    replicas: Option<i32>,
+    selector: Option<LabelSelector>,
+    template: Option<PodTemplateSpec>,
+    #[serde(rename = "workloadRef")]
+    workload_ref: Option<ArgoWorkloadRef>,
+    #[serde(default)]
+    strategy: ArgoStrategy,
+    #[serde(rename = "revisionHistoryLimit")]
+    revision_history_limit: Option<i32>,
+    #[serde(rename = "progressDeadlineSeconds")]
+    progress_deadline_seconds: Option<i32>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ArgoWorkloadRef {
+    #[serde(default)]
+    kind: String,
+    name: String,
+    #[serde(rename = "scaleDown")]
+    scale_down: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct ArgoStrategy {
+    canary: Option<ArgoCanary>,
+    #[serde(rename = "blueGreen")]
+    blue_green: Option<ArgoBlueGreen>,
+}
+
+#[derive(Deserialize)]
+struct ArgoCanary {
+    #[serde(rename = "canaryService")]
+    canary_service: Option<String>,
+    #[serde(rename = "stableService")]
+    stable_service: Option<String>,
+    #[serde(default)]
+    steps: Vec<ArgoCanaryStep>,
+    #[serde(rename = "trafficRouting")]
+    traffic_routing: Option<ArgoTrafficRouting>,
+    #[serde(rename = "maxSurge")]
+    max_surge: Option<IntOrString>,
+    #[serde(rename = "maxUnavailable")]
+    max_unavailable: Option<IntOrString>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ArgoCanaryStep {
+    #[serde(rename = "setWeight")]
+    set_weight: Option<i32>,
+    pause: Option<ArgoPause>,
+    #[serde(rename = "setCanaryScale")]
+    set_canary_scale: Option<SetCanaryScale>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ArgoPause {
+    duration: Option<IntOrString>,
+}
+
+#[derive(Deserialize)]
+struct ArgoTrafficRouting {
+    nginx: Option<ArgoNginx>,
+    #[serde(default)]
+    plugins: BTreeMap<String, Value>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ArgoNginx {
+    #[serde(rename = "stableIngress")]
+    stable_ingress: Option<String>,
+    #[serde(rename = "annotationPrefix")]
+    annotation_prefix: Option<String>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct ArgoBlueGreen {
+    #[serde(rename = "activeService")]
+    active_service: Option<String>,
+    #[serde(rename = "previewService")]
+    preview_service: Option<String>,
+    #[serde(rename = "autoPromotionEnabled")]
+    auto_promotion_enabled: Option<bool>,
+    #[serde(rename = "autoPromotionSeconds")]
+    auto_promotion_seconds: Option<i32>,
+    #[serde(flatten)]
+    unsupported: BTreeMap<String, Value>,
+}
+
+/// Convert an `argoproj.io/v1alpha1` Rollout into a `kulta.io` Rollout
+///
+/// The result passes [`validate_rollout`]; fields without a KULTA
+/// equivalent are listed in [`ArgoConversion::warnings`].
+pub fn convert_argo_rollout(manifest: &Value) -> Result<ArgoConversion, ArgoConversionError> {
+    let argo: ArgoRollout = serde_json::from_value(manifest.clone())
+        .map_err(|e| ArgoConversionError::Parse(e.to_string()))?;
+    if argo.api_version != ARGO_API_VERSION || argo.kind != "Rollout" {
+        return Err(ArgoConversionError::NotArgoRollout {
+            api_version: argo.api_version,
+            kind: argo.kind,
+        });
+    }
+    let name = argo
+        .metadata
+        .name
+        .clone()
+        .ok_or(ArgoConversionError::MissingField("metadata.name"))?;
+
+    let mut warnings = Vec::new();
+    let spec = argo.spec;
+    unsupported(&mut warnings, "spec", &spec.unsupported);
+
+    let selector = spec
+        .selector
+        .ok_or(ArgoConversionError::MissingField("spec.selector"))?;
+    let workload_ref = spec
+        .workload_ref
+        .map(|workload_ref| convert_workload_ref(workload_ref, &mut warnings))
+        .transpose()?;
+
+    let (strategy, max_surge, max_unavailable) =
+        match (spec.strategy.canary, spec.strategy.blue_green) {
+            (Some(canary), _) => {
+                let max_surge = canary.max_surge.as_ref().map(int_or_string);
+                let max_unavailable = canary.max_unavailable.as_ref().map(int_or_string);
+                let canary = convert_canary(canary, &name, &mut warnings)?;
+                (
+                    RolloutStrategy {
+                        canary: Some(canary),
+                        blue_green: None,
+                        simple: None,
+                        ab_testing: None,
+                    },
+                    max_surge,
+                    max_unavailable,
+                )
+            }
+            (None, Some(blue_green)) => (
+                RolloutStrategy {
+                    canary: None,
+                    blue_green: Some(convert_blue_green(blue_green, &mut warnings)?),
+                    simple: None,
+                    ab_testing: None,
+                },
+                None,
+                None,
+            ),
+            (None, None) => return Err(ArgoConversionError::UnsupportedStrategy),
+        };
+
+    let mut rollout = Rollout::new(
+        &name,
+        RolloutSpec {
+            replicas: spec.replicas.unwrap_or(1),
+            selector,
+            template: spec.template.unwrap_or_default(),
+            workload_ref,
+            strategy,
+            max_surge,
+            max_unavailable,
+            progress_deadline_seconds: spec.progress_deadline_seconds,
+            advisor: Default::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: spec.revision_history_limit,
+            cleanup_policy: None,
+        },
+    );
+    rollout.metadata.namespace = argo.metadata.namespace;
+    rollout.metadata.labels = argo.metadata.labels;
+    rollout.metadata.annotations = argo.metadata.annotations.map(|annotations| {
+        annotations
+            .into_iter()
+            .filter(|(key, _)| {
+                let argo_owned = key.starts_with(ARGO_ANNOTATION_PREFIX);
+                if argo_owned {
+                    warnings.push(format!("metadata.annotations.{}: dropped", key));
+                }
+                !argo_owned
+            })
+            .collect()
+    });
+
+    validate_rollout(&rollout).map_err(ArgoConversionError::InvalidSpec)?;
+    Ok(ArgoConversion { rollout, warnings })
+}
+
+fn convert_workload_ref(
+    workload_ref: ArgoWorkloadRef,
+    warnings: &mut Vec<String>,
+) -> Result<WorkloadRef, ArgoConversionError> {
+    if workload_ref.kind != "Deployment" {
+        return Err(ArgoConversionError::InvalidSpec(format!(
+            "spec.workloadRef.kind {:?} is not supported (only Deployment)",
+            workload_ref.kind
+        )));
+    }
+    let scale_down = match workload_ref.scale_down.as_deref() {
+        None | Some("never") => WorkloadScaleDown::Never,
+        Some("onsuccess") => WorkloadScaleDown::OnSuccess,
+        Some(other) => {
+            warnings.push(format!(
+                "spec.workloadRef.scaleDown: {:?} converted to OnSuccess",
+                other
+            ));
+            WorkloadScaleDown::OnSuccess
+        }
+    };
+    Ok(WorkloadRef {
+        kind: WorkloadKind::Deployment,
+        name: workload_ref.name,
+        scale_down,
+    })
+}
+
+fn convert_canary(
+    canary: ArgoCanary,
+    name: &str,
+    warnings: &mut Vec<String>,
+) -> Result<CanaryStrategy, ArgoConversionError> {
+    unsupported(warnings, "spec.strategy.canary", &canary.unsupported);
+
+    let traffic_routing = match canary.traffic_routing {
+        Some(routing) => convert_traffic_routing(routing, warnings)?,
+        None => Some(TrafficRouting {
+            gateway_api: None,
+            none: Some(NoTrafficRouting {
+                replica_weighting: Some(true),
+            }),
+            nginx: None,
+        }),
+    };
+    let mut service = |field: &str, service: Option<String>, suffix: &str| {
+        service.unwrap_or_else(|| {
+            let derived = format!("{}-{}", name, suffix);
+            warnings.push(format!(
+                "spec.strategy.canary.{}: not set, using Service {:?} (create it)",
+                field, derived
+            ));
+            derived
+        })
+    };
+    let canary_service = service("canaryService", canary.canary_service, "canary");
+    let stable_service = service("stableService", canary.stable_service, "stable");
+
+    // Without a traffic router the canary's size is its weight
+    let replica_weighting = traffic_routing
+        .as_ref()
+        .is_some_and(TrafficRouting::uses_replica_weighting);
+
+    let mut steps: Vec<CanaryStep> = Vec::new();
+    let mut pending_scale = None;
+    for (i, step) in canary.steps.into_iter().enumerate() {
+        let field = format!("spec.strategy.canary.steps[{}]", i);
+        unsupported(warnings, &field, &step.unsupported);
+        if let Some(scale) = step.set_canary_scale {
+            if replica_weighting {
+                warnings.push(format!(
+                    "{}.setCanaryScale: dropped, without trafficRouting the canary is sized by its weight",
+                    field
+                ));
+            } else {
+                pending_scale = Some(scale);
+            }
+        }
+        let pause = step.pause.map(convert_pause);
+        match (step.set_weight, pause) {
+            (Some(weight), pause) => steps.push(CanaryStep {
+                set_weight: Some(weight),
+                pause,
+                alert_gate: None,
+                set_mirror: None,
+                set_canary_scale: pending_scale.take(),
+                set_header_route: None,
+                experiment: None,
+                analysis: None,
+            }),
+            // A bare pause holds the previous step's weight
+            (None, Some(pause)) => {
+                let weight = steps.last().and_then(|p| p.set_weight).unwrap_or(0);
+                match steps.last_mut() {
+                    Some(previous) if previous.pause.is_none() => {
+                        previous.pause = Some(pause);
+                        if let Some(scale) = pending_scale.take() {
+                            previous.set_canary_scale = Some(scale);
+                        }
+                    }
+                    _ => steps.push(CanaryStep {
+                        set_weight: Some(weight),
+                        pause: Some(pause),
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: pending_scale.take(),
+                        set_header_route: None,
+                        experiment: None,
+                        analysis: None,
+                    }),
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    if steps.is_empty() {
+        warnings.push("spec.strategy.canary.steps: none left, using a single 100% step".into());
+        steps.push(CanaryStep {
+            set_weight: Some(100),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        });
+    }
+
+    Ok(CanaryStrategy {
+        canary_service,
+        stable_service,
+        port: None,
+        steps,
+        steps_from: None,
+        traffic_routing,
+        analysis: None,
+        gates: vec![],
+        baseline: None,
+        rollback_verification: None,
+    })
+}
+
+fn convert_traffic_routing(
+    routing: ArgoTrafficRouting,
+    warnings: &mut Vec<String>,
+) -> Result<Option<TrafficRouting>, ArgoConversionError> {
+    unsupported(
+        warnings,
+        "spec.strategy.canary.trafficRouting",
+        &routing.unsupported,
+    );
+
+    let mut gateway_api = None;
+    for (plugin, config) in &routing.plugins {
+        if plugin != GATEWAY_API_PLUGIN {
+            warnings.push(format!(
+                "spec.strategy.canary.trafficRouting.plugins.{}: dropped",
+                plugin
+            ));
+            continue;
+        }
+        let http_route = config
+            .get("httpRoute")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let route_list = |field: &str| -> Vec<String> {
+            config
+                .get(field)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|route| route.get("name").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        };
+        let (kind, http_routes) = match (http_route.is_empty(), route_list("httpRoutes")) {
+            // TLS passthrough rollouts only list tlsRoutes
+            (true, http_routes) if http_routes.is_empty() => {
+                (GatewayRouteKind::TLSRoute, route_list("tlsRoutes"))
+            }
+            (_, http_routes) => (GatewayRouteKind::HTTPRoute, http_routes),
+        };
+        gateway_api = Some(GatewayAPIRouting {
+            kind,
+            http_route,
+            http_routes,
+            weight_constraints: None,
+            sections: Vec::new(),
+        });
+    }
+
+    let nginx = routing
+        .nginx
+        .map(|nginx| {
+            unsupported(
+                warnings,
+                "spec.strategy.canary.trafficRouting.nginx",
+                &nginx.unsupported,
+            );
+            Ok::<_, ArgoConversionError>(NginxTrafficRouting {
+                stable_ingress: nginx
+                    .stable_ingress
+                    .ok_or(ArgoConversionError::MissingField(
+                        "spec.strategy.canary.trafficRouting.nginx.stableIngress",
+                    ))?,
+                annotation_prefix: nginx.annotation_prefix,
+            })
+        })
+        .transpose()?;
+
+    if gateway_api.is_none() && nginx.is_none() {
+        warnings.push(
+            "spec.strategy.canary.trafficRouting: no supported router, traffic is not split".into(),
+        );
+        return Ok(None);
+    }
+    Ok(Some(TrafficRouting {
+        gateway_api,
+        none: None,
+        nginx,
+    }))
+}
+
+fn convert_blue_green(
+    blue_green: ArgoBlueGreen,
+    warnings: &mut Vec<String>,
+) -> Result<BlueGreenStrategy, ArgoConversionError> {
+    unsupported(warnings, "spec.strategy.blueGreen", &blue_green.unsupported);
+    Ok(BlueGreenStrategy {
+        active_service: blue_green
+            .active_service
+            .ok_or(ArgoConversionError::MissingField(
+                "spec.strategy.blueGreen.activeService",
+            ))?,
+        preview_service: blue_green
+            .preview_service
+            .ok_or(ArgoConversionError::MissingField(
+                "spec.strategy.blueGreen.previewService",
+            ))?,
+        port: None,
+        auto_promotion_enabled: blue_green.auto_promotion_enabled,
+        auto_promotion_seconds: blue_green.auto_promotion_seconds,
+        traffic_routing: None,
+        analysis: None,
+        migrated_from: None,
+    })
+}
+
+/// Argo pause durations are seconds as a number, or a string with a unit
+fn convert_pause(pause: ArgoPause) -> Pause {
+    match pause.duration {
+        Some(IntOrString::Int(seconds)) => Pause::Duration(format!("{}s", seconds)),
+        Some(IntOrString::String(s)) if s.ends_with(|c: char| c.is_ascii_digit()) => {
+            Pause::Duration(format!("{}s", s))
+        }
+        Some(IntOrString::String(s)) => Pause::Duration(s),
+        None => Pause::Indefinite,
+    }
+}
+
+fn int_or_string(value: &IntOrString) -> String {
+    match value {
+        IntOrString::Int(n) => n.to_string(),
+        IntOrString::String(s) => s.clone(),
+    }
+}
+
+fn unsupported(warnings: &mut Vec<String>, path: &str, fields: &BTreeMap<String, Value>) {
+    warnings.extend(
+        fields
+            .keys()
+            .map(|field| format!("{}.{}: not supported, dropped", path, field)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::rollout::Pause;
+
+    fn argo_rollout(strategy: Value) -> Value {
+        serde_json::json!({
+            "apiVersion": "argoproj.io/v1alpha1",
+            "kind": "Rollout",
+            "metadata": {
+                "name": "checkout",
+                "namespace": "shop",
+                "annotations": { "rollout.argoproj.io/revision": "3", "team": "payments" }
+            },
+            "spec": {
+                "replicas": 4,
+                "selector": { "matchLabels": { "app": "checkout" } },
+                "template": {
+                    "metadata": { "labels": { "app": "checkout" } },
+                    "spec": { "containers": [{ "name": "app", "image": "checkout:2.1" }] }
+                },
+                "minReadySeconds": 10,
+                "strategy": strategy
+            }
+        })
+    }
+
+    #[test]
+    fn test_convert_canary() {
+        let conversion = convert_argo_rollout(&argo_rollout(serde_json::json!({
+            "canary": {
+                "canaryService": "checkout-canary",
+                "stableService": "checkout-stable",
+                "maxSurge": 1,
+                "trafficRouting": {
+                    "plugins": { "argoproj-labs/gatewayAPI": { "httpRoute": "checkout", "namespace": "shop" } }
+                },
+                "steps": [
+                    { "pause": { "duration": 30 } },
+                    { "setWeight": 20 },
+                    { "pause": { "duration": "5m" } },
+                    { "analysis": { "templates": [{ "templateName": "success-rate" }] } },
+                    { "setCanaryScale": { "replicas": 2 } },
+                    { "setWeight": 50 },
+                    { "pause": {} }
+                ]
+            }
+        })))
+        .unwrap();
+
+        let rollout = conversion.rollout;
+        assert_eq!(rollout.metadata.namespace.as_deref(), Some("shop"));
+        assert_eq!(rollout.spec.replicas, 4);
+        assert_eq!(rollout.spec.max_surge.as_deref(), Some("1"));
+        let annotations = rollout.metadata.annotations.unwrap();
+        assert!(annotations.contains_key("team"));
+        assert!(!annotations.contains_key("rollout.argoproj.io/revision"));
+
+        let canary = rollout.spec.strategy.canary.unwrap();
+        let gateway_api = canary.traffic_routing.unwrap().gateway_api.unwrap();
+        assert_eq!(gateway_api.http_route, "checkout");
+        let steps: Vec<_> = canary
+            .steps
+            .iter()
+            .map(|s| (s.set_weight, s.pause.clone()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                (Some(0), Some(Pause::Duration("30s".to_string()))),
+                (Some(20), Some(Pause::Duration("5m".to_string()))),
+                (Some(50), Some(Pause::Indefinite)),
+            ]
+        );
+        assert_eq!(canary.steps[1].set_canary_scale, None);
+        assert_eq!(
+            canary.steps[2].set_canary_scale.as_ref().unwrap().replicas,
+            Some(2)
+        );
+
+        let warnings = conversion.warnings.join("\n");
+        assert!(warnings.contains("spec.minReadySeconds"));
+        assert!(warnings.contains("steps[3].analysis"));
+        assert!(warnings.contains("rollout.argoproj.io/revision"));
+    }
+
+    #[test]
+    fn test_convert_basic_canary_and_blue_green() {
+        let basic = convert_argo_rollout(&argo_rollout(serde_json::json!({
+            "canary": { "steps": [{ "setCanaryScale": { "weight": 50 } }, { "setWeight": 50 }] }
+        })))
+        .unwrap();
+        let canary = basic.rollout.spec.strategy.canary.unwrap();
+        assert!(canary.traffic_routing.unwrap().uses_replica_weighting());
+        assert_eq!(canary.steps[0].set_canary_scale, None);
+        assert!(basic
+            .warnings
+            .iter()
+            .any(|w| w.contains("steps[0].setCanaryScale: dropped")));
+        assert_eq!(canary.stable_service, "checkout-stable");
+        assert!(basic.warnings.iter().any(|w| w.contains("stableService")));
+
+        let blue_green = convert_argo_rollout(&argo_rollout(serde_json::json!({
+            "blueGreen": {
+                "activeService": "checkout-active",
+                "previewService": "checkout-preview",
+                "autoPromotionEnabled": false,
+                "scaleDownDelaySeconds": 30
+            }
+        })))
+        .unwrap();
+        let strategy = blue_green.rollout.spec.strategy.blue_green.unwrap();
+        assert_eq!(strategy.active_service, "checkout-active");
+        assert_eq!(strategy.auto_promotion_enabled, Some(false));
+        assert!(blue_green
+            .warnings
+            .iter()
+            .any(|w| w.contains("scaleDownDelaySeconds")));
+    }
+
+    #[test]
+    fn test_convert_workload_ref() {
+        let mut manifest = argo_rollout(serde_json::json!({
+            "blueGreen": { "activeService": "checkout-active", "previewService": "checkout-preview" }
+        }));
+        manifest["spec"].as_object_mut().unwrap().remove("template");
+        manifest["spec"]["workloadRef"] = serde_json::json!({
+            "apiVersion": "apps/v1", "kind": "Deployment", "name": "checkout", "scaleDown": "onsuccess"
+        });
+
+        let rollout = convert_argo_rollout(&manifest).unwrap().rollout;
+        let workload_ref = rollout.spec.workload_ref.unwrap();
+        assert_eq!(workload_ref.name, "checkout");
+        assert_eq!(workload_ref.scale_down, WorkloadScaleDown::OnSuccess);
+    }
+
+    #[test]
+    fn test_convert_rejects_non_argo_and_unsupported() {
+        let mut kulta = argo_rollout(serde_json::json!({}));
+        kulta["apiVersion"] = "kulta.io/v1alpha1".into();
+        assert!(matches!(
+            convert_argo_rollout(&kulta),
+            Err(ArgoConversionError::NotArgoRollout { .. })
+        ));
+        assert_eq!(
+            convert_argo_rollout(&argo_rollout(serde_json::json!({}))).unwrap_err(),
+            ArgoConversionError::UnsupportedStrategy
+        );
+        assert_eq!(
+            convert_argo_rollout(&argo_rollout(serde_json::json!({
+                "blueGreen": { "activeService": "checkout-active" }
+            })))
+            .unwrap_err(),
+            ArgoConversionError::MissingField("spec.strategy.blueGreen.previewService")
+        );
+    }
+}
@@ -0,0 +1,447 @@
+//! Fluent builder for Rollouts
+//!
+//! Platforms generating Rollouts in Rust would otherwise assemble the nested
+//! metadata/selector/template/strategy structs by hand, where it is easy to
+//! forget a selector that matches the pod labels or to set two strategies.
+//! [`RolloutBuilder`] keeps those consistent (every [`label`] goes on both
+//! the selector and the pod template, a strategy setter replaces any other
+//! strategy) and runs the controller's validation in [`build`]:
+//!
+//! ```
+//! use kulta::api::{CanaryStep, CanaryStrategy, RolloutBuilder};
+//!
+//! let step = |weight| CanaryStep {
+//!     set_weight: Some(weight),
+//!     pause: None,
+//!     alert_gate: None,
+//!     set_mirror: None,
+//!     set_canary_scale: None,
+//!     set_header_route: None,
+//! };
+//! let rollout = RolloutBuilder::new("checkout")
+//!     .namespace("shop")
+//!     .replicas(4)
+//!     .label("app", "checkout")
+//!     .image("app", "checkout:2.1")
+//!     .canary(CanaryStrategy {
+//!         stable_service: "checkout-stable".to_string(),
+//!         canary_service: "checkout-canary".to_string(),
+//!         port: None,
+//!         steps: vec![step(20), step(100)],
+//!         steps_from: None,
+//!         traffic_routing: None,
+//!         analysis: None,
+//!         gates: vec![],
+//!         baseline: None,
+//!         rollback_verification: None,
+//!     })
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(rollout.spec.replicas, 4);
+//! ```
+//!
+//! [`label`]: RolloutBuilder::label
+//! [`build`]: RolloutBuilder::build
+
+use super::rollout::{
+    ABStrategy, AdvisorConfig, BlueGreenStrategy, CanaryStrategy, CleanupPolicy, DegradedPolicy,
+    Rollout, RolloutSpec, RolloutStrategy, SimpleStrategy, TemplateChangePolicy, WorkloadRef,
+};
+use crate::controller::rollout::validate_rollout;
+use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::api::ObjectMeta;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RolloutBuildError {
+    #[error("Rollout needs a strategy (canary, blueGreen, abTesting or simple)")]
+    MissingStrategy,
+
+    #[error("Rollout needs at least one selector label")]
+    MissingSelector,
+
+    #[error("Pod template needs at least one container (or a workloadRef)")]
+    MissingContainers,
+
+    #[error("Invalid Rollout spec: {0}")]
+    InvalidSpec(String),
+}
+
+/// Fluent builder for a [`Rollout`]
+#[derive(Clone, Debug)]
+pub struct RolloutBuilder {
+    name: String,
+    namespace: Option<String>,
+    labels: BTreeMap<String, String>,
+    annotations: BTreeMap<String, String>,
+    selector: BTreeMap<String, String>,
+    pod_labels: BTreeMap<String, String>,
+    pod_spec: PodSpec,
+    replicas: i32,
+    strategy: Option<RolloutStrategy>,
+    max_surge: Option<String>,
+    max_unavailable: Option<String>,
+    progress_deadline_seconds: Option<i32>,
+    advisor: AdvisorConfig,
+    on_degraded: Option<DegradedPolicy>,
+    on_template_change: Option<TemplateChangePolicy>,
+    revision_history_limit: Option<i32>,
+    cleanup_policy: Option<CleanupPolicy>,
+    workload_ref: Option<WorkloadRef>,
+}
+
+impl RolloutBuilder {
+    /// Start a Rollout named `name` with one replica and no strategy
+    pub fn new(name: impl Into<String>) -> Self {
+        RolloutBuilder {
+            name: name.into(),
+            namespace: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            selector: BTreeMap::new(),
+            pod_labels: BTreeMap::new(),
+            pod_spec: PodSpec::default(),
+            replicas: 1,
+            strategy: None,
+            max_surge: None,
+            max_unavailable: None,
+            progress_deadline_seconds: None,
+            advisor: AdvisorConfig::default(),
+            on_degraded: None,
+            on_template_change: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
+        }
+    }
+
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn replicas(mut self, replicas: i32) -> Self {
+        self.replicas = replicas;
+        self
+    }
+
+    /// Pod label that is also part of the selector
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let (key, value) = (key.into(), value.into());
+        self.selector.insert(key.clone(), value.clone());
+        self.pod_labels.insert(key, value);
+        self
+    }
+
+    /// Pod label that is not part of the selector
+    pub fn pod_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.pod_labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Label on the Rollout itself
+    pub fn rollout_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Annotation on the Rollout itself (e.g. `links.kulta.io/change-ticket`)
+    pub fn annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.insert(key.into(), value.into());
+        self
+    }
+
+    /// Pod spec of the template; containers added before are replaced
+    pub fn pod_spec(mut self, pod_spec: PodSpec) -> Self {
+        self.pod_spec = pod_spec;
+        self
+    }
+
+    /// Add a container to the pod template
+    pub fn container(mut self, container: Container) -> Self {
+        self.pod_spec.containers.push(container);
+        self
+    }
+
+    /// Add a container running `image`
+    pub fn image(self, container: impl Into<String>, image: impl Into<String>) -> Self {
+        self.container(Container {
+            name: container.into(),
+            image: Some(image.into()),
+            ..Default::default()
+        })
+    }
+
+    pub fn canary(self, canary: CanaryStrategy) -> Self {
+        self.strategy(RolloutStrategy {
+            canary: Some(canary),
+            blue_green: None,
+            simple: None,
+            ab_testing: None,
+        })
+    }
+
+    pub fn blue_green(self, blue_green: BlueGreenStrategy) -> Self {
+        self.strategy(RolloutStrategy {
+            canary: None,
+            blue_green: Some(blue_green),
+            simple: None,
+            ab_testing: None,
+        })
+    }
+
+    pub fn ab_testing(self, ab_testing: ABStrategy) -> Self {
+        self.strategy(RolloutStrategy {
+            canary: None,
+            blue_green: None,
+            simple: None,
+            ab_testing: Some(ab_testing),
+        })
+    }
+
+    pub fn simple(self, simple: SimpleStrategy) -> Self {
+        self.strategy(RolloutStrategy {
+            canary: None,
+            blue_green: None,
+            simple: Some(simple),
+            ab_testing: None,
+        })
+    }
+
+    fn strategy(mut self, strategy: RolloutStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    pub fn max_surge(mut self, max_surge: impl Into<String>) -> Self {
+        self.max_surge = Some(max_surge.into());
+        self
+    }
+
+    pub fn max_unavailable(mut self, max_unavailable: impl Into<String>) -> Self {
+        self.max_unavailable = Some(max_unavailable.into());
+        self
+    }
+
+    pub fn progress_deadline_seconds(mut self, seconds: i32) -> Self {
+        self.progress_deadline_seconds = Some(seconds);
+        self
+    }
+
+    pub fn advisor(mut self, advisor: AdvisorConfig) -> Self {
+        self.advisor = advisor;
+        self
+    }
+
+    pub fn on_degraded(mut self, on_degraded: DegradedPolicy) -> Self {
+        self.on_degraded = Some(on_degraded);
+        self
+    }
+
+    pub fn on_template_change(mut self, policy: TemplateChangePolicy) -> Self {
+        self.on_template_change = Some(policy);
+        self
+    }
+
+    pub fn revision_history_limit(mut self, limit: i32) -> Self {
+        self.revision_history_limit = Some(limit);
+        self
+    }
+
+    pub fn cleanup_policy(mut self, policy: CleanupPolicy) -> Self {
+        self.cleanup_policy = Some(policy);
+        self
+    }
+
+    /// Adopt an existing workload's pod template instead of adding containers
+    pub fn workload_ref(mut self, workload_ref: WorkloadRef) -> Self {
+        self.workload_ref = Some(workload_ref);
+        self
+    }
+
+    /// Assemble the Rollout and validate it as the controller would
+    pub fn build(self) -> Result<Rollout, RolloutBuildError> {
+        let strategy = self.strategy.ok_or(RolloutBuildError::MissingStrategy)?;
+        if self.selector.is_empty() {
+            return Err(RolloutBuildError::MissingSelector);
+        }
+        if self.pod_spec.containers.is_empty() && self.workload_ref.is_none() {
+            return Err(RolloutBuildError::MissingContainers);
+        }
+
+        let non_empty = |map: BTreeMap<String, String>| (!map.is_empty()).then_some(map);
+        let mut rollout = Rollout::new(
+            &self.name,
+            RolloutSpec {
+                replicas: self.replicas,
+                selector: LabelSelector {
+                    match_labels: Some(self.selector),
+                    match_expressions: None,
+                },
+                template: PodTemplateSpec {
+                    metadata: Some(ObjectMeta {
+                        labels: Some(self.pod_labels),
+                        ..Default::default()
+                    }),
+                    spec: Some(self.pod_spec),
+                },
+                strategy,
+                max_surge: self.max_surge,
+                max_unavailable: self.max_unavailable,
+                progress_deadline_seconds: self.progress_deadline_seconds,
+                advisor: self.advisor,
+                on_degraded: self.on_degraded,
+                on_template_change: self.on_template_change,
+                revision_history_limit: self.revision_history_limit,
+                cleanup_policy: self.cleanup_policy,
+                workload_ref: self.workload_ref,
+            },
+        );
+        rollout.metadata.namespace = self.namespace;
+        rollout.metadata.labels = non_empty(self.labels);
+        rollout.metadata.annotations = non_empty(self.annotations);
+
+        validate_rollout(&rollout).map_err(RolloutBuildError::InvalidSpec)?;
+        Ok(rollout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::crd::rollout::CanaryStep;
+
+    fn canary(steps: Vec<CanaryStep>) -> CanaryStrategy {
+        CanaryStrategy {
+            stable_service: "checkout-stable".to_string(),
+            canary_service: "checkout-canary".to_string(),
+            port: None,
+            steps,
+            steps_from: None,
+            traffic_routing: None,
+            analysis: None,
+            gates: vec![],
+            baseline: None,
+            rollback_verification: None,
+        }
+    }
+
+    fn step(weight: i32) -> CanaryStep {
+        CanaryStep {
+            set_weight: Some(weight),
+            pause: None,
+            alert_gate: None,
+            set_mirror: None,
+            set_canary_scale: None,
+            set_header_route: None,
+            experiment: None,
+            analysis: None,
+        }
+    }
+
+    #[test]
+    fn test_build_keeps_selector_and_pod_labels_in_sync() {
+        let rollout = RolloutBuilder::new("checkout")
+            .namespace("shop")
+            .replicas(4)
+            .label("app", "checkout")
+            .pod_label("team", "payments")
+            .annotation("links.kulta.io/change-ticket", "CHG-1234")
+            .image("app", "checkout:2.1")
+            .canary(canary(vec![step(20), step(100)]))
+            .build()
+            .unwrap();
+
+        assert_eq!(rollout.metadata.name.as_deref(), Some("checkout"));
+        assert_eq!(rollout.metadata.namespace.as_deref(), Some("shop"));
+        assert_eq!(rollout.spec.replicas, 4);
+        let selector = rollout.spec.selector.match_labels.unwrap();
+        assert_eq!(selector.len(), 1);
+        let pod_labels = rollout.spec.template.metadata.unwrap().labels.unwrap();
+        assert_eq!(pod_labels["app"], "checkout");
+        assert_eq!(pod_labels["team"], "payments");
+        assert!(rollout.spec.strategy.canary.is_some());
+        assert_eq!(
+            rollout.spec.template.spec.unwrap().containers[0]
+                .image
+                .as_deref(),
+            Some("checkout:2.1")
+        );
+    }
+
+    #[test]
+    fn test_strategy_setters_replace_each_other() {
+        let rollout = RolloutBuilder::new("checkout")
+            .label("app", "checkout")
+            .image("app", "checkout:2.1")
+            .canary(canary(vec![step(100)]))
+            .simple(SimpleStrategy { analysis: None })
+            .build()
+            .unwrap();
+
+        assert!(rollout.spec.strategy.canary.is_none());
+        assert!(rollout.spec.strategy.simple.is_some());
+    }
+
+    #[test]
+    fn test_build_rejects_incomplete_rollouts() {
+        let base = RolloutBuilder::new("checkout")
+            .label("app", "checkout")
+            .image("app", "checkout:2.1");
+
+        assert_eq!(
+            base.clone().build().unwrap_err(),
+            RolloutBuildError::MissingStrategy
+        );
+        assert_eq!(
+            RolloutBuilder::new("checkout")
+                .image("app", "checkout:2.1")
+                .canary(canary(vec![step(100)]))
+                .build()
+                .unwrap_err(),
+            RolloutBuildError::MissingSelector
+        );
+        assert_eq!(
+            RolloutBuilder::new("checkout")
+                .label("app", "checkout")
+                .canary(canary(vec![step(100)]))
+                .build()
+                .unwrap_err(),
+            RolloutBuildError::MissingContainers
+        );
+        assert!(matches!(
+            base.canary(canary(vec![step(150)])).build(),
+            Err(RolloutBuildError::InvalidSpec(_))
+        ));
+    }
+
+    #[test]
+    fn test_workload_ref_replaces_containers() {
+        let workload_ref = WorkloadRef {
+            kind: Default::default(),
+            name: "checkout".to_string(),
+            scale_down: Default::default(),
+        };
+        let rollout = RolloutBuilder::new("checkout")
+            .label("app", "checkout")
+            .canary(canary(vec![step(100)]))
+            .workload_ref(workload_ref.clone())
+            .build()
+            .unwrap();
+        assert_eq!(rollout.spec.workload_ref, Some(workload_ref.clone()));
+
+        // Containers and a workloadRef are mutually exclusive
+        assert!(matches!(
+            RolloutBuilder::new("checkout")
+                .label("app", "checkout")
+                .image("app", "checkout:2.1")
+                .canary(canary(vec![step(100)]))
+                .workload_ref(workload_ref)
+                .build(),
+            Err(RolloutBuildError::InvalidSpec(_))
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! ClusterRollout: a cluster-scoped Rollout
+//!
+//! Operators and system components are usually installed into a fixed
+//! namespace but owned by cluster admins. A ClusterRollout carries a full
+//! Rollout spec plus the `targetNamespace` the workload runs in, and is
+//! reconciled exactly like a Rollout of the same name in that namespace:
+//! ReplicaSets, Services and routes are all looked up in `targetNamespace`,
+//! while status is written back to the ClusterRollout. A Rollout of the same
+//! name in `targetNamespace` would collide with it; see [`yields_to`].
+
+use crate::crd::rollout::{Rollout, RolloutSpec, RolloutStatus};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use kube::{CustomResource, Resource, ResourceExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Annotation marking a Rollout that stands in for a ClusterRollout
+///
+/// Only set on the in-memory Rollout built by [`ClusterRollout::to_rollout`];
+/// the value is the ClusterRollout name.
+pub const CLUSTER_ROLLOUT_ANNOTATION: &str = "kulta.io/cluster-rollout";
+
+/// ClusterRollout is a cluster-scoped Rollout for cluster-wide workloads
+///
+/// The workload lives in `targetNamespace`; the spec is otherwise identical
+/// to a Rollout's.
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[kube(
+    group = "kulta.io",
+    version = "v1alpha1",
+    kind = "ClusterRollout",
+    status = "RolloutStatus",
+    shortname = "cro",
+    category = "all",
+    printcolumn = r#"{"name":"Namespace", "type":"string", "jsonPath":".spec.targetNamespace"}"#,
+    printcolumn = r#"{"name":"Desired", "type":"integer", "jsonPath":".spec.replicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Ready", "type":"integer", "jsonPath":".status.readyReplicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Strategy", "type":"string", "jsonPath":".status.strategy"}"#,
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Weight", "type":"integer", "jsonPath":".status.currentWeight"}"#,
+    printcolumn = r#"{"name":"Summary", "type":"string", "jsonPath":".status.summary"}"#,
+    printcolumn = r#"{"name":"Message", "type":"string", "jsonPath":".status.message", "priority":1}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct ClusterRolloutSpec {
+    /// Namespace the workload's ReplicaSets, Services and routes live in
+    #[serde(rename = "targetNamespace")]
+    pub target_namespace: String,
+
+    /// The Rollout spec, inlined
+    #[serde(flatten)]
+    pub rollout: RolloutSpec,
+}
+
+impl ClusterRollout {
+    /// The namespaced Rollout this ClusterRollout is reconciled as
+    ///
+    /// Keeps the name, uid, labels and annotations, places it in
+    /// `targetNamespace`, and marks it with [`CLUSTER_ROLLOUT_ANNOTATION`].
+    pub fn to_rollout(&self) -> Rollout {
+        let mut metadata = self.metadata.clone();
+        metadata.namespace = Some(self.spec.target_namespace.clone());
+        metadata
+            .annotations
+            .get_or_insert_with(Default::default)
+            .insert(CLUSTER_ROLLOUT_ANNOTATION.to_string(), self.name_any());
+
+        Rollout {
+            metadata,
+            spec: self.spec.rollout.clone(),
+            status: self.status.clone(),
+        }
+    }
+}
+
+/// Name of the ClusterRollout a Rollout stands in for, if any
+pub fn source_cluster_rollout(rollout: &Rollout) -> Option<&str> {
+    rollout
+        .annotations()
+        .get(CLUSTER_ROLLOUT_ANNOTATION)
+        .map(String::as_str)
+}
+
+/// Controller owner reference to the Rollout, or to its ClusterRollout
+///
+/// Objects created for a ClusterRollout must be owned by the cluster-scoped
+/// resource: an owner reference to the stand-in Rollout would point at an
+/// object that doesn't exist, and the garbage collector would delete them.
+pub fn rollout_owner_reference(rollout: &Rollout) -> Option<OwnerReference> {
+    match source_cluster_rollout(rollout) {
+        Some(name) => Some(OwnerReference {
+            api_version: ClusterRollout::api_version(&()).to_string(),
+            kind: ClusterRollout::kind(&()).to_string(),
+            name: name.to_string(),
+            uid: rollout.metadata.uid.clone()?,
+            controller: Some(true),
+            ..Default::default()
+        }),
+        None => rollout.controller_owner_ref(&()),
+    }
+}
+
+/// Whether a Rollout leaves its resources to a same-named object
+///
+/// A ClusterRollout and a Rollout of the same name in its `targetNamespace`
+/// would manage the same ReplicaSets and routes. The one created first keeps
+/// them; the other (the ClusterRollout, on a tie) is not reconciled until the
+/// first is gone.
+///
+/// # Arguments
+/// * `rollout` - The Rollout, or a ClusterRollout's stand-in
+/// * `other` - Metadata of the colliding ClusterRollout or Rollout
+pub fn yields_to(rollout: &Rollout, other: &ObjectMeta) -> bool {
+    let stand_in = source_cluster_rollout(rollout).is_some();
+    match (
+        &rollout.metadata.creation_timestamp,
+        &other.creation_timestamp,
+    ) {
+        (Some(own), Some(other)) if own.0 != other.0 => own.0 > other.0,
+        _ => stand_in,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use kube::CustomResourceExt;
+
+    fn cluster_rollout() -> ClusterRollout {
+        serde_yaml::from_str(
+            r#"
+apiVersion: kulta.io/v1alpha1
+kind: ClusterRollout
+metadata:
+  name: ingress-controller
+  uid: uid-1
+  annotations:
+    team: platform
+spec:
+  targetNamespace: ingress-system
+  replicas: 2
+  selector:
+    matchLabels:
+      app: ingress-controller
+  template:
+    metadata:
+      labels:
+        app: ingress-controller
+    spec:
+      containers:
+      - name: controller
+        image: ingress-controller:1.2
+  strategy:
+    simple: {}
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cluster_rollout_deserializes_inline_spec() {
+        let cluster_rollout = cluster_rollout();
+
+        assert_eq!(cluster_rollout.spec.target_namespace, "ingress-system");
+        assert_eq!(cluster_rollout.spec.rollout.replicas, 2);
+        assert!(cluster_rollout.spec.rollout.strategy.simple.is_some());
+    }
+
+    #[test]
+    fn test_to_rollout_targets_namespace() {
+        let rollout = cluster_rollout().to_rollout();
+
+        assert_eq!(rollout.name_any(), "ingress-controller");
+        assert_eq!(rollout.namespace().as_deref(), Some("ingress-system"));
+        assert_eq!(rollout.annotations()["team"], "platform");
+        assert_eq!(source_cluster_rollout(&rollout), Some("ingress-controller"));
+        assert_eq!(rollout.spec.replicas, 2);
+    }
+
+    #[test]
+    fn test_owner_reference_points_at_cluster_rollout() {
+        let rollout = cluster_rollout().to_rollout();
+        let owner = rollout_owner_reference(&rollout).unwrap();
+        assert_eq!(owner.kind, "ClusterRollout");
+        assert_eq!(owner.name, "ingress-controller");
+        assert_eq!(owner.uid, "uid-1");
+        assert_eq!(owner.controller, Some(true));
+
+        let mut namespaced = rollout;
+        namespaced.metadata.annotations = None;
+        assert_eq!(
+            rollout_owner_reference(&namespaced).unwrap().kind,
+            "Rollout"
+        );
+    }
+
+    #[test]
+    fn test_newer_of_colliding_rollouts_yields() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+        let created = |at: &str| ObjectMeta {
+            creation_timestamp: Some(Time(at.parse().unwrap())),
+            ..Default::default()
+        };
+        let mut stand_in = cluster_rollout().to_rollout();
+        stand_in.metadata.creation_timestamp = created("2026-03-02T09:00:00Z").creation_timestamp;
+        let mut namespaced = stand_in.clone();
+        namespaced.metadata.annotations = None;
+
+        // The ClusterRollout came first: the Rollout yields
+        assert!(!yields_to(&stand_in, &created("2026-03-02T10:00:00Z")));
+        assert!(yields_to(&namespaced, &created("2026-03-02T08:00:00Z")));
+        // The Rollout came first: the ClusterRollout yields
+        assert!(yields_to(&stand_in, &created("2026-03-02T08:00:00Z")));
+        assert!(!yields_to(&namespaced, &created("2026-03-02T10:00:00Z")));
+        // Created in the same second: the ClusterRollout yields
+        assert!(yields_to(&stand_in, &created("2026-03-02T09:00:00Z")));
+        assert!(!yields_to(&namespaced, &created("2026-03-02T09:00:00Z")));
+    }
+
+    #[test]
+    fn test_cluster_rollout_crd_is_cluster_scoped() {
+        let crd = ClusterRollout::crd();
+
+        assert_eq!(crd.spec.scope, "Cluster");
+        assert_eq!(crd.spec.names.kind, "ClusterRollout");
+    }
+
+    /// Regenerate with:
+    ///   cargo run --bin gen-crd -- cluster-rollout | python3 -c "import sys,json,yaml; print(yaml.dump(json.load(sys.stdin), default_flow_style=False, sort_keys=False))" > deploy/cluster-rollout-crd.yaml
+    #[test]
+    fn test_cluster_rollout_crd_matches_deployed_yaml() {
+        let generated = serde_json::to_value(ClusterRollout::crd()).unwrap();
+        let deployed: serde_json::Value =
+            serde_yaml::from_str(include_str!("../../deploy/cluster-rollout-crd.yaml")).unwrap();
+
+        assert_eq!(
+            generated, deployed,
+            "Generated ClusterRollout CRD doesn't match deploy/cluster-rollout-crd.yaml"
+        );
+    }
+}
@@ -30,6 +30,7 @@ pub fn convert_to_v1beta1(spec: &v1alpha1::RolloutSpec) -> v1beta1::RolloutSpec
         replicas: spec.replicas,
         selector: spec.selector.clone(),
         template: spec.template.clone(),
+        workload_ref: spec.workload_ref.clone(),
         strategy: spec.strategy.clone(),
         // Use existing values if present, otherwise use defaults
         max_surge: spec
@@ -43,6 +44,10 @@ pub fn convert_to_v1beta1(spec: &v1alpha1::RolloutSpec) -> v1beta1::RolloutSpec
         progress_deadline_seconds: spec
             .progress_deadline_seconds
             .or(Some(DEFAULT_PROGRESS_DEADLINE_SECONDS)),
+        on_degraded: spec.on_degraded.clone(),
+        on_template_change: spec.on_template_change,
+        revision_history_limit: spec.revision_history_limit,
+        cleanup_policy: spec.cleanup_policy,
     }
 }
 
@@ -55,12 +60,17 @@ pub fn convert_to_v1alpha1(spec: &v1beta1::RolloutSpec) -> v1alpha1::RolloutSpec
         replicas: spec.replicas,
         selector: spec.selector.clone(),
         template: spec.template.clone(),
+        workload_ref: spec.workload_ref.clone(),
         strategy: spec.strategy.clone(),
         // Preserve v1beta1 fields to avoid data loss in round-trip conversion
         max_surge: spec.max_surge.clone(),
         max_unavailable: spec.max_unavailable.clone(),
         progress_deadline_seconds: spec.progress_deadline_seconds,
         advisor: Default::default(),
+        on_degraded: spec.on_degraded.clone(),
+        on_template_change: spec.on_template_change,
+        revision_history_limit: spec.revision_history_limit,
+        cleanup_policy: spec.cleanup_policy,
     }
 }
 
@@ -20,6 +20,11 @@ fn test_v1alpha1_to_v1beta1_adds_default_max_surge() {
         max_unavailable: None,
         progress_deadline_seconds: None,
         advisor: Default::default(),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1beta1_spec = convert_to_v1beta1(&v1alpha1_spec);
@@ -40,6 +45,11 @@ fn test_v1alpha1_to_v1beta1_adds_default_max_unavailable() {
         max_unavailable: None,
         progress_deadline_seconds: None,
         advisor: Default::default(),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1beta1_spec = convert_to_v1beta1(&v1alpha1_spec);
@@ -60,6 +70,11 @@ fn test_v1alpha1_to_v1beta1_adds_default_progress_deadline() {
         max_unavailable: None,
         progress_deadline_seconds: None,
         advisor: Default::default(),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1beta1_spec = convert_to_v1beta1(&v1alpha1_spec);
@@ -84,9 +99,19 @@ fn test_v1alpha1_to_v1beta1_preserves_existing_fields() {
                 steps: vec![v1alpha1::CanaryStep {
                     set_weight: Some(20),
                     pause: None,
+                    alert_gate: None,
+                    set_mirror: None,
+                    set_canary_scale: None,
+                    set_header_route: None,
+                    experiment: None,
+                    analysis: None,
                 }],
                 traffic_routing: None,
                 analysis: None,
+                steps_from: None,
+                gates: vec![],
+                baseline: None,
+                rollback_verification: None,
             }),
             blue_green: None,
             ab_testing: None,
@@ -95,6 +120,11 @@ fn test_v1alpha1_to_v1beta1_preserves_existing_fields() {
         max_unavailable: None,
         progress_deadline_seconds: None,
         advisor: Default::default(),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1beta1_spec = convert_to_v1beta1(&v1alpha1_spec);
@@ -125,6 +155,11 @@ fn test_v1beta1_to_v1alpha1_preserves_v1beta1_fields() {
         max_surge: Some("50%".to_string()),
         max_unavailable: Some("1".to_string()),
         progress_deadline_seconds: Some(300),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1alpha1_spec = convert_to_v1alpha1(&v1beta1_spec);
@@ -152,6 +187,10 @@ fn test_v1beta1_to_v1alpha1_preserves_existing_fields() {
                 steps: vec![],
                 traffic_routing: None,
                 analysis: None,
+                steps_from: None,
+                gates: vec![],
+                baseline: None,
+                rollback_verification: None,
             }),
             blue_green: None,
             ab_testing: None,
@@ -159,6 +198,11 @@ fn test_v1beta1_to_v1alpha1_preserves_existing_fields() {
         max_surge: Some("25%".to_string()),
         max_unavailable: Some("0".to_string()),
         progress_deadline_seconds: Some(600),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let v1alpha1_spec = convert_to_v1alpha1(&v1beta1_spec);
@@ -190,6 +234,11 @@ fn test_roundtrip_v1alpha1_to_v1beta1_to_v1alpha1() {
         max_unavailable: None,
         progress_deadline_seconds: None,
         advisor: Default::default(),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let converted = convert_to_v1beta1(&original);
@@ -211,6 +260,11 @@ fn test_roundtrip_v1beta1_to_v1alpha1_to_v1beta1() {
         max_surge: Some("50%".to_string()),
         max_unavailable: Some("2".to_string()),
         progress_deadline_seconds: Some(900),
+        on_degraded: None,
+        on_template_change: None,
+        revision_history_limit: None,
+        cleanup_policy: None,
+        workload_ref: None,
     };
 
     let converted = convert_to_v1alpha1(&original);
@@ -0,0 +1,222 @@
+//! Experiment: short-lived ReplicaSets run side by side, then analyzed
+//!
+//! An Experiment runs one ReplicaSet per template for `duration`, evaluates
+//! its analyses and deletes the ReplicaSets again. KULTA routes no traffic
+//! to them, so a baseline and a candidate of the same size can be compared
+//! without changing the stable ReplicaSet or its traffic weight.
+//!
+//! Experiments are created by hand, or by a canary step's `experiment`,
+//! which fills the templates in from the Rollout's stable and canary pod
+//! templates.
+
+use crate::crd::rollout::AnalysisConfig;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Experiment runs temporary ReplicaSets for a while and analyzes them
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[kube(
+    group = "kulta.io",
+    version = "v1alpha1",
+    kind = "Experiment",
+    namespaced,
+    status = "ExperimentStatus",
+    shortname = "exp",
+    printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
+    printcolumn = r#"{"name":"Duration", "type":"string", "jsonPath":".spec.duration"}"#,
+    printcolumn = r#"{"name":"Message", "type":"string", "jsonPath":".status.message", "priority":1}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+pub struct ExperimentSpec {
+    /// ReplicaSets to run, one per template
+    pub templates: Vec<ExperimentTemplate>,
+
+    /// How long the ReplicaSets run once all are ready (e.g., "10m")
+    pub duration: String,
+
+    /// Analyses evaluated when `duration` is over; all must pass
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub analyses: Vec<ExperimentAnalysis>,
+}
+
+/// One ReplicaSet of an Experiment
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ExperimentTemplate {
+    /// Unique within the Experiment; the ReplicaSet is `{experiment}-{name}`
+    pub name: String,
+
+    /// Number of pods (default 1)
+    #[serde(default = "default_experiment_replicas")]
+    pub replicas: i32,
+
+    /// Pod template of the ReplicaSet
+    pub template: PodTemplateSpec,
+}
+
+fn default_experiment_replicas() -> i32 {
+    1
+}
+
+/// Named analysis of an Experiment
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ExperimentAnalysis {
+    /// Analysis name, reported in status
+    pub name: String,
+
+    /// Metrics and Prometheus settings, as in a strategy's `analysis`
+    #[serde(flatten)]
+    pub analysis: AnalysisConfig,
+}
+
+/// Lifecycle of an Experiment
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum ExperimentPhase {
+    /// ReplicaSets are being created and scaled up
+    Pending,
+    /// All ReplicaSets are ready; `duration` is running
+    Running,
+    /// All analyses passed
+    Successful,
+    /// An analysis failed, or the ReplicaSets never became ready
+    Failed,
+}
+
+impl ExperimentPhase {
+    /// Whether the Experiment is over (its ReplicaSets are deleted)
+    pub fn is_finished(self) -> bool {
+        matches!(self, ExperimentPhase::Successful | ExperimentPhase::Failed)
+    }
+}
+
+/// Status of an Experiment
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ExperimentStatus {
+    /// Current phase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<ExperimentPhase>,
+
+    /// Human-readable details of the phase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// When all ReplicaSets were ready (RFC3339); `duration` counts from here
+    #[serde(rename = "availableAt", skip_serializing_if = "Option::is_none")]
+    pub available_at: Option<String>,
+
+    /// When the Experiment succeeded or failed (RFC3339)
+    #[serde(rename = "finishedAt", skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+
+    /// ReplicaSet of each template
+    #[serde(
+        rename = "templateStatuses",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub template_statuses: Vec<ExperimentTemplateStatus>,
+
+    /// Verdict of each analysis, once evaluated
+    #[serde(
+        rename = "analysisResults",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub analysis_results: Vec<ExperimentAnalysisResult>,
+}
+
+/// ReplicaSet of an Experiment template
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ExperimentTemplateStatus {
+    /// Template name
+    pub name: String,
+
+    /// Name of the template's ReplicaSet
+    #[serde(rename = "replicaSet")]
+    pub replica_set: String,
+
+    /// Desired pods
+    pub replicas: i32,
+
+    /// Ready pods
+    #[serde(rename = "readyReplicas")]
+    pub ready_replicas: i32,
+}
+
+/// Verdict of an Experiment analysis
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ExperimentAnalysisResult {
+    /// Analysis name
+    pub name: String,
+
+    /// Whether the analysis passed
+    pub passed: bool,
+
+    /// Why it failed, when it couldn't be evaluated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use kube::CustomResourceExt;
+
+    #[test]
+    fn test_experiment_deserializes_flattened_analyses() {
+        let experiment: Experiment = serde_yaml::from_str(
+            r#"
+apiVersion: kulta.io/v1alpha1
+kind: Experiment
+metadata:
+  name: checkout-baseline
+  namespace: shop
+spec:
+  duration: 10m
+  templates:
+  - name: baseline
+    template:
+      spec:
+        containers:
+        - name: app
+          image: checkout:1.4
+  - name: candidate
+    replicas: 2
+    template:
+      spec:
+        containers:
+        - name: app
+          image: checkout:1.5
+  analyses:
+  - name: errors
+    prometheus:
+      address: http://prometheus:9090
+    metrics:
+    - name: error-rate
+      threshold: 1.0
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(experiment.spec.templates[0].replicas, 1);
+        assert_eq!(experiment.spec.templates[1].replicas, 2);
+        let analysis = &experiment.spec.analyses[0];
+        assert_eq!(analysis.name, "errors");
+        assert_eq!(analysis.analysis.metrics[0].name, "error-rate");
+        assert!(analysis.analysis.prometheus.is_some());
+    }
+
+    #[test]
+    fn test_experiment_crd_is_namespaced() {
+        let crd = Experiment::crd();
+
+        assert_eq!(crd.spec.scope, "Namespaced");
+        assert_eq!(crd.spec.names.kind, "Experiment");
+        assert_eq!(
+            crd.spec.names.short_names.as_deref(),
+            Some(&["exp".to_string()][..])
+        );
+    }
+}
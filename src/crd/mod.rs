@@ -1,4 +1,8 @@
+pub mod argo;
+pub mod builder;
+pub mod cluster_rollout;
 pub mod conversion;
+pub mod experiment;
 pub mod rollout;
 pub mod v1alpha1;
 pub mod v1beta1;
@@ -1,3 +1,6 @@
+use crate::controller::cdevents_data::StrategyKind;
+use crate::controller::promotion_policy::PromotionMode;
+use crate::crd::experiment::{ExperimentAnalysis, ExperimentPhase};
 use k8s_openapi::api::core::v1::PodTemplateSpec;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::CustomResource;
@@ -14,11 +17,20 @@ use serde::{Deserialize, Serialize};
     kind = "Rollout",
     namespaced,
     status = "RolloutStatus",
-    printcolumn = r#"{"name":"Desired", "type":"integer", "jsonPath":".spec.replicas"}"#,
-    printcolumn = r#"{"name":"Current", "type":"integer", "jsonPath":".status.replicas"}"#,
-    printcolumn = r#"{"name":"Ready", "type":"integer", "jsonPath":".status.readyReplicas"}"#,
+    shortname = "ro",
+    shortname = "kulta",
+    category = "all",
+    selectable = ".status.phase",
+    selectable = ".status.strategy",
+    scale = r#"{"specReplicasPath":".spec.replicas", "statusReplicasPath":".status.replicas", "labelSelectorPath":".status.selector"}"#,
+    printcolumn = r#"{"name":"Desired", "type":"integer", "jsonPath":".spec.replicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Current", "type":"integer", "jsonPath":".status.replicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Ready", "type":"integer", "jsonPath":".status.readyReplicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Strategy", "type":"string", "jsonPath":".status.strategy"}"#,
     printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
     printcolumn = r#"{"name":"Weight", "type":"integer", "jsonPath":".status.currentWeight"}"#,
+    printcolumn = r#"{"name":"Summary", "type":"string", "jsonPath":".status.summary"}"#,
+    printcolumn = r#"{"name":"Message", "type":"string", "jsonPath":".status.message", "priority":1}"#,
     printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
 )]
 pub struct RolloutSpec {
@@ -29,9 +41,14 @@ pub struct RolloutSpec {
     /// Label selector for pods
     pub selector: LabelSelector,
 
-    /// Template describes the pods that will be created
+    /// Template describes the pods that will be created (omitted with `workloadRef`)
+    #[serde(default)]
     pub template: PodTemplateSpec,
 
+    /// Existing Deployment whose pod template the Rollout uses instead of `template`
+    #[serde(rename = "workloadRef", skip_serializing_if = "Option::is_none")]
+    pub workload_ref: Option<WorkloadRef>,
+
     /// Deployment strategy (currently only canary)
     pub strategy: RolloutStrategy,
 
@@ -59,12 +76,36 @@ pub struct RolloutSpec {
     /// AI advisor configuration for progressive AI adoption
     #[serde(default, skip_serializing_if = "is_default_advisor_config")]
     pub advisor: AdvisorConfig,
+
+    /// What to do when the new revision's pods can't become ready
+    /// (crash loops, image pull errors, exhausted quota)
+    #[serde(rename = "onDegraded", skip_serializing_if = "Option::is_none")]
+    pub on_degraded: Option<DegradedPolicy>,
+
+    /// What happens when `template` changes while a revision is still being
+    /// rolled out: replace (default), queue or reject
+    #[serde(rename = "onTemplateChange", skip_serializing_if = "Option::is_none")]
+    pub on_template_change: Option<TemplateChangePolicy>,
+
+    /// Number of old revisions kept as scaled-down ReplicaSets for rollback.
+    /// Defaults to 10 when not specified.
+    #[serde(
+        rename = "revisionHistoryLimit",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub revision_history_limit: Option<i32>,
+
+    /// What happens to auxiliary objects created for the Rollout (e.g. the
+    /// NGINX canary Ingress) once they are unused. Defaults to Delete.
+    #[serde(rename = "cleanupPolicy", skip_serializing_if = "Option::is_none")]
+    pub cleanup_policy: Option<CleanupPolicy>,
 }
 
 fn is_default_advisor_config(c: &AdvisorConfig) -> bool {
     c.level == AdvisorLevel::Off
         && c.endpoint.is_none()
         && c.timeout_seconds == DEFAULT_ADVISOR_TIMEOUT_SECONDS
+        && c.min_confidence.is_none()
 }
 
 fn default_replicas() -> i32 {
@@ -141,6 +182,31 @@ pub struct BlueGreenStrategy {
     /// Analysis configuration for automated metrics-based rollback
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analysis: Option<AnalysisConfig>,
+
+    /// Strategy this Rollout was migrated from (set by `kulta.io/migrate-strategy`)
+    ///
+    /// `canary` keeps the canary's ReplicaSets: `{name}-stable` serves as the
+    /// active environment and `{name}-canary` as the preview, so the Services
+    /// selecting their pods keep working.
+    #[serde(rename = "migratedFrom", skip_serializing_if = "Option::is_none")]
+    pub migrated_from: Option<MigratedFrom>,
+}
+
+impl BlueGreenStrategy {
+    /// `rollouts.kulta.io/type` of the active and preview ReplicaSets
+    pub fn replicaset_types(&self) -> (&'static str, &'static str) {
+        match self.migrated_from {
+            Some(MigratedFrom::Canary) => ("stable", "canary"),
+            None => ("active", "preview"),
+        }
+    }
+}
+
+/// Strategy a blue-green Rollout was migrated from
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MigratedFrom {
+    Canary,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -161,6 +227,10 @@ pub struct CanaryStrategy {
     #[serde(default)]
     pub steps: Vec<CanaryStep>,
 
+    /// Import the steps from a ConfigMap instead of listing them (excludes `steps`)
+    #[serde(rename = "stepsFrom", skip_serializing_if = "Option::is_none")]
+    pub steps_from: Option<StepsFrom>,
+
     /// Traffic routing configuration
     #[serde(rename = "trafficRouting", skip_serializing_if = "Option::is_none")]
     pub traffic_routing: Option<TrafficRouting>,
@@ -168,6 +238,129 @@ pub struct CanaryStrategy {
     /// Analysis configuration for automated metrics-based rollback
     #[serde(skip_serializing_if = "Option::is_none")]
     pub analysis: Option<AnalysisConfig>,
+
+    /// Dependencies that must be healthy before each canary weight increase
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gates: Vec<DependencyGate>,
+
+    /// Run a fresh baseline ReplicaSet from the stable template next to the
+    /// canary, for metrics with `compareToBaseline`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<CanaryBaseline>,
+
+    /// Measure the stable revision against the analysis thresholds after a
+    /// rollback, to tell a bad canary from a system that is unhealthy anyway
+    #[serde(
+        rename = "rollbackVerification",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rollback_verification: Option<RollbackVerification>,
+}
+
+/// Fresh baseline ReplicaSet of a canary
+///
+/// `{rollout}-baseline` runs the stable pod template, by default with as
+/// many pods as the canary, while a rollout is in flight. Old stable pods
+/// (warm caches, long-lived connections) don't compare fairly with a
+/// brand-new canary; a baseline started at the same time does.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct CanaryBaseline {
+    /// Fixed number of baseline pods (default: as many as the canary)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<i32>,
+}
+
+/// Verification of the stable revision after a canary rollback
+///
+/// `delay` after the rollback, the metrics of `canary.analysis` are
+/// measured once against the stable revision. Comparisons with the stable
+/// revision or the baseline ReplicaSet are skipped.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct RollbackVerification {
+    /// Time for the stable revision to serve all traffic before it is
+    /// measured (e.g., "2m", the default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<String>,
+}
+
+/// Dependency that must be healthy before the canary takes more traffic
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DependencyGate {
+    /// Name of the Deployment or Rollout to check (e.g. a database proxy)
+    pub service: String,
+
+    /// Namespace of the dependency (default: the Rollout's namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Kind of the dependency
+    #[serde(default)]
+    pub kind: DependencyKind,
+
+    /// Deployment condition that must be True (e.g. "Available"), or the Rollout phase required (e.g. "Completed")
+    pub condition: String,
+}
+
+/// Kind of workload a dependency gate checks
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum DependencyKind {
+    /// apps/v1 Deployment, gated on `status.conditions`
+    #[default]
+    Deployment,
+    /// KULTA Rollout, gated on `status.phase`
+    Rollout,
+}
+
+/// Existing workload adopted by a Rollout
+///
+/// The controller reads the pod template from the workload on every
+/// reconcile and scales the workload down once the Rollout has taken over.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WorkloadRef {
+    /// Kind of the workload
+    #[serde(default)]
+    pub kind: WorkloadKind,
+
+    /// Name of the workload in the Rollout's namespace
+    pub name: String,
+
+    /// When to scale the workload down
+    #[serde(rename = "scaleDown", default)]
+    pub scale_down: WorkloadScaleDown,
+}
+
+/// Kind of workload a Rollout can adopt
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum WorkloadKind {
+    /// apps/v1 Deployment
+    #[default]
+    Deployment,
+}
+
+/// Cleanup of auxiliary objects KULTA creates for a Rollout
+///
+/// Auxiliary objects are everything besides ReplicaSets and the HTTPRoute
+/// rules KULTA writes; today that is the NGINX canary Ingress. They carry
+/// the `rollouts.kulta.io/managed` label and an owner reference to the Rollout.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum CleanupPolicy {
+    /// Delete objects the Rollout no longer uses once it completes, and all
+    /// of them with the Rollout
+    #[default]
+    Delete,
+    /// Keep them: nothing is deleted on completion, and deleting the Rollout
+    /// releases them (owner reference and managed label removed)
+    Retain,
+}
+
+/// When an adopted workload is scaled down
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum WorkloadScaleDown {
+    /// Scale to zero once the Rollout first reaches Completed
+    #[default]
+    OnSuccess,
+    /// Leave the workload's replica count alone
+    Never,
 }
 
 /// A/B Testing deployment strategy
@@ -276,6 +469,94 @@ pub struct ABAnalysisConfig {
     /// Statistical confidence level (default: 0.95)
     #[serde(rename = "confidenceLevel", skip_serializing_if = "Option::is_none")]
     pub confidence_level: Option<f64>,
+
+    /// Stop-loss metrics for variant B, checked on every reconcile
+    ///
+    /// A breach concludes the experiment at once with variant A as the
+    /// winner, regardless of minDuration, minSampleSize or significance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guardrails: Vec<ABGuardrail>,
+
+    /// Only count samples taken inside this time window (e.g. business hours)
+    #[serde(rename = "sampleWindow", skip_serializing_if = "Option::is_none")]
+    pub sample_window: Option<SampleWindow>,
+}
+
+/// Recurring time window that A/B samples must fall into
+///
+/// Samples and error rates are summed over `lookback` in 5 minute buckets,
+/// keeping only the buckets inside the window.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SampleWindow {
+    /// Days to include (default: every day)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+
+    /// First hour of the window, inclusive (0-23, default: 0)
+    #[serde(rename = "startHour", skip_serializing_if = "Option::is_none")]
+    pub start_hour: Option<u32>,
+
+    /// Hour the window ends, exclusive (1-24, default: 24)
+    #[serde(rename = "endHour", skip_serializing_if = "Option::is_none")]
+    pub end_hour: Option<u32>,
+
+    /// Offset of the window's local time from UTC in hours (default: 0)
+    #[serde(rename = "utcOffsetHours", skip_serializing_if = "Option::is_none")]
+    pub utc_offset_hours: Option<i32>,
+
+    /// How far back samples are counted, e.g. "7d" (default: since the experiment started)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lookback: Option<String>,
+}
+
+/// Day of the week
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// PromQL `day_of_week()` value (0 = Sunday)
+    pub fn promql_day(self) -> u32 {
+        match self {
+            Weekday::Sun => 0,
+            Weekday::Mon => 1,
+            Weekday::Tue => 2,
+            Weekday::Wed => 3,
+            Weekday::Thu => 4,
+            Weekday::Fri => 5,
+            Weekday::Sat => 6,
+        }
+    }
+}
+
+/// Stop-loss limit on a variant B metric
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ABGuardrail {
+    /// Metric template: error-rate (ratio, 0.05 = 5%) or latency-p95 (seconds)
+    pub name: String,
+
+    /// Limit for variant B's value
+    pub threshold: f64,
+
+    /// How the value must compare to the threshold to stay within the guardrail (default: lt)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<MetricComparison>,
+}
+
+impl ABGuardrail {
+    /// Whether a variant B value stays within the guardrail
+    pub fn holds(&self, value: f64) -> bool {
+        self.comparison
+            .unwrap_or_default()
+            .holds(value, self.threshold)
+    }
 }
 
 /// Metric configuration for A/B comparison
@@ -305,6 +586,29 @@ pub enum ABMetricDirection {
     Higher,
 }
 
+/// Source of a shared step ladder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StepsFrom {
+    /// ConfigMap holding the steps
+    #[serde(rename = "configMapRef")]
+    pub config_map_ref: StepsConfigMapRef,
+}
+
+/// ConfigMap key holding a YAML list of canary steps
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StepsConfigMapRef {
+    /// ConfigMap name
+    pub name: String,
+
+    /// ConfigMap namespace (default: the Rollout's namespace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Key holding the steps (default: "steps")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct CanaryStep {
     /// Set the percentage of traffic to route to canary
@@ -313,15 +617,245 @@ pub struct CanaryStep {
 
     /// Pause the rollout
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pause: Option<PauseDuration>,
+    pub pause: Option<Pause>,
+
+    /// Duplicate this percentage of requests to the canary service while at this step
+    ///
+    /// Mirrored responses are discarded, so users only see the stable version.
+    /// Requires Gateway API traffic routing with an HTTPRoute.
+    #[serde(rename = "setMirror", skip_serializing_if = "Option::is_none")]
+    pub set_mirror: Option<i32>,
+
+    /// Size the canary independently of the traffic weight from this step on
+    ///
+    /// Applies until a later step sets `setCanaryScale` again.
+    #[serde(rename = "setCanaryScale", skip_serializing_if = "Option::is_none")]
+    pub set_canary_scale: Option<SetCanaryScale>,
+
+    /// Route requests carrying these headers to the canary from this step on,
+    /// whatever the weight
+    ///
+    /// Applies until a later step sets `setHeaderRoute` again; an empty
+    /// `match` removes the route. Requires Gateway API traffic routing with
+    /// an HTTPRoute.
+    #[serde(rename = "setHeaderRoute", skip_serializing_if = "Option::is_none")]
+    pub set_header_route: Option<SetHeaderRoute>,
+
+    /// Hold at this step while matching Alertmanager alerts are firing
+    #[serde(rename = "alertGate", skip_serializing_if = "Option::is_none")]
+    pub alert_gate: Option<AlertGate>,
+
+    /// Analysis evaluated only while this step is active, in addition to
+    /// the strategy's analysis
+    ///
+    /// A failing measurement rolls back; the step doesn't advance before
+    /// its analysis has passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<StepAnalysis>,
+
+    /// Experiment launched when this step starts
+    ///
+    /// The step doesn't advance before the Experiment has succeeded; a
+    /// failed one rolls back.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experiment: Option<ExperimentStep>,
+}
+
+/// Canary replica count of a `setCanaryScale` step
+///
+/// Exactly one of `replicas`, `weight` and `matchTrafficWeight` is set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SetCanaryScale {
+    /// Fixed number of canary replicas (capped at `spec.replicas`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<i32>,
+
+    /// Canary replicas as a percentage of `spec.replicas` (rounded up)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<i32>,
+
+    /// Size the canary by the traffic weight again (the default behaviour)
+    #[serde(rename = "matchTrafficWeight", skip_serializing_if = "Option::is_none")]
+    pub match_traffic_weight: Option<bool>,
+}
+
+/// Header match of a `setHeaderRoute` step
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct SetHeaderRoute {
+    /// Headers a request must all carry to reach the canary (e.g., X-Canary: internal)
+    #[serde(rename = "match", default)]
+    pub matches: Vec<ABHeaderMatch>,
+}
+
+/// Inline analysis of a canary step
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct StepAnalysis {
+    /// Metrics that must pass at this step (prometheus settings and
+    /// `revisionLabelKey` come from the strategy's analysis)
+    pub metrics: Vec<MetricConfig>,
+
+    /// How long the step is analyzed before it may advance (e.g., "10m")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+
+    /// Passing measurements needed before the step may advance (default 1),
+    /// spread evenly over `duration`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i32>,
 }
 
+/// Experiment launched by a canary step
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
-pub struct PauseDuration {
+pub struct ExperimentStep {
+    /// ReplicaSets to run, each from the Rollout's stable or canary pod template
+    pub templates: Vec<ExperimentStepTemplate>,
+
+    /// How long the ReplicaSets run once all are ready (e.g., "10m")
+    pub duration: String,
+
+    /// Analyses evaluated when `duration` is over; all must pass
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub analyses: Vec<ExperimentAnalysis>,
+}
+
+/// ReplicaSet of a step's Experiment
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ExperimentStepTemplate {
+    /// Unique within the Experiment; the ReplicaSet is `{experiment}-{name}`
+    pub name: String,
+
+    /// Pod template the ReplicaSet runs
+    #[serde(rename = "specRef")]
+    pub spec_ref: ExperimentSpecRef,
+
+    /// Number of pods (default 1)
+    #[serde(default = "default_experiment_step_replicas")]
+    pub replicas: i32,
+}
+
+fn default_experiment_step_replicas() -> i32 {
+    1
+}
+
+/// Rollout pod template an experiment ReplicaSet runs
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExperimentSpecRef {
+    /// The template of the stable ReplicaSet
+    Stable,
+    /// `spec.template`, the revision being rolled out
+    Canary,
+}
+
+/// Gate that blocks step progression while matching alerts are firing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AlertGate {
+    /// Label matchers selecting the alerts to watch (all must match, e.g. alertname, service)
+    pub matchers: std::collections::BTreeMap<String, String>,
+
+    /// Alertmanager base URL (defaults to KULTA_ALERTMANAGER_ADDRESS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// What to do when Alertmanager cannot be queried
+    #[serde(rename = "onError", default)]
+    pub on_error: AlertGateErrorPolicy,
+}
+
+/// What an alert gate does when Alertmanager is unreachable
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum AlertGateErrorPolicy {
+    /// Hold the step until Alertmanager answers (default, safest)
+    #[default]
+    Block,
+    /// Let the step proceed as if no alerts were firing
+    Ignore,
+}
+
+/// How a step pauses
+///
+/// Written as `{ duration: "5m" }`, `{ until: "<RFC3339>" }`,
+/// `{ untilApproved: true }` or `{}`; at most one field may be set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(try_from = "PauseFields", into = "PauseFields")]
+pub enum Pause {
+    /// Resume once the duration has elapsed (e.g., "30s", "5m")
+    Duration(String),
+    /// Resume at a point in time (RFC3339)
+    UntilTime(String),
+    /// Resume on `kulta.io/approved-by`; `kulta.io/promote` doesn't count
+    UntilApproved,
+    /// Resume on `kulta.io/promote`
+    Indefinite,
+}
+
+/// Wire shape of [`Pause`]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+struct PauseFields {
     /// Duration in seconds (e.g., "30s", "5m")
     /// If not specified, pauses indefinitely until manually resumed
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration: Option<String>,
+    duration: Option<String>,
+
+    /// Resume at this time (RFC3339), e.g. after a business-hours cutoff
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<String>,
+
+    /// Wait for an explicit sign-off via the `kulta.io/approved-by` annotation
+    ///
+    /// `kulta.io/promote` doesn't resume this pause. The approver is recorded
+    /// in the status message.
+    #[serde(rename = "untilApproved", skip_serializing_if = "Option::is_none")]
+    until_approved: Option<bool>,
+}
+
+impl TryFrom<PauseFields> for Pause {
+    type Error = String;
+
+    fn try_from(fields: PauseFields) -> Result<Self, Self::Error> {
+        // An explicit `untilApproved: false` counts as a field, like the
+        // schema's `maxProperties: 1`
+        match (fields.duration, fields.until, fields.until_approved) {
+            (Some(duration), None, None) => Ok(Pause::Duration(duration)),
+            (None, Some(until), None) => Ok(Pause::UntilTime(until)),
+            (None, None, Some(true)) => Ok(Pause::UntilApproved),
+            (None, None, None | Some(false)) => Ok(Pause::Indefinite),
+            _ => Err("only one of duration, until and untilApproved may be set".to_string()),
+        }
+    }
+}
+
+impl From<Pause> for PauseFields {
+    fn from(pause: Pause) -> Self {
+        match pause {
+            Pause::Duration(duration) => PauseFields {
+                duration: Some(duration),
+                ..Default::default()
+            },
+            Pause::UntilTime(until) => PauseFields {
+                until: Some(until),
+                ..Default::default()
+            },
+            Pause::UntilApproved => PauseFields {
+                until_approved: Some(true),
+                ..Default::default()
+            },
+            Pause::Indefinite => PauseFields::default(),
+        }
+    }
+}
+
+// The CRD schema is the wire shape; the API server rejects several fields
+impl JsonSchema for Pause {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Pause".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let mut schema = PauseFields::json_schema(generator);
+        schema.insert("maxProperties".to_string(), 1.into());
+        schema
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -329,13 +863,220 @@ pub struct TrafficRouting {
     /// Gateway API configuration (KULTA-specific)
     #[serde(rename = "gatewayAPI", skip_serializing_if = "Option::is_none")]
     pub gateway_api: Option<GatewayAPIRouting>,
+
+    /// No traffic router: a plain Service selects both stable and canary pods
+    ///
+    /// Experimental, canary only. Mutually exclusive with gatewayAPI.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub none: Option<NoTrafficRouting>,
+
+    /// NGINX Ingress canary annotations
+    ///
+    /// Canary only. Mutually exclusive with gatewayAPI and none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nginx: Option<NginxTrafficRouting>,
+}
+
+/// Traffic splitting through the NGINX Ingress controller
+///
+/// KULTA manages a canary Ingress, `{rollout}-{stableIngress}-canary`, copied
+/// from the stable Ingress with the stable service replaced by the canary
+/// service, and sets its `canary-weight` annotation to the step weight.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NginxTrafficRouting {
+    /// Existing Ingress that routes to the stable service
+    #[serde(rename = "stableIngress")]
+    pub stable_ingress: String,
+
+    /// Annotation prefix of the NGINX Ingress controller (default: "nginx.ingress.kubernetes.io")
+    #[serde(rename = "annotationPrefix", skip_serializing_if = "Option::is_none")]
+    pub annotation_prefix: Option<String>,
+}
+
+/// Traffic routing without a gateway or mesh
+///
+/// With `replicaWeighting`, the canary weight is approximated by the share of
+/// ready endpoints: the stable and canary ReplicaSets are sized so that
+/// canary / replicas is as close to the step weight as possible, without surge.
+/// Accuracy is limited to 100 / replicas percent (e.g., 3 replicas can only
+/// express 0%, 33%, 67% and 100%), and kube-proxy balances connections, not
+/// requests, so long-lived connections skew the split further.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct NoTrafficRouting {
+    /// Approximate canary weight through replica counts
+    #[serde(rename = "replicaWeighting", skip_serializing_if = "Option::is_none")]
+    pub replica_weighting: Option<bool>,
+}
+
+impl TrafficRouting {
+    /// Whether canary weight is approximated by replica counts (no traffic router)
+    pub fn uses_replica_weighting(&self) -> bool {
+        self.none
+            .as_ref()
+            .and_then(|none| none.replica_weighting)
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct GatewayAPIRouting {
+    /// Kind of the routes named by `httpRoute`/`httpRoutes` (default: HTTPRoute)
+    ///
+    /// TLSRoute (SNI passthrough) and UDPRoute carry weighted backends for
+    /// services that handle TLS or UDP themselves; canary and blue-green only.
+    #[serde(default)]
+    pub kind: GatewayRouteKind,
+
     /// Name of the HTTPRoute to manipulate
+    #[serde(
+        rename = "httpRoute",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub http_route: String,
+
+    /// HTTPRoutes that all receive the same weights (e.g., internal and external gateways)
+    ///
+    /// Can be used instead of, or in addition to, `httpRoute`.
+    #[serde(rename = "httpRoutes", default, skip_serializing_if = "Vec::is_empty")]
+    pub http_routes: Vec<String>,
+
+    /// Backend weights the gateway implementation can honour
+    ///
+    /// Step weights are checked against these at admission; with `normalize`
+    /// they are rounded to the nearest supported weight instead.
+    #[serde(rename = "weightConstraints", skip_serializing_if = "Option::is_none")]
+    pub weight_constraints: Option<WeightConstraints>,
+
+    /// Route rules that receive the weights, per HTTPRoute
+    ///
+    /// A route's rules are normally replaced by the weighted rule. On a route
+    /// listed here only the rule named `sectionName` is updated, so other
+    /// rules (e.g., the internal listener's) keep routing to stable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<RouteSection>,
+}
+
+/// Rule of an HTTPRoute that receives the canary weights
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RouteSection {
+    /// HTTPRoute (one of `httpRoute`/`httpRoutes`)
     #[serde(rename = "httpRoute")]
     pub http_route: String,
+
+    /// `name` of the rule whose backends are weighted
+    ///
+    /// Its matches and other settings are kept; the step's mirror filter and
+    /// header route apply to it alone.
+    #[serde(rename = "sectionName")]
+    pub section_name: String,
+}
+
+/// Gateway API route kind whose backend weights are managed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum GatewayRouteKind {
+    /// gateway.networking.k8s.io/v1 HTTPRoute
+    #[default]
+    HTTPRoute,
+    /// gateway.networking.k8s.io/v1alpha2 TLSRoute (SNI passthrough)
+    TLSRoute,
+    /// gateway.networking.k8s.io/v1alpha2 UDPRoute
+    UDPRoute,
+}
+
+/// Weight limits of a gateway implementation
+///
+/// KULTA writes backend weights as whole percentages (stable + canary = 100),
+/// so ranges like 1-256 or 0-1000 always fit. What varies between proxies is
+/// the resolution: some only split in coarse increments, some drop backends
+/// with very small weights.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct WeightConstraints {
+    /// Weights must be multiples of this (must divide 100, e.g., 5 or 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granularity: Option<i32>,
+
+    /// Smallest non-zero weight either backend may get (1-50)
+    #[serde(rename = "minWeight", skip_serializing_if = "Option::is_none")]
+    pub min_weight: Option<i32>,
+
+    /// Round unsupported step weights to the nearest supported weight
+    /// instead of rejecting the Rollout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize: Option<bool>,
+}
+
+impl GatewayAPIRouting {
+    /// All managed HTTPRoute names: `httpRoute` first, then `httpRoutes`, without duplicates
+    pub fn route_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in std::iter::once(&self.http_route).chain(&self.http_routes) {
+            if !name.is_empty() && !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Rule of `route` that receives the weights, if limited to one
+    pub fn section_name(&self, route: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|section| section.http_route == route)
+            .map(|section| section.section_name.as_str())
+    }
+}
+
+/// Reaction to infrastructure-level degradation of the new revision
+///
+/// The new revision is degraded while its ReplicaSet has fewer ready pods than
+/// desired or reports a `ReplicaFailure` condition (e.g., quota exceeded).
+/// The action fires once the degradation has lasted `afterSeconds`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DegradedPolicy {
+    /// Action once the grace period has passed (default: Rollback)
+    #[serde(default)]
+    pub action: DegradedAction,
+
+    /// Grace period in seconds before acting (default: 300)
+    #[serde(rename = "afterSeconds", skip_serializing_if = "Option::is_none")]
+    pub after_seconds: Option<i32>,
+}
+
+/// Default grace period for `onDegraded` when `afterSeconds` is not set
+pub const DEFAULT_DEGRADED_AFTER_SECONDS: i32 = 300;
+
+impl DegradedPolicy {
+    /// Grace period in seconds (falls back to the default)
+    pub fn after_seconds(&self) -> i32 {
+        self.after_seconds.unwrap_or(DEFAULT_DEGRADED_AFTER_SECONDS)
+    }
+}
+
+/// Action taken when the new revision stays degraded
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum DegradedAction {
+    /// Mark the rollout Failed, like a metrics breach (default)
+    #[default]
+    Rollback,
+    /// Hold at the current step until the pods recover (or `kulta.io/promote`)
+    Pause,
+    /// Only report the degradation in status
+    None,
+}
+
+/// Handling of a pod template change while a revision is in flight
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateChangePolicy {
+    /// Abandon the in-flight revision and start the new template from the
+    /// first step (default)
+    #[default]
+    Replace,
+    /// Finish the in-flight revision, then roll out the new template
+    Queue,
+    /// Deny the update in the admission webhook
+    Reject,
 }
 
 /// What to do when Prometheus is unreachable during analysis
@@ -351,7 +1092,7 @@ pub enum FailurePolicy {
 }
 
 /// Analysis configuration for automated rollback based on metrics
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct AnalysisConfig {
     /// Prometheus configuration
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -368,25 +1109,220 @@ pub struct AnalysisConfig {
     /// List of metrics to monitor
     #[serde(default)]
     pub metrics: Vec<MetricConfig>,
+
+    /// Per-zone breakdown: a threshold breach in any single zone fails the analysis
+    #[serde(rename = "perZone", skip_serializing_if = "Option::is_none")]
+    pub per_zone: Option<ZoneAnalysisConfig>,
+
+    /// Prometheus label the built-in templates select the revision by
+    ///
+    /// Unset, they match `revision="canary"`. Set, they match
+    /// `<key>="<pod-template-hash>"`, the value of the `kulta.io/revision`
+    /// pod label, for scrape configs that relabel it into `<key>`.
+    #[serde(rename = "revisionLabelKey", skip_serializing_if = "Option::is_none")]
+    pub revision_label_key: Option<String>,
+
+    /// How metric results combine into the verdict (default: all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregation: Option<AnalysisAggregation>,
+
+    /// Weighted share of passing metrics (0.0-1.0) needed with `aggregation: score`
+    #[serde(rename = "passScore", skip_serializing_if = "Option::is_none")]
+    pub pass_score: Option<f64>,
+
+    /// What resuming from a manual pause does to the analysis (default: Continue)
+    ///
+    /// A pause is resumed manually by `kulta.io/promote` or
+    /// `kulta.io/approved-by`. Only used in `canary.analysis`.
+    #[serde(rename = "onResume", skip_serializing_if = "Option::is_none")]
+    pub on_resume: Option<AnalysisOnResume>,
+}
+
+impl AnalysisConfig {
+    /// Weighted share of passing metrics, from 0.0 to 1.0
+    ///
+    /// `results` line up with `metrics`. Without any weight, the score is 1.0.
+    pub fn score(&self, results: &[bool]) -> f64 {
+        let (passed, total) =
+            self.metrics
+                .iter()
+                .zip(results)
+                .fold((0.0, 0.0), |(passed, total), (metric, ok)| {
+                    let weight = metric.weight();
+                    (if *ok { passed + weight } else { passed }, total + weight)
+                });
+        if total > 0.0 {
+            passed / total
+        } else {
+            1.0
+        }
+    }
+
+    /// Verdict from per-metric results (lined up with `metrics`)
+    pub fn is_healthy(&self, results: &[bool]) -> bool {
+        match self.aggregation.unwrap_or_default() {
+            AnalysisAggregation::All => results.iter().all(|ok| *ok),
+            AnalysisAggregation::Any => results.is_empty() || results.iter().any(|ok| *ok),
+            AnalysisAggregation::Score => self.score(results) >= self.pass_score.unwrap_or(1.0),
+        }
+    }
+}
+
+/// Analysis state after a rollout resumes from a manual pause
+///
+/// Analysis keeps running while a step waits for promotion or approval, so
+/// by the time someone resumes, warmup has long passed and metrics may have
+/// failed below their `failureThreshold`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum AnalysisOnResume {
+    /// Keep the analysis as it was: warmup isn't repeated, consecutive
+    /// failures in `status.metricFailures` carry over to the next step
+    #[default]
+    Continue,
+    /// Start over: warmup counts again from the resume (`stepStartTime`),
+    /// and `status.metricFailures` is cleared
+    Reset,
+}
+
+/// How metric results combine into the analysis verdict
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalysisAggregation {
+    /// Every metric must pass
+    #[default]
+    All,
+    /// At least one metric must pass
+    Any,
+    /// The weighted share of passing metrics must reach `passScore`
+    Score,
+}
+
+/// Failure-domain aware analysis
+///
+/// Every metric is additionally evaluated once per zone, with the built-in
+/// queries restricted by a `<label>="<zone>"` matcher. A regression confined
+/// to one zone is then caught instead of being averaged away by healthy
+/// traffic in the others.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ZoneAnalysisConfig {
+    /// Prometheus label carrying the zone (default: "zone")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Zones to evaluate individually (e.g., ["us-east-1a", "us-east-1b"])
+    #[serde(default)]
+    pub zones: Vec<String>,
 }
 
 /// Prometheus configuration
+///
+/// With credentials or `tls` set, the analysis queries `address` (or the
+/// controller's `KULTA_PROMETHEUS_ADDRESS`) with them; Secrets are read from
+/// the Rollout's namespace and must be labelled
+/// `kulta.io/prometheus-credentials: "true"`. Otherwise the controller's
+/// client is used.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct PrometheusConfig {
     /// Prometheus server address (e.g., "http://prometheus:9090")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub address: Option<String>,
+
+    /// Secret key holding a bearer token sent with every query
+    #[serde(
+        rename = "bearerTokenSecretRef",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bearer_token_secret_ref: Option<SecretKeyRef>,
+
+    /// HTTP basic auth (excludes `bearerTokenSecretRef`)
+    #[serde(rename = "basicAuth", skip_serializing_if = "Option::is_none")]
+    pub basic_auth: Option<PrometheusBasicAuth>,
+
+    /// TLS settings for an https `address`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<PrometheusTlsConfig>,
+}
+
+impl PrometheusConfig {
+    /// Whether the analysis needs its own client (credentials or TLS settings)
+    pub fn has_client_options(&self) -> bool {
+        self.bearer_token_secret_ref.is_some() || self.basic_auth.is_some() || self.tls.is_some()
+    }
+}
+
+/// Key of a Secret in the Rollout's namespace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+pub struct SecretKeyRef {
+    /// Secret name
+    pub name: String,
+
+    /// Key within the Secret's data
+    pub key: String,
+}
+
+/// HTTP basic auth for Prometheus
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PrometheusBasicAuth {
+    pub username: String,
+
+    /// Secret key holding the password
+    #[serde(rename = "passwordSecretRef")]
+    pub password_secret_ref: SecretKeyRef,
+}
+
+/// TLS settings of the Prometheus connection
+///
+/// Certificates and keys are PEM encoded.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct PrometheusTlsConfig {
+    /// Secret key holding the CA bundle that signed the server certificate
+    #[serde(rename = "caSecretRef", skip_serializing_if = "Option::is_none")]
+    pub ca_secret_ref: Option<SecretKeyRef>,
+
+    /// Secret key holding the client certificate (mTLS, requires `keySecretRef`)
+    #[serde(rename = "certSecretRef", skip_serializing_if = "Option::is_none")]
+    pub cert_secret_ref: Option<SecretKeyRef>,
+
+    /// Secret key holding the client private key (mTLS, requires `certSecretRef`)
+    #[serde(rename = "keySecretRef", skip_serializing_if = "Option::is_none")]
+    pub key_secret_ref: Option<SecretKeyRef>,
+
+    /// Skip verification of the server certificate (testing only)
+    #[serde(rename = "insecureSkipVerify", skip_serializing_if = "Option::is_none")]
+    pub insecure_skip_verify: Option<bool>,
 }
 
 /// Metric configuration for analysis
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct MetricConfig {
-    /// Metric name/template (error-rate, latency-p95, latency-p99)
+    /// Metric name/template (error-rate, latency-p95, latency-p99), or just a
+    /// name when `query` or `web` is set
     pub name: String,
 
-    /// Threshold value (metric must be below this)
+    /// Custom PromQL query, used instead of the built-in template
+    ///
+    /// `{{rollout}}`, `{{namespace}}` and `{{revision}}` are replaced by the
+    /// rollout's name and namespace and the revision label value under
+    /// analysis. Can't be combined with `baselineOffset`, `compareToStable` or
+    /// `perZone`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Read the value from an HTTP endpoint instead of Prometheus
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web: Option<WebMetric>,
+
+    /// Threshold value (by default the metric must be below this)
     pub threshold: f64,
 
+    /// How the metric value is compared to the threshold (default: lt)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<MetricComparison>,
+
+    /// Unit the threshold is written in; the query value is scaled to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<MetricUnit>,
+
     /// Check interval (e.g., "30s", "1m")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interval: Option<String>,
@@ -398,6 +1334,196 @@ pub struct MetricConfig {
     /// Minimum sample size required for metric evaluation
     #[serde(rename = "minSampleSize", skip_serializing_if = "Option::is_none")]
     pub min_sample_size: Option<i32>,
+
+    /// Compare against the stable revision at this PromQL offset (e.g., "7d")
+    ///
+    /// When set, the comparison applies to the difference between the canary
+    /// value and the stable value at the same time of day/week in the past
+    /// (with the default `lt`, threshold is the maximum allowed increase).
+    #[serde(rename = "baselineOffset", skip_serializing_if = "Option::is_none")]
+    pub baseline_offset: Option<String>,
+
+    /// Compare against the stable revision's current value
+    ///
+    /// The comparison applies to the difference between the canary and the
+    /// stable value, as with `baselineOffset` but without the offset.
+    #[serde(rename = "compareToStable", skip_serializing_if = "Option::is_none")]
+    pub compare_to_stable: Option<bool>,
+
+    /// Compare against the baseline ReplicaSet (`canary.baseline`)
+    ///
+    /// The comparison applies to the ratio of the canary value to the
+    /// baseline value, so `threshold: 1.5` allows the canary 1.5 times the
+    /// baseline's value.
+    #[serde(rename = "compareToBaseline", skip_serializing_if = "Option::is_none")]
+    pub compare_to_baseline: Option<bool>,
+
+    /// Scale the difference to the stable revision by the request volume
+    /// behind it, so a handful of canary requests can't fail the metric
+    ///
+    /// Requires `compareToStable` or `baselineOffset`.
+    #[serde(rename = "volumeWeighting", skip_serializing_if = "Option::is_none")]
+    pub volume_weighting: Option<VolumeWeighting>,
+
+    /// Weight of this metric with `aggregation: score` (default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+}
+
+impl MetricConfig {
+    /// Weight in the analysis score (1 unless set)
+    pub fn weight(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+
+    /// Whether the metric is judged by its difference to the stable revision
+    pub fn compares_to_stable(&self) -> bool {
+        self.baseline_offset.is_some() || self.compare_to_stable == Some(true)
+    }
+
+    /// Whether a raw query value passes this metric's threshold
+    ///
+    /// The value is scaled to `unit` first. With `baselineOffset` the value is
+    /// the difference to the baseline.
+    pub fn is_healthy(&self, value: f64) -> bool {
+        let value = self.unit.unwrap_or_default().scale(value);
+        self.comparison
+            .unwrap_or_default()
+            .holds(value, self.threshold)
+    }
+}
+
+/// Request volume weighting of a comparison with the stable revision
+///
+/// Both revisions' request counts over the query window are read from
+/// `http_requests_total`. Their effective sample size
+/// `canary·stable / (canary + stable)` is dominated by the smaller one, so a
+/// busy stable revision doesn't lend a quiet canary credibility. The
+/// difference is multiplied by `sqrt(effective / fullWeightRequests)`, capped
+/// at 1.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct VolumeWeighting {
+    /// Effective request count at which the difference counts in full
+    /// (default: 1000)
+    #[serde(rename = "fullWeightRequests", skip_serializing_if = "Option::is_none")]
+    pub full_weight_requests: Option<i64>,
+}
+
+/// Effective request count at which a stable comparison counts in full
+pub const DEFAULT_FULL_WEIGHT_REQUESTS: i64 = 1000;
+
+impl VolumeWeighting {
+    /// Effective request count at which the difference counts in full
+    pub fn full_weight_requests(&self) -> i64 {
+        self.full_weight_requests
+            .unwrap_or(DEFAULT_FULL_WEIGHT_REQUESTS)
+    }
+}
+
+/// Metric value read from an arbitrary HTTP endpoint
+///
+/// The response must be JSON; `jsonPath` selects a number (or a string
+/// holding one). `{{rollout}}`, `{{namespace}}` and `{{revision}}` are
+/// replaced in `url`, `body` and header values.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WebMetric {
+    /// http:// or https:// URL to request
+    pub url: String,
+
+    /// HTTP method (default: GET)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<WebMetricMethod>,
+
+    /// Request headers (e.g., Authorization)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<WebMetricHeader>,
+
+    /// Request body (POST only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// JSONPath of the value in the response (e.g., "$.data.errorRate",
+    /// "$.results[0].value")
+    #[serde(rename = "jsonPath")]
+    pub json_path: String,
+
+    /// Request timeout (e.g., "10s", default: 10s)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+/// HTTP method of a web metric request
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum WebMetricMethod {
+    #[default]
+    #[serde(rename = "GET")]
+    Get,
+    #[serde(rename = "POST")]
+    Post,
+}
+
+/// Header sent with a web metric request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WebMetricHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// Comparison between a metric value and its threshold
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum MetricComparison {
+    /// Value must be below the threshold (e.g., error rate)
+    #[default]
+    #[serde(rename = "lt")]
+    Lt,
+    /// Value must be at most the threshold
+    #[serde(rename = "le")]
+    Le,
+    /// Value must be above the threshold (e.g., availability)
+    #[serde(rename = "gt")]
+    Gt,
+    /// Value must be at least the threshold
+    #[serde(rename = "ge")]
+    Ge,
+}
+
+impl MetricComparison {
+    pub fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            MetricComparison::Lt => value < threshold,
+            MetricComparison::Le => value <= threshold,
+            MetricComparison::Gt => value > threshold,
+            MetricComparison::Ge => value >= threshold,
+        }
+    }
+}
+
+/// Unit of a metric threshold
+///
+/// Scales the query value so the threshold can be written in a friendlier
+/// unit, e.g. `250` milliseconds for a latency measured in seconds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum MetricUnit {
+    /// Value as returned by the query (default)
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    /// Ratio scaled to percent (x100)
+    #[serde(rename = "percent")]
+    Percent,
+    /// Seconds scaled to milliseconds (x1000)
+    #[serde(rename = "milliseconds")]
+    Milliseconds,
+}
+
+impl MetricUnit {
+    pub fn scale(self, value: f64) -> f64 {
+        match self {
+            MetricUnit::None => value,
+            MetricUnit::Percent => value * 100.0,
+            MetricUnit::Milliseconds => value * 1000.0,
+        }
+    }
 }
 
 /// Phase of a Rollout
@@ -408,6 +1534,8 @@ pub enum Phase {
     /// Initial phase when rollout is being set up
     #[default]
     Initializing,
+    /// Waiting for a free slot under the controller's concurrent rollout limit
+    Pending,
     /// Rollout is actively progressing through canary steps
     Progressing,
     /// Rollout is paused waiting for manual promotion or duration
@@ -443,6 +1571,10 @@ pub enum DecisionAction {
     Resume,
     /// Rollout completed successfully
     Complete,
+    /// Canary weight set outside the step plan
+    SetWeight,
+    /// Remaining steps replaced by an advisor plan
+    Replan,
 }
 
 /// Reason for the decision
@@ -458,10 +1590,20 @@ pub enum DecisionReason {
     PauseDurationExpired,
     /// User triggered manual promotion
     ManualPromotion,
+    /// An `untilApproved` pause was approved via `kulta.io/approved-by`
+    ManualApproval,
     /// User triggered manual rollback
     ManualRollback,
+    /// Weight set via `kulta.io/set-weight`
+    ManualOverride,
+    /// Step plan proposed by the advisor
+    AdvisorPlan,
+    /// Recommendation of a Driven-level advisor
+    AdvisorRecommendation,
     /// Operation timed out
     Timeout,
+    /// New revision stayed degraded past `spec.onDegraded.afterSeconds`
+    Degraded,
     /// Initial rollout setup
     Initialization,
 }
@@ -488,12 +1630,24 @@ pub struct Decision {
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metrics: Option<std::collections::HashMap<String, MetricSnapshot>>,
+    /// Number of similar consecutive decisions compacted into this entry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Timestamp of the last compacted decision (RFC3339 format)
+    #[serde(rename = "lastTimestamp", skip_serializing_if = "Option::is_none")]
+    pub last_timestamp: Option<String>,
+    /// Approver named by `kulta.io/approved-by` (for `ManualApproval`)
+    #[serde(rename = "approvedBy", skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
 }
 
 /// Status of the Rollout
+///
+/// Optional fields marked `#[serde(default)]` without `skip_serializing_if`
+/// serialize as null when unset, so the status merge patch clears them.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct RolloutStatus {
-    /// Total number of non-terminated pods
+    /// Replica count the Rollout is scaled to (spec.replicas, read by the scale subresource)
     #[serde(default)]
     pub replicas: i32,
 
@@ -505,6 +1659,14 @@ pub struct RolloutStatus {
     #[serde(rename = "updatedReplicas", default)]
     pub updated_replicas: i32,
 
+    /// Label selector of the Rollout's pods, as a string (for the scale subresource)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+
+    /// `metadata.generation` of the spec this status reflects
+    #[serde(rename = "observedGeneration", skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
+
     /// Current canary step index (0-indexed)
     #[serde(rename = "currentStepIndex", skip_serializing_if = "Option::is_none")]
     pub current_step_index: Option<i32>,
@@ -521,6 +1683,11 @@ pub struct RolloutStatus {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 
+    /// One-line summary of where the rollout stands, e.g. "canary 40% (step
+    /// 3/6), analysis healthy, awaiting approval" (the Summary printcolumn)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
     /// Timestamp when current pause started (RFC3339 format)
     #[serde(rename = "pauseStartTime", skip_serializing_if = "Option::is_none")]
     pub pause_start_time: Option<String>,
@@ -543,9 +1710,491 @@ pub struct RolloutStatus {
     #[serde(rename = "abExperiment", skip_serializing_if = "Option::is_none")]
     pub ab_experiment: Option<ABExperimentStatus>,
 
-    /// Source of last analysis decision (Threshold, Advisor, Human)
-    #[serde(rename = "lastDecisionSource", skip_serializing_if = "Option::is_none")]
+    /// Source of the last decision, if not the thresholds (`advisor` when a
+    /// Driven-level advisor made it)
+    #[serde(rename = "lastDecisionSource", default)]
     pub last_decision_source: Option<String>,
+
+    /// Pod template hash of the revision being rolled out
+    #[serde(rename = "podTemplateHash", skip_serializing_if = "Option::is_none")]
+    pub pod_template_hash: Option<String>,
+
+    /// Revision number of the pod template being rolled out
+    ///
+    /// Starts at 1 and increases with every new pod template, including
+    /// rollbacks to an earlier one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
+
+    /// Summary of pod template changes introduced by the latest revision
+    #[serde(rename = "templateDiff", skip_serializing_if = "Option::is_none")]
+    pub template_diff: Option<TemplateDiff>,
+
+    /// Per-route sync state when traffic is split across multiple HTTPRoutes
+    #[serde(rename = "httpRoutes", default, skip_serializing_if = "Vec::is_empty")]
+    pub http_routes: Vec<HttpRouteStatus>,
+
+    /// When the new revision was first seen degraded (RFC3339, cleared on recovery)
+    #[serde(rename = "degradedSince", default)]
+    pub degraded_since: Option<String>,
+
+    /// CDEvents subject id of the incident opened by an analysis breach
+    /// (cleared when a later rollout completes)
+    #[serde(rename = "openIncident", default)]
+    pub open_incident: Option<String>,
+
+    /// Deployment freeze currently holding this rollout
+    #[serde(rename = "activeFreeze", default)]
+    pub active_freeze: Option<ActiveFreeze>,
+
+    /// When the rollout was rolled back to stable (RFC3339), by `kulta.io/abort`
+    /// or automatically when analysis or the progress deadline failed it
+    ///
+    /// While set, all traffic goes to stable and the canary (or blue-green
+    /// preview) is scaled to zero.
+    /// Serialized as null when unset so `kulta.io/retry` clears it.
+    #[serde(rename = "abortedAt", default)]
+    pub aborted_at: Option<String>,
+
+    /// When the pause at the current step ends on its own (RFC3339)
+    ///
+    /// Set for `duration` and `until` pauses, null otherwise.
+    #[serde(rename = "pausedUntil", default)]
+    pub paused_until: Option<String>,
+
+    /// When `kulta.io/promote` was applied (RFC3339), until the annotation is removed
+    ///
+    /// A promote annotation found while this is set was already acted on
+    /// (the controller stopped before removing it) and is only removed.
+    #[serde(rename = "promotedAt", default)]
+    pub promoted_at: Option<String>,
+
+    /// `resourceVersion` of the Rollout whose `kulta.io/promote` was applied
+    ///
+    /// Set and cleared with `promotedAt`. The status update applying a
+    /// promotion is conditional on this version, so when two reconciles act
+    /// on the same annotation (a lagging watch cache, a second promote racing
+    /// the annotation's removal) only the first advances a step.
+    #[serde(rename = "promotedVersion", default)]
+    pub promoted_version: Option<String>,
+
+    /// Progress of the current step's inline analysis
+    #[serde(rename = "stepAnalysis", default)]
+    pub step_analysis: Option<StepAnalysisStatus>,
+
+    /// Experiment launched by the current step
+    #[serde(rename = "stepExperiment", default)]
+    pub step_experiment: Option<StepExperimentStatus>,
+
+    /// Canary weight set by `kulta.io/set-weight`, replacing the step's
+    /// `setWeight` until the rollout leaves the step
+    #[serde(rename = "weightOverride", default)]
+    pub weight_override: Option<WeightOverride>,
+
+    /// Step plan proposed by the advisor (level Planned) and accepted by the
+    /// guardrails, replacing the steps after `fromStep` for this revision
+    #[serde(rename = "advisorPlan", default)]
+    pub advisor_plan: Option<AdvisorPlan>,
+
+    /// Verdict on the stable revision after the last rollback
+    /// (`canary.rollbackVerification`)
+    #[serde(rename = "rollbackVerification", default)]
+    pub rollback_verification: Option<RollbackVerificationStatus>,
+
+    /// Consecutive failures of metrics with a `failureThreshold`, while non-zero
+    #[serde(rename = "metricFailures", default)]
+    pub metric_failures: Option<Vec<MetricFailureCount>>,
+
+    /// Conditions of the Rollout (`Degraded` while a referenced Service is
+    /// missing, `Warnings` while the spec has lint warnings, `RevisionQueued`
+    /// while a template change waits for the in-flight revision)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<RolloutCondition>,
+
+    /// ReplicaSets and HTTPRoutes the controller manages for this Rollout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ManagedResources>,
+
+    /// Strategy driving this Rollout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<StrategyKind>,
+
+    /// Configuration the last reconcile acted on, after defaulting,
+    /// namespace policies and imported steps
+    #[serde(rename = "effectiveConfig", skip_serializing_if = "Option::is_none")]
+    pub effective_config: Option<EffectiveConfig>,
+}
+
+/// Fully-defaulted configuration of a Rollout as the controller applies it
+///
+/// Spec fields are shown with their defaults filled in; the rest records
+/// where the template and steps came from and what namespace policies
+/// changed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct EffectiveConfig {
+    /// Where the pod template comes from (`spec.template` or `Deployment/<name>`)
+    #[serde(rename = "templateSource")]
+    pub template_source: String,
+
+    /// Pod template fields removed by the namespace pod policy
+    #[serde(
+        rename = "strippedFields",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub stripped_fields: Vec<String>,
+
+    /// Spec fields filled from the controller defaults (`KULTA_DEFAULTS_FILE`)
+    #[serde(
+        rename = "defaultedFields",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub defaulted_fields: Vec<String>,
+
+    /// Where the canary steps come from (`spec.strategy.canary.steps`,
+    /// `ConfigMap <namespace>/<name>[<key>]` or `controller defaults`)
+    #[serde(rename = "stepsSource", skip_serializing_if = "Option::is_none")]
+    pub steps_source: Option<String>,
+
+    /// Canary traffic weight of each step, after `weightConstraints` normalization
+    #[serde(rename = "stepWeights", default, skip_serializing_if = "Vec::is_empty")]
+    pub step_weights: Vec<i32>,
+
+    /// Promotion mode set by the namespace promotion policy
+    #[serde(rename = "promotionMode", skip_serializing_if = "Option::is_none")]
+    pub promotion_mode: Option<PromotionMode>,
+
+    /// Advisor level after the namespace guardrails
+    #[serde(rename = "advisorLevel")]
+    pub advisor_level: AdvisorLevel,
+
+    #[serde(rename = "maxSurge")]
+    pub max_surge: String,
+
+    #[serde(rename = "maxUnavailable")]
+    pub max_unavailable: String,
+
+    /// Progress deadline in seconds (unset: no deadline)
+    #[serde(
+        rename = "progressDeadlineSeconds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub progress_deadline_seconds: Option<i32>,
+
+    /// Old revisions kept for rollback
+    #[serde(rename = "revisionHistoryLimit")]
+    pub revision_history_limit: i32,
+}
+
+/// Measurements of a step's inline analysis
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StepAnalysisStatus {
+    /// Step the measurements belong to
+    #[serde(rename = "stepIndex")]
+    pub step_index: i32,
+
+    /// When the analysis of the step started (RFC3339)
+    #[serde(rename = "startedAt")]
+    pub started_at: String,
+
+    /// Passing measurements so far
+    pub passed: i32,
+
+    /// When the last measurement was taken (RFC3339)
+    #[serde(rename = "lastMeasuredAt", skip_serializing_if = "Option::is_none")]
+    pub last_measured_at: Option<String>,
+}
+
+/// Experiment of a canary step
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StepExperimentStatus {
+    /// Step that launched the Experiment
+    #[serde(rename = "stepIndex")]
+    pub step_index: i32,
+
+    /// Name of the Experiment
+    pub name: String,
+
+    /// Phase of the Experiment when last read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<ExperimentPhase>,
+}
+
+/// Canary weight set manually, outside the step plan
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct WeightOverride {
+    /// Canary traffic weight (0-100)
+    pub weight: i32,
+
+    /// Step the override applies to
+    #[serde(rename = "stepIndex")]
+    pub step_index: i32,
+
+    /// When the override was applied (RFC3339)
+    #[serde(rename = "setAt")]
+    pub set_at: String,
+}
+
+/// One step of an advisor plan, run as a step of the Rollout's own ladder
+///
+/// The step keeps its analysis, experiment and gates; only its weight and
+/// timed pause can be changed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PlannedStep {
+    /// Index of the canary step this step runs
+    pub step: i32,
+
+    /// Weight instead of the step's `setWeight`
+    #[serde(rename = "setWeight", skip_serializing_if = "Option::is_none")]
+    pub set_weight: Option<i32>,
+
+    /// Timed pause instead of the step's own (e.g., "10m")
+    #[serde(rename = "pauseDuration", skip_serializing_if = "Option::is_none")]
+    pub pause_duration: Option<String>,
+}
+
+/// Step plan of an advisor at the Planned level
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AdvisorPlan {
+    /// Steps up to this one run as declared (the rollout's step when it was planned)
+    #[serde(rename = "fromStep")]
+    pub from_step: i32,
+
+    /// Steps to run after `fromStep`, in order (canary steps left out are skipped)
+    pub steps: Vec<PlannedStep>,
+
+    /// Revision the plan applies to
+    #[serde(rename = "podTemplateHash", skip_serializing_if = "Option::is_none")]
+    pub pod_template_hash: Option<String>,
+
+    /// When the plan was accepted (RFC3339)
+    #[serde(rename = "acceptedAt")]
+    pub accepted_at: String,
+}
+
+/// Verdict on the stable revision after a rollback
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RollbackVerificationStatus {
+    /// The rollback verified (its `abortedAt`)
+    #[serde(rename = "rolledBackAt")]
+    pub rolled_back_at: String,
+
+    /// When the stable revision was measured (RFC3339)
+    #[serde(rename = "verifiedAt")]
+    pub verified_at: String,
+
+    /// Whether the stable revision passed the analysis thresholds
+    pub outcome: RollbackVerificationOutcome,
+
+    /// Metrics the stable revision fails too
+    #[serde(
+        rename = "failingMetrics",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub failing_metrics: Vec<String>,
+
+    /// Human-readable verdict
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Outcome of a rollback verification
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum RollbackVerificationOutcome {
+    /// The stable revision passes: the canary was the problem
+    Recovered,
+    /// The stable revision fails too: the system is unhealthy beyond the canary
+    Unhealthy,
+    /// The metrics couldn't be measured, or none apply to the stable revision
+    Inconclusive,
+}
+
+/// Consecutive failed evaluations of a metric with a `failureThreshold`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MetricFailureCount {
+    /// Metric name
+    pub metric: String,
+
+    /// Failed evaluations in a row (reset by a passing one)
+    #[serde(rename = "consecutiveFailures")]
+    pub consecutive_failures: i32,
+
+    /// When the last failure was counted (RFC3339); at most one is counted
+    /// per metric `interval`
+    #[serde(rename = "lastEvaluationTime", skip_serializing_if = "Option::is_none")]
+    pub last_evaluation_time: Option<String>,
+}
+
+/// A freeze window from the controller's freeze calendar
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ActiveFreeze {
+    /// Window name (ConfigMap key or iCal SUMMARY)
+    pub name: String,
+
+    /// When the freeze ends (RFC3339)
+    pub until: String,
+}
+
+/// Child resources owned by a Rollout, as of the last reconcile
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct ManagedResources {
+    /// ReplicaSets created or scaled by the controller
+    #[serde(rename = "replicaSets", default)]
+    pub replica_sets: Vec<ManagedReplicaSet>,
+
+    /// HTTPRoutes whose backend weights or rules the controller writes
+    #[serde(rename = "httpRoutes", default)]
+    pub http_routes: Vec<String>,
+}
+
+/// A ReplicaSet managed by the controller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ManagedReplicaSet {
+    /// ReplicaSet name
+    pub name: String,
+
+    /// `pod-template-hash` label of the ReplicaSet (the revision it runs)
+    pub hash: String,
+
+    /// Role within the strategy
+    pub role: ReplicaSetRole,
+
+    /// Desired replica count
+    pub replicas: i32,
+}
+
+/// Role of a managed ReplicaSet within its strategy
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReplicaSetRole {
+    Stable,
+    Canary,
+    Baseline,
+    Active,
+    Preview,
+    VariantA,
+    VariantB,
+    Simple,
+}
+
+impl ReplicaSetRole {
+    /// Role as it appears in `rollouts.kulta.io/type` and ReplicaSet names
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReplicaSetRole::Stable => "stable",
+            ReplicaSetRole::Canary => "canary",
+            ReplicaSetRole::Baseline => "baseline",
+            ReplicaSetRole::Active => "active",
+            ReplicaSetRole::Preview => "preview",
+            ReplicaSetRole::VariantA => "variant-a",
+            ReplicaSetRole::VariantB => "variant-b",
+            ReplicaSetRole::Simple => "simple",
+        }
+    }
+}
+
+/// Condition of a Rollout, shaped like a Kubernetes `metav1.Condition`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RolloutCondition {
+    /// Condition type (e.g., "Degraded")
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// "True", "False" or "Unknown"
+    pub status: String,
+
+    /// CamelCase reason for the condition's last transition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// Human-readable details
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// When the condition last changed status (RFC3339)
+    #[serde(rename = "lastTransitionTime", skip_serializing_if = "Option::is_none")]
+    pub last_transition_time: Option<String>,
+}
+
+/// Sync state of a single managed HTTPRoute
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct HttpRouteStatus {
+    /// HTTPRoute name
+    pub name: String,
+
+    /// Whether the route carries the current weights
+    pub synced: bool,
+
+    /// Error from the last failed update
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Summarized difference between two pod template revisions
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TemplateDiff {
+    /// Pod template hash of the previously running revision
+    #[serde(rename = "fromHash")]
+    pub from_hash: String,
+
+    /// Pod template hash of the new revision
+    #[serde(rename = "toHash")]
+    pub to_hash: String,
+
+    /// Container image changes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageChange>,
+
+    /// Containers whose env or envFrom changed
+    #[serde(rename = "envChanged", default, skip_serializing_if = "Vec::is_empty")]
+    pub env_changed: Vec<String>,
+
+    /// Containers whose resource requests or limits changed
+    #[serde(
+        rename = "resourcesChanged",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub resources_changed: Vec<String>,
+
+    /// Containers present only in the new revision
+    #[serde(
+        rename = "containersAdded",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub containers_added: Vec<String>,
+
+    /// Containers present only in the previous revision
+    #[serde(
+        rename = "containersRemoved",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub containers_removed: Vec<String>,
+
+    /// Whether anything else in the template changed (labels, volumes, probes, ...)
+    #[serde(rename = "otherChanges", default)]
+    pub other_changes: bool,
+
+    /// Whether lists were cut short (each keeps at most 20 entries)
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Image change for a single container
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ImageChange {
+    /// Container name
+    pub container: String,
+
+    /// Previous image
+    pub from: String,
+
+    /// New image
+    pub to: String,
 }
 
 /// A/B experiment status tracking
@@ -626,6 +2275,8 @@ pub enum ABConclusionReason {
     ManualConclusion,
     /// Consensus reached (all metrics show same winner)
     ConsensusReached,
+    /// Variant B breached a guardrail metric (variant A wins)
+    GuardrailBreached,
 }
 
 /// AI advisor integration level
@@ -641,14 +2292,18 @@ pub enum AdvisorLevel {
     Context,
     /// AI analyzes metrics and returns recommendations (threshold still decides)
     Advised,
-    /// AI proposes rollout strategy (human approves) — future
+    /// AI may replan the remaining steps within the guardrails (threshold still decides)
     Planned,
-    /// AI drives the loop with human override — future
+    /// AI drives the loop within the guardrails (recommendations above
+    /// `minConfidence` advance, hold or roll back the rollout)
     Driven,
 }
 
 const DEFAULT_ADVISOR_TIMEOUT_SECONDS: u64 = 10;
 
+/// Confidence a Driven-level recommendation needs unless `minConfidence` is set
+pub const DEFAULT_ADVISOR_MIN_CONFIDENCE: f64 = 0.8;
+
 /// Configuration for the AI advisor
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AdvisorConfig {
@@ -667,6 +2322,19 @@ pub struct AdvisorConfig {
         skip_serializing_if = "is_default_advisor_timeout"
     )]
     pub timeout_seconds: u64,
+
+    /// Confidence (0-1) a recommendation needs to drive the rollout at the
+    /// Driven level (default 0.8)
+    #[serde(rename = "minConfidence", skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f64>,
+}
+
+impl AdvisorConfig {
+    /// Confidence a Driven-level recommendation needs to be acted on
+    pub fn driving_confidence(&self) -> f64 {
+        self.min_confidence
+            .unwrap_or(DEFAULT_ADVISOR_MIN_CONFIDENCE)
+    }
 }
 
 impl Default for AdvisorConfig {
@@ -675,6 +2343,7 @@ impl Default for AdvisorConfig {
             level: AdvisorLevel::Off,
             endpoint: None,
             timeout_seconds: DEFAULT_ADVISOR_TIMEOUT_SECONDS,
+            min_confidence: None,
         }
     }
 }
@@ -693,6 +2362,9 @@ pub struct Recommendation {
     pub action: RecommendedAction,
     pub confidence: f64,
     pub reasoning: String,
+    /// Steps to run after the current one (applied from level Planned on)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<Vec<PlannedStep>>,
 }
 
 /// Recommended action from the advisor
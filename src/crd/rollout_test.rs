@@ -160,6 +160,28 @@ spec:
     assert_eq!(traffic.gateway_api.unwrap().http_route, "test-route");
 }
 
+#[test]
+fn test_gateway_api_multiple_http_routes() {
+    let routing: GatewayAPIRouting = serde_yaml::from_str(
+        r#"
+httpRoute: external-route
+httpRoutes: [internal-route, external-route]
+"#,
+    )
+    .expect("Failed to deserialize GatewayAPIRouting");
+
+    assert_eq!(
+        routing.route_names(),
+        vec!["external-route", "internal-route"],
+        "httpRoute comes first and duplicates are dropped"
+    );
+
+    let plural_only: GatewayAPIRouting = serde_yaml::from_str("httpRoutes: [a, b]")
+        .expect("Failed to deserialize GatewayAPIRouting");
+    assert_eq!(plural_only.http_route, "");
+    assert_eq!(plural_only.route_names(), vec!["a", "b"]);
+}
+
 #[test]
 fn test_rollout_crd_schema_generation() {
     // Generate CRD YAML that gets installed in Kubernetes
@@ -176,6 +198,51 @@ fn test_rollout_crd_schema_generation() {
     assert!(version.served);
     assert!(version.storage);
     assert!(version.schema.is_some());
+
+    // kubectl conveniences: `kubectl get ro`, `kubectl get all`, field selectors
+    assert_eq!(
+        crd.spec.names.short_names,
+        Some(vec!["ro".to_string(), "kulta".to_string()])
+    );
+    assert_eq!(crd.spec.names.categories, Some(vec!["all".to_string()]));
+    let selectable: Vec<&str> = version
+        .selectable_fields
+        .iter()
+        .flatten()
+        .map(|field| field.json_path.as_str())
+        .collect();
+    assert_eq!(selectable, vec![".status.phase", ".status.strategy"]);
+    let columns: Vec<&str> = version
+        .additional_printer_columns
+        .iter()
+        .flatten()
+        .map(|column| column.name.as_str())
+        .collect();
+    assert!(columns.contains(&"Strategy"));
+    assert!(columns.contains(&"Message"));
+    // Replica counts are mostly zero; the summary is what `kubectl get ro` shows
+    let wide_only: Vec<&str> = version
+        .additional_printer_columns
+        .iter()
+        .flatten()
+        .filter(|column| column.priority == Some(1))
+        .map(|column| column.name.as_str())
+        .collect();
+    assert_eq!(wide_only, vec!["Desired", "Current", "Ready", "Message"]);
+    assert!(columns.contains(&"Summary"));
+
+    // HPAs target Rollouts through the scale subresource
+    let scale = version
+        .subresources
+        .as_ref()
+        .and_then(|s| s.scale.as_ref())
+        .expect("scale subresource");
+    assert_eq!(scale.spec_replicas_path, ".spec.replicas");
+    assert_eq!(scale.status_replicas_path, ".status.replicas");
+    assert_eq!(
+        scale.label_selector_path.as_deref(),
+        Some(".status.selector")
+    );
 }
 
 #[test]
@@ -222,6 +289,9 @@ fn test_status_decisions_serialization() {
             reason: DecisionReason::AnalysisPassed,
             message: None,
             metrics: None,
+            count: None,
+            last_timestamp: None,
+            approved_by: None,
         }],
         ..Default::default()
     };
@@ -486,3 +556,131 @@ fn test_crd_matches_deployed_yaml() {
          > deploy/crd.yaml"
     );
 }
+
+#[test]
+fn test_metric_comparison_and_unit_deserialize() {
+    let yaml = r#"
+name: availability
+threshold: 99.9
+comparison: gt
+unit: percent
+"#;
+    let metric: MetricConfig = serde_yaml::from_str(yaml).expect("Failed to deserialize metric");
+    assert_eq!(metric.comparison, Some(MetricComparison::Gt));
+    assert_eq!(metric.unit, Some(MetricUnit::Percent));
+
+    assert!(metric.is_healthy(0.9995), "99.95% is above 99.9%");
+    assert!(!metric.is_healthy(0.998), "99.8% is not above 99.9%");
+
+    // Defaults keep "must be below threshold" on the raw value
+    let metric: MetricConfig =
+        serde_yaml::from_str("name: error-rate\nthreshold: 5.0").expect("deserialize");
+    assert!(metric.is_healthy(4.9));
+    assert!(!metric.is_healthy(5.0));
+}
+
+#[test]
+fn test_analysis_aggregation() {
+    let yaml = r#"
+aggregation: score
+passScore: 0.6
+metrics:
+  - name: error-rate
+    threshold: 1.0
+    weight: 2
+  - name: latency-p95
+    threshold: 300
+  - name: latency-p99
+    threshold: 800
+"#;
+    let mut analysis: AnalysisConfig = serde_yaml::from_str(yaml).expect("deserialize analysis");
+    assert_eq!(analysis.aggregation, Some(AnalysisAggregation::Score));
+
+    // Error rate passes, both latencies fail: 2 of 4
+    assert_eq!(analysis.score(&[true, false, false]), 0.5);
+    assert!(!analysis.is_healthy(&[true, false, false]));
+    // Error rate and one latency pass: 3 of 4
+    assert!(analysis.is_healthy(&[true, true, false]));
+
+    analysis.aggregation = Some(AnalysisAggregation::Any);
+    assert!(analysis.is_healthy(&[false, false, true]));
+    assert!(!analysis.is_healthy(&[false, false, false]));
+
+    analysis.aggregation = None;
+    assert!(!analysis.is_healthy(&[true, true, false]));
+    assert!(analysis.is_healthy(&[true, true, true]));
+}
+
+#[test]
+fn test_pause_shapes_round_trip() {
+    use serde_json::json;
+
+    let shapes = [
+        (
+            json!({ "duration": "5m" }),
+            Pause::Duration("5m".to_string()),
+        ),
+        (
+            json!({ "until": "2026-03-02T09:00:00Z" }),
+            Pause::UntilTime("2026-03-02T09:00:00Z".to_string()),
+        ),
+        (json!({ "untilApproved": true }), Pause::UntilApproved),
+        (json!({}), Pause::Indefinite),
+    ];
+    for (value, pause) in shapes {
+        assert_eq!(
+            serde_json::from_value::<Pause>(value.clone()).unwrap(),
+            pause
+        );
+        assert_eq!(serde_json::to_value(&pause).unwrap(), value);
+    }
+
+    // untilApproved: false is an ordinary pause
+    assert_eq!(
+        serde_json::from_value::<Pause>(json!({ "untilApproved": false })).unwrap(),
+        Pause::Indefinite
+    );
+
+    let error = serde_json::from_value::<Pause>(
+        json!({ "duration": "5m", "until": "2026-03-02T09:00:00Z" }),
+    )
+    .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("only one of duration, until and untilApproved"),
+        "got: {}",
+        error
+    );
+}
+
+// The API server checks pauses against the CRD schema and the controller
+// deserializes them, so both must accept the same shapes
+#[test]
+fn test_pause_schema_and_deserialization_agree() {
+    use serde_json::json;
+
+    let schema = serde_json::to_value(schemars::schema_for!(Pause)).unwrap();
+    let max_properties = schema["maxProperties"].as_u64().unwrap() as usize;
+
+    let shapes = [
+        json!({}),
+        json!({ "duration": "5m" }),
+        json!({ "until": "2026-03-02T09:00:00Z" }),
+        json!({ "untilApproved": true }),
+        json!({ "untilApproved": false }),
+        json!({ "duration": "5m", "untilApproved": false }),
+        json!({ "until": "2026-03-02T09:00:00Z", "untilApproved": false }),
+        json!({ "duration": "5m", "untilApproved": true }),
+        json!({ "duration": "5m", "until": "2026-03-02T09:00:00Z" }),
+    ];
+    for shape in shapes {
+        let schema_accepts = shape.as_object().unwrap().len() <= max_properties;
+        assert_eq!(
+            serde_json::from_value::<Pause>(shape.clone()).is_ok(),
+            schema_accepts,
+            "{}",
+            shape
+        );
+    }
+}
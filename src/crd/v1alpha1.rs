@@ -5,7 +5,7 @@
 
 pub use super::rollout::{
     AnalysisConfig, BlueGreenStrategy, CanaryStep, CanaryStrategy, Decision, DecisionAction,
-    DecisionReason, FailurePolicy, GatewayAPIRouting, MetricConfig, MetricSnapshot, PauseDuration,
-    Phase, PrometheusConfig, Rollout, RolloutSpec, RolloutStatus, RolloutStrategy, SimpleStrategy,
+    DecisionReason, FailurePolicy, GatewayAPIRouting, MetricConfig, MetricSnapshot, Pause, Phase,
+    PrometheusConfig, Rollout, RolloutSpec, RolloutStatus, RolloutStrategy, SimpleStrategy,
     TrafficRouting,
 };
@@ -13,9 +13,18 @@ use serde::{Deserialize, Serialize};
 
 // Re-export unchanged types from v1alpha1
 pub use super::rollout::{
-    AnalysisConfig, BlueGreenStrategy, CanaryStep, CanaryStrategy, Decision, DecisionAction,
-    DecisionReason, FailurePolicy, GatewayAPIRouting, MetricConfig, MetricSnapshot, PauseDuration,
-    Phase, PrometheusConfig, RolloutStatus, RolloutStrategy, SimpleStrategy, TrafficRouting,
+    ActiveFreeze, AlertGate, AlertGateErrorPolicy, AnalysisConfig, BlueGreenStrategy,
+    CanaryBaseline, CanaryStep, CanaryStrategy, CleanupPolicy, Decision, DecisionAction,
+    DecisionReason, DegradedAction, DegradedPolicy, DependencyGate, DependencyKind,
+    ExperimentSpecRef, ExperimentStep, ExperimentStepTemplate, FailurePolicy, GatewayAPIRouting,
+    HttpRouteStatus, ManagedReplicaSet, ManagedResources, MetricComparison, MetricConfig,
+    MetricFailureCount, MetricSnapshot, MetricUnit, NginxTrafficRouting, NoTrafficRouting, Pause,
+    Phase, PrometheusBasicAuth, PrometheusConfig, PrometheusTlsConfig, ReplicaSetRole,
+    RollbackVerification, RollbackVerificationOutcome, RollbackVerificationStatus,
+    RolloutCondition, RolloutStatus, RolloutStrategy, RouteSection, SecretKeyRef, SetCanaryScale,
+    SetHeaderRoute, SimpleStrategy, StepAnalysis, StepAnalysisStatus, StepExperimentStatus,
+    StepsConfigMapRef, StepsFrom, TemplateChangePolicy, TemplateDiff, TrafficRouting,
+    VolumeWeighting, WebMetric, WebMetricHeader, WebMetricMethod, WeightConstraints, WorkloadRef,
 };
 
 /// Rollout v1beta1 - Progressive delivery with enhanced rollout controls
@@ -31,11 +40,20 @@ pub use super::rollout::{
     kind = "Rollout",
     namespaced,
     status = "RolloutStatus",
-    printcolumn = r#"{"name":"Desired", "type":"integer", "jsonPath":".spec.replicas"}"#,
-    printcolumn = r#"{"name":"Current", "type":"integer", "jsonPath":".status.replicas"}"#,
-    printcolumn = r#"{"name":"Ready", "type":"integer", "jsonPath":".status.readyReplicas"}"#,
+    shortname = "ro",
+    shortname = "kulta",
+    category = "all",
+    selectable = ".status.phase",
+    selectable = ".status.strategy",
+    scale = r#"{"specReplicasPath":".spec.replicas", "statusReplicasPath":".status.replicas", "labelSelectorPath":".status.selector"}"#,
+    printcolumn = r#"{"name":"Desired", "type":"integer", "jsonPath":".spec.replicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Current", "type":"integer", "jsonPath":".status.replicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Ready", "type":"integer", "jsonPath":".status.readyReplicas", "priority":1}"#,
+    printcolumn = r#"{"name":"Strategy", "type":"string", "jsonPath":".status.strategy"}"#,
     printcolumn = r#"{"name":"Phase", "type":"string", "jsonPath":".status.phase"}"#,
     printcolumn = r#"{"name":"Weight", "type":"integer", "jsonPath":".status.currentWeight"}"#,
+    printcolumn = r#"{"name":"Summary", "type":"string", "jsonPath":".status.summary"}"#,
+    printcolumn = r#"{"name":"Message", "type":"string", "jsonPath":".status.message", "priority":1}"#,
     printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
 )]
 pub struct RolloutSpec {
@@ -46,9 +64,14 @@ pub struct RolloutSpec {
     /// Label selector for pods
     pub selector: LabelSelector,
 
-    /// Template describes the pods that will be created
+    /// Template describes the pods that will be created (omitted with `workloadRef`)
+    #[serde(default)]
     pub template: PodTemplateSpec,
 
+    /// Existing Deployment whose pod template the Rollout uses instead of `template`
+    #[serde(rename = "workloadRef", skip_serializing_if = "Option::is_none")]
+    pub workload_ref: Option<WorkloadRef>,
+
     /// Deployment strategy
     pub strategy: RolloutStrategy,
 
@@ -72,6 +95,29 @@ pub struct RolloutSpec {
         skip_serializing_if = "Option::is_none"
     )]
     pub progress_deadline_seconds: Option<i32>,
+
+    /// What to do when the new revision's pods can't become ready
+    /// (crash loops, image pull errors, exhausted quota)
+    #[serde(rename = "onDegraded", skip_serializing_if = "Option::is_none")]
+    pub on_degraded: Option<DegradedPolicy>,
+
+    /// What happens when `template` changes while a revision is still being
+    /// rolled out: replace (default), queue or reject
+    #[serde(rename = "onTemplateChange", skip_serializing_if = "Option::is_none")]
+    pub on_template_change: Option<TemplateChangePolicy>,
+
+    /// Number of old revisions kept as scaled-down ReplicaSets for rollback.
+    /// Defaults to 10.
+    #[serde(
+        rename = "revisionHistoryLimit",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub revision_history_limit: Option<i32>,
+
+    /// What happens to auxiliary objects created for the Rollout (e.g. the
+    /// NGINX canary Ingress) once they are unused. Defaults to Delete.
+    #[serde(rename = "cleanupPolicy", skip_serializing_if = "Option::is_none")]
+    pub cleanup_policy: Option<CleanupPolicy>,
 }
 
 fn default_replicas() -> i32 {
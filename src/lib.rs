@@ -1,7 +1,14 @@
+//! KULTA - progressive delivery controller for Kubernetes
+//!
+//! Embedders should use [`api`], the stable, semver-guarded surface. The
+//! `controller`, `crd` and `server` modules are public for the `kulta`
+//! binary and its tests; their layout changes between releases.
+
 // Strict code quality lints
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::expect_used)]
 
+pub mod api;
 pub mod controller;
 pub mod crd;
 pub mod server;
@@ -1,15 +1,35 @@
 use futures::StreamExt;
+use k8s_openapi::api::apps::v1::{Deployment, ReplicaSet};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::api::{ListParams, Patch, PatchParams};
 use kube::runtime::controller::Action;
-use kube::runtime::{watcher, Controller};
-use kube::{Api, Client};
+use kube::runtime::{watcher, Controller, WatchStreamExt};
+use kube::{Api, Client, ResourceExt};
 use kulta::controller::cdevents::HttpEventSink;
-use kulta::controller::prometheus::HttpPrometheusClient;
+use kulta::controller::experiment::{reconcile_experiment, EXPERIMENT_LABEL};
+use kulta::controller::lint::{lint_rollout, LintOptions, ProductionNamespaces};
+use kulta::controller::orphans::{run_orphan_gc, OrphanGcConfig};
+use kulta::controller::planner::plan_rollout;
+use kulta::controller::prometheus::{self, HttpPrometheusClient};
+use kulta::controller::prometheus_auth;
+use kulta::controller::rollout::{
+    preview_backend_refs, reconcile_cluster_rollout, validate_rollout, ABORT_ANNOTATION,
+    RETRY_ANNOTATION, SET_WEIGHT_ANNOTATION,
+};
+use kulta::controller::steps_from::rollouts_importing_steps;
+use kulta::controller::support_bundle::{collect_support_bundle, SupportBundleOptions};
+use kulta::controller::work_queue::report_queue_metrics;
+use kulta::controller::workload_ref::rollouts_referencing_workload;
 use kulta::controller::{reconcile, Context, ReconcileError};
-use kulta::crd::rollout::Rollout;
+use kulta::crd::argo::convert_argo_rollout;
+use kulta::crd::cluster_rollout::ClusterRollout;
+use kulta::crd::experiment::Experiment;
+use kulta::crd::rollout::{Phase, Rollout};
 use kulta::server::{
     build_rustls_config, create_metrics, initialize_tls, run_health_server, run_health_server_tls,
-    run_leader_election, shutdown_channel, wait_for_signal, LeaderConfig, LeaderState,
-    ReadinessState, DEFAULT_TLS_SECRET_NAME,
+    run_leader_election, shutdown_channel, wait_for_rollouts, wait_for_signal, wait_until_idle,
+    IdleConfig, LeaderConfig, LeaderState, ObjectLeaseConfig, ObjectLeases, ReadinessState,
+    DEFAULT_TLS_SECRET_NAME,
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -56,23 +76,417 @@ pub fn error_policy(rollout: Arc<Rollout>, error: &ReconcileError, ctx: Arc<Cont
 
     // Record error metric
     if let Some(ref metrics) = ctx.metrics {
-        // Determine strategy from rollout spec for metric labeling
-        let strategy = if rollout.spec.strategy.simple.is_some() {
-            "simple"
-        } else if rollout.spec.strategy.blue_green.is_some() {
-            "blue_green"
-        } else {
-            "canary"
-        };
+        let strategy = kulta::controller::strategies::select_strategy(&rollout);
         // Duration unknown for errors (didn't complete), use 0
-        metrics.record_reconciliation_error(strategy, 0.0);
+        metrics.record_reconciliation_error(
+            rollout.namespace().as_deref().unwrap_or_default(),
+            strategy.name(),
+            0.0,
+        );
     }
 
     Action::requeue(Duration::from_secs(10))
 }
 
+/// Error policy for the ClusterRollout controller (same as for Rollouts)
+pub fn cluster_error_policy(
+    cluster_rollout: Arc<ClusterRollout>,
+    error: &ReconcileError,
+    ctx: Arc<Context>,
+) -> Action {
+    error_policy(Arc::new(cluster_rollout.to_rollout()), error, ctx)
+}
+
+/// Error policy for the Experiment controller
+pub fn experiment_error_policy(
+    experiment: Arc<Experiment>,
+    error: &ReconcileError,
+    _ctx: Arc<Context>,
+) -> Action {
+    warn!(experiment = ?experiment.name_any(), "Experiment reconcile error (will retry): {:?}", error);
+    Action::requeue(Duration::from_secs(10))
+}
+
+/// Extract the manifest path from `plan` arguments (`-f <file>` or `--filename <file>`)
+fn plan_manifest_path(args: &[String]) -> Option<&str> {
+    match args {
+        [flag, path] if flag == "-f" || flag == "--filename" => Some(path.as_str()),
+        _ => None,
+    }
+}
+
+/// Extract the manifest path and step index from `preview` arguments
+/// (`-f <file> --step <index>`, in either order)
+fn preview_args(args: &[String]) -> Option<(&str, i32)> {
+    match args {
+        [flag, path, step_flag, step] | [step_flag, step, flag, path]
+            if (flag == "-f" || flag == "--filename") && step_flag == "--step" =>
+        {
+            Some((path.as_str(), step.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Read a manifest from a file, or from stdin for `-`
+fn read_manifest(path: &str) -> anyhow::Result<String> {
+    use std::io::Read;
+
+    if path == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("failed to read {}: {}", path, e))
+    }
+}
+
+/// Read a Rollout manifest from a file, or from stdin for `-`
+fn read_rollout(path: &str) -> anyhow::Result<Rollout> {
+    Ok(serde_yaml::from_str(&read_manifest(path)?)?)
+}
+
+/// `kulta plan -f rollout.yaml`: print the simulated step/phase sequence
+///
+/// Runs fully offline; `-f -` reads the manifest from stdin.
+fn run_plan(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let path = plan_manifest_path(args)
+        .ok_or_else(|| anyhow::anyhow!("usage: kulta plan -f <rollout.yaml | ->"))?;
+    let rollout = read_rollout(path)?;
+    let plan = plan_rollout(&rollout)?;
+    write!(std::io::stdout().lock(), "{}", plan)?;
+    Ok(())
+}
+
+/// Extract the manifest path and `--production` from `lint` arguments
+fn lint_args(args: &[String]) -> Option<(&str, bool)> {
+    match args {
+        [flag, path] if flag == "-f" || flag == "--filename" => Some((path.as_str(), false)),
+        [flag, path, production] | [production, flag, path]
+            if (flag == "-f" || flag == "--filename") && production == "--production" =>
+        {
+            Some((path.as_str(), true))
+        }
+        _ => None,
+    }
+}
+
+/// `kulta lint -f rollout.yaml [--production]`: print warnings about risky
+/// but valid parts of the spec
+///
+/// Runs fully offline; `-f -` reads the manifest from stdin. The Rollout is
+/// linted as production with `--production` or when its namespace matches
+/// `KULTA_PRODUCTION_NAMESPACES`. Exits 1 when the spec is invalid, not on
+/// warnings.
+fn run_lint(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let (path, production) = lint_args(args)
+        .ok_or_else(|| anyhow::anyhow!("usage: kulta lint -f <rollout.yaml | -> [--production]"))?;
+    let rollout = read_rollout(path)?;
+    validate_rollout(&rollout).map_err(|e| anyhow::anyhow!("invalid Rollout spec: {}", e))?;
+    let options = LintOptions {
+        production: production
+            || ProductionNamespaces::from_env()
+                .matches(rollout.namespace().as_deref().unwrap_or_default()),
+    };
+    let warnings = lint_rollout(&rollout, &options);
+    let mut stdout = std::io::stdout().lock();
+    for warning in &warnings {
+        writeln!(stdout, "warning: {}", warning)?;
+    }
+    writeln!(stdout, "{} warning(s)", warnings.len())?;
+    Ok(())
+}
+
+/// `kulta preview -f rollout.yaml --step N`: print the HTTPRoute backendRefs
+/// that promoting to step N would apply
+///
+/// Runs fully offline; `-f -` reads the manifest from stdin.
+fn run_preview(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let (path, step) = preview_args(args).ok_or_else(|| {
+        anyhow::anyhow!("usage: kulta preview -f <rollout.yaml | -> --step <index>")
+    })?;
+    let rollout = read_rollout(path)?;
+    let backend_refs = preview_backend_refs(&rollout, step).map_err(|e| anyhow::anyhow!(e))?;
+    write!(
+        std::io::stdout().lock(),
+        "{}",
+        serde_yaml::to_string(&serde_json::json!({ "backendRefs": backend_refs }))?
+    )?;
+    Ok(())
+}
+
+/// `kulta convert -f argo-rollout.yaml`: print the KULTA equivalent of an
+/// Argo Rollouts Rollout
+///
+/// Runs fully offline; `-f -` reads the manifest from stdin. Dropped or
+/// approximated Argo fields are printed to stderr.
+fn run_convert(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let path = plan_manifest_path(args)
+        .ok_or_else(|| anyhow::anyhow!("usage: kulta convert -f <argo-rollout.yaml | ->"))?;
+    let manifest: serde_json::Value = serde_yaml::from_str(&read_manifest(path)?)?;
+    let conversion = convert_argo_rollout(&manifest)?;
+    let mut stderr = std::io::stderr().lock();
+    for warning in &conversion.warnings {
+        writeln!(stderr, "warning: {}", warning)?;
+    }
+    write!(
+        std::io::stdout().lock(),
+        "{}",
+        serde_yaml::to_string(&conversion.rollout)?
+    )?;
+    Ok(())
+}
+
+/// Rollout named on the command line of `promote`, `abort`, `retry`,
+/// `set-weight`, `status` or `support-bundle`
+#[derive(Debug, PartialEq, Eq)]
+struct RolloutTarget<'a> {
+    name: &'a str,
+    /// `-n/--namespace`; the kubeconfig's namespace when unset
+    namespace: Option<&'a str>,
+    /// `-w/--watch` (status only)
+    watch: bool,
+}
+
+/// Parse `<name> [-n <namespace>] [--watch]`, flags in any position
+fn rollout_target(args: &[String]) -> Option<RolloutTarget<'_>> {
+    let mut name = None;
+    let mut namespace = None;
+    let mut watch = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--namespace" => namespace = Some(args.next()?.as_str()),
+            "-w" | "--watch" => watch = true,
+            flag if flag.starts_with('-') => return None,
+            positional if name.is_none() => name = Some(positional),
+            _ => return None,
+        }
+    }
+    Some(RolloutTarget {
+        name: name?,
+        namespace,
+        watch,
+    })
+}
+
+/// Annotation patch requesting `promote`, `abort` or `retry` from the controller
+///
+/// Each request clears the ones it conflicts with, so the latest request wins.
+fn request_patch(command: &str) -> Option<serde_json::Value> {
+    let annotations = match command {
+        "promote" => serde_json::json!({ "kulta.io/promote": "true", ABORT_ANNOTATION: null }),
+        "abort" => serde_json::json!({
+            ABORT_ANNOTATION: "true",
+            RETRY_ANNOTATION: null,
+            "kulta.io/promote": null,
+        }),
+        "retry" => serde_json::json!({ RETRY_ANNOTATION: "true", ABORT_ANNOTATION: null }),
+        _ => return None,
+    };
+    Some(serde_json::json!({ "metadata": { "annotations": annotations } }))
+}
+
+/// `<rollout> <weight> [-n <namespace>]` of `set-weight`
+fn set_weight_args(args: &[String]) -> Option<(RolloutTarget<'_>, i32)> {
+    let [name, weight, flags @ ..] = args else {
+        return None;
+    };
+    let weight = weight
+        .parse()
+        .ok()
+        .filter(|weight| (0..=100).contains(weight))?;
+    let namespace = match flags {
+        [] => None,
+        [flag, namespace] if flag == "-n" || flag == "--namespace" => Some(namespace.as_str()),
+        _ => return None,
+    };
+    let target = RolloutTarget {
+        name: name.as_str(),
+        namespace,
+        watch: false,
+    };
+    (!name.starts_with('-')).then_some((target, weight))
+}
+
+/// Annotation patch setting the canary weight outside the step plan
+fn set_weight_patch(weight: i32) -> serde_json::Value {
+    serde_json::json!({
+        "metadata": { "annotations": { SET_WEIGHT_ANNOTATION: weight.to_string() } }
+    })
+}
+
+/// Human-readable summary of a Rollout's status for `kulta status`
+fn format_status(rollout: &Rollout) -> String {
+    let status = rollout.status.clone().unwrap_or_default();
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    format!(
+        "Name:      {}\nPhase:     {}\nStrategy:  {}\nStep:      {}\nWeight:    {}\nMessage:   {}\n",
+        rollout.name_any(),
+        or_dash(status.phase.map(|p| format!("{:?}", p))),
+        or_dash(status.strategy.map(|s| s.as_str().to_string())),
+        or_dash(status.current_step_index.map(|i| i.to_string())),
+        or_dash(status.current_weight.map(|w| format!("{}%", w))),
+        or_dash(status.message),
+    )
+}
+
+/// Rollouts API for a target, in its namespace or the kubeconfig's
+async fn rollout_api(target: &RolloutTarget<'_>) -> anyhow::Result<Api<Rollout>> {
+    let client = Client::try_default().await?;
+    Ok(match target.namespace {
+        Some(namespace) => Api::namespaced(client, namespace),
+        None => Api::default_namespaced(client),
+    })
+}
+
+/// `kulta promote|abort|retry <rollout> [-n <namespace>]`: annotate the Rollout
+///
+/// The controller acts on the annotation at its next reconcile and removes it.
+async fn run_request(command: &str, args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let usage = || anyhow::anyhow!("usage: kulta {} <rollout> [-n <namespace>]", command);
+    let target = rollout_target(args)
+        .filter(|target| !target.watch)
+        .ok_or_else(usage)?;
+    let patch = request_patch(command).ok_or_else(usage)?;
+
+    let api = rollout_api(&target).await?;
+    api.patch(target.name, &PatchParams::default(), &Patch::Merge(&patch))
+        .await?;
+    writeln!(
+        std::io::stdout().lock(),
+        "rollout {} {} requested",
+        target.name,
+        command
+    )?;
+    Ok(())
+}
+
+/// `kulta set-weight <rollout> <weight> [-n <namespace>]`: override the canary weight
+///
+/// The controller applies the weight at its next reconcile, within the
+/// guardrail limits, and removes the annotation.
+async fn run_set_weight(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let (target, weight) = set_weight_args(args).ok_or_else(|| {
+        anyhow::anyhow!("usage: kulta set-weight <rollout> <0-100> [-n <namespace>]")
+    })?;
+    let api = rollout_api(&target).await?;
+    api.patch(
+        target.name,
+        &PatchParams::default(),
+        &Patch::Merge(&set_weight_patch(weight)),
+    )
+    .await?;
+    writeln!(
+        std::io::stdout().lock(),
+        "rollout {} weight {}% requested",
+        target.name,
+        weight
+    )?;
+    Ok(())
+}
+
+/// `kulta status <rollout> [-n <namespace>] [--watch]`: print the Rollout status
+///
+/// With `--watch`, prints every change until the rollout completes (exit 0)
+/// or fails (exit 1).
+async fn run_status(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let target = rollout_target(args).ok_or_else(|| {
+        anyhow::anyhow!("usage: kulta status <rollout> [-n <namespace>] [--watch]")
+    })?;
+    let api = rollout_api(&target).await?;
+
+    if !target.watch {
+        let status = format_status(&api.get(target.name).await?);
+        write!(std::io::stdout().lock(), "{}", status)?;
+        return Ok(());
+    }
+
+    let config = watcher::Config::default().fields(&format!("metadata.name={}", target.name));
+    let mut rollouts = std::pin::pin!(watcher(api, config).applied_objects());
+    let mut last = String::new();
+    while let Some(rollout) = rollouts.next().await {
+        let rollout = rollout?;
+        let summary = format_status(&rollout);
+        if summary != last {
+            writeln!(std::io::stdout().lock(), "{}", summary)?;
+            last = summary;
+        }
+        match rollout.status.and_then(|s| s.phase) {
+            Some(Phase::Completed) => return Ok(()),
+            Some(Phase::Failed) => anyhow::bail!("rollout {} failed", target.name),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// `kulta support-bundle <rollout> [-n <namespace>]`: print a support bundle
+///
+/// The JSON bundle goes to stdout, to be redirected into a file and attached
+/// to a bug report. What couldn't be collected is also printed to stderr.
+async fn run_support_bundle(args: &[String]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let target = rollout_target(args)
+        .filter(|target| !target.watch)
+        .ok_or_else(|| anyhow::anyhow!("usage: kulta support-bundle <rollout> [-n <namespace>]"))?;
+    let client = Client::try_default().await?;
+    let namespace = target
+        .namespace
+        .unwrap_or(client.default_namespace())
+        .to_string();
+    let bundle = collect_support_bundle(
+        &client,
+        &namespace,
+        target.name,
+        &SupportBundleOptions::default(),
+    )
+    .await?;
+    let mut stderr = std::io::stderr().lock();
+    for error in &bundle.errors {
+        writeln!(stderr, "warning: {}", error)?;
+    }
+    writeln!(
+        std::io::stdout().lock(),
+        "{}",
+        serde_json::to_string_pretty(&bundle)?
+    )?;
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // CLI subcommands don't need the controller runtime (plan, preview, lint and convert not even a cluster)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("plan") => return run_plan(&args[1..]),
+        Some("preview") => return run_preview(&args[1..]),
+        Some("lint") => return run_lint(&args[1..]),
+        Some("convert") => return run_convert(&args[1..]),
+        Some(command @ ("promote" | "abort" | "retry")) => {
+            return run_request(command, &args[1..]).await
+        }
+        Some("set-weight") => return run_set_weight(&args[1..]).await,
+        Some("status") => return run_status(&args[1..]).await,
+        Some("support-bundle") => return run_support_bundle(&args[1..]).await,
+        _ => {}
+    }
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -172,8 +586,15 @@ async fn main() -> anyhow::Result<()> {
         "Server task spawned"
     );
 
+    // Per-object leases replace leader election: every replica reconciles,
+    // one object at a time (experimental)
+    let object_lease_config = ObjectLeaseConfig::from_env();
+    if object_lease_config.is_some() && is_leader_election_enabled() {
+        warn!("KULTA_OBJECT_LEASES is set, ignoring KULTA_LEADER_ELECTION");
+    }
+
     // Start leader election if enabled
-    let leader_election_enabled = is_leader_election_enabled();
+    let leader_election_enabled = is_leader_election_enabled() && object_lease_config.is_none();
     let leader_handle = if leader_election_enabled {
         let leader_client = client.clone();
         let leader_config = LeaderConfig::from_env();
@@ -195,31 +616,62 @@ async fn main() -> anyhow::Result<()> {
             .await;
         }))
     } else {
-        info!("Leader election disabled - running as single instance");
-        // If no leader election, we're always the leader
-        leader_state.set_leader(true);
+        match &object_lease_config {
+            // Cluster-wide tasks are guarded by their own Leases instead
+            Some(config) => info!(
+                holder_id = %config.holder_id,
+                lease_seconds = config.lease_duration_seconds,
+                "Per-object leases enabled (experimental) - replicas share reconciles"
+            ),
+            None => {
+                info!("Leader election disabled - running as single instance");
+                // If no leader election, we're always the leader
+                leader_state.set_leader(true);
+            }
+        }
         None
     };
+    let object_leases =
+        object_lease_config.map(|config| Arc::new(ObjectLeases::new(client.clone(), config)));
+
+    // Garbage-collect ReplicaSets whose Rollout was deleted while we weren't watching
+    let orphan_gc_handle = tokio::spawn(run_orphan_gc(
+        client.clone(),
+        OrphanGcConfig::from_env(),
+        leader_state.clone(),
+        object_leases.clone(),
+        shutdown_signal.clone(),
+    ));
 
     // Create API for Rollout resources
     let rollouts = Api::<Rollout>::all(client.clone());
 
     // Create CDEvents sink (configured from env vars)
-    let cdevents_sink = HttpEventSink::new();
+    let cdevents_sink = HttpEventSink::new().with_metrics(metrics.clone());
     info!(
         enabled = std::env::var("KULTA_CDEVENTS_ENABLED").unwrap_or_else(|_| "false".to_string()),
         "CDEvents sink configured"
     );
 
-    // Create Prometheus client (configured from env var)
-    let prometheus_address =
-        std::env::var("KULTA_PROMETHEUS_ADDRESS").unwrap_or_else(|_| "".to_string());
-    let prometheus_client = if prometheus_address.is_empty() {
+    // Create Prometheus client (configured from env vars)
+    let prometheus_addresses =
+        prometheus::parse_addresses(&std::env::var("KULTA_PROMETHEUS_ADDRESS").unwrap_or_default());
+    let prometheus_policy = prometheus::ReplicaPolicy::from_env();
+    let prometheus_options = prometheus_auth::ClientOptions::from_env()?;
+    let prometheus_client = if prometheus_addresses.is_empty() {
         info!("Prometheus address not configured - metrics analysis disabled");
         HttpPrometheusClient::new("http://localhost:9090".to_string()) // Dummy address, metrics will be skipped
     } else {
-        info!(address = %prometheus_address, "Prometheus client configured");
-        HttpPrometheusClient::new(prometheus_address)
+        info!(
+            addresses = ?prometheus_addresses,
+            policy = ?prometheus_policy,
+            "Prometheus client configured"
+        );
+        HttpPrometheusClient::with_options(
+            prometheus_addresses,
+            prometheus_policy,
+            &prometheus_options,
+        )?
     };
 
     // Create clock for time-dependent logic
@@ -227,24 +679,26 @@ async fn main() -> anyhow::Result<()> {
         Arc::new(kulta::controller::clock::SystemClock);
 
     // Create controller context (with metrics for observability)
-    let ctx = if leader_election_enabled {
-        Arc::new(Context::new_with_leader(
+    let mut ctx = if leader_election_enabled {
+        Context::new_with_leader(
             client.clone(),
             cdevents_sink,
             prometheus_client,
             clock,
             leader_state.clone(),
             Some(metrics.clone()),
-        ))
+        )
     } else {
-        Arc::new(Context::new(
+        Context::new(
             client.clone(),
             cdevents_sink,
             prometheus_client,
             clock,
             Some(metrics.clone()),
-        ))
+        )
     };
+    ctx.object_leases = object_leases;
+    let ctx = Arc::new(ctx);
 
     // Mark as ready - controller is initialized and about to start
     //
@@ -256,22 +710,153 @@ async fn main() -> anyhow::Result<()> {
     readiness.set_ready();
     info!("Controller ready, starting reconciliation loop");
 
-    // Create the controller stream
-    // Note: error_policy already logs errors with warn!, so we only log success here
-    let controller = Controller::new(rollouts, watcher::Config::default())
-        .run(reconcile, error_policy, ctx)
-        .for_each(|res| async move {
-            if let Ok(o) = res {
-                info!("Reconciled: {:?}", o);
+    let idle_config = IdleConfig::from_env();
+    if idle_config.enabled {
+        info!(
+            poll_interval_secs = idle_config.poll_interval.as_secs(),
+            "Idle mode enabled"
+        );
+    }
+
+    // Run the controller, dropping back to idle polling when no Rollouts exist.
+    // Dropping the controller stream closes its watches and frees its caches.
+    let controller_loop = async {
+        loop {
+            if idle_config.enabled {
+                wait_for_rollouts(&rollouts, &idle_config).await;
+            }
+
+            // Create the controller stream
+            // Note: error_policy already logs errors with warn!, so we only log success here
+            let controller = Controller::new(rollouts.clone(), watcher::Config::default());
+
+            // Changed step ladder ConfigMaps re-reconcile the Rollouts importing them
+            let store = controller.store();
+            let workload_store = store.clone();
+            let queue_metrics =
+                report_queue_metrics(ctx.rollout_queue.clone(), store.clone(), metrics.clone());
+            let steps_ctx = ctx.clone();
+            let controller = controller
+                .watches(
+                    Api::<ConfigMap>::all(client.clone()),
+                    watcher::Config::default(),
+                    move |cm| {
+                        let namespace = cm.namespace().unwrap_or_default();
+                        let name = cm.name_any();
+                        steps_ctx.steps_resolver.invalidate(&namespace, &name);
+                        rollouts_importing_steps(&store.state(), &namespace, &name)
+                    },
+                )
+                .watches(
+                    Api::<Deployment>::all(client.clone()),
+                    watcher::Config::default(),
+                    move |deployment| {
+                        let namespace = deployment.namespace().unwrap_or_default();
+                        rollouts_referencing_workload(
+                            &workload_store.state(),
+                            &namespace,
+                            &deployment.name_any(),
+                        )
+                    },
+                )
+                .run(reconcile, error_policy, ctx.clone())
+                .for_each(|res| async move {
+                    if let Ok(o) = res {
+                        info!("Reconciled: {:?}", o);
+                    }
+                    // Errors are logged in error_policy, no duplicate logging
+                });
+            // Queue metrics are reported for as long as this controller stream runs
+            let controller = async move {
+                tokio::select! {
+                    _ = controller => {}
+                    _ = queue_metrics => {}
+                }
+            };
+
+            if !idle_config.enabled {
+                controller.await;
+                return;
+            }
+
+            tokio::select! {
+                _ = controller => return,
+                _ = wait_until_idle(&rollouts, &idle_config) => {
+                    info!("No Rollouts remain, stopping controller and dropping caches");
+                }
+            }
+        }
+    };
+
+    // ClusterRollouts get a second controller stream, unaffected by idle mode.
+    // Clusters without the ClusterRollout CRD simply don't run it.
+    let cluster_rollouts = Api::<ClusterRollout>::all(client.clone());
+    let cluster_controller = async {
+        if let Err(kube::Error::Api(err)) =
+            cluster_rollouts.list(&ListParams::default().limit(1)).await
+        {
+            if err.code == 404 {
+                info!("ClusterRollout CRD not installed - cluster-scoped rollouts disabled");
+                return std::future::pending::<()>().await;
             }
-            // Errors are logged in error_policy, no duplicate logging
-        });
+        }
+
+        let controller = Controller::new(cluster_rollouts.clone(), watcher::Config::default());
+        let queue_metrics = report_queue_metrics(
+            ctx.cluster_rollout_queue.clone(),
+            controller.store(),
+            metrics.clone(),
+        );
+        let controller = controller
+            .run(reconcile_cluster_rollout, cluster_error_policy, ctx.clone())
+            .for_each(|res| async move {
+                if let Ok(o) = res {
+                    info!("Reconciled: {:?}", o);
+                }
+            });
+        tokio::select! {
+            _ = controller => {}
+            _ = queue_metrics => {}
+        }
+    };
+
+    // Experiments get their own stream as well, watching the ReplicaSets they
+    // own for readiness. Skipped when the Experiment CRD isn't installed.
+    let experiments = Api::<Experiment>::all(client.clone());
+    let experiment_controller = async {
+        if let Err(kube::Error::Api(err)) = experiments.list(&ListParams::default().limit(1)).await
+        {
+            if err.code == 404 {
+                info!("Experiment CRD not installed - experiments disabled");
+                return std::future::pending::<()>().await;
+            }
+        }
+
+        Controller::new(experiments.clone(), watcher::Config::default())
+            .owns(
+                Api::<ReplicaSet>::all(client.clone()),
+                watcher::Config::default().labels(EXPERIMENT_LABEL),
+            )
+            .run(reconcile_experiment, experiment_error_policy, ctx.clone())
+            .for_each(|res| async move {
+                if let Ok(o) = res {
+                    info!("Reconciled: {:?}", o);
+                }
+            })
+            .await;
+    };
 
-    // Run controller until shutdown signal received
+    // Run controllers until shutdown signal received
     tokio::select! {
-        _ = controller => {
+        _ = controller_loop => {
             info!("Controller stream ended");
         }
+        _ = cluster_controller => {
+            info!("ClusterRollout controller stream ended");
+        }
+        _ = experiment_controller => {
+            info!("Experiment controller stream ended");
+        }
         signal = wait_for_signal() => {
             info!(signal = signal, "Initiating graceful shutdown");
             // Mark not ready so K8s stops sending traffic during shutdown
@@ -288,6 +873,7 @@ async fn main() -> anyhow::Result<()> {
     if let Some(handle) = leader_handle {
         handle.abort();
     }
+    orphan_gc_handle.abort();
     health_handle.abort();
 
     info!("KULTA controller shut down gracefully");
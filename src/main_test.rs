@@ -15,3 +15,150 @@ fn test_error_policy_returns_requeue() {
     // This is a smoke test to ensure the constant hasn't changed
     assert_eq!(expected_requeue_duration, Duration::from_secs(10));
 }
+
+#[test]
+fn test_plan_manifest_path() {
+    use super::plan_manifest_path;
+
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        plan_manifest_path(&args(&["-f", "rollout.yaml"])),
+        Some("rollout.yaml")
+    );
+    assert_eq!(plan_manifest_path(&args(&["--filename", "-"])), Some("-"));
+    assert_eq!(plan_manifest_path(&args(&["rollout.yaml"])), None);
+    assert_eq!(plan_manifest_path(&args(&[])), None);
+}
+
+#[test]
+fn test_preview_args() {
+    use super::preview_args;
+
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        preview_args(&args(&["-f", "rollout.yaml", "--step", "2"])),
+        Some(("rollout.yaml", 2))
+    );
+    assert_eq!(
+        preview_args(&args(&["--step", "0", "--filename", "-"])),
+        Some(("-", 0))
+    );
+    assert_eq!(preview_args(&args(&["-f", "rollout.yaml"])), None);
+    assert_eq!(
+        preview_args(&args(&["-f", "rollout.yaml", "--step", "two"])),
+        None
+    );
+}
+
+#[test]
+fn test_lint_args() {
+    use super::lint_args;
+
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        lint_args(&args(&["-f", "rollout.yaml"])),
+        Some(("rollout.yaml", false))
+    );
+    assert_eq!(
+        lint_args(&args(&["--production", "--filename", "-"])),
+        Some(("-", true))
+    );
+    assert_eq!(lint_args(&args(&["-f", "rollout.yaml", "--strict"])), None);
+}
+
+#[test]
+fn test_rollout_target() {
+    use super::{rollout_target, RolloutTarget};
+
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        rollout_target(&args(&["checkout", "-n", "shop"])),
+        Some(RolloutTarget {
+            name: "checkout",
+            namespace: Some("shop"),
+            watch: false,
+        })
+    );
+    assert_eq!(
+        rollout_target(&args(&["--watch", "checkout"])),
+        Some(RolloutTarget {
+            name: "checkout",
+            namespace: None,
+            watch: true,
+        })
+    );
+    assert_eq!(rollout_target(&args(&[])), None);
+    assert_eq!(rollout_target(&args(&["checkout", "-n"])), None);
+    assert_eq!(rollout_target(&args(&["checkout", "cart"])), None);
+    assert_eq!(rollout_target(&args(&["checkout", "--force"])), None);
+}
+
+#[test]
+fn test_request_patch() {
+    use super::request_patch;
+
+    assert_eq!(
+        request_patch("promote").unwrap()["metadata"]["annotations"]["kulta.io/promote"],
+        "true"
+    );
+    let abort = request_patch("abort").unwrap();
+    assert_eq!(abort["metadata"]["annotations"]["kulta.io/abort"], "true");
+    assert!(abort["metadata"]["annotations"]["kulta.io/retry"].is_null());
+    let retry = request_patch("retry").unwrap();
+    assert_eq!(retry["metadata"]["annotations"]["kulta.io/retry"], "true");
+    assert!(request_patch("status").is_none());
+}
+
+#[test]
+fn test_set_weight_args() {
+    use super::{set_weight_args, set_weight_patch, RolloutTarget};
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        set_weight_args(&args(&["checkout", "35", "-n", "shop"])),
+        Some((
+            RolloutTarget {
+                name: "checkout",
+                namespace: Some("shop"),
+                watch: false
+            },
+            35
+        ))
+    );
+    assert_eq!(set_weight_args(&args(&["checkout"])), None);
+    assert_eq!(set_weight_args(&args(&["checkout", "150"])), None);
+    assert_eq!(set_weight_args(&args(&["checkout", "35", "--watch"])), None);
+    assert_eq!(
+        set_weight_patch(0)["metadata"]["annotations"]["kulta.io/set-weight"],
+        "0"
+    );
+}
+
+#[test]
+fn test_format_status() {
+    use super::format_status;
+    use kulta::api::{Phase, RolloutBuilder, RolloutStatus, SimpleStrategy};
+
+    let mut rollout = RolloutBuilder::new("checkout")
+        .label("app", "checkout")
+        .image("app", "checkout:2")
+        .simple(SimpleStrategy { analysis: None })
+        .build()
+        .unwrap();
+    assert!(format_status(&rollout).contains("Phase:     -\n"));
+
+    rollout.status = Some(RolloutStatus {
+        phase: Some(Phase::Paused),
+        current_step_index: Some(1),
+        current_weight: Some(20),
+        ..Default::default()
+    });
+    let summary = format_status(&rollout);
+    assert!(summary.starts_with("Name:      checkout\n"));
+    assert!(summary.contains("Phase:     Paused\n"));
+    assert!(summary.contains("Weight:    20%\n"));
+}
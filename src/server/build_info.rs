@@ -0,0 +1,25 @@
+//! Controller build identity
+//!
+//! Values are captured at compile time (see `build.rs`) and exposed through
+//! the `kulta_build_info` gauge and the `/version` endpoint so operators can
+//! inventory controller versions across clusters.
+
+use serde::Serialize;
+
+/// Build identity of the running controller
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Crate version (Cargo.toml)
+    pub version: &'static str,
+    /// Short git commit SHA, or "unknown"
+    pub git_sha: &'static str,
+    /// `rustc --version` of the compiler that built the binary
+    pub rustc: &'static str,
+}
+
+/// Build identity of this binary
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    git_sha: env!("KULTA_GIT_SHA"),
+    rustc: env!("KULTA_RUSTC_VERSION"),
+};
@@ -3,15 +3,21 @@
 //! - `/healthz` - Liveness: Is the process alive?
 //! - `/readyz` - Readiness: Is the controller ready to handle requests?
 //! - `/metrics` - Prometheus metrics in text format
+//! - `/version` - Controller build identity (JSON)
+//! - `/argocd/health.lua` - Argo CD custom health check for Rollouts
 //! - `/convert` - CRD conversion webhook (v1alpha1 <-> v1beta1)
 
+use crate::controller::argocd::health_check_lua;
+use crate::server::build_info::BUILD_INFO;
 use crate::server::metrics::SharedMetrics;
+use crate::server::scaler::{NamespaceActivity, RolloutActivity};
+use crate::server::webhook::AdmissionPolicy;
 use axum::{
-    extract::State,
+    extract::{Path, State},
     http::{header::CONTENT_TYPE, StatusCode},
     response::IntoResponse,
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -60,17 +66,30 @@ impl Default for ReadinessState {
     }
 }
 
-/// Combined server state for health and metrics endpoints
+/// Combined server state for health, metrics and webhook endpoints
 #[derive(Clone)]
 pub struct ServerState {
     readiness: ReadinessState,
     metrics: SharedMetrics,
+    admission: Arc<AdmissionPolicy>,
 }
 
 impl ServerState {
     /// Create new server state
+    ///
+    /// The admission policy is read from the environment here, once, rather
+    /// than on every `/validate` request.
     pub fn new(readiness: ReadinessState, metrics: SharedMetrics) -> Self {
-        Self { readiness, metrics }
+        Self {
+            readiness,
+            metrics,
+            admission: Arc::new(AdmissionPolicy::from_env()),
+        }
+    }
+
+    /// Policy and defaults `/validate` checks admission requests against
+    pub fn admission(&self) -> &AdmissionPolicy {
+        &self.admission
     }
 }
 
@@ -111,6 +130,44 @@ async fn metrics(State(state): State<ServerState>) -> impl IntoResponse {
     }
 }
 
+/// Version handler
+///
+/// Returns the controller build identity as JSON.
+async fn version() -> impl IntoResponse {
+    Json(BUILD_INFO)
+}
+
+/// Argo CD health check handler
+///
+/// Returns the Lua script for `resource.customizations.health.kulta.io_Rollout`.
+async fn argocd_health_lua() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+        health_check_lua(),
+    )
+}
+
+/// Scaler handler for one Rollout
+///
+/// Returns whether the Rollout is in flight, for KEDA's metrics-api scaler.
+async fn rollout_activity(
+    State(state): State<ServerState>,
+    Path((namespace, name)): Path<(String, String)>,
+) -> Json<RolloutActivity> {
+    Json(state.metrics.activity().rollout(&namespace, &name))
+}
+
+/// Scaler handler for a namespace
+///
+/// Returns how many of the namespace's Rollouts are in flight.
+async fn namespace_activity(
+    State(state): State<ServerState>,
+    Path(namespace): Path<String>,
+) -> Json<NamespaceActivity> {
+    Json(state.metrics.activity().namespace(&namespace))
+}
+
 /// Build the router for health, metrics, and webhook endpoints
 fn build_router(readiness: ReadinessState, metrics: SharedMetrics) -> Router {
     let state = ServerState::new(readiness, metrics);
@@ -119,6 +176,10 @@ fn build_router(readiness: ReadinessState, metrics: SharedMetrics) -> Router {
         .route("/healthz", get(healthz))
         .route("/readyz", get(readyz))
         .route("/metrics", get(self::metrics))
+        .route("/version", get(version))
+        .route("/argocd/health.lua", get(argocd_health_lua))
+        .route("/scaler/rollouts/{namespace}", get(namespace_activity))
+        .route("/scaler/rollouts/{namespace}/{name}", get(rollout_activity))
         .route("/convert", post(super::webhook::handle_convert))
         .route("/validate", post(super::webhook::handle_validate))
         .with_state(state)
@@ -130,6 +191,9 @@ fn build_router(readiness: ReadinessState, metrics: SharedMetrics) -> Router {
 /// - GET /healthz - Always returns 200 OK (liveness)
 /// - GET /readyz - Returns 200 OK if ready, 503 Service Unavailable if not
 /// - GET /metrics - Prometheus metrics in text format
+/// - GET /version - Controller build identity
+/// - GET /argocd/health.lua - Argo CD health check for Rollouts
+/// - GET /scaler/rollouts/{namespace}[/{name}] - Rollouts in flight (KEDA)
 ///
 /// # Arguments
 /// * `port` - The port to listen on
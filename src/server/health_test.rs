@@ -173,7 +173,7 @@ async fn test_metrics_returns_prometheus_format() {
     let port = 18083;
 
     // Record some metrics so they appear in output
-    metrics.record_reconciliation_success("canary", 0.5);
+    metrics.record_reconciliation_success("default", "canary", 0.5);
 
     // Start server in background
     let server_readiness = readiness.clone();
@@ -221,3 +221,180 @@ async fn test_metrics_returns_prometheus_format() {
 
     server_handle.abort();
 }
+
+/// Test that /version returns the build identity as JSON
+#[tokio::test]
+async fn test_version_returns_build_info() {
+    let readiness = ReadinessState::new();
+    let metrics = create_metrics().expect("create metrics");
+    let port = 18084;
+
+    let server_handle =
+        tokio::spawn(async move { run_health_server(port, readiness, metrics).await });
+
+    let client = wait_for_server(port, 10).await;
+
+    let response = client
+        .get(format!("http://127.0.0.1:{}/version", port))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .expect("Failed to connect to version endpoint");
+
+    assert_eq!(response.status(), 200, "Version should return 200");
+
+    let body: serde_json::Value = response.json().await.expect("should be JSON");
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["git_sha"].is_string());
+    assert!(body["rustc"].is_string());
+
+    server_handle.abort();
+}
+
+/// Test that /argocd/health.lua serves the Argo CD health check
+#[tokio::test]
+async fn test_argocd_health_lua_is_served() {
+    let readiness = ReadinessState::new();
+    let metrics = create_metrics().expect("create metrics");
+    let port = 18085;
+
+    let server_handle =
+        tokio::spawn(async move { run_health_server(port, readiness, metrics).await });
+
+    let client = wait_for_server(port, 10).await;
+
+    let response = client
+        .get(format!("http://127.0.0.1:{}/argocd/health.lua", port))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .expect("Failed to connect to Argo CD health endpoint");
+
+    assert_eq!(response.status(), 200, "Health check should return 200");
+
+    let body = response.text().await.expect("should be text");
+    assert_eq!(body, crate::controller::argocd::health_check_lua());
+
+    server_handle.abort();
+}
+
+/// Test that /scaler/rollouts serves rollout activity for KEDA
+#[tokio::test]
+async fn test_scaler_endpoints_serve_rollout_activity() {
+    let readiness = ReadinessState::new();
+    let metrics = create_metrics().expect("create metrics");
+    let rollout: crate::crd::rollout::Rollout = serde_json::from_value(serde_json::json!({
+        "apiVersion": "kulta.io/v1alpha1",
+        "kind": "Rollout",
+        "metadata": { "name": "checkout", "namespace": "shop" },
+        "spec": {
+            "replicas": 6,
+            "selector": {},
+            "template": { "spec": { "containers": [{ "name": "app", "image": "app:2" }] } },
+            "strategy": { "canary": { "stableService": "s", "canaryService": "c" } }
+        }
+    }))
+    .expect("valid rollout");
+    let status = crate::crd::rollout::RolloutStatus {
+        phase: Some(crate::crd::rollout::Phase::Progressing),
+        current_weight: Some(20),
+        ..Default::default()
+    };
+    metrics.record_rollout_activity(crate::server::scaler::RolloutActivity::new(
+        &rollout, &status,
+    ));
+    let port = 18086;
+
+    let server_metrics = metrics.clone();
+    let server_handle =
+        tokio::spawn(async move { run_health_server(port, readiness, server_metrics).await });
+
+    let client = wait_for_server(port, 10).await;
+
+    let get = |path: &str| {
+        client
+            .get(format!("http://127.0.0.1:{}{}", port, path))
+            .timeout(Duration::from_secs(5))
+            .send()
+    };
+    let body: serde_json::Value = get("/scaler/rollouts/shop/checkout")
+        .await
+        .expect("Failed to connect to scaler endpoint")
+        .json()
+        .await
+        .expect("should be JSON");
+    assert_eq!(body["inProgress"], true);
+    assert_eq!(body["holdReplicas"], 6);
+    assert_eq!(body["canaryWeight"], 20);
+
+    let body: serde_json::Value = get("/scaler/rollouts/shop")
+        .await
+        .expect("Failed to connect to scaler endpoint")
+        .json()
+        .await
+        .expect("should be JSON");
+    assert_eq!(body["rolloutsInProgress"], 1);
+
+    let unknown: serde_json::Value = get("/scaler/rollouts/shop/cart")
+        .await
+        .expect("Failed to connect to scaler endpoint")
+        .json()
+        .await
+        .expect("should be JSON");
+    assert_eq!(unknown["value"], 0);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains("kulta_rollout_in_progress{namespace=\"shop\",rollout=\"checkout\"} 1"));
+
+    server_handle.abort();
+}
+
+/// Test that /validate answers admission reviews from the server state
+#[tokio::test]
+async fn test_validate_answers_admission_reviews() {
+    let readiness = ReadinessState::new();
+    let metrics = create_metrics().expect("create metrics");
+    let port = 18087;
+
+    let server_handle =
+        tokio::spawn(async move { run_health_server(port, readiness, metrics).await });
+
+    let client = wait_for_server(port, 10).await;
+
+    let review = serde_json::json!({
+        "apiVersion": "admission.k8s.io/v1",
+        "kind": "AdmissionReview",
+        "request": {
+            "uid": "review-uid",
+            "kind": { "group": "kulta.io", "version": "v1alpha1", "kind": "Rollout" },
+            "name": "checkout",
+            "namespace": "shop",
+            "operation": "CREATE",
+            "object": {
+                "apiVersion": "kulta.io/v1alpha1",
+                "kind": "Rollout",
+                "metadata": { "name": "checkout", "namespace": "shop" },
+                "spec": {
+                    "replicas": -1,
+                    "selector": {},
+                    "template": { "spec": { "containers": [{ "name": "app", "image": "app:2" }] } },
+                    "strategy": { "simple": {} }
+                }
+            }
+        }
+    });
+    let body: serde_json::Value = client
+        .post(format!("http://127.0.0.1:{}/validate", port))
+        .json(&review)
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .expect("Failed to connect to /validate")
+        .json()
+        .await
+        .expect("should be JSON");
+    assert_eq!(body["response"]["uid"], "review-uid");
+    assert_eq!(body["response"]["allowed"], false);
+
+    server_handle.abort();
+}
@@ -0,0 +1,167 @@
+//! Idle mode for clusters without Rollouts
+//!
+//! On fleet installs KULTA often runs in clusters where nobody has created a
+//! Rollout yet. The controller would still hold a watch open and keep a
+//! reflector cache in memory. In idle mode the controller is only started once
+//! a Rollout exists. Until then a cheap `limit=1` list poll runs instead. The
+//! controller is stopped again (dropping its watches and caches) after the
+//! cluster has been empty for several consecutive polls.
+
+use crate::crd::rollout::Rollout;
+use kube::api::{Api, ListParams};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Default interval between Rollout existence polls
+pub const DEFAULT_IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default number of consecutive empty polls before the controller goes idle
+pub const DEFAULT_IDLE_EMPTY_POLLS: u32 = 3;
+
+/// Idle mode configuration
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdleConfig {
+    /// Whether idle mode is enabled (`KULTA_IDLE_MODE`)
+    pub enabled: bool,
+    /// How often to check for Rollouts (`KULTA_IDLE_POLL_INTERVAL_SECS`)
+    pub poll_interval: Duration,
+    /// Consecutive empty polls required before stopping the controller
+    pub empty_polls_before_idle: u32,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            poll_interval: DEFAULT_IDLE_POLL_INTERVAL,
+            empty_polls_before_idle: DEFAULT_IDLE_EMPTY_POLLS,
+        }
+    }
+}
+
+impl IdleConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create config from an arbitrary key lookup (testable without touching env)
+    ///
+    /// Uses:
+    /// - `KULTA_IDLE_MODE` (`true`/`1` to enable, default disabled)
+    /// - `KULTA_IDLE_POLL_INTERVAL_SECS` (default 30, must be > 0)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+
+        let enabled = lookup("KULTA_IDLE_MODE")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(defaults.enabled);
+
+        let poll_interval = lookup("KULTA_IDLE_POLL_INTERVAL_SECS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.poll_interval);
+
+        Self {
+            enabled,
+            poll_interval,
+            ..defaults
+        }
+    }
+}
+
+/// Tracks consecutive empty polls to decide when to go idle
+///
+/// A single empty list (e.g. a Rollout being deleted and recreated) should not
+/// tear down the controller, so idling requires a run of empty observations.
+#[derive(Debug)]
+pub struct IdleDetector {
+    threshold: u32,
+    consecutive_empty: u32,
+}
+
+impl IdleDetector {
+    /// Create a detector that trips after `threshold` consecutive empty polls
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            consecutive_empty: 0,
+        }
+    }
+
+    /// Record a poll result
+    ///
+    /// Returns true once the threshold of consecutive empty polls is reached.
+    pub fn observe(&mut self, rollouts_exist: bool) -> bool {
+        if rollouts_exist {
+            self.consecutive_empty = 0;
+        } else {
+            self.consecutive_empty = self.consecutive_empty.saturating_add(1);
+        }
+        self.consecutive_empty >= self.threshold
+    }
+}
+
+/// Check whether any Rollout exists in the watched scope
+///
+/// Lists metadata only with `limit=1`, so the cost is independent of how many
+/// Rollouts exist.
+pub async fn rollouts_exist(api: &Api<Rollout>) -> Result<bool, kube::Error> {
+    let list = api.list_metadata(&ListParams::default().limit(1)).await?;
+    Ok(!list.items.is_empty())
+}
+
+/// Wait until at least one Rollout exists
+///
+/// Returns immediately if a Rollout is already present. API errors are logged
+/// and polling continues.
+pub async fn wait_for_rollouts(api: &Api<Rollout>, config: &IdleConfig) {
+    let mut announced = false;
+    loop {
+        match rollouts_exist(api).await {
+            Ok(true) => {
+                if announced {
+                    info!("Rollout detected, leaving idle mode");
+                }
+                return;
+            }
+            Ok(false) => {
+                if !announced {
+                    info!(
+                        poll_interval_secs = config.poll_interval.as_secs(),
+                        "No Rollouts found, entering idle mode"
+                    );
+                    announced = true;
+                } else {
+                    debug!("Still no Rollouts, staying idle");
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to poll for Rollouts while idle");
+            }
+        }
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
+
+/// Wait until the cluster has had no Rollouts for several consecutive polls
+///
+/// Used while the controller is running; when this resolves the caller drops
+/// the controller stream. API errors are logged and do not count as empty.
+pub async fn wait_until_idle(api: &Api<Rollout>, config: &IdleConfig) {
+    let mut detector = IdleDetector::new(config.empty_polls_before_idle);
+    loop {
+        tokio::time::sleep(config.poll_interval).await;
+        match rollouts_exist(api).await {
+            Ok(exist) => {
+                if detector.observe(exist) {
+                    return;
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to poll for Rollouts");
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Tests for idle mode
+
+use super::idle::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn lookup_from(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let map: HashMap<String, String> = pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |key| map.get(key).cloned()
+}
+
+/// Idle mode is opt-in
+#[test]
+fn test_idle_config_defaults() {
+    let config = IdleConfig::from_lookup(lookup_from(&[]));
+    assert!(!config.enabled);
+    assert_eq!(config.poll_interval, DEFAULT_IDLE_POLL_INTERVAL);
+    assert_eq!(config.empty_polls_before_idle, DEFAULT_IDLE_EMPTY_POLLS);
+}
+
+#[test]
+fn test_idle_config_from_lookup() {
+    let config = IdleConfig::from_lookup(lookup_from(&[
+        ("KULTA_IDLE_MODE", "true"),
+        ("KULTA_IDLE_POLL_INTERVAL_SECS", "120"),
+    ]));
+    assert!(config.enabled);
+    assert_eq!(config.poll_interval, Duration::from_secs(120));
+
+    let config = IdleConfig::from_lookup(lookup_from(&[("KULTA_IDLE_MODE", "1")]));
+    assert!(config.enabled);
+}
+
+/// Zero or unparsable intervals fall back to the default
+#[test]
+fn test_idle_config_rejects_invalid_interval() {
+    for value in ["0", "abc", "-5"] {
+        let config =
+            IdleConfig::from_lookup(lookup_from(&[("KULTA_IDLE_POLL_INTERVAL_SECS", value)]));
+        assert_eq!(config.poll_interval, DEFAULT_IDLE_POLL_INTERVAL);
+    }
+}
+
+#[test]
+fn test_idle_detector_requires_consecutive_empty_polls() {
+    let mut detector = IdleDetector::new(3);
+    assert!(!detector.observe(false));
+    assert!(!detector.observe(false));
+    assert!(detector.observe(false));
+}
+
+/// A Rollout reappearing resets the count
+#[test]
+fn test_idle_detector_resets_when_rollouts_reappear() {
+    let mut detector = IdleDetector::new(2);
+    assert!(!detector.observe(false));
+    assert!(!detector.observe(true));
+    assert!(!detector.observe(false));
+    assert!(detector.observe(false));
+}
+
+/// A threshold of zero behaves like one (never idle without an empty poll)
+#[test]
+fn test_idle_detector_zero_threshold() {
+    let mut detector = IdleDetector::new(0);
+    assert!(!detector.observe(true));
+    assert!(detector.observe(false));
+}
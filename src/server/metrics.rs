@@ -4,12 +4,100 @@
 //! - Reconciliation counts and durations
 //! - Rollout phase transitions
 //! - Traffic weight distribution
+//! - Work queue depth, reconcile lag and the longest running reconcile, per
+//!   controller (`rollout`, `cluster_rollout`)
+//! - Traffic shift latency, from a weight patch to the gateway accepting it
+//! - Rollouts in flight, for autoscalers (see [`scaler`](super::scaler))
+//! - Build identity (`kulta_build_info`)
+//!
+//! Reconciliation metrics share one label schema (`namespace`, `strategy`,
+//! `outcome`) so they aggregate per namespace. Label values are bounded:
+//! strategies map to a fixed set, and namespaces (and rollouts for
+//! `kulta_traffic_weight` and `kulta_rollout_in_progress`) beyond a cap are
+//! folded into [`OVERFLOW_LABEL`].
 
+use crate::server::build_info::BUILD_INFO;
+use crate::server::scaler::{ActivityBoard, RolloutActivity};
 use prometheus::{
-    self, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry,
-    TextEncoder,
+    self, Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+    Opts, Registry, TextEncoder,
 };
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Default cap on distinct namespace label values
+pub const DEFAULT_MAX_NAMESPACES: usize = 200;
+
+/// Default cap on distinct rollouts reported by `kulta_traffic_weight`
+pub const DEFAULT_MAX_ROLLOUTS: usize = 1000;
+
+/// Label value used once a cardinality cap is reached
+pub const OVERFLOW_LABEL: &str = "_other";
+
+/// Reconciliation outcome label
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconcileOutcome {
+    Success,
+    Error,
+    /// Not the leader
+    Skipped,
+}
+
+impl ReconcileOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReconcileOutcome::Success => "success",
+            ReconcileOutcome::Error => "error",
+            ReconcileOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+/// Map a strategy name to its metric label
+///
+/// Accepts both handler names (`blue-green`) and spec-style names
+/// (`blue_green`); anything else becomes `unknown`.
+pub fn strategy_label(strategy: &str) -> &'static str {
+    match strategy {
+        "canary" => "canary",
+        "blue-green" | "blue_green" => "blue_green",
+        "ab-testing" | "ab_testing" => "ab_testing",
+        "simple" => "simple",
+        _ => "unknown",
+    }
+}
+
+/// Bounds the number of distinct values a label can take
+///
+/// Values seen before the cap keep their own series; later ones share
+/// [`OVERFLOW_LABEL`].
+struct CardinalityGuard {
+    max: usize,
+    seen: Mutex<HashSet<String>>,
+}
+
+impl CardinalityGuard {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn admit(&self, value: &str) -> String {
+        let Ok(mut seen) = self.seen.lock() else {
+            return OVERFLOW_LABEL.to_string();
+        };
+        if seen.contains(value) {
+            return value.to_string();
+        }
+        if seen.len() < self.max {
+            seen.insert(value.to_string());
+            return value.to_string();
+        }
+        OVERFLOW_LABEL.to_string()
+    }
+}
 
 /// Controller metrics registry
 ///
@@ -18,19 +106,52 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct ControllerMetrics {
     registry: Registry,
-    /// Total reconciliations by result (success, error, skipped)
+    namespaces: Arc<CardinalityGuard>,
+    rollouts: Arc<CardinalityGuard>,
+    /// Total reconciliations by namespace, strategy and outcome (success, error, skipped)
     pub reconciliations_total: IntCounterVec,
-    /// Reconciliation duration in seconds
+    /// Reconciliation duration in seconds by namespace, strategy and outcome
     pub reconciliation_duration_seconds: HistogramVec,
     /// Active rollouts by phase (Progressing, Paused, etc.)
     pub rollouts_active: IntGaugeVec,
     /// Traffic weight per rollout (0-100)
     pub traffic_weight: IntGaugeVec,
+    /// 1 while a rollout is in flight, otherwise 0
+    pub rollout_in_progress: IntGaugeVec,
+    /// Rollout activity served to autoscalers as JSON
+    activity: Arc<ActivityBoard>,
+    /// Objects changed since their last reconcile, by controller
+    pub work_queue_depth: IntGaugeVec,
+    /// Time from an object change to the reconcile picking it up, by controller
+    pub reconcile_lag_seconds: HistogramVec,
+    /// Age of the oldest reconcile still running, by controller (0 when idle)
+    pub reconcile_longest_running_seconds: GaugeVec,
+    /// Time from a weight patch to the gateway accepting (or rejecting) the
+    /// route, by namespace and outcome (accepted, rejected, timeout)
+    pub traffic_shift_duration_seconds: HistogramVec,
+    /// CDEvents over `KULTA_CDEVENTS_MAX_BYTES`, by event type and action
+    /// (truncated, dropped)
+    pub cdevents_oversized_total: IntCounterVec,
+    /// CDEvents given up on, by event type and reason (queue_full,
+    /// retries_exhausted, rejected)
+    pub cdevents_dropped_total: IntCounterVec,
+    /// CDEvents waiting in the retry queue
+    pub cdevents_retry_queue_depth: IntGauge,
+    /// Constant 1, labelled with the controller build identity
+    pub build_info: IntGaugeVec,
 }
 
 impl ControllerMetrics {
     /// Create a new metrics registry with all KULTA metrics
     pub fn new() -> Result<Self, prometheus::Error> {
+        Self::with_limits(DEFAULT_MAX_NAMESPACES, DEFAULT_MAX_ROLLOUTS)
+    }
+
+    /// Create the registry with explicit label cardinality caps
+    pub fn with_limits(
+        max_namespaces: usize,
+        max_rollouts: usize,
+    ) -> Result<Self, prometheus::Error> {
         let registry = Registry::new();
 
         // Reconciliation counter
@@ -39,7 +160,7 @@ impl ControllerMetrics {
                 "kulta_reconciliations_total",
                 "Total number of reconciliations",
             ),
-            &["result"], // success, error, skipped
+            &["namespace", "strategy", "outcome"],
         )?;
         registry.register(Box::new(reconciliations_total.clone()))?;
 
@@ -50,7 +171,7 @@ impl ControllerMetrics {
                 "Duration of reconciliation in seconds",
             )
             .buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
-            &["strategy"], // canary, blue_green, simple
+            &["namespace", "strategy", "outcome"],
         )?;
         registry.register(Box::new(reconciliation_duration_seconds.clone()))?;
 
@@ -74,49 +195,219 @@ impl ControllerMetrics {
         )?;
         registry.register(Box::new(traffic_weight.clone()))?;
 
+        // Rollout in progress gauge (for autoscalers)
+        let rollout_in_progress = IntGaugeVec::new(
+            Opts::new(
+                "kulta_rollout_in_progress",
+                "Whether a rollout is in flight (1) or not (0)",
+            ),
+            &["namespace", "rollout"],
+        )?;
+        registry.register(Box::new(rollout_in_progress.clone()))?;
+
+        // Work queue gauges and lag histogram
+        let work_queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "kulta_work_queue_depth",
+                "Number of objects changed since their last reconcile",
+            ),
+            &["controller"],
+        )?;
+        registry.register(Box::new(work_queue_depth.clone()))?;
+
+        let reconcile_lag_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "kulta_reconcile_lag_seconds",
+                "Time from an object change to its reconcile in seconds",
+            )
+            .buckets(vec![
+                0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+            ]),
+            &["controller"],
+        )?;
+        registry.register(Box::new(reconcile_lag_seconds.clone()))?;
+
+        let reconcile_longest_running_seconds = GaugeVec::new(
+            Opts::new(
+                "kulta_reconcile_longest_running_seconds",
+                "Age of the oldest reconcile still running in seconds",
+            ),
+            &["controller"],
+        )?;
+        registry.register(Box::new(reconcile_longest_running_seconds.clone()))?;
+
+        // Traffic shift latency histogram
+        let traffic_shift_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "kulta_traffic_shift_duration_seconds",
+                "Time from a traffic weight patch to the gateway accepting the route in seconds",
+            )
+            .buckets(vec![
+                0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+            ]),
+            &["namespace", "outcome"],
+        )?;
+        registry.register(Box::new(traffic_shift_duration_seconds.clone()))?;
+
+        // Oversized CDEvents counter
+        let cdevents_oversized_total = IntCounterVec::new(
+            Opts::new(
+                "kulta_cdevents_oversized_total",
+                "CDEvents over the payload size cap, truncated or dropped",
+            ),
+            &["type", "action"],
+        )?;
+        registry.register(Box::new(cdevents_oversized_total.clone()))?;
+
+        // CDEvents delivery: dropped events and retry backlog
+        let cdevents_dropped_total = IntCounterVec::new(
+            Opts::new(
+                "kulta_cdevents_dropped_total",
+                "CDEvents dropped without reaching the sink",
+            ),
+            &["type", "reason"],
+        )?;
+        registry.register(Box::new(cdevents_dropped_total.clone()))?;
+        let cdevents_retry_queue_depth = IntGauge::new(
+            "kulta_cdevents_retry_queue_depth",
+            "CDEvents waiting to be retried",
+        )?;
+        registry.register(Box::new(cdevents_retry_queue_depth.clone()))?;
+
+        // Build info gauge (always 1, identity carried in labels)
+        let build_info = IntGaugeVec::new(
+            Opts::new("kulta_build_info", "KULTA controller build information"),
+            &["version", "git_sha", "rustc"],
+        )?;
+        registry.register(Box::new(build_info.clone()))?;
+        build_info
+            .with_label_values(&[BUILD_INFO.version, BUILD_INFO.git_sha, BUILD_INFO.rustc])
+            .set(1);
+
         Ok(Self {
             registry,
+            namespaces: Arc::new(CardinalityGuard::new(max_namespaces)),
+            rollouts: Arc::new(CardinalityGuard::new(max_rollouts)),
             reconciliations_total,
             reconciliation_duration_seconds,
             rollouts_active,
             traffic_weight,
+            rollout_in_progress,
+            activity: Arc::new(ActivityBoard::default()),
+            work_queue_depth,
+            reconcile_lag_seconds,
+            reconcile_longest_running_seconds,
+            traffic_shift_duration_seconds,
+            cdevents_oversized_total,
+            cdevents_dropped_total,
+            cdevents_retry_queue_depth,
+            build_info,
         })
     }
 
+    /// Record a reconciliation
+    ///
+    /// `duration_secs` is only observed for success and error; skipped
+    /// reconciles do no work.
+    pub fn record_reconciliation(
+        &self,
+        namespace: &str,
+        strategy: &str,
+        outcome: ReconcileOutcome,
+        duration_secs: Option<f64>,
+    ) {
+        let namespace = self.namespaces.admit(namespace);
+        let labels = [
+            namespace.as_str(),
+            strategy_label(strategy),
+            outcome.as_str(),
+        ];
+        self.reconciliations_total.with_label_values(&labels).inc();
+        if let Some(duration_secs) = duration_secs {
+            self.reconciliation_duration_seconds
+                .with_label_values(&labels)
+                .observe(duration_secs);
+        }
+    }
+
     /// Record a successful reconciliation
-    pub fn record_reconciliation_success(&self, strategy: &str, duration_secs: f64) {
-        self.reconciliations_total
-            .with_label_values(&["success"])
-            .inc();
-        self.reconciliation_duration_seconds
-            .with_label_values(&[strategy])
-            .observe(duration_secs);
+    pub fn record_reconciliation_success(
+        &self,
+        namespace: &str,
+        strategy: &str,
+        duration_secs: f64,
+    ) {
+        self.record_reconciliation(
+            namespace,
+            strategy,
+            ReconcileOutcome::Success,
+            Some(duration_secs),
+        );
     }
 
     /// Record a failed reconciliation
-    pub fn record_reconciliation_error(&self, strategy: &str, duration_secs: f64) {
-        self.reconciliations_total
-            .with_label_values(&["error"])
-            .inc();
-        self.reconciliation_duration_seconds
-            .with_label_values(&[strategy])
-            .observe(duration_secs);
+    pub fn record_reconciliation_error(&self, namespace: &str, strategy: &str, duration_secs: f64) {
+        self.record_reconciliation(
+            namespace,
+            strategy,
+            ReconcileOutcome::Error,
+            Some(duration_secs),
+        );
     }
 
     /// Record a skipped reconciliation (not leader)
-    pub fn record_reconciliation_skipped(&self) {
-        self.reconciliations_total
-            .with_label_values(&["skipped"])
-            .inc();
+    pub fn record_reconciliation_skipped(&self, namespace: &str, strategy: &str) {
+        self.record_reconciliation(namespace, strategy, ReconcileOutcome::Skipped, None);
     }
 
     /// Update traffic weight for a rollout
+    ///
+    /// Rollouts past the cap are reported as one [`OVERFLOW_LABEL`] series
+    /// per namespace (last write wins).
     pub fn set_traffic_weight(&self, namespace: &str, rollout: &str, weight: i64) {
+        let (namespace, rollout) = self.rollout_labels(namespace, rollout);
         self.traffic_weight
-            .with_label_values(&[namespace, rollout])
+            .with_label_values(&[namespace.as_str(), rollout])
             .set(weight);
     }
 
+    /// `namespace` and `rollout` label values, within the cardinality caps
+    fn rollout_labels<'a>(&self, namespace: &str, rollout: &'a str) -> (String, &'a str) {
+        let namespace = self.namespaces.admit(namespace);
+        let rollout =
+            if self.rollouts.admit(&format!("{}/{}", namespace, rollout)) == OVERFLOW_LABEL {
+                OVERFLOW_LABEL
+            } else {
+                rollout
+            };
+        (namespace, rollout)
+    }
+
+    /// Record whether a rollout is in flight, for the gauge and the scaler API
+    pub fn record_rollout_activity(&self, activity: RolloutActivity) {
+        let (namespace, rollout) = self.rollout_labels(&activity.namespace, &activity.rollout);
+        self.rollout_in_progress
+            .with_label_values(&[namespace.as_str(), rollout])
+            .set(activity.value);
+        self.activity.record(activity);
+    }
+
+    /// Drop a deleted rollout from the scaler API and its gauge series
+    pub fn forget_rollout_activity(&self, namespace: &str, rollout: &str) {
+        let (namespace_label, rollout_label) = self.rollout_labels(namespace, rollout);
+        if rollout_label != OVERFLOW_LABEL {
+            let _ = self
+                .rollout_in_progress
+                .remove_label_values(&[namespace_label.as_str(), rollout_label]);
+        }
+        self.activity.forget(namespace, rollout);
+    }
+
+    /// Rollout activity served to autoscalers
+    pub fn activity(&self) -> &ActivityBoard {
+        &self.activity
+    }
+
     /// Update active rollout count for a phase
     pub fn set_rollouts_active(&self, phase: &str, strategy: &str, count: i64) {
         self.rollouts_active
@@ -124,6 +415,45 @@ impl ControllerMetrics {
             .set(count);
     }
 
+    /// Record how long a changed object waited for its reconcile
+    pub fn observe_reconcile_lag(&self, controller: &str, lag_secs: f64) {
+        self.reconcile_lag_seconds
+            .with_label_values(&[controller])
+            .observe(lag_secs);
+    }
+
+    /// Update the work queue gauges of a controller
+    pub fn set_work_queue(&self, controller: &str, depth: i64, longest_running_secs: f64) {
+        self.work_queue_depth
+            .with_label_values(&[controller])
+            .set(depth);
+        self.reconcile_longest_running_seconds
+            .with_label_values(&[controller])
+            .set(longest_running_secs);
+    }
+
+    /// Record how long the gateway took to act on a weight patch
+    pub fn observe_traffic_shift(&self, namespace: &str, outcome: &str, duration_secs: f64) {
+        let namespace = self.namespaces.admit(namespace);
+        self.traffic_shift_duration_seconds
+            .with_label_values(&[namespace.as_str(), outcome])
+            .observe(duration_secs);
+    }
+
+    /// Record a CDEvent over the size cap (`truncated` or `dropped`)
+    pub fn record_cdevent_oversized(&self, event_type: &str, action: &str) {
+        self.cdevents_oversized_total
+            .with_label_values(&[event_type, action])
+            .inc();
+    }
+
+    /// Record a CDEvent dropped without reaching the sink
+    pub fn record_cdevent_dropped(&self, event_type: &str, reason: &str) {
+        self.cdevents_dropped_total
+            .with_label_values(&[event_type, reason])
+            .inc();
+    }
+
     /// Encode all metrics to Prometheus text format
     pub fn encode(&self) -> Result<String, prometheus::Error> {
         let encoder = TextEncoder::new();
@@ -2,7 +2,7 @@
 
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
-use super::metrics::{create_metrics, ControllerMetrics};
+use super::metrics::{create_metrics, ControllerMetrics, OVERFLOW_LABEL};
 
 #[test]
 fn test_metrics_creation() {
@@ -10,7 +10,7 @@ fn test_metrics_creation() {
 
     // Record some values so metrics appear in output
     // (Prometheus only outputs metrics with values)
-    metrics.record_reconciliation_success("canary", 0.1);
+    metrics.record_reconciliation_success("default", "canary", 0.1);
     metrics.set_rollouts_active("Progressing", "canary", 1);
     metrics.set_traffic_weight("default", "test", 50);
 
@@ -26,19 +26,20 @@ fn test_metrics_creation() {
 fn test_record_reconciliation_success() {
     let metrics = ControllerMetrics::new().expect("should create metrics");
 
-    metrics.record_reconciliation_success("canary", 0.5);
-    metrics.record_reconciliation_success("canary", 1.2);
-    metrics.record_reconciliation_success("blue_green", 0.3);
+    metrics.record_reconciliation_success("default", "canary", 0.5);
+    metrics.record_reconciliation_success("default", "canary", 1.2);
+    metrics.record_reconciliation_success("default", "blue_green", 0.3);
 
     let output = metrics.encode().expect("should encode metrics");
 
     // Check counter incremented
-    assert!(output.contains("kulta_reconciliations_total{result=\"success\"} 3"));
+    assert!(output.contains("kulta_reconciliations_total{namespace=\"default\",outcome=\"success\",strategy=\"canary\"} 2"));
+    assert!(output.contains("kulta_reconciliations_total{namespace=\"default\",outcome=\"success\",strategy=\"blue_green\"} 1"));
 
     // Check histogram has observations
-    assert!(output.contains("kulta_reconciliation_duration_seconds_count{strategy=\"canary\"} 2"));
+    assert!(output.contains("kulta_reconciliation_duration_seconds_count{namespace=\"default\",outcome=\"success\",strategy=\"canary\"} 2"));
     assert!(
-        output.contains("kulta_reconciliation_duration_seconds_count{strategy=\"blue_green\"} 1")
+        output.contains("kulta_reconciliation_duration_seconds_count{namespace=\"default\",outcome=\"success\",strategy=\"blue_green\"} 1")
     );
 }
 
@@ -46,24 +47,24 @@ fn test_record_reconciliation_success() {
 fn test_record_reconciliation_error() {
     let metrics = ControllerMetrics::new().expect("should create metrics");
 
-    metrics.record_reconciliation_error("canary", 2.0);
+    metrics.record_reconciliation_error("default", "canary", 2.0);
 
     let output = metrics.encode().expect("should encode metrics");
 
-    assert!(output.contains("kulta_reconciliations_total{result=\"error\"} 1"));
-    assert!(output.contains("kulta_reconciliation_duration_seconds_count{strategy=\"canary\"} 1"));
+    assert!(output.contains("kulta_reconciliations_total{namespace=\"default\",outcome=\"error\",strategy=\"canary\"} 1"));
+    assert!(output.contains("kulta_reconciliation_duration_seconds_count{namespace=\"default\",outcome=\"error\",strategy=\"canary\"} 1"));
 }
 
 #[test]
 fn test_record_reconciliation_skipped() {
     let metrics = ControllerMetrics::new().expect("should create metrics");
 
-    metrics.record_reconciliation_skipped();
-    metrics.record_reconciliation_skipped();
+    metrics.record_reconciliation_skipped("default", "canary");
+    metrics.record_reconciliation_skipped("default", "canary");
 
     let output = metrics.encode().expect("should encode metrics");
 
-    assert!(output.contains("kulta_reconciliations_total{result=\"skipped\"} 2"));
+    assert!(output.contains("kulta_reconciliations_total{namespace=\"default\",outcome=\"skipped\",strategy=\"canary\"} 2"));
 }
 
 #[test]
@@ -102,10 +103,10 @@ fn test_create_shared_metrics() {
 
     // Verify Arc sharing works
     let metrics2 = metrics.clone();
-    metrics.record_reconciliation_success("simple", 0.1);
+    metrics.record_reconciliation_success("default", "simple", 0.1);
 
     let output = metrics2.encode().expect("should encode from clone");
-    assert!(output.contains("kulta_reconciliations_total{result=\"success\"} 1"));
+    assert!(output.contains("kulta_reconciliations_total{namespace=\"default\",outcome=\"success\",strategy=\"simple\"} 1"));
 }
 
 #[test]
@@ -113,22 +114,22 @@ fn test_histogram_buckets() {
     let metrics = ControllerMetrics::new().expect("should create metrics");
 
     // Record values in different buckets
-    metrics.record_reconciliation_success("canary", 0.005); // < 0.01
-    metrics.record_reconciliation_success("canary", 0.03); // < 0.05
-    metrics.record_reconciliation_success("canary", 0.8); // < 1.0
-    metrics.record_reconciliation_success("canary", 3.0); // < 5.0
+    metrics.record_reconciliation_success("default", "canary", 0.005); // < 0.01
+    metrics.record_reconciliation_success("default", "canary", 0.03); // < 0.05
+    metrics.record_reconciliation_success("default", "canary", 0.8); // < 1.0
+    metrics.record_reconciliation_success("default", "canary", 3.0); // < 5.0
 
     let output = metrics.encode().expect("should encode metrics");
 
     // Verify histogram has proper bucket structure
     assert!(output
-        .contains("kulta_reconciliation_duration_seconds_bucket{strategy=\"canary\",le=\"0.01\"}"));
+        .contains("kulta_reconciliation_duration_seconds_bucket{namespace=\"default\",outcome=\"success\",strategy=\"canary\",le=\"0.01\"}"));
     assert!(output
-        .contains("kulta_reconciliation_duration_seconds_bucket{strategy=\"canary\",le=\"1\"}"));
+        .contains("kulta_reconciliation_duration_seconds_bucket{namespace=\"default\",outcome=\"success\",strategy=\"canary\",le=\"1\"}"));
     assert!(output
-        .contains("kulta_reconciliation_duration_seconds_bucket{strategy=\"canary\",le=\"+Inf\"}"));
-    assert!(output.contains("kulta_reconciliation_duration_seconds_sum{strategy=\"canary\"}"));
-    assert!(output.contains("kulta_reconciliation_duration_seconds_count{strategy=\"canary\"} 4"));
+        .contains("kulta_reconciliation_duration_seconds_bucket{namespace=\"default\",outcome=\"success\",strategy=\"canary\",le=\"+Inf\"}"));
+    assert!(output.contains("kulta_reconciliation_duration_seconds_sum{namespace=\"default\",outcome=\"success\",strategy=\"canary\"}"));
+    assert!(output.contains("kulta_reconciliation_duration_seconds_count{namespace=\"default\",outcome=\"success\",strategy=\"canary\"} 4"));
 }
 
 #[test]
@@ -138,9 +139,149 @@ fn test_metrics_new_is_infallible_in_practice() {
     let metrics = ControllerMetrics::new().expect("should create metrics");
 
     // Record a value so metric appears in output
-    metrics.record_reconciliation_success("simple", 0.1);
+    metrics.record_reconciliation_success("default", "simple", 0.1);
 
     // Verify basic functionality works
     let output = metrics.encode().expect("should encode metrics");
     assert!(output.contains("kulta_reconciliations_total"));
 }
+
+#[test]
+fn test_build_info_gauge_is_set() {
+    use crate::server::BUILD_INFO;
+
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    let gauge = metrics.build_info.with_label_values(&[
+        BUILD_INFO.version,
+        BUILD_INFO.git_sha,
+        BUILD_INFO.rustc,
+    ]);
+    assert_eq!(gauge.get(), 1);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains("kulta_build_info"));
+    assert!(output.contains(&format!("version=\"{}\"", env!("CARGO_PKG_VERSION"))));
+}
+
+#[test]
+fn test_strategy_label_is_normalized() {
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    // Handler names and spec-style names share one series
+    metrics.record_reconciliation_success("default", "blue-green", 0.1);
+    metrics.record_reconciliation_success("default", "blue_green", 0.1);
+    // Anything else (e.g. a rollout name) can't create new series
+    metrics.record_reconciliation_error("default", "my-app", 0.1);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains(
+        "kulta_reconciliations_total{namespace=\"default\",outcome=\"success\",strategy=\"blue_green\"} 2"
+    ));
+    assert!(output.contains(
+        "kulta_reconciliations_total{namespace=\"default\",outcome=\"error\",strategy=\"unknown\"} 1"
+    ));
+    assert!(!output.contains("my-app"));
+}
+
+#[test]
+fn test_namespace_cardinality_guard() {
+    let metrics = ControllerMetrics::with_limits(2, 10).expect("should create metrics");
+
+    metrics.record_reconciliation_success("team-a", "canary", 0.1);
+    metrics.record_reconciliation_success("team-b", "canary", 0.1);
+    metrics.record_reconciliation_success("team-c", "canary", 0.1);
+    metrics.record_reconciliation_success("team-d", "canary", 0.1);
+    // Namespaces admitted before the cap keep their series
+    metrics.record_reconciliation_success("team-a", "canary", 0.1);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains(
+        "kulta_reconciliations_total{namespace=\"team-a\",outcome=\"success\",strategy=\"canary\"} 2"
+    ));
+    assert!(output.contains(&format!(
+        "kulta_reconciliations_total{{namespace=\"{}\",outcome=\"success\",strategy=\"canary\"}} 2",
+        OVERFLOW_LABEL
+    )));
+    assert!(!output.contains("team-c"));
+}
+
+#[test]
+fn test_traffic_weight_rollout_cap() {
+    let metrics = ControllerMetrics::with_limits(10, 1).expect("should create metrics");
+
+    metrics.set_traffic_weight("default", "first", 20);
+    metrics.set_traffic_weight("default", "second", 40);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains("kulta_traffic_weight{namespace=\"default\",rollout=\"first\"} 20"));
+    assert!(output.contains(&format!(
+        "kulta_traffic_weight{{namespace=\"default\",rollout=\"{}\"}} 40",
+        OVERFLOW_LABEL
+    )));
+}
+
+#[test]
+fn test_work_queue_metrics() {
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    metrics.observe_reconcile_lag("rollout", 0.7);
+    metrics.set_work_queue("rollout", 3, 12.5);
+    metrics.set_work_queue("cluster_rollout", 0, 0.0);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains("kulta_work_queue_depth{controller=\"rollout\"} 3"));
+    assert!(output.contains("kulta_work_queue_depth{controller=\"cluster_rollout\"} 0"));
+    assert!(output.contains("kulta_reconcile_longest_running_seconds{controller=\"rollout\"} 12.5"));
+    assert!(
+        output.contains("kulta_reconcile_lag_seconds_bucket{controller=\"rollout\",le=\"1\"} 1")
+    );
+    assert!(output.contains("kulta_reconcile_lag_seconds_count{controller=\"rollout\"} 1"));
+}
+
+#[test]
+fn test_traffic_shift_metrics() {
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    metrics.observe_traffic_shift("prod", "accepted", 0.8);
+    metrics.observe_traffic_shift("prod", "timeout", 300.0);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains(
+        "kulta_traffic_shift_duration_seconds_bucket{namespace=\"prod\",outcome=\"accepted\",le=\"1\"} 1"
+    ));
+    assert!(output.contains(
+        "kulta_traffic_shift_duration_seconds_count{namespace=\"prod\",outcome=\"timeout\"} 1"
+    ));
+}
+
+#[test]
+fn test_cdevents_oversized_metrics() {
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    metrics.record_cdevent_oversized("dev.cdevents.service.deployed.0.2.0", "truncated");
+    metrics.record_cdevent_oversized("dev.cdevents.service.deployed.0.2.0", "truncated");
+    metrics.record_cdevent_oversized("dev.cdevents.service.deployed.0.2.0", "dropped");
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains(
+        "kulta_cdevents_oversized_total{action=\"truncated\",type=\"dev.cdevents.service.deployed.0.2.0\"} 2"
+    ));
+    assert!(output.contains(
+        "kulta_cdevents_oversized_total{action=\"dropped\",type=\"dev.cdevents.service.deployed.0.2.0\"} 1"
+    ));
+}
+
+#[test]
+fn test_cdevents_dropped_metrics() {
+    let metrics = ControllerMetrics::new().expect("should create metrics");
+
+    metrics.record_cdevent_dropped("dev.cdevents.service.upgraded.0.2.0", "retries_exhausted");
+    metrics.cdevents_retry_queue_depth.set(3);
+
+    let output = metrics.encode().expect("should encode metrics");
+    assert!(output.contains(
+        "kulta_cdevents_dropped_total{reason=\"retries_exhausted\",type=\"dev.cdevents.service.upgraded.0.2.0\"} 1"
+    ));
+    assert!(output.contains("kulta_cdevents_retry_queue_depth 3"));
+}
@@ -4,21 +4,35 @@
 //! - `/healthz` - Liveness probe (process is running)
 //! - `/readyz` - Readiness probe (controller is ready to serve)
 //! - `/metrics` - Prometheus metrics endpoint
+//! - `/version` - Controller build identity
+//! - `/scaler/rollouts/...` - Rollouts in flight, for KEDA's metrics-api scaler
 //!
 //! Also provides:
 //! - Graceful shutdown handling for SIGTERM/SIGINT
 //! - Leader election for multi-replica safety
+//! - Per-object leases as a leader-election-free alternative (experimental)
+//! - Idle mode for clusters without Rollouts
 
+pub mod build_info;
 mod health;
+pub mod idle;
 pub mod leader;
 pub mod metrics;
+pub mod object_leases;
+pub mod scaler;
 pub mod shutdown;
 pub mod tls;
 pub mod webhook;
 
+pub use build_info::{BuildInfo, BUILD_INFO};
 pub use health::{run_health_server, run_health_server_tls, ReadinessState};
+pub use idle::{wait_for_rollouts, wait_until_idle, IdleConfig};
 pub use leader::{run_leader_election, LeaderConfig, LeaderState};
 pub use metrics::{create_metrics, ControllerMetrics, SharedMetrics};
+pub use object_leases::{
+    object_lease_name, task_lease_name, Claim, ObjectLeaseConfig, ObjectLeases, CONCURRENCY_TASK,
+    ORPHAN_GC_TASK,
+};
 pub use shutdown::{shutdown_channel, wait_for_signal, ShutdownController, ShutdownSignal};
 pub use tls::{
     build_rustls_config, generate_certificate_bundle, initialize_tls, CertificateBundle, TlsError,
@@ -38,10 +52,22 @@ mod shutdown_tests;
 #[path = "leader_test.rs"]
 mod leader_tests;
 
+#[cfg(test)]
+#[path = "object_leases_test.rs"]
+mod object_leases_tests;
+
+#[cfg(test)]
+#[path = "idle_test.rs"]
+mod idle_tests;
+
 #[cfg(test)]
 #[path = "metrics_test.rs"]
 mod metrics_tests;
 
+#[cfg(test)]
+#[path = "scaler_test.rs"]
+mod scaler_tests;
+
 #[cfg(test)]
 #[path = "tls_test.rs"]
 mod tls_tests;
@@ -0,0 +1,350 @@
+//! Per-object leases for multi-replica work sharing (experimental)
+//!
+//! An alternative to whole-controller leader election for small clusters:
+//! with `KULTA_OBJECT_LEASES=true` every replica runs the controllers, and a
+//! reconcile first claims a short-lived Lease for its object
+//! (`kulta-rollout-<uid>` next to the object and owned by it, so it is
+//! garbage-collected with it). The replica holding the lease reconciles the
+//! object; the others requeue until it expires. Holders renew on every
+//! reconcile, so an object stays with the replica that picked it up and
+//! moves to another one when that replica stops.
+//!
+//! The lease duration (`KULTA_OBJECT_LEASE_SECONDS`) must exceed the longest
+//! reconcile, or two replicas may work on the same object at once. Writes to
+//! a Lease carry its resourceVersion, so of two replicas racing for an
+//! expired lease only one gets it.
+//!
+//! Work that spans objects (orphaned ReplicaSet collection, the per-namespace
+//! concurrency limit) is guarded the same way by a task Lease in the
+//! controller's namespace (`kulta-task-<task>`, see [`ObjectLeases::claim_task`]).
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{MicroTime, OwnerReference};
+use kube::api::{Api, ObjectMeta, Patch, PatchParams, PostParams};
+use kube::Client;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::leader::is_lease_expired;
+
+/// Default object lease TTL
+pub const DEFAULT_OBJECT_LEASE_TTL: Duration = Duration::from_secs(60);
+
+/// Shortest object lease TTL accepted from `KULTA_OBJECT_LEASE_SECONDS`
+const MIN_OBJECT_LEASE_SECONDS: i32 = 10;
+
+/// Per-object lease configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectLeaseConfig {
+    /// Unique identifier for this instance (usually pod name)
+    pub holder_id: String,
+    /// How long a claim is valid (in seconds)
+    pub lease_duration_seconds: i32,
+    /// Namespace of the task Leases (the controller's own)
+    pub task_namespace: String,
+}
+
+impl ObjectLeaseConfig {
+    /// Create config from environment variables, if per-object leases are enabled
+    ///
+    /// Uses `KULTA_OBJECT_LEASES`, `KULTA_OBJECT_LEASE_SECONDS` and, like
+    /// [`LeaderConfig`](super::LeaderConfig), `POD_NAME` for the holder and
+    /// `POD_NAMESPACE` for the task Leases.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Create config from an arbitrary key lookup (testable without touching env)
+    ///
+    /// Invalid values are logged and ignored.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if !lookup("KULTA_OBJECT_LEASES").is_some_and(|v| v == "true" || v == "1") {
+            return None;
+        }
+
+        let holder_id = lookup("POD_NAME")
+            .or_else(|| lookup("HOSTNAME"))
+            .unwrap_or_else(|| format!("kulta-{}", uuid::Uuid::new_v4()));
+        let lease_duration_seconds = lookup("KULTA_OBJECT_LEASE_SECONDS")
+            .and_then(|v| {
+                v.trim()
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|seconds| *seconds >= MIN_OBJECT_LEASE_SECONDS)
+                    .or_else(|| {
+                        warn!(value = %v, "Ignoring invalid KULTA_OBJECT_LEASE_SECONDS");
+                        None
+                    })
+            })
+            .unwrap_or(DEFAULT_OBJECT_LEASE_TTL.as_secs() as i32);
+
+        Some(Self {
+            holder_id,
+            lease_duration_seconds,
+            task_namespace: lookup("POD_NAMESPACE").unwrap_or_else(|| "kulta-system".to_string()),
+        })
+    }
+
+    /// How long a renewed claim is trusted without asking the API server again
+    fn renew_interval(&self) -> chrono::Duration {
+        chrono::Duration::seconds(i64::from(self.lease_duration_seconds) / 3)
+    }
+}
+
+/// Name of the Lease guarding an object, e.g. `kulta-rollout-<uid>`
+///
+/// The uid keeps the name short and unique across namespaces and across
+/// objects recreated under the same name.
+pub fn object_lease_name(kind: &str, uid: &str) -> String {
+    format!("kulta-{}-{}", kind.to_ascii_lowercase(), uid)
+}
+
+/// Task Lease of orphaned ReplicaSet collection
+pub const ORPHAN_GC_TASK: &str = "orphan-gc";
+
+/// Task Lease of starts held back by the per-namespace concurrency limit
+pub const CONCURRENCY_TASK: &str = "concurrency";
+
+/// Name of the Lease guarding a cluster-wide task, e.g. `kulta-task-orphan-gc`
+pub fn task_lease_name(task: &str) -> String {
+    format!("kulta-task-{}", task)
+}
+
+/// What claiming an object's Lease takes, given the Lease as found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseAction {
+    /// No Lease exists yet: create it
+    Create,
+    /// This replica holds it: renew
+    Renew,
+    /// Expired or released: take it over
+    TakeOver { transitions: i32 },
+    /// Another replica holds it until `expires_at`
+    HeldBy {
+        holder: String,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+/// Decide how to claim a Lease for `holder_id`
+///
+/// Pure function that can be unit tested independently.
+pub fn lease_action(lease: Option<&Lease>, holder_id: &str, now: DateTime<Utc>) -> LeaseAction {
+    let Some(lease) = lease else {
+        return LeaseAction::Create;
+    };
+    let spec = lease.spec.as_ref();
+    let holder = spec.and_then(|s| s.holder_identity.as_deref());
+    let renew_time = spec.and_then(|s| s.renew_time.as_ref());
+    let lease_duration = spec.and_then(|s| s.lease_duration_seconds);
+
+    match holder {
+        Some(holder) if holder == holder_id => LeaseAction::Renew,
+        Some(holder) if !is_lease_expired(renew_time, lease_duration, now) => {
+            // is_lease_expired guarantees both are set
+            let expires_at = renew_time.map_or(now, |MicroTime(renew)| *renew)
+                + chrono::Duration::seconds(i64::from(lease_duration.unwrap_or_default()));
+            LeaseAction::HeldBy {
+                holder: holder.to_string(),
+                expires_at,
+            }
+        }
+        _ => LeaseAction::TakeOver {
+            transitions: spec.and_then(|s| s.lease_transitions).unwrap_or(0) + 1,
+        },
+    }
+}
+
+/// Merge patch renewing or taking over a Lease
+///
+/// The resourceVersion makes the API server reject the patch if another
+/// replica wrote the Lease since it was read.
+pub fn claim_patch(
+    resource_version: Option<&str>,
+    action: &LeaseAction,
+    config: &ObjectLeaseConfig,
+    now: DateTime<Utc>,
+) -> serde_json::Value {
+    let now_micro = MicroTime(now);
+    let spec = match action {
+        LeaseAction::TakeOver { transitions } => serde_json::json!({
+            "holderIdentity": config.holder_id,
+            "acquireTime": now_micro,
+            "renewTime": now_micro,
+            "leaseDurationSeconds": config.lease_duration_seconds,
+            "leaseTransitions": transitions
+        }),
+        _ => serde_json::json!({
+            "renewTime": now_micro,
+            "leaseDurationSeconds": config.lease_duration_seconds
+        }),
+    };
+    serde_json::json!({
+        "metadata": { "resourceVersion": resource_version },
+        "spec": spec
+    })
+}
+
+/// Outcome of claiming an object's Lease
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// This replica holds the lease and may reconcile the object
+    Held,
+    /// Another replica holds it (unknown if it won a race just now)
+    Busy {
+        holder: Option<String>,
+        retry_after: Duration,
+    },
+}
+
+/// Claims per-object Leases for this replica
+pub struct ObjectLeases {
+    client: Client,
+    config: ObjectLeaseConfig,
+    /// When each Lease this replica holds was last renewed, by namespace/name
+    renewed: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl ObjectLeases {
+    pub fn new(client: Client, config: ObjectLeaseConfig) -> Self {
+        Self {
+            client,
+            config,
+            renewed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Identifier this replica claims Leases as
+    pub fn holder_id(&self) -> &str {
+        &self.config.holder_id
+    }
+
+    fn record_renewal(&self, name: &str, at: Option<DateTime<Utc>>) {
+        if let Ok(mut renewed) = self.renewed.lock() {
+            match at {
+                Some(at) => {
+                    // Forget leases of objects this replica no longer reconciles
+                    let ttl =
+                        chrono::Duration::seconds(i64::from(self.config.lease_duration_seconds));
+                    renewed.retain(|_, renewed_at| at - *renewed_at < ttl);
+                    renewed.insert(name.to_string(), at);
+                }
+                None => {
+                    renewed.remove(name);
+                }
+            }
+        }
+    }
+
+    fn renewed_recently(&self, name: &str, now: DateTime<Utc>) -> bool {
+        self.renewed
+            .lock()
+            .ok()
+            .and_then(|renewed| renewed.get(name).copied())
+            .is_some_and(|at| now - at < self.config.renew_interval())
+    }
+
+    /// A lost race: another replica wrote the Lease first
+    fn conflict(&self, name: &str) -> Claim {
+        debug!(lease = %name, holder_id = %self.config.holder_id, "Conflict claiming object lease");
+        self.record_renewal(name, None);
+        Claim::Busy {
+            holder: None,
+            retry_after: Duration::from_secs(self.config.lease_duration_seconds as u64 / 3),
+        }
+    }
+
+    /// Claim the Lease `namespace/name`, creating, renewing or taking it over
+    ///
+    /// A Lease renewed within the last third of its duration is trusted
+    /// without a round trip to the API server.
+    ///
+    /// # Arguments
+    /// * `owner` - Owner of a created Lease (the object it guards)
+    pub async fn claim(
+        &self,
+        namespace: &str,
+        name: &str,
+        owner: Option<OwnerReference>,
+        now: DateTime<Utc>,
+    ) -> Result<Claim, kube::Error> {
+        let api: Api<Lease> = Api::namespaced(self.client.clone(), namespace);
+        let key = format!("{}/{}", namespace, name);
+        if self.renewed_recently(&key, now) {
+            return Ok(Claim::Held);
+        }
+
+        let existing = api.get_opt(name).await?;
+        let action = lease_action(existing.as_ref(), &self.config.holder_id, now);
+        let written = match &action {
+            LeaseAction::HeldBy { holder, expires_at } => {
+                self.record_renewal(&key, None);
+                let retry_after = (*expires_at - now).to_std().unwrap_or_default();
+                return Ok(Claim::Busy {
+                    holder: Some(holder.clone()),
+                    retry_after: retry_after + Duration::from_secs(1),
+                });
+            }
+            LeaseAction::Create => {
+                let lease = Lease {
+                    metadata: ObjectMeta {
+                        name: Some(name.to_string()),
+                        namespace: Some(namespace.to_string()),
+                        owner_references: owner.map(|owner| vec![owner]),
+                        ..Default::default()
+                    },
+                    #[allow(clippy::needless_update)]
+                    spec: Some(LeaseSpec {
+                        holder_identity: Some(self.config.holder_id.clone()),
+                        acquire_time: Some(MicroTime(now)),
+                        renew_time: Some(MicroTime(now)),
+                        lease_duration_seconds: Some(self.config.lease_duration_seconds),
+                        lease_transitions: Some(0),
+                        ..Default::default()
+                    }),
+                };
+                api.create(&PostParams::default(), &lease).await
+            }
+            LeaseAction::Renew | LeaseAction::TakeOver { .. } => {
+                let resource_version = existing
+                    .as_ref()
+                    .and_then(|lease| lease.metadata.resource_version.as_deref());
+                let patch = claim_patch(resource_version, &action, &self.config, now);
+                api.patch(name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await
+            }
+        };
+
+        match written {
+            Ok(_) => {
+                if let LeaseAction::TakeOver { .. } = action {
+                    info!(lease = %key, holder_id = %self.config.holder_id, "Took over object lease");
+                }
+                self.record_renewal(&key, Some(now));
+                Ok(Claim::Held)
+            }
+            Err(kube::Error::Api(e)) if e.code == 409 => Ok(self.conflict(&key)),
+            Err(e) => {
+                self.record_renewal(&key, None);
+                Err(e)
+            }
+        }
+    }
+
+    /// Claim the Lease of a cluster-wide task, like [`ObjectLeases::claim`]
+    ///
+    /// Only the holder runs the task, so it runs on one replica at a time
+    /// even though every replica reconciles.
+    pub async fn claim_task(&self, task: &str, now: DateTime<Utc>) -> Result<Claim, kube::Error> {
+        self.claim(
+            &self.config.task_namespace,
+            &task_lease_name(task),
+            None,
+            now,
+        )
+        .await
+    }
+}
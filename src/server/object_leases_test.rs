@@ -0,0 +1,168 @@
+//! Tests for per-object leases
+
+use super::object_leases::*;
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::coordination::v1::{Lease, LeaseSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::MicroTime;
+use kube::api::ObjectMeta;
+
+fn now() -> DateTime<Utc> {
+    "2026-03-02T09:00:00Z".parse().unwrap_or_default()
+}
+
+fn lease(holder: Option<&str>, renewed_secs_ago: i64, transitions: i32) -> Lease {
+    Lease {
+        metadata: ObjectMeta {
+            name: Some("kulta-rollout-1234".to_string()),
+            resource_version: Some("42".to_string()),
+            ..Default::default()
+        },
+        spec: Some(LeaseSpec {
+            holder_identity: holder.map(String::from),
+            renew_time: Some(MicroTime(
+                now() - chrono::Duration::seconds(renewed_secs_ago),
+            )),
+            lease_duration_seconds: Some(60),
+            lease_transitions: Some(transitions),
+            ..Default::default()
+        }),
+    }
+}
+
+fn config(holder: &str) -> ObjectLeaseConfig {
+    ObjectLeaseConfig {
+        holder_id: holder.to_string(),
+        lease_duration_seconds: 60,
+        task_namespace: "kulta-system".to_string(),
+    }
+}
+
+#[test]
+fn test_object_lease_name_uses_kind_and_uid() {
+    assert_eq!(
+        object_lease_name("Rollout", "0f1e2d3c-aaaa-bbbb-cccc-1234567890ab"),
+        "kulta-rollout-0f1e2d3c-aaaa-bbbb-cccc-1234567890ab"
+    );
+}
+
+#[test]
+fn test_task_lease_names() {
+    assert_eq!(task_lease_name(ORPHAN_GC_TASK), "kulta-task-orphan-gc");
+    assert_eq!(task_lease_name(CONCURRENCY_TASK), "kulta-task-concurrency");
+}
+
+#[test]
+fn test_missing_lease_is_created() {
+    assert_eq!(lease_action(None, "replica-a", now()), LeaseAction::Create);
+}
+
+#[test]
+fn test_own_lease_is_renewed() {
+    let held = lease(Some("replica-a"), 10, 3);
+    assert_eq!(
+        lease_action(Some(&held), "replica-a", now()),
+        LeaseAction::Renew
+    );
+}
+
+#[test]
+fn test_lease_held_by_another_replica_is_left_alone() {
+    let held = lease(Some("replica-a"), 10, 3);
+
+    assert_eq!(
+        lease_action(Some(&held), "replica-b", now()),
+        LeaseAction::HeldBy {
+            holder: "replica-a".to_string(),
+            expires_at: now() + chrono::Duration::seconds(50),
+        }
+    );
+}
+
+#[test]
+fn test_expired_or_released_lease_is_taken_over() {
+    let expired = lease(Some("replica-a"), 61, 3);
+    assert_eq!(
+        lease_action(Some(&expired), "replica-b", now()),
+        LeaseAction::TakeOver { transitions: 4 }
+    );
+
+    let released = lease(None, 10, 3);
+    assert_eq!(
+        lease_action(Some(&released), "replica-b", now()),
+        LeaseAction::TakeOver { transitions: 4 }
+    );
+}
+
+/// Two replicas racing for an expired lease both decide to take it over; the
+/// resourceVersion in their patches lets only the first write succeed
+#[test]
+fn test_racing_takeovers_are_guarded_by_resource_version() {
+    let expired = lease(Some("replica-a"), 120, 0);
+    let b = lease_action(Some(&expired), "replica-b", now());
+    let c = lease_action(Some(&expired), "replica-c", now());
+    assert_eq!(b, c);
+
+    let patch = claim_patch(Some("42"), &b, &config("replica-b"), now());
+    assert_eq!(patch["metadata"]["resourceVersion"], "42");
+    assert_eq!(patch["spec"]["holderIdentity"], "replica-b");
+    assert_eq!(patch["spec"]["leaseTransitions"], 1);
+
+    // Once replica-b's write lands, replica-c sees it as the holder
+    let mut taken = lease(Some("replica-b"), 0, 1);
+    taken.metadata.resource_version = Some("43".to_string());
+    assert!(matches!(
+        lease_action(Some(&taken), "replica-c", now()),
+        LeaseAction::HeldBy { holder, .. } if holder == "replica-b"
+    ));
+}
+
+#[test]
+fn test_renewal_keeps_the_holder() {
+    let patch = claim_patch(Some("42"), &LeaseAction::Renew, &config("replica-a"), now());
+
+    assert_eq!(patch["metadata"]["resourceVersion"], "42");
+    assert!(patch["spec"].get("holderIdentity").is_none());
+    assert_eq!(patch["spec"]["leaseDurationSeconds"], 60);
+}
+
+#[test]
+fn test_object_lease_config_from_lookup() {
+    let lookup = |extra: &'static [(&'static str, &'static str)]| {
+        move |key: &str| {
+            [("POD_NAME", "kulta-0")]
+                .iter()
+                .chain(extra)
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    };
+
+    assert_eq!(ObjectLeaseConfig::from_lookup(lookup(&[])), None);
+    assert_eq!(
+        ObjectLeaseConfig::from_lookup(lookup(&[("KULTA_OBJECT_LEASES", "true")])),
+        Some(ObjectLeaseConfig {
+            holder_id: "kulta-0".to_string(),
+            lease_duration_seconds: DEFAULT_OBJECT_LEASE_TTL.as_secs() as i32,
+            task_namespace: "kulta-system".to_string(),
+        })
+    );
+
+    let tuned = ObjectLeaseConfig::from_lookup(lookup(&[
+        ("KULTA_OBJECT_LEASES", "1"),
+        ("KULTA_OBJECT_LEASE_SECONDS", "120"),
+        ("POD_NAMESPACE", "platform"),
+    ]))
+    .unwrap();
+    assert_eq!(tuned.lease_duration_seconds, 120);
+    assert_eq!(tuned.task_namespace, "platform");
+
+    // Too short to outlast a reconcile
+    let invalid = ObjectLeaseConfig::from_lookup(lookup(&[
+        ("KULTA_OBJECT_LEASES", "true"),
+        ("KULTA_OBJECT_LEASE_SECONDS", "2"),
+    ]));
+    assert_eq!(
+        invalid.map(|c| c.lease_duration_seconds),
+        Some(DEFAULT_OBJECT_LEASE_TTL.as_secs() as i32)
+    );
+}
@@ -0,0 +1,165 @@
+//! Rollout activity for autoscalers (KEDA)
+//!
+//! Scaling a workload down while a canary is being judged skews its metrics,
+//! so autoscaling policies often want to hold replicas during a rollout. The
+//! controller publishes which Rollouts are in flight in two forms:
+//!
+//! - the `kulta_rollout_in_progress{namespace, rollout}` gauge, for KEDA's
+//!   `prometheus` scaler (works with any number of controller replicas)
+//! - JSON on the health port, for KEDA's `metrics-api` scaler
+//!
+//! # JSON contract
+//!
+//! `GET /scaler/rollouts/{namespace}/{name}`:
+//!
+//! ```json
+//! { "namespace": "shop", "rollout": "checkout", "phase": "Progressing",
+//!   "inProgress": true, "value": 1, "canaryWeight": 20,
+//!   "replicas": 10, "holdReplicas": 10 }
+//! ```
+//!
+//! `GET /scaler/rollouts/{namespace}`:
+//!
+//! ```json
+//! { "namespace": "shop", "rolloutsInProgress": 2, "value": 2, "holdReplicas": 14 }
+//! ```
+//!
+//! - `inProgress`/`value`: a revision is being rolled out (phase
+//!   Progressing, Paused, Preview, Experimenting or Concluded); `value` is
+//!   1 or 0, and the number of such Rollouts for a namespace.
+//! - `holdReplicas`: `spec.replicas` while in progress, otherwise 0. With
+//!   `valueLocation: holdReplicas` and `targetValue: "1"` the trigger asks
+//!   for at least that many replicas during a rollout, so the HPA doesn't
+//!   scale below it; afterwards it asks for none.
+//! - A Rollout the controller hasn't reconciled (or that was deleted) reads
+//!   as not in progress, with `phase` omitted and every number 0, so the
+//!   scaler never errors on it.
+//!
+//! Fields are only ever added. The JSON reflects what this controller
+//! replica reconciled: with leader election, only the leader knows.
+
+use crate::controller::rollout::is_in_flight;
+use crate::crd::rollout::{Rollout, RolloutStatus};
+use kube::ResourceExt;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Whether one Rollout is in flight
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutActivity {
+    pub namespace: String,
+    pub rollout: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    pub in_progress: bool,
+    /// 1 while in progress, otherwise 0
+    pub value: i64,
+    pub canary_weight: i32,
+    pub replicas: i32,
+    /// `replicas` while in progress, otherwise 0
+    pub hold_replicas: i32,
+}
+
+impl RolloutActivity {
+    /// Activity of a Rollout with `status`
+    pub fn new(rollout: &Rollout, status: &RolloutStatus) -> Self {
+        let in_progress = is_in_flight(Some(status));
+        let replicas = rollout.spec.replicas;
+        RolloutActivity {
+            namespace: rollout.namespace().unwrap_or_default(),
+            rollout: rollout.name_any(),
+            phase: status.phase.as_ref().map(|p| format!("{:?}", p)),
+            in_progress,
+            value: i64::from(in_progress),
+            canary_weight: status.current_weight.unwrap_or(0),
+            replicas,
+            hold_replicas: if in_progress { replicas } else { 0 },
+        }
+    }
+
+    /// A Rollout nothing is known about
+    fn idle(namespace: &str, rollout: &str) -> Self {
+        RolloutActivity {
+            namespace: namespace.to_string(),
+            rollout: rollout.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// In-flight Rollouts of a namespace
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceActivity {
+    pub namespace: String,
+    pub rollouts_in_progress: i64,
+    /// Same as `rolloutsInProgress`
+    pub value: i64,
+    /// Sum of `holdReplicas` of the namespace's Rollouts
+    pub hold_replicas: i64,
+}
+
+/// Last known activity of every reconciled Rollout, keyed by namespace and name
+#[derive(Default)]
+pub struct ActivityBoard {
+    rollouts: Mutex<BTreeMap<(String, String), RolloutActivity>>,
+}
+
+impl ActivityBoard {
+    /// Record the activity of a Rollout
+    pub fn record(&self, activity: RolloutActivity) {
+        if let Ok(mut rollouts) = self.rollouts.lock() {
+            rollouts.insert(
+                (activity.namespace.clone(), activity.rollout.clone()),
+                activity,
+            );
+        }
+    }
+
+    /// Forget a deleted Rollout
+    pub fn forget(&self, namespace: &str, rollout: &str) {
+        if let Ok(mut rollouts) = self.rollouts.lock() {
+            rollouts.remove(&(namespace.to_string(), rollout.to_string()));
+        }
+    }
+
+    /// Activity of one Rollout (idle when unknown)
+    pub fn rollout(&self, namespace: &str, rollout: &str) -> RolloutActivity {
+        self.rollouts
+            .lock()
+            .ok()
+            .and_then(|rollouts| {
+                rollouts
+                    .get(&(namespace.to_string(), rollout.to_string()))
+                    .cloned()
+            })
+            .unwrap_or_else(|| RolloutActivity::idle(namespace, rollout))
+    }
+
+    /// Activity of every Rollout in `namespace`
+    pub fn namespace(&self, namespace: &str) -> NamespaceActivity {
+        let (in_progress, hold_replicas) = self
+            .rollouts
+            .lock()
+            .map(|rollouts| {
+                rollouts
+                    .values()
+                    .filter(|activity| activity.namespace == namespace)
+                    .fold((0, 0), |(count, hold), activity| {
+                        (
+                            count + activity.value,
+                            hold + i64::from(activity.hold_replicas),
+                        )
+                    })
+            })
+            .unwrap_or_default();
+        NamespaceActivity {
+            namespace: namespace.to_string(),
+            rollouts_in_progress: in_progress,
+            value: in_progress,
+            hold_replicas,
+        }
+    }
+}
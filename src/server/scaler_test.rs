@@ -0,0 +1,83 @@
+//! Tests for rollout activity published to autoscalers
+
+#![allow(clippy::unwrap_used)]
+
+use super::scaler::*;
+use crate::crd::builder::RolloutBuilder;
+use crate::crd::rollout::{Phase, Rollout, RolloutStatus, SimpleStrategy};
+
+fn rollout(namespace: &str, name: &str, replicas: i32) -> Rollout {
+    RolloutBuilder::new(name)
+        .namespace(namespace)
+        .replicas(replicas)
+        .label("app", name)
+        .image("app", "app:2")
+        .simple(SimpleStrategy { analysis: None })
+        .build()
+        .unwrap()
+}
+
+fn status(phase: Phase, weight: i32) -> RolloutStatus {
+    RolloutStatus {
+        phase: Some(phase),
+        current_weight: Some(weight),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_rollout_activity_holds_replicas_while_in_flight() {
+    let checkout = rollout("shop", "checkout", 10);
+    let progressing = RolloutActivity::new(&checkout, &status(Phase::Progressing, 20));
+    assert!(progressing.in_progress);
+    assert_eq!(progressing.value, 1);
+    assert_eq!(progressing.canary_weight, 20);
+    assert_eq!(progressing.hold_replicas, 10);
+
+    let completed = RolloutActivity::new(&checkout, &status(Phase::Completed, 100));
+    assert!(!completed.in_progress);
+    assert_eq!(completed.hold_replicas, 0);
+
+    let json = serde_json::to_value(&progressing).unwrap();
+    assert_eq!(json["inProgress"], true);
+    assert_eq!(json["holdReplicas"], 10);
+    assert_eq!(json["phase"], "Progressing");
+}
+
+#[test]
+fn test_activity_board_by_rollout_and_namespace() {
+    let board = ActivityBoard::default();
+    board.record(RolloutActivity::new(
+        &rollout("shop", "checkout", 10),
+        &status(Phase::Progressing, 20),
+    ));
+    board.record(RolloutActivity::new(
+        &rollout("shop", "cart", 4),
+        &status(Phase::Paused, 50),
+    ));
+    board.record(RolloutActivity::new(
+        &rollout("shop", "search", 3),
+        &status(Phase::Completed, 100),
+    ));
+    board.record(RolloutActivity::new(
+        &rollout("web", "home", 2),
+        &status(Phase::Progressing, 10),
+    ));
+
+    assert!(board.rollout("shop", "checkout").in_progress);
+    let shop = board.namespace("shop");
+    assert_eq!(shop.rollouts_in_progress, 2);
+    assert_eq!(shop.hold_replicas, 14);
+
+    board.forget("shop", "checkout");
+    let unknown = board.rollout("shop", "checkout");
+    assert!(!unknown.in_progress);
+    assert_eq!(unknown.hold_replicas, 0);
+    assert_eq!(unknown.rollout, "checkout");
+    assert!(serde_json::to_value(&unknown)
+        .unwrap()
+        .get("phase")
+        .is_none());
+    assert_eq!(board.namespace("shop").value, 1);
+    assert_eq!(board.namespace("empty").value, 0);
+}
@@ -17,15 +17,24 @@
 //! - canary.steps must have at least one step
 //! - step.setWeight must be 0-100
 //! - pause.duration must be valid format
+//! - pod template policy (`KULTA_RESTRICTED_NAMESPACES`) for restricted namespaces
+//! - `onTemplateChange: reject` denies template changes while a revision is in flight
 
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{info, warn};
 
+use crate::controller::defaults::RolloutDefaults;
+use crate::controller::pod_policy::{check_pod_template, PodTemplatePolicy};
+use crate::controller::rollout::check_template_change;
+use crate::crd::cluster_rollout::ClusterRollout;
 use crate::crd::conversion::{
     DEFAULT_MAX_SURGE, DEFAULT_MAX_UNAVAILABLE, DEFAULT_PROGRESS_DEADLINE_SECONDS,
 };
+use crate::crd::rollout::Rollout;
+use crate::server::health::ServerState;
 
 /// Kubernetes ConversionReview request
 #[derive(Debug, Deserialize)]
@@ -278,6 +287,9 @@ pub struct AdmissionRequest {
     pub operation: String,
     /// The object being validated
     pub object: Value,
+    /// The object before the change (UPDATE only)
+    #[serde(default)]
+    pub old_object: Option<Value>,
 }
 
 /// Group/Version/Kind identifier
@@ -305,6 +317,9 @@ pub struct AdmissionResponse {
     pub allowed: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<AdmissionStatus>,
+    /// Warnings shown to the client (e.g., kubectl) when the object is admitted
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 /// Full AdmissionReview response
@@ -318,46 +333,181 @@ pub struct AdmissionReviewResponse {
 
 /// Validate a Rollout spec from JSON
 ///
-/// This function deserializes the JSON into a Rollout and validates it.
-fn validate_rollout_from_json(object: &Value) -> Result<(), String> {
-    use crate::crd::rollout::Rollout;
-
+/// This function deserializes the JSON into a Rollout and validates it with
+/// the controller defaults applied, including the pod template policy for
+/// the request's namespace.
+///
+/// # Returns
+/// * `Ok(warnings)` - Valid; warnings to return with the admission (may be empty)
+/// * `Err(message)` - Invalid
+fn validate_rollout_from_json(
+    object: &Value,
+    namespace: &str,
+    pod_policy: &PodTemplatePolicy,
+    defaults: &RolloutDefaults,
+) -> Result<Vec<String>, String> {
     // Deserialize the object into a Rollout
     let rollout: Rollout = serde_json::from_value(object.clone())
         .map_err(|e| format!("Failed to parse Rollout: {}", e))?;
+    validate_parsed_rollout(rollout, namespace, pod_policy, defaults)
+}
+
+/// Validate a ClusterRollout spec from JSON
+///
+/// Validated as the Rollout it is reconciled as, with the pod template
+/// policy of its `targetNamespace`.
+fn validate_cluster_rollout_from_json(
+    object: &Value,
+    pod_policy: &PodTemplatePolicy,
+    defaults: &RolloutDefaults,
+) -> Result<Vec<String>, String> {
+    let cluster_rollout: ClusterRollout = serde_json::from_value(object.clone())
+        .map_err(|e| format!("Failed to parse ClusterRollout: {}", e))?;
+    if cluster_rollout.spec.target_namespace.is_empty() {
+        return Err("spec.targetNamespace cannot be empty".to_string());
+    }
+    validate_parsed_rollout(
+        cluster_rollout.to_rollout(),
+        &cluster_rollout.spec.target_namespace,
+        pod_policy,
+        defaults,
+    )
+}
+
+/// Validate a parsed Rollout: spec, strategy feature support and pod template policy
+///
+/// The Rollout is validated as the controller reconciles it, with the
+/// controller defaults filling what it leaves unset.
+fn validate_parsed_rollout(
+    mut rollout: Rollout,
+    namespace: &str,
+    pod_policy: &PodTemplatePolicy,
+    defaults: &RolloutDefaults,
+) -> Result<Vec<String>, String> {
+    use crate::controller::rollout::{digest_pod_template, pod_template_size_warning};
+
+    defaults.apply(&mut rollout);
 
     // Use the existing validation logic
-    crate::controller::rollout::validate_rollout(&rollout)
+    crate::controller::rollout::validate_rollout(&rollout)?;
+    let strategy = crate::controller::strategies::select_strategy(&rollout);
+    crate::controller::strategies::check_feature_support(&rollout, strategy.as_ref())?;
+    check_pod_template(&rollout.spec.template, namespace, pod_policy)?;
+
+    let digest = digest_pod_template(&rollout.spec.template).map_err(|e| e.to_string())?;
+    Ok(pod_template_size_warning(digest.size).into_iter().collect())
+}
+
+/// Check an UPDATE against the rules that compare it to the old object
+///
+/// Today that is `onTemplateChange: reject`. `as_rollout` turns the parsed
+/// object into the Rollout it is reconciled as.
+fn check_rollout_update<T: DeserializeOwned>(
+    request: &AdmissionRequest,
+    as_rollout: fn(&T) -> Rollout,
+) -> Result<(), String> {
+    let Some(old_object) = request
+        .old_object
+        .as_ref()
+        .filter(|_| request.operation == "UPDATE")
+    else {
+        return Ok(());
+    };
+    let parse = |object: &Value| {
+        serde_json::from_value::<T>(object.clone())
+            .map(|parsed| as_rollout(&parsed))
+            .map_err(|e| format!("Failed to parse {}: {}", request.kind.kind, e))
+    };
+    check_template_change(&parse(old_object)?, &parse(&request.object)?)
+}
+
+/// Controller configuration admission requests are validated against
+///
+/// Read from the environment once, when the server starts.
+#[derive(Clone, Debug, Default)]
+pub struct AdmissionPolicy {
+    /// Pod template policy (`KULTA_RESTRICTED_NAMESPACES`)
+    pub pod_policy: PodTemplatePolicy,
+    /// Controller-wide defaults applied before validation
+    pub defaults: RolloutDefaults,
+}
+
+impl AdmissionPolicy {
+    /// Policy and defaults configured in the environment
+    pub fn from_env() -> Self {
+        AdmissionPolicy {
+            pod_policy: PodTemplatePolicy::from_env(),
+            defaults: RolloutDefaults::from_env(),
+        }
+    }
 }
 
 /// Validate an admission request
 pub fn validate_admission(request: AdmissionRequest) -> AdmissionResponse {
+    validate_admission_with_policy(request, &PodTemplatePolicy::from_env())
+}
+
+/// Validate an admission request against an explicit pod template policy
+pub fn validate_admission_with_policy(
+    request: AdmissionRequest,
+    pod_policy: &PodTemplatePolicy,
+) -> AdmissionResponse {
+    validate_admission_with_defaults(request, pod_policy, &RolloutDefaults::from_env())
+}
+
+/// Validate an admission request against an explicit pod template policy and defaults
+pub fn validate_admission_with_defaults(
+    request: AdmissionRequest,
+    pod_policy: &PodTemplatePolicy,
+    defaults: &RolloutDefaults,
+) -> AdmissionResponse {
     let object_name = request.name.as_deref().unwrap_or("unknown");
     let object_ns = request.namespace.as_deref().unwrap_or("default");
 
-    // Only validate Rollout resources
-    if request.kind.kind != "Rollout" || request.kind.group != "kulta.io" {
-        // Allow non-Rollout resources (shouldn't happen with proper webhook config)
-        return AdmissionResponse {
-            uid: request.uid,
-            allowed: true,
-            status: None,
-        };
-    }
+    // Only validate Rollout and ClusterRollout resources
+    let result = match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        ("kulta.io", "Rollout") => {
+            validate_rollout_from_json(&request.object, object_ns, pod_policy, defaults).and_then(
+                |warnings| {
+                    check_rollout_update::<Rollout>(&request, Rollout::clone)?;
+                    Ok(warnings)
+                },
+            )
+        }
+        ("kulta.io", "ClusterRollout") => {
+            validate_cluster_rollout_from_json(&request.object, pod_policy, defaults).and_then(
+                |warnings| {
+                    check_rollout_update::<ClusterRollout>(&request, ClusterRollout::to_rollout)?;
+                    Ok(warnings)
+                },
+            )
+        }
+        _ => {
+            // Allow other resources (shouldn't happen with proper webhook config)
+            return AdmissionResponse {
+                uid: request.uid,
+                allowed: true,
+                status: None,
+                warnings: Vec::new(),
+            };
+        }
+    };
 
-    // Validate the Rollout
-    match validate_rollout_from_json(&request.object) {
-        Ok(()) => {
+    // Report the validation result
+    match result {
+        Ok(warnings) => {
             info!(
                 name = %object_name,
                 namespace = %object_ns,
                 operation = %request.operation,
+                warnings = warnings.len(),
                 "Rollout validation passed"
             );
             AdmissionResponse {
                 uid: request.uid,
                 allowed: true,
                 status: None,
+                warnings,
             }
         }
         Err(validation_error) => {
@@ -375,13 +525,17 @@ pub fn validate_admission(request: AdmissionRequest) -> AdmissionResponse {
                     code: Some(400),
                     message: Some(validation_error),
                 }),
+                warnings: Vec::new(),
             }
         }
     }
 }
 
 /// Axum handler for the /validate endpoint
-pub async fn handle_validate(Json(review): Json<AdmissionReview>) -> impl IntoResponse {
+pub async fn handle_validate(
+    State(state): State<ServerState>,
+    Json(review): Json<AdmissionReview>,
+) -> impl IntoResponse {
     info!(
         uid = %review.request.uid,
         kind = %review.request.kind.kind,
@@ -389,7 +543,12 @@ pub async fn handle_validate(Json(review): Json<AdmissionReview>) -> impl IntoRe
         "Processing validation request"
     );
 
-    let response = validate_admission(review.request);
+    let admission = state.admission();
+    let response = validate_admission_with_defaults(
+        review.request,
+        &admission.pod_policy,
+        &admission.defaults,
+    );
 
     let review_response = AdmissionReviewResponse {
         api_version: "admission.k8s.io/v1".to_string(),
@@ -235,6 +235,7 @@ fn test_validate_valid_rollout_allowed() {
         name: Some("test-rollout".to_string()),
         namespace: Some("default".to_string()),
         operation: "CREATE".to_string(),
+        old_object: None,
         object: json!({
             "apiVersion": "kulta.io/v1alpha1",
             "kind": "Rollout",
@@ -263,6 +264,50 @@ fn test_validate_valid_rollout_allowed() {
     assert_eq!(response.uid, "valid-uid");
 }
 
+/// Test: ClusterRollout is validated as a Rollout in its target namespace
+#[test]
+fn test_validate_cluster_rollout() {
+    let request = |target_namespace: &str, replicas: i32| AdmissionRequest {
+        uid: "cluster-uid".to_string(),
+        kind: super::GroupVersionKind {
+            group: "kulta.io".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: "ClusterRollout".to_string(),
+        },
+        name: Some("ingress-controller".to_string()),
+        namespace: None,
+        operation: "CREATE".to_string(),
+        old_object: None,
+        object: json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "ClusterRollout",
+            "metadata": {"name": "ingress-controller"},
+            "spec": {
+                "targetNamespace": target_namespace,
+                "replicas": replicas,
+                "selector": {"matchLabels": {"app": "ingress"}},
+                "template": {
+                    "metadata": {"labels": {"app": "ingress"}},
+                    "spec": {"containers": [{"name": "app", "image": "ingress:1.0"}]}
+                },
+                "strategy": {"simple": {}}
+            }
+        }),
+    };
+
+    assert!(validate_admission(request("ingress-system", 2)).allowed);
+
+    let response = validate_admission(request("ingress-system", -1));
+    assert!(!response.allowed, "Negative replicas should be denied");
+
+    let response = validate_admission(request("", 2));
+    assert!(!response.allowed, "Empty targetNamespace should be denied");
+    assert!(response
+        .status
+        .and_then(|s| s.message)
+        .is_some_and(|m| m.contains("targetNamespace")));
+}
+
 /// Test: Invalid rollout with negative replicas is denied
 #[test]
 fn test_validate_negative_replicas_denied() {
@@ -276,6 +321,7 @@ fn test_validate_negative_replicas_denied() {
         name: Some("test-rollout".to_string()),
         namespace: Some("default".to_string()),
         operation: "CREATE".to_string(),
+        old_object: None,
         object: json!({
             "apiVersion": "kulta.io/v1alpha1",
             "kind": "Rollout",
@@ -313,6 +359,7 @@ fn test_validate_empty_canary_service_denied() {
         name: Some("test-rollout".to_string()),
         namespace: Some("default".to_string()),
         operation: "CREATE".to_string(),
+        old_object: None,
         object: json!({
             "apiVersion": "kulta.io/v1alpha1",
             "kind": "Rollout",
@@ -356,6 +403,7 @@ fn test_validate_weight_out_of_range_denied() {
         name: Some("test-rollout".to_string()),
         namespace: Some("default".to_string()),
         operation: "CREATE".to_string(),
+        old_object: None,
         object: json!({
             "apiVersion": "kulta.io/v1alpha1",
             "kind": "Rollout",
@@ -393,6 +441,7 @@ fn test_validate_malformed_object_denied() {
         name: Some("test-rollout".to_string()),
         namespace: Some("default".to_string()),
         operation: "CREATE".to_string(),
+        old_object: None,
         object: json!({
             "apiVersion": "kulta.io/v1alpha1",
             "kind": "Rollout",
@@ -405,3 +454,204 @@ fn test_validate_malformed_object_denied() {
 
     assert!(!response.allowed, "Malformed rollout should be denied");
 }
+
+/// Test: Restricted namespaces reject privileged pod templates
+#[test]
+fn test_validate_pod_policy_denies_host_network_in_restricted_namespace() {
+    use super::validate_admission_with_policy;
+    use crate::controller::pod_policy::{PodPolicyAction, PodTemplatePolicy};
+
+    let request = |namespace: &str| AdmissionRequest {
+        uid: "policy-uid".to_string(),
+        kind: super::GroupVersionKind {
+            group: "kulta.io".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: "Rollout".to_string(),
+        },
+        name: Some("test-rollout".to_string()),
+        namespace: Some(namespace.to_string()),
+        operation: "CREATE".to_string(),
+        old_object: None,
+        object: json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "Rollout",
+            "metadata": {"name": "test-rollout"},
+            "spec": {
+                "replicas": 3,
+                "selector": {"matchLabels": {"app": "test"}},
+                "template": {
+                    "metadata": {"labels": {"app": "test"}},
+                    "spec": {
+                        "hostNetwork": true,
+                        "containers": [{"name": "app", "image": "nginx"}]
+                    }
+                },
+                "strategy": {"simple": {}}
+            }
+        }),
+    };
+    let policy = PodTemplatePolicy {
+        namespaces: vec!["prod".to_string()],
+        action: PodPolicyAction::Reject,
+    };
+
+    let response = validate_admission_with_policy(request("prod"), &policy);
+    assert!(!response.allowed, "hostNetwork should be denied in prod");
+    assert!(response
+        .status
+        .as_ref()
+        .and_then(|s| s.message.as_ref())
+        .map(|m| m.contains("hostNetwork"))
+        .unwrap_or(false));
+
+    let response = validate_admission_with_policy(request("dev"), &policy);
+    assert!(response.allowed, "Unrestricted namespaces are not affected");
+}
+
+/// Test: Oversized pod templates are admitted with a warning
+#[test]
+fn test_validate_large_template_warns() {
+    let env: Vec<serde_json::Value> = (0..4000)
+        .map(|i| json!({"name": format!("SETTING_{}", i), "value": "x".repeat(64)}))
+        .collect();
+    let request = |env: Vec<serde_json::Value>| AdmissionRequest {
+        uid: "large-uid".to_string(),
+        kind: super::GroupVersionKind {
+            group: "kulta.io".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: "Rollout".to_string(),
+        },
+        name: Some("test-rollout".to_string()),
+        namespace: Some("default".to_string()),
+        operation: "CREATE".to_string(),
+        old_object: None,
+        object: json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "Rollout",
+            "metadata": {"name": "test-rollout", "namespace": "default"},
+            "spec": {
+                "replicas": 3,
+                "selector": {"matchLabels": {"app": "test"}},
+                "template": {
+                    "metadata": {"labels": {"app": "test"}},
+                    "spec": {"containers": [{"name": "app", "image": "nginx", "env": env}]}
+                },
+                "strategy": {"simple": {}}
+            }
+        }),
+    };
+
+    let response = validate_admission(request(env));
+    assert!(response.allowed, "Large templates are allowed");
+    assert_eq!(response.warnings.len(), 1);
+    assert!(response.warnings[0].contains("spec.template"));
+
+    let response = validate_admission(request(Vec::new()));
+    assert!(response.warnings.is_empty());
+}
+
+/// Test: Canaries without steps are admitted when the controller defaults provide them
+#[test]
+fn test_validate_canary_steps_from_controller_defaults() {
+    use super::validate_admission_with_defaults;
+    use crate::controller::defaults::RolloutDefaults;
+    use crate::controller::pod_policy::PodTemplatePolicy;
+
+    let request = || AdmissionRequest {
+        uid: "defaults-uid".to_string(),
+        kind: super::GroupVersionKind {
+            group: "kulta.io".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: "Rollout".to_string(),
+        },
+        name: Some("test-rollout".to_string()),
+        namespace: Some("default".to_string()),
+        operation: "CREATE".to_string(),
+        old_object: None,
+        object: json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "Rollout",
+            "metadata": {"name": "test-rollout"},
+            "spec": {
+                "replicas": 3,
+                "selector": {"matchLabels": {"app": "test"}},
+                "template": {
+                    "metadata": {"labels": {"app": "test"}},
+                    "spec": {"containers": [{"name": "app", "image": "nginx"}]}
+                },
+                "strategy": {"canary": {
+                    "stableService": "test-stable",
+                    "canaryService": "test-canary"
+                }}
+            }
+        }),
+    };
+    let policy = PodTemplatePolicy::default();
+
+    let response =
+        validate_admission_with_defaults(request(), &policy, &RolloutDefaults::default());
+    assert!(!response.allowed, "A canary without steps needs defaults");
+
+    let defaults =
+        RolloutDefaults::parse("canary: { steps: [{ setWeight: 20 }, { setWeight: 100 }] }")
+            .unwrap_or_default();
+    let response = validate_admission_with_defaults(request(), &policy, &defaults);
+    assert!(response.allowed, "Default steps should be validated in");
+}
+
+/// Test: onTemplateChange reject denies a template change while a revision is in flight
+#[test]
+fn test_validate_reject_template_change_mid_rollout() {
+    let rollout = |image: &str, phase: &str| {
+        json!({
+            "apiVersion": "kulta.io/v1alpha1",
+            "kind": "Rollout",
+            "metadata": {"name": "test-rollout", "namespace": "default"},
+            "spec": {
+                "replicas": 3,
+                "selector": {"matchLabels": {"app": "test"}},
+                "template": {
+                    "metadata": {"labels": {"app": "test"}},
+                    "spec": {"containers": [{"name": "app", "image": image}]}
+                },
+                "strategy": {"canary": {
+                    "stableService": "test-stable",
+                    "canaryService": "test-canary",
+                    "steps": [{"setWeight": 20}, {"pause": {}}, {"setWeight": 100}]
+                }},
+                "onTemplateChange": "reject"
+            },
+            "status": {"phase": phase, "currentStepIndex": 1}
+        })
+    };
+    let request = |old: serde_json::Value, new: serde_json::Value| AdmissionRequest {
+        uid: "template-change-uid".to_string(),
+        kind: super::GroupVersionKind {
+            group: "kulta.io".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: "Rollout".to_string(),
+        },
+        name: Some("test-rollout".to_string()),
+        namespace: Some("default".to_string()),
+        operation: "UPDATE".to_string(),
+        old_object: Some(old),
+        object: new,
+    };
+
+    let response = validate_admission(request(
+        rollout("nginx:1", "Paused"),
+        rollout("nginx:2", "Paused"),
+    ));
+    assert!(!response.allowed);
+    assert!(response
+        .status
+        .and_then(|s| s.message)
+        .unwrap_or_default()
+        .contains("onTemplateChange: reject"));
+
+    let response = validate_admission(request(
+        rollout("nginx:1", "Completed"),
+        rollout("nginx:2", "Completed"),
+    ));
+    assert!(response.allowed, "A completed rollout takes a new template");
+}
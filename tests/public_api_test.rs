@@ -0,0 +1,231 @@
+//! Guards the stable embedding API (`kulta::api`)
+//!
+//! Only imports from `kulta::api`. If a change here is needed to make the
+//! build pass, the change is breaking for embedders.
+//!
+//! Run with: cargo test --test public_api_test
+
+#![allow(clippy::expect_used)]
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use kulta::api::{
+    check_feature_support, notification_for, plan_rollout, select_strategy, simulate_next_status,
+    validate_rollout, Clock, MetricsQuerier, Notification, NotificationError, NotificationTrigger,
+    Notifications, Notifier, OccurrenceExtension, OccurrenceExtensions, Phase, PlanGate,
+    PrometheusError, Rollout, RolloutBuildError, RolloutBuilder, RolloutStatus, RolloutStrategy,
+};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+fn canary_rollout() -> Rollout {
+    RolloutBuilder::new("checkout")
+        .namespace("shop")
+        .replicas(3)
+        .label("app", "checkout")
+        .image("app", "checkout:2.0")
+        .canary(
+            serde_json::from_value(serde_json::json!({
+                "canaryService": "checkout-canary",
+                "stableService": "checkout-stable",
+                "steps": [
+                    { "setWeight": 20, "pause": {} },
+                    { "setWeight": 100 }
+                ]
+            }))
+            .expect("canary strategy should deserialize"),
+        )
+        .build()
+        .expect("rollout should build")
+}
+
+struct FixedClock(DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+struct ConstantMetrics(f64);
+
+#[async_trait]
+impl MetricsQuerier for ConstantMetrics {
+    async fn query_instant(&self, _query: &str) -> Result<f64, PrometheusError> {
+        Ok(self.0)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn test_validate_and_plan_through_api() {
+    let rollout = canary_rollout();
+    validate_rollout(&rollout).expect("rollout should be valid");
+
+    let plan = plan_rollout(&rollout).expect("rollout should plan");
+    assert_eq!(plan.strategy, "canary");
+    assert_eq!(plan.manual_gates(), 1);
+    assert!(plan
+        .entries
+        .iter()
+        .any(|entry| entry.waits_for == Some(PlanGate::Promotion)));
+    assert_eq!(
+        plan.entries.last().map(|entry| entry.phase.clone()),
+        Some(Phase::Completed)
+    );
+}
+
+#[test]
+fn test_builder_through_api() {
+    let built = canary_rollout();
+    let selector = built.spec.selector.match_labels.expect("selector labels");
+    let pod_labels = built
+        .spec
+        .template
+        .metadata
+        .and_then(|metadata| metadata.labels)
+        .expect("pod labels");
+    assert_eq!(selector["app"], "checkout");
+    assert_eq!(pod_labels["app"], "checkout");
+
+    let missing = RolloutBuilder::new("checkout")
+        .label("app", "checkout")
+        .image("app", "checkout:2.0")
+        .build();
+    assert!(matches!(missing, Err(RolloutBuildError::MissingStrategy)));
+}
+
+#[test]
+fn test_strategy_trait_through_api() {
+    let rollout = canary_rollout();
+    let strategy: Box<dyn RolloutStrategy> = select_strategy(&rollout);
+
+    assert_eq!(strategy.name(), "canary");
+    assert!(check_feature_support(&rollout, strategy.as_ref()).is_ok());
+
+    let clock = FixedClock(
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+            .single()
+            .expect("valid timestamp"),
+    );
+    let status = strategy.compute_next_status(&rollout, clock.now());
+    assert_eq!(status.current_weight, Some(20));
+}
+
+#[test]
+fn test_simulate_next_status_through_api() {
+    let rollout = canary_rollout();
+    let start = Utc
+        .with_ymd_and_hms(2026, 1, 1, 0, 0, 0)
+        .single()
+        .expect("valid timestamp");
+    let none = BTreeMap::new();
+
+    let started = simulate_next_status(&rollout.spec, None, start, &none);
+    assert_eq!(started.current_step_index, Some(0));
+    assert_eq!(started.current_weight, Some(20));
+    assert_eq!(
+        simulate_next_status(&rollout.spec, None, start, &none),
+        started
+    );
+
+    // Paused until promoted
+    let later = start + chrono::Duration::hours(1);
+    let waiting = simulate_next_status(&rollout.spec, Some(&started), later, &none);
+    assert_eq!(waiting, started);
+    let promote = BTreeMap::from([("kulta.io/promote".to_string(), "true".to_string())]);
+    let promoted = simulate_next_status(&rollout.spec, Some(&started), later, &promote);
+    assert_eq!(promoted.phase, Some(Phase::Completed));
+
+    // Weight overrides and aborts are applied like the controller does
+    let set_weight = BTreeMap::from([("kulta.io/set-weight".to_string(), "5".to_string())]);
+    let drained = simulate_next_status(&rollout.spec, Some(&started), later, &set_weight);
+    assert_eq!(drained.current_weight, Some(5));
+    let abort = BTreeMap::from([("kulta.io/abort".to_string(), "true".to_string())]);
+    let aborted = simulate_next_status(&rollout.spec, Some(&started), later, &abort);
+    assert_eq!(aborted.phase, Some(Phase::Failed));
+    assert_eq!(aborted.current_weight, Some(0));
+}
+
+#[tokio::test]
+async fn test_metrics_querier_implementable_outside_crate() {
+    let querier = ConstantMetrics(0.5);
+
+    let value = querier
+        .query_instant("up")
+        .await
+        .expect("constant query should succeed");
+    assert_eq!(value, 0.5);
+}
+
+struct TicketExtension;
+
+impl OccurrenceExtension for TicketExtension {
+    fn key(&self) -> &str {
+        "ticket"
+    }
+
+    fn data(&self, rollout: &Rollout) -> Option<serde_json::Value> {
+        rollout
+            .metadata
+            .name
+            .as_ref()
+            .map(|name| serde_json::json!({ "id": format!("CHG-{}", name) }))
+    }
+}
+
+#[test]
+fn test_occurrence_extension_implementable_outside_crate() {
+    let mut extensions = OccurrenceExtensions::new();
+    extensions.register(TicketExtension);
+
+    assert_eq!(
+        TicketExtension.data(&canary_rollout()),
+        Some(serde_json::json!({ "id": "CHG-checkout" }))
+    );
+    assert_eq!(format!("{:?}", extensions), r#"["ticket"]"#);
+}
+
+#[derive(Clone, Default)]
+struct PagerNotifier(Arc<Mutex<Vec<String>>>);
+
+#[async_trait]
+impl Notifier for PagerNotifier {
+    fn kind(&self) -> &'static str {
+        "pager"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationError> {
+        self.0
+            .lock()
+            .expect("pages lock")
+            .push(notification.summary());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_notifier_implementable_outside_crate() {
+    let pager = PagerNotifier::default();
+    let mut notifications = Notifications::disabled();
+    notifications.register(pager.clone());
+
+    let failed = RolloutStatus {
+        phase: Some(Phase::Failed),
+        ..Default::default()
+    };
+    let notification =
+        notification_for(&canary_rollout(), None, &failed, Utc::now()).expect("a failure notifies");
+    assert_eq!(notification.trigger, NotificationTrigger::Failed);
+    pager
+        .send(&notification)
+        .await
+        .expect("pager should accept the notification");
+    assert_eq!(
+        *pager.0.lock().expect("pages lock"),
+        ["Rollout shop/checkout failed"]
+    );
+}
@@ -20,7 +20,7 @@ use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::api::{ObjectMeta, Patch, PatchParams};
 use kube::Api;
 use kulta::crd::rollout::{
-    BlueGreenStrategy, CanaryStep, CanaryStrategy, PauseDuration, Phase, Rollout, RolloutSpec,
+    BlueGreenStrategy, CanaryStep, CanaryStrategy, Pause, Phase, Rollout, RolloutSpec,
     RolloutStrategy, SimpleStrategy, TrafficRouting,
 };
 use seppo::Context;
@@ -236,22 +236,47 @@ async fn test_canary_full_lifecycle(ctx: TestContext) {
                         CanaryStep {
                             set_weight: Some(25),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(75),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                     ],
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(kulta::crd::rollout::GatewayAPIRouting {
                             http_route: name.to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -259,6 +284,10 @@ async fn test_canary_full_lifecycle(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -380,15 +409,29 @@ async fn test_canary_pause_and_promote(ctx: TestContext) {
                     steps: vec![
                         CanaryStep {
                             set_weight: Some(30),
-                            pause: Some(PauseDuration { duration: None }), // Manual pause
+                            pause: Some(Pause::Indefinite), // Manual pause
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(100),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                     ],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -396,6 +439,10 @@ async fn test_canary_pause_and_promote(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -488,9 +535,18 @@ async fn test_status_decisions_tracking(ctx: TestContext) {
                     steps: vec![CanaryStep {
                         set_weight: Some(50),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        analysis: None,
                     }],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -498,6 +554,10 @@ async fn test_status_decisions_tracking(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -578,6 +638,7 @@ async fn test_blue_green_promotion(ctx: TestContext) {
                     auto_promotion_seconds: None,
                     traffic_routing: None,
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -586,6 +647,10 @@ async fn test_blue_green_promotion(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -714,6 +779,7 @@ async fn test_blue_green_auto_promotion(ctx: TestContext) {
                     auto_promotion_seconds: Some(5),
                     traffic_routing: None,
                     analysis: None,
+                    migrated_from: None,
                 }),
                 ab_testing: None,
             },
@@ -722,6 +788,10 @@ async fn test_blue_green_auto_promotion(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -798,18 +868,38 @@ async fn test_httproute_weight_updates(ctx: TestContext) {
                         CanaryStep {
                             set_weight: Some(30),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(70),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                     ],
                     traffic_routing: Some(TrafficRouting {
                         gateway_api: Some(kulta::crd::rollout::GatewayAPIRouting {
                             http_route: name.to_string(),
+                            http_routes: vec![],
+                            weight_constraints: None,
+                            sections: Vec::new(),
+                            kind: Default::default(),
                         }),
+                        none: None,
+                        nginx: None,
                     }),
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -817,6 +907,10 @@ async fn test_httproute_weight_updates(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -925,6 +1019,10 @@ async fn test_simple_strategy_lifecycle(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -1006,9 +1104,18 @@ async fn test_image_update_triggers_rollout(ctx: TestContext) {
                     steps: vec![CanaryStep {
                         set_weight: Some(50),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        analysis: None,
                     }],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1016,6 +1123,10 @@ async fn test_image_update_triggers_rollout(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -1059,9 +1170,18 @@ async fn test_image_update_triggers_rollout(ctx: TestContext) {
                     steps: vec![CanaryStep {
                         set_weight: Some(50),
                         pause: None,
+                        alert_gate: None,
+                        set_mirror: None,
+                        set_canary_scale: None,
+                        set_header_route: None,
+                        analysis: None,
                     }],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -1069,6 +1189,10 @@ async fn test_image_update_triggers_rollout(ctx: TestContext) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };
@@ -17,7 +17,7 @@ use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::api::{DeleteParams, ObjectMeta};
 use kube::Api;
 use kulta::crd::rollout::{
-    CanaryStep, CanaryStrategy, PauseDuration, Phase, Rollout, RolloutSpec, RolloutStrategy,
+    CanaryStep, CanaryStrategy, Pause, Phase, Rollout, RolloutSpec, RolloutStrategy,
 };
 use seppo::Context;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -100,18 +100,37 @@ fn create_rollout(name: &str, namespace: &str, replicas: i32, image: &str) -> Ro
                         CanaryStep {
                             set_weight: Some(25),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(75),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                     ],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -119,6 +138,10 @@ fn create_rollout(name: &str, namespace: &str, replicas: i32, image: &str) -> Ro
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     }
@@ -156,25 +179,38 @@ fn create_rollout_with_pauses(
                     steps: vec![
                         CanaryStep {
                             set_weight: Some(25),
-                            pause: Some(PauseDuration {
-                                duration: Some(pause_duration.to_string()),
-                            }),
+                            pause: Some(Pause::Duration(pause_duration.to_string())),
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(50),
-                            pause: Some(PauseDuration {
-                                duration: Some(pause_duration.to_string()),
-                            }),
+                            pause: Some(Pause::Duration(pause_duration.to_string())),
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                         CanaryStep {
                             set_weight: Some(75),
-                            pause: Some(PauseDuration {
-                                duration: Some(pause_duration.to_string()),
-                            }),
+                            pause: Some(Pause::Duration(pause_duration.to_string())),
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         },
                     ],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -182,6 +218,10 @@ fn create_rollout_with_pauses(
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     }
@@ -813,10 +853,19 @@ async fn test_edge_minimal_steps(ctx: Context) {
                         CanaryStep {
                             set_weight: Some(100),
                             pause: None,
+                            alert_gate: None,
+                            set_mirror: None,
+                            set_canary_scale: None,
+                            set_header_route: None,
+                            analysis: None,
                         }, // Direct to 100%
                     ],
                     traffic_routing: None,
                     analysis: None,
+                    steps_from: None,
+                    gates: vec![],
+                    baseline: None,
+                    rollback_verification: None,
                 }),
             },
 
@@ -824,6 +873,10 @@ async fn test_edge_minimal_steps(ctx: Context) {
             max_unavailable: None,
             progress_deadline_seconds: None,
             advisor: Default::default(),
+            on_degraded: None,
+            revision_history_limit: None,
+            cleanup_policy: None,
+            workload_ref: None,
         },
         status: None,
     };